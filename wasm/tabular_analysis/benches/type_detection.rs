@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tabular_analysis::config::DetectionConfig;
+use tabular_analysis::types::type_scoring::TypeScores;
+
+const ROWS: usize = 10_000;
+
+// Columns of each kind the detectors see, with a few stray values like real exports
+fn columns() -> Vec<(&'static str, Vec<String>)> {
+    let column = |value: &dyn Fn(usize) -> String| -> Vec<String> {
+        (0..ROWS)
+            .map(|i| {
+                if i % 50 == 0 {
+                    "n/a".to_string()
+                } else {
+                    value(i)
+                }
+            })
+            .collect()
+    };
+    vec![
+        ("integer", column(&|i| (i * 37).to_string())),
+        ("decimal", column(&|i| format!("{}.{:02}", i, i % 100))),
+        (
+            "currency",
+            column(&|i| format!("${},{:03}.50", i % 90 + 1, i % 1000)),
+        ),
+        (
+            "date",
+            column(&|i| format!("2024-{:02}-{:02}", i % 12 + 1, i % 28 + 1)),
+        ),
+        ("email", column(&|i| format!("user{}@example.com", i))),
+        (
+            "phone",
+            column(&|i| format!("(555) {:03}-{:04}", i % 1000, i)),
+        ),
+        (
+            "categorical",
+            column(&|i| ["active", "pending", "failed"][i % 3].to_string()),
+        ),
+        ("text", column(&|i| format!("Order note number {}", i))),
+    ]
+}
+
+fn type_detection(c: &mut Criterion) {
+    let config = DetectionConfig::default();
+    let mut group = c.benchmark_group("type_detection");
+    for (kind, values) in columns() {
+        group.bench_with_input(BenchmarkId::from_parameter(kind), &values, |b, values| {
+            b.iter(|| TypeScores::from_column(black_box(values), &config))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, type_detection);
+criterion_main!(benches);
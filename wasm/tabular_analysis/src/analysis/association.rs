@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+// A contingency table this wide is almost certainly an identifier column
+const MAX_CATEGORIES: usize = 1000;
+
+/// Counts of every pair of values of two categorical columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ContingencyTable {
+    /// Distinct values of the first column, in order of first appearance
+    pub rows: Vec<String>,
+    /// Distinct values of the second column, in order of first appearance
+    pub columns: Vec<String>,
+    pub counts: Vec<Vec<usize>>,
+}
+
+/// Chi-square test of independence between two categorical columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Association {
+    pub table: ContingencyTable,
+    /// Rows where both values are present
+    pub observations: usize,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    /// Probability of an association at least this strong between independent columns
+    pub p_value: f64,
+    /// Strength of the association from 0 (independent) to 1 (one determines the other)
+    pub cramers_v: f64,
+}
+
+/// Builds the contingency table of two columns, skipping rows where either is empty
+pub fn contingency_table(a: &[String], b: &[String]) -> Result<ContingencyTable, AnalysisError> {
+    let mut table = ContingencyTable {
+        rows: Vec::new(),
+        columns: Vec::new(),
+        counts: Vec::new(),
+    };
+    let mut row_index: HashMap<&str, usize> = HashMap::new();
+    let mut column_index: HashMap<&str, usize> = HashMap::new();
+
+    for (x, y) in a.iter().zip(b).map(|(x, y)| (x.trim(), y.trim())) {
+        if x.is_empty() || y.is_empty() {
+            continue;
+        }
+        let r = *row_index.entry(x).or_insert_with(|| {
+            table.rows.push(x.to_string());
+            table.counts.push(vec![0; table.columns.len()]);
+            table.rows.len() - 1
+        });
+        let c = *column_index.entry(y).or_insert_with(|| {
+            table.columns.push(y.to_string());
+            table.counts.iter_mut().for_each(|counts| counts.push(0));
+            table.columns.len() - 1
+        });
+        if table.rows.len() > MAX_CATEGORIES || table.columns.len() > MAX_CATEGORIES {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Columns with more than {} distinct values can't be compared",
+                MAX_CATEGORIES
+            )));
+        }
+        table.counts[r][c] += 1;
+    }
+    Ok(table)
+}
+
+/// Runs a chi-square test of independence on a contingency table
+pub fn chi_square_test(table: ContingencyTable) -> Association {
+    let row_totals: Vec<usize> = table.counts.iter().map(|row| row.iter().sum()).collect();
+    let column_totals: Vec<usize> = (0..table.columns.len())
+        .map(|c| table.counts.iter().map(|row| row[c]).sum())
+        .collect();
+    let n: usize = row_totals.iter().sum();
+
+    let mut chi_square = 0.0;
+    for (r, row) in table.counts.iter().enumerate() {
+        for (c, &observed) in row.iter().enumerate() {
+            let expected = (row_totals[r] * column_totals[c]) as f64 / n as f64;
+            chi_square += (observed as f64 - expected).powi(2) / expected;
+        }
+    }
+
+    let degrees_of_freedom =
+        table.rows.len().saturating_sub(1) * table.columns.len().saturating_sub(1);
+    let p_value = ChiSquared::new(degrees_of_freedom as f64)
+        .map(|distribution| 1.0 - distribution.cdf(chi_square))
+        .unwrap_or(1.0);
+    // Undefined with a single category on either side, where nothing can vary together
+    let k = table.rows.len().min(table.columns.len()).saturating_sub(1);
+    let cramers_v = if k == 0 {
+        0.0
+    } else {
+        (chi_square / (n * k) as f64).sqrt()
+    };
+
+    Association {
+        table,
+        observations: n,
+        chi_square,
+        degrees_of_freedom,
+        p_value,
+        cramers_v,
+    }
+}
+
+impl CSV {
+    /// Chi-square test of independence between two categorical columns
+    pub fn column_association(
+        &self,
+        col_a: usize,
+        col_b: usize,
+    ) -> Result<Association, AnalysisError> {
+        let column = |index: usize| {
+            self.get_column(index)
+                .map(|(_, values)| values)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })
+        };
+        let table = contingency_table(column(col_a)?, column(col_b)?)?;
+        Ok(chi_square_test(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_association() {
+        let data = "plan,churned,region\n\
+                    basic,yes,north\n\
+                    basic,yes,south\n\
+                    basic,yes,north\n\
+                    basic,no,south\n\
+                    pro,no,north\n\
+                    pro,no,south\n\
+                    pro,no,north\n\
+                    pro,,south";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let association = csv.column_association(0, 1).unwrap();
+        assert_eq!(association.table.rows, vec!["basic", "pro"]);
+        assert_eq!(association.table.columns, vec!["yes", "no"]);
+        assert_eq!(association.table.counts, vec![vec![3, 1], vec![0, 3]]);
+        assert_eq!(association.observations, 7);
+        assert_eq!(association.degrees_of_freedom, 1);
+        // Expected counts are 12/7, 16/7, 9/7 and 12/7
+        assert!((association.chi_square - 3.9375).abs() < 1e-9);
+        assert!((association.cramers_v - (3.9375f64 / 7.0).sqrt()).abs() < 1e-9);
+        assert!((association.p_value - 0.0472).abs() < 1e-3);
+
+        let independent = csv.column_association(0, 2).unwrap();
+        assert_eq!(independent.chi_square, 0.0);
+        assert_eq!(independent.cramers_v, 0.0);
+        assert!((independent.p_value - 1.0).abs() < 1e-9);
+
+        assert!(csv.column_association(0, 3).is_err());
+    }
+
+    #[test]
+    fn test_single_category() {
+        let a = vec!["x".to_string(); 3];
+        let b = vec!["p".to_string(), "q".to_string(), "p".to_string()];
+        let association = chi_square_test(contingency_table(&a, &b).unwrap());
+        assert_eq!(association.degrees_of_freedom, 0);
+        assert_eq!(association.cramers_v, 0.0);
+        assert_eq!(association.p_value, 1.0);
+    }
+}
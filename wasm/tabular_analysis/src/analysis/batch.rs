@@ -0,0 +1,128 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::analysis::profile::ProfileReport;
+use crate::config::AnalysisConfig;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::metrics::now_ms;
+use crate::parallel::ParallelExecutor;
+
+/// Outcome of one file in a batch: its profile, or why it couldn't be read
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AnalysisError>,
+}
+
+/// Reports of every file in a batch, keyed by file name
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub files: BTreeMap<String, FileReport>,
+    /// Files that failed to parse
+    pub failed: usize,
+    /// Wall time of the whole batch
+    pub total_ms: f64,
+}
+
+/// Profiles several CSVs with the same options, e.g. a folder dropped onto a workspace. Files
+/// are spread over the shared thread pool on native builds and analyzed in turn on wasm. A
+/// file that fails to parse is reported with its error without stopping the others.
+pub fn analyze_files(
+    files: Vec<(String, String)>,
+    config: &AnalysisConfig,
+) -> Result<BatchReport, AnalysisError> {
+    let start = now_ms();
+    // Checked once for the batch rather than per file
+    config.validate()?;
+    for (i, (name, _)) in files.iter().enumerate() {
+        if files[..i].iter().any(|(earlier, _)| earlier == name) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Duplicate file name '{}'",
+                name
+            )));
+        }
+    }
+
+    // Each table lives and dies on one thread; only the reports cross back
+    let reports = ParallelExecutor::for_host().map(&files, |(_, data)| {
+        let mut csv = CSV::from_string(data.clone())?;
+        csv.apply_config(config.clone())?;
+        Ok(csv.build_profile())
+    });
+
+    let mut failed = 0;
+    let files = files
+        .into_iter()
+        .zip(reports)
+        .map(|((name, _), report)| {
+            let report = match report {
+                Ok(profile) => FileReport {
+                    profile: Some(profile),
+                    error: None,
+                },
+                Err(error) => {
+                    failed += 1;
+                    FileReport {
+                        profile: None,
+                        error: Some(error),
+                    }
+                }
+            };
+            (name, report)
+        })
+        .collect();
+
+    Ok(BatchReport {
+        files,
+        failed,
+        total_ms: now_ms() - start,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_analyze_files() {
+        let files = vec![
+            (
+                "people.csv".to_string(),
+                "id,email\n1,ada@example.com\n2,alan@example.com".to_string(),
+            ),
+            (
+                "prices.csv".to_string(),
+                "sku,price\nA1,$1.50\nB2,$2.00".to_string(),
+            ),
+            ("broken.csv".to_string(), "a,b\n1,2,3".to_string()),
+        ];
+        let report = analyze_files(files, &AnalysisConfig::default()).unwrap();
+
+        assert_eq!(report.files.len(), 3);
+        assert_eq!(report.failed, 1);
+        let people = report.files["people.csv"].profile.as_ref().unwrap();
+        assert_eq!(people.columns[1].data_type, DataType::Email);
+        let prices = report.files["prices.csv"].profile.as_ref().unwrap();
+        assert_eq!(prices.columns[1].data_type, DataType::Currency);
+        assert!(matches!(
+            report.files["broken.csv"].error,
+            Some(AnalysisError::ParseError { row: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_analyze_files_rejects_duplicate_names() {
+        let files = vec![
+            ("a.csv".to_string(), "x\n1".to_string()),
+            ("a.csv".to_string(), "y\n2".to_string()),
+        ];
+        assert!(matches!(
+            analyze_files(files, &AnalysisConfig::default()),
+            Err(AnalysisError::InvalidArgument { .. })
+        ));
+    }
+}
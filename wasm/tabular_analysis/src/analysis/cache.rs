@@ -0,0 +1,127 @@
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+
+use crate::csv::ColumnMetadata;
+use crate::types::DataType;
+
+// Columns whose metadata is kept; the oldest entry is dropped past this
+const CACHE_CAPACITY: usize = 256;
+
+/// SHA-256 of a column's header and values. Each is prefixed with its length, so moving text
+/// from one value to the next changes the hash.
+pub fn column_content_hash(header: &str, values: &[String]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for text in std::iter::once(header).chain(values.iter().map(String::as_str)) {
+        hasher.update((text.len() as u64).to_le_bytes());
+        hasher.update(text.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    content: [u8; 32],
+    config_hash: String,
+    // Type the column was pinned to, whose statistics differ from the inferred type's
+    pinned: Option<DataType>,
+}
+
+/// Metadata of columns analyzed before in this session, keyed by their content, the config
+/// and the type they were pinned to, so analyzing an unchanged column again is a lookup.
+/// Lineage isn't part of the key and is set again by the caller.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnCache {
+    entries: HashMap<CacheKey, ColumnMetadata>,
+    // Keys in insertion order, for dropping the oldest
+    order: VecDeque<CacheKey>,
+}
+
+impl ColumnCache {
+    fn key(content: [u8; 32], config_hash: &str, pinned: Option<DataType>) -> CacheKey {
+        CacheKey {
+            content,
+            config_hash: config_hash.to_string(),
+            pinned,
+        }
+    }
+
+    pub fn contains(&self, content: [u8; 32], config_hash: &str, pinned: Option<DataType>) -> bool {
+        self.entries
+            .contains_key(&Self::key(content, config_hash, pinned))
+    }
+
+    pub fn get(
+        &self,
+        content: [u8; 32],
+        config_hash: &str,
+        pinned: Option<DataType>,
+    ) -> Option<&ColumnMetadata> {
+        self.entries.get(&Self::key(content, config_hash, pinned))
+    }
+
+    pub fn insert(
+        &mut self,
+        content: [u8; 32],
+        config_hash: &str,
+        pinned: Option<DataType>,
+        metadata: ColumnMetadata,
+    ) {
+        let key = Self::key(content, config_hash, pinned);
+        if self.entries.insert(key.clone(), metadata).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let hash = column_content_hash("id", &column(&["1", "2"]));
+        assert_eq!(hash, column_content_hash("id", &column(&["1", "2"])));
+        assert_ne!(hash, column_content_hash("id", &column(&["12", ""])));
+        assert_ne!(hash, column_content_hash("ID", &column(&["1", "2"])));
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let mut cache = ColumnCache::default();
+        let metadata: ColumnMetadata =
+            serde_json::from_str(r#"{"name":"n","data_type":"Integer","confidence":1.0}"#).unwrap();
+        for i in 0..=CACHE_CAPACITY {
+            let content = column_content_hash("n", &[i.to_string()]);
+            cache.insert(content, "config", None, metadata.clone());
+        }
+        assert_eq!(cache.len(), CACHE_CAPACITY);
+        let first = column_content_hash("n", &["0".to_string()]);
+        assert!(!cache.contains(first, "config", None));
+        let last = column_content_hash("n", &[CACHE_CAPACITY.to_string()]);
+        assert!(cache.contains(last, "config", None));
+        assert!(!cache.contains(last, "other", None));
+        assert!(!cache.contains(last, "config", Some(DataType::Text)));
+    }
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::random::{RandomSource, SplitMix64};
+use crate::table::rows::typed_value;
+
+/// One cluster, with its centroid in the columns' original units
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClusterSummary {
+    pub centroid: Vec<f64>,
+    pub size: usize,
+}
+
+/// Result of k-means over standardized columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Clustering {
+    pub columns: Vec<String>,
+    /// Cluster of every row; None for rows missing a numeric value in any column
+    pub assignments: Vec<Option<usize>>,
+    pub clusters: Vec<ClusterSummary>,
+    /// Sum of squared standardized distances from each point to its centroid
+    pub inertia: f64,
+    /// Iterations run before the assignments stopped changing or the limit was reached
+    pub iterations: usize,
+    /// Seed the initial centroids were drawn with, reproducing these clusters
+    pub seed: u32,
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+fn nearest(point: &[f64], centroids: &[Vec<f64>]) -> (usize, f64) {
+    centroids
+        .iter()
+        .map(|centroid| squared_distance(point, centroid))
+        .enumerate()
+        .fold(
+            (0, f64::INFINITY),
+            |best, (i, d)| if d < best.1 { (i, d) } else { best },
+        )
+}
+
+// k-means++ seeding: each further centroid is drawn with probability proportional to its
+// squared distance from the nearest centroid so far
+fn seed_centroids(points: &[Vec<f64>], k: usize, rng: &mut impl RandomSource) -> Vec<Vec<f64>> {
+    let mut centroids = vec![points[rng.below(points.len())].clone()];
+    while centroids.len() < k {
+        let distances: Vec<f64> = points.iter().map(|p| nearest(p, &centroids).1).collect();
+        let total: f64 = distances.iter().sum();
+        if total == 0.0 {
+            // Fewer distinct points than clusters
+            break;
+        }
+        let mut target = rng.next_f64() * total;
+        let index = distances
+            .iter()
+            .position(|&d| {
+                target -= d;
+                target < 0.0
+            })
+            .unwrap_or(points.len() - 1);
+        centroids.push(points[index].clone());
+    }
+    centroids
+}
+
+/// Clusters points into at most `k` groups with Lloyd's algorithm, returning each point's
+/// cluster, the centroids, the inertia and the iterations run
+pub fn kmeans(
+    points: &[Vec<f64>],
+    k: usize,
+    max_iterations: usize,
+    rng: &mut impl RandomSource,
+) -> (Vec<usize>, Vec<Vec<f64>>, f64, usize) {
+    let mut centroids = seed_centroids(points, k, rng);
+    let dimensions = points[0].len();
+    let mut assignments = vec![usize::MAX; points.len()];
+
+    let mut iterations = 0;
+    while iterations < max_iterations {
+        iterations += 1;
+        let mut changed = false;
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            let (cluster, _) = nearest(point, &centroids);
+            if *assignment != cluster {
+                *assignment = cluster;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0; dimensions]; centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for (point, &cluster) in points.iter().zip(&assignments) {
+            counts[cluster] += 1;
+            for (sum, value) in sums[cluster].iter_mut().zip(point) {
+                *sum += value;
+            }
+        }
+        // A cluster that lost all its points keeps its previous centroid
+        for ((centroid, sum), &count) in centroids.iter_mut().zip(sums).zip(&counts) {
+            if count > 0 {
+                *centroid = sum.into_iter().map(|s| s / count as f64).collect();
+            }
+        }
+    }
+
+    let inertia = points
+        .iter()
+        .zip(&assignments)
+        .map(|(point, &cluster)| squared_distance(point, &centroids[cluster]))
+        .sum();
+    (assignments, centroids, inertia, iterations)
+}
+
+impl CSV {
+    /// Groups rows into `k` clusters by the given numeric columns, standardized so each weighs the
+    /// same
+    pub fn cluster_rows(
+        &self,
+        columns: &[usize],
+        k: usize,
+        iterations: usize,
+        seed: u32,
+    ) -> Result<Clustering, AnalysisError> {
+        if columns.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "At least one column is required",
+            ));
+        }
+        if k == 0 {
+            return Err(AnalysisError::invalid_argument("k must be at least 1"));
+        }
+
+        let mut headers = Vec::with_capacity(columns.len());
+        let mut numbers: Vec<Vec<Option<f64>>> = Vec::with_capacity(columns.len());
+        for &index in columns {
+            let (header, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let metadata = self.column_metadata(index);
+            headers.push(header.to_string());
+            numbers.push(
+                values
+                    .iter()
+                    .map(|value| match typed_value(value, metadata) {
+                        Value::Number(n) => n.as_f64(),
+                        _ => None,
+                    })
+                    .collect(),
+            );
+        }
+
+        // Rows with a number in every selected column
+        let rows: Vec<(usize, Vec<f64>)> = (0..self.row_count())
+            .filter_map(|row| {
+                let point: Option<Vec<f64>> = numbers.iter().map(|column| column[row]).collect();
+                point.map(|point| (row, point))
+            })
+            .collect();
+        if rows.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "No rows have numeric values in every selected column",
+            ));
+        }
+
+        // Standardize to zero mean and unit variance; constant columns only get centered
+        let n = rows.len() as f64;
+        let scales: Vec<(f64, f64)> = (0..columns.len())
+            .map(|d| {
+                let mean = rows.iter().map(|(_, p)| p[d]).sum::<f64>() / n;
+                let variance = rows.iter().map(|(_, p)| (p[d] - mean).powi(2)).sum::<f64>() / n;
+                let std_dev = variance.sqrt();
+                (mean, if std_dev > 0.0 { std_dev } else { 1.0 })
+            })
+            .collect();
+        let points: Vec<Vec<f64>> = rows
+            .iter()
+            .map(|(_, p)| {
+                p.iter()
+                    .zip(&scales)
+                    .map(|(value, (mean, std_dev))| (value - mean) / std_dev)
+                    .collect()
+            })
+            .collect();
+
+        let mut rng = SplitMix64::new(seed as u64);
+        let (point_clusters, centroids, inertia, iterations) =
+            kmeans(&points, k, iterations.max(1), &mut rng);
+
+        let mut assignments = vec![None; self.row_count()];
+        let mut sizes = vec![0; centroids.len()];
+        for ((row, _), &cluster) in rows.iter().zip(&point_clusters) {
+            assignments[*row] = Some(cluster);
+            sizes[cluster] += 1;
+        }
+        let clusters = centroids
+            .into_iter()
+            .zip(sizes)
+            .map(|(centroid, size)| ClusterSummary {
+                centroid: centroid
+                    .iter()
+                    .zip(&scales)
+                    .map(|(value, (mean, std_dev))| value * std_dev + mean)
+                    .collect(),
+                size,
+            })
+            .collect();
+
+        Ok(Clustering {
+            columns: headers,
+            assignments,
+            clusters,
+            inertia,
+            iterations,
+            seed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_groups() -> CSV {
+        let mut data = "height,weight,name\n".to_string();
+        for i in 0..10 {
+            data.push_str(&format!("{},{},small{}\n", 150 + i % 3, 50 + i % 4, i));
+            data.push_str(&format!("{},{},large{}\n", 190 + i % 3, 95 + i % 4, i));
+        }
+        data.push_str(",70,missing\n");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+        csv
+    }
+
+    #[test]
+    fn test_kmeans_separates_groups() {
+        let csv = two_groups();
+        let clustering = csv.cluster_rows(&[0, 1], 2, 50, 3).unwrap();
+
+        assert_eq!(clustering.columns, vec!["height", "weight"]);
+        assert_eq!(clustering.assignments.len(), 21);
+        assert_eq!(clustering.assignments[20], None);
+        let small = clustering.assignments[0].unwrap();
+        let large = clustering.assignments[1].unwrap();
+        assert_ne!(small, large);
+        for row in 0..20 {
+            let expected = if row % 2 == 0 { small } else { large };
+            assert_eq!(clustering.assignments[row], Some(expected), "row {}", row);
+        }
+        assert_eq!(clustering.clusters[small].size, 10);
+        assert!((clustering.clusters[large].centroid[0] - 190.9).abs() < 1e-9);
+
+        assert_eq!(clustering, csv.cluster_rows(&[0, 1], 2, 50, 3).unwrap());
+    }
+
+    #[test]
+    fn test_kmeans_edge_cases() {
+        let csv = two_groups();
+        assert!(csv.cluster_rows(&[], 2, 10, 1).is_err());
+        assert!(csv.cluster_rows(&[0], 0, 10, 1).is_err());
+        assert!(csv.cluster_rows(&[0, 9], 2, 10, 1).is_err());
+        assert!(
+            csv.cluster_rows(&[2], 2, 10, 1).is_err(),
+            "text columns have no numbers"
+        );
+
+        let mut constant = CSV::from_string("a\n1\n1\n1".to_string()).unwrap();
+        constant.infer_column_types();
+        let clustering = constant.cluster_rows(&[0], 3, 10, 1).unwrap();
+        assert_eq!(
+            clustering.clusters.len(),
+            1,
+            "no more clusters than distinct points"
+        );
+        assert_eq!(clustering.inertia, 0.0);
+    }
+}
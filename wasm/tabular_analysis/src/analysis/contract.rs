@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::saved_metadata::SavedMetadata;
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::types::{locale::SynonymDictionary, type_scoring::value_matches, DataType};
+
+// Row-level violations kept in a report; every violation is still counted
+const MAX_VIOLATIONS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// The schema describes a column this CSV doesn't have
+    MissingColumn,
+    /// The value doesn't parse as the expected type
+    TypeMismatch,
+    /// A number outside the range seen when the schema was built
+    OutOfRange,
+    /// The value has the wrong shape for the column, e.g. the wrong code width
+    FormatMismatch,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Violation {
+    pub column: String,
+    /// None for column-level violations
+    pub row: Option<usize>,
+    pub value: Option<String>,
+    pub kind: ViolationKind,
+}
+
+/// Violation count for one schema column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColumnValidation {
+    pub column: String,
+    pub expected_type: DataType,
+    pub violation_count: usize,
+}
+
+/// Result of checking a CSV against a saved schema
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub violation_count: usize,
+    pub columns: Vec<ColumnValidation>,
+    /// The first violations in column then row order
+    pub violations: Vec<Violation>,
+}
+
+/// Checks one non-empty value against a column's saved metadata
+pub fn check_value(
+    value: &str,
+    expected: &ColumnMetadata,
+    synonyms: &SynonymDictionary,
+) -> Option<ViolationKind> {
+    let value = value.trim();
+    if expected
+        .fixed_width()
+        .is_some_and(|width| value.chars().count() != width)
+    {
+        return Some(ViolationKind::FormatMismatch);
+    }
+
+    let number = match expected.data_type {
+        DataType::Integer | DataType::Decimal | DataType::Currency => {
+            let format = expected.number_format.unwrap_or_default();
+            match format.parse(value) {
+                Some(number)
+                    if expected.data_type != DataType::Integer || number.fract() == 0.0 =>
+                {
+                    Some(number)
+                }
+                _ if expected.data_type == DataType::Currency
+                    && value_matches(DataType::Currency, value, synonyms) =>
+                {
+                    None
+                }
+                _ => return Some(ViolationKind::TypeMismatch),
+            }
+        }
+        DataType::Quantity => {
+            let number = expected
+                .quantity
+                .as_ref()
+                .and_then(|quantity| quantity.normalized_value(value));
+            if number.is_none() {
+                return Some(ViolationKind::TypeMismatch);
+            }
+            number
+        }
+        DataType::Date | DataType::Email | DataType::Phone | DataType::CreditCard => {
+            if !value_matches(expected.data_type, value, synonyms) {
+                return Some(ViolationKind::TypeMismatch);
+            }
+            None
+        }
+        // Free-form columns accept any value
+        DataType::Categorical | DataType::Text | DataType::Mixed => None,
+    };
+
+    let stats = expected.numeric_stats.as_ref();
+    match (number, stats) {
+        (Some(number), Some(stats)) if number < stats.min || number > stats.max => {
+            Some(ViolationKind::OutOfRange)
+        }
+        _ => None,
+    }
+}
+
+impl CSV {
+    /// Checks the data against a JSON schema of expected columns, types and ranges
+    pub fn validate_against_schema(&self, schema: &str) -> Result<ValidationReport, AnalysisError> {
+        let schema = SavedMetadata::from_json(schema)?;
+        let synonyms = schema.config.detection.synonyms().unwrap_or_default();
+        let columns = self.get_columns();
+
+        let mut report = ValidationReport {
+            valid: true,
+            violation_count: 0,
+            columns: Vec::new(),
+            violations: Vec::new(),
+        };
+        for expected in &schema.columns {
+            let mut violations: Vec<Violation> =
+                match columns.iter().find(|(header, _)| *header == expected.name) {
+                    Some((_, values)) => values
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, value)| !value.trim().is_empty())
+                        .filter_map(|(row, value)| {
+                            check_value(value, expected, &synonyms).map(|kind| Violation {
+                                column: expected.name.clone(),
+                                row: Some(row),
+                                value: Some(value.clone()),
+                                kind,
+                            })
+                        })
+                        .collect(),
+                    None => vec![Violation {
+                        column: expected.name.clone(),
+                        row: None,
+                        value: None,
+                        kind: ViolationKind::MissingColumn,
+                    }],
+                };
+
+            report.columns.push(ColumnValidation {
+                column: expected.name.clone(),
+                expected_type: expected.data_type,
+                violation_count: violations.len(),
+            });
+            report.violation_count += violations.len();
+            violations.truncate(MAX_VIOLATIONS - report.violations.len());
+            report.violations.append(&mut violations);
+        }
+        report.valid = report.violation_count == 0;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(data: &str) -> String {
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.export_metadata().unwrap()
+    }
+
+    #[test]
+    fn test_conforming_file() {
+        let schema = schema("zip,qty,joined\n02134,1,2024-01-01\n90210,5,2024-02-01");
+        let csv = CSV::from_string("zip,qty,joined\n00501,3,2024-03-01".to_string()).unwrap();
+
+        let report = csv.validate_against_schema(&schema).unwrap();
+        assert!(report.valid);
+        assert_eq!(report.columns.len(), 3);
+    }
+
+    #[test]
+    fn test_violations() {
+        let schema =
+            schema("zip,qty,joined,email\n02134,1,2024-01-01,a@b.com\n90210,5,2024-02-01,c@d.com");
+        let csv = CSV::from_string(
+            "zip,qty,joined\n0213,3,2024-03-01\n90210,2.5,soon\n12345,9,\n".to_string(),
+        )
+        .unwrap();
+
+        let report = csv.validate_against_schema(&schema).unwrap();
+        assert!(!report.valid);
+        let found: Vec<(&str, Option<usize>, ViolationKind)> = report
+            .violations
+            .iter()
+            .map(|v| (v.column.as_str(), v.row, v.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("zip", Some(0), ViolationKind::FormatMismatch),
+                ("qty", Some(1), ViolationKind::TypeMismatch),
+                ("qty", Some(2), ViolationKind::OutOfRange),
+                ("joined", Some(1), ViolationKind::TypeMismatch),
+                ("email", None, ViolationKind::MissingColumn),
+            ]
+        );
+        assert_eq!(report.violation_count, 5);
+        assert_eq!(report.columns[1].violation_count, 2);
+        assert!(csv.validate_against_schema("{}").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::cleaning::dates::{parse_with_order, resolve_day_first};
+use crate::types::date::DateFormat;
+
+// Keeps reports small for long, irregular series
+const MAX_REPORTED_GAPS: usize = 20;
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Ordering of a date column in row order
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonicity {
+    Increasing,
+    Decreasing,
+    Constant,
+    Unordered,
+}
+
+/// A stretch of the series where consecutive dates are further apart than the usual interval
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DateGap {
+    pub start: String,
+    pub end: String,
+    pub days: i64,
+}
+
+/// Summary statistics for Date columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DateStats {
+    /// Earliest and latest dates, formatted as ISO-8601
+    pub min_date: String,
+    pub max_date: String,
+    pub span_days: i64,
+    pub most_common_year: i32,
+    pub most_common_month: u32,
+    pub most_common_weekday: String,
+    pub monotonicity: Monotonicity,
+    /// Most frequent spacing in days between consecutive distinct dates, if there are at least two
+    pub interval_days: Option<i64>,
+    pub gap_count: usize,
+    pub gaps: Vec<DateGap>,
+}
+
+fn most_common<K: Copy + Ord + std::hash::Hash>(keys: impl Iterator<Item = K>) -> Option<K> {
+    let mut counts: HashMap<K, usize> = HashMap::new();
+    for key in keys {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    // Ties resolve to the smallest key so results are deterministic
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(key, _)| key)
+}
+
+fn monotonicity(dates: &[NaiveDate]) -> Monotonicity {
+    let pairs = || dates.windows(2);
+    if pairs().all(|w| w[0] == w[1]) {
+        Monotonicity::Constant
+    } else if pairs().all(|w| w[0] <= w[1]) {
+        Monotonicity::Increasing
+    } else if pairs().all(|w| w[0] >= w[1]) {
+        Monotonicity::Decreasing
+    } else {
+        Monotonicity::Unordered
+    }
+}
+
+pub fn calculate_date_stats(values: &[String]) -> Option<DateStats> {
+    let (day_first, _, _) = resolve_day_first(values);
+
+    // Dates in row order, skipping values that don't parse
+    let dates: Vec<NaiveDate> = values
+        .iter()
+        .filter_map(|value| parse_with_order(value, day_first))
+        .filter_map(|date| {
+            let iso = date.to_format(DateFormat::Iso8601);
+            NaiveDate::parse_from_str(&iso, "%Y-%m-%d").ok()
+        })
+        .collect();
+
+    if dates.is_empty() {
+        return None;
+    }
+
+    let mut sorted = dates.clone();
+    sorted.sort();
+    sorted.dedup();
+
+    let min_date = sorted[0];
+    let max_date = sorted[sorted.len() - 1];
+
+    // The usual spacing is the most frequent difference between consecutive distinct dates
+    let intervals: Vec<i64> = sorted
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_days())
+        .collect();
+    let interval_days = most_common(intervals.iter().copied());
+
+    let gaps: Vec<DateGap> = match interval_days {
+        Some(interval) => sorted
+            .windows(2)
+            .filter(|w| (w[1] - w[0]).num_days() > interval)
+            .map(|w| DateGap {
+                start: w[0].format("%Y-%m-%d").to_string(),
+                end: w[1].format("%Y-%m-%d").to_string(),
+                days: (w[1] - w[0]).num_days(),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Some(DateStats {
+        min_date: min_date.format("%Y-%m-%d").to_string(),
+        max_date: max_date.format("%Y-%m-%d").to_string(),
+        span_days: (max_date - min_date).num_days(),
+        most_common_year: most_common(dates.iter().map(|d| d.year()))?,
+        most_common_month: most_common(dates.iter().map(|d| d.month()))?,
+        most_common_weekday: most_common(dates.iter().map(|d| d.weekday().num_days_from_monday()))
+            .map(|day| WEEKDAY_NAMES[day as usize].to_string())?,
+        monotonicity: monotonicity(&dates),
+        interval_days,
+        gap_count: gaps.len(),
+        gaps: gaps.into_iter().take(MAX_REPORTED_GAPS).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_daily_series_with_gap() {
+        let values = column(&[
+            "2024-03-01",
+            "2024-03-02",
+            "2024-03-03",
+            "2024-03-07",
+            "2024-03-08",
+            "",
+        ]);
+        let stats = calculate_date_stats(&values).unwrap();
+
+        assert_eq!(stats.min_date, "2024-03-01");
+        assert_eq!(stats.max_date, "2024-03-08");
+        assert_eq!(stats.span_days, 7);
+        assert_eq!(stats.most_common_year, 2024);
+        assert_eq!(stats.most_common_month, 3);
+        assert_eq!(stats.monotonicity, Monotonicity::Increasing);
+        assert_eq!(stats.interval_days, Some(1));
+        assert_eq!(stats.gap_count, 1);
+        assert_eq!(
+            stats.gaps[0],
+            DateGap {
+                start: "2024-03-03".to_string(),
+                end: "2024-03-07".to_string(),
+                days: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_weekday_and_ordering() {
+        // 2024-01-01 and 2024-01-08 are Mondays
+        let values = column(&["01/08/2024", "2024-01-01", "01/08/2024", "2024-01-03"]);
+        let stats = calculate_date_stats(&values).unwrap();
+
+        assert_eq!(stats.most_common_weekday, "Monday");
+        assert_eq!(stats.monotonicity, Monotonicity::Unordered);
+
+        let values = column(&["2024-01-03", "2024-01-02", "2024-01-02"]);
+        let stats = calculate_date_stats(&values).unwrap();
+        assert_eq!(stats.monotonicity, Monotonicity::Decreasing);
+
+        let values = column(&["2024-01-03", "2024-01-03"]);
+        let stats = calculate_date_stats(&values).unwrap();
+        assert_eq!(stats.monotonicity, Monotonicity::Constant);
+        assert_eq!(stats.interval_days, None);
+        assert_eq!(stats.gap_count, 0);
+    }
+
+    #[test]
+    fn test_no_dates() {
+        assert!(calculate_date_stats(&column(&["", "abc"])).is_none());
+    }
+}
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::{email::EmailType, DataType, TypeDetection};
+
+// Domains listed in `top_domains`
+const TOP_DOMAINS: usize = 10;
+// Near-valid values listed with their suggestion; the rest are only counted
+const MAX_LISTED_SUGGESTIONS: usize = 20;
+// Shortest provider domain that misspellings are matched against
+const MIN_TYPO_PROVIDER_LENGTH: usize = 8;
+
+// Webmail providers anyone can sign up to, as opposed to a company's own domain
+const FREE_PROVIDERS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "yahoo.com",
+    "yahoo.co.uk",
+    "yahoo.fr",
+    "hotmail.com",
+    "hotmail.co.uk",
+    "outlook.com",
+    "live.com",
+    "msn.com",
+    "aol.com",
+    "icloud.com",
+    "me.com",
+    "mac.com",
+    "proton.me",
+    "protonmail.com",
+    "gmx.com",
+    "gmx.de",
+    "web.de",
+    "mail.com",
+    "yandex.ru",
+    "yandex.com",
+    "mail.ru",
+    "qq.com",
+    "163.com",
+    "zoho.com",
+    "fastmail.com",
+];
+
+/// Why a value failing the email check looks like a mistyped address
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailIssue {
+    /// More than one `@` in a row, e.g. `ann@@example.com`
+    DoubleAt,
+    /// A domain without a top-level domain, e.g. `ann@gmail`
+    MissingTld,
+    /// Spaces inside the address, e.g. `ann @example.com`
+    StraySpaces,
+    /// A valid address at a domain one typo away from a common provider, e.g. `gmial.com`
+    DomainTypo,
+}
+
+/// A value that is close to a valid address, with the fix when one is clear
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EmailSuggestion {
+    pub row: usize,
+    pub value: String,
+    pub issue: EmailIssue,
+    pub suggestion: Option<String>,
+}
+
+/// Domain breakdown of an email column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EmailStats {
+    pub valid_count: usize,
+    pub distinct_domains: usize,
+    /// Domains of the valid addresses, lowercase, most frequent first
+    pub top_domains: Vec<(String, usize)>,
+    /// Valid addresses at webmail providers like gmail.com, and at any other domain
+    pub free_provider_count: usize,
+    pub corporate_count: usize,
+    /// Free-provider addresses over valid ones
+    pub free_provider_ratio: f64,
+    /// Values close to a valid address: a doubled `@`, a missing TLD, stray spaces or a
+    /// misspelled provider
+    pub near_valid_count: usize,
+    /// The first of them, in row order
+    pub near_valid: Vec<EmailSuggestion>,
+}
+
+/// Lowercase domain of a valid address, None for anything else
+pub fn email_domain(value: &str) -> Option<String> {
+    if !EmailType::is_definite_match(value) {
+        return None;
+    }
+    let (_, domain) = value.trim().rsplit_once('@')?;
+    Some(domain.to_lowercase())
+}
+
+fn is_free_provider(domain: &str) -> bool {
+    FREE_PROVIDERS.contains(&domain)
+}
+
+// Edits turning one string into the other, counting a swap of neighbours as one
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+// Provider a domain is one edit away from, like gmail.com for gmial.com or gmail.con. Short
+// providers like me.com are left out, as real domains such as ae.com sit one edit from them.
+fn provider_typo(domain: &str) -> Option<&'static str> {
+    if is_free_provider(domain) {
+        return None;
+    }
+    FREE_PROVIDERS
+        .iter()
+        .copied()
+        .filter(|provider| provider.len() >= MIN_TYPO_PROVIDER_LENGTH)
+        .find(|provider| edit_distance(domain, provider) == 1)
+}
+
+// The issue of a value that isn't a valid address but nearly is, with its fix. Domains
+// missing their TLD are completed from the column's own domains first, then the providers.
+fn near_valid(
+    value: &str,
+    domains: &HashMap<String, usize>,
+) -> Option<(EmailIssue, Option<String>)> {
+    let value = value.trim();
+    if value.contains(char::is_whitespace) {
+        let compact: String = value.split_whitespace().collect();
+        if EmailType::is_definite_match(&compact) {
+            return Some((EmailIssue::StraySpaces, Some(compact)));
+        }
+    }
+    if value.contains("@@") {
+        let mut single = value.to_string();
+        while single.contains("@@") {
+            single = single.replace("@@", "@");
+        }
+        let suggestion = EmailType::is_definite_match(&single).then_some(single);
+        return Some((EmailIssue::DoubleAt, suggestion));
+    }
+
+    let (local, domain) = value.split_once('@')?;
+    let domain = domain.to_lowercase();
+    if local.is_empty() || domain.contains(['@', '.']) {
+        return None;
+    }
+    if domain.is_empty()
+        || !domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return None;
+    }
+    let prefix = format!("{}.", domain);
+    let completed = domains
+        .iter()
+        .filter(|(known, _)| known.starts_with(&prefix))
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(known, _)| known.as_str())
+        .or_else(|| {
+            FREE_PROVIDERS
+                .iter()
+                .copied()
+                .find(|p| p.starts_with(&prefix))
+        });
+    let suggestion = completed.map(|domain| format!("{}@{}", local, domain));
+    Some((EmailIssue::MissingTld, suggestion))
+}
+
+/// Domain statistics of an email column, None when it has no non-empty values
+pub fn calculate_email_stats(values: &[String]) -> Option<EmailStats> {
+    let mut domains: HashMap<String, usize> = HashMap::new();
+    let mut non_empty = 0;
+    for value in values.iter().filter(|v| !v.trim().is_empty()) {
+        non_empty += 1;
+        if let Some(domain) = email_domain(value) {
+            *domains.entry(domain).or_default() += 1;
+        }
+    }
+    if non_empty == 0 {
+        return None;
+    }
+
+    let mut near_valid_count = 0;
+    let mut suggestions = Vec::new();
+    for (row, value) in values.iter().enumerate() {
+        if value.trim().is_empty() {
+            continue;
+        }
+        let found = match email_domain(value) {
+            Some(domain) => provider_typo(&domain).map(|provider| {
+                let local = value.trim().rsplit_once('@').map_or("", |(local, _)| local);
+                (
+                    EmailIssue::DomainTypo,
+                    Some(format!("{}@{}", local, provider)),
+                )
+            }),
+            None => near_valid(value, &domains),
+        };
+        if let Some((issue, suggestion)) = found {
+            near_valid_count += 1;
+            if suggestions.len() < MAX_LISTED_SUGGESTIONS {
+                suggestions.push(EmailSuggestion {
+                    row,
+                    value: value.clone(),
+                    issue,
+                    suggestion,
+                });
+            }
+        }
+    }
+
+    let valid_count: usize = domains.values().sum();
+    let free_provider_count: usize = domains
+        .iter()
+        .filter(|(domain, _)| is_free_provider(domain))
+        .map(|(_, count)| count)
+        .sum();
+    let distinct_domains = domains.len();
+    let mut top_domains: Vec<(String, usize)> = domains.into_iter().collect();
+    top_domains.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_domains.truncate(TOP_DOMAINS);
+
+    Some(EmailStats {
+        valid_count,
+        distinct_domains,
+        top_domains,
+        free_provider_count,
+        corporate_count: valid_count - free_provider_count,
+        free_provider_ratio: match valid_count {
+            0 => 0.0,
+            _ => free_provider_count as f64 / valid_count as f64,
+        },
+        near_valid_count,
+        near_valid: suggestions,
+    })
+}
+
+impl CSV {
+    /// Appends a `<header>_domain` column holding the lowercase domain of every valid address
+    /// of an email column, empty for other values, and returns its index
+    pub fn derive_email_domain(&mut self, column: usize) -> Result<usize, AnalysisError> {
+        let (header, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let data_type = match self.column_metadata(column) {
+            Some(metadata) => metadata.data_type,
+            None => self
+                .infer_column(column)
+                .map_or(DataType::Text, |m| m.data_type),
+        };
+        if data_type != DataType::Email {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Column {} is {}, not Email",
+                column, data_type
+            )));
+        }
+
+        let header = format!("{}_domain", header);
+        let domains = values
+            .iter()
+            .map(|value| email_domain(value).unwrap_or_default())
+            .collect();
+        Ok(self.push_derived_column(header, domains, &[column], "email_domain", None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_domain_breakdown() {
+        let values = column(&[
+            "ann@acme.io",
+            "Bob@ACME.io",
+            "cat@gmail.com",
+            "",
+            "dan@yahoo.com",
+            "eve@acme",
+            "fay@@gmail.com",
+            "gus@gmial.com",
+            "hal @outlook.com",
+            "not an address",
+        ]);
+        let stats = calculate_email_stats(&values).unwrap();
+        assert_eq!(stats.valid_count, 5);
+        assert_eq!(stats.top_domains[0], ("acme.io".to_string(), 2));
+        assert_eq!(stats.free_provider_count, 2);
+        assert_eq!(stats.corporate_count, 3);
+        assert!((stats.free_provider_ratio - 0.4).abs() < 1e-9);
+
+        let suggested: Vec<(usize, EmailIssue, Option<&str>)> = stats
+            .near_valid
+            .iter()
+            .map(|s| (s.row, s.issue, s.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            suggested,
+            vec![
+                (5, EmailIssue::MissingTld, Some("eve@acme.io")),
+                (6, EmailIssue::DoubleAt, Some("fay@gmail.com")),
+                (7, EmailIssue::DomainTypo, Some("gus@gmail.com")),
+                (8, EmailIssue::StraySpaces, Some("hal@outlook.com")),
+            ]
+        );
+        assert_eq!(stats.near_valid_count, 4);
+        assert!(calculate_email_stats(&column(&["", " "])).is_none());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("gmail.com", "gmail.com"), 0);
+        assert_eq!(edit_distance("gmial.com", "gmail.com"), 1);
+        assert_eq!(edit_distance("gmail.con", "gmail.com"), 1);
+        assert_eq!(edit_distance("gmal.com", "gmail.com"), 1);
+        assert_eq!(provider_typo("acme.io"), None);
+        assert_eq!(provider_typo("ae.com"), None);
+    }
+
+    #[test]
+    fn test_derive_domain_column() {
+        let mut csv =
+            CSV::from_string("email,n\nann@acme.io,1\nbad,2\nBOB@Gmail.com,3".to_string()).unwrap();
+        csv.override_type(0, DataType::Email).unwrap();
+        let index = csv.derive_email_domain(0).unwrap();
+        let (header, values) = csv.get_column(index).unwrap();
+        assert_eq!(header, "email_domain");
+        assert_eq!(values, &["acme.io", "", "gmail.com"]);
+        assert_eq!(
+            csv.column_lineage(index).unwrap().steps[0].operation,
+            "email_domain"
+        );
+        assert!(csv.derive_email_domain(1).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::credit_card::mask_card_number;
+use crate::types::type_scoring::{value_matches, TypeScores};
+use crate::types::DataType;
+
+/// Failing values reported per candidate when no limit is given
+pub const DEFAULT_MAX_EXAMPLES: usize = 5;
+
+/// Evidence for one candidate type, with values that kept it from matching
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CandidateExplanation {
+    pub data_type: DataType,
+    /// Share of non-empty values matching the type plus the header prior, from 0 to 1
+    pub score: f64,
+    pub matches: usize,
+    pub mismatches: usize,
+    pub prior: f64,
+    /// Distinct non-empty values that don't match the type, in order of first appearance,
+    /// with card numbers masked to their last four digits
+    pub failing_examples: Vec<String>,
+}
+
+/// Why a column got its type: the chosen type and every candidate it was picked from
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TypeExplanation {
+    pub column: String,
+    pub data_type: DataType,
+    pub confidence: f64,
+    /// Score a candidate needs to be chosen over Text
+    pub threshold: f64,
+    /// True when the type was pinned by the user rather than inferred
+    pub user_overridden: bool,
+    /// Best candidate first
+    pub candidates: Vec<CandidateExplanation>,
+}
+
+impl CSV {
+    /// Explains a column's type with every candidate's score and up to `max_examples` values
+    /// failing each
+    pub fn type_explanation(
+        &self,
+        column: usize,
+        max_examples: usize,
+    ) -> Result<TypeExplanation, AnalysisError> {
+        let (header, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        // Columns that were never inferred are explained as inference would type them
+        let metadata = match self.column_metadata(column) {
+            Some(metadata) => metadata.clone(),
+            None => self
+                .infer_column(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })?,
+        };
+
+        let detection = &self.analysis_config().detection;
+        let synonyms = detection.synonyms().unwrap_or_default();
+        let scores = TypeScores::from_column(values, detection)
+            .with_header_hints(header, detection.header_hint_weight);
+        let non_empty: Vec<&str> = values
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        let candidates = scores
+            .candidates()
+            .iter()
+            .map(|candidate| {
+                let mut failing_examples: Vec<String> = Vec::new();
+                for value in &non_empty {
+                    if failing_examples.len() >= max_examples {
+                        break;
+                    }
+                    if value_matches(candidate.data_type, value, &synonyms) {
+                        continue;
+                    }
+                    // Card numbers are shown masked, like everywhere else they're reported
+                    let example = mask_card_number(value).unwrap_or_else(|| value.to_string());
+                    if !failing_examples.contains(&example) {
+                        failing_examples.push(example);
+                    }
+                }
+                CandidateExplanation {
+                    data_type: candidate.data_type,
+                    score: candidate.score,
+                    matches: candidate.matches,
+                    mismatches: candidate.mismatches,
+                    prior: candidate.prior,
+                    failing_examples,
+                }
+            })
+            .collect();
+
+        Ok(TypeExplanation {
+            column: header.to_string(),
+            data_type: metadata.data_type,
+            confidence: metadata.confidence,
+            threshold: detection.type_threshold,
+            user_overridden: metadata.user_overridden,
+            candidates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_type() {
+        let mut data = "amount\n".to_string();
+        for i in 0..18 {
+            data.push_str(&format!("{}\n", i));
+        }
+        data.push_str("n/a\nn/a\nunknown\n\n");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+
+        let explanation = csv.type_explanation(0, 5).unwrap();
+        assert_eq!(explanation.column, "amount");
+        assert_eq!(explanation.data_type, DataType::Text);
+        assert_eq!(explanation.threshold, 0.95);
+        assert!(!explanation.user_overridden);
+
+        let integer = explanation
+            .candidates
+            .iter()
+            .find(|c| c.data_type == DataType::Integer)
+            .unwrap();
+        assert_eq!((integer.matches, integer.mismatches), (18, 3));
+        assert_eq!(integer.failing_examples, vec!["n/a", "unknown"]);
+        assert_eq!(
+            explanation.candidates[0].data_type,
+            DataType::Integer,
+            "best candidate first"
+        );
+
+        let limited = csv.type_explanation(0, 1).unwrap();
+        assert!(limited
+            .candidates
+            .iter()
+            .all(|c| c.failing_examples.len() <= 1));
+
+        assert!(csv.type_explanation(1, 5).is_err());
+    }
+
+    #[test]
+    fn test_card_numbers_are_masked() {
+        let data = "card\n4111111111111111\n5500000000000004\n4111-1111-1111-1234\nnone";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let explanation = csv.type_explanation(0, 5).unwrap();
+        let examples: Vec<&String> = explanation
+            .candidates
+            .iter()
+            .flat_map(|c| &c.failing_examples)
+            .collect();
+        assert!(examples.iter().any(|e| *e == "**** **** **** 1234"));
+        assert!(examples.iter().all(|e| !e.contains("4111")));
+    }
+
+    #[test]
+    fn test_explain_uninferred_and_overridden_columns() {
+        let mut csv = CSV::from_string("id\n1\n2\n3".to_string()).unwrap();
+        let explanation = csv.type_explanation(0, 5).unwrap();
+        assert_eq!(explanation.data_type, DataType::Integer);
+
+        csv.override_type(0, DataType::Text).unwrap();
+        let explanation = csv.type_explanation(0, 5).unwrap();
+        assert_eq!(explanation.data_type, DataType::Text);
+        assert!(explanation.user_overridden);
+    }
+}
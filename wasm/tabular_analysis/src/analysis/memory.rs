@@ -0,0 +1,318 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+use crate::config::{AnalysisConfig, SpillStrategy};
+use crate::csv::{for_each_record, parse_error, ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::logging::targets;
+use crate::metrics::peak_memory;
+use crate::random::{RandomSource, SplitMix64};
+use crate::table::dialect::Dialect;
+
+/// How much of the data a column's statistics were computed from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisMode {
+    /// Every row, with the whole table in memory
+    #[default]
+    Full,
+    /// A random sample of the rows, as many as fit the memory limit
+    Sampled,
+    /// Every row, one column in memory at a time
+    ColumnByColumn,
+}
+
+/// Column metadata of a table analyzed under `MemoryConfig::limit_bytes`
+#[derive(Debug, Clone, Serialize)]
+pub struct BoundedAnalysis {
+    pub mode: AnalysisMode,
+    /// Data rows in the input
+    pub rows: usize,
+    /// Rows the statistics were computed from, fewer than `rows` when sampled
+    pub analyzed_rows: usize,
+    /// Estimated peak bytes of analyzing the whole table at once
+    pub estimated_bytes: usize,
+    /// Seed the sampled rows were picked with, from the config's `sample_seed`; None
+    /// unless sampled
+    pub seed: Option<u64>,
+    pub columns: Vec<ColumnMetadata>,
+}
+
+fn reader<'a>(raw_data: &'a str, dialect: &Dialect) -> csv::Reader<&'a [u8]> {
+    dialect.reader_builder().from_reader(raw_data.as_bytes())
+}
+
+/// Analyzes CSV text without exceeding the configured memory limit. The input is read once
+/// to estimate what the parsed table would take; when that is over the limit the
+/// `spill_strategy` decides between analyzing a sample of the rows that fits and reading one
+/// column at a time. Each column's metadata records the mode that was used.
+pub fn analyze_with_memory_limit(
+    raw_data: &str,
+    config: &AnalysisConfig,
+) -> Result<BoundedAnalysis, AnalysisError> {
+    config.validate()?;
+    // Detected once, so every mode reads the same columns
+    let dialect = Dialect::sniff(raw_data);
+    let mut reader = reader(raw_data, &dialect);
+    let headers = dialect.header_names(reader.headers().map_err(|e| parse_error(e, true, 0))?);
+
+    // Sized like `AnalysisMetrics::peak_memory_bytes`, without keeping any value
+    let mut column_bytes: Vec<usize> = headers.iter().map(String::len).collect();
+    let mut rows = 0;
+    for_each_record(&mut reader, headers.len(), 0, |_, record| {
+        rows += 1;
+        for (i, bytes) in column_bytes.iter_mut().enumerate() {
+            *bytes += size_of::<String>() + record.get(i).map_or(0, str::len);
+        }
+    })?;
+    let estimated_bytes = peak_memory(&column_bytes);
+
+    let limit = match config.memory.limit_bytes {
+        Some(limit) if estimated_bytes > limit => limit,
+        _ => {
+            let mut csv = CSV::from_string_with_dialect(raw_data.to_string(), dialect)?;
+            csv.apply_config(config.clone())?;
+            csv.infer_column_types();
+            return Ok(BoundedAnalysis {
+                mode: AnalysisMode::Full,
+                rows,
+                analyzed_rows: rows,
+                estimated_bytes,
+                seed: None,
+                columns: column_metadata(&csv),
+            });
+        }
+    };
+
+    let (mode, analyzed_rows, columns) = match config.memory.spill_strategy {
+        SpillStrategy::Sample => {
+            // Memory grows with the rows kept, so the share of rows that fits is the
+            // share of the estimate the limit allows
+            let keep = (rows as f64 * limit as f64 / estimated_bytes as f64) as usize;
+            if keep == 0 {
+                return Err(AnalysisError::invalid_argument(format!(
+                    "Memory limit of {} bytes is too small to analyze a single row",
+                    limit
+                )));
+            }
+            let mut csv = sampled_rows(raw_data, &dialect, headers, rows, keep, config)?;
+            csv.infer_column_types();
+            (AnalysisMode::Sampled, keep, column_metadata(&csv))
+        }
+        SpillStrategy::ColumnByColumn => {
+            let columns = headers
+                .into_iter()
+                .enumerate()
+                .map(|(index, header)| {
+                    // The column's values plus their two working copies
+                    let needed = 3 * column_bytes[index];
+                    if needed > limit {
+                        return Err(AnalysisError::invalid_argument(format!(
+                            "Memory limit of {} bytes is too small for column '{}', which \
+                             needs about {}",
+                            limit, header, needed
+                        )));
+                    }
+                    let mut csv = CSV::from_parts(
+                        vec![(header, column_values(raw_data, &dialect, index)?, None)],
+                        config.clone(),
+                    );
+                    csv.infer_column_types();
+                    Ok(csv.column_metadata(0).cloned())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            (
+                AnalysisMode::ColumnByColumn,
+                rows,
+                columns.into_iter().flatten().collect(),
+            )
+        }
+    };
+
+    info!(
+        target: targets::INFERENCE,
+        "Estimated {} bytes over the {} byte limit; analyzed {} of {} rows {:?}",
+        estimated_bytes,
+        limit,
+        analyzed_rows,
+        rows,
+        mode
+    );
+    Ok(BoundedAnalysis {
+        mode,
+        rows,
+        analyzed_rows,
+        estimated_bytes,
+        seed: (mode == AnalysisMode::Sampled).then_some(config.memory.sample_seed),
+        columns: columns
+            .into_iter()
+            .map(|metadata| ColumnMetadata {
+                analysis_mode: mode,
+                ..metadata
+            })
+            .collect(),
+    })
+}
+
+fn column_metadata(csv: &CSV) -> Vec<ColumnMetadata> {
+    (0..csv.column_count())
+        .filter_map(|i| csv.column_metadata(i).cloned())
+        .collect()
+}
+
+// Table of `keep` of the `rows` rows picked uniformly in one pass (selection sampling), so
+// only the kept rows are ever held
+fn sampled_rows(
+    raw_data: &str,
+    dialect: &Dialect,
+    headers: Vec<String>,
+    rows: usize,
+    keep: usize,
+    config: &AnalysisConfig,
+) -> Result<CSV, AnalysisError> {
+    let mut rng = SplitMix64::new(config.memory.sample_seed);
+    let mut values = vec![Vec::with_capacity(keep); headers.len()];
+    let mut kept = 0;
+    for_each_record(
+        &mut reader(raw_data, dialect),
+        headers.len(),
+        0,
+        |row, record| {
+            if rng.below(rows - row) < keep - kept {
+                kept += 1;
+                for (i, column) in values.iter_mut().enumerate() {
+                    column.push(record.get(i).unwrap_or_default().to_string());
+                }
+            }
+        },
+    )?;
+    let columns = headers
+        .into_iter()
+        .zip(values)
+        .map(|(header, values)| (header, values, None))
+        .collect();
+    Ok(CSV::from_parts(columns, config.clone()))
+}
+
+// Every value of one column, read without keeping the others
+fn column_values(
+    raw_data: &str,
+    dialect: &Dialect,
+    index: usize,
+) -> Result<Vec<String>, AnalysisError> {
+    let mut reader = reader(raw_data, dialect);
+    let width = reader.headers().map_err(|e| parse_error(e, true, 0))?.len();
+    let mut values = Vec::new();
+    for_each_record(&mut reader, width, 0, |_, record| {
+        values.push(record.get(index).unwrap_or_default().to_string());
+    })?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemoryConfig;
+    use crate::types::DataType;
+
+    fn data(rows: usize) -> String {
+        let mut data = "id,price,city\n".to_string();
+        for i in 0..rows {
+            data.push_str(&format!("{},{}.25,{}\n", i, i * 2, ["Oslo", "Lima"][i % 2]));
+        }
+        data
+    }
+
+    fn limited(limit_bytes: usize, spill_strategy: SpillStrategy) -> AnalysisConfig {
+        AnalysisConfig {
+            memory: MemoryConfig {
+                limit_bytes: Some(limit_bytes),
+                spill_strategy,
+                ..MemoryConfig::default()
+            },
+            ..AnalysisConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_data_within_limit_is_analyzed_in_full() {
+        let data = data(200);
+        let analysis =
+            analyze_with_memory_limit(&data, &limited(1 << 30, SpillStrategy::Sample)).unwrap();
+        assert_eq!(analysis.mode, AnalysisMode::Full);
+        assert_eq!((analysis.rows, analysis.analyzed_rows), (200, 200));
+        assert_eq!(analysis.columns.len(), 3);
+        assert!(analysis
+            .columns
+            .iter()
+            .all(|c| c.analysis_mode == AnalysisMode::Full));
+
+        let unlimited = analyze_with_memory_limit(&data, &AnalysisConfig::default()).unwrap();
+        assert_eq!(unlimited.mode, AnalysisMode::Full);
+        assert_eq!(unlimited.estimated_bytes, analysis.estimated_bytes);
+    }
+
+    #[test]
+    fn test_sampled_mode_keeps_rows_that_fit() {
+        let data = data(1000);
+        let estimated = analyze_with_memory_limit(&data, &AnalysisConfig::default())
+            .unwrap()
+            .estimated_bytes;
+
+        let config = limited(estimated / 4, SpillStrategy::Sample);
+        let analysis = analyze_with_memory_limit(&data, &config).unwrap();
+        assert_eq!(analysis.mode, AnalysisMode::Sampled);
+        assert_eq!(analysis.rows, 1000);
+        assert!((240..=250).contains(&analysis.analyzed_rows));
+        assert_eq!(analysis.columns[0].data_type, DataType::Integer);
+        assert!(analysis
+            .columns
+            .iter()
+            .all(|c| c.analysis_mode == AnalysisMode::Sampled));
+
+        // The same input and seed give the same sample
+        assert_eq!(analysis.seed, Some(0x5eed));
+        let again = analyze_with_memory_limit(&data, &config).unwrap();
+        assert_eq!(
+            serde_json::to_value(&again.columns).unwrap(),
+            serde_json::to_value(&analysis.columns).unwrap()
+        );
+
+        assert!(analyze_with_memory_limit(&data, &limited(10, SpillStrategy::Sample)).is_err());
+    }
+
+    #[test]
+    fn test_column_by_column_mode_covers_every_row() {
+        let data = data(500);
+        let full = analyze_with_memory_limit(&data, &AnalysisConfig::default()).unwrap();
+        // Just short of the whole table, which still leaves room for any one column
+        let config = limited(full.estimated_bytes - 1, SpillStrategy::ColumnByColumn);
+        let analysis = analyze_with_memory_limit(&data, &config).unwrap();
+        assert_eq!(analysis.mode, AnalysisMode::ColumnByColumn);
+        assert_eq!(analysis.analyzed_rows, 500);
+
+        for (column, expected) in analysis.columns.iter().zip(&full.columns) {
+            assert_eq!(column.analysis_mode, AnalysisMode::ColumnByColumn);
+            assert_eq!(column.data_type, expected.data_type);
+            assert_eq!(column.numeric_stats, expected.numeric_stats);
+        }
+
+        let config = limited(100, SpillStrategy::ColumnByColumn);
+        assert!(analyze_with_memory_limit(&data, &config).is_err());
+    }
+
+    #[test]
+    fn test_every_mode_reads_the_detected_delimiter() {
+        let data = data(1000).replace(',', ";");
+        let full = analyze_with_memory_limit(&data, &AnalysisConfig::default()).unwrap();
+        for strategy in [SpillStrategy::Sample, SpillStrategy::ColumnByColumn] {
+            let config = limited(full.estimated_bytes - 1, strategy);
+            let analysis = analyze_with_memory_limit(&data, &config).unwrap();
+            assert_ne!(analysis.mode, AnalysisMode::Full);
+            let names: Vec<&str> = analysis.columns.iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, vec!["id", "price", "city"]);
+            assert_eq!(analysis.columns[1].data_type, full.columns[1].data_type);
+        }
+    }
+}
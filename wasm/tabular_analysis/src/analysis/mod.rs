@@ -0,0 +1,21 @@
+pub mod association;
+pub mod batch;
+pub mod cache;
+pub mod clustering;
+pub mod contract;
+pub mod date_stats;
+pub mod email_stats;
+pub mod explain;
+pub mod memory;
+pub mod patterns;
+pub mod phone_stats;
+pub mod pii;
+pub mod profile;
+pub mod regression;
+pub mod rules;
+pub mod saved_metadata;
+pub mod spill;
+pub mod statistics;
+pub mod streaming;
+pub mod tdigest;
+pub mod tokens;
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+const MAX_REPORTED_PATTERNS: usize = 10;
+const MAX_REPORTED_ANOMALIES: usize = 100;
+// Deviations are only meaningful when most values share one structure
+const DOMINANT_PATTERN_MIN_COVERAGE: f64 = 50.0;
+
+/// A symbolic pattern and how much of the column it covers
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatternFrequency {
+    pub pattern: String,
+    pub count: usize,
+    /// Percentage (0-100) of non-empty values with this pattern
+    pub coverage: f64,
+    /// First value seen with this pattern
+    pub example: String,
+}
+
+/// A value whose structure differs from the column's dominant pattern
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatternAnomaly {
+    pub row: usize,
+    pub value: String,
+    pub pattern: String,
+}
+
+/// Structural profile of a column's values
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatternReport {
+    pub column: usize,
+    pub distinct_patterns: usize,
+    pub patterns: Vec<PatternFrequency>,
+    /// Most common pattern, only set when it covers at least half of the values
+    pub dominant_pattern: Option<String>,
+    pub anomaly_count: usize,
+    pub anomalies: Vec<PatternAnomaly>,
+}
+
+/// Abstracts a value to its shape: `A` for uppercase letters, `a` for lowercase letters,
+/// `9` for digits, with punctuation and spaces kept as they are (`ABC-123` becomes `AAA-999`)
+pub fn symbolic_pattern(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_digit() {
+                '9'
+            } else if c.is_uppercase() {
+                'A'
+            } else if c.is_alphabetic() {
+                'a'
+            } else if c.is_whitespace() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+pub fn profile_patterns(column: usize, values: &[String]) -> PatternReport {
+    // Pattern -> (count, first row), ignoring empty cells
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut row_patterns: Vec<Option<String>> = Vec::with_capacity(values.len());
+    let mut total = 0;
+
+    for (row, value) in values.iter().enumerate() {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            row_patterns.push(None);
+            continue;
+        }
+        let pattern = symbolic_pattern(trimmed);
+        counts.entry(pattern.clone()).or_insert((0, row)).0 += 1;
+        row_patterns.push(Some(pattern));
+        total += 1;
+    }
+
+    let mut patterns: Vec<PatternFrequency> = counts
+        .iter()
+        .map(|(pattern, &(count, first_row))| PatternFrequency {
+            pattern: pattern.clone(),
+            count,
+            coverage: count as f64 / total as f64 * 100.0,
+            example: values[first_row].trim().to_string(),
+        })
+        .collect();
+    patterns.sort_by(|a, b| b.count.cmp(&a.count).then(a.pattern.cmp(&b.pattern)));
+
+    let dominant_pattern = patterns
+        .first()
+        .filter(|p| p.coverage >= DOMINANT_PATTERN_MIN_COVERAGE)
+        .map(|p| p.pattern.clone());
+
+    let mut anomalies: Vec<PatternAnomaly> = Vec::new();
+    let mut anomaly_count = 0;
+    if let Some(dominant) = &dominant_pattern {
+        for (row, pattern) in row_patterns.into_iter().enumerate() {
+            let Some(pattern) = pattern.filter(|p| p != dominant) else {
+                continue;
+            };
+            anomaly_count += 1;
+            if anomalies.len() < MAX_REPORTED_ANOMALIES {
+                anomalies.push(PatternAnomaly {
+                    row,
+                    value: values[row].clone(),
+                    pattern,
+                });
+            }
+        }
+    }
+
+    let distinct_patterns = patterns.len();
+    patterns.truncate(MAX_REPORTED_PATTERNS);
+
+    PatternReport {
+        column,
+        distinct_patterns,
+        patterns,
+        dominant_pattern,
+        anomaly_count,
+        anomalies,
+    }
+}
+
+impl CSV {
+    /// Most common value shapes in a column and the rows breaking the dominant one
+    pub fn pattern_report(&self, column: usize) -> Result<PatternReport, AnalysisError> {
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        Ok(profile_patterns(column, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_symbolic_pattern() {
+        let test_cases = vec![
+            ("ABC-123", "AAA-999"),
+            ("Abc 12", "Aaa 99"),
+            ("user@example.com", "aaaa@aaaaaaa.aaa"),
+            ("(555) 010-9999", "(999) 999-9999"),
+            ("Émile", "Aaaaa"),
+            ("", ""),
+        ];
+        for (value, expected) in test_cases {
+            assert_eq!(symbolic_pattern(value), expected, "pattern of {:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_profile_patterns() {
+        let values = column(&["ABC-123", "XYZ-999", "", "QRS-001", "abc123", "DEF-456"]);
+        let report = profile_patterns(0, &values);
+
+        assert_eq!(report.distinct_patterns, 2);
+        assert_eq!(report.patterns[0].pattern, "AAA-999");
+        assert_eq!(report.patterns[0].count, 4);
+        assert_eq!(report.patterns[0].coverage, 80.0);
+        assert_eq!(report.patterns[0].example, "ABC-123");
+        assert_eq!(report.dominant_pattern.as_deref(), Some("AAA-999"));
+
+        assert_eq!(report.anomaly_count, 1);
+        assert_eq!(report.anomalies[0].row, 4);
+        assert_eq!(report.anomalies[0].pattern, "aaa999");
+    }
+
+    #[test]
+    fn test_no_dominant_pattern_means_no_anomalies() {
+        let values = column(&["a", "12", "A-1", "x y"]);
+        let report = profile_patterns(0, &values);
+
+        assert_eq!(report.distinct_patterns, 4);
+        assert_eq!(report.dominant_pattern, None);
+        assert_eq!(report.anomaly_count, 0);
+
+        let empty = profile_patterns(0, &column(&["", " "]));
+        assert!(empty.patterns.is_empty());
+        assert_eq!(empty.dominant_pattern, None);
+    }
+
+    #[test]
+    fn test_pattern_report_bounds() {
+        let csv = CSV::from_string("code\nA-1\nB-2".to_string()).unwrap();
+        assert_eq!(
+            csv.pattern_report(0).unwrap().dominant_pattern.as_deref(),
+            Some("A-9")
+        );
+        assert!(csv.pattern_report(3).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::DataType;
+
+// Digits of an E.164 number, country code included
+const MIN_E164_DIGITS: usize = 8;
+const MAX_E164_DIGITS: usize = 15;
+
+// Country calling codes with the main country using them. No code is the prefix of another,
+// so the first match reading one to three digits is the code.
+const CALLING_CODES: &[(&str, &str)] = &[
+    ("1", "US"),
+    ("7", "RU"),
+    ("20", "EG"),
+    ("27", "ZA"),
+    ("30", "GR"),
+    ("31", "NL"),
+    ("32", "BE"),
+    ("33", "FR"),
+    ("34", "ES"),
+    ("36", "HU"),
+    ("39", "IT"),
+    ("40", "RO"),
+    ("41", "CH"),
+    ("43", "AT"),
+    ("44", "GB"),
+    ("45", "DK"),
+    ("46", "SE"),
+    ("47", "NO"),
+    ("48", "PL"),
+    ("49", "DE"),
+    ("51", "PE"),
+    ("52", "MX"),
+    ("53", "CU"),
+    ("54", "AR"),
+    ("55", "BR"),
+    ("56", "CL"),
+    ("57", "CO"),
+    ("58", "VE"),
+    ("60", "MY"),
+    ("61", "AU"),
+    ("62", "ID"),
+    ("63", "PH"),
+    ("64", "NZ"),
+    ("65", "SG"),
+    ("66", "TH"),
+    ("81", "JP"),
+    ("82", "KR"),
+    ("84", "VN"),
+    ("86", "CN"),
+    ("90", "TR"),
+    ("91", "IN"),
+    ("92", "PK"),
+    ("94", "LK"),
+    ("98", "IR"),
+    ("212", "MA"),
+    ("213", "DZ"),
+    ("216", "TN"),
+    ("234", "NG"),
+    ("254", "KE"),
+    ("351", "PT"),
+    ("352", "LU"),
+    ("353", "IE"),
+    ("354", "IS"),
+    ("358", "FI"),
+    ("359", "BG"),
+    ("370", "LT"),
+    ("371", "LV"),
+    ("372", "EE"),
+    ("380", "UA"),
+    ("385", "HR"),
+    ("386", "SI"),
+    ("420", "CZ"),
+    ("421", "SK"),
+    ("852", "HK"),
+    ("880", "BD"),
+    ("886", "TW"),
+    ("966", "SA"),
+    ("971", "AE"),
+    ("972", "IL"),
+];
+
+/// Numbers of a phone column with one country calling code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CountryCodeCount {
+    /// Calling code with its plus, e.g. `+44`
+    pub code: String,
+    /// ISO 3166 code of the main country using it; `+1` also covers Canada and the Caribbean
+    pub country: String,
+    pub count: usize,
+}
+
+/// Country breakdown of a phone column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PhoneStats {
+    /// Most frequent first
+    pub country_codes: Vec<CountryCodeCount>,
+    /// Numbers written without a country code, counted under `+1` when they have ten digits
+    pub national_count: usize,
+    /// Non-empty values with no E.164 form: letters, too few or many digits, an unknown
+    /// country code, or national numbers of other countries
+    pub unnormalizable_count: usize,
+}
+
+fn calling_code(digits: &str) -> Option<(&'static str, &'static str)> {
+    (1..=3.min(digits.len())).find_map(|length| {
+        CALLING_CODES
+            .iter()
+            .copied()
+            .find(|(code, _)| *code == &digits[..length])
+    })
+}
+
+/// A phone number in E.164 form, `+` then the country code and national number, e.g.
+/// `+442079460958` for `+44 (0)20 7946 0958`. Numbers without a country code take
+/// `default_code` after dropping a leading trunk `0`, or `+1` when they have ten digits;
+/// None when the value can't be read as a number.
+pub fn to_e164(value: &str, default_code: Option<&str>) -> Option<String> {
+    let value = value.trim();
+    let allowed = |c: char| c.is_ascii_digit() || " -.()/+".contains(c);
+    if value.is_empty() || !value.chars().all(allowed) || value.rfind('+').is_some_and(|i| i > 0) {
+        return None;
+    }
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+
+    // International numbers start with a plus, the 00 exit code or NANP's 011
+    let international = match value.starts_with('+') {
+        true => Some(digits.as_str()),
+        false => digits
+            .strip_prefix("00")
+            .or_else(|| digits.strip_prefix("011")),
+    };
+    let (code, national) = match international {
+        Some(rest) => {
+            let (code, _) = calling_code(rest)?;
+            let national = &rest[code.len()..];
+            // "+44 (0)20 ..." writes the trunk prefix dialled at home; Italy keeps its 0
+            match national.strip_prefix('0') {
+                Some(stripped) if code != "39" => (code, stripped.to_string()),
+                _ => (code, national.to_string()),
+            }
+        }
+        None if digits.len() == 10 && !digits.starts_with(['0', '1']) => ("1", digits),
+        None if digits.len() == 11 && digits.starts_with('1') => ("1", digits[1..].to_string()),
+        None => {
+            let code = default_code?;
+            let national = digits.strip_prefix('0').unwrap_or(&digits);
+            (code, national.to_string())
+        }
+    };
+
+    let length = code.len() + national.len();
+    let valid = match code {
+        "1" => national.len() == 10,
+        _ => (MIN_E164_DIGITS..=MAX_E164_DIGITS).contains(&length),
+    };
+    valid.then(|| format!("+{}{}", code, national))
+}
+
+/// Country code statistics of a phone column, None when it has no non-empty values
+pub fn calculate_phone_stats(values: &[String]) -> Option<PhoneStats> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let mut non_empty = 0;
+    let mut national_count = 0;
+    let mut unnormalizable_count = 0;
+
+    for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        non_empty += 1;
+        let Some(e164) = to_e164(value, None) else {
+            unnormalizable_count += 1;
+            continue;
+        };
+        if !value.starts_with('+') && !value.starts_with("00") && !value.starts_with("011") {
+            national_count += 1;
+        }
+        if let Some((code, _)) = calling_code(&e164[1..]) {
+            *counts.entry(code).or_default() += 1;
+        }
+    }
+    if non_empty == 0 {
+        return None;
+    }
+
+    let mut country_codes: Vec<CountryCodeCount> = counts
+        .into_iter()
+        .filter_map(|(code, count)| {
+            let (_, country) = calling_code(code)?;
+            Some(CountryCodeCount {
+                code: format!("+{}", code),
+                country: country.to_string(),
+                count,
+            })
+        })
+        .collect();
+    country_codes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+
+    Some(PhoneStats {
+        country_codes,
+        national_count,
+        unnormalizable_count,
+    })
+}
+
+impl CSV {
+    /// Appends a `<header>_e164` column with every number of a phone column in E.164 form,
+    /// e.g. `+14155550123`, empty where a value can't be normalized, and returns its index.
+    /// Numbers without a country code take `default_code`, such as `"44"`, when given.
+    pub fn derive_e164(
+        &mut self,
+        column: usize,
+        default_code: Option<&str>,
+    ) -> Result<usize, AnalysisError> {
+        let default_code = match default_code {
+            Some(code) => {
+                let digits = code.trim().trim_start_matches('+');
+                match calling_code(digits) {
+                    Some((known, _)) if known == digits => Some(known),
+                    _ => {
+                        return Err(AnalysisError::invalid_argument(format!(
+                            "Unknown country calling code '{}'",
+                            code
+                        )))
+                    }
+                }
+            }
+            None => None,
+        };
+        let (header, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let data_type = match self.column_metadata(column) {
+            Some(metadata) => metadata.data_type,
+            None => self
+                .infer_column(column)
+                .map_or(DataType::Text, |m| m.data_type),
+        };
+        if data_type != DataType::Phone {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Column {} is {}, not Phone",
+                column, data_type
+            )));
+        }
+
+        let header = format!("{}_e164", header);
+        let numbers = values
+            .iter()
+            .map(|value| to_e164(value, default_code).unwrap_or_default())
+            .collect();
+        Ok(self.push_derived_column(
+            header,
+            numbers,
+            &[column],
+            "e164",
+            default_code.map(|code| format!("+{}", code)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_e164() {
+        let test_cases = vec![
+            ("(415) 555-0123", None, Some("+14155550123")),
+            ("1-415-555-0123", None, Some("+14155550123")),
+            ("+44 (0)20 7946 0958", None, Some("+442079460958")),
+            ("0044 20 7946 0958", None, Some("+442079460958")),
+            ("+39 06 1234 5678", None, Some("+390612345678")),
+            ("020 7946 0958", None, None),
+            ("020 7946 0958", Some("44"), Some("+442079460958")),
+            ("+999 1234 5678", None, None),
+            ("555-0123", None, None),
+            ("call 415 555 0123", None, None),
+            ("+1 415 555 01234", None, None),
+        ];
+        for (value, default_code, expected) in test_cases {
+            assert_eq!(
+                to_e164(value, default_code).as_deref(),
+                expected,
+                "Failed for {}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_country_code_distribution() {
+        let values: Vec<String> = [
+            "+44 20 7946 0958",
+            "(415) 555-0123",
+            "+1 212 555 0199",
+            "",
+            "+49 30 1234567",
+            "+44 161 496 0000",
+            "+44 7700 900123",
+            "n/a",
+        ]
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+        let stats = calculate_phone_stats(&values).unwrap();
+        let codes: Vec<(&str, &str, usize)> = stats
+            .country_codes
+            .iter()
+            .map(|c| (c.code.as_str(), c.country.as_str(), c.count))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![("+44", "GB", 3), ("+1", "US", 2), ("+49", "DE", 1)]
+        );
+        assert_eq!(stats.national_count, 1);
+        assert_eq!(stats.unnormalizable_count, 1);
+    }
+
+    #[test]
+    fn test_derive_e164_column() {
+        let data = "phone\n020 7946 0958\n+1 415 555 0123\nunknown";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.override_type(0, DataType::Phone).unwrap();
+        assert!(csv.derive_e164(0, Some("+999")).is_err());
+
+        let index = csv.derive_e164(0, Some("+44")).unwrap();
+        let (header, values) = csv.get_column(index).unwrap();
+        assert_eq!(header, "phone_e164");
+        assert_eq!(values, &["+442079460958", "+14155550123", ""]);
+        assert!(csv.derive_e164(index + 1, None).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    credit_card::CreditCardType, email::EmailType, phone::PhoneType, TypeDetection,
+};
+
+const MAX_SAMPLE_ROWS: usize = 5;
+
+static SSN_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{3})-(\d{2})-(\d{4})$").unwrap());
+
+// Values that look like a person's name: one to four capitalized words
+static NAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\p{Lu}[\p{L}'.-]*(?:,? \p{Lu}[\p{L}'.-]*){0,3}$").unwrap());
+
+// Header words suggesting a column holds people's names
+const NAME_HEADER_HINTS: &[&str] = &[
+    "name",
+    "firstname",
+    "lastname",
+    "fullname",
+    "surname",
+    "forename",
+    "givenname",
+    "familyname",
+];
+// Headers that contain "name" but refer to things rather than people
+const NON_PERSON_NAME_HEADERS: &[&str] = &[
+    "user", "file", "host", "company", "product", "brand", "city", "country", "street", "domain",
+    "table", "column",
+];
+
+/// Kinds of personally identifiable information the detector recognizes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Name,
+    CreditCard,
+    Ssn,
+}
+
+impl PiiKind {
+    /// Placeholder used when redacting values of this kind
+    pub fn redaction_label(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "[EMAIL]",
+            PiiKind::Phone => "[PHONE]",
+            PiiKind::Name => "[NAME]",
+            PiiKind::CreditCard => "[CREDIT_CARD]",
+            PiiKind::Ssn => "[SSN]",
+        }
+    }
+}
+
+/// Cells of one PII kind found in a column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PiiColumnReport {
+    pub column: usize,
+    pub name: String,
+    pub kind: PiiKind,
+    pub matches: usize,
+    /// Fraction of non-empty values that matched
+    pub ratio: f64,
+    pub sample_rows: Vec<usize>,
+}
+
+/// Every column containing PII, one entry per column and kind
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PiiReport {
+    pub flagged_cells: usize,
+    pub columns: Vec<PiiColumnReport>,
+}
+
+/// US Social Security numbers in `AAA-GG-SSSS` form, excluding ranges that are never issued
+pub fn is_ssn(value: &str) -> bool {
+    let Some(captures) = SSN_PATTERN.captures(value) else {
+        return false;
+    };
+    let area = &captures[1];
+    area != "000"
+        && area != "666"
+        && !area.starts_with('9')
+        && &captures[2] != "00"
+        && &captures[3] != "0000"
+}
+
+/// True when a header suggests the column holds people's names
+pub fn is_name_header(header: &str) -> bool {
+    let normalized: String = header
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    NAME_HEADER_HINTS
+        .iter()
+        .any(|hint| normalized.contains(hint))
+        && !NON_PERSON_NAME_HEADERS
+            .iter()
+            .any(|word| normalized.contains(word))
+}
+
+/// Classifies a single value; names are only recognized in columns whose header suggests them
+pub fn classify_value(value: &str, name_column: bool) -> Option<PiiKind> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    // Card numbers are checked before phones since long digit runs can satisfy both shapes
+    if EmailType::is_definite_match(value) {
+        Some(PiiKind::Email)
+    } else if CreditCardType::is_definite_match(value) {
+        Some(PiiKind::CreditCard)
+    } else if is_ssn(value) {
+        Some(PiiKind::Ssn)
+    } else if PhoneType::is_definite_match(value) {
+        Some(PiiKind::Phone)
+    } else if name_column && NAME_PATTERN.is_match(value) {
+        Some(PiiKind::Name)
+    } else {
+        None
+    }
+}
+
+pub fn detect_pii(columns: &[(&str, &[String])]) -> PiiReport {
+    let mut reports = Vec::new();
+
+    for (column, (header, values)) in columns.iter().enumerate() {
+        let name_column = is_name_header(header);
+        let mut found: Vec<PiiColumnReport> = Vec::new();
+        let mut non_empty = 0;
+
+        for (row, value) in values.iter().enumerate() {
+            if value.trim().is_empty() {
+                continue;
+            }
+            non_empty += 1;
+            let Some(kind) = classify_value(value, name_column) else {
+                continue;
+            };
+
+            let index = match found.iter().position(|r| r.kind == kind) {
+                Some(index) => index,
+                None => {
+                    found.push(PiiColumnReport {
+                        column,
+                        name: header.to_string(),
+                        kind,
+                        matches: 0,
+                        ratio: 0.0,
+                        sample_rows: Vec::new(),
+                    });
+                    found.len() - 1
+                }
+            };
+            let report = &mut found[index];
+            report.matches += 1;
+            if report.sample_rows.len() < MAX_SAMPLE_ROWS {
+                report.sample_rows.push(row);
+            }
+        }
+
+        for report in found.iter_mut() {
+            report.ratio = report.matches as f64 / non_empty as f64;
+        }
+        found.sort_by_key(|r| r.kind);
+        reports.extend(found);
+    }
+
+    PiiReport {
+        flagged_cells: reports.iter().map(|r| r.matches).sum(),
+        columns: reports,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_classify_value() {
+        let test_cases = vec![
+            ("jane.doe@example.com", false, Some(PiiKind::Email)),
+            ("4111 1111 1111 1111", false, Some(PiiKind::CreditCard)),
+            ("4111 1111 1111 1112", false, None),
+            ("123-45-6789", false, Some(PiiKind::Ssn)),
+            ("666-45-6789", false, None),
+            ("(555) 123-4567", false, Some(PiiKind::Phone)),
+            ("Jane Doe", true, Some(PiiKind::Name)),
+            ("Jane Doe", false, None),
+            ("O'Brien, Mary-Kate", true, Some(PiiKind::Name)),
+            ("not a name", true, None),
+            ("", true, None),
+        ];
+        for (value, name_column, expected) in test_cases {
+            assert_eq!(
+                classify_value(value, name_column),
+                expected,
+                "Failed for input: {}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_name_headers() {
+        let test_cases = vec![
+            ("Name", true),
+            ("first_name", true),
+            ("Customer Full Name", true),
+            ("Surname", true),
+            ("username", false),
+            ("file_name", false),
+            ("Company Name", false),
+            ("status", false),
+        ];
+        for (header, expected) in test_cases {
+            assert_eq!(
+                is_name_header(header),
+                expected,
+                "Failed for header: {}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_pii() {
+        let names = column(&["Ada Lovelace", "Alan Turing", ""]);
+        let contacts = column(&["ada@example.com", "(555) 123-4567", "n/a"]);
+        let amounts = column(&["10", "20", "30"]);
+        let columns: Vec<(&str, &[String])> = vec![
+            ("full_name", &names),
+            ("contact", &contacts),
+            ("amount", &amounts),
+        ];
+        let report = detect_pii(&columns);
+
+        assert_eq!(report.flagged_cells, 4);
+        let summary: Vec<(usize, PiiKind, usize)> = report
+            .columns
+            .iter()
+            .map(|r| (r.column, r.kind, r.matches))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, PiiKind::Name, 2),
+                (1, PiiKind::Email, 1),
+                (1, PiiKind::Phone, 1)
+            ]
+        );
+        assert_eq!(report.columns[0].ratio, 1.0);
+        assert_eq!(report.columns[2].sample_rows, vec![1]);
+    }
+}
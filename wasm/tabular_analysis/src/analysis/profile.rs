@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::rules::{Rule, RuleReport};
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::warning::AnalysisWarning;
+
+/// Table-level profiling report combining per-column metadata with data quality counts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileReport {
+    pub row_count: usize,
+    pub column_count: usize,
+    /// Rows that exactly repeat an earlier row
+    pub duplicate_row_count: usize,
+    /// Share of non-empty cells
+    pub completeness: f64,
+    /// Overall quality from 0 to 1: the mean of completeness, the share of unique rows
+    /// and, when rules were given, their pass rate
+    pub quality_score: f64,
+    pub rules: Option<RuleReport>,
+    pub columns: Vec<ColumnMetadata>,
+    /// Conditions that may make the figures above misleading, e.g. a small sample
+    #[serde(default)]
+    pub warnings: Vec<AnalysisWarning>,
+}
+
+impl ProfileReport {
+    /// Renders the report as Markdown: the table-level counts followed by `column_table` and
+    /// the lineage of columns made by cleaning, split, merge or computed expressions
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "| Rows | Columns | Duplicate rows | Completeness | Quality score |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | {} | {} | {} | {:.1}% | {:.3} |\n",
+            self.row_count,
+            self.column_count,
+            self.duplicate_row_count,
+            self.completeness * 100.0,
+            self.quality_score
+        );
+        if let Some(rules) = &self.rules {
+            markdown.push_str(&format!(
+                "\nRules pass rate: {:.1}%\n",
+                rules.pass_rate * 100.0
+            ));
+        }
+        markdown.push('\n');
+        markdown.push_str(&column_table(&self.columns));
+        let derived: Vec<&ColumnMetadata> = self
+            .columns
+            .iter()
+            .filter(|c| c.lineage.is_some())
+            .collect();
+        if !derived.is_empty() {
+            markdown.push_str("\nDerived columns:\n");
+            for column in derived {
+                if let Some(lineage) = &column.lineage {
+                    markdown.push_str(&format!("- {}: {}\n", column.name, lineage.describe()));
+                }
+            }
+        }
+        if !self.warnings.is_empty() {
+            markdown.push_str("\nWarnings:\n");
+            for warning in &self.warnings {
+                markdown.push_str(&format!("- {}\n", warning.message));
+            }
+        }
+        markdown
+    }
+}
+
+/// Markdown table of each column's type, confidence, SQL type and numeric range
+pub fn column_table(columns: &[ColumnMetadata]) -> String {
+    let rows: Vec<Vec<String>> = columns
+        .iter()
+        .map(|column| {
+            let range = column
+                .numeric_stats
+                .as_ref()
+                .map_or(String::new(), |stats| {
+                    format!("{} to {}", stats.min, stats.max)
+                });
+            vec![
+                column.name.clone(),
+                column.data_type.to_string(),
+                format!("{:.2}", column.confidence),
+                column
+                    .sql_type
+                    .clone()
+                    .unwrap_or_else(|| column.data_type.default_sql_type().to_string()),
+                range,
+            ]
+        })
+        .collect();
+    markdown_table(
+        &["Column", "Type", "Confidence", "SQL type", "Range"],
+        &rows,
+    )
+}
+
+/// Markdown table with the given header row, escaping pipes and line breaks in cells
+pub fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let escape = |cell: &str| cell.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+
+    let mut markdown = line(headers.iter().map(|h| escape(h)).collect());
+    markdown.push_str(&line(headers.iter().map(|_| "---".to_string()).collect()));
+    for row in rows {
+        markdown.push_str(&line(row.iter().map(|cell| escape(cell)).collect()));
+    }
+    markdown
+}
+
+impl CSV {
+    /// Profiles the CSV, inferring types for any column not analyzed yet
+    pub fn build_profile(&mut self) -> ProfileReport {
+        // Without rules there is nothing that can fail
+        self.build_profile_with_rules(&[])
+            .expect("profiling without rules never fails")
+    }
+
+    /// Profiles the CSV, counting the pass rate of `rules` towards the quality score
+    pub fn build_profile_with_rules(
+        &mut self,
+        rules: &[Rule],
+    ) -> Result<ProfileReport, AnalysisError> {
+        if (0..self.column_count()).any(|i| self.column_metadata(i).is_none()) {
+            self.infer_column_types();
+        }
+
+        let duplicate_row_count = self
+            .duplicate_report(None)
+            .map(|report| report.duplicate_row_count)
+            .unwrap_or(0);
+        let rules = if rules.is_empty() {
+            None
+        } else {
+            Some(self.rule_report(rules)?)
+        };
+
+        let cell_count = self.row_count() * self.column_count();
+        let empty_count: usize = self
+            .get_columns()
+            .iter()
+            .map(|(_, values)| values.iter().filter(|v| v.trim().is_empty()).count())
+            .sum();
+        let share = |count: usize, total: usize| {
+            if total == 0 {
+                1.0
+            } else {
+                1.0 - count as f64 / total as f64
+            }
+        };
+        let completeness = share(empty_count, cell_count);
+
+        let mut components = vec![completeness, share(duplicate_row_count, self.row_count())];
+        components.extend(rules.as_ref().map(|report| report.pass_rate));
+        let quality_score = components.iter().sum::<f64>() / components.len() as f64;
+
+        Ok(ProfileReport {
+            row_count: self.row_count(),
+            column_count: self.column_count(),
+            duplicate_row_count,
+            completeness,
+            quality_score,
+            rules,
+            columns: (0..self.column_count())
+                .filter_map(|i| self.column_metadata(i).cloned())
+                .collect(),
+            warnings: self.warnings(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::rules::parse_rules;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_profile_report() {
+        let data = "id,status\n1,active\n2,pending\n1,active\n3,active";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let report = csv.build_profile();
+
+        assert_eq!(report.row_count, 4);
+        assert_eq!(report.column_count, 2);
+        assert_eq!(report.duplicate_row_count, 1);
+        assert_eq!(report.columns.len(), 2);
+        assert_eq!(report.columns[0].data_type, DataType::Integer);
+        assert_eq!(report.columns[0].numeric_stats.as_ref().unwrap().max, 3.0);
+        assert!(report.columns[1].text_stats.is_some());
+        assert_eq!(report.completeness, 1.0);
+        assert_eq!(report.quality_score, 0.875);
+        assert!(report.rules.is_none());
+    }
+
+    #[test]
+    fn test_rules_lower_quality_score() {
+        let data = "id,status\n1,active\n2,\n3,gone\n4,active";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let rules =
+            parse_rules(r#"[{"rule": "in", "column": "status", "values": ["active"]}]"#).unwrap();
+        let report = csv.build_profile_with_rules(&rules).unwrap();
+
+        assert_eq!(report.completeness, 0.875);
+        assert_eq!(report.rules.as_ref().unwrap().pass_rate, 2.0 / 3.0);
+        assert!((report.quality_score - (0.875 + 1.0 + 2.0 / 3.0) / 3.0).abs() < 1e-12);
+        assert_eq!(csv.build_profile().quality_score, 0.9375);
+    }
+
+    #[test]
+    fn test_profile_date_stats() {
+        let data = "day\n2024-01-01\n2024-01-02\n2024-01-04";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let report = csv.build_profile();
+
+        let column = &report.columns[0];
+        assert_eq!(column.data_type, DataType::Date);
+        assert!(column.numeric_stats.is_none());
+        let stats = column.date_stats.as_ref().unwrap();
+        assert_eq!(stats.span_days, 3);
+        assert_eq!(stats.gap_count, 1);
+    }
+
+    #[test]
+    fn test_profile_markdown() {
+        let data = "id,note\n1,a|b\n2,c";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let markdown = csv.build_profile().to_markdown();
+
+        assert!(markdown.starts_with("| Rows | Columns |"));
+        assert!(markdown.contains("| 2 | 2 | 0 | 100.0% | 1.000 |"));
+        assert!(markdown.contains("| id | Integer | 1.00 | "));
+        assert!(markdown.contains("Warnings:\n- Only 2 rows"));
+        assert!(!markdown.contains("Derived columns:"));
+
+        csv.merge_column_values(&[0, 1], "-").unwrap();
+        let markdown = csv.build_profile().to_markdown();
+        assert!(markdown.contains("Derived columns:\n- id_note: id, note via merge\n"));
+
+        let table = markdown_table(&["value"], &[vec!["a|b\nc".to_string()]]);
+        assert_eq!(table, "| value |\n| --- |\n| a\\|b c |\n");
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::parallel::ParallelExecutor;
+use crate::table::rows::typed_value;
+
+// Residuals this many standard errors from the line are reported as outliers
+const OUTLIER_THRESHOLD: f64 = 3.0;
+
+/// Means and co-moments of (x, y) pairs, mergeable so chunks can be summed independently
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PairSums {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    /// Sum of squared deviations of x
+    m2_x: f64,
+    m2_y: f64,
+    /// Sum of products of the x and y deviations
+    c_xy: f64,
+}
+
+impl PairSums {
+    pub fn from_pairs(pairs: &[(f64, f64)]) -> Self {
+        let mut sums = PairSums::default();
+        for &(x, y) in pairs {
+            sums.push(x, y);
+        }
+        sums
+    }
+
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// Combines two accumulators as if every pair had been pushed into one (Chan et al.)
+    pub fn merge(&mut self, other: &PairSums) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        self.m2_x += other.m2_x + dx * dx * na * nb / n;
+        self.m2_y += other.m2_y + dy * dy * na * nb / n;
+        self.c_xy += other.c_xy + dx * dy * na * nb / n;
+        self.mean_x += dx * nb / n;
+        self.mean_y += dy * nb / n;
+        self.count += other.count;
+    }
+}
+
+/// A row whose y value lies far from the fitted line
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ResidualOutlier {
+    pub row: usize,
+    pub x: f64,
+    pub y: f64,
+    pub predicted: f64,
+    pub residual: f64,
+}
+
+/// Least-squares line `y = slope * x + intercept` through the rows where both values are
+/// numeric
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    /// Share of the variance of y explained by the line, from 0 to 1
+    pub r_squared: f64,
+    /// Rows used for the fit
+    pub observations: usize,
+    /// Standard deviation of the residuals
+    pub residual_std_error: f64,
+    pub outliers: Vec<ResidualOutlier>,
+}
+
+/// Fits a line through `(row, x, y)` points, reporting points whose residual is more than
+/// three residual standard errors
+pub fn fit_line(points: &[(usize, f64, f64)]) -> Result<LinearFit, AnalysisError> {
+    fit_line_with(&ParallelExecutor::for_host(), points)
+}
+
+/// `fit_line` with the sums of each chunk of points computed on `executor`
+pub fn fit_line_with(
+    executor: &ParallelExecutor,
+    points: &[(usize, f64, f64)],
+) -> Result<LinearFit, AnalysisError> {
+    let pairs: Vec<(f64, f64)> = points.iter().map(|&(_, x, y)| (x, y)).collect();
+    // Summing a chunk can't fail, so only a cancelled executor returns an error
+    let sums = executor.process_column(
+        &pairs,
+        PairSums::default,
+        |chunk| Ok(PairSums::from_pairs(chunk)),
+        |mut total, chunk| {
+            total.merge(&chunk);
+            total
+        },
+    )?;
+
+    if sums.count < 2 {
+        return Err(AnalysisError::invalid_argument(
+            "At least two rows with numeric values are required",
+        ));
+    }
+    if sums.m2_x == 0.0 {
+        return Err(AnalysisError::invalid_argument(
+            "The x column has the same value in every row",
+        ));
+    }
+
+    let slope = sums.c_xy / sums.m2_x;
+    let intercept = sums.mean_y - slope * sums.mean_x;
+    let explained = slope * sums.c_xy;
+    let r_squared = if sums.m2_y == 0.0 {
+        1.0
+    } else {
+        (explained / sums.m2_y).clamp(0.0, 1.0)
+    };
+    let residual_sum = (sums.m2_y - explained).max(0.0);
+    let residual_std_error = if sums.count > 2 {
+        (residual_sum / (sums.count - 2) as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    let outliers = points
+        .iter()
+        .filter_map(|&(row, x, y)| {
+            let predicted = slope * x + intercept;
+            let residual = y - predicted;
+            (residual_std_error > 0.0 && residual.abs() > OUTLIER_THRESHOLD * residual_std_error)
+                .then_some(ResidualOutlier {
+                    row,
+                    x,
+                    y,
+                    predicted,
+                    residual,
+                })
+        })
+        .collect();
+
+    Ok(LinearFit {
+        slope,
+        intercept,
+        r_squared,
+        observations: sums.count,
+        residual_std_error,
+        outliers,
+    })
+}
+
+impl CSV {
+    /// Least-squares line predicting `y_col` from `x_col`, over the rows where both are numeric
+    pub fn linear_fit(&self, x_col: usize, y_col: usize) -> Result<LinearFit, AnalysisError> {
+        let numbers = |index: usize| -> Result<Vec<Option<f64>>, AnalysisError> {
+            let (_, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let metadata = self.column_metadata(index);
+            Ok(values
+                .iter()
+                .map(|value| match typed_value(value, metadata) {
+                    Value::Number(n) => n.as_f64(),
+                    _ => None,
+                })
+                .collect())
+        };
+
+        let points: Vec<(usize, f64, f64)> = numbers(x_col)?
+            .into_iter()
+            .zip(numbers(y_col)?)
+            .enumerate()
+            .filter_map(|(row, (x, y))| Some((row, x?, y?)))
+            .collect();
+        fit_line(&points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_sums_match_sequential() {
+        let pairs: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, (i * i % 17) as f64)).collect();
+        let whole = PairSums::from_pairs(&pairs);
+        let mut merged = PairSums::from_pairs(&pairs[..37]);
+        merged.merge(&PairSums::from_pairs(&pairs[37..]));
+
+        assert_eq!(merged.count, whole.count);
+        assert!((merged.mean_y - whole.mean_y).abs() < 1e-9);
+        assert!((merged.c_xy - whole.c_xy).abs() < 1e-6);
+        assert!((merged.m2_x - whole.m2_x).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fit_over_many_chunks() {
+        let points: Vec<(usize, f64, f64)> = (0..1000)
+            .map(|i| (i, i as f64, 3.0 * i as f64 - 7.0 + (i % 5) as f64))
+            .collect();
+        let chunked = fit_line_with(&ParallelExecutor::new().with_chunk_size(64), &points).unwrap();
+        let whole = fit_line_with(
+            &ParallelExecutor::sequential().with_chunk_size(1000),
+            &points,
+        )
+        .unwrap();
+
+        assert_eq!(chunked.observations, 1000);
+        assert!((chunked.slope - whole.slope).abs() < 1e-9);
+        assert!((chunked.intercept - whole.intercept).abs() < 1e-9);
+        assert!((chunked.r_squared - whole.r_squared).abs() < 1e-9);
+        assert!((chunked.residual_std_error - whole.residual_std_error).abs() < 1e-9);
+
+        let cancelled =
+            ParallelExecutor::sequential().with_cancel_flag(std::sync::Arc::new(true.into()));
+        assert_eq!(
+            fit_line_with(&cancelled, &points),
+            Err(AnalysisError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_linear_fit() {
+        let mut data = "x,y\n".to_string();
+        for i in 0..20 {
+            let y = if i == 7 { 100.0 } else { 2.0 * i as f64 + 1.0 };
+            data.push_str(&format!("{},{}\n", i, y));
+        }
+        data.push_str("20,\n");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+
+        let fit = csv.linear_fit(0, 1).unwrap();
+        assert_eq!(fit.observations, 20);
+        assert!(fit.slope > 1.5 && fit.slope < 2.5);
+        assert!(fit.r_squared < 1.0);
+        assert_eq!(fit.outliers.len(), 1);
+        assert_eq!(fit.outliers[0].row, 7);
+    }
+
+    #[test]
+    fn test_exact_fit_and_errors() {
+        let fit = fit_line(&[(0, 1.0, 3.0), (1, 2.0, 5.0), (2, 4.0, 9.0)]).unwrap();
+        assert!((fit.slope - 2.0).abs() < 1e-12);
+        assert!((fit.intercept - 1.0).abs() < 1e-12);
+        assert!((fit.r_squared - 1.0).abs() < 1e-12);
+        assert!(fit.outliers.is_empty());
+
+        assert!(fit_line(&[(0, 1.0, 3.0)]).is_err());
+        assert!(fit_line(&[(0, 1.0, 3.0), (1, 1.0, 4.0)]).is_err());
+    }
+}
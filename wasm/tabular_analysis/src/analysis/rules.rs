@@ -0,0 +1,307 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+use crate::types::big_integer::{compare_integer_with_float, compare_integers};
+
+// Failing rows listed per rule; every failure is still counted
+const MAX_FAILING_ROWS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Greater => ordering.is_gt(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::NotEqual => ordering.is_ne(),
+        }
+    }
+}
+
+/// A user-defined check on the values of one or two columns, referenced by header.
+/// Apart from `not_null`, rules skip empty cells.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Rule {
+    NotNull {
+        column: String,
+    },
+    /// The value, ignoring surrounding whitespace, is one of `values`
+    In {
+        column: String,
+        values: Vec<String>,
+    },
+    /// The whole value matches a regular expression
+    Matches {
+        column: String,
+        pattern: String,
+    },
+    /// Compares two columns row by row, as numbers or dates when the columns were
+    /// inferred as such and as text otherwise
+    Compare {
+        left: String,
+        op: Comparison,
+        right: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RuleResult {
+    pub rule: Rule,
+    pub passed: usize,
+    pub failed: usize,
+    /// The first failing row indices
+    pub failing_rows: Vec<usize>,
+}
+
+/// Outcome of a rule set; `pass_rate` is the share of checked cells that passed
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RuleReport {
+    pub results: Vec<RuleResult>,
+    pub passed: usize,
+    pub failed: usize,
+    pub pass_rate: f64,
+}
+
+/// Parses a JSON array of rules
+pub fn parse_rules(json: &str) -> Result<Vec<Rule>, AnalysisError> {
+    serde_json::from_str(json).map_err(|e| AnalysisError::InvalidRule {
+        message: format!("Invalid rules: {}", e),
+    })
+}
+
+// `big_integers` tells which sides come from big-integer columns, whose digit strings are
+// ordered by value rather than as text
+fn compare(left: &Value, right: &Value, big_integers: (bool, bool)) -> Option<Ordering> {
+    match (left, right, big_integers) {
+        (Value::Number(a), Value::Number(b), _) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b), (true, true)) => Some(compare_integers(a, b)),
+        (Value::String(a), Value::Number(b), (true, _)) => {
+            compare_integer_with_float(a, b.as_f64()?)
+        }
+        (Value::Number(a), Value::String(b), (_, true)) => {
+            compare_integer_with_float(b, a.as_f64()?).map(Ordering::reverse)
+        }
+        (Value::String(a), Value::String(b), _) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+impl CSV {
+    // Index of the column with the given header
+    fn rule_column(&self, header: &str) -> Result<usize, AnalysisError> {
+        self.get_columns()
+            .iter()
+            .position(|(h, _)| *h == header)
+            .ok_or_else(|| AnalysisError::UnknownColumn {
+                header: header.to_string(),
+            })
+    }
+
+    // Outcome of a rule for every row it checks, as (row, passed)
+    fn rule_outcomes(&self, rule: &Rule) -> Result<Vec<(usize, bool)>, AnalysisError> {
+        let values = |header: &str| -> Result<&[String], AnalysisError> {
+            let index = self.rule_column(header)?;
+            Ok(self
+                .get_column(index)
+                .map(|(_, values)| values)
+                .unwrap_or(&[]))
+        };
+        let non_empty = |values: &'_ [String]| {
+            values
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| !value.trim().is_empty())
+                .map(|(row, value)| (row, value.trim().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let outcomes = match rule {
+            Rule::NotNull { column } => values(column)?
+                .iter()
+                .enumerate()
+                .map(|(row, value)| (row, !value.trim().is_empty()))
+                .collect(),
+            Rule::In {
+                column,
+                values: allowed,
+            } => non_empty(values(column)?)
+                .into_iter()
+                .map(|(row, value)| (row, allowed.iter().any(|a| a.trim() == value)))
+                .collect(),
+            Rule::Matches { column, pattern } => {
+                let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+                    AnalysisError::InvalidPattern {
+                        pattern: pattern.clone(),
+                        message: e.to_string(),
+                    }
+                })?;
+                non_empty(values(column)?)
+                    .into_iter()
+                    .map(|(row, value)| (row, regex.is_match(&value)))
+                    .collect()
+            }
+            Rule::Compare { left, op, right } => {
+                let (left, right) = (self.rule_column(left)?, self.rule_column(right)?);
+                let (_, left_values) = self.get_column(left).unwrap_or_default();
+                let (_, right_values) = self.get_column(right).unwrap_or_default();
+                let big_integer = |index| {
+                    self.column_metadata(index)
+                        .is_some_and(|m| m.big_integer.is_some())
+                };
+                let big_integers = (big_integer(left), big_integer(right));
+                left_values
+                    .iter()
+                    .zip(right_values)
+                    .enumerate()
+                    .filter(|(_, (a, b))| !a.trim().is_empty() && !b.trim().is_empty())
+                    .map(|(row, (a, b))| {
+                        let a = typed_value(a.trim(), self.column_metadata(left));
+                        let b = typed_value(b.trim(), self.column_metadata(right));
+                        (
+                            row,
+                            compare(&a, &b, big_integers).is_some_and(|o| op.holds(o)),
+                        )
+                    })
+                    .collect()
+            }
+        };
+        Ok(outcomes)
+    }
+
+    /// Runs validation rules, reporting the failing rows of each
+    pub fn rule_report(&self, rules: &[Rule]) -> Result<RuleReport, AnalysisError> {
+        let results = rules
+            .iter()
+            .map(|rule| {
+                let outcomes = self.rule_outcomes(rule)?;
+                let failing: Vec<usize> = outcomes
+                    .iter()
+                    .filter(|(_, passed)| !passed)
+                    .map(|(row, _)| *row)
+                    .collect();
+                Ok(RuleResult {
+                    rule: rule.clone(),
+                    passed: outcomes.len() - failing.len(),
+                    failed: failing.len(),
+                    failing_rows: failing.into_iter().take(MAX_FAILING_ROWS).collect(),
+                })
+            })
+            .collect::<Result<Vec<_>, AnalysisError>>()?;
+
+        let passed: usize = results.iter().map(|r| r.passed).sum();
+        let failed: usize = results.iter().map(|r| r.failed).sum();
+        Ok(RuleReport {
+            results,
+            passed,
+            failed,
+            pass_rate: if passed + failed == 0 {
+                1.0
+            } else {
+                passed as f64 / (passed + failed) as f64
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_report() {
+        let data = "id,status,code,start,end\n\
+                    1,active,AB-1,2024-01-01,2024-02-01\n\
+                    ,paused,AB-22,2024-03-01,2024-02-01\n\
+                    3,unknown,x,,2024-01-01";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let rules = parse_rules(
+            r#"[
+                {"rule": "not_null", "column": "id"},
+                {"rule": "in", "column": "status", "values": ["active", "paused"]},
+                {"rule": "matches", "column": "code", "pattern": "[A-Z]{2}-\\d+"},
+                {"rule": "compare", "left": "start", "op": "<", "right": "end"}
+            ]"#,
+        )
+        .unwrap();
+        let report = csv.rule_report(&rules).unwrap();
+
+        let failing: Vec<&[usize]> = report
+            .results
+            .iter()
+            .map(|r| r.failing_rows.as_slice())
+            .collect();
+        assert_eq!(failing, vec![&[1][..], &[2], &[2], &[1]]);
+        assert_eq!(
+            report.results[3].passed, 1,
+            "rows with an empty side are skipped"
+        );
+        assert_eq!(report.passed, 7);
+        assert_eq!(report.failed, 4);
+        assert_eq!(report.pass_rate, 7.0 / 11.0);
+    }
+
+    #[test]
+    fn test_numeric_comparison_and_errors() {
+        let mut csv = CSV::from_string("low,high\n9,10\n2,1.5".to_string()).unwrap();
+        csv.infer_column_types();
+        let rules =
+            parse_rules(r#"[{"rule": "compare", "left": "low", "op": "<=", "right": "high"}]"#)
+                .unwrap();
+        assert_eq!(
+            csv.rule_report(&rules).unwrap().results[0].failing_rows,
+            vec![1]
+        );
+
+        assert!(parse_rules(r#"[{"rule": "unique", "column": "low"}]"#).is_err());
+        let unknown = parse_rules(r#"[{"rule": "not_null", "column": "mid"}]"#).unwrap();
+        assert!(csv.rule_report(&unknown).is_err());
+        let bad_regex =
+            parse_rules(r#"[{"rule": "matches", "column": "low", "pattern": "("}]"#).unwrap();
+        assert!(csv.rule_report(&bad_regex).is_err());
+    }
+
+    #[test]
+    fn test_big_integer_comparison() {
+        let data = "low,high,small\n\
+                    9999999999999999999,10000000000000000000,5\n\
+                    -20000000000000000000,-3000000000000000000,-7\n\
+                    900719925474099300,900719925474099299,1.5";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        let rules = parse_rules(
+            r#"[
+                {"rule": "compare", "left": "low", "op": "<", "right": "high"},
+                {"rule": "compare", "left": "small", "op": "<", "right": "high"}
+            ]"#,
+        )
+        .unwrap();
+        let report = csv.rule_report(&rules).unwrap();
+        assert_eq!(report.results[0].failing_rows, vec![2]);
+        assert_eq!(report.results[1].failing_rows, vec![1]);
+    }
+}
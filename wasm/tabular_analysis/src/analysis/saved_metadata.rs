@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AnalysisConfig, ANALYSIS_VERSION};
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+
+/// Version written into exported metadata; bump it when the layout changes incompatibly
+pub const METADATA_VERSION: u32 = 1;
+
+/// Everything inference computed for a CSV, saved so it can be applied to another file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedMetadata {
+    pub version: u32,
+    /// Version of the inference rules that produced the metadata
+    #[serde(default)]
+    pub analysis_version: u32,
+    /// Hash of `config`, equal for metadata computed with the same options
+    #[serde(default)]
+    pub config_hash: String,
+    /// Options the metadata was computed with
+    pub config: AnalysisConfig,
+    /// Metadata of every analyzed column, matched to columns by name on import
+    pub columns: Vec<ColumnMetadata>,
+}
+
+impl SavedMetadata {
+    /// Parses metadata written by `export_metadata`, rejecting versions newer than this one
+    pub fn from_json(json: &str) -> Result<SavedMetadata, AnalysisError> {
+        let saved: SavedMetadata =
+            serde_json::from_str(json).map_err(|e| AnalysisError::InvalidMetadata {
+                message: format!("Invalid metadata: {}", e),
+            })?;
+        if saved.version > METADATA_VERSION {
+            return Err(AnalysisError::InvalidMetadata {
+                message: format!(
+                    "Metadata version {} is newer than the supported version {}",
+                    saved.version, METADATA_VERSION
+                ),
+            });
+        }
+        Ok(saved)
+    }
+}
+
+/// Which columns received saved metadata on import
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetadataImportReport {
+    pub applied_columns: Vec<String>,
+    /// Saved columns with no matching header in this CSV
+    pub missing_columns: Vec<String>,
+    /// Columns of this CSV that the saved metadata doesn't describe
+    pub unmatched_columns: Vec<String>,
+}
+
+impl CSV {
+    /// Per-column metadata and analysis options, in the layout `apply_saved_metadata` reads
+    pub fn saved_metadata(&self) -> SavedMetadata {
+        SavedMetadata {
+            version: METADATA_VERSION,
+            analysis_version: ANALYSIS_VERSION,
+            config_hash: self.analysis_config().hash(),
+            config: self.analysis_config().clone(),
+            columns: (0..self.column_count())
+                .filter_map(|i| self.column_metadata(i).cloned())
+                .collect(),
+        }
+    }
+
+    /// Serializes `saved_metadata` to a versioned JSON string
+    pub fn export_metadata(&self) -> Result<String, AnalysisError> {
+        serde_json::to_string(&self.saved_metadata()).map_err(|e| {
+            AnalysisError::SerializationError {
+                message: format!("Failed to serialize metadata: {}", e),
+            }
+        })
+    }
+
+    /// Applies exported metadata to the columns with the same headers, skipping inference for them
+    pub fn apply_saved_metadata(
+        &mut self,
+        json: &str,
+    ) -> Result<MetadataImportReport, AnalysisError> {
+        let saved = SavedMetadata::from_json(json)?;
+        self.apply_config(saved.config)?;
+
+        let headers: Vec<String> = self
+            .get_columns()
+            .iter()
+            .map(|(header, _)| header.to_string())
+            .collect();
+        let mut report = MetadataImportReport {
+            applied_columns: Vec::new(),
+            missing_columns: Vec::new(),
+            unmatched_columns: Vec::new(),
+        };
+        for metadata in saved.columns {
+            match headers.iter().position(|header| *header == metadata.name) {
+                Some(index) => {
+                    report.applied_columns.push(metadata.name.clone());
+                    self.replace_column_metadata(index, metadata)?;
+                }
+                None => report.missing_columns.push(metadata.name),
+            }
+        }
+        report.unmatched_columns = headers
+            .into_iter()
+            .filter(|header| !report.applied_columns.contains(header))
+            .collect();
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_identical_input_gives_identical_output() {
+        let mut data = "id,name,status,joined,weight,notes\n".to_string();
+        for i in 0..40 {
+            data.push_str(&format!(
+                "{},user{},{},2024-01-{:02},{} kg,{}\n",
+                i,
+                i % 13,
+                ["active", "inactive"][i % 2],
+                i % 28 + 1,
+                50 + i % 7,
+                ["a", "b", "c", "d", "1"][i % 5]
+            ));
+        }
+        let export = || {
+            let mut csv = CSV::from_string(data.clone()).unwrap();
+            csv.infer_column_types();
+            let profile = serde_json::to_string(&csv.build_profile()).unwrap();
+            (csv.export_metadata().unwrap(), profile)
+        };
+
+        let (metadata, profile) = export();
+        for _ in 0..5 {
+            assert_eq!(export(), (metadata.clone(), profile.clone()));
+        }
+
+        let saved = SavedMetadata::from_json(&metadata).unwrap();
+        assert_eq!(saved.analysis_version, ANALYSIS_VERSION);
+        assert_eq!(saved.config_hash, AnalysisConfig::default().hash());
+        assert!(saved.columns.iter().all(|column| {
+            column.analysis_version == ANALYSIS_VERSION && column.config_hash == saved.config_hash
+        }));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut original = CSV::from_string("id,price\n1,\"1,5\"\n2,\"2,5\"".to_string()).unwrap();
+        let config = AnalysisConfig {
+            percentiles: vec![50.0],
+            ..AnalysisConfig::default()
+        };
+        original.apply_config(config).unwrap();
+        original.infer_column_types();
+        let json = original.export_metadata().unwrap();
+
+        let mut next = CSV::from_string("price,notes\n\"3,5\",x".to_string()).unwrap();
+        let report = next.apply_saved_metadata(&json).unwrap();
+        assert_eq!(report.applied_columns, vec!["price"]);
+        assert_eq!(report.missing_columns, vec!["id"]);
+        assert_eq!(report.unmatched_columns, vec!["notes"]);
+
+        let price = next.column_metadata(0).unwrap();
+        assert_eq!(price.data_type, DataType::Decimal);
+        assert_eq!(price.number_format.unwrap().decimal_separator, ',');
+        assert_eq!(
+            price.numeric_stats.as_ref().unwrap().max,
+            2.5,
+            "stats are the saved ones, not recomputed"
+        );
+        assert!(next.column_metadata(1).is_none());
+        assert_eq!(next.analysis_config().percentiles, vec![50.0]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_metadata() {
+        let mut csv = CSV::from_string("a\n1".to_string()).unwrap();
+        assert!(csv.apply_saved_metadata("not json").is_err());
+
+        let mut saved = csv.saved_metadata();
+        saved.version = METADATA_VERSION + 1;
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(csv.apply_saved_metadata(&json).is_err());
+    }
+}
@@ -0,0 +1,346 @@
+use log::info;
+use std::mem::size_of;
+
+use crate::analysis::memory::{AnalysisMode, BoundedAnalysis};
+use crate::config::AnalysisConfig;
+use crate::csv::{for_each_record, parse_error, ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::logging::targets;
+use crate::metrics::peak_memory;
+use crate::parallel::{pack_column, unpack_column};
+use crate::table::dialect::Dialect;
+
+/// Where one staged chunk lies in a spill store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpillSegment {
+    pub offset: u64,
+    pub len: usize,
+}
+
+/// Append-only storage outside the wasm heap, such as a file in the Origin Private File
+/// System, holding the column chunks `SpilledAnalysis` stages
+pub trait SpillStore {
+    /// Writes `bytes` after everything written so far, returning where they went
+    fn append(&mut self, bytes: &[u8]) -> Result<SpillSegment, AnalysisError>;
+    /// Reads back a segment returned by `append`
+    fn read(&mut self, segment: SpillSegment) -> Result<Vec<u8>, AnalysisError>;
+}
+
+/// Spill store in memory, for tests and small inputs
+#[derive(Debug, Default)]
+pub struct MemorySpillStore {
+    bytes: Vec<u8>,
+}
+
+impl SpillStore for MemorySpillStore {
+    fn append(&mut self, bytes: &[u8]) -> Result<SpillSegment, AnalysisError> {
+        let segment = SpillSegment {
+            offset: self.bytes.len() as u64,
+            len: bytes.len(),
+        };
+        self.bytes.extend_from_slice(bytes);
+        Ok(segment)
+    }
+
+    fn read(&mut self, segment: SpillSegment) -> Result<Vec<u8>, AnalysisError> {
+        let start = segment.offset as usize;
+        self.bytes
+            .get(start..start + segment.len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| spill_error("Segment is outside the spill store"))
+    }
+}
+
+/// Spill store in a file, for native builds
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct FileSpillStore {
+    file: std::fs::File,
+    len: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSpillStore {
+    /// Stages chunks in `file`, which should be empty and open for reading and writing
+    pub fn new(file: std::fs::File) -> FileSpillStore {
+        FileSpillStore { file, len: 0 }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SpillStore for FileSpillStore {
+    fn append(&mut self, bytes: &[u8]) -> Result<SpillSegment, AnalysisError> {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let segment = SpillSegment {
+            offset: self.len,
+            len: bytes.len(),
+        };
+        self.file
+            .seek(SeekFrom::Start(self.len))
+            .and_then(|_| self.file.write_all(bytes))
+            .map_err(|e| spill_error(format!("Failed to write to the spill file: {}", e)))?;
+        self.len += bytes.len() as u64;
+        Ok(segment)
+    }
+
+    fn read(&mut self, segment: SpillSegment) -> Result<Vec<u8>, AnalysisError> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let mut bytes = vec![0; segment.len];
+        self.file
+            .seek(SeekFrom::Start(segment.offset))
+            .and_then(|_| self.file.read_exact(&mut bytes))
+            .map_err(|e| spill_error(format!("Failed to read the spill file: {}", e)))?;
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn spill_error(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::SpillError {
+        message: message.into(),
+    }
+}
+
+// End of the last complete row in `text`: just past a line break outside quotes. Doubled
+// quotes inside a quoted field toggle twice, so counting every quote is enough.
+pub(crate) fn complete_rows_end(text: &str) -> usize {
+    let mut in_quotes = false;
+    let mut end = 0;
+    for (i, byte) in text.bytes().enumerate() {
+        match byte {
+            b'"' => in_quotes = !in_quotes,
+            b'\n' if !in_quotes => end = i + 1,
+            _ => {}
+        }
+    }
+    end
+}
+
+/// Profiles CSV text too large to hold at once. Text pushed in chunks, e.g. from
+/// `File.stream()`, is parsed and each column's part of it is staged in the spill store;
+/// `finish` then reads the columns back one at a time to compute full-file statistics, so
+/// only one chunk of rows or one column is ever resident.
+pub struct SpilledAnalysis<S: SpillStore> {
+    store: S,
+    config: AnalysisConfig,
+    headers: Option<Vec<String>>,
+    // Detected from the first staged text and used to read every later chunk
+    dialect: Option<Dialect>,
+    segments: Vec<Vec<SpillSegment>>,
+    column_bytes: Vec<usize>,
+    rows: usize,
+    // Start of a row cut off at the end of the last chunk
+    pending: String,
+}
+
+impl<S: SpillStore> SpilledAnalysis<S> {
+    pub fn new(store: S, config: AnalysisConfig) -> Result<SpilledAnalysis<S>, AnalysisError> {
+        config.validate()?;
+        Ok(SpilledAnalysis {
+            store,
+            config,
+            headers: None,
+            dialect: None,
+            segments: Vec::new(),
+            column_bytes: Vec::new(),
+            rows: 0,
+            pending: String::new(),
+        })
+    }
+
+    /// Data rows staged so far
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Parses the complete rows of the text so far, headers first, and stages them; a row
+    /// cut off at the end waits for the next chunk
+    pub fn push_text(&mut self, chunk: &str) -> Result<(), AnalysisError> {
+        self.pending.push_str(chunk);
+        let end = complete_rows_end(&self.pending);
+        if end == 0 {
+            return Ok(());
+        }
+        let rest = self.pending.split_off(end);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        self.stage(&complete)
+    }
+
+    /// Stages whatever is left and analyzes every column, reading back its chunks
+    pub fn finish(mut self) -> Result<BoundedAnalysis, AnalysisError> {
+        let rest = std::mem::take(&mut self.pending);
+        if !rest.trim().is_empty() {
+            self.stage(&rest)?;
+        }
+        let headers = self
+            .headers
+            .take()
+            .ok_or_else(|| AnalysisError::invalid_argument("No CSV text was pushed"))?;
+
+        let mut columns = Vec::with_capacity(headers.len());
+        for (index, header) in headers.into_iter().enumerate() {
+            // The column's values plus their two working copies
+            let needed = 3 * self.column_bytes[index];
+            if let Some(limit) = self.config.memory.limit_bytes.filter(|&l| needed > l) {
+                return Err(AnalysisError::invalid_argument(format!(
+                    "Memory limit of {} bytes is too small for column '{}', which needs about {}",
+                    limit, header, needed
+                )));
+            }
+            let mut values = Vec::with_capacity(self.rows);
+            for &segment in &self.segments[index] {
+                values.extend(unpack_column(&self.store.read(segment)?)?);
+            }
+            let csv = CSV::from_parts(vec![(header, values, None)], self.config.clone());
+            if let Some(metadata) = csv.infer_column(0) {
+                columns.push(ColumnMetadata {
+                    analysis_mode: AnalysisMode::ColumnByColumn,
+                    ..metadata
+                });
+            }
+        }
+
+        info!(
+            target: targets::INFERENCE,
+            "Analyzed {} rows of {} columns staged in {} chunks",
+            self.rows,
+            columns.len(),
+            self.segments.first().map_or(0, Vec::len)
+        );
+        Ok(BoundedAnalysis {
+            mode: AnalysisMode::ColumnByColumn,
+            rows: self.rows,
+            analyzed_rows: self.rows,
+            estimated_bytes: peak_memory(&self.column_bytes),
+            seed: None,
+            columns,
+        })
+    }
+
+    // Parses complete rows and appends each column's values to the store
+    fn stage(&mut self, text: &str) -> Result<(), AnalysisError> {
+        let dialect = self.dialect.get_or_insert_with(|| Dialect::sniff(text));
+        let mut reader = dialect
+            .reader_builder()
+            .has_headers(self.headers.is_none() && dialect.has_headers)
+            .from_reader(text.as_bytes());
+        if self.headers.is_none() {
+            let headers =
+                dialect.header_names(reader.headers().map_err(|e| parse_error(e, true, 0))?);
+            self.column_bytes = headers.iter().map(String::len).collect();
+            self.segments = vec![Vec::new(); headers.len()];
+            self.headers = Some(headers);
+        }
+
+        let width = self.segments.len();
+        let mut columns = vec![Vec::new(); width];
+        let column_bytes = &mut self.column_bytes;
+        for_each_record(&mut reader, width, self.rows, |_, record| {
+            for (i, column) in columns.iter_mut().enumerate() {
+                let value = record.get(i).unwrap_or_default();
+                column_bytes[i] += size_of::<String>() + value.len();
+                column.push(value.to_string());
+            }
+        })?;
+        let Some(staged) = columns.first().map(Vec::len).filter(|&n| n > 0) else {
+            return Ok(());
+        };
+        for (index, values) in columns.iter().enumerate() {
+            let segment = self.store.append(&pack_column(values)?)?;
+            self.segments[index].push(segment);
+        }
+        self.rows += staged;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::memory::analyze_with_memory_limit;
+    use crate::config::SpillStrategy;
+    use crate::types::DataType;
+
+    fn data() -> String {
+        let mut data = String::from("id,name,joined\n");
+        for i in 0..200 {
+            data.push_str(&format!(
+                "{},\"Smith, {}\",2024-01-{:02}\n",
+                i,
+                i,
+                i % 28 + 1
+            ));
+        }
+        data
+    }
+
+    #[test]
+    fn test_chunks_match_column_by_column() {
+        let data = data();
+        let mut spilled =
+            SpilledAnalysis::new(MemorySpillStore::default(), Default::default()).unwrap();
+        // Chunks end mid-row and inside quoted fields
+        for chunk in data.as_bytes().chunks(37) {
+            spilled
+                .push_text(std::str::from_utf8(chunk).unwrap())
+                .unwrap();
+        }
+        assert!(spilled.rows() > 150);
+        let analysis = spilled.finish().unwrap();
+
+        let mut config = AnalysisConfig::default();
+        config.memory.limit_bytes = Some(25_000);
+        config.memory.spill_strategy = SpillStrategy::ColumnByColumn;
+        let expected = analyze_with_memory_limit(&data, &config).unwrap();
+        assert_eq!(expected.mode, AnalysisMode::ColumnByColumn);
+
+        assert_eq!(analysis.rows, 200);
+        assert_eq!(analysis.mode, AnalysisMode::ColumnByColumn);
+        assert_eq!(analysis.estimated_bytes, expected.estimated_bytes);
+        for (column, expected) in analysis.columns.iter().zip(&expected.columns) {
+            assert_eq!(column.name, expected.name);
+            assert_eq!(column.data_type, expected.data_type);
+            assert_eq!(column.analysis_mode, AnalysisMode::ColumnByColumn);
+        }
+        assert_eq!(analysis.columns[2].data_type, DataType::Date);
+        let stats = analysis.columns[0].numeric_stats.as_ref().unwrap();
+        assert_eq!(stats.max, 199.0);
+    }
+
+    #[test]
+    fn test_file_store_and_errors() {
+        let path = std::env::temp_dir().join(format!("spill-test-{}", std::process::id()));
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        let mut spilled =
+            SpilledAnalysis::new(FileSpillStore::new(file), Default::default()).unwrap();
+        spilled.push_text("a,b\n1,x\n2,").unwrap();
+        spilled.push_text("y\n3,z").unwrap();
+        let analysis = spilled.finish().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(analysis.rows, 3);
+        assert_eq!(analysis.columns[0].data_type, DataType::Integer);
+
+        let mut spilled =
+            SpilledAnalysis::new(MemorySpillStore::default(), Default::default()).unwrap();
+        spilled.push_text("a;b\n1;x\n2;").unwrap();
+        spilled.push_text("y\n3;z").unwrap();
+        let analysis = spilled.finish().unwrap();
+        assert_eq!(analysis.columns.len(), 2);
+        assert_eq!(analysis.columns[0].data_type, DataType::Integer);
+
+        let spilled = SpilledAnalysis::new(MemorySpillStore::default(), Default::default());
+        assert!(spilled.unwrap().finish().is_err());
+        let mut store = MemorySpillStore::default();
+        assert!(matches!(
+            store.read(SpillSegment { offset: 4, len: 1 }),
+            Err(AnalysisError::SpillError { .. })
+        ));
+    }
+}
@@ -0,0 +1,742 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::analysis::streaming::{OnlineStats, StreamingNumericStats};
+use crate::analysis::tdigest::DEFAULT_COMPRESSION;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::credit_card::{CardBrand, CreditCardType};
+use crate::types::number_format::NumberFormat;
+use crate::types::{DataType, TypeDetection};
+
+// Columns with more values than this get streaming quartile estimates instead of exact ones
+pub const EXACT_QUANTILE_LIMIT: usize = 100_000;
+
+/// Summary statistics for numeric, decimal and currency columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NumericStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub quartiles: [f64; 3],
+    // Requested percentiles, in the order they were configured
+    pub percentiles: Vec<Percentile>,
+    pub skewness: f64,
+    // Excess kurtosis, zero for a normal distribution
+    pub kurtosis: f64,
+    // Most frequent value, None when no value repeats or the column was streamed
+    pub mode: Option<f64>,
+    pub zero_count: usize,
+    pub negative_count: usize,
+    // Digits and fraction digits a DECIMAL needs for every value, counted on the values as
+    // written so "0.1" has a scale of 1 whatever its float is; zero for converted quantities
+    #[serde(default)]
+    pub precision: usize,
+    #[serde(default)]
+    pub scale: usize,
+    // NaN and infinities, written out or parsed, left out of every other statistic
+    #[serde(default)]
+    pub nan_count: usize,
+    #[serde(default)]
+    pub infinite_count: usize,
+    // Configured sentinels like -999 found beyond every other value, left out as missing
+    #[serde(default)]
+    pub sentinels: Vec<SentinelCount>,
+}
+
+/// A sentinel value standing for missing data, and how many values hold it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct SentinelCount {
+    pub value: f64,
+    pub count: usize,
+}
+
+/// NaN or an infinity written out, like `NaN`, `inf` or `-Infinity`
+pub fn special_float(value: &str) -> Option<f64> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "nan" | "+nan" | "-nan" => Some(f64::NAN),
+        "inf" | "+inf" | "infinity" | "+infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+/// IEEE specials and configured sentinels set aside while reading a column's numbers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecialValues {
+    nan_count: usize,
+    infinite_count: usize,
+    // One entry per configured sentinel, counted whether or not it turns out to mean missing
+    sentinels: Vec<SentinelCount>,
+}
+
+impl SpecialValues {
+    pub fn new(sentinels: &[f64]) -> Self {
+        Self {
+            sentinels: sentinels
+                .iter()
+                .map(|&value| SentinelCount { value, count: 0 })
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Counts the number if it is NaN, infinite or a sentinel, which keeps it out of the
+    /// statistics for now
+    pub fn set_aside(&mut self, number: f64) -> bool {
+        if number.is_nan() {
+            self.nan_count += 1;
+        } else if number.is_infinite() {
+            self.infinite_count += 1;
+        } else if let Some(sentinel) = self.sentinels.iter_mut().find(|s| s.value == number) {
+            sentinel.count += 1;
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Splits the sentinels found into those beyond every other value, `range` of the
+    /// numbers kept, which stand for missing data, and those within it, which are data
+    /// after all. Without other values nothing marks them out, so they are all data.
+    pub fn resolve(&self, range: Option<(f64, f64)>) -> (Vec<SentinelCount>, Vec<SentinelCount>) {
+        self.sentinels
+            .iter()
+            .filter(|sentinel| sentinel.count > 0)
+            .partition(|sentinel| {
+                range.is_some_and(|(min, max)| sentinel.value < min || sentinel.value > max)
+            })
+    }
+
+    pub fn nan_count(&self) -> usize {
+        self.nan_count
+    }
+
+    pub fn infinite_count(&self) -> usize {
+        self.infinite_count
+    }
+}
+
+impl NumericStats {
+    /// DECIMAL type holding every value exactly, or None past the 38 digits dialects take
+    pub fn decimal_type(&self) -> Option<String> {
+        (self.precision > 0 && self.precision <= MAX_DECIMAL_PRECISION)
+            .then(|| format!("DECIMAL({},{})", self.precision, self.scale))
+    }
+}
+
+// Largest precision every dialect's DECIMAL takes
+pub const MAX_DECIMAL_PRECISION: usize = 38;
+
+/// Integer and fraction digits of a number written plainly, like `-1250.50`, without its
+/// sign, leading zeros or trailing fraction zeros
+pub fn digit_counts(plain: &str) -> (usize, usize) {
+    let unsigned = plain.trim_start_matches('-');
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    (
+        integer.trim_start_matches('0').len(),
+        fraction.trim_end_matches('0').len(),
+    )
+}
+
+/// Widest integer part and fraction seen over a column's values, for its DECIMAL type
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DecimalShape {
+    pub integer_digits: usize,
+    pub scale: usize,
+}
+
+impl DecimalShape {
+    /// Widens the shape to a number written plainly, as `NumberFormat::plain_digits` gives it
+    pub fn push(&mut self, plain: &str) {
+        let (integer_digits, scale) = digit_counts(plain);
+        self.integer_digits = self.integer_digits.max(integer_digits);
+        self.scale = self.scale.max(scale);
+    }
+
+    pub fn precision(&self) -> usize {
+        // A column of zeros still needs one digit
+        (self.integer_digits + self.scale).max(1)
+    }
+}
+
+/// A single percentile (0-100) and its value
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Percentile {
+    pub p: f64,
+    pub value: f64,
+}
+
+/// Length and frequency statistics for text-like columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TextStats {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub avg_length: f64,
+    pub most_common: Vec<(String, usize)>,
+    pub distinct_count: usize,
+    // Distinct values over non-empty values; 1.0 for identifier-like columns
+    pub uniqueness_ratio: f64,
+    // Shannon entropy of the value distribution in bits; 0.0 for constant columns
+    pub entropy: f64,
+    // Prefix shared by every non-empty value, such as "INV-" in invoice numbers
+    pub common_prefix: String,
+}
+
+/// Brand breakdown for credit card columns; the numbers themselves are never included
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CardStats {
+    pub brands: Vec<(CardBrand, usize)>,
+    /// Valid numbers whose prefix matches no known brand
+    pub unknown_brand_count: usize,
+    /// Non-empty values that fail the length or Luhn check
+    pub invalid_count: usize,
+}
+
+/// Shannon entropy in bits of a frequency distribution
+pub fn shannon_entropy(counts: impl IntoIterator<Item = usize>, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    // Summed in sorted order so counts coming from a HashMap give the same bits every run
+    let mut counts: Vec<usize> = counts.into_iter().filter(|&count| count > 0).collect();
+    counts.sort_unstable();
+    let entropy: f64 = counts
+        .into_iter()
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+    // Avoid reporting -0.0 for constant columns
+    entropy.max(0.0)
+}
+
+/// Longest prefix shared by all values, respecting character boundaries
+pub fn longest_common_prefix<'a>(values: &[&'a str]) -> &'a str {
+    let Some((&first, rest)) = values.split_first() else {
+        return "";
+    };
+    let mut end = first.len();
+    for value in rest {
+        end = first[..end]
+            .char_indices()
+            .zip(value.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(end.min(value.len()), |((i, _), _)| i);
+    }
+    &first[..end]
+}
+
+/// Linearly interpolated percentile (0-100) of an already sorted, non-empty slice
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+// Longest run of equal values in a sorted slice; ties go to the smallest value
+fn sorted_mode(sorted: &[f64]) -> Option<f64> {
+    let mut best: Option<(f64, usize)> = None;
+    for run in sorted.chunk_by(|a, b| a == b) {
+        if run.len() > 1 && best.is_none_or(|(_, count)| run.len() > count) {
+            best = Some((run[0], run.len()));
+        }
+    }
+    best.map(|(value, _)| value)
+}
+
+/// What numeric statistics report and how, from the analysis config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericOptions<'a> {
+    /// Percentiles (0-100) reported in addition to the quartiles
+    pub percentiles: &'a [f64],
+    /// Values standing for missing data when they lie beyond every other value
+    pub sentinels: &'a [f64],
+    /// t-digest compression for columns too large for exact quantiles
+    pub compression: f64,
+}
+
+impl Default for NumericOptions<'_> {
+    fn default() -> Self {
+        Self {
+            percentiles: &[],
+            sentinels: &[],
+            compression: DEFAULT_COMPRESSION,
+        }
+    }
+}
+
+/// Numeric statistics of a column, parsing its values with the column's number format.
+/// NaN, infinities and sentinels standing for missing data are counted but left out.
+pub fn calculate_numeric_stats(
+    values: &[String],
+    format: &NumberFormat,
+    options: &NumericOptions,
+) -> Option<NumericStats> {
+    let NumericOptions {
+        percentiles,
+        sentinels,
+        compression,
+    } = *options;
+    // Large columns are summarized in one pass without copying them into a sorted Vec
+    if values.len() > EXACT_QUANTILE_LIMIT {
+        let mut streaming = StreamingNumericStats::with_percentiles(percentiles)
+            .with_compression(compression)
+            .with_format(*format)
+            .with_sentinels(sentinels);
+        streaming.extend(values.iter().map(|v| v.as_str()));
+        return streaming.finish();
+    }
+
+    let mut specials = SpecialValues::new(sentinels);
+    let mut shape = DecimalShape::default();
+    let mut sorted: Vec<f64> = values
+        .iter()
+        .filter_map(|v| {
+            let Some(plain) = format.plain_digits(v) else {
+                if let Some(special) = special_float(v) {
+                    specials.set_aside(special);
+                }
+                return None;
+            };
+            let number = plain.parse().ok()?;
+            shape.push(&plain);
+            (!specials.set_aside(number)).then_some(number)
+        })
+        .collect();
+
+    let range = sorted.iter().fold(None, |range: Option<(f64, f64)>, &n| {
+        Some(range.map_or((n, n), |(min, max)| (min.min(n), max.max(n))))
+    });
+    let (missing, in_range) = specials.resolve(range);
+    for sentinel in in_range {
+        sorted.extend(std::iter::repeat_n(sentinel.value, sentinel.count));
+    }
+    if sorted.is_empty() {
+        return None;
+    }
+
+    // Every value is at hand, so the moments are taken in two passes
+    let stats = OnlineStats::from_values(&sorted);
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    // Calculate median and quartiles
+    let len = sorted.len();
+    let median = sorted[len / 2];
+    let quartiles = [
+        sorted[len / 4],     // Q1
+        median,              // Q2
+        sorted[3 * len / 4], // Q3
+    ];
+    let percentiles = percentiles
+        .iter()
+        .map(|&p| Percentile {
+            p,
+            value: percentile(&sorted, p),
+        })
+        .collect();
+
+    Some(NumericStats {
+        min: stats.min(),
+        max: stats.max(),
+        mean: stats.mean(),
+        median,
+        std_dev: stats.std_dev(),
+        quartiles,
+        percentiles,
+        skewness: stats.skewness(),
+        kurtosis: stats.kurtosis(),
+        mode: sorted_mode(&sorted),
+        zero_count: sorted.iter().filter(|&&v| v == 0.0).count(),
+        negative_count: sorted.iter().filter(|&&v| v < 0.0).count(),
+        precision: shape.precision(),
+        scale: shape.scale,
+        nan_count: specials.nan_count(),
+        infinite_count: specials.infinite_count(),
+        sentinels: missing,
+    })
+}
+
+pub fn calculate_text_stats(values: &[String]) -> Option<TextStats> {
+    let non_empty_values: Vec<&str> = values
+        .iter()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect();
+
+    if non_empty_values.is_empty() {
+        return None;
+    }
+
+    // Calculate length statistics
+    let lengths: Vec<usize> = non_empty_values.iter().map(|s| s.len()).collect();
+    let min_length = *lengths.iter().min().unwrap();
+    let max_length = *lengths.iter().max().unwrap();
+    let avg_length = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+
+    // Find most common values and their frequencies
+    let mut value_counts: HashMap<&str, usize> = HashMap::new();
+    for &value in &non_empty_values {
+        *value_counts.entry(value).or_insert(0) += 1;
+    }
+
+    let distinct_count = value_counts.len();
+    let uniqueness_ratio = distinct_count as f64 / non_empty_values.len() as f64;
+    let entropy = shannon_entropy(value_counts.values().copied(), non_empty_values.len());
+    let common_prefix = longest_common_prefix(&non_empty_values).to_string();
+
+    // Sort by frequency and take top 5
+    let mut most_common: Vec<(String, usize)> = value_counts
+        .iter()
+        .map(|(&k, &v)| (k.to_string(), v))
+        .collect();
+    most_common.sort_by(|a, b| {
+        b.1.cmp(&a.1) // Sort by count descending
+            .then(a.0.cmp(&b.0)) // Then by value ascending for stability
+    });
+    most_common.truncate(5);
+
+    Some(TextStats {
+        min_length,
+        max_length,
+        avg_length,
+        most_common,
+        distinct_count,
+        uniqueness_ratio,
+        entropy,
+        common_prefix,
+    })
+}
+
+pub fn calculate_card_stats(values: &[String]) -> Option<CardStats> {
+    let mut brands: Vec<(CardBrand, usize)> = Vec::new();
+    let mut unknown_brand_count = 0;
+    let mut invalid_count = 0;
+    let mut non_empty = 0;
+
+    for value in values.iter().filter(|v| !v.trim().is_empty()) {
+        non_empty += 1;
+        if !CreditCardType::is_definite_match(value) {
+            invalid_count += 1;
+            continue;
+        }
+        match CreditCardType::brand(value) {
+            Some(brand) => match brands.iter_mut().find(|(b, _)| *b == brand) {
+                Some((_, count)) => *count += 1,
+                None => brands.push((brand, 1)),
+            },
+            None => unknown_brand_count += 1,
+        }
+    }
+
+    if non_empty == 0 {
+        return None;
+    }
+    // Most common brand first; the sort is stable so ties keep first-seen order
+    brands.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    Some(CardStats {
+        brands,
+        unknown_brand_count,
+        invalid_count,
+    })
+}
+
+impl CSV {
+    /// Any percentiles (0-100) of a numeric column: exact up to `EXACT_QUANTILE_LIMIT`
+    /// values, t-digest estimates at the configured compression beyond
+    pub fn column_percentiles(
+        &self,
+        index: usize,
+        percentiles: &[f64],
+    ) -> Result<Vec<Percentile>, AnalysisError> {
+        if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Percentile {} must be between 0 and 100",
+                p
+            )));
+        }
+        let (_, values) = self
+            .get_column(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        let metadata = match self.column_metadata(index) {
+            Some(metadata) => metadata.clone(),
+            None => self
+                .infer_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+        };
+        if !matches!(
+            metadata.data_type,
+            DataType::Integer | DataType::Decimal | DataType::Currency
+        ) || metadata.big_integer.is_some()
+        {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Column '{}' is {}, not a number column",
+                metadata.name, metadata.data_type
+            )));
+        }
+
+        let options = NumericOptions {
+            percentiles,
+            ..self.analysis_config().numeric_options()
+        };
+        let format = metadata.number_format.unwrap_or_default();
+        Ok(calculate_numeric_stats(values, &format, &options)
+            .map(|stats| stats.percentiles)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_numeric_stats() {
+        let values = column(&["1", "2", "3", "4", "$1,000", "", "abc"]);
+        let stats = calculate_numeric_stats(
+            &values,
+            &NumberFormat::default(),
+            &NumericOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 1000.0);
+        assert_eq!(stats.mean, 202.0);
+        assert_eq!(stats.median, 3.0);
+        assert_eq!(stats.quartiles, [2.0, 3.0, 4.0]);
+
+        assert_eq!(stats.mode, None);
+
+        let single = calculate_numeric_stats(
+            &column(&["5"]),
+            &NumberFormat::default(),
+            &NumericOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(single.std_dev, 0.0);
+        assert!(calculate_numeric_stats(
+            &column(&["", "x"]),
+            &NumberFormat::default(),
+            &NumericOptions::default()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_extended_numeric_stats() {
+        let values = column(&["-3", "0", "0", "2", "2", "5", "10", "-1"]);
+        let options = NumericOptions {
+            percentiles: &[0.0, 50.0, 100.0],
+            ..NumericOptions::default()
+        };
+        let stats = calculate_numeric_stats(&values, &NumberFormat::default(), &options).unwrap();
+
+        let percentiles: Vec<(f64, f64)> =
+            stats.percentiles.iter().map(|p| (p.p, p.value)).collect();
+        assert_eq!(percentiles, vec![(0.0, -3.0), (50.0, 1.0), (100.0, 10.0)]);
+        assert_eq!(stats.mode, Some(0.0), "ties should pick the smallest value");
+        assert_eq!(stats.zero_count, 2);
+        assert_eq!(stats.negative_count, 2);
+        assert!(stats.skewness > 0.0, "long right tail should skew positive");
+    }
+
+    #[test]
+    fn test_numeric_stats_with_format() {
+        let values = column(&["1.234,5", "(10,5)", "2"]);
+        let format = NumberFormat {
+            thousands_separator: Some('.'),
+            decimal_separator: ',',
+            parentheses_negative: true,
+        };
+        let stats = calculate_numeric_stats(&values, &format, &NumericOptions::default()).unwrap();
+
+        assert_eq!(stats.min, -10.5);
+        assert_eq!(stats.max, 1234.5);
+        assert_eq!(stats.negative_count, 1);
+        assert_eq!((stats.precision, stats.scale), (5, 1));
+    }
+
+    #[test]
+    fn test_specials_and_sentinels_left_out() {
+        let values = column(&[
+            "12",
+            "NaN",
+            "-999",
+            "15",
+            "inf",
+            "-Infinity",
+            "-999",
+            "18",
+            "9999",
+        ]);
+        let options = NumericOptions {
+            sentinels: &[-999.0, 9999.0, 15.0],
+            ..NumericOptions::default()
+        };
+        let stats = calculate_numeric_stats(&values, &NumberFormat::default(), &options).unwrap();
+
+        assert_eq!((stats.min, stats.max), (12.0, 18.0));
+        assert_eq!(stats.mean, 15.0);
+        assert_eq!((stats.nan_count, stats.infinite_count), (1, 2));
+        // 15 lies within the column's range, so it is data
+        let missing: Vec<(f64, usize)> =
+            stats.sentinels.iter().map(|s| (s.value, s.count)).collect();
+        assert_eq!(missing, vec![(-999.0, 2), (9999.0, 1)]);
+
+        let mut streaming = StreamingNumericStats::default().with_sentinels(options.sentinels);
+        streaming.extend(values.iter().map(|v| v.as_str()));
+        let streamed = streaming.finish().unwrap();
+        assert_eq!((streamed.min, streamed.max), (12.0, 18.0));
+        assert_eq!(streamed.mean, 15.0);
+        assert_eq!(streamed.sentinels, stats.sentinels);
+        assert_eq!(streamed.infinite_count, 2);
+
+        // Nothing marks a lone sentinel out
+        let only = calculate_numeric_stats(&column(&["-999"]), &NumberFormat::default(), &options)
+            .unwrap();
+        assert_eq!(only.min, -999.0);
+        assert!(only.sentinels.is_empty());
+    }
+
+    #[test]
+    fn test_decimal_precision_from_raw_values() {
+        // 0.1 + 0.2 style float artifacts must not widen the scale
+        let values = column(&["0.1", "0.30", "-1234.56", "007"]);
+        let stats = calculate_numeric_stats(
+            &values,
+            &NumberFormat::default(),
+            &NumericOptions::default(),
+        )
+        .unwrap();
+        assert_eq!((stats.precision, stats.scale), (6, 2));
+        assert_eq!(stats.decimal_type().as_deref(), Some("DECIMAL(6,2)"));
+
+        let mut streaming = StreamingNumericStats::default();
+        streaming.extend(["0.1", "0.30", "-1234.56", "007"]);
+        assert_eq!(streaming.finish().unwrap().scale, 2);
+
+        let wide = column(&["1234567890123456789012345678901234567890.5"]);
+        let stats =
+            calculate_numeric_stats(&wide, &NumberFormat::default(), &NumericOptions::default())
+                .unwrap();
+        assert_eq!(stats.decimal_type(), None);
+    }
+
+    #[test]
+    fn test_percentile_interpolation() {
+        let sorted = [10.0, 20.0, 30.0, 40.0];
+        let test_cases = vec![(0.0, 10.0), (25.0, 17.5), (50.0, 25.0), (100.0, 40.0)];
+        for (p, expected) in test_cases {
+            assert_eq!(percentile(&sorted, p), expected, "p{}", p);
+        }
+        assert_eq!(percentile(&[7.0], 99.0), 7.0);
+    }
+
+    #[test]
+    fn test_large_columns_use_streaming() {
+        let values: Vec<String> = (0..EXACT_QUANTILE_LIMIT + 1)
+            .map(|x| x.to_string())
+            .collect();
+        let options = NumericOptions {
+            percentiles: &[99.0],
+            ..NumericOptions::default()
+        };
+        let stats = calculate_numeric_stats(&values, &NumberFormat::default(), &options).unwrap();
+
+        assert_eq!(stats.min, 0.0);
+        assert_eq!(stats.max, EXACT_QUANTILE_LIMIT as f64);
+        assert!((stats.mean - EXACT_QUANTILE_LIMIT as f64 / 2.0).abs() < 1e-6);
+        assert!((stats.median - stats.mean).abs() < EXACT_QUANTILE_LIMIT as f64 * 0.01);
+        assert!((stats.percentiles[0].value - 99_000.0).abs() < EXACT_QUANTILE_LIMIT as f64 * 0.01);
+    }
+
+    #[test]
+    fn test_column_percentiles() {
+        let data = "n,name\n4,a\n1,b\n3,c\n2,d\n5,e";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+        let percentiles = csv.column_percentiles(0, &[50.0, 12.5]).unwrap();
+        let values: Vec<(f64, f64)> = percentiles.iter().map(|p| (p.p, p.value)).collect();
+        assert_eq!(values, vec![(50.0, 3.0), (12.5, 1.5)]);
+        assert!(csv.column_percentiles(0, &[101.0]).is_err());
+        assert!(csv.column_percentiles(1, &[50.0]).is_err());
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let values = column(&["red", "blue", "red", " ", "green"]);
+        let stats = calculate_text_stats(&values).unwrap();
+
+        assert_eq!(stats.min_length, 3);
+        assert_eq!(stats.max_length, 5);
+        assert_eq!(stats.avg_length, 3.75);
+        assert_eq!(stats.most_common[0], ("red".to_string(), 2));
+        assert_eq!(stats.most_common[1], ("blue".to_string(), 1));
+        assert_eq!(stats.distinct_count, 3);
+        assert_eq!(stats.uniqueness_ratio, 0.75);
+        assert_eq!(stats.entropy, 1.5);
+        assert_eq!(stats.common_prefix, "");
+        assert!(calculate_text_stats(&column(&["", " "])).is_none());
+    }
+
+    #[test]
+    fn test_card_stats() {
+        let values = column(&[
+            "4111111111111111",
+            "4012888888881881",
+            "5500000000000004",
+            "4111111111111112",
+            "",
+        ]);
+        let stats = calculate_card_stats(&values).unwrap();
+
+        assert_eq!(
+            stats.brands,
+            vec![(CardBrand::Visa, 2), (CardBrand::Mastercard, 1)]
+        );
+        assert_eq!(stats.unknown_brand_count, 0);
+        assert_eq!(stats.invalid_count, 1);
+        assert!(calculate_card_stats(&column(&[""])).is_none());
+    }
+
+    #[test]
+    fn test_text_stats_identifiers_and_constants() {
+        let ids = calculate_text_stats(&column(&["INV-001", "INV-002", "INV-013"])).unwrap();
+        assert_eq!(ids.uniqueness_ratio, 1.0);
+        assert_eq!(ids.common_prefix, "INV-0");
+        assert!((ids.entropy - 3f64.log2()).abs() < 1e-12);
+
+        let constant = calculate_text_stats(&column(&["yes", "yes", "yes"])).unwrap();
+        assert_eq!(constant.distinct_count, 1);
+        assert_eq!(constant.entropy, 0.0);
+        assert_eq!(constant.common_prefix, "yes");
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let test_cases = vec![
+            (vec!["abc", "abd", "ab"], "ab"),
+            (vec!["abc"], "abc"),
+            (vec!["abc", "xyz"], ""),
+            (vec!["café", "cafè"], "caf"),
+            (vec!["ab", "abc"], "ab"),
+            (vec![], ""),
+        ];
+        for (values, expected) in test_cases {
+            assert_eq!(
+                longest_common_prefix(&values),
+                expected,
+                "prefix of {:?}",
+                values
+            );
+        }
+    }
+}
@@ -0,0 +1,641 @@
+use crate::analysis::statistics::{
+    special_float, DecimalShape, NumericStats, Percentile, SpecialValues,
+};
+use crate::analysis::tdigest::TDigest;
+use crate::types::number_format::NumberFormat;
+
+/// Floating-point sum carrying the low-order bits each addition loses (Neumaier's variant of
+/// Kahan summation), so values of mixed magnitudes like `1e16 + 1 - 1e16` sum exactly
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NeumaierSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl NeumaierSum {
+    pub fn add(&mut self, value: f64) {
+        let total = self.sum + value;
+        // Whichever operand is smaller in magnitude lost its low bits to the rounding
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - total) + value
+        } else {
+            (value - total) + self.sum
+        };
+        self.sum = total;
+    }
+
+    pub fn merge(&mut self, other: &NeumaierSum) {
+        self.add(other.sum);
+        self.add(other.compensation);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Compensated sum of the values, see `NeumaierSum`
+pub fn compensated_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sum = NeumaierSum::default();
+    values.into_iter().for_each(|value| sum.add(value));
+    sum.value()
+}
+
+/// Running count, mean and central moments using Welford's algorithm (extended to the third
+/// and fourth moments), plus min/max. Accumulators from separate chunks can be combined with
+/// `merge`. The mean is reported from a compensated sum of the values, which stays exact
+/// where the running mean drifts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnlineStats {
+    count: usize,
+    sum: NeumaierSum,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for OnlineStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: NeumaierSum::default(),
+            mean: 0.0,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl OnlineStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moments of values held in memory, taken in two passes: the compensated mean first,
+    /// then compensated sums of the deviations from it, which loses less than updating them
+    /// value by value
+    pub fn from_values(values: &[f64]) -> Self {
+        let mut stats = Self::default();
+        let mut sum = NeumaierSum::default();
+        for &value in values {
+            sum.add(value);
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+        }
+        if values.is_empty() {
+            return stats;
+        }
+        stats.count = values.len();
+        stats.sum = sum;
+        stats.mean = sum.value() / values.len() as f64;
+
+        let mut m2 = NeumaierSum::default();
+        let mut m3 = NeumaierSum::default();
+        let mut m4 = NeumaierSum::default();
+        for &value in values {
+            let deviation = value - stats.mean;
+            let squared = deviation * deviation;
+            m2.add(squared);
+            m3.add(squared * deviation);
+            m4.add(squared * squared);
+        }
+        stats.m2 = m2.value();
+        stats.m3 = m3.value();
+        stats.m4 = m4.value();
+        stats
+    }
+
+    pub fn push(&mut self, value: f64) {
+        let previous = self.count as f64;
+        self.count += 1;
+        self.sum.add(value);
+        let n = self.count as f64;
+
+        let delta = value - self.mean;
+        let delta_n = delta / n;
+        let term = delta * delta_n * previous;
+
+        self.mean += delta_n;
+        self.m4 += term * delta_n * delta_n * (n * n - 3.0 * n + 3.0)
+            + 6.0 * delta_n * delta_n * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Combines two accumulators as if every value had been pushed into one (Chan et al.)
+    pub fn merge(&mut self, other: &OnlineStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+        let m3 = self.m3
+            + other.m3
+            + delta * delta2 * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+        let m4 = self.m4
+            + other.m4
+            + delta2 * delta2 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+            + 6.0 * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + 4.0 * delta * (na * other.m3 - nb * self.m3) / n;
+
+        self.mean += delta * nb / n;
+        self.sum.merge(&other.sum);
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum.value() / self.count as f64
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Sample variance, zero for fewer than two values
+    pub fn variance(&self) -> f64 {
+        if self.count > 1 {
+            self.m2 / (self.count - 1) as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Sample skewness (g1), zero when the values have no spread
+    pub fn skewness(&self) -> f64 {
+        if self.count < 2 || self.m2 == 0.0 {
+            return 0.0;
+        }
+        (self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5)
+    }
+
+    /// Excess kurtosis (g2), so a normal distribution scores close to zero
+    pub fn kurtosis(&self) -> f64 {
+        if self.count < 2 || self.m2 == 0.0 {
+            return 0.0;
+        }
+        self.count as f64 * self.m4 / (self.m2 * self.m2) - 3.0
+    }
+}
+
+/// Estimates a single quantile in constant memory with the P² algorithm (Jain & Chlamtac, 1985)
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    p: f64,
+    // Marker heights, actual positions and desired positions
+    heights: Vec<f64>,
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    pub fn new(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        Self {
+            p,
+            heights: Vec::with_capacity(5),
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        // The first five observations seed the markers
+        if self.heights.len() < 5 {
+            self.heights.push(value);
+            if self.heights.len() == 5 {
+                self.heights.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+
+        // Find the cell containing the value, extending the extremes if needed
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (1..5).find(|&i| value < self.heights[i]).unwrap_or(4) - 1
+        };
+
+        for position in self.positions.iter_mut().skip(cell + 1) {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        // Adjust the three middle markers towards their desired positions
+        for i in 1..4 {
+            let offset = self.desired[i] - self.positions[i];
+            let forward = self.positions[i + 1] - self.positions[i];
+            let backward = self.positions[i - 1] - self.positions[i];
+            if (offset >= 1.0 && forward > 1.0) || (offset <= -1.0 && backward < -1.0) {
+                let step = offset.signum();
+                let candidate = self.parabolic(i, step);
+                self.heights[i] =
+                    if self.heights[i - 1] < candidate && candidate < self.heights[i + 1] {
+                        candidate
+                    } else {
+                        self.linear(i, step)
+                    };
+                self.positions[i] += step;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, step: f64) -> f64 {
+        let (n, q) = (&self.positions, &self.heights);
+        q[i] + step / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + step) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - step) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, step: f64) -> f64 {
+        let j = if step > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i]
+            + step * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// Current estimate, exact while fewer than five values have been seen
+    pub fn estimate(&self) -> Option<f64> {
+        if self.heights.is_empty() {
+            return None;
+        }
+        if self.heights.len() < 5 {
+            let mut sorted = self.heights.clone();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let index = (self.p * (sorted.len() - 1) as f64).round() as usize;
+            return Some(sorted[index]);
+        }
+        Some(self.heights[2])
+    }
+}
+
+/// One-pass numeric statistics for chunked ingestion, without keeping the column in memory.
+/// Quartiles and percentiles are t-digest estimates rather than exact values, and no mode is
+/// reported.
+#[derive(Debug, Clone)]
+pub struct StreamingNumericStats {
+    stats: OnlineStats,
+    digest: TDigest,
+    percentiles: Vec<f64>,
+    zero_count: usize,
+    negative_count: usize,
+    format: NumberFormat,
+    // Only raw values added with `push_value` widen it
+    shape: Option<DecimalShape>,
+    specials: SpecialValues,
+}
+
+impl Default for StreamingNumericStats {
+    fn default() -> Self {
+        Self::with_percentiles(&[])
+    }
+}
+
+impl StreamingNumericStats {
+    /// Tracks the given percentiles (0-100) alongside the quartiles
+    pub fn with_percentiles(percentiles: &[f64]) -> Self {
+        Self {
+            stats: OnlineStats::new(),
+            digest: TDigest::default(),
+            percentiles: percentiles.to_vec(),
+            zero_count: 0,
+            negative_count: 0,
+            format: NumberFormat::default(),
+            shape: None,
+            specials: SpecialValues::default(),
+        }
+    }
+
+    /// Trades the digest's memory for quantile accuracy, see `TDigest::new`
+    pub fn with_compression(mut self, compression: f64) -> Self {
+        self.digest = TDigest::new(compression);
+        self
+    }
+
+    /// Parses raw values with the given convention instead of `1,234.56`
+    pub fn with_format(mut self, format: NumberFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets aside these values, like -999, when they turn out to lie beyond every other
+    /// value, as they do when they stand for missing data
+    pub fn with_sentinels(mut self, sentinels: &[f64]) -> Self {
+        self.specials = SpecialValues::new(sentinels);
+        self
+    }
+
+    /// Adds a raw cell value, ignoring anything that doesn't parse as a number
+    pub fn push_value(&mut self, value: &str) {
+        let Some(plain) = self.format.plain_digits(value) else {
+            if let Some(special) = special_float(value) {
+                self.specials.set_aside(special);
+            }
+            return;
+        };
+        if let Ok(number) = plain.parse() {
+            self.shape
+                .get_or_insert_with(DecimalShape::default)
+                .push(&plain);
+            self.push(number);
+        }
+    }
+
+    /// Adds a number; NaN, infinities and sentinels are only counted
+    pub fn push(&mut self, number: f64) {
+        if !self.specials.set_aside(number) {
+            self.add(number);
+        }
+    }
+
+    fn add(&mut self, number: f64) {
+        self.stats.push(number);
+        self.digest.push(number);
+        if number == 0.0 {
+            self.zero_count += 1;
+        } else if number < 0.0 {
+            self.negative_count += 1;
+        }
+    }
+
+    /// Adds a chunk of raw cell values
+    pub fn extend<'a>(&mut self, values: impl IntoIterator<Item = &'a str>) {
+        for value in values {
+            self.push_value(value);
+        }
+    }
+
+    /// Estimated value at any percentile (0-100) of the numbers added so far
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        self.digest.quantile(p / 100.0)
+    }
+
+    pub fn finish(&self) -> Option<NumericStats> {
+        let range = (self.stats.count() > 0).then(|| (self.stats.min(), self.stats.max()));
+        let (missing, in_range) = self.specials.resolve(range);
+        // Sentinels among the other values are data after all
+        if !in_range.is_empty() {
+            let mut with_data = self.clone();
+            with_data.specials = SpecialValues::default();
+            for sentinel in in_range {
+                (0..sentinel.count).for_each(|_| with_data.add(sentinel.value));
+            }
+            return with_data.finish().map(|stats| NumericStats {
+                nan_count: self.specials.nan_count(),
+                infinite_count: self.specials.infinite_count(),
+                sentinels: missing,
+                ..stats
+            });
+        }
+        if self.stats.count() == 0 {
+            return None;
+        }
+
+        let mut digest = self.digest.clone();
+        digest.compress();
+        let quartiles = [
+            digest.quantile(0.25)?,
+            digest.quantile(0.5)?,
+            digest.quantile(0.75)?,
+        ];
+        let percentiles = self
+            .percentiles
+            .iter()
+            .map(|&p| {
+                digest
+                    .quantile(p / 100.0)
+                    .map(|value| Percentile { p, value })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(NumericStats {
+            min: self.stats.min(),
+            max: self.stats.max(),
+            mean: self.stats.mean(),
+            median: quartiles[1],
+            std_dev: self.stats.std_dev(),
+            quartiles,
+            percentiles,
+            skewness: self.stats.skewness(),
+            kurtosis: self.stats.kurtosis(),
+            mode: None,
+            zero_count: self.zero_count,
+            negative_count: self.negative_count,
+            precision: self.shape.map_or(0, |shape| shape.precision()),
+            scale: self.shape.map_or(0, |shape| shape.scale),
+            nan_count: self.specials.nan_count(),
+            infinite_count: self.specials.infinite_count(),
+            sentinels: missing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_online_stats_matches_two_pass() {
+        let values: Vec<f64> = (1..=100).map(|x| (x as f64).sqrt() * 3.0).collect();
+        let mut stats = OnlineStats::new();
+        values.iter().for_each(|&v| stats.push(v));
+
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance =
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+
+        assert!((stats.mean() - mean).abs() < 1e-12);
+        assert!((stats.variance() - variance).abs() < 1e-9);
+        assert_eq!(stats.min(), values[0]);
+        assert_eq!(stats.max(), values[99]);
+    }
+
+    #[test]
+    fn test_compensated_summation() {
+        // Each 1 is lost to rounding when added to 1e16 naively
+        let values: Vec<f64> = (0..1000).flat_map(|_| [1e16, 1.0, -1e16]).collect();
+        assert_eq!(values.iter().sum::<f64>(), 0.0);
+        assert_eq!(compensated_sum(values.iter().copied()), 1000.0);
+
+        let mut streamed = OnlineStats::new();
+        values.iter().for_each(|&v| streamed.push(v));
+        assert_eq!(streamed.mean(), 1000.0 / 3000.0);
+        assert_eq!(OnlineStats::from_values(&values).mean(), 1000.0 / 3000.0);
+
+        let mut left = OnlineStats::new();
+        let mut right = OnlineStats::new();
+        [1e16, 1.0].iter().for_each(|&v| left.push(v));
+        [-1e16, 1.0].iter().for_each(|&v| right.push(v));
+        left.merge(&right);
+        assert_eq!(left.mean(), 0.5);
+    }
+
+    #[test]
+    fn test_variance_with_large_offset() {
+        // Variance 30 around a mean of 1e9 + 10, where summing squares cancels catastrophically
+        let values: Vec<f64> = [4.0, 7.0, 13.0, 16.0].iter().map(|v| 1e9 + v).collect();
+        let two_pass = OnlineStats::from_values(&values);
+        assert_eq!(two_pass.variance(), 30.0);
+        assert_eq!(two_pass.mean(), 1e9 + 10.0);
+        assert_eq!(two_pass.skewness(), 0.0);
+
+        let mut welford = OnlineStats::new();
+        values.iter().for_each(|&v| welford.push(v));
+        assert!((welford.variance() - 30.0).abs() < 1e-6);
+
+        let n = values.len() as f64;
+        let naive = (values.iter().map(|v| v * v).sum::<f64>()
+            - values.iter().sum::<f64>().powi(2) / n)
+            / (n - 1.0);
+        assert!(
+            (naive - 30.0).abs() > 1.0,
+            "naive formula should lose the variance"
+        );
+
+        assert_eq!(OnlineStats::from_values(&[]), OnlineStats::new());
+    }
+
+    #[test]
+    fn test_online_stats_merge() {
+        let mut whole = OnlineStats::new();
+        let mut left = OnlineStats::new();
+        let mut right = OnlineStats::new();
+        for x in 0..50 {
+            whole.push(x as f64);
+            left.push(x as f64);
+        }
+        for x in 50..80 {
+            whole.push(x as f64);
+            right.push(x as f64);
+        }
+
+        left.merge(&right);
+        assert_eq!(left.count(), whole.count());
+        assert!((left.mean() - whole.mean()).abs() < 1e-12);
+        assert!((left.variance() - whole.variance()).abs() < 1e-9);
+        assert_eq!(left.max(), 79.0);
+
+        assert!((left.skewness() - whole.skewness()).abs() < 1e-9);
+        assert!((left.kurtosis() - whole.kurtosis()).abs() < 1e-9);
+
+        let mut empty = OnlineStats::new();
+        empty.merge(&whole);
+        assert_eq!(empty, whole);
+    }
+
+    #[test]
+    fn test_online_stats_shape() {
+        let values = [1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 10.0, 20.0];
+        let mut stats = OnlineStats::new();
+        values.iter().for_each(|&v| stats.push(v));
+
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let moment = |k: i32| values.iter().map(|v| (v - mean).powi(k)).sum::<f64>() / n;
+        let skewness = moment(3) / moment(2).powf(1.5);
+        let kurtosis = moment(4) / moment(2).powi(2) - 3.0;
+
+        assert!((stats.skewness() - skewness).abs() < 1e-9);
+        assert!((stats.kurtosis() - kurtosis).abs() < 1e-9);
+
+        let mut constant = OnlineStats::new();
+        [4.0, 4.0, 4.0].iter().for_each(|&v| constant.push(v));
+        assert_eq!(constant.skewness(), 0.0);
+        assert_eq!(constant.kurtosis(), 0.0);
+    }
+
+    #[test]
+    fn test_p2_quantile_accuracy() {
+        // Values 0..10000 in a scrambled but deterministic order
+        let mut median = P2Quantile::new(0.5);
+        let mut upper = P2Quantile::new(0.9);
+        for i in 0..10_000u64 {
+            let value = ((i * 7919) % 10_000) as f64;
+            median.push(value);
+            upper.push(value);
+        }
+
+        assert!((median.estimate().unwrap() - 5_000.0).abs() < 100.0);
+        assert!((upper.estimate().unwrap() - 9_000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn test_p2_small_samples_are_exact() {
+        let mut median = P2Quantile::new(0.5);
+        assert_eq!(median.estimate(), None);
+        for value in [3.0, 1.0, 2.0] {
+            median.push(value);
+        }
+        assert_eq!(median.estimate(), Some(2.0));
+    }
+
+    #[test]
+    fn test_streaming_numeric_stats_in_chunks() {
+        let mut streaming = StreamingNumericStats::default();
+        streaming.extend(["1", "2", "", "n/a"]);
+        streaming.extend(["3", "4", "5"]);
+
+        let stats = streaming.finish().unwrap();
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 5.0);
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.median, 3.0);
+        assert!((stats.std_dev - 2.5f64.sqrt()).abs() < 1e-12);
+
+        assert_eq!(stats.mode, None);
+        assert!(StreamingNumericStats::default().finish().is_none());
+
+        let mut streaming = StreamingNumericStats::with_percentiles(&[10.0, 90.0]);
+        streaming.extend(["-2", "0", "0", "1", "3", "5"]);
+        let stats = streaming.finish().unwrap();
+        assert_eq!(stats.percentiles.len(), 2);
+        assert_eq!(stats.percentiles[0].p, 10.0);
+        assert_eq!(stats.zero_count, 2);
+        assert_eq!(stats.negative_count, 1);
+    }
+}
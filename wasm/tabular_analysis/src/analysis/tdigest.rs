@@ -0,0 +1,221 @@
+use std::f64::consts::PI;
+
+// Compression used unless configured otherwise: a few hundred centroids, quantiles within
+// about 1% of the true rank in the middle and far closer in the tails
+pub const DEFAULT_COMPRESSION: f64 = 100.0;
+
+// Values buffered per unit of compression before they are merged into the centroids
+const BUFFER_FACTOR: usize = 5;
+
+/// A cluster of nearby values summarized by their mean and count
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest (Dunning & Ertl, 2019): a sketch answering any quantile query over a
+/// column in memory bounded by its compression. Centroids stay small near the extremes and
+/// grow towards the median, so tail quantiles like p99 stay accurate. Digests of separate
+/// chunks can be combined with `merge`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    // Sorted by mean once compressed
+    centroids: Vec<Centroid>,
+    buffer: Vec<Centroid>,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION)
+    }
+}
+
+impl TDigest {
+    /// An empty digest; higher compression keeps more centroids for more accurate quantiles
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression: compression.max(1.0),
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.push_weighted(value, 1.0);
+    }
+
+    fn push_weighted(&mut self, mean: f64, weight: f64) {
+        self.min = self.min.min(mean);
+        self.max = self.max.max(mean);
+        self.buffer.push(Centroid { mean, weight });
+        if self.buffer.len() >= BUFFER_FACTOR * self.compression as usize {
+            self.compress();
+        }
+    }
+
+    /// Adds every value another digest has seen
+    pub fn merge(&mut self, other: &TDigest) {
+        for centroid in other.centroids.iter().chain(&other.buffer) {
+            self.push_weighted(centroid.mean, centroid.weight);
+        }
+    }
+
+    pub fn count(&self) -> f64 {
+        self.centroids
+            .iter()
+            .chain(&self.buffer)
+            .map(|c| c.weight)
+            .sum()
+    }
+
+    // Scale function k1: centroid sizes shrink towards q = 0 and q = 1
+    fn scale(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn inverse_scale(&self, k: f64) -> f64 {
+        ((k * 2.0 * PI / self.compression).sin() + 1.0) / 2.0
+    }
+
+    /// Merges buffered values into the centroids, keeping each centroid within one unit of
+    /// the scale function
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut all = std::mem::take(&mut self.centroids);
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = all.iter().map(|c| c.weight).sum();
+        let mut merged = Vec::with_capacity(all.len().min(2 * self.compression as usize));
+        let mut current = all[0];
+        let mut weight_before = 0.0;
+        let mut limit = self.inverse_scale(self.scale(0.0) + 1.0);
+        for &next in &all[1..] {
+            if weight_before + (current.weight + next.weight) / total <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight / total;
+                limit = self.inverse_scale(self.scale(weight_before) + 1.0);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// Estimated value at quantile `q` (0-1), interpolating between centroid centers and out
+    /// to the exact min and max; None before any value is pushed
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if !self.buffer.is_empty() {
+            let mut compressed = self.clone();
+            compressed.compress();
+            return compressed.quantile(q);
+        }
+        let first = self.centroids.first()?;
+        let last = self.centroids.last()?;
+        let q = q.clamp(0.0, 1.0);
+        if self.centroids.len() == 1 {
+            return Some(first.mean);
+        }
+
+        let total: f64 = self.centroids.iter().map(|c| c.weight).sum();
+        let rank = q * total;
+        // Below the first center the values run from the min up to its mean
+        if rank < first.weight / 2.0 {
+            return Some(self.min + (first.mean - self.min) * rank / (first.weight / 2.0));
+        }
+        let mut weight_before = 0.0;
+        for pair in self.centroids.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let left_center = weight_before + left.weight / 2.0;
+            let right_center = weight_before + left.weight + right.weight / 2.0;
+            if rank <= right_center {
+                let fraction = (rank - left_center) / (right_center - left_center);
+                return Some(left.mean + (right.mean - left.mean) * fraction);
+            }
+            weight_before += left.weight;
+        }
+        let last_center = total - last.weight / 2.0;
+        let fraction = ((rank - last_center) / (last.weight / 2.0)).min(1.0);
+        Some(last.mean + (self.max - last.mean) * fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic pseudo-random values in [0, 1)
+    fn uniform(count: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_quantiles_within_rank_error() {
+        let values = uniform(100_000);
+        let mut digest = TDigest::default();
+        values.iter().for_each(|&v| digest.push(v));
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        for q in [0.001, 0.01, 0.25, 0.5, 0.75, 0.99, 0.999] {
+            let estimate = digest.quantile(q).unwrap();
+            let rank = sorted.partition_point(|&v| v < estimate) as f64 / sorted.len() as f64;
+            assert!((rank - q).abs() < 0.01, "q{} landed at rank {}", q, rank);
+        }
+        assert_eq!(digest.quantile(0.0), Some(sorted[0]));
+        assert_eq!(digest.quantile(1.0), Some(sorted[sorted.len() - 1]));
+        assert!(digest.centroids.len() < 200);
+    }
+
+    #[test]
+    fn test_merge_matches_single_digest() {
+        let values = uniform(20_000);
+        let mut whole = TDigest::new(200.0);
+        let mut left = TDigest::new(200.0);
+        let mut right = TDigest::new(200.0);
+        for (i, &v) in values.iter().enumerate() {
+            whole.push(v);
+            if i % 2 == 0 {
+                left.push(v);
+            } else {
+                right.push(v);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.count(), 20_000.0);
+        let (a, b) = (left.quantile(0.5).unwrap(), whole.quantile(0.5).unwrap());
+        assert!((a - b).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_small_digests_are_exact() {
+        let mut digest = TDigest::default();
+        assert_eq!(digest.quantile(0.5), None);
+        [5.0, 1.0, 3.0, 2.0, 4.0]
+            .iter()
+            .for_each(|&v| digest.push(v));
+        assert_eq!(digest.quantile(0.5), Some(3.0));
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(5.0));
+    }
+}
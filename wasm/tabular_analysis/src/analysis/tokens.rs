@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Average length from which a text column's values are tokenized
+pub const LONG_TEXT_LENGTH: f64 = 16.0;
+// Terms listed in `top_terms`
+const TOP_TERMS: usize = 10;
+// Share of tokens a language's stopwords must make up for the language to be guessed
+const MIN_STOPWORD_SHARE: f64 = 0.05;
+
+// Frequent function words of each language guessed, by ISO 639-1 code
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "a", "in", "is", "it", "that", "for", "was", "on", "with",
+            "as", "this", "but", "be", "are", "not", "have", "at", "by", "from", "or", "an",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "de", "que", "y", "en", "los", "las", "del", "se", "por", "un", "una",
+            "con", "no", "es", "para", "lo", "como", "pero", "muy",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "de", "des", "et", "est", "un", "une", "du", "en", "que", "qui",
+            "pas", "pour", "dans", "sur", "au", "avec", "ce", "très",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "den", "mit", "von",
+            "sich", "des", "auf", "für", "im", "dem", "auch", "sehr",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "de", "que", "e", "do", "da", "em", "um", "uma", "para", "com",
+            "não", "no", "na", "por", "mais", "muito",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "la", "gli", "le", "di", "che", "e", "è", "un", "una", "per", "non", "con",
+            "del", "della", "sono", "ma", "molto",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "niet", "dat", "op", "te", "met", "voor",
+            "zijn", "maar", "ook", "heel",
+        ],
+    ),
+];
+
+/// Word-level statistics of a long text column, such as comments or descriptions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TokenStats {
+    /// Tokens per non-empty value, splitting on anything but letters, digits and apostrophes
+    pub avg_token_count: f64,
+    pub distinct_terms: usize,
+    /// Most frequent words, lowercase, leaving out stopwords, numbers and single letters
+    pub top_terms: Vec<(String, usize)>,
+    /// ISO 639-1 code of the language whose stopwords are most frequent, when they are
+    /// frequent enough to tell
+    pub language: Option<String>,
+    /// Whether the values read as free-form prose rather than codes, IDs or short labels
+    pub prose: bool,
+}
+
+/// Lowercase tokens of a value: runs of letters, digits and apostrophes
+pub fn tokenize(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .map(|token| token.trim_matches(|c| c == '\'' || c == '’'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn is_stopword(token: &str) -> bool {
+    STOPWORDS.iter().any(|(_, words)| words.contains(&token))
+}
+
+/// Token statistics of the non-empty values, None when there are none
+pub fn calculate_token_stats(values: &[String]) -> Option<TokenStats> {
+    let mut value_count = 0;
+    let mut token_count = 0;
+    let mut word_count = 0;
+    let mut terms: HashMap<String, usize> = HashMap::new();
+    let mut stopwords = vec![0usize; STOPWORDS.len()];
+
+    for value in values.iter().filter(|v| !v.trim().is_empty()) {
+        value_count += 1;
+        for token in tokenize(value) {
+            token_count += 1;
+            if token.chars().all(char::is_alphabetic) {
+                word_count += 1;
+            }
+            for (hits, (_, words)) in stopwords.iter_mut().zip(STOPWORDS) {
+                *hits += words.contains(&token.as_str()) as usize;
+            }
+            *terms.entry(token).or_default() += 1;
+        }
+    }
+    if value_count == 0 {
+        return None;
+    }
+
+    let distinct_terms = terms.len();
+    let mut top_terms: Vec<(String, usize)> = terms
+        .into_iter()
+        .filter(|(term, _)| {
+            term.chars().count() > 1 && term.chars().any(char::is_alphabetic) && !is_stopword(term)
+        })
+        .collect();
+    top_terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_terms.truncate(TOP_TERMS);
+
+    // Ties go to the language listed first
+    let (best, hits) = stopwords
+        .iter()
+        .enumerate()
+        .fold((0, 0), |best, (i, &hits)| match hits > best.1 {
+            true => (i, hits),
+            false => best,
+        });
+    let stopword_share = hits as f64 / token_count.max(1) as f64;
+    let language =
+        (hits >= 3 && stopword_share >= MIN_STOPWORD_SHARE).then(|| STOPWORDS[best].0.to_string());
+
+    // Prose runs to several words per value, mostly letters, with function words among them;
+    // codes split into digit runs and product names lack the function words
+    let avg_token_count = token_count as f64 / value_count as f64;
+    let word_share = word_count as f64 / token_count.max(1) as f64;
+    let prose = avg_token_count >= 3.0 && word_share >= 0.7 && language.is_some();
+
+    Some(TokenStats {
+        avg_token_count,
+        distinct_terms,
+        top_terms,
+        language,
+        prose,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_prose_column() {
+        let reviews = column(&[
+            "The delivery was late and the box was damaged.",
+            "Great product, the battery lasts for days!",
+            "",
+            "It's fine but the battery is weak in the cold.",
+        ]);
+        let stats = calculate_token_stats(&reviews).unwrap();
+        assert!(stats.prose);
+        assert_eq!(stats.language.as_deref(), Some("en"));
+        assert_eq!(stats.top_terms[0], ("battery".to_string(), 2));
+        assert!(stats.top_terms.iter().all(|(term, _)| term != "the"));
+        assert!((stats.avg_token_count - 26.0 / 3.0).abs() < 1e-9);
+
+        let spanish = column(&[
+            "El envío llegó tarde y la caja estaba rota",
+            "Muy buen producto, la batería dura mucho",
+            "No me gusta el color de la funda",
+        ]);
+        let stats = calculate_token_stats(&spanish).unwrap();
+        assert_eq!(stats.language.as_deref(), Some("es"));
+    }
+
+    #[test]
+    fn test_codes_are_not_prose() {
+        let codes = column(&["INV-2024-000123", "INV-2024-000124", "PO-2023-7781-A"]);
+        let stats = calculate_token_stats(&codes).unwrap();
+        assert!(!stats.prose);
+        assert_eq!(stats.language, None);
+        assert_eq!(stats.top_terms[0], ("inv".to_string(), 2));
+
+        let products = column(&["Red Cotton Crew T-Shirt", "Blue Denim Slim Jeans"]);
+        assert!(!calculate_token_stats(&products).unwrap().prose);
+        assert!(calculate_token_stats(&column(&["", " "])).is_none());
+    }
+}
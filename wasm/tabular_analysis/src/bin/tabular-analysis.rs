@@ -0,0 +1,402 @@
+//! Command-line front end to the analysis engine, running the same inference as the canvas
+//! on local files or stdin.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use tabular_analysis::analysis::profile::{column_table, markdown_table};
+use tabular_analysis::analysis::rules::parse_rules;
+use tabular_analysis::cleaning::corrections::CorrectionPolicy;
+use tabular_analysis::logging::{set_log_sink, LogFilter};
+use tabular_analysis::parallel::configure_pool;
+use tabular_analysis::warning::Severity;
+use tabular_analysis::{AnalysisConfig, DataType, CSV};
+
+const USAGE: &str = "\
+Usage: tabular-analysis <COMMAND> [FILE] [OPTIONS]
+
+Reads CSV from FILE, or from stdin when FILE is missing or `-`.
+
+Commands:
+  profile    Column types, statistics and data quality counts
+  schema     Column metadata, in the layout read back by `validate_against`
+  clean      Applies type corrections and writes the cleaned CSV
+  convert    Writes the rows as typed JSON objects or a Markdown table
+
+Options:
+  -f, --format <json|markdown>  Output format (default: json)
+  -o, --output <FILE>           Write to FILE instead of stdout
+  -c, --config <FILE>           Analysis options as JSON
+      --rules <FILE>            Data quality rules as JSON (profile)
+      --min-confidence <N>      Confidence floor for corrections (clean, default: 0.9)
+      --standardize-dates       Rewrite date columns to ISO-8601 (clean)
+      --dedupe                  Drop repeated rows, keeping the first (clean)
+      --log <FILE>              Write the cleaning log as JSON (clean)
+      --threads <N>             Threads to analyze with (default: one per core)
+  -v, --verbose                 Print parsing, inference and cleaning diagnostics to stderr
+  -h, --help                    Print this help
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Profile,
+    Schema,
+    Clean,
+    Convert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Markdown,
+}
+
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    input: Option<String>,
+    output: Option<String>,
+    format: Format,
+    config: Option<String>,
+    rules: Option<String>,
+    min_confidence: Option<f64>,
+    standardize_dates: bool,
+    dedupe: bool,
+    log: Option<String>,
+    threads: Option<usize>,
+    verbose: bool,
+}
+
+// Failures while running a command: engine errors, or I/O and JSON errors described as text
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+// Outcome of parsing the command line: options to run with, or a request for the usage text
+enum Parsed {
+    Run(Options),
+    Help,
+}
+
+fn parse_args(args: &[String]) -> Result<Parsed, String> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        Some("profile") => Command::Profile,
+        Some("schema") => Command::Schema,
+        Some("clean") => Command::Clean,
+        Some("convert") => Command::Convert,
+        Some("-h") | Some("--help") | Some("help") => return Ok(Parsed::Help),
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+        None => return Err("Missing command".to_string()),
+    };
+
+    let mut options = Options {
+        command,
+        input: None,
+        output: None,
+        format: Format::Json,
+        config: None,
+        rules: None,
+        min_confidence: None,
+        standardize_dates: false,
+        dedupe: false,
+        log: None,
+        threads: None,
+        verbose: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Parsed::Help),
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "markdown" | "md" => Format::Markdown,
+                    other => return Err(format!("Unknown format: {}", other)),
+                }
+            }
+            "-o" | "--output" => options.output = Some(value()?),
+            "-c" | "--config" => options.config = Some(value()?),
+            "--rules" => options.rules = Some(value()?),
+            "--log" => options.log = Some(value()?),
+            "--min-confidence" => {
+                let confidence = value()?;
+                options.min_confidence = Some(
+                    confidence
+                        .parse()
+                        .map_err(|_| format!("Invalid confidence: {}", confidence))?,
+                );
+            }
+            "--threads" => {
+                let threads = value()?;
+                options.threads = Some(
+                    threads
+                        .parse()
+                        .map_err(|_| format!("Invalid thread count: {}", threads))?,
+                );
+            }
+            "--standardize-dates" => options.standardize_dates = true,
+            "--dedupe" => options.dedupe = true,
+            "-v" | "--verbose" => options.verbose = true,
+            "-" => options.input = None,
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            path if options.input.is_none() => options.input = Some(path.to_string()),
+            extra => return Err(format!("Unexpected argument: {}", extra)),
+        }
+    }
+
+    Ok(Parsed::Run(options))
+}
+
+fn read_input(path: Option<&str>) -> CliResult<String> {
+    match path {
+        Some(path) => {
+            Ok(fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?)
+        }
+        None => {
+            let mut data = String::new();
+            io::stdin()
+                .read_to_string(&mut data)
+                .map_err(|e| format!("Failed to read stdin: {}", e))?;
+            Ok(data)
+        }
+    }
+}
+
+fn write_output(path: Option<&str>, contents: &str) -> CliResult<()> {
+    match path {
+        Some(path) => {
+            fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?
+        }
+        None => io::stdout()
+            .write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write output: {}", e))?,
+    }
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> CliResult<String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {}", e))?;
+    Ok(json + "\n")
+}
+
+fn load_csv(options: &Options) -> CliResult<CSV> {
+    let mut csv = CSV::from_string(read_input(options.input.as_deref())?)?;
+    if let Some(path) = &options.config {
+        let json = read_input(Some(path))?;
+        let config: AnalysisConfig =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid config: {}", e))?;
+        csv.apply_config(config)?;
+    }
+    csv.infer_column_types();
+    Ok(csv)
+}
+
+fn profile(csv: &mut CSV, options: &Options) -> CliResult<String> {
+    let report = match &options.rules {
+        Some(path) => csv.build_profile_with_rules(&parse_rules(&read_input(Some(path))?)?)?,
+        None => csv.build_profile(),
+    };
+    match options.format {
+        Format::Json => to_json(&report),
+        Format::Markdown => Ok(report.to_markdown()),
+    }
+}
+
+fn schema(csv: &CSV, options: &Options) -> CliResult<String> {
+    match options.format {
+        Format::Json => to_json(&csv.saved_metadata()),
+        Format::Markdown => {
+            let columns: Vec<_> = (0..csv.column_count())
+                .filter_map(|i| csv.column_metadata(i).cloned())
+                .collect();
+            Ok(column_table(&columns))
+        }
+    }
+}
+
+fn clean(mut csv: CSV, options: &Options) -> CliResult<String> {
+    let mut policy = CorrectionPolicy::default();
+    if let Some(min_confidence) = options.min_confidence {
+        policy.min_confidence = min_confidence;
+    }
+    let report = csv.apply_corrections(&policy)?;
+    eprintln!(
+        "Applied {} corrections, {} values left for review",
+        report.applied.len(),
+        report.flagged.len()
+    );
+
+    if options.standardize_dates {
+        let date_columns: Vec<usize> = (0..csv.column_count())
+            .filter(|&i| {
+                csv.column_metadata(i)
+                    .is_some_and(|m| m.data_type == DataType::Date)
+            })
+            .collect();
+        for column in date_columns {
+            csv.standardize_date_column(column)?;
+        }
+    }
+    if options.dedupe {
+        let before = csv.row_count();
+        csv = csv.dedupe(None)?;
+        eprintln!("Removed {} duplicate rows", before - csv.row_count());
+    }
+
+    if let Some(path) = &options.log {
+        write_output(Some(path), &to_json(&csv.cleaning_steps())?)?;
+    }
+    Ok(csv.to_csv_string()?)
+}
+
+fn convert(csv: &CSV, options: &Options) -> CliResult<String> {
+    match options.format {
+        Format::Json => to_json(&csv.row_page(0, csv.row_count())?.rows),
+        Format::Markdown => {
+            let rows: Vec<Vec<String>> = (0..csv.row_count())
+                .map(|row| {
+                    csv.get_columns()
+                        .iter()
+                        .map(|(_, values)| values.get(row).cloned().unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            Ok(markdown_table(&csv.headers(), &rows))
+        }
+    }
+}
+
+fn run(options: &Options) -> CliResult<()> {
+    if options.verbose {
+        set_log_sink(
+            |record| eprintln!("[{} {}] {}", record.level, record.target, record.message),
+            LogFilter::parse("debug")?,
+        );
+    }
+    if let Some(threads) = options.threads {
+        configure_pool(threads);
+    }
+    let mut csv = load_csv(options)?;
+    for warning in csv.warnings() {
+        if warning.severity == Severity::Warning {
+            eprintln!("warning: {}", warning.message);
+        }
+    }
+    let output = match options.command {
+        Command::Profile => profile(&mut csv, options)?,
+        Command::Schema => schema(&csv, options)?,
+        Command::Clean => clean(csv, options)?,
+        Command::Convert => convert(&csv, options)?,
+    };
+    write_output(options.output.as_deref(), &output)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Parsed::Run(options)) => options,
+        Ok(Parsed::Help) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn options(line: &str) -> Options {
+        match parse_args(&args(line)) {
+            Ok(Parsed::Run(options)) => options,
+            _ => panic!("Expected options for {}", line),
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = options("clean data.csv --min-confidence 0.8 --dedupe -o out.csv -v");
+        assert_eq!(parsed.command, Command::Clean);
+        assert_eq!(parsed.input.as_deref(), Some("data.csv"));
+        assert_eq!(parsed.output.as_deref(), Some("out.csv"));
+        assert_eq!(parsed.min_confidence, Some(0.8));
+        assert!(parsed.dedupe && parsed.verbose && !parsed.standardize_dates);
+        assert_eq!(parsed.format, Format::Json);
+
+        let parsed = options("convert - -f md --threads 2");
+        assert_eq!(parsed.command, Command::Convert);
+        assert_eq!(parsed.input, None);
+        assert_eq!(parsed.format, Format::Markdown);
+        assert_eq!(parsed.threads, Some(2));
+
+        assert!(matches!(parse_args(&args("--help")), Ok(Parsed::Help)));
+        assert!(matches!(parse_args(&args("profile -h")), Ok(Parsed::Help)));
+        for line in [
+            "",
+            "summarize",
+            "profile -f xml",
+            "profile --min-confidence high",
+            "profile --rules",
+            "profile --colour",
+            "profile a.csv b.csv",
+        ] {
+            assert!(parse_args(&args(line)).is_err(), "Accepted {:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_convert_to_json() {
+        let mut csv = CSV::from_string("id,price,name\n1,2.5,a\n2,,b".to_string()).unwrap();
+        csv.infer_column_types();
+
+        let json = convert(&csv, &options("convert")).unwrap();
+        let rows: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            rows,
+            serde_json::json!([
+                {"id": 1, "price": 2.5, "name": "a"},
+                {"id": 2, "price": null, "name": "b"}
+            ])
+        );
+        assert!(json.contains("\"id\": 1,"));
+
+        let markdown = convert(&csv, &options("convert -f markdown")).unwrap();
+        assert!(markdown.starts_with("| id | price | name |"));
+    }
+
+    #[test]
+    fn test_schema_and_profile_output() {
+        let mut csv = CSV::from_string("id,name\n1,a\n2,b".to_string()).unwrap();
+        csv.infer_column_types();
+
+        let schema: serde_json::Value =
+            serde_json::from_str(&schema(&csv, &options("schema")).unwrap()).unwrap();
+        assert_eq!(schema["columns"][0]["data_type"], "Integer");
+
+        let profile = profile(&mut csv, &options("profile -f markdown")).unwrap();
+        assert!(profile.contains("id"));
+    }
+}
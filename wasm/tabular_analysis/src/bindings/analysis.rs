@@ -0,0 +1,168 @@
+use serde::Serialize;
+use serde_wasm_bindgen::{to_value, Serializer};
+use wasm_bindgen::prelude::*;
+
+use super::csv::JsCsv;
+use super::{optional_config, serialization_error};
+use crate::analysis::batch;
+use crate::analysis::explain::DEFAULT_MAX_EXAMPLES;
+use crate::analysis::memory;
+use crate::analysis::pii::detect_pii;
+use crate::analysis::rules::parse_rules;
+use crate::error::AnalysisError;
+
+/// Profiles several CSVs given as `[name, text]` pairs with the same options, returning each
+/// file's profile or parse error keyed by name
+#[wasm_bindgen(unchecked_return_type = "BatchReport")]
+pub fn analyze_files(
+    #[wasm_bindgen(unchecked_param_type = "[name: string, data: string][]")] files: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "AnalysisConfig | undefined")] config: JsValue,
+) -> Result<JsValue, JsValue> {
+    let files: Vec<(String, String)> = serde_wasm_bindgen::from_value(files).map_err(|e| {
+        AnalysisError::invalid_argument(format!("Expected [name, data] pairs: {}", e))
+    })?;
+    let report = batch::analyze_files(files, &optional_config(config)?)?;
+    to_value(&report).map_err(|e| serialization_error("batch report", e))
+}
+
+/// Analyzes CSV text within the config's memory limit, sampling rows or reading one column at
+/// a time when the whole table wouldn't fit
+#[wasm_bindgen(unchecked_return_type = "BoundedAnalysis")]
+pub fn analyze_with_memory_limit(
+    data: &str,
+    #[wasm_bindgen(unchecked_param_type = "AnalysisConfig | undefined")] config: JsValue,
+) -> Result<JsValue, JsValue> {
+    let analysis = memory::analyze_with_memory_limit(data, &optional_config(config)?)?;
+    to_value(&analysis).map_err(|e| serialization_error("bounded analysis", e))
+}
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// Tests whether two categorical columns are related, returning their contingency
+    /// table, the chi-square statistic with its p-value and Cramér's V
+    #[wasm_bindgen(unchecked_return_type = "Association")]
+    pub fn association(&self, col_a: usize, col_b: usize) -> Result<JsValue, JsValue> {
+        let association = self.inner.column_association(col_a, col_b)?;
+        to_value(&association).map_err(|e| serialization_error("association", e))
+    }
+
+    /// Groups rows into `k` clusters by the given numeric columns, standardized so each
+    /// column weighs the same; the same seed gives the same clusters
+    #[wasm_bindgen(unchecked_return_type = "Clustering")]
+    pub fn kmeans(
+        &self,
+        columns: Vec<usize>,
+        k: usize,
+        iterations: usize,
+        seed: u32,
+    ) -> Result<JsValue, JsValue> {
+        let clustering = self.inner.cluster_rows(&columns, k, iterations, seed)?;
+        clustering
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("clustering", e))
+    }
+
+    /// Checks every column described by a schema from `export_metadata` against its
+    /// expected type, format and value range
+    #[wasm_bindgen(unchecked_return_type = "ValidationReport")]
+    pub fn validate_against(&self, schema: &str) -> Result<JsValue, JsValue> {
+        let report = self.inner.validate_against_schema(schema)?;
+        to_value(&report).map_err(|e| serialization_error("validation report", e))
+    }
+
+    /// Explains the type of a column with the score of every candidate type and up to
+    /// `max_examples` (default 5) values that failed each one
+    #[wasm_bindgen(unchecked_return_type = "TypeExplanation")]
+    pub fn explain_type(
+        &self,
+        column: usize,
+        max_examples: Option<usize>,
+    ) -> Result<JsValue, JsValue> {
+        let explanation = self
+            .inner
+            .type_explanation(column, max_examples.unwrap_or(DEFAULT_MAX_EXAMPLES))?;
+        to_value(&explanation).map_err(|e| serialization_error("type explanation", e))
+    }
+
+    /// Reports the most common value shapes in a column and the rows that break the dominant one
+    #[wasm_bindgen(unchecked_return_type = "PatternReport")]
+    pub fn pattern_profile(&self, column: usize) -> Result<JsValue, JsValue> {
+        let report = self.inner.pattern_report(column)?;
+        to_value(&report).map_err(|e| serialization_error("pattern report", e))
+    }
+
+    /// Scans every column for emails, phone numbers, names, card numbers and SSNs
+    #[wasm_bindgen(unchecked_return_type = "PiiReport")]
+    pub fn pii_report(&self) -> Result<JsValue, JsValue> {
+        let report = detect_pii(&self.inner.get_columns());
+        to_value(&report).map_err(|e| serialization_error("PII report", e))
+    }
+
+    /// Lists conditions worth surfacing next to the results, such as rows padded while
+    /// parsing, a sample too small for reliable statistics or dates of unclear order
+    #[wasm_bindgen(unchecked_return_type = "AnalysisWarning[]")]
+    pub fn warnings(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.warnings()).map_err(|e| serialization_error("warnings", e))
+    }
+
+    /// Profiles the CSV, running type inference for any column that has not been analyzed yet
+    #[wasm_bindgen(unchecked_return_type = "ProfileReport")]
+    pub fn profile(&mut self) -> Result<JsValue, JsValue> {
+        let report = self.inner.build_profile();
+        to_value(&report).map_err(|e| serialization_error("profile", e))
+    }
+
+    /// Profiles the CSV like `profile`, also running a JSON array of validation rules
+    /// (see `validate_rules`) whose pass rate counts towards the quality score
+    #[wasm_bindgen(unchecked_return_type = "ProfileReport")]
+    pub fn profile_with_rules(&mut self, rules: &str) -> Result<JsValue, JsValue> {
+        let report =
+            parse_rules(rules).and_then(|rules| self.inner.build_profile_with_rules(&rules))?;
+        to_value(&report).map_err(|e| serialization_error("profile", e))
+    }
+
+    /// Any percentiles (0-100) of a numeric column, exact for columns small enough to sort
+    /// and t-digest estimates at the configured `quantile_compression` beyond
+    #[wasm_bindgen(unchecked_return_type = "Percentile[]")]
+    pub fn column_percentiles(
+        &self,
+        column: usize,
+        percentiles: Vec<f64>,
+    ) -> Result<JsValue, JsValue> {
+        let percentiles = self.inner.column_percentiles(column, &percentiles)?;
+        to_value(&percentiles).map_err(|e| serialization_error("percentiles", e))
+    }
+
+    /// Fits a straight line predicting one numeric column from another, with R² and the
+    /// rows lying far from the line
+    #[wasm_bindgen(unchecked_return_type = "LinearFit")]
+    pub fn fit_linear(&self, x_col: usize, y_col: usize) -> Result<JsValue, JsValue> {
+        let fit = self.inner.linear_fit(x_col, y_col)?;
+        to_value(&fit).map_err(|e| serialization_error("linear fit", e))
+    }
+
+    /// Runs a JSON array of rules such as
+    /// `[{"rule": "compare", "left": "start", "op": "<", "right": "end"}]` and returns
+    /// pass/fail counts with failing row indices per rule
+    #[wasm_bindgen(unchecked_return_type = "RuleReport")]
+    pub fn validate_rules(&self, rules: &str) -> Result<JsValue, JsValue> {
+        let report = parse_rules(rules).and_then(|rules| self.inner.rule_report(&rules))?;
+        to_value(&report).map_err(|e| serialization_error("rule report", e))
+    }
+
+    /// Serializes the per-column metadata and analysis options to a versioned JSON string
+    #[wasm_bindgen]
+    pub fn export_metadata(&self) -> Result<String, JsValue> {
+        self.inner.export_metadata().map_err(JsValue::from)
+    }
+
+    /// Applies metadata from `export_metadata` to the columns with the same headers,
+    /// skipping type inference for them
+    #[wasm_bindgen(unchecked_return_type = "MetadataImportReport")]
+    pub fn import_metadata(&mut self, json: &str) -> Result<JsValue, JsValue> {
+        let report = self
+            .inner
+            .with_history("import_metadata", |csv| csv.apply_saved_metadata(json))?;
+        to_value(&report).map_err(|e| serialization_error("import report", e))
+    }
+}
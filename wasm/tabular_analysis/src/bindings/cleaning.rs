@@ -0,0 +1,239 @@
+use serde_wasm_bindgen::{from_value, to_value};
+use wasm_bindgen::prelude::*;
+
+use super::csv::JsCsv;
+use super::serialization_error;
+use crate::cleaning::corrections::CorrectionPolicy;
+use crate::cleaning::impute::ImputeStrategy;
+use crate::cleaning::mask::MaskOptions;
+use crate::error::AnalysisError;
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// Lists the values of an analyzed column that don't match its type, with suggested
+    /// fixes and their confidence
+    #[wasm_bindgen(unchecked_return_type = "Correction[]")]
+    pub fn suggest_corrections(&self, column: usize) -> Result<JsValue, JsValue> {
+        let corrections = self.inner.column_suggestions(column)?;
+        to_value(&corrections).map_err(|e| serialization_error("corrections", e))
+    }
+
+    /// Applies suggested fixes at or above the policy's confidence floor, e.g.
+    /// `{ min_confidence: 0.8, columns: [2] }`, recording every change in the cleaning log
+    /// and returning the applied changes with the values left for review
+    #[wasm_bindgen(unchecked_return_type = "CorrectionReport")]
+    pub fn apply_suggestions(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "CorrectionPolicy | undefined")] policy: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let policy: CorrectionPolicy = if policy.is_undefined() || policy.is_null() {
+            CorrectionPolicy::default()
+        } else {
+            from_value(policy).map_err(|e| {
+                AnalysisError::invalid_argument(format!("Invalid correction policy: {}", e))
+            })?
+        };
+        let report = self
+            .inner
+            .with_history("apply_suggestions", |csv| csv.apply_corrections(&policy))?;
+        to_value(&report).map_err(|e| serialization_error("correction report", e))
+    }
+
+    /// Rewrites a column of mixed-format dates to ISO-8601 (YYYY-MM-DD), resolving
+    /// DD/MM vs MM/DD ambiguity from the rest of the column
+    #[wasm_bindgen(unchecked_return_type = "DateStandardizationReport")]
+    pub fn standardize_dates(&mut self, column: usize) -> Result<JsValue, JsValue> {
+        let report = self.inner.with_history("standardize_dates", |csv| {
+            csv.standardize_date_column(column)
+        })?;
+        to_value(&report).map_err(|e| serialization_error("date report", e))
+    }
+
+    /// Reports exact-duplicate rows, comparing the full row or only the given key columns
+    #[wasm_bindgen(unchecked_return_type = "DuplicateReport")]
+    pub fn find_duplicates(&self, keys: Option<Vec<usize>>) -> Result<JsValue, JsValue> {
+        let report = self.inner.duplicate_report(keys)?;
+        to_value(&report).map_err(|e| serialization_error("duplicates", e))
+    }
+
+    /// Returns a copy of the CSV keeping only the first occurrence of each duplicate row
+    #[wasm_bindgen]
+    pub fn dedupe(&self, keys: Option<Vec<usize>>) -> Result<JsCsv, JsValue> {
+        self.inner
+            .dedupe(keys)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Fills missing values in a column using a strategy object such as
+    /// `{ strategy: "median" }` or `{ strategy: "constant", value: "0" }`
+    #[wasm_bindgen(unchecked_return_type = "ImputationReport")]
+    pub fn impute(
+        &mut self,
+        column: usize,
+        #[wasm_bindgen(unchecked_param_type = "ImputeStrategy")] strategy: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let strategy: ImputeStrategy = from_value(strategy).map_err(|e| {
+            AnalysisError::invalid_argument(format!("Invalid imputation strategy: {}", e))
+        })?;
+        let report = self
+            .inner
+            .with_history("impute", |csv| csv.impute_column(column, &strategy))?;
+
+        to_value(&report).map_err(|e| serialization_error("imputation report", e))
+    }
+
+    /// Redacts or hashes detected PII in place, e.g. before exporting the data
+    #[wasm_bindgen(unchecked_return_type = "MaskReport")]
+    pub fn mask_pii(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "MaskOptions | undefined")] options: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let options: MaskOptions = if options.is_undefined() || options.is_null() {
+            MaskOptions::default()
+        } else {
+            from_value(options).map_err(|e| {
+                AnalysisError::invalid_argument(format!("Invalid mask options: {}", e))
+            })?
+        };
+
+        let report = self
+            .inner
+            .with_history("mask_pii", |csv| csv.mask_pii_values(&options))?;
+        to_value(&report).map_err(|e| serialization_error("mask report", e))
+    }
+
+    /// Reports how many rows a replacement would change, with sample before/after values,
+    /// without modifying the data
+    #[wasm_bindgen(unchecked_return_type = "ReplaceReport")]
+    pub fn preview_replace(
+        &self,
+        column: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<JsValue, JsValue> {
+        let report = self.inner.replace_report(column, pattern, replacement)?;
+        to_value(&report).map_err(|e| serialization_error("replace report", e))
+    }
+
+    /// Replaces every regex match in a column and records the changes in the cleaning log.
+    /// The replacement may reference capture groups, e.g. `$1`.
+    #[wasm_bindgen(unchecked_return_type = "ReplaceReport")]
+    pub fn replace(
+        &mut self,
+        column: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<JsValue, JsValue> {
+        let report = self.inner.with_history("replace", |csv| {
+            csv.replace_in_column(column, pattern, replacement)
+        })?;
+        to_value(&report).map_err(|e| serialization_error("replace report", e))
+    }
+
+    /// Levels of a categorical column with their counts, most frequent first
+    #[wasm_bindgen(unchecked_return_type = "LevelCount[]")]
+    pub fn categorical_levels(&self, column: usize) -> Result<JsValue, JsValue> {
+        let levels = self.inner.categorical_levels(column)?;
+        to_value(&levels).map_err(|e| serialization_error("levels", e))
+    }
+
+    /// Folds levels of a categorical column into one, e.g. `["NY", "N.Y."]` into
+    /// "New York", recording the changes and recomputing the column's statistics
+    #[wasm_bindgen(unchecked_return_type = "LevelReport")]
+    pub fn merge_levels(
+        &mut self,
+        column: usize,
+        levels: Vec<String>,
+        into: &str,
+    ) -> Result<JsValue, JsValue> {
+        let report = self.inner.with_history("merge_levels", |csv| {
+            csv.merge_levels(column, &levels, into)
+        })?;
+        to_value(&report).map_err(|e| serialization_error("level report", e))
+    }
+
+    /// Renames a level of a categorical column
+    #[wasm_bindgen(unchecked_return_type = "LevelReport")]
+    pub fn rename_level(
+        &mut self,
+        column: usize,
+        level: &str,
+        new_name: &str,
+    ) -> Result<JsValue, JsValue> {
+        let report = self.inner.with_history("rename_level", |csv| {
+            csv.rename_level(column, level, new_name)
+        })?;
+        to_value(&report).map_err(|e| serialization_error("level report", e))
+    }
+
+    /// Folds the levels of a categorical column held by less than `min_share` (0 to 1) of
+    /// its cells into `other`, "Other" by default
+    #[wasm_bindgen(unchecked_return_type = "LevelReport")]
+    pub fn bucket_rare_levels(
+        &mut self,
+        column: usize,
+        min_share: f64,
+        other: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let other = other.unwrap_or_else(|| "Other".to_string());
+        let report = self.inner.with_history("bucket_rare_levels", |csv| {
+            csv.bucket_rare_levels(column, min_share, &other)
+        })?;
+        to_value(&report).map_err(|e| serialization_error("level report", e))
+    }
+
+    /// Splits a column into new columns appended to the table, e.g. "Last, First" on ",".
+    /// Returns the indices of the new columns, which have their types inferred.
+    #[wasm_bindgen(unchecked_return_type = "number[]")]
+    pub fn split_column(
+        &mut self,
+        index: usize,
+        delimiter: &str,
+        regex: Option<bool>,
+    ) -> Result<JsValue, JsValue> {
+        let new_columns = self.inner.with_history("split_column", |csv| {
+            csv.split_column_values(index, delimiter, regex.unwrap_or(false))
+        })?;
+        to_value(&new_columns).map_err(|e| serialization_error("column indices", e))
+    }
+
+    /// Joins several columns into a new column appended to the table, returning its index
+    #[wasm_bindgen]
+    pub fn merge_columns(
+        &mut self,
+        indices: Vec<usize>,
+        separator: &str,
+    ) -> Result<usize, JsValue> {
+        self.inner
+            .with_history("merge_columns", |csv| {
+                csv.merge_column_values(&indices, separator)
+            })
+            .map_err(JsValue::from)
+    }
+
+    /// Appends a `<header>_domain` column with the lowercase domain of every valid address of
+    /// an email column, empty for other values, returning its index
+    #[wasm_bindgen]
+    pub fn derive_email_domain(&mut self, column: usize) -> Result<usize, JsValue> {
+        self.inner
+            .with_history("derive_email_domain", |csv| csv.derive_email_domain(column))
+            .map_err(JsValue::from)
+    }
+
+    /// Appends a `<header>_e164` column with every number of a phone column in E.164 form,
+    /// e.g. `+14155550123`, empty where a value can't be normalized, returning its index.
+    /// Numbers without a country code take `default_code`, such as `"+44"`, when given.
+    #[wasm_bindgen]
+    pub fn derive_e164(
+        &mut self,
+        column: usize,
+        default_code: Option<String>,
+    ) -> Result<usize, JsValue> {
+        self.inner
+            .with_history("derive_e164", |csv| {
+                csv.derive_e164(column, default_code.as_deref())
+            })
+            .map_err(JsValue::from)
+    }
+}
@@ -0,0 +1,891 @@
+use js_sys::{Function, Promise, Reflect};
+use serde_wasm_bindgen::{from_value, to_value};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use super::serialization_error;
+use crate::config::AnalysisConfig;
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::table::dialect::{Dialect, ParseOptions};
+use crate::table::fixed_width::FixedWidthLayout;
+use crate::types::DataType;
+
+// Time `analyze` spends on columns before yielding, short enough to keep a frame
+const ANALYZE_SLICE_MS: f64 = 10.0;
+
+// Resolves in a new task queued with setTimeout, so the page can render and handle input
+// in between; hosts without timers continue in a microtask
+async fn yield_to_event_loop() {
+    let set_timeout = Reflect::get(&js_sys::global(), &"setTimeout".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok());
+    let promise = Promise::new(&mut |resolve, _| match &set_timeout {
+        Some(set_timeout) => {
+            let _ = set_timeout.call1(&JsValue::NULL, &resolve);
+        }
+        None => {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// JS handle to a CSV, exported as `CSV`
+#[wasm_bindgen(js_name = CSV)]
+#[derive(Debug)]
+pub struct JsCsv {
+    pub(super) inner: CSV,
+}
+
+impl From<CSV> for JsCsv {
+    fn from(inner: CSV) -> Self {
+        JsCsv { inner }
+    }
+}
+
+impl JsCsv {
+    /// The wrapped CSV, for Rust code sharing a table with JS
+    pub fn inner(&self) -> &CSV {
+        &self.inner
+    }
+
+    /// Unwraps the handle, e.g. to keep working with the table natively
+    pub fn into_inner(self) -> CSV {
+        self.inner
+    }
+}
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    // Constructor that creates a CSV from a string
+    #[wasm_bindgen(constructor)]
+    pub fn new(raw_data: String) -> Result<JsCsv, JsValue> {
+        CSV::from_string(raw_data)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Creates a CSV from UTF-8 bytes, e.g. a Node `Buffer` or a `Uint8Array` from `fetch`
+    #[wasm_bindgen]
+    pub fn from_bytes(bytes: &[u8]) -> Result<JsCsv, JsValue> {
+        CSV::from_bytes(bytes)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Parses text in the given dialect instead of the detected one; omitted fields keep
+    /// the comma separated defaults
+    #[wasm_bindgen]
+    pub fn from_string_with_dialect(
+        raw_data: String,
+        #[wasm_bindgen(unchecked_param_type = "Dialect")] dialect: JsValue,
+    ) -> Result<JsCsv, JsValue> {
+        let dialect: Dialect = from_value(dialect)
+            .map_err(|e| AnalysisError::invalid_argument(format!("Invalid dialect: {}", e)))?;
+        CSV::from_string_with_dialect(raw_data, dialect)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Parses text after dropping banner, comment and footer lines, in the given dialect or
+    /// the detected one
+    #[wasm_bindgen]
+    pub fn from_string_with_options(
+        raw_data: String,
+        #[wasm_bindgen(unchecked_param_type = "ParseOptions")] options: JsValue,
+    ) -> Result<JsCsv, JsValue> {
+        let options: ParseOptions = from_value(options).map_err(|e| {
+            AnalysisError::invalid_argument(format!("Invalid parse options: {}", e))
+        })?;
+        CSV::from_string_with_options(raw_data, &options)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Reads a fixed-width file laid out by `layout`, or by the layout `detect_fixed_width`
+    /// finds when it is omitted
+    #[wasm_bindgen]
+    pub fn from_fixed_width(
+        text: &str,
+        #[wasm_bindgen(unchecked_param_type = "FixedWidthLayout | undefined")] layout: JsValue,
+    ) -> Result<JsCsv, JsValue> {
+        let layout = match layout.is_undefined() || layout.is_null() {
+            true => FixedWidthLayout::detect(text),
+            false => from_value(layout)
+                .map_err(|e| AnalysisError::invalid_argument(format!("Invalid layout: {}", e)))?,
+        };
+        CSV::from_fixed_width(text, &layout)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Column positions and header line found in a fixed-width file, to show and adjust
+    /// before `from_fixed_width`
+    #[wasm_bindgen(unchecked_return_type = "FixedWidthLayout")]
+    pub fn detect_fixed_width(text: &str) -> Result<JsValue, JsValue> {
+        to_value(&FixedWidthLayout::detect(text))
+            .map_err(|e| serialization_error("fixed-width layout", e))
+    }
+
+    /// Delimiter, quote, header row and decimal separator the text was read with
+    #[wasm_bindgen(unchecked_return_type = "Dialect")]
+    pub fn dialect(&self) -> Result<JsValue, JsValue> {
+        to_value(self.inner.dialect()).map_err(|e| serialization_error("dialect", e))
+    }
+
+    // Get the number of rows in the CSV
+    #[wasm_bindgen]
+    pub fn row_count(&self) -> usize {
+        self.inner.row_count()
+    }
+
+    // Get the number of columns in the CSV
+    #[wasm_bindgen]
+    pub fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    // Get the headers of the CSV
+    #[wasm_bindgen(unchecked_return_type = "string[]")]
+    pub fn headers(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.headers()).map_err(|e| serialization_error("headers", e))
+    }
+
+    // Returns every cleaning operation applied to this CSV, with the cells it changed
+    #[wasm_bindgen(unchecked_return_type = "CleaningStep[]")]
+    pub fn cleaning_log(&self) -> Result<JsValue, JsValue> {
+        to_value(self.inner.cleaning_steps()).map_err(|e| serialization_error("cleaning log", e))
+    }
+
+    // Replaces the analysis options; takes effect on the next type inference
+    #[wasm_bindgen]
+    pub fn set_config(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "AnalysisConfig")] js_config: JsValue,
+    ) -> Result<(), JsValue> {
+        let config: AnalysisConfig =
+            from_value(js_config).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to deserialize config: {}", e),
+            })?;
+        self.inner.apply_config(config).map_err(JsValue::from)
+    }
+
+    // Returns the analysis options currently in effect
+    #[wasm_bindgen(unchecked_return_type = "AnalysisConfig")]
+    pub fn config(&self) -> Result<JsValue, JsValue> {
+        to_value(self.inner.analysis_config()).map_err(|e| serialization_error("config", e))
+    }
+
+    #[wasm_bindgen]
+    pub fn infer_column_types(&mut self) {
+        self.inner.infer_column_types();
+    }
+
+    /// Detects types one column at a time until `budget_ms` has passed, for hosts without
+    /// workers: call it again after yielding to the event loop until the result is `done`.
+    /// `on_progress` is called with the progress after each column.
+    #[wasm_bindgen(unchecked_return_type = "InferenceProgress")]
+    pub fn infer_column_types_slice(
+        &mut self,
+        budget_ms: f64,
+        #[wasm_bindgen(
+            unchecked_param_type = "((progress: InferenceProgress) => void) | undefined"
+        )]
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<JsValue, JsValue> {
+        let progress = self.inner.infer_column_types_slice(budget_ms, |progress| {
+            if let (Some(callback), Ok(progress)) = (&on_progress, to_value(progress)) {
+                // A throwing callback shouldn't stop the analysis
+                let _ = callback.call1(&JsValue::NULL, &progress);
+            }
+        });
+        to_value(&progress).map_err(|e| serialization_error("progress", e))
+    }
+
+    /// Detects types like `infer_column_types` without blocking the thread it runs on:
+    /// columns are analyzed in slices of `budget_ms` (10 ms by default), yielding to the
+    /// event loop between them. `on_progress` is called after each column. Resolves to the
+    /// run's metrics once every column is done; other methods of the CSV throw until then.
+    /// Once `signal` is aborted it rejects with a `cancelled` error at the next slice.
+    #[wasm_bindgen(unchecked_return_type = "Promise<AnalysisMetrics>")]
+    pub async fn analyze(
+        &mut self,
+        budget_ms: Option<f64>,
+        #[wasm_bindgen(
+            unchecked_param_type = "((progress: InferenceProgress) => void) | undefined"
+        )]
+        on_progress: Option<Function>,
+        #[wasm_bindgen(unchecked_param_type = "AbortSignal | undefined")] signal: Option<JsValue>,
+    ) -> Result<JsValue, JsValue> {
+        let budget_ms = budget_ms.unwrap_or(ANALYZE_SLICE_MS);
+        let aborted = || {
+            signal.as_ref().is_some_and(|signal| {
+                Reflect::get(signal, &"aborted".into()).is_ok_and(|aborted| aborted.is_truthy())
+            })
+        };
+        loop {
+            if aborted() {
+                self.inner.cancel_sliced_inference();
+                return Err(AnalysisError::Cancelled.into());
+            }
+            let progress = self.inner.infer_column_types_slice(budget_ms, |progress| {
+                if let (Some(callback), Ok(progress)) = (&on_progress, to_value(progress)) {
+                    let _ = callback.call1(&JsValue::NULL, &progress);
+                }
+            });
+            if progress.done {
+                break;
+            }
+            yield_to_event_loop().await;
+        }
+        to_value(&self.inner.last_analysis_metrics())
+            .map_err(|e| serialization_error("analysis metrics", e))
+    }
+
+    /// Appends rows of headerless CSV text and updates the column metadata incrementally,
+    /// inferring a column again only when the new rows could change its type. The append
+    /// can be undone like any other operation.
+    #[wasm_bindgen(unchecked_return_type = "AppendReport")]
+    pub fn append_rows(&mut self, csv_chunk: &str) -> Result<JsValue, JsValue> {
+        let report = self
+            .inner
+            .with_history("append_rows", |csv| csv.append_rows(csv_chunk))?;
+        to_value(&report).map_err(|e| serialization_error("append report", e))
+    }
+
+    /// Time per phase, estimated peak memory, throughput and worker use of the last
+    /// `infer_column_types`, or undefined before the first one
+    #[wasm_bindgen(unchecked_return_type = "AnalysisMetrics | undefined")]
+    pub fn last_analysis_metrics(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.last_analysis_metrics())
+            .map_err(|e| serialization_error("analysis metrics", e))
+    }
+
+    /// Pins a column to the given type, recomputing its statistics and SQL type under it.
+    /// Later calls to `infer_column_types` keep the chosen type.
+    #[wasm_bindgen(unchecked_return_type = "ColumnMetadata")]
+    pub fn override_column_type(
+        &mut self,
+        index: usize,
+        data_type: DataType,
+    ) -> Result<JsValue, JsValue> {
+        let metadata = self.inner.with_history("override_column_type", |csv| {
+            csv.override_type(index, data_type)
+        })?;
+        to_value(&metadata).map_err(|e| serialization_error("metadata", e))
+    }
+
+    /// Sets metadata for a specific column
+    #[wasm_bindgen]
+    pub fn set_column_metadata(
+        &mut self,
+        index: usize,
+        #[wasm_bindgen(unchecked_param_type = "ColumnMetadata")] js_metadata: JsValue,
+    ) -> Result<(), JsValue> {
+        let metadata: ColumnMetadata =
+            from_value(js_metadata).map_err(|e| AnalysisError::InvalidMetadata {
+                message: format!("Failed to deserialize metadata: {}", e),
+            })?;
+
+        self.inner
+            .with_history("set_column_metadata", |csv| {
+                csv.replace_column_metadata(index, metadata)
+            })
+            .map_err(JsValue::from)
+    }
+
+    /// Retrieves metadata for a specific column
+    #[wasm_bindgen(unchecked_return_type = "ColumnMetadata")]
+    pub fn get_column_metadata(&self, index: usize) -> Result<JsValue, JsValue> {
+        let metadata = self
+            .inner
+            .column_metadata(index)
+            .ok_or_else(|| AnalysisError::invalid_column(index, "No metadata found for column"))?;
+
+        to_value(metadata).map_err(|e| serialization_error("metadata", e))
+    }
+
+    /// Retrieves a summary of the CSV structure and types
+    #[wasm_bindgen(unchecked_return_type = "ColumnSummary[]")]
+    pub fn get_structure_summary(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.structure_summary()).map_err(|e| serialization_error("summary", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    // Numeric type detection tests
+    #[wasm_bindgen_test]
+    fn test_numeric_detection() {
+        // Test integer detection
+        let data = "numbers\n123\n456\n789\n1,234\n-5,678";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Integer);
+        assert!(metadata.confidence > 0.9);
+
+        // Test decimal detection
+        let data = "decimals\n123.45\n456.78\n789.01\n1,234.56\n-5,678.90";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Decimal);
+        assert!(metadata.confidence > 0.9);
+    }
+
+    // Currency detection tests
+    #[wasm_bindgen_test]
+    fn test_currency_detection() {
+        let data = "amounts\n$1,234.56\n$2,345.67\n$3,456.78\nUSD 4,567.89\n$-1,234.56";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Currency);
+        assert!(metadata.confidence > 0.9);
+
+        // Test with some missing currency symbols
+        let data = "amounts\n$1,234.56\n2,345.67\n$3,456.78\n4,567.89";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        // Should still detect as currency if pattern is consistent enough
+        assert_eq!(metadata.data_type, DataType::Currency);
+    }
+
+    // Date format detection tests
+    #[wasm_bindgen_test]
+    fn test_date_detection() {
+        // Test ISO format dates
+        let data = "dates\n2024-01-01\n2024-02-15\n2024-03-30";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Date);
+        assert!(metadata.confidence > 0.9);
+
+        // Test mixed date formats
+        let data = "dates\n2024-01-01\n01/15/2024\n2024/01/30\n2024-02-01";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Date);
+        // Confidence might be lower with mixed formats but should still be reasonable
+        assert!(metadata.confidence > 0.7);
+    }
+
+    // Email format detection tests
+    #[wasm_bindgen_test]
+    fn test_email_detection() {
+        let data =
+            "emails\nuser@example.com\nname.surname@domain.co.uk\ntest123@subdomain.site.com";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Email);
+        assert!(metadata.confidence > 0.9);
+
+        // Test with some invalid emails mixed in
+        let data = "emails\nuser@example.com\ninvalid.email\ntest@domain.com";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        // Should fall back to Text if too many invalid emails
+        assert!(matches!(
+            metadata.data_type,
+            DataType::Email | DataType::Text
+        ));
+    }
+
+    // Phone number detection tests
+    #[wasm_bindgen_test]
+    fn test_phone_detection() {
+        let data = "phones\n(123) 456-7890\n123-456-7890\n1234567890\n+1-123-456-7890";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Phone);
+        assert!(metadata.confidence > 0.8);
+
+        // Test international formats
+        let data = "phones\n+44 20 7123 4567\n+1 (123) 456-7890\n+61 2 8123 4567";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Phone);
+    }
+
+    // Categorical data detection tests
+    #[wasm_bindgen_test]
+    fn test_categorical_detection() {
+        // Test obvious categorical data
+        let data = "status\nactive\npending\nactive\npending\nactive\ncompleted";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Categorical);
+
+        // Test with larger number of categories but still categorical
+        let mut data = String::from("priority\n");
+        for _ in 0..100 {
+            data.push_str("High\nMedium\nLow\nCritical\n");
+        }
+
+        let mut csv = JsCsv::new(data).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Categorical);
+    }
+
+    // Multiple column type detection tests
+    #[wasm_bindgen_test]
+    fn test_multiple_columns() {
+        let data = "id,name,email,status,amount\n\
+                   1,John Smith,john@test.com,active,$1,234.56\n\
+                   2,Jane Doe,jane@test.com,pending,$2,345.67\n\
+                   3,Bob Wilson,bob@test.com,completed,$3,456.78";
+
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        // Check each column's type
+        let id_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(id_meta.data_type, DataType::Integer);
+
+        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
+        assert_eq!(name_meta.data_type, DataType::Text);
+
+        let email_meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
+        assert_eq!(email_meta.data_type, DataType::Email);
+
+        let status_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
+        assert_eq!(status_meta.data_type, DataType::Categorical);
+
+        let amount_meta: ColumnMetadata = from_value(csv.get_column_metadata(4).unwrap()).unwrap();
+        assert_eq!(amount_meta.data_type, DataType::Currency);
+    }
+
+    // Data quality and edge case tests
+    #[wasm_bindgen_test]
+    fn test_data_quality_handling() {
+        // Test handling of missing values
+        let data = "values\n123\n\n456\n\t\n789\n  \n";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(
+            metadata.data_type,
+            DataType::Integer,
+            "Should handle empty/whitespace values"
+        );
+
+        // Test handling of quoted values
+        let data = "text,\"header,with,comma\"\n\
+                   value1,\"value,with,commas\"\n\
+                   value2,\"another,quoted,value\"";
+        let csv = JsCsv::new(data.to_string());
+        assert!(csv.is_ok(), "Should handle quoted values with commas");
+    }
+
+    // Unicode and special character handling tests
+    #[wasm_bindgen_test]
+    fn test_special_characters() {
+        // Test Unicode in text fields
+        let data = "description\n🌟 Special offer!\n⭐ Featured item\n❤️ Popular choice";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Text);
+
+        // Test special characters in categorical data
+        let data = "status\n★ Gold\n★ Gold\n☆ Silver\n★ Gold\n☆ Silver";
+        let mut csv = JsCsv::new(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(metadata.data_type, DataType::Categorical);
+    }
+
+    // Error handling tests
+    #[wasm_bindgen_test]
+    fn test_error_handling() {
+        // Test invalid column index
+        let data = "header\nvalue";
+        let csv = JsCsv::new(data.to_string()).unwrap();
+        assert!(csv.get_column_metadata(999).is_err());
+
+        // Test completely empty CSV
+        let data = "";
+        assert!(JsCsv::new(data.to_string()).is_err());
+
+        // Test headers only
+        let data = "header1,header2";
+        let csv = JsCsv::new(data.to_string()).unwrap();
+        assert_eq!(csv.row_count(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors_carry_code() {
+        let error = JsCsv::new("a,b\n1,2\n3,4,5".to_string()).unwrap_err();
+        let code = js_sys::Reflect::get(&error, &"code".into()).unwrap();
+        assert_eq!(code.as_string().as_deref(), Some("parse_error"));
+        let row = js_sys::Reflect::get(&error, &"row".into()).unwrap();
+        assert_eq!(row.as_f64(), Some(1.0));
+        assert!(error.is_instance_of::<js_sys::Error>());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_from_bytes() {
+        let csv = JsCsv::from_bytes(b"\xEF\xBB\xBFid,name\n1,Ada\n2,Alan").unwrap();
+        assert_eq!(csv.row_count(), 2);
+        assert_eq!(csv.inner().headers(), vec!["id", "name"]);
+    }
+}
+
+// Reads the sample files through the test page, so these only run in a browser
+#[cfg(all(test, feature = "browser"))]
+mod example_csv_file_wasm_tests {
+    use super::*;
+    use crate::csv::ColumnMetadata;
+    use crate::types::DataType;
+    use js_sys::{Object, Reflect, Uint8Array};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::*;
+    use web_sys::Window;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn read_csv_file(filename: &str) -> Result<String, JsError> {
+        // Get the window object
+        let window = web_sys::window().ok_or_else(|| JsError::new("No window object found"))?;
+
+        // Access the fs object through the window's custom property
+        let fs = Reflect::get(&window, &JsValue::from_str("fs"))
+            .map_err(|_| JsError::new("No fs object found on window"))?;
+
+        // Call readFile on the fs object
+        let read_file = Reflect::get(&fs, &JsValue::from_str("readFile"))
+            .map_err(|_| JsError::new("No readFile method found on fs"))?;
+
+        // Create options object for UTF-8 encoding
+        let options = Object::new();
+        Reflect::set(
+            &options,
+            &JsValue::from_str("encoding"),
+            &JsValue::from_str("utf8"),
+        )
+        .map_err(|_| JsError::new("Failed to set encoding option"))?;
+
+        // Convert readFile to a JavaScript function and call it
+        let read_file_func = js_sys::Function::from(read_file);
+        let promise = read_file_func
+            .call2(&fs, &JsValue::from_str(filename), &options)
+            .map_err(|_| JsError::new("Failed to call readFile"))?;
+
+        // Convert to Promise, handling the conversion error explicitly
+        let promise = promise
+            .dyn_into::<js_sys::Promise>()
+            .map_err(|_| JsError::new("Failed to convert to Promise"))?;
+
+        // Wait for the promise to resolve
+        let content = JsFuture::from(promise)
+            .await
+            .map_err(|e| JsError::new(&format!("Failed to read file: {:?}", e)))?;
+
+        // Convert the content to a string
+        let content_str = content
+            .as_string()
+            .ok_or_else(|| JsError::new("Failed to convert file content to string"))?;
+
+        Ok(content_str)
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_pokemon_csv_types() {
+        // Read the actual pokemon.csv file
+        let csv_content = read_csv_file("pokemon.csv").await.unwrap();
+        let mut csv = JsCsv::new(csv_content).unwrap();
+        csv.infer_column_types();
+
+        // Test #/Number column (should be Integer)
+        let number_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        assert_eq!(number_meta.data_type, DataType::Integer);
+        assert!(
+            number_meta.confidence > 0.9,
+            "Should have high confidence for Pokemon numbers"
+        );
+
+        // Test Name column (should be Text)
+        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
+        assert_eq!(name_meta.data_type, DataType::Text);
+
+        // Test Type 1 column (should be Categorical)
+        let type1_meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
+        assert_eq!(type1_meta.data_type, DataType::Categorical);
+        assert!(
+            type1_meta.confidence > 0.8,
+            "Should have high confidence for Pokemon types"
+        );
+
+        // Test Type 2 column (should be Categorical)
+        let type2_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
+        assert_eq!(type2_meta.data_type, DataType::Categorical);
+
+        // Test Total column (should be Integer)
+        let total_meta: ColumnMetadata = from_value(csv.get_column_metadata(4).unwrap()).unwrap();
+        assert_eq!(total_meta.data_type, DataType::Integer);
+        assert!(
+            total_meta.confidence > 0.9,
+            "Should have high confidence for total stats"
+        );
+
+        // Test stat columns (should all be Integer)
+        let stat_columns = ["HP", "Attack", "Defense", "Sp. Atk", "Sp. Def", "Speed"];
+        for (i, &name) in stat_columns.iter().enumerate() {
+            let stat_meta: ColumnMetadata =
+                from_value(csv.get_column_metadata(i + 5).unwrap()).unwrap();
+            assert_eq!(
+                stat_meta.data_type,
+                DataType::Integer,
+                "Stat column {} ({}) should be Integer",
+                i + 5,
+                name
+            );
+            assert!(
+                stat_meta.confidence > 0.9,
+                "Should have high confidence for {} stats",
+                name
+            );
+        }
+
+        // Test Generation column (should be Integer)
+        let gen_meta: ColumnMetadata = from_value(csv.get_column_metadata(11).unwrap()).unwrap();
+        assert_eq!(gen_meta.data_type, DataType::Integer);
+        assert!(
+            gen_meta.confidence > 0.9,
+            "Should have high confidence for generation"
+        );
+
+        // Test Legendary column (should be Categorical)
+        let legendary_meta: ColumnMetadata =
+            from_value(csv.get_column_metadata(12).unwrap()).unwrap();
+        assert_eq!(legendary_meta.data_type, DataType::Categorical);
+        assert!(
+            legendary_meta.confidence > 0.9,
+            "Should have high confidence for legendary status"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_starters_csv_types() {
+        // Read the actual starters.csv file
+        let csv_content = read_csv_file("starters.csv").await.unwrap();
+        let mut csv = JsCsv::new(csv_content).unwrap();
+        csv.infer_column_types();
+
+        // Define column groups with their expected types
+        let integer_columns = [
+            ("attack", 19),
+            ("base_egg_steps", 20),
+            ("base_happiness", 21),
+            ("base_total", 22),
+            ("capture_rate", 23),
+            ("defense", 25),
+            ("experience_growth", 26),
+            ("hp", 28),
+            ("pokedex_number", 32),
+            ("sp_attack", 33),
+            ("sp_defense", 34),
+            ("speed", 35),
+            ("generation", 39),
+            ("is_legendary", 40),
+        ];
+
+        let decimal_columns = [
+            ("against_bug", 1),
+            ("against_electric", 4),
+            ("against_fairy", 5),
+            ("against_fight", 6),
+            ("against_fire", 7),
+            ("against_flying", 8),
+            ("against_ghost", 9),
+            ("against_grass", 10),
+            ("against_ground", 11),
+            ("against_ice", 12),
+            ("against_normal", 13),
+            ("against_poison", 14),
+            ("against_psychic", 15),
+            ("against_rock", 16),
+            ("against_steel", 17),
+            ("against_water", 18),
+            ("height_m", 27),
+            ("percentage_male", 31),
+            ("weight_kg", 38),
+        ];
+
+        let categorical_columns = [
+            ("abilities", 0),
+            ("classfication", 24),
+            ("type1", 36),
+            ("type2", 37),
+        ];
+
+        let text_columns = [("japanese_name", 29), ("name", 30)];
+
+        // Test integer columns
+        for (name, idx) in integer_columns.iter() {
+            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
+            assert_eq!(
+                meta.data_type,
+                DataType::Integer,
+                "Column {} ({}) should be Integer",
+                idx,
+                name
+            );
+            assert!(
+                meta.confidence > 0.8,
+                "Should have high confidence for integer column {} ({})",
+                idx,
+                name
+            );
+        }
+
+        // Test decimal columns
+        for (name, idx) in decimal_columns.iter() {
+            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
+            assert_eq!(
+                meta.data_type,
+                DataType::Decimal,
+                "Column {} ({}) should be Decimal",
+                idx,
+                name
+            );
+            assert!(
+                meta.confidence > 0.8,
+                "Should have high confidence for decimal column {} ({})",
+                idx,
+                name
+            );
+        }
+
+        // Test categorical columns
+        for (name, idx) in categorical_columns.iter() {
+            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
+            assert_eq!(
+                meta.data_type,
+                DataType::Categorical,
+                "Column {} ({}) should be Categorical",
+                idx,
+                name
+            );
+            assert!(
+                meta.confidence > 0.7,
+                "Should have reasonable confidence for categorical column {} ({})",
+                idx,
+                name
+            );
+        }
+
+        // Test text columns
+        for (name, idx) in text_columns.iter() {
+            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
+            assert_eq!(
+                meta.data_type,
+                DataType::Text,
+                "Column {} ({}) should be Text",
+                idx,
+                name
+            );
+        }
+
+        // Additional validation for column count
+        assert_eq!(
+            csv.column_count(),
+            41,
+            "Should have 41 columns in starters.csv"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_analyze_yields_until_done() {
+        let mut csv = JsCsv::new("id,price,joined\n1,$1.50,2024-01-01".to_string()).unwrap();
+        let calls = js_sys::Array::new();
+        let record = calls.clone();
+        let on_progress = Closure::<dyn Fn(JsValue)>::new(move |p: JsValue| {
+            record.push(&p);
+        });
+        // A zero budget yields after every column
+        let metrics = csv
+            .analyze(
+                Some(0.0),
+                Some(on_progress.as_ref().unchecked_ref::<Function>().clone()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(calls.length(), 3);
+        let columns = js_sys::Reflect::get(&metrics, &"columns".into()).unwrap();
+        assert_eq!(columns.as_f64(), Some(3.0));
+        let meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
+        assert_eq!(meta.data_type, DataType::Date);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_undo_append_rows() {
+        let mut csv = JsCsv::new("id\n1\n2".to_string()).unwrap();
+        csv.infer_column_types();
+        csv.append_rows("3\n4").unwrap();
+        assert_eq!(csv.inner.row_count(), 4);
+        assert_eq!(csv.undo().unwrap(), "append_rows");
+        assert_eq!(csv.inner.row_count(), 2);
+        assert_eq!(csv.redo().unwrap(), "append_rows");
+        assert_eq!(csv.inner.row_count(), 4);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_analyze_rejects_when_aborted() {
+        let mut csv = JsCsv::new("id,price\n1,$1.50".to_string()).unwrap();
+        // Any object with a truthy `aborted`, like an aborted AbortSignal
+        let signal = js_sys::Object::new();
+        js_sys::Reflect::set(&signal, &"aborted".into(), &JsValue::TRUE).unwrap();
+        let error = csv
+            .analyze(Some(0.0), None, Some(signal.into()))
+            .await
+            .unwrap_err();
+        let code = js_sys::Reflect::get(&error, &"code".into()).unwrap();
+        assert_eq!(code.as_string().as_deref(), Some("cancelled"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_file_not_found() {
+        let result = read_csv_file("nonexistent.csv").await;
+        assert!(result.is_err(), "Should error on nonexistent file");
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_empty_csv() {
+        let empty_csv = "\n\n\n";
+        let result = JsCsv::new(empty_csv.to_string());
+        assert!(result.is_err(), "Should error on empty CSV");
+    }
+}
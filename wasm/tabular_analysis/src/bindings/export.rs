@@ -0,0 +1,134 @@
+use serde::Serialize;
+use serde_wasm_bindgen::{from_value, Serializer};
+use wasm_bindgen::prelude::*;
+
+use super::csv::JsCsv;
+use super::serialization_error;
+use crate::error::AnalysisError;
+use crate::export::loader::LoaderTarget;
+use crate::export::scaling::ScaleMethod;
+use crate::export::sql::{SqlDialect, SqlOptions};
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// CREATE TABLE and INSERT statements for DuckDB matched to the detected types, with
+    /// the typed values of each column for duckdb-wasm's `insertArrowTable`
+    #[wasm_bindgen(unchecked_return_type = "DuckDbStatements")]
+    pub fn to_duckdb_statements(&self, table_name: &str) -> Result<JsValue, JsValue> {
+        let statements = self.inner.to_duckdb_statements(table_name)?;
+        statements
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("DuckDB statements", e))
+    }
+
+    /// CREATE TABLE statement for a SQL dialect, Postgres unless the options say otherwise,
+    /// with column types matched to the detected ones and reserved words quoted or renamed
+    #[wasm_bindgen(unchecked_return_type = "SqlSchema")]
+    pub fn to_sql_schema(
+        &self,
+        table_name: &str,
+        #[wasm_bindgen(unchecked_param_type = "SqlOptions | undefined")] options: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let options = sql_options(options)?;
+        let schema = self.inner.to_sql_schema(table_name, &options)?;
+        schema
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("SQL schema", e))
+    }
+
+    /// Script creating the table of `to_sql_schema` and bulk-loading the file with the
+    /// dialect's loader, given the file's delimiter, missing-value tokens and date formats
+    #[wasm_bindgen]
+    pub fn to_sql_load_script(
+        &self,
+        table_name: &str,
+        #[wasm_bindgen(unchecked_param_type = "SqlOptions | undefined")] options: JsValue,
+    ) -> Result<String, JsValue> {
+        let options = sql_options(options)?;
+        self.inner
+            .to_sql_load_script(table_name, &options)
+            .map_err(JsValue::from)
+    }
+
+    /// Values that would fail to load, or load changed, under the column types of
+    /// `to_sql_schema` for a dialect, Postgres unless given: integers out of range, numbers
+    /// with too many digits, text too long for its VARCHAR and invalid numbers and dates
+    #[wasm_bindgen(unchecked_return_type = "CoercionPreview")]
+    pub fn preview_coercions(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "SqlDialect | undefined")] dialect: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let dialect = match dialect {
+            Some(name) => SqlDialect::parse(&name)?,
+            None => SqlDialect::default(),
+        };
+        let preview = self.inner.preview_coercions(dialect)?;
+        preview
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("coercion preview", e))
+    }
+
+    /// JSON Schema (draft 2020-12) of the rows returned by `rows`, with each column's type,
+    /// format, categorical levels and numeric range
+    #[wasm_bindgen(unchecked_return_type = "Record<string, unknown>")]
+    pub fn to_json_schema(&self) -> Result<JsValue, JsValue> {
+        let schema = self.inner.to_json_schema()?;
+        schema
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("JSON schema", e))
+    }
+
+    /// Integer codes of an ordinal categorical column in the order of its levels, e.g. 0, 1, 2
+    /// for low, medium, high, with null for empty cells
+    #[wasm_bindgen(unchecked_return_type = "OrdinalEncoding")]
+    pub fn ordinal_encoding(&self, column: usize) -> Result<JsValue, JsValue> {
+        let encoding = self.inner.ordinal_encoding(column)?;
+        encoding
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("ordinal encoding", e))
+    }
+
+    /// Numeric columns scaled by `"standardize"`, `"min_max"` or `"robust"` with their analyzed
+    /// statistics, with each column's center and scale for undoing it as
+    /// `value * scale + center`
+    #[wasm_bindgen(unchecked_return_type = "ScaledColumns")]
+    pub fn scale(&self, columns: Vec<usize>, method: &str) -> Result<JsValue, JsValue> {
+        let scaled =
+            ScaleMethod::parse(method).and_then(|method| self.inner.scale(&columns, method))?;
+        scaled
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("scaled columns", e))
+    }
+
+    /// The values of `scale` as a row-major matrix, one row per CSV row and one entry per
+    /// column, with NaN for missing values
+    #[wasm_bindgen]
+    pub fn scaled_matrix(&self, columns: Vec<usize>, method: &str) -> Result<Vec<f64>, JsValue> {
+        let scaled =
+            ScaleMethod::parse(method).and_then(|method| self.inner.scale(&columns, method))?;
+        Ok(scaled
+            .matrix()
+            .into_iter()
+            .flatten()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect())
+    }
+
+    /// Code loading the file with the detected types in `"pandas"` or `"readr"`: dtypes or
+    /// column specifications, date formats and the missing-value tokens found in the data
+    #[wasm_bindgen]
+    pub fn generate_loader_code(&self, target: &str) -> Result<String, JsValue> {
+        LoaderTarget::parse(target)
+            .and_then(|target| self.inner.generate_loader_code(target))
+            .map_err(JsValue::from)
+    }
+}
+
+// Options given to the SQL exports, the defaults when left out
+fn sql_options(options: JsValue) -> Result<SqlOptions, AnalysisError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(SqlOptions::default());
+    }
+    from_value(options)
+        .map_err(|e| AnalysisError::invalid_argument(format!("Invalid SQL options: {}", e)))
+}
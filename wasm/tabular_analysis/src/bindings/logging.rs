@@ -0,0 +1,39 @@
+use js_sys::Function;
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::prelude::*;
+
+use crate::logging::{self, LogFilter};
+
+/// Sends the engine's log messages to `callback` as `{ level, target, message }` records.
+/// `filter` holds directives like `"info,tabular_analysis::inference=debug"`; by default only
+/// warnings and errors are sent.
+#[wasm_bindgen]
+pub fn set_log_sink(
+    #[wasm_bindgen(unchecked_param_type = "(record: LogRecord) => void")] callback: Function,
+    filter: Option<String>,
+) -> Result<(), JsValue> {
+    let filter = LogFilter::parse(filter.as_deref().unwrap_or_default())?;
+    logging::set_log_sink(
+        move |record| {
+            if let Ok(record) = to_value(record) {
+                // A throwing sink shouldn't fail the operation that logged
+                let _ = callback.call1(&JsValue::NULL, &record);
+            }
+        },
+        filter,
+    );
+    Ok(())
+}
+
+/// Changes which messages reach the sink set with `set_log_sink`
+#[wasm_bindgen]
+pub fn set_log_filter(filter: &str) -> Result<(), JsValue> {
+    logging::set_log_filter(LogFilter::parse(filter)?);
+    Ok(())
+}
+
+/// Stops sending log messages to the sink
+#[wasm_bindgen]
+pub fn clear_log_sink() {
+    logging::clear_log_sink();
+}
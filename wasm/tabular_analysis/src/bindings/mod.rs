@@ -0,0 +1,71 @@
+//! wasm-bindgen layer over the analysis engine: JS classes wrapping the engine types,
+//! converting arguments and results with serde-wasm-bindgen. Failures are thrown as JS `Error`s
+//! carrying the `AnalysisError` fields, so callers can branch on `error.code`.
+//!
+//! Every method runs synchronously on the calling thread and touches no browser APIs, so the
+//! same package works in a page, a web worker or Node (`wasm-pack build --target nodejs`).
+//! Browser-only pieces sit behind the `browser` feature.
+
+mod analysis;
+mod cleaning;
+mod csv;
+mod export;
+mod logging;
+mod remote;
+mod spill;
+mod table;
+mod typescript;
+mod workers;
+
+use std::fmt::Display;
+
+use js_sys::Object;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::config::AnalysisConfig;
+use crate::error::AnalysisError;
+
+pub use self::analysis::analyze_files;
+pub use self::csv::JsCsv;
+pub use self::logging::{clear_log_sink, set_log_filter, set_log_sink};
+pub use self::spill::JsSpilledAnalysis;
+pub use self::table::JsCsvView;
+#[cfg(feature = "browser")]
+pub use self::workers::worker_bundle;
+pub use self::workers::{analyze_packed_column, workers_available, JsColumnQueue};
+
+impl From<AnalysisError> for JsValue {
+    /// A JS `Error` with the error's message, `code` and the fields of its kind, e.g.
+    /// `{ code: "parse_error", row: 3, column: 1 }`
+    fn from(error: AnalysisError) -> JsValue {
+        let js_error = js_sys::Error::new("");
+        if let Ok(fields) = serde_wasm_bindgen::to_value(&error) {
+            Object::assign(&js_error, fields.unchecked_ref());
+        }
+        // Set after the fields, whose own `message` lacks the row or column prefix
+        js_error.set_message(&error.to_string());
+        js_error.set_name("AnalysisError");
+        js_error.into()
+    }
+}
+
+// Analysis options passed from JS, the defaults when undefined
+fn optional_config(config: JsValue) -> Result<AnalysisConfig, JsValue> {
+    if config.is_undefined() {
+        return Ok(AnalysisConfig::default());
+    }
+    serde_wasm_bindgen::from_value(config).map_err(|e| {
+        AnalysisError::ConfigError {
+            message: format!("Failed to deserialize config: {}", e),
+        }
+        .into()
+    })
+}
+
+// Error for a result that couldn't be converted to a JS value
+fn serialization_error(what: &str, error: impl Display) -> JsValue {
+    AnalysisError::SerializationError {
+        message: format!("Failed to serialize {}: {}", what, error),
+    }
+    .into()
+}
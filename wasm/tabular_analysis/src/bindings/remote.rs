@@ -0,0 +1,202 @@
+use js_sys::{Function, Object, Promise, Reflect, Uint8Array};
+use serde_wasm_bindgen::{from_value, to_value};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use super::csv::JsCsv;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::remote::{content_range_total, sample_ranges, FetchOptions, FetchProgress};
+
+// HTTP status of a partial response to a range request
+const PARTIAL_CONTENT: u16 = 206;
+
+fn fetch_error(status: Option<u16>, message: impl Into<String>) -> AnalysisError {
+    AnalysisError::FetchError {
+        status,
+        message: message.into(),
+    }
+}
+
+// Message of a rejected promise or thrown exception
+fn describe(error: JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map_or_else(|| format!("{:?}", error), |e| String::from(e.message()))
+}
+
+// Calls a method of a JS object and awaits the promise it returns
+async fn call_async(target: &JsValue, method: &str, args: &[JsValue]) -> Result<JsValue, String> {
+    let function: Function = Reflect::get(target, &method.into())
+        .map_err(describe)?
+        .dyn_into()
+        .map_err(|_| format!("{} is not available", method))?;
+    let result = function
+        .apply(target, &args.iter().collect())
+        .map_err(describe)?;
+    JsFuture::from(Promise::resolve(&result))
+        .await
+        .map_err(describe)
+}
+
+// Download state reported to the progress callback after every chunk
+struct Progress {
+    loaded: u64,
+    total: Option<u64>,
+    callback: Option<Function>,
+}
+
+impl Progress {
+    fn add(&mut self, bytes: u64) {
+        self.loaded += bytes;
+        let Some(callback) = &self.callback else {
+            return;
+        };
+        let progress = FetchProgress {
+            loaded: self.loaded,
+            total: self.total,
+        };
+        if let Ok(progress) = to_value(&progress) {
+            let _ = callback.call1(&JsValue::NULL, &progress);
+        }
+    }
+}
+
+// Starts a GET, of the given inclusive byte range when set, failing on error statuses
+async fn fetch(url: &str, range: Option<(u64, u64)>) -> Result<JsValue, AnalysisError> {
+    let init = Object::new();
+    if let Some((start, end)) = range {
+        let headers = Object::new();
+        let value = format!("bytes={}-{}", start, end);
+        let _ = Reflect::set(&headers, &"Range".into(), &value.into());
+        let _ = Reflect::set(&init, &"headers".into(), &headers);
+    }
+    let response = call_async(&js_sys::global(), "fetch", &[url.into(), init.into()])
+        .await
+        .map_err(|e| fetch_error(None, format!("Failed to fetch {}: {}", url, e)))?;
+
+    let status = status(&response);
+    if !Reflect::get(&response, &"ok".into()).is_ok_and(|ok| ok.is_truthy()) {
+        return Err(fetch_error(
+            status,
+            format!(
+                "Fetching {} failed with status {}",
+                url,
+                status.unwrap_or(0)
+            ),
+        ));
+    }
+    Ok(response)
+}
+
+fn status(response: &JsValue) -> Option<u16> {
+    Reflect::get(response, &"status".into())
+        .ok()?
+        .as_f64()
+        .map(|status| status as u16)
+}
+
+fn header(response: &JsValue, name: &str) -> Option<String> {
+    let headers = Reflect::get(response, &"headers".into()).ok()?;
+    let get: Function = Reflect::get(&headers, &"get".into())
+        .ok()?
+        .dyn_into()
+        .ok()?;
+    get.call1(&headers, &name.into()).ok()?.as_string()
+}
+
+// Reads a response body chunk by chunk, stopping once `limit` bytes have arrived
+async fn read_body(
+    response: &JsValue,
+    limit: Option<u64>,
+    progress: &mut Progress,
+) -> Result<Vec<u8>, AnalysisError> {
+    let failed = |e: String| fetch_error(None, format!("Failed to read the response: {}", e));
+    let body = Reflect::get(response, &"body".into()).map_err(|e| failed(describe(e)))?;
+    let reader = call_async(&body, "getReader", &[]).await.map_err(failed)?;
+
+    let mut bytes = Vec::new();
+    loop {
+        let chunk = call_async(&reader, "read", &[]).await.map_err(failed)?;
+        if Reflect::get(&chunk, &"done".into()).is_ok_and(|done| done.is_truthy()) {
+            break;
+        }
+        let value: Uint8Array = Reflect::get(&chunk, &"value".into())
+            .map_err(|e| failed(describe(e)))?
+            .unchecked_into();
+        bytes.extend(value.to_vec());
+        progress.add(value.length() as u64);
+        if let Some(limit) = limit.filter(|&limit| bytes.len() as u64 >= limit) {
+            bytes.truncate(limit as usize);
+            let _ = call_async(&reader, "cancel", &[]).await;
+            break;
+        }
+    }
+    Ok(bytes)
+}
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// Downloads and parses a remote CSV, streaming the body and calling `on_progress` with
+    /// each `FetchProgress`. With `sample` set, only the start of the file and random byte
+    /// ranges of the rest are fetched, using range requests, for quick inference on giant
+    /// files; `warnings()` then says how much of the file the rows came from.
+    #[wasm_bindgen]
+    pub async fn from_url(
+        url: String,
+        #[wasm_bindgen(unchecked_param_type = "FetchOptions | undefined")] options: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "((progress: FetchProgress) => void) | undefined")]
+        on_progress: Option<Function>,
+    ) -> Result<JsCsv, JsValue> {
+        let options: FetchOptions = if options.is_undefined() {
+            FetchOptions::default()
+        } else {
+            from_value(options).map_err(|e| {
+                AnalysisError::invalid_argument(format!("Invalid fetch options: {}", e))
+            })?
+        };
+        options.validate()?;
+        let mut progress = Progress {
+            loaded: 0,
+            total: None,
+            callback: on_progress,
+        };
+
+        if !options.sample {
+            let response = fetch(&url, None).await?;
+            progress.total = header(&response, "content-length").and_then(|n| n.parse().ok());
+            let bytes = read_body(&response, None, &mut progress).await?;
+            return Ok(CSV::from_bytes(&bytes)?.into());
+        }
+
+        let response = fetch(&url, Some((0, options.head_bytes - 1))).await?;
+        let ranged = status(&response) == Some(PARTIAL_CONTENT);
+        let total = match ranged {
+            true => header(&response, "content-range").and_then(|h| content_range_total(&h)),
+            false => header(&response, "content-length").and_then(|n| n.parse().ok()),
+        };
+        // Servers ignoring the Range header send everything; only the head is read then
+        let ranges = match (ranged, total) {
+            (true, Some(total)) => sample_ranges(total, &options),
+            _ => Vec::new(),
+        };
+        let planned = ranges
+            .iter()
+            .map(|(start, end)| end - start + 1)
+            .sum::<u64>();
+        progress.total =
+            Some(total.unwrap_or(options.head_bytes).min(options.head_bytes) + planned);
+
+        let head = read_body(&response, Some(options.head_bytes), &mut progress).await?;
+        let mut sampled = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            let response = fetch(&url, Some((start, end))).await?;
+            if status(&response) != Some(PARTIAL_CONTENT) {
+                break;
+            }
+            sampled.push(read_body(&response, Some(end - start + 1), &mut progress).await?);
+        }
+        Ok(CSV::from_sampled_ranges(&head, &sampled, total, options.seed)?.into())
+    }
+}
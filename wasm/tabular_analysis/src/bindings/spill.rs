@@ -0,0 +1,126 @@
+use js_sys::{Array, Function, Object, Reflect, Uint8Array};
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{optional_config, serialization_error};
+use crate::analysis::spill::{spill_error, SpillSegment, SpillStore, SpilledAnalysis};
+use crate::error::AnalysisError;
+
+// Spill store in an OPFS file, through the `FileSystemSyncAccessHandle` dedicated workers can
+// open on it, whose reads and writes are synchronous
+struct OpfsSpillStore {
+    handle: JsValue,
+    len: u64,
+}
+
+impl OpfsSpillStore {
+    // Calls a method of the handle, turning a thrown exception into a SpillError
+    fn call(&self, method: &str, args: &Array) -> Result<JsValue, AnalysisError> {
+        let describe = |error: JsValue| {
+            let message = error
+                .dyn_ref::<js_sys::Error>()
+                .map_or_else(|| format!("{:?}", error), |e| String::from(e.message()));
+            spill_error(format!("OPFS {} failed: {}", method, message))
+        };
+        let function: Function = Reflect::get(&self.handle, &method.into())
+            .map_err(describe)?
+            .dyn_into()
+            .map_err(|_| {
+                spill_error(format!(
+                    "Expected a FileSystemSyncAccessHandle with a {} method",
+                    method
+                ))
+            })?;
+        function.apply(&self.handle, args).map_err(describe)
+    }
+
+    fn at(offset: u64) -> Object {
+        let options = Object::new();
+        let _ = Reflect::set(&options, &"at".into(), &(offset as f64).into());
+        options
+    }
+}
+
+impl SpillStore for OpfsSpillStore {
+    fn append(&mut self, bytes: &[u8]) -> Result<SpillSegment, AnalysisError> {
+        let buffer = Uint8Array::from(bytes);
+        let written = self.call("write", &Array::of2(&buffer, &Self::at(self.len)))?;
+        if written.as_f64() != Some(bytes.len() as f64) {
+            return Err(spill_error(
+                "OPFS write was cut short, the origin may be out of quota",
+            ));
+        }
+        let segment = SpillSegment {
+            offset: self.len,
+            len: bytes.len(),
+        };
+        self.len += bytes.len() as u64;
+        Ok(segment)
+    }
+
+    fn read(&mut self, segment: SpillSegment) -> Result<Vec<u8>, AnalysisError> {
+        let buffer = Uint8Array::new_with_length(segment.len as u32);
+        let read = self.call("read", &Array::of2(&buffer, &Self::at(segment.offset)))?;
+        if read.as_f64() != Some(segment.len as f64) {
+            return Err(spill_error(
+                "OPFS read returned fewer bytes than were written",
+            ));
+        }
+        Ok(buffer.to_vec())
+    }
+}
+
+/// Full-file profile of CSV text too large for memory, built in a dedicated worker: push the
+/// text in chunks, e.g. from `file.stream()`, and each column's part is staged in an OPFS file
+/// until `finish()` reads the columns back one at a time. Only a chunk of rows or one column
+/// is resident at once.
+#[wasm_bindgen(js_name = SpilledAnalysis)]
+pub struct JsSpilledAnalysis {
+    inner: Option<SpilledAnalysis<OpfsSpillStore>>,
+}
+
+#[wasm_bindgen(js_class = SpilledAnalysis)]
+impl JsSpilledAnalysis {
+    /// Stages into the file behind `handle`, from `await fileHandle.createSyncAccessHandle()`,
+    /// emptying it first
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        #[wasm_bindgen(unchecked_param_type = "FileSystemSyncAccessHandle")] handle: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "AnalysisConfig | undefined")] config: JsValue,
+    ) -> Result<JsSpilledAnalysis, JsValue> {
+        let store = OpfsSpillStore { handle, len: 0 };
+        store.call("truncate", &Array::of1(&0.into()))?;
+        let inner = SpilledAnalysis::new(store, optional_config(config)?)?;
+        Ok(JsSpilledAnalysis { inner: Some(inner) })
+    }
+
+    /// Parses and stages the complete rows pushed so far
+    #[wasm_bindgen]
+    pub fn push_text(&mut self, chunk: &str) -> Result<(), JsValue> {
+        self.analysis()?.push_text(chunk).map_err(JsValue::from)
+    }
+
+    /// Data rows staged so far
+    #[wasm_bindgen]
+    pub fn rows(&self) -> usize {
+        self.inner.as_ref().map_or(0, SpilledAnalysis::rows)
+    }
+
+    /// Analyzes every column; the handle can be closed afterwards
+    #[wasm_bindgen(unchecked_return_type = "BoundedAnalysis")]
+    pub fn finish(&mut self) -> Result<JsValue, JsValue> {
+        let analysis = self.inner.take().ok_or_else(finished)?.finish()?;
+        to_value(&analysis).map_err(|e| serialization_error("bounded analysis", e))
+    }
+}
+
+impl JsSpilledAnalysis {
+    fn analysis(&mut self) -> Result<&mut SpilledAnalysis<OpfsSpillStore>, AnalysisError> {
+        self.inner.as_mut().ok_or_else(finished)
+    }
+}
+
+fn finished() -> AnalysisError {
+    AnalysisError::invalid_argument("The analysis has already finished")
+}
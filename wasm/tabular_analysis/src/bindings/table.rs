@@ -0,0 +1,291 @@
+use serde::Serialize;
+use serde_wasm_bindgen::{from_value, to_value, Serializer};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+use super::csv::JsCsv;
+use super::serialization_error;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::columns::HeaderStyle;
+use crate::table::pivot::Aggregate;
+use crate::table::view::CsvView;
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// Appends a column computed from an expression over the other columns, e.g.
+    /// `price * quantity`, `first & " " & last`, `[end date] - [start date]` or
+    /// `if(score >= 50, "pass", "fail")`, and returns its index
+    #[wasm_bindgen]
+    pub fn add_computed_column(&mut self, name: &str, expression: &str) -> Result<usize, JsValue> {
+        self.inner
+            .with_history("add_computed_column", |csv| {
+                csv.push_computed_column(name, expression)
+            })
+            .map_err(JsValue::from)
+    }
+
+    /// Renames columns from their current header to the new one, all at once so headers can
+    /// be swapped
+    #[wasm_bindgen]
+    pub fn rename_columns(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Record<string, string>")] renames: JsValue,
+    ) -> Result<(), JsValue> {
+        let renames: BTreeMap<String, String> = from_value(renames).map_err(|e| {
+            AnalysisError::invalid_argument(format!("Invalid column renames: {}", e))
+        })?;
+        let renames: Vec<(String, String)> = renames.into_iter().collect();
+        self.inner
+            .with_history("rename_columns", |csv| csv.rename_columns(&renames))
+            .map_err(JsValue::from)
+    }
+
+    /// Renames every header to a SQL-safe identifier, `snake` case unless another style is
+    /// given, returning each original header with its new one
+    #[wasm_bindgen(unchecked_return_type = "HeaderRename[]")]
+    pub fn normalize_headers(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "HeaderStyle | undefined")] style: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let style: HeaderStyle = if style.is_undefined() || style.is_null() {
+            HeaderStyle::default()
+        } else {
+            from_value(style).map_err(|e| {
+                AnalysisError::invalid_argument(format!("Invalid header style: {}", e))
+            })?
+        };
+        let renames = self
+            .inner
+            .with_history("normalize_headers", |csv| csv.normalize_headers(style))?;
+        to_value(&renames).map_err(|e| serialization_error("header renames", e))
+    }
+
+    /// Moves the columns into the given order of their current indices
+    #[wasm_bindgen]
+    pub fn reorder_columns(&mut self, order: Vec<usize>) -> Result<(), JsValue> {
+        self.inner
+            .with_history("reorder_columns", |csv| csv.reorder_columns(&order))
+            .map_err(JsValue::from)
+    }
+
+    /// Compares this CSV, as the old version, with `other`, matching rows on the key
+    /// columns or by position when none are given
+    #[wasm_bindgen(unchecked_return_type = "CsvDiff")]
+    pub fn diff(&self, other: &JsCsv, key_columns: Vec<usize>) -> Result<JsValue, JsValue> {
+        let diff = self.inner.diff_against(&other.inner, &key_columns)?;
+        to_value(&diff).map_err(|e| serialization_error("diff", e))
+    }
+
+    /// Reverts the most recent operation, returning its name
+    #[wasm_bindgen]
+    pub fn undo(&mut self) -> Result<String, JsValue> {
+        self.inner.undo_operation().map_err(JsValue::from)
+    }
+
+    /// Re-applies the most recently undone operation, returning its name
+    #[wasm_bindgen]
+    pub fn redo(&mut self) -> Result<String, JsValue> {
+        self.inner.redo_operation().map_err(JsValue::from)
+    }
+
+    /// Source columns and operations behind a column's values
+    #[wasm_bindgen(unchecked_return_type = "ColumnLineage")]
+    pub fn column_lineage(&self, index: usize) -> Result<JsValue, JsValue> {
+        let lineage = self.inner.column_lineage(index)?;
+        to_value(&lineage).map_err(|e| serialization_error("column lineage", e))
+    }
+
+    /// Saves the table, metadata, cleaning log, undo history and options into a
+    /// `Uint8Array` for IndexedDB, deflated when `compress` is set
+    #[wasm_bindgen]
+    pub fn export_session(&self, compress: bool) -> Result<Vec<u8>, JsValue> {
+        self.inner.export_session(compress).map_err(JsValue::from)
+    }
+
+    /// Restores a CSV saved by `export_session`, with its undo history
+    #[wasm_bindgen]
+    pub fn import_session(blob: &[u8]) -> Result<JsCsv, JsValue> {
+        CSV::import_session(blob)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Lists the operations that can be undone and redone
+    #[wasm_bindgen(unchecked_return_type = "HistoryReport")]
+    pub fn history(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.history_report()).map_err(|e| serialization_error("history", e))
+    }
+
+    /// Builds a crosstab of `value_col` aggregated by the values of `row_col` and
+    /// `col_col`; `agg` is one of count, sum, mean, min or max
+    #[wasm_bindgen(unchecked_return_type = "PivotTable")]
+    pub fn pivot(
+        &self,
+        row_col: usize,
+        col_col: usize,
+        value_col: usize,
+        agg: &str,
+    ) -> Result<JsValue, JsValue> {
+        let table = Aggregate::parse(agg).and_then(|aggregate| {
+            self.inner
+                .pivot_table(row_col, col_col, value_col, aggregate)
+        })?;
+        table
+            .serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("pivot table", e))
+    }
+
+    /// Checks whether the table looks stored one attribute per row, with entities as columns
+    #[wasm_bindgen(unchecked_return_type = "TransposeCheck")]
+    pub fn check_transposed(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.check_transposed())
+            .map_err(|e| serialization_error("transpose check", e))
+    }
+
+    /// Flips rows and columns into a new CSV, taking headers from the first column, and
+    /// infers its types
+    #[wasm_bindgen]
+    pub fn transpose(&self) -> Result<JsCsv, JsValue> {
+        self.inner
+            .transpose()
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Reshapes wide to long into a new CSV with the id columns, `variable` and `value`,
+    /// melting every other column when `value_columns` is empty
+    #[wasm_bindgen]
+    pub fn melt(
+        &self,
+        id_columns: Vec<usize>,
+        value_columns: Vec<usize>,
+    ) -> Result<JsCsv, JsValue> {
+        self.inner
+            .melt(&id_columns, &value_columns)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Reshapes long to wide into a new CSV with a column per distinct key
+    #[wasm_bindgen]
+    pub fn spread(&self, key_column: usize, value_column: usize) -> Result<JsCsv, JsValue> {
+        self.inner
+            .spread(key_column, value_column)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Returns up to `limit` rows starting at `start` as typed objects keyed by header,
+    /// with paging metadata, so table UIs can render only the visible rows
+    #[wasm_bindgen(unchecked_return_type = "RowPage")]
+    pub fn rows(&self, start: usize, limit: usize) -> Result<JsValue, JsValue> {
+        let page = self.inner.row_page(start, limit)?;
+        page.serialize(&Serializer::json_compatible())
+            .map_err(|e| serialization_error("rows", e))
+    }
+
+    /// Returns a new CSV with `n` rows picked at random; the same seed gives the same rows
+    #[wasm_bindgen]
+    pub fn sample(&self, n: usize, seed: u32) -> JsCsv {
+        self.inner.sample(n, seed).into()
+    }
+
+    /// Returns a new CSV with up to `n_per_group` random rows for every distinct value
+    /// of a column, so rare groups stay represented
+    #[wasm_bindgen]
+    pub fn stratified_sample(
+        &self,
+        column: usize,
+        n_per_group: usize,
+        seed: u32,
+    ) -> Result<JsCsv, JsValue> {
+        self.inner
+            .stratified_sample_rows(column, n_per_group, seed)
+            .map(JsCsv::from)
+            .map_err(JsValue::from)
+    }
+
+    /// Splits the rows at random into `[train, test]` CSVs, `ratio` of them for training.
+    /// With `stratify_by`, every value of that column is split by the ratio on its own, so
+    /// rare classes show up in both.
+    #[wasm_bindgen(unchecked_return_type = "[CSV, CSV]")]
+    pub fn train_test_split(
+        &self,
+        ratio: f64,
+        seed: u32,
+        stratify_by: Option<usize>,
+    ) -> Result<Vec<JsCsv>, JsValue> {
+        let (train, test) = self.inner.train_test_split(ratio, seed, stratify_by)?;
+        Ok(vec![train.into(), test.into()])
+    }
+
+    /// Returns a view of every row and column of this CSV
+    #[wasm_bindgen]
+    pub fn view(&self) -> JsCsvView {
+        JsCsvView {
+            inner: self.inner.view(),
+        }
+    }
+}
+
+/// JS handle to a view of a CSV, exported as `CsvView`
+#[wasm_bindgen(js_name = CsvView)]
+#[derive(Debug, Clone)]
+pub struct JsCsvView {
+    inner: CsvView,
+}
+
+#[wasm_bindgen(js_class = CsvView)]
+impl JsCsvView {
+    #[wasm_bindgen]
+    pub fn row_count(&self) -> usize {
+        self.inner.row_count()
+    }
+
+    #[wasm_bindgen]
+    pub fn column_count(&self) -> usize {
+        self.inner.column_count()
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "string[]")]
+    pub fn headers(&self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.headers()).map_err(|e| serialization_error("headers", e))
+    }
+
+    /// Keeps only the given columns, in the given order
+    #[wasm_bindgen]
+    pub fn select_columns(&self, columns: Vec<usize>) -> Result<JsCsvView, JsValue> {
+        let inner = self.inner.select(&columns)?;
+        Ok(JsCsvView { inner })
+    }
+
+    /// Keeps the rows whose value in a column equals `value`, ignoring surrounding whitespace
+    #[wasm_bindgen]
+    pub fn filter(&self, column: usize, value: &str) -> Result<JsCsvView, JsValue> {
+        let inner = self.inner.filter_rows(column, value)?;
+        Ok(JsCsvView { inner })
+    }
+
+    /// Orders the rows by a column: ordinal levels such as S/M/L in their order, numbers by
+    /// value and other text alphabetically, with empty cells last
+    #[wasm_bindgen]
+    pub fn sort_by(&self, column: usize, descending: Option<bool>) -> Result<JsCsvView, JsValue> {
+        let inner = self.inner.sort_by(column, descending.unwrap_or(false))?;
+        Ok(JsCsvView { inner })
+    }
+
+    /// Keeps `n` random rows; the same seed gives the same rows
+    #[wasm_bindgen]
+    pub fn sample(&self, n: usize, seed: u32) -> JsCsvView {
+        JsCsvView {
+            inner: self.inner.sample(n, seed),
+        }
+    }
+
+    /// Copies the view's rows and columns into a standalone CSV
+    #[wasm_bindgen]
+    pub fn materialize(&self) -> JsCsv {
+        self.inner.materialize().into()
+    }
+}
@@ -0,0 +1,1041 @@
+// Shapes of the objects passed to and returned from the CSV and CsvView classes.
+// Optional fields are left out when the engine has no value for them; fields typed
+// `T | null` come from results serialized as plain JSON.
+
+/** Name of a `DataType`, as it appears in metadata and reports */
+export type DataTypeName =
+    | "Integer"
+    | "Decimal"
+    | "Currency"
+    | "Date"
+    | "Email"
+    | "Phone"
+    | "Categorical"
+    | "Text"
+    | "CreditCard"
+    | "Mixed"
+    | "Quantity";
+
+export type CardBrand =
+    | "Visa"
+    | "Mastercard"
+    | "AmericanExpress"
+    | "Discover"
+    | "DinersClub"
+    | "Jcb"
+    | "UnionPay";
+
+export type DateFormat =
+    | "Iso8601"
+    | "UsSlash"
+    | "EuropeanDash"
+    | "EuropeanSlash"
+    | "JapaneseSlash"
+    | "UsDash";
+
+export type Monotonicity = "Increasing" | "Decreasing" | "Constant" | "Unordered";
+
+export type Dimension = "mass" | "length" | "volume" | "data_size" | "speed" | "duration";
+
+export type PiiKind = "Email" | "Phone" | "Name" | "CreditCard" | "Ssn";
+
+export type Aggregate = "count" | "sum" | "mean" | "min" | "max";
+
+// ---- errors ----
+
+export type AnalysisErrorCode =
+    | "parse_error"
+    | "encoding_error"
+    | "config_error"
+    | "column_out_of_bounds"
+    | "row_out_of_bounds"
+    | "unknown_column"
+    | "invalid_column"
+    | "invalid_argument"
+    | "invalid_pattern"
+    | "invalid_rule"
+    | "expression_error"
+    | "invalid_metadata"
+    | "duplicate_key"
+    | "nothing_to_undo"
+    | "nothing_to_redo"
+    | "serialization_error"
+    | "spill_error"
+    | "fetch_error"
+    | "cancelled";
+
+/**
+ * Thrown by every method that can fail: an `Error` named "AnalysisError" with a stable
+ * `code` and, depending on the code, the row, column or value at fault
+ */
+export interface AnalysisError extends Error {
+    code: AnalysisErrorCode;
+    /** Data row, counting from 0 after the header */
+    row?: number;
+    column?: number;
+    /** Byte offset of invalid UTF-8 */
+    offset?: number;
+    header?: string;
+    pattern?: string;
+    key?: string[];
+    /** HTTP status of a failed download */
+    status?: number;
+}
+
+// ---- warnings ----
+
+export type Severity = "info" | "warning";
+
+export type WarningCode =
+    | "small_sample"
+    | "ragged_rows"
+    | "ambiguous_dates"
+    | "empty_column"
+    | "sampled_input";
+
+export interface AnalysisWarning {
+    code: WarningCode;
+    severity: Severity;
+    message: string;
+    column?: number;
+    /** Data rows involved, counting from 0 after the header */
+    rows: number[];
+}
+
+// ---- logging ----
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+/** Message passed to the `set_log_sink` callback */
+export interface LogRecord {
+    level: LogLevel;
+    /** Area of the engine, e.g. "tabular_analysis::parse" or "tabular_analysis::inference" */
+    target: string;
+    message: string;
+}
+
+// ---- configuration ----
+
+export interface SynonymDictionary {
+    locale?: string;
+    true_values?: string[];
+    false_values?: string[];
+    categories?: string[];
+}
+
+export interface DetectionConfig {
+    type_threshold?: number;
+    header_hint_weight?: number;
+    locale?: string;
+    dictionaries?: SynonymDictionary[];
+}
+
+/** What to do when the parsed table wouldn't fit `limit_bytes` */
+export type SpillStrategy = "sample" | "column_by_column";
+
+export interface MemoryConfig {
+    /** Estimated bytes the parsed values may take; unlimited when omitted */
+    limit_bytes?: number;
+    spill_strategy?: SpillStrategy;
+    /** Seed of the rows kept by the sample strategy */
+    sample_seed?: number;
+}
+
+/** Analysis options; omitted fields keep their defaults */
+export interface AnalysisConfig {
+    percentiles?: number[];
+    /**
+     * Numbers standing for missing data, like -999, left out of statistics beyond the other
+     * values; none by default
+     */
+    sentinel_values?: number[];
+    /**
+     * t-digest compression for quantiles of columns too large to sort and of streamed rows;
+     * higher is more accurate, 10 to 10000
+     */
+    quantile_compression?: number;
+    detection?: DetectionConfig;
+    memory?: MemoryConfig;
+}
+
+/** How the text is written; detected by `new CSV(text)` */
+export interface Dialect {
+    /** One character, a longer string like `||`, or with `regex` a pattern */
+    delimiter?: string;
+    /** Reads `delimiter` as a regular expression matched outside quoted fields */
+    regex?: boolean;
+    quote?: string;
+    /** When false, columns are named `column_1`, `column_2`, ... */
+    has_headers?: boolean;
+    /** Preferred when numbers like `1,250` read either way */
+    decimal_separator?: string;
+}
+
+/** Lines left out before the header is read, and the dialect, detected when omitted */
+export interface ParseOptions {
+    dialect?: Dialect;
+    /** Lines dropped from the start, such as banners */
+    skip_rows?: number;
+    /** Lines starting with this, like `#`, are dropped */
+    comment_prefix?: string;
+    /** Non-blank lines dropped from the end, such as totals */
+    skip_footer_rows?: number;
+    /** Stacked header rows merged into names like `2023_Q1_Revenue`; 1 by default */
+    header_rows?: number;
+}
+
+/** Character positions of a fixed-width column, `end` excluded */
+export interface FixedWidthColumn {
+    /** Taken from the header line when empty */
+    name?: string;
+    start: number;
+    /** Up to the end of the line when omitted */
+    end?: number;
+}
+
+export interface FixedWidthLayout {
+    columns: FixedWidthColumn[];
+    has_headers?: boolean;
+}
+
+// ---- column metadata ----
+
+export interface Percentile {
+    p: number;
+    value: number;
+}
+
+export interface NumericStats {
+    min: number;
+    max: number;
+    mean: number;
+    median: number;
+    std_dev: number;
+    quartiles: [number, number, number];
+    percentiles: Percentile[];
+    skewness: number;
+    kurtosis: number;
+    mode?: number;
+    zero_count: number;
+    negative_count: number;
+    /** Digits a DECIMAL needs for every value as written; 0 for quantities */
+    precision: number;
+    /** Fraction digits a DECIMAL needs for every value as written */
+    scale: number;
+    /** NaN and infinities, left out of the other statistics */
+    nan_count: number;
+    infinite_count: number;
+    /** Sentinel values found beyond every other value, left out as missing */
+    sentinels: SentinelCount[];
+}
+
+export interface SentinelCount {
+    value: number;
+    count: number;
+}
+
+export interface TextStats {
+    min_length: number;
+    max_length: number;
+    avg_length: number;
+    /** Values with their counts, most frequent first */
+    most_common: [string, number][];
+    distinct_count: number;
+    uniqueness_ratio: number;
+    entropy: number;
+    common_prefix: string;
+}
+
+/** Word-level statistics of text columns whose values average 16 characters or more */
+export interface TokenStats {
+    avg_token_count: number;
+    distinct_terms: number;
+    /** Words with their counts, most frequent first, leaving out stopwords and numbers */
+    top_terms: [string, number][];
+    /** ISO 639-1 code: `en`, `es`, `fr`, `de`, `pt`, `it` or `nl` */
+    language?: string;
+    /** Whether the values read as free-form prose rather than codes or labels */
+    prose: boolean;
+}
+
+export type EmailIssue = "double_at" | "missing_tld" | "stray_spaces" | "domain_typo";
+
+export interface EmailSuggestion {
+    row: number;
+    value: string;
+    issue: EmailIssue;
+    /** The corrected address, when the fix is clear */
+    suggestion?: string;
+}
+
+/** Domain breakdown of email columns */
+export interface EmailStats {
+    valid_count: number;
+    distinct_domains: number;
+    /** Domains with their counts, lowercase, most frequent first */
+    top_domains: [string, number][];
+    /** Addresses at webmail providers like gmail.com */
+    free_provider_count: number;
+    /** Addresses at any other domain */
+    corporate_count: number;
+    free_provider_ratio: number;
+    /** Values close to a valid address, of which the first 20 are listed */
+    near_valid_count: number;
+    near_valid: EmailSuggestion[];
+}
+
+export interface CountryCodeCount {
+    /** Calling code with its plus, e.g. `+44` */
+    code: string;
+    /** ISO 3166 code of the main country using it; `+1` also covers Canada and the Caribbean */
+    country: string;
+    count: number;
+}
+
+/** Country breakdown of phone columns */
+export interface PhoneStats {
+    /** Most frequent first */
+    country_codes: CountryCodeCount[];
+    /** Numbers written without a country code, counted under `+1` when they have ten digits */
+    national_count: number;
+    /** Non-empty values with no E.164 form */
+    unnormalizable_count: number;
+}
+
+export interface DateGap {
+    start: string;
+    end: string;
+    days: number;
+}
+
+export interface DateStats {
+    min_date: string;
+    max_date: string;
+    span_days: number;
+    most_common_year: number;
+    most_common_month: number;
+    most_common_weekday: string;
+    monotonicity: Monotonicity;
+    interval_days?: number;
+    gap_count: number;
+    gaps: DateGap[];
+}
+
+export interface CardStats {
+    brands: [CardBrand, number][];
+    unknown_brand_count: number;
+    invalid_count: number;
+}
+
+export interface NumberFormat {
+    thousands_separator?: string;
+    decimal_separator: string;
+    parentheses_negative: boolean;
+}
+
+export interface TypeCandidate {
+    data_type: DataTypeName;
+    score: number;
+    matches: number;
+    mismatches: number;
+    prior: number;
+}
+
+export interface MixedComponent {
+    data_type: DataTypeName;
+    count: number;
+    ratio: number;
+    rows: number[];
+}
+
+export interface MixedComposition {
+    components: MixedComponent[];
+    unmatched_rows: number[];
+}
+
+export interface UnitCount {
+    unit: string;
+    count: number;
+}
+
+export interface QuantityColumn {
+    dimension: Dimension;
+    units: UnitCount[];
+    normalized_unit: string;
+    coverage: number;
+    number_format: NumberFormat;
+}
+
+/** Integers past ±2^53, kept as digit strings since JavaScript numbers would round them */
+export interface BigIntegerColumn {
+    min: string;
+    max: string;
+    max_digits: number;
+    /** Whether every value fits a 64-bit BIGINT */
+    fits_i64: boolean;
+}
+
+/** Levels of a categorical column that follow a known order, lowest first */
+export interface OrdinalScale {
+    /** `size`, `level`, `frequency`, `agreement`, `quality`, `satisfaction`, `tier`, `priority` or `stars` */
+    name: string;
+    levels: string[];
+}
+
+export interface ColumnMetadata {
+    name: string;
+    data_type: DataTypeName;
+    confidence: number;
+    numeric_stats?: NumericStats;
+    text_stats?: TextStats;
+    date_stats?: DateStats;
+    card_stats?: CardStats;
+    email_stats?: EmailStats;
+    phone_stats?: PhoneStats;
+    sql_type?: string;
+    number_format?: NumberFormat;
+    type_candidates?: TypeCandidate[];
+    mixed_composition?: MixedComposition;
+    quantity?: QuantityColumn;
+    user_overridden?: boolean;
+    analysis_version?: number;
+    config_hash?: string;
+    /** Whether the statistics cover every row or a sample kept under the memory limit */
+    analysis_mode?: AnalysisMode;
+    /** Set on columns made or rewritten by cleaning, split, merge or computed expressions */
+    lineage?: ColumnLineage;
+    /** Set on integer columns too large for numbers; they get no `numeric_stats` */
+    big_integer?: BigIntegerColumn;
+    /** Set on categorical columns whose levels follow a known order, such as low/medium/high */
+    ordinal?: OrdinalScale;
+    token_stats?: TokenStats;
+}
+
+export interface LineageStep {
+    /** `split`, `merge`, `computed`, `rename` or the cleaning operation, e.g. `impute:median` */
+    operation: string;
+    /** Headers of the columns the operation read */
+    inputs: string[];
+    detail?: string;
+}
+
+/** Columns of the loaded file a column derives from and the operations applied since */
+export interface ColumnLineage {
+    sources: string[];
+    steps: LineageStep[];
+}
+
+export type HeaderStyle = "snake" | "upper_snake" | "camel";
+
+export interface HeaderRename {
+    original: string;
+    normalized: string;
+}
+
+/** Header, value count and, once inferred, type name and confidence */
+export type ColumnSummary = [string, number, [string, number] | undefined];
+
+// ---- analysis ----
+
+/** Wall time of each phase in milliseconds */
+export interface PhaseTimings {
+    parse_ms: number;
+    inference_ms: number;
+    stats_ms: number;
+    sql_ms: number;
+}
+
+export interface AnalysisMetrics {
+    phases: PhaseTimings;
+    total_ms: number;
+    rows: number;
+    columns: number;
+    rows_per_second: number;
+    peak_memory_bytes: number;
+    workers: number;
+    /** Share of the workers' combined time spent scoring candidate types, from 0 to 1 */
+    worker_utilization: number;
+    /** Columns whose metadata was reused from an earlier run over the same values and config */
+    cached_columns: number;
+}
+
+export interface InferenceProgress {
+    columns_done: number;
+    columns: number;
+    done: boolean;
+}
+
+export interface AppendReport {
+    rows_appended: number;
+    /** Columns whose confidence and statistics were updated under their existing type */
+    updated_columns: number[];
+    /** Columns inferred again because the new rows could change their type */
+    reinferred_columns: number[];
+}
+
+export interface RetryPolicy {
+    /** Milliseconds before a column still out is treated as failed, 0 for no limit */
+    timeout_ms?: number;
+    /** Tries per column, the first one included */
+    max_attempts?: number;
+}
+
+export interface ColumnFailure {
+    column: number;
+    attempts: number;
+    message: string;
+}
+
+export interface QueueReport {
+    completed: number[];
+    failed: ColumnFailure[];
+}
+
+export interface FetchOptions {
+    /** Download the start and random byte ranges of the file instead of all of it */
+    sample?: boolean;
+    head_bytes?: number;
+    sample_ranges?: number;
+    range_bytes?: number;
+    seed?: number;
+}
+
+/** Bytes downloaded so far and the bytes expected, when known */
+export interface FetchProgress {
+    loaded: number;
+    total?: number;
+}
+
+/** Message the worker from `worker_bundle` answers with a `PackedColumnResult` */
+export interface PackedColumnRequest {
+    column: number;
+    header: string;
+    /** From `CSV.pack_column`; transfer it rather than copying */
+    buffer: ArrayBuffer;
+    config?: AnalysisConfig;
+}
+
+export interface PackedColumnResult {
+    column: number;
+    metadata?: ColumnMetadata;
+    /** Why the column couldn't be analyzed: the fields of the `AnalysisError` */
+    error?: { code: AnalysisErrorCode; message?: string; [field: string]: unknown };
+}
+
+export type Rule =
+    | { rule: "not_null"; column: string }
+    | { rule: "in"; column: string; values: string[] }
+    | { rule: "matches"; column: string; pattern: string }
+    | { rule: "compare"; left: string; op: "<" | "<=" | ">" | ">=" | "==" | "!="; right: string };
+
+export interface RuleResult {
+    rule: Rule;
+    passed: number;
+    failed: number;
+    failing_rows: number[];
+}
+
+export interface RuleReport {
+    results: RuleResult[];
+    passed: number;
+    failed: number;
+    pass_rate: number;
+}
+
+export interface ProfileReport {
+    row_count: number;
+    column_count: number;
+    duplicate_row_count: number;
+    completeness: number;
+    quality_score: number;
+    rules?: RuleReport;
+    columns: ColumnMetadata[];
+    warnings: AnalysisWarning[];
+}
+
+export interface FileReport {
+    profile?: ProfileReport;
+    /** Why the file couldn't be read: the fields of the `AnalysisError` it would throw */
+    error?: { code: AnalysisErrorCode; message?: string; [field: string]: unknown };
+}
+
+export interface BatchReport {
+    files: Record<string, FileReport>;
+    failed: number;
+    total_ms: number;
+}
+
+export type AnalysisMode = "full" | "sampled" | "column_by_column";
+
+export interface BoundedAnalysis {
+    mode: AnalysisMode;
+    /** Data rows in the input */
+    rows: number;
+    /** Rows the statistics were computed from, fewer than `rows` when sampled */
+    analyzed_rows: number;
+    /** Estimated peak bytes of analyzing the whole table at once */
+    estimated_bytes: number;
+    /** Seed the sampled rows were picked with, when sampled */
+    seed?: number;
+    columns: ColumnMetadata[];
+}
+
+export interface ContingencyTable {
+    rows: string[];
+    columns: string[];
+    counts: number[][];
+}
+
+export interface Association {
+    table: ContingencyTable;
+    observations: number;
+    chi_square: number;
+    degrees_of_freedom: number;
+    p_value: number;
+    cramers_v: number;
+}
+
+export interface ClusterSummary {
+    centroid: number[];
+    size: number;
+}
+
+export interface Clustering {
+    columns: string[];
+    /** Cluster of every row, null for rows missing a value */
+    assignments: (number | null)[];
+    clusters: ClusterSummary[];
+    inertia: number;
+    iterations: number;
+    /** Seed the initial centroids were drawn with */
+    seed: number;
+}
+
+export interface Violation {
+    column: string;
+    row?: number;
+    value?: string;
+    kind: "missing_column" | "type_mismatch" | "out_of_range" | "format_mismatch";
+}
+
+export interface ColumnValidation {
+    column: string;
+    expected_type: DataTypeName;
+    violation_count: number;
+}
+
+export interface ValidationReport {
+    valid: boolean;
+    violation_count: number;
+    columns: ColumnValidation[];
+    violations: Violation[];
+}
+
+export interface CandidateExplanation {
+    data_type: DataTypeName;
+    score: number;
+    matches: number;
+    mismatches: number;
+    prior: number;
+    /** Card numbers are masked to their last four digits */
+    failing_examples: string[];
+}
+
+export interface TypeExplanation {
+    column: string;
+    data_type: DataTypeName;
+    confidence: number;
+    threshold: number;
+    user_overridden: boolean;
+    candidates: CandidateExplanation[];
+}
+
+export interface PatternFrequency {
+    pattern: string;
+    count: number;
+    coverage: number;
+    example: string;
+}
+
+export interface PatternAnomaly {
+    row: number;
+    value: string;
+    pattern: string;
+}
+
+export interface PatternReport {
+    column: number;
+    distinct_patterns: number;
+    patterns: PatternFrequency[];
+    dominant_pattern?: string;
+    anomaly_count: number;
+    anomalies: PatternAnomaly[];
+}
+
+export interface PiiColumnReport {
+    column: number;
+    name: string;
+    kind: PiiKind;
+    matches: number;
+    ratio: number;
+    sample_rows: number[];
+}
+
+export interface PiiReport {
+    flagged_cells: number;
+    columns: PiiColumnReport[];
+}
+
+export interface ResidualOutlier {
+    row: number;
+    x: number;
+    y: number;
+    predicted: number;
+    residual: number;
+}
+
+export interface LinearFit {
+    slope: number;
+    intercept: number;
+    r_squared: number;
+    observations: number;
+    residual_std_error: number;
+    outliers: ResidualOutlier[];
+}
+
+export interface MetadataImportReport {
+    applied_columns: string[];
+    missing_columns: string[];
+    unmatched_columns: string[];
+}
+
+// ---- cleaning ----
+
+export interface CellChange {
+    row: number;
+    column: number;
+    old_value: string;
+    new_value: string;
+    reason: string;
+}
+
+export interface CleaningStep {
+    operation: string;
+    column?: number;
+    changes: CellChange[];
+}
+
+export interface Correction {
+    row: number;
+    column: number;
+    value: string;
+    suggestion?: string;
+    confidence: number;
+    reason: string;
+}
+
+export interface CorrectionPolicy {
+    min_confidence?: number;
+    columns?: number[];
+}
+
+export interface CorrectionReport {
+    applied: CellChange[];
+    flagged: Correction[];
+}
+
+export interface DateStandardizationReport {
+    column: number;
+    day_first: boolean;
+    day_first_evidence: number;
+    month_first_evidence: number;
+    converted: number;
+    unparseable_rows: number[];
+    format_counts: [DateFormat, number][];
+}
+
+export interface DuplicateGroup {
+    first_row: number;
+    duplicate_rows: number[];
+}
+
+export interface DuplicateReport {
+    key_columns: number[];
+    duplicate_row_count: number;
+    groups: DuplicateGroup[];
+}
+
+export type ImputeStrategy =
+    | { strategy: "mean" }
+    | { strategy: "median" }
+    | { strategy: "mode" }
+    | { strategy: "constant"; value: string }
+    | { strategy: "forward_fill" }
+    | { strategy: "backward_fill" };
+
+export interface ImputationReport {
+    column: number;
+    strategy: ImputeStrategy;
+    fill_value?: string;
+    imputed_rows: number[];
+    unfilled_rows: number[];
+}
+
+export type MaskStrategy = "redact" | "hash";
+
+export interface MaskOptions {
+    strategy?: MaskStrategy;
+    columns?: number[];
+    kinds?: PiiKind[];
+    salt?: string;
+}
+
+export interface MaskReport {
+    strategy: MaskStrategy;
+    masked_cells: number;
+    masked_columns: number[];
+}
+
+export interface ReplaceSample {
+    row: number;
+    before: string;
+    after: string;
+}
+
+export interface ReplaceReport {
+    column: number;
+    pattern: string;
+    replacement: string;
+    affected_rows: number;
+    samples: ReplaceSample[];
+    committed: boolean;
+}
+
+export interface LevelCount {
+    level: string;
+    count: number;
+}
+
+export interface LevelReport {
+    column: number;
+    /** Levels folded into another, most frequent first */
+    merged_levels: string[];
+    /** Cells rewritten to the new level */
+    affected_rows: number;
+    /** The column's levels afterwards */
+    levels: LevelCount[];
+}
+
+// ---- table ----
+
+export interface CellDiff {
+    column: string;
+    old_value: string;
+    new_value: string;
+}
+
+export interface RowRef {
+    key: string[];
+    row: number;
+}
+
+export interface RowChange {
+    key: string[];
+    old_row: number;
+    new_row: number;
+    cells: CellDiff[];
+}
+
+export interface DiffSummary {
+    added_rows: number;
+    removed_rows: number;
+    changed_rows: number;
+    unchanged_rows: number;
+    changed_cells: number;
+}
+
+export interface CsvDiff {
+    key_columns: string[];
+    added_columns: string[];
+    removed_columns: string[];
+    summary: DiffSummary;
+    added: RowRef[];
+    removed: RowRef[];
+    changed: RowChange[];
+}
+
+export interface HistoryReport {
+    undo: string[];
+    redo: string[];
+}
+
+export interface PivotRow {
+    key: string;
+    values: (number | null)[];
+}
+
+export interface PivotTable {
+    row_header: string;
+    column_header: string;
+    value_header: string;
+    aggregate: Aggregate;
+    columns: string[];
+    rows: PivotRow[];
+}
+
+/** Whether values keep one kind along rows rather than down columns */
+export interface TransposeCheck {
+    row_consistency: number;
+    column_consistency: number;
+    likely_transposed: boolean;
+}
+
+/** Cell value typed from its column: numbers, ISO 8601 date strings, text or null */
+export type CellValue = number | string | boolean | null;
+
+export interface RowPage {
+    start: number;
+    row_count: number;
+    has_more: boolean;
+    rows: Record<string, CellValue>[];
+}
+
+// ---- export ----
+
+export type SqlDialect = "postgres" | "mysql" | "sqlite" | "mssql" | "duckdb" | "bigquery";
+
+export interface SqlOptions {
+    dialect?: SqlDialect;
+    /** Rename columns named like a reserved word, `order` to `order_`, instead of quoting them */
+    rename_reserved?: boolean;
+    /** Schema, or BigQuery dataset, to create the table in */
+    schema?: string;
+    if_not_exists?: boolean;
+    /** Create a temporary table, `#name` in SQL Server; can't be combined with a schema */
+    temporary?: boolean;
+    /** Add a DROP TABLE IF EXISTS statement, `drop_table`, to run first */
+    drop_existing?: boolean;
+    /**
+     * Suggest partitioning and clustering from the column profiles, as clauses for BigQuery
+     * and comments for Postgres
+     */
+    layout_hints?: boolean;
+    /** Suggest indexes from the column profiles, each with a comment saying why; not for BigQuery */
+    index_hints?: boolean;
+    /** Restrict categorical columns to their levels: ENUM in MySQL, CHECK elsewhere but BigQuery */
+    level_constraints?: boolean;
+}
+
+export interface IndexSuggestion {
+    name: string;
+    /** Indexed columns in key order */
+    columns: string[];
+    unique: boolean;
+    /** Why the index is suggested, also a comment above the statement */
+    reason: string;
+    statement: string;
+}
+
+export type PartitionGranularity = "day" | "month";
+
+export interface TableLayout {
+    partition_column?: string;
+    partition_granularity?: PartitionGranularity;
+    /** Low-cardinality text columns, fewest distinct values first */
+    cluster_columns: string[];
+}
+
+export interface SqlColumn {
+    /** Name in the table, unquoted */
+    name: string;
+    header: string;
+    sql_type: string;
+    /** Whether the column has empty values, leaving out NOT NULL */
+    nullable: boolean;
+    /** Whether the header is a reserved word in the dialect */
+    reserved: boolean;
+    /** Values the column is restricted to by `level_constraints` */
+    levels: string[];
+}
+
+export interface SqlSchema {
+    dialect: SqlDialect;
+    table_name: string;
+    /** The table as written in the statements, with its schema and quoting */
+    table_reference: string;
+    drop_table?: string;
+    create_table: string;
+    columns: SqlColumn[];
+    layout?: TableLayout;
+    /** CREATE INDEX statements to run after creating the table, when asked for */
+    indexes: IndexSuggestion[];
+}
+
+export type CoercionKind = "overflow" | "rounded" | "too_long" | "invalid";
+
+export interface CoercionIssue {
+    row: number;
+    value: string;
+    kind: CoercionKind;
+}
+
+export interface ColumnCoercions {
+    column: number;
+    name: string;
+    sql_type: string;
+    issue_count: number;
+    /** The first 100 values that wouldn't load as they are */
+    issues: CoercionIssue[];
+    /** A wider type taking every value that overflows, rounds or is too long */
+    widened_type?: string;
+}
+
+export interface CoercionPreview {
+    dialect: SqlDialect;
+    /** Only columns with values that wouldn't load as they are */
+    columns: ColumnCoercions[];
+}
+
+export interface OrdinalEncoding {
+    column: number;
+    scale: string;
+    /** Level of each code, lowest first */
+    levels: string[];
+    /** Code of every row; null for empty cells and values off the scale */
+    codes: (number | null)[];
+}
+
+export type ScaleMethod = "standardize" | "min_max" | "robust";
+
+/** A column scaled as `(value - center) / scale`; `value * scale + center` undoes it */
+export interface ScaledColumn {
+    column: number;
+    name: string;
+    center: number;
+    /** 1 for columns without spread, whose values all scale to 0 */
+    scale: number;
+    /** null for empty cells, unparsed values and sentinels */
+    values: (number | null)[];
+}
+
+export interface ScaledColumns {
+    method: ScaleMethod;
+    columns: ScaledColumn[];
+}
+
+export interface DuckDbColumn {
+    name: string;
+    duckdb_type: string;
+    /** One value per row, typed for the column; null for empty or unparsed cells */
+    values: CellValue[];
+    /** Non-empty values that didn't parse as the column's type, loaded as null */
+    unparsed: number;
+}
+
+export interface DuckDbStatements {
+    table_name: string;
+    create_table: string;
+    /** INSERT statements covering every row, for connections without Arrow support */
+    inserts: string[];
+    columns: DuckDbColumn[];
+}
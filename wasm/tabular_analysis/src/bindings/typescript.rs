@@ -0,0 +1,265 @@
+use wasm_bindgen::prelude::*;
+
+// Interfaces for the objects the bindings exchange as `JsValue`, appended to the generated
+// .d.ts and referenced from the methods' `unchecked_return_type` and `unchecked_param_type`
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = include_str!("types.d.ts");
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::Value;
+    use std::collections::HashSet;
+
+    use crate::analysis::batch::analyze_files;
+    use crate::analysis::email_stats::calculate_email_stats;
+    use crate::analysis::memory::analyze_with_memory_limit;
+    use crate::analysis::phone_stats::calculate_phone_stats;
+    use crate::analysis::pii::detect_pii;
+    use crate::analysis::statistics::SentinelCount;
+    use crate::analysis::tokens::calculate_token_stats;
+    use crate::config::AnalysisConfig;
+    use crate::csv::CSV;
+    use crate::export::scaling::ScaleMethod;
+    use crate::export::sql::{SqlDialect, SqlOptions};
+    use crate::logging::{targets, LogRecord};
+    use crate::parallel::{pack_column, ColumnQueue, PackedColumnResult, RetryPolicy};
+    use crate::table::columns::HeaderStyle;
+    use crate::table::dialect::ParseOptions;
+    use crate::table::fixed_width::FixedWidthLayout;
+    use crate::table::remote::{FetchOptions, FetchProgress};
+    use crate::types::DataType;
+
+    const TYPES_D_TS: &str = include_str!("types.d.ts");
+
+    // Field names of an interface, split into required and optional ones
+    fn interface_fields(name: &str) -> (HashSet<String>, HashSet<String>) {
+        let start = TYPES_D_TS
+            .find(&format!("export interface {} {{", name))
+            .unwrap_or_else(|| panic!("interface {} is not declared", name));
+        let body = &TYPES_D_TS[start..];
+        let body = &body[body.find('{').unwrap() + 1..body.find("\n}").unwrap()];
+
+        let mut required = HashSet::new();
+        let mut optional = HashSet::new();
+        for line in body.lines().map(str::trim) {
+            let Some((field, _)) = line.split_once(':') else {
+                continue;
+            };
+            if line.starts_with("/**") || line.starts_with("//") {
+                continue;
+            }
+            match field.strip_suffix('?') {
+                Some(field) => optional.insert(field.to_string()),
+                None => required.insert(field.to_string()),
+            };
+        }
+        (required, optional)
+    }
+
+    // Asserts the serialized value has every required field and nothing undeclared
+    fn assert_matches<T: Serialize>(name: &str, value: &T) {
+        let json = serde_json::to_value(value).unwrap();
+        let Value::Object(object) = json else {
+            panic!("{} does not serialize to an object", name);
+        };
+        let (required, optional) = interface_fields(name);
+
+        for key in object.keys() {
+            assert!(
+                required.contains(key) || optional.contains(key),
+                "{}.{} is missing from types.d.ts",
+                name,
+                key
+            );
+        }
+        for field in &required {
+            assert!(
+                object.contains_key(field),
+                "{}.{} is declared but never serialized",
+                name,
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn test_typescript_interfaces_match_results() {
+        let data = "id,name,joined,status,email\n\
+                    1,Ada,2024-01-01,active,ada@example.com\n\
+                    2,Alan,2024-01-03,active,alan@example.com\n\
+                    3,Grace,2024-01-04,gone,grace@example.com\n\
+                    3,Grace,2024-01-04,gone,grace@example.com";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let profile = csv.build_profile();
+        assert_matches("ProfileReport", &profile);
+        let metrics = csv.last_analysis_metrics().unwrap();
+        assert_matches("AnalysisMetrics", metrics);
+        assert_matches("PhaseTimings", &metrics.phases);
+        assert_matches("AnalysisWarning", &profile.warnings[0]);
+
+        let id = csv.column_metadata(0).unwrap();
+        assert_matches("ColumnMetadata", id);
+        assert_matches("NumericStats", id.numeric_stats.as_ref().unwrap());
+        assert_matches(
+            "SentinelCount",
+            &SentinelCount {
+                value: -999.0,
+                count: 2,
+            },
+        );
+        let name = csv.column_metadata(1).unwrap();
+        assert_matches("TextStats", name.text_stats.as_ref().unwrap());
+        let comments = vec!["The parcel came late and the box was torn".to_string()];
+        assert_matches("TokenStats", &calculate_token_stats(&comments).unwrap());
+        let emails = vec!["ann@acme.io".to_string(), "bob@gmail".to_string()];
+        let email_stats = calculate_email_stats(&emails).unwrap();
+        assert_matches("EmailStats", &email_stats);
+        assert_matches("EmailSuggestion", &email_stats.near_valid[0]);
+        let phones = vec!["+44 20 7946 0958".to_string()];
+        let phone_stats = calculate_phone_stats(&phones).unwrap();
+        assert_matches("PhoneStats", &phone_stats);
+        assert_matches("CountryCodeCount", &phone_stats.country_codes[0]);
+        let joined = csv.column_metadata(2).unwrap();
+        assert_matches("DateStats", joined.date_stats.as_ref().unwrap());
+        assert_matches("TypeCandidate", &joined.type_candidates[0]);
+
+        assert_matches("AnalysisConfig", csv.analysis_config());
+        assert_matches("DetectionConfig", &csv.analysis_config().detection);
+        assert_matches("MemoryConfig", &csv.analysis_config().memory);
+        assert_matches("Dialect", csv.dialect());
+        assert_matches("TransposeCheck", &csv.check_transposed());
+        assert_matches(
+            "ParseOptions",
+            &ParseOptions {
+                dialect: Some(csv.dialect().clone()),
+                comment_prefix: Some("#".to_string()),
+                ..ParseOptions::default()
+            },
+        );
+        let layout = FixedWidthLayout::detect("id  name\n 1  Ada\n 2  Alan");
+        assert_matches("FixedWidthLayout", &layout);
+        assert_matches("FixedWidthColumn", &layout.columns[0]);
+        assert_matches("TypeExplanation", &csv.type_explanation(0, 5).unwrap());
+        assert_matches("DuplicateReport", &csv.duplicate_report(None).unwrap());
+        assert_matches("PiiReport", &detect_pii(&csv.get_columns()));
+        assert_matches("PatternReport", &csv.pattern_report(1).unwrap());
+        assert_matches("LinearFit", &csv.linear_fit(0, 0).unwrap());
+        assert_matches("Association", &csv.column_association(3, 1).unwrap());
+        assert_matches("RowPage", &csv.row_page(0, 2).unwrap());
+        let duckdb = csv.to_duckdb_statements("people").unwrap();
+        assert_matches("DuckDbStatements", &duckdb);
+        assert_matches("DuckDbColumn", &duckdb.columns[0]);
+        let schema = csv.to_sql_schema("people", &SqlOptions::default()).unwrap();
+        assert_matches("SqlSchema", &schema);
+        assert_matches("SqlColumn", &schema.columns[0]);
+        assert_matches("SqlOptions", &SqlOptions::default());
+        let options = SqlOptions {
+            layout_hints: true,
+            index_hints: true,
+            ..SqlOptions::default()
+        };
+        let schema = csv.to_sql_schema("people", &options).unwrap();
+        assert_matches("TableLayout", &schema.layout.unwrap());
+        assert_matches("IndexSuggestion", &schema.indexes[0]);
+        let mut counts = CSV::from_string("count\n1\n3000000000".to_string()).unwrap();
+        counts.infer_column_types();
+        counts.override_type(0, DataType::Integer).unwrap();
+        let preview = counts.preview_coercions(SqlDialect::Postgres).unwrap();
+        let mut ids = CSV::from_string("id\n12345678901234567890".to_string()).unwrap();
+        ids.infer_column_types();
+        let big_integer = ids.column_metadata(0).unwrap().big_integer.as_ref();
+        assert_matches("BigIntegerColumn", big_integer.unwrap());
+        assert_matches("CoercionPreview", &preview);
+        assert_matches("ColumnCoercions", &preview.columns[0]);
+        assert_matches("CoercionIssue", &preview.columns[0].issues[0]);
+        let unique = csv.dedupe(None).unwrap();
+        assert_matches("CsvDiff", &unique.diff_against(&unique, &[0]).unwrap());
+
+        let report = csv
+            .with_history("replace", |csv| csv.replace_in_column(3, "gone", "left"))
+            .unwrap();
+        assert_matches("ReplaceReport", &report);
+        let mut sizes = CSV::from_string("size\nS\nM\nS".to_string()).unwrap();
+        sizes.override_type(0, DataType::Categorical).unwrap();
+        let levels = sizes.rename_level(0, "S", "Small").unwrap();
+        assert_matches("LevelReport", &levels);
+        assert_matches("LevelCount", &levels.levels[0]);
+        let mut sizes = CSV::from_string("size\nS\nM\nL".to_string()).unwrap();
+        sizes.override_type(0, DataType::Categorical).unwrap();
+        let ordinal = sizes.column_metadata(0).unwrap().ordinal.as_ref();
+        assert_matches("OrdinalScale", ordinal.unwrap());
+        assert_matches("OrdinalEncoding", &sizes.ordinal_encoding(0).unwrap());
+        let scaled = counts.scale(&[0], ScaleMethod::Robust).unwrap();
+        assert_matches("ScaledColumns", &scaled);
+        assert_matches("ScaledColumn", &scaled.columns[0]);
+        assert_matches("CleaningStep", &csv.cleaning_steps()[0]);
+        assert_matches("CellChange", &csv.cleaning_steps()[0].changes[0]);
+        let lineage = csv.column_lineage(3).unwrap();
+        assert_matches("ColumnLineage", &lineage);
+        assert_matches("LineageStep", &lineage.steps[0]);
+        assert_matches("HistoryReport", &csv.history_report());
+        let mut normalized = CSV::from_string("Sp. Atk\n1".to_string()).unwrap();
+        assert_matches(
+            "HeaderRename",
+            &normalized.normalize_headers(HeaderStyle::Snake).unwrap()[0],
+        );
+        assert_matches(
+            "LogRecord",
+            &LogRecord {
+                level: "info".to_string(),
+                target: targets::CLEANING.to_string(),
+                message: "Applied replace".to_string(),
+            },
+        );
+
+        assert_matches(
+            "InferenceProgress",
+            &csv.infer_column_types_slice(f64::INFINITY, |_| {}),
+        );
+        assert_matches("AppendReport", &csv.append_rows("9,9").unwrap());
+        assert_matches("RetryPolicy", &RetryPolicy::default());
+        assert_matches("FetchOptions", &FetchOptions::default());
+        assert_matches(
+            "FetchProgress",
+            &FetchProgress {
+                loaded: 10,
+                total: Some(20),
+            },
+        );
+        let batch = analyze_files(
+            vec![
+                ("ok.csv".to_string(), "a\n1".to_string()),
+                ("bad.csv".to_string(), "a\n1,2".to_string()),
+            ],
+            &AnalysisConfig::default(),
+        )
+        .unwrap();
+        assert_matches("BatchReport", &batch);
+        assert_matches("FileReport", &batch.files["ok.csv"]);
+        assert_matches("FileReport", &batch.files["bad.csv"]);
+        let mut config = AnalysisConfig::default();
+        config.memory.limit_bytes = Some(600);
+        assert_matches(
+            "BoundedAnalysis",
+            &analyze_with_memory_limit(data, &config).unwrap(),
+        );
+        let mut queue = ColumnQueue::for_csv(&csv, 1, RetryPolicy::default());
+        for attempt in 0..2 {
+            let column = queue.next_column(attempt).unwrap();
+            queue.fail(column, "worker threw").unwrap();
+        }
+        assert_matches("QueueReport", queue.report());
+        assert_matches("ColumnFailure", &queue.report().failed[0]);
+        let packed = pack_column(csv.get_column(0).unwrap().1).unwrap();
+        let config = AnalysisConfig::default();
+        assert_matches(
+            "PackedColumnResult",
+            &PackedColumnResult::analyze(0, "id".to_string(), &packed, config.clone()),
+        );
+        assert_matches(
+            "PackedColumnResult",
+            &PackedColumnResult::analyze(0, "id".to_string(), &[], config),
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use js_sys::{ArrayBuffer, Function, Reflect, Uint8Array};
+use serde::Serialize;
+use serde_wasm_bindgen::{from_value, to_value, Serializer};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::csv::JsCsv;
+use super::{optional_config, serialization_error};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::parallel::{pack_column, ColumnQueue, PackedColumnResult, RetryPolicy};
+
+// Global set by the `worker_bundle` script before it loads the package, telling `start` it
+// runs in the generated worker
+const WORKER_FLAG: &str = "__tabular_analysis_worker";
+
+// Script of the generated worker. Messages arriving while the package loads are queued and
+// handed to the handler `start` installs once it has.
+#[cfg(feature = "browser")]
+const WORKER_SCRIPT: &str = r#"self.__tabular_analysis_worker = true;
+const pending = [];
+self.onmessage = (event) => pending.push(event);
+const { default: init } = await import(MODULE_URL);
+await init();
+for (const event of pending) self.onmessage(event);
+"#;
+
+#[wasm_bindgen(js_class = CSV)]
+impl JsCsv {
+    /// Packs a column's values into an `ArrayBuffer` for a worker. Posting it as a transferable,
+    /// `worker.postMessage({ header, buffer }, [buffer])`, moves it without cloning each value.
+    #[wasm_bindgen]
+    pub fn pack_column(&self, index: usize) -> Result<ArrayBuffer, JsValue> {
+        let (_, values) = self
+            .inner
+            .get_column(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        let packed = pack_column(values)?;
+        Ok(Uint8Array::from(packed.as_slice()).buffer())
+    }
+}
+
+/// Whether this environment can start web workers. Without them, `CSV.analyze`
+/// analyzes on the calling thread while letting the event loop run between slices.
+#[wasm_bindgen]
+pub fn workers_available() -> bool {
+    js_sys::Reflect::get(&js_sys::global(), &"Worker".into())
+        .is_ok_and(|worker| worker.is_function())
+}
+
+/// Runs when the package is initialized. Inside a worker started from `worker_bundle` it
+/// answers each `{ column, header, buffer, config }` message, with `buffer` from
+/// `CSV.pack_column`, by posting a `PackedColumnResult`; anywhere else it does nothing.
+#[wasm_bindgen(start)]
+pub fn start() {
+    let global = js_sys::global();
+    let is_worker = Reflect::get(&global, &WORKER_FLAG.into()).is_ok_and(|flag| flag.is_truthy());
+    if !is_worker {
+        return;
+    }
+
+    let scope = global.clone();
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        let reply = match answer_packed_column(&event) {
+            Ok(reply) => reply,
+            Err(error) => error,
+        };
+        if let Ok(post) = Reflect::get(&scope, &"postMessage".into()) {
+            let _ = post.unchecked_into::<Function>().call1(&scope, &reply);
+        }
+    });
+    let _ = Reflect::set(&global, &"onmessage".into(), handler.as_ref());
+    // The handler lives as long as the worker
+    handler.forget();
+}
+
+// Analyzes the column in a message posted to the generated worker
+fn answer_packed_column(event: &JsValue) -> Result<JsValue, JsValue> {
+    let data = Reflect::get(event, &"data".into())?;
+    let field = |name: &str| Reflect::get(&data, &name.into());
+    let invalid =
+        || AnalysisError::invalid_argument("Worker messages need column, header and buffer");
+
+    let column = field("column")?.as_f64().ok_or_else(invalid)? as usize;
+    let header = field("header")?.as_string().ok_or_else(invalid)?;
+    let buffer: ArrayBuffer = field("buffer")?.dyn_into().map_err(|_| invalid())?;
+    let config = optional_config(field("config")?)?;
+
+    let packed = Uint8Array::new(&buffer).to_vec();
+    PackedColumnResult::analyze(column, header, &packed, config)
+        .serialize(&Serializer::json_compatible())
+        .map_err(|e| serialization_error("worker result", e))
+}
+
+/// Object URL of a module worker script that loads this package from `module_url`, the URL
+/// of its JS file, and answers packed columns, so no worker script has to be written:
+/// `new Worker(worker_bundle(url), { type: "module" })`. Relative URLs resolve against the
+/// page; revoke the URL with `URL.revokeObjectURL` once the workers are started.
+#[cfg(feature = "browser")]
+#[wasm_bindgen]
+pub fn worker_bundle(module_url: &str) -> Result<String, JsValue> {
+    use web_sys::{Blob, BlobPropertyBag, Url};
+
+    let base = Reflect::get(&js_sys::global(), &"location".into())
+        .and_then(|location| Reflect::get(&location, &"href".into()))?
+        .as_string();
+    let url = match base {
+        Some(base) => Url::new_with_base(module_url, &base)?.href(),
+        None => module_url.to_string(),
+    };
+    let quoted = serde_json::to_string(&url).map_err(|e| serialization_error("module URL", e))?;
+    let script = WORKER_SCRIPT.replace("MODULE_URL", &quoted);
+
+    let options = BlobPropertyBag::new();
+    options.set_type("text/javascript");
+    let parts = js_sys::Array::of1(&script.into());
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    Url::create_object_url_with_blob(&blob)
+}
+
+/// Detects the type and statistics of a column packed with `CSV.pack_column`, for use inside
+/// the worker it was posted to. Default analysis options apply when `config` is undefined.
+#[wasm_bindgen(unchecked_return_type = "ColumnMetadata")]
+pub fn analyze_packed_column(
+    header: String,
+    buffer: &ArrayBuffer,
+    #[wasm_bindgen(unchecked_param_type = "AnalysisConfig | undefined")] config: JsValue,
+) -> Result<JsValue, JsValue> {
+    let config = optional_config(config)?;
+    let packed = Uint8Array::new(buffer).to_vec();
+    let metadata = CSV::analyze_packed_column(header, &packed, config)?;
+    to_value(&metadata).map_err(|e| serialization_error("metadata", e))
+}
+
+/// Column queue for a pool of workers, exported as `ColumnQueue`: an idle worker takes
+/// `next_column(worker)`, packs it with `CSV.pack_column` and reports back with
+/// `complete(index)` or `fail(index, message)`, so slow columns don't delay the rest and only
+/// `max_in_flight` packed copies exist at once. Call `expire_timed_out()` periodically to
+/// requeue columns from hung workers.
+#[wasm_bindgen(js_name = ColumnQueue)]
+pub struct JsColumnQueue {
+    inner: ColumnQueue,
+}
+
+#[wasm_bindgen(js_class = ColumnQueue)]
+impl JsColumnQueue {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        csv: &JsCsv,
+        max_in_flight: usize,
+        #[wasm_bindgen(unchecked_param_type = "RetryPolicy | undefined")] policy: JsValue,
+    ) -> Result<JsColumnQueue, JsValue> {
+        let policy: RetryPolicy = if policy.is_undefined() {
+            RetryPolicy::default()
+        } else {
+            from_value(policy).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to deserialize retry policy: {}", e),
+            })?
+        };
+        Ok(JsColumnQueue {
+            inner: ColumnQueue::for_csv(&csv.inner, max_in_flight, policy),
+        })
+    }
+
+    /// Index of the next column for `worker` to analyze, or undefined when none is free to
+    /// hand out
+    #[wasm_bindgen]
+    pub fn next_column(&mut self, worker: usize) -> Option<usize> {
+        self.inner.next_column(worker)
+    }
+
+    #[wasm_bindgen]
+    pub fn complete(&mut self, column: usize) -> Result<(), JsValue> {
+        self.inner.complete(column).map_err(JsValue::from)
+    }
+
+    /// Records a failed attempt, e.g. after the worker threw; the column is tried again on
+    /// another worker while it has attempts left
+    #[wasm_bindgen]
+    pub fn fail(&mut self, column: usize, message: &str) -> Result<(), JsValue> {
+        self.inner.fail(column, message).map_err(JsValue::from)
+    }
+
+    /// Fails columns out past the timeout, returning the workers to terminate and replace
+    #[wasm_bindgen(unchecked_return_type = "number[]")]
+    pub fn expire_timed_out(&mut self) -> Result<JsValue, JsValue> {
+        to_value(&self.inner.expire_timed_out()).map_err(|e| serialization_error("workers", e))
+    }
+
+    /// Columns analyzed and given up on so far; once `is_done()`, the partial results
+    #[wasm_bindgen(unchecked_return_type = "QueueReport")]
+    pub fn report(&self) -> Result<JsValue, JsValue> {
+        to_value(self.inner.report()).map_err(|e| serialization_error("queue report", e))
+    }
+
+    #[wasm_bindgen]
+    pub fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_done(&self) -> bool {
+        self.inner.is_done()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::ColumnMetadata;
+    use crate::parallel::QueueReport;
+    use crate::types::DataType;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn test_packed_column_round_trip() {
+        let csv = JsCsv::new("id,name\n1,ann\n2,bo\n3,cy".to_string()).unwrap();
+        let buffer = csv.pack_column(0).unwrap();
+
+        let metadata: ColumnMetadata = from_value(
+            analyze_packed_column("id".to_string(), &buffer, JsValue::UNDEFINED).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.data_type, DataType::Integer);
+        assert!(csv.pack_column(2).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_column_queue() {
+        let csv = JsCsv::new("id,notes\n1,a long note\n2,another long note".to_string()).unwrap();
+        let mut queue = JsColumnQueue::new(&csv, 1, JsValue::UNDEFINED).unwrap();
+        assert_eq!(queue.next_column(0), Some(1));
+        assert_eq!(queue.next_column(1), None);
+        queue.complete(1).unwrap();
+        assert_eq!(queue.next_column(1), Some(0));
+        assert!(queue.complete(1).is_err());
+        queue.fail(0, "worker threw").unwrap();
+        assert_eq!(queue.next_column(1), Some(0));
+        queue.complete(0).unwrap();
+        assert!(queue.is_done());
+
+        let report: QueueReport = from_value(queue.report().unwrap()).unwrap();
+        assert_eq!(report.completed, vec![1, 0]);
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// A single cell modified by a cleaning operation
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CellChange {
+    pub row: usize,
+    pub column: usize,
+    pub old_value: String,
+    pub new_value: String,
+    /// Short machine-readable explanation, e.g. `impute:median`
+    pub reason: String,
+}
+
+/// One cleaning operation and every cell it changed, kept so cleaned exports can be audited
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CleaningStep {
+    pub operation: String,
+    pub column: Option<usize>,
+    pub changes: Vec<CellChange>,
+}
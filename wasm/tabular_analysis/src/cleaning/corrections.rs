@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::analysis::contract::{check_value, ViolationKind};
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::types::{
+    date::DateType, email::EmailType, locale::SynonymDictionary, phone::PhoneType, DataType,
+    TypeDetection,
+};
+
+/// A value that doesn't match its column's type, with a proposed fix when one was found
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Correction {
+    pub row: usize,
+    pub column: usize,
+    pub value: String,
+    pub suggestion: Option<String>,
+    /// How likely the suggestion is what the value meant, from 0 to 1; 0 without one
+    pub confidence: f64,
+    /// Short machine-readable explanation, e.g. `number:strip_symbols`
+    pub reason: String,
+}
+
+/// Which suggestions `apply_suggestions` may apply
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CorrectionPolicy {
+    /// Suggestions below this confidence are only flagged
+    pub min_confidence: f64,
+    /// Columns to correct; every analyzed column when None
+    pub columns: Option<Vec<usize>>,
+}
+
+impl Default for CorrectionPolicy {
+    fn default() -> Self {
+        CorrectionPolicy {
+            min_confidence: 0.9,
+            columns: None,
+        }
+    }
+}
+
+/// Cells changed by `apply_suggestions` and the problems left for review
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CorrectionReport {
+    pub applied: Vec<CellChange>,
+    /// Mismatching values without a suggestion or with one below the confidence floor
+    pub flagged: Vec<Correction>,
+}
+
+// Numbers wrapped in stray symbols or text, rewritten in the column's number format
+fn suggest_number(value: &str, metadata: &ColumnMetadata) -> Option<(String, f64, &'static str)> {
+    let format = metadata.number_format.unwrap_or_default();
+    let kept: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || "-.,()".contains(*c))
+        .collect();
+    let number = format.parse(&kept)?;
+    if metadata.data_type == DataType::Integer && number.fract() != 0.0 {
+        return None;
+    }
+
+    let dropped_text = value
+        .chars()
+        .any(|c| c.is_alphabetic() && !kept.contains(c));
+    let (confidence, reason) = if dropped_text {
+        (0.6, "number:strip_text")
+    } else {
+        (0.9, "number:strip_symbols")
+    };
+    Some((format.format(number), confidence, reason))
+}
+
+/// Proposes a fix for a value that doesn't match its column's type, as
+/// `(suggestion, confidence, reason)`
+pub fn suggest_correction(
+    value: &str,
+    metadata: &ColumnMetadata,
+) -> Option<(String, f64, &'static str)> {
+    let value = value.trim();
+    match metadata.data_type {
+        DataType::Integer | DataType::Decimal | DataType::Currency => {
+            suggest_number(value, metadata)
+        }
+        DataType::Date => {
+            // Dates written with unusual separators, like "2024.03.19" or "2024 03 19"
+            let dashed = value.replace(['.', ' ', '_'], "-");
+            DateType::normalize(&dashed).map(|date| (date, 0.8, "date:separators"))
+        }
+        DataType::Email => {
+            let compact: String = value
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+                .to_lowercase();
+            EmailType::is_definite_match(&compact).then_some((compact, 0.9, "email:whitespace"))
+        }
+        DataType::Phone => {
+            let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+            PhoneType::is_definite_match(&digits).then_some((digits, 0.7, "phone:digits"))
+        }
+        _ => None,
+    }
+}
+
+// Categories written with a different case or spacing than their most common spelling
+fn category_variants(values: &[String]) -> HashMap<&str, &str> {
+    let mut spellings: HashMap<String, HashMap<&str, usize>> = HashMap::new();
+    for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        *spellings
+            .entry(value.to_lowercase())
+            .or_default()
+            .entry(value)
+            .or_default() += 1;
+    }
+
+    let mut variants = HashMap::new();
+    for forms in spellings.values().filter(|forms| forms.len() > 1) {
+        let canonical = forms
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(form, _)| *form)
+            .unwrap_or_default();
+        for form in forms.keys().filter(|form| **form != canonical) {
+            variants.insert(*form, canonical);
+        }
+    }
+    variants
+}
+
+/// Finds the values of a column that don't match its inferred type and suggests fixes
+pub fn column_corrections(
+    column: usize,
+    values: &[String],
+    metadata: &ColumnMetadata,
+    synonyms: &SynonymDictionary,
+) -> Vec<Correction> {
+    if metadata.data_type == DataType::Categorical {
+        let variants = category_variants(values);
+        return values
+            .iter()
+            .enumerate()
+            .filter_map(|(row, value)| {
+                let canonical = variants.get(value.trim())?;
+                Some(Correction {
+                    row,
+                    column,
+                    value: value.clone(),
+                    suggestion: Some(canonical.to_string()),
+                    confidence: 0.95,
+                    reason: "category:spelling".to_string(),
+                })
+            })
+            .collect();
+    }
+
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .filter(|(_, value)| {
+            check_value(value, metadata, synonyms) == Some(ViolationKind::TypeMismatch)
+        })
+        .map(|(row, value)| {
+            let suggestion = suggest_correction(value, metadata);
+            Correction {
+                row,
+                column,
+                value: value.clone(),
+                confidence: suggestion.as_ref().map_or(0.0, |s| s.1),
+                reason: suggestion
+                    .as_ref()
+                    .map_or("no_suggestion", |s| s.2)
+                    .to_string(),
+                suggestion: suggestion.map(|s| s.0),
+            }
+        })
+        .collect()
+}
+
+impl CSV {
+    /// Suggests a fix for every value of a column that doesn't parse as its inferred type
+    pub fn column_suggestions(&self, column: usize) -> Result<Vec<Correction>, AnalysisError> {
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let Some(metadata) = self.column_metadata(column) else {
+            return Err(AnalysisError::invalid_column(
+                column,
+                "Column has not been analyzed",
+            ));
+        };
+        let synonyms = self
+            .analysis_config()
+            .detection
+            .synonyms()
+            .unwrap_or_default();
+        Ok(column_corrections(column, values, metadata, &synonyms))
+    }
+
+    /// Applies the suggestions the policy is confident enough in, recording them in the cleaning
+    /// log
+    pub fn apply_corrections(
+        &mut self,
+        policy: &CorrectionPolicy,
+    ) -> Result<CorrectionReport, AnalysisError> {
+        let columns = match &policy.columns {
+            Some(columns) => columns.clone(),
+            // Columns without inferred types have nothing to be checked against
+            None => (0..self.column_count())
+                .filter(|&i| self.column_metadata(i).is_some())
+                .collect(),
+        };
+
+        let mut report = CorrectionReport {
+            applied: Vec::new(),
+            flagged: Vec::new(),
+        };
+        for column in columns {
+            let (accepted, flagged): (Vec<Correction>, Vec<Correction>) = self
+                .column_suggestions(column)?
+                .into_iter()
+                .partition(|c| c.suggestion.is_some() && c.confidence >= policy.min_confidence);
+            report.flagged.extend(flagged);
+            if accepted.is_empty() {
+                continue;
+            }
+
+            let values = self
+                .column_values_mut(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+            let changes: Vec<CellChange> = accepted
+                .into_iter()
+                .map(|correction| {
+                    let new_value = correction.suggestion.unwrap_or_default();
+                    CellChange {
+                        row: correction.row,
+                        column,
+                        old_value: std::mem::replace(
+                            &mut values[correction.row],
+                            new_value.clone(),
+                        ),
+                        new_value,
+                        reason: format!("suggestion:{}", correction.reason),
+                    }
+                })
+                .collect();
+
+            report.applied.extend(changes.iter().cloned());
+            self.record_cleaning_step(CleaningStep {
+                operation: "apply_suggestions".to_string(),
+                column: Some(column),
+                changes,
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyzed(data: &str) -> CSV {
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut data = "amount,email,state\n".to_string();
+        for i in 0..100 {
+            data.push_str(&format!(
+                "{},user{}@example.com,{}\n",
+                i * 10,
+                i,
+                ["CA", "NY", "TX"][i % 3]
+            ));
+        }
+        data.push_str("42*,Bob @Example.com,ca\n");
+        data.push_str("about 7,not an email,NY\n");
+        let csv = analyzed(&data);
+
+        let test_cases = vec![
+            (0, 100, Some("42"), 0.9),
+            (0, 101, Some("7"), 0.6),
+            (1, 100, Some("bob@example.com"), 0.9),
+            (1, 101, None, 0.0),
+            (2, 100, Some("CA"), 0.95),
+        ];
+        for (column, row, suggestion, confidence) in test_cases {
+            let corrections = csv.column_suggestions(column).unwrap();
+            let correction = corrections
+                .iter()
+                .find(|c| c.row == row)
+                .unwrap_or_else(|| panic!("Failed for input: {} {}", column, row));
+            assert_eq!(correction.suggestion.as_deref(), suggestion);
+            assert_eq!(correction.confidence, confidence);
+        }
+    }
+
+    #[test]
+    fn test_apply_suggestions() {
+        let mut data = "amount,state\n".to_string();
+        for i in 0..100 {
+            data.push_str(&format!("{},{}\n", i, ["CA", "NY", "TX"][i % 3]));
+        }
+        data.push_str("12*,ca\nabout 7,NY\n");
+        let mut csv = analyzed(&data);
+
+        let report = csv.apply_corrections(&CorrectionPolicy::default()).unwrap();
+        let applied: Vec<(usize, usize, &str)> = report
+            .applied
+            .iter()
+            .map(|c| (c.row, c.column, c.new_value.as_str()))
+            .collect();
+        assert_eq!(applied, vec![(100, 0, "12"), (100, 1, "CA")]);
+        assert_eq!(report.flagged.len(), 1);
+        assert_eq!(report.flagged[0].value, "about 7");
+        assert_eq!(csv.get_column(0).unwrap().1[100], "12");
+        assert_eq!(csv.cleaning_steps().len(), 2);
+        assert_eq!(
+            csv.cleaning_steps()[0].changes[0].reason,
+            "suggestion:number:strip_symbols"
+        );
+
+        let lenient = CorrectionPolicy {
+            min_confidence: 0.5,
+            columns: Some(vec![0]),
+        };
+        let report = csv.apply_corrections(&lenient).unwrap();
+        assert_eq!(report.applied.len(), 1);
+        assert!(report.flagged.is_empty());
+
+        assert!(csv
+            .apply_corrections(&CorrectionPolicy {
+                columns: Some(vec![5]),
+                ..CorrectionPolicy::default()
+            })
+            .is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::date::{Date, DateFormat};
+
+// Formats whose component order is fixed, tried before the ambiguous ones
+const UNAMBIGUOUS_FORMATS: [DateFormat; 2] = [DateFormat::Iso8601, DateFormat::JapaneseSlash];
+// Month-first formats, each paired with its day-first counterpart through `swapped_day_month`
+const AMBIGUOUS_FORMATS: [DateFormat; 2] = [DateFormat::UsSlash, DateFormat::UsDash];
+
+/// Outcome of rewriting a date column to ISO-8601
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DateStandardizationReport {
+    pub column: usize,
+    /// Whether ambiguous values like `03/04/2024` were read as day-first
+    pub day_first: bool,
+    /// Values that were unambiguously day-first or month-first, used to pick the order
+    pub day_first_evidence: usize,
+    pub month_first_evidence: usize,
+    /// Number of values rewritten to a different string
+    pub converted: usize,
+    /// Non-empty values that could not be parsed in any known format and were left untouched
+    pub unparseable_rows: Vec<usize>,
+    /// How many values were read in each source format
+    pub format_counts: Vec<(DateFormat, usize)>,
+}
+
+/// Decides whether ambiguous dates in a column are day-first by counting values
+/// that can only be read one way, e.g. `25/12/2024` (day-first) or `12/25/2024` (month-first).
+/// Ties default to month-first, matching single-value detection.
+pub fn resolve_day_first(values: &[String]) -> (bool, usize, usize) {
+    let mut day_first = 0;
+    let mut month_first = 0;
+
+    for value in values {
+        for format in AMBIGUOUS_FORMATS {
+            let swapped = format.swapped_day_month().unwrap_or(format);
+            match (
+                Date::parse_as(value, format).is_some(),
+                Date::parse_as(value, swapped).is_some(),
+            ) {
+                (true, false) => month_first += 1,
+                (false, true) => day_first += 1,
+                _ => {}
+            }
+        }
+    }
+
+    (day_first > month_first, day_first, month_first)
+}
+
+/// Rows holding dates like `03/04/2024` that name a different day when read day-first
+/// than month-first
+pub fn ambiguous_date_rows(values: &[String]) -> Vec<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| {
+            AMBIGUOUS_FORMATS.iter().any(|&format| {
+                let swapped = format.swapped_day_month().unwrap_or(format);
+                match (
+                    Date::parse_as(value, format),
+                    Date::parse_as(value, swapped),
+                ) {
+                    (Some(month_first), Some(day_first)) => {
+                        month_first.month() != day_first.month()
+                    }
+                    _ => false,
+                }
+            })
+        })
+        .map(|(row, _)| row)
+        .collect()
+}
+
+/// Parses a value using the column-level day/month order, falling back to the other order
+/// only for values that cannot be read the preferred way
+pub fn parse_with_order(value: &str, day_first: bool) -> Option<Date> {
+    if let Some(date) = UNAMBIGUOUS_FORMATS
+        .iter()
+        .find_map(|&format| Date::parse_as(value, format))
+    {
+        return Some(date);
+    }
+
+    AMBIGUOUS_FORMATS.iter().find_map(|&month_first_format| {
+        let day_first_format = month_first_format
+            .swapped_day_month()
+            .unwrap_or(month_first_format);
+        let (preferred, fallback) = if day_first {
+            (day_first_format, month_first_format)
+        } else {
+            (month_first_format, day_first_format)
+        };
+        Date::parse_as(value, preferred).or_else(|| Date::parse_as(value, fallback))
+    })
+}
+
+/// Rewrites every parseable date to ISO-8601 in place, returning the audit records for changed cells
+pub fn standardize_dates(
+    column: usize,
+    values: &mut [String],
+) -> (DateStandardizationReport, Vec<CellChange>) {
+    let (day_first, day_first_evidence, month_first_evidence) = resolve_day_first(values);
+    let mut format_counts: HashMap<DateFormat, usize> = HashMap::new();
+    let mut unparseable_rows = Vec::new();
+    let mut changes = Vec::new();
+
+    for (row, value) in values.iter_mut().enumerate() {
+        if value.trim().is_empty() {
+            continue;
+        }
+
+        match parse_with_order(value, day_first) {
+            Some(date) => {
+                *format_counts.entry(date.format()).or_insert(0) += 1;
+                let iso = date.to_format(DateFormat::Iso8601);
+                if *value != iso {
+                    changes.push(CellChange {
+                        row,
+                        column,
+                        old_value: std::mem::replace(value, iso.clone()),
+                        new_value: iso,
+                        reason: "standardize_date".to_string(),
+                    });
+                }
+            }
+            None => unparseable_rows.push(row),
+        }
+    }
+
+    let mut format_counts: Vec<(DateFormat, usize)> = format_counts.into_iter().collect();
+    format_counts.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then(format!("{:?}", a.0).cmp(&format!("{:?}", b.0)))
+    });
+
+    let report = DateStandardizationReport {
+        column,
+        day_first,
+        day_first_evidence,
+        month_first_evidence,
+        converted: changes.len(),
+        unparseable_rows,
+        format_counts,
+    };
+    (report, changes)
+}
+
+impl CSV {
+    /// Rewrites a column of mixed-format dates to ISO-8601, recording the changes
+    pub fn standardize_date_column(
+        &mut self,
+        column: usize,
+    ) -> Result<DateStandardizationReport, AnalysisError> {
+        let values = self
+            .column_values_mut(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let (report, changes) = standardize_dates(column, values);
+
+        self.record_cleaning_step(CleaningStep {
+            operation: "standardize_dates".to_string(),
+            column: Some(column),
+            changes,
+        });
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_day_first() {
+        // 25/12 can only be day-first
+        let values = column(&["03/04/2024", "25/12/2024", "2024-01-01"]);
+        assert_eq!(resolve_day_first(&values), (true, 1, 0));
+
+        // 12/25 can only be month-first
+        let values = column(&["03/04/2024", "12/25/2024"]);
+        assert_eq!(resolve_day_first(&values), (false, 0, 1));
+
+        // No evidence defaults to month-first
+        let values = column(&["03/04/2024", "2024-01-01"]);
+        assert_eq!(resolve_day_first(&values), (false, 0, 0));
+    }
+
+    #[test]
+    fn test_ambiguous_date_rows() {
+        let values = column(&["03/04/2024", "25/12/2024", "2024-01-01", "05/05/2024", ""]);
+        assert_eq!(ambiguous_date_rows(&values), vec![0]);
+    }
+
+    #[test]
+    fn test_standardize_mixed_formats() {
+        let mut values = column(&[
+            "2024-01-01",
+            "15/01/2024",
+            "03/02/2024",
+            "2024/01/30",
+            "01-02-2024",
+            "",
+            "not a date",
+        ]);
+        let (report, changes) = standardize_dates(0, &mut values);
+
+        assert!(report.day_first);
+        assert_eq!(
+            values,
+            column(&[
+                "2024-01-01",
+                "2024-01-15",
+                "2024-02-03",
+                "2024-01-30",
+                "2024-02-01",
+                "",
+                "not a date",
+            ])
+        );
+        assert_eq!(report.converted, 4);
+        assert_eq!(report.unparseable_rows, vec![6]);
+        assert_eq!(changes[0].old_value, "15/01/2024");
+        assert_eq!(report.format_counts[0], (DateFormat::EuropeanSlash, 2));
+    }
+
+    #[test]
+    fn test_minority_order_falls_back() {
+        // Mostly month-first, but 31/01 can only be read day-first
+        let mut values = column(&["12/25/2024", "11/30/2024", "31/01/2024"]);
+        let (report, _) = standardize_dates(0, &mut values);
+
+        assert!(!report.day_first);
+        assert_eq!(values, column(&["2024-12-25", "2024-11-30", "2024-01-31"]));
+        assert!(report.unparseable_rows.is_empty());
+    }
+
+    #[test]
+    fn test_csv_standardize_dates() {
+        let data = "joined\n2024-01-01\n01/15/2024\n2024/01/30";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+
+        let report = csv.standardize_date_column(0).unwrap();
+        assert_eq!(report.converted, 2);
+
+        let (_, values) = csv.get_column(0).unwrap();
+        assert_eq!(values, &["2024-01-01", "2024-01-15", "2024-01-30"]);
+        assert_eq!(csv.cleaning_steps()[0].changes.len(), 2);
+        assert!(csv.standardize_date_column(4).is_err());
+    }
+}
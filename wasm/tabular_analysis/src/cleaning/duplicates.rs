@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+/// A set of rows whose compared values are identical
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    /// Index of the first occurrence, which is the row kept by deduplication
+    pub first_row: usize,
+    /// Indices of every later row repeating the first one
+    pub duplicate_rows: Vec<usize>,
+}
+
+/// Summary of exact-duplicate rows found in a table
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DuplicateReport {
+    /// Columns compared to decide whether two rows are duplicates
+    pub key_columns: Vec<usize>,
+    /// Number of rows that would be removed by deduplication
+    pub duplicate_row_count: usize,
+    pub groups: Vec<DuplicateGroup>,
+}
+
+impl DuplicateReport {
+    /// Returns the sorted indices of every row that repeats an earlier one
+    pub fn duplicate_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self
+            .groups
+            .iter()
+            .flat_map(|group| group.duplicate_rows.iter().copied())
+            .collect();
+        rows.sort_unstable();
+        rows
+    }
+}
+
+/// Groups rows with identical values across the given key columns.
+/// Values are compared exactly, so whitespace and casing differences are kept distinct.
+pub fn find_duplicates(
+    key_columns: Vec<usize>,
+    key_values: &[&[String]],
+    row_count: usize,
+) -> DuplicateReport {
+    let mut first_seen: HashMap<Vec<&str>, usize> = HashMap::new();
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+
+    for row in 0..row_count {
+        let key: Vec<&str> = key_values
+            .iter()
+            .map(|values| values.get(row).map(|v| v.as_str()).unwrap_or(""))
+            .collect();
+
+        match first_seen.get(&key) {
+            Some(&group_index) => groups[group_index].duplicate_rows.push(row),
+            None => {
+                first_seen.insert(key, groups.len());
+                groups.push(DuplicateGroup {
+                    first_row: row,
+                    duplicate_rows: Vec::new(),
+                });
+            }
+        }
+    }
+
+    // Only keep groups that actually repeat
+    groups.retain(|group| !group.duplicate_rows.is_empty());
+    let duplicate_row_count = groups.iter().map(|g| g.duplicate_rows.len()).sum();
+
+    DuplicateReport {
+        key_columns,
+        duplicate_row_count,
+        groups,
+    }
+}
+
+impl CSV {
+    /// Reports exact-duplicate rows, comparing the full row or only the given key columns
+    pub fn duplicate_report(
+        &self,
+        keys: Option<Vec<usize>>,
+    ) -> Result<DuplicateReport, AnalysisError> {
+        let keys = keys.unwrap_or_else(|| (0..self.column_count()).collect());
+
+        let key_values = keys
+            .iter()
+            .map(|&index| {
+                self.get_column(index)
+                    .map(|(_, values)| values)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })
+            })
+            .collect::<Result<Vec<&[String]>, AnalysisError>>()?;
+
+        Ok(find_duplicates(keys, &key_values, self.row_count()))
+    }
+
+    /// Returns a copy of the CSV keeping only the first occurrence of each duplicate row
+    pub fn dedupe(&self, keys: Option<Vec<usize>>) -> Result<CSV, AnalysisError> {
+        let duplicates = self.duplicate_report(keys)?.duplicate_rows();
+        let kept_rows: Vec<usize> = (0..self.row_count())
+            .filter(|row| duplicates.binary_search(row).is_err())
+            .collect();
+
+        Ok(self.select_rows(&kept_rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_full_row_duplicates() {
+        let ids = column(&["1", "2", "1", "3", "1"]);
+        let names = column(&["a", "b", "a", "c", "a"]);
+        let report = find_duplicates(vec![0, 1], &[&ids, &names], 5);
+
+        assert_eq!(report.duplicate_row_count, 2);
+        assert_eq!(
+            report.groups,
+            vec![DuplicateGroup {
+                first_row: 0,
+                duplicate_rows: vec![2, 4],
+            }]
+        );
+        assert_eq!(report.duplicate_rows(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_key_subset_duplicates() {
+        let emails = column(&["a@x.com", "b@x.com", "a@x.com", "b@x.com"]);
+        let report = find_duplicates(vec![1], &[&emails], 4);
+
+        assert_eq!(report.key_columns, vec![1]);
+        assert_eq!(report.duplicate_row_count, 2);
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.duplicate_rows(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_exact_comparison() {
+        // Whitespace and case differences are not exact duplicates
+        let values = column(&["Active", "active", "Active ", "Active"]);
+        let report = find_duplicates(vec![0], &[&values], 4);
+
+        assert_eq!(report.duplicate_row_count, 1);
+        assert_eq!(report.duplicate_rows(), vec![3]);
+    }
+
+    #[test]
+    fn test_csv_dedupe() {
+        let data = "id,name\n1,a\n2,b\n1,a\n3,b";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let report = csv.duplicate_report(None).unwrap();
+        assert_eq!(report.key_columns, vec![0, 1]);
+        assert_eq!(report.duplicate_row_count, 1);
+
+        let deduped = csv.dedupe(None).unwrap();
+        assert_eq!(deduped.row_count(), 3);
+
+        // Deduplicating on the name column only keeps the first "a" and "b"
+        let deduped = csv.dedupe(Some(vec![1])).unwrap();
+        assert_eq!(deduped.row_count(), 2);
+        let (_, ids) = deduped.get_column(0).unwrap();
+        assert_eq!(ids, &["1", "2"]);
+
+        assert!(csv.duplicate_report(Some(vec![5])).is_err());
+    }
+
+    #[test]
+    fn test_no_duplicates() {
+        let values = column(&["1", "2", "3"]);
+        let report = find_duplicates(vec![0], &[&values], 3);
+        assert_eq!(report.duplicate_row_count, 0);
+        assert!(report.groups.is_empty());
+
+        let report = find_duplicates(vec![], &[], 0);
+        assert_eq!(report.duplicate_row_count, 0);
+    }
+}
@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::analysis::streaming::compensated_sum;
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::{number_format::NumberFormat, DataType};
+
+/// How missing (empty or whitespace-only) values should be filled
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum ImputeStrategy {
+    /// Mean of the numeric values in the column
+    Mean,
+    /// Median of the numeric values in the column
+    Median,
+    /// Most frequent non-missing value
+    Mode,
+    /// A fixed replacement value
+    Constant { value: String },
+    /// Previous non-missing value in row order
+    ForwardFill,
+    /// Next non-missing value in row order
+    BackwardFill,
+}
+
+impl ImputeStrategy {
+    fn name(&self) -> &'static str {
+        match self {
+            ImputeStrategy::Mean => "mean",
+            ImputeStrategy::Median => "median",
+            ImputeStrategy::Mode => "mode",
+            ImputeStrategy::Constant { .. } => "constant",
+            ImputeStrategy::ForwardFill => "forward_fill",
+            ImputeStrategy::BackwardFill => "backward_fill",
+        }
+    }
+}
+
+/// Outcome of imputing a single column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ImputationReport {
+    pub column: usize,
+    pub strategy: ImputeStrategy,
+    /// The value used for every fill, for strategies that use a single value
+    pub fill_value: Option<String>,
+    pub imputed_rows: Vec<usize>,
+    /// Missing rows that could not be filled, e.g. leading gaps for a forward fill
+    pub unfilled_rows: Vec<usize>,
+}
+
+fn is_missing(value: &str) -> bool {
+    value.trim().is_empty()
+}
+
+fn format_number(value: f64, integer: bool, format: &NumberFormat) -> String {
+    if integer {
+        return format.format(value.round());
+    }
+    format.format(value)
+}
+
+/// Computes the single fill value used by mean, median, mode and constant strategies
+fn fill_value(
+    values: &[String],
+    strategy: &ImputeStrategy,
+    integer: bool,
+    format: &NumberFormat,
+) -> Result<String, String> {
+    match strategy {
+        ImputeStrategy::Mean | ImputeStrategy::Median => {
+            let mut numbers: Vec<f64> = values.iter().filter_map(|v| format.parse(v)).collect();
+            if numbers.is_empty() {
+                return Err(format!(
+                    "Column has no numeric values to compute a {}",
+                    strategy.name()
+                ));
+            }
+
+            let fill = if *strategy == ImputeStrategy::Mean {
+                compensated_sum(numbers.iter().copied()) / numbers.len() as f64
+            } else {
+                numbers.sort_by(|a, b| a.total_cmp(b));
+                let mid = numbers.len() / 2;
+                if numbers.len().is_multiple_of(2) {
+                    (numbers[mid - 1] + numbers[mid]) / 2.0
+                } else {
+                    numbers[mid]
+                }
+            };
+            Ok(format_number(fill, integer, format))
+        }
+        ImputeStrategy::Mode => {
+            // Count occurrences, breaking ties by first appearance to stay deterministic
+            let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+            for (row, value) in values.iter().enumerate() {
+                if !is_missing(value) {
+                    counts.entry(value.as_str()).or_insert((0, row)).0 += 1;
+                }
+            }
+            counts
+                .into_iter()
+                .max_by(|a, b| a.1 .0.cmp(&b.1 .0).then(b.1 .1.cmp(&a.1 .1)))
+                .map(|(value, _)| value.to_string())
+                .ok_or_else(|| "Column has no values to compute a mode".to_string())
+        }
+        ImputeStrategy::Constant { value } => Ok(value.clone()),
+        ImputeStrategy::ForwardFill | ImputeStrategy::BackwardFill => Err(format!(
+            "{} does not use a single fill value",
+            strategy.name()
+        )),
+    }
+}
+
+/// Fills missing values in place, returning the audit records for every changed cell
+pub fn impute_values(
+    column: usize,
+    values: &mut [String],
+    strategy: &ImputeStrategy,
+    integer: bool,
+    format: &NumberFormat,
+) -> Result<(ImputationReport, Vec<CellChange>), AnalysisError> {
+    let reason = format!("impute:{}", strategy.name());
+    let mut changes = Vec::new();
+    let mut unfilled_rows = Vec::new();
+    let mut fill = None;
+
+    match strategy {
+        ImputeStrategy::ForwardFill | ImputeStrategy::BackwardFill => {
+            let rows: Vec<usize> = if *strategy == ImputeStrategy::ForwardFill {
+                (0..values.len()).collect()
+            } else {
+                (0..values.len()).rev().collect()
+            };
+
+            let mut last_seen: Option<String> = None;
+            for row in rows {
+                if !is_missing(&values[row]) {
+                    last_seen = Some(values[row].clone());
+                    continue;
+                }
+                match &last_seen {
+                    Some(previous) => {
+                        changes.push(CellChange {
+                            row,
+                            column,
+                            old_value: std::mem::replace(&mut values[row], previous.clone()),
+                            new_value: previous.clone(),
+                            reason: reason.clone(),
+                        });
+                    }
+                    None => unfilled_rows.push(row),
+                }
+            }
+            changes.sort_by_key(|change| change.row);
+            unfilled_rows.sort_unstable();
+        }
+        _ => {
+            let value = fill_value(values, strategy, integer, format)
+                .map_err(|message| AnalysisError::invalid_column(column, message))?;
+            for (row, cell) in values.iter_mut().enumerate() {
+                if is_missing(cell) {
+                    changes.push(CellChange {
+                        row,
+                        column,
+                        old_value: std::mem::replace(cell, value.clone()),
+                        new_value: value.clone(),
+                        reason: reason.clone(),
+                    });
+                }
+            }
+            fill = Some(value);
+        }
+    }
+
+    let report = ImputationReport {
+        column,
+        strategy: strategy.clone(),
+        fill_value: fill,
+        imputed_rows: changes.iter().map(|change| change.row).collect(),
+        unfilled_rows,
+    };
+    Ok((report, changes))
+}
+
+impl CSV {
+    /// Fills the empty cells of a column using the given strategy, recording the changes
+    pub fn impute_column(
+        &mut self,
+        column: usize,
+        strategy: &ImputeStrategy,
+    ) -> Result<ImputationReport, AnalysisError> {
+        // Keep integer columns integral when filling with a mean or median, and write
+        // fills in the column's own number format
+        let metadata = self.column_metadata(column);
+        let integer = metadata.is_some_and(|metadata| metadata.data_type == DataType::Integer);
+        let format = metadata
+            .and_then(|metadata| metadata.number_format)
+            .unwrap_or_default();
+
+        let values = self
+            .column_values_mut(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let (report, changes) = impute_values(column, values, strategy, integer, &format)?;
+
+        self.record_cleaning_step(CleaningStep {
+            operation: format!("impute:{}", strategy.name()),
+            column: Some(column),
+            changes,
+        });
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_statistical_strategies() {
+        let test_cases = vec![
+            (ImputeStrategy::Mean, false, "2.5"),
+            (ImputeStrategy::Mean, true, "3"),
+            (ImputeStrategy::Median, false, "2"),
+            (ImputeStrategy::Mode, false, "1"),
+            (
+                ImputeStrategy::Constant {
+                    value: "0".to_string(),
+                },
+                false,
+                "0",
+            ),
+        ];
+
+        for (strategy, integer, expected) in test_cases {
+            let mut values = column(&["1", "", "1", "3", " ", "5"]);
+            let (report, changes) =
+                impute_values(0, &mut values, &strategy, integer, &NumberFormat::default())
+                    .unwrap();
+
+            assert_eq!(
+                report.fill_value.as_deref(),
+                Some(expected),
+                "Failed for strategy: {:?}",
+                strategy
+            );
+            assert_eq!(report.imputed_rows, vec![1, 4]);
+            assert_eq!(values[1], expected);
+            assert_eq!(changes[1].old_value, " ");
+            assert_eq!(changes[1].reason, format!("impute:{}", strategy.name()));
+        }
+    }
+
+    #[test]
+    fn test_fill_directions() {
+        let mut values = column(&["", "a", "", "", "b", ""]);
+        let (report, _) = impute_values(
+            0,
+            &mut values,
+            &ImputeStrategy::ForwardFill,
+            false,
+            &NumberFormat::default(),
+        )
+        .unwrap();
+        assert_eq!(values, column(&["", "a", "a", "a", "b", "b"]));
+        assert_eq!(report.imputed_rows, vec![2, 3, 5]);
+        assert_eq!(report.unfilled_rows, vec![0]);
+
+        let mut values = column(&["", "a", "", "", "b", ""]);
+        let (report, _) = impute_values(
+            0,
+            &mut values,
+            &ImputeStrategy::BackwardFill,
+            false,
+            &NumberFormat::default(),
+        )
+        .unwrap();
+        assert_eq!(values, column(&["a", "a", "b", "b", "b", ""]));
+        assert_eq!(report.imputed_rows, vec![0, 2, 3]);
+        assert_eq!(report.unfilled_rows, vec![5]);
+    }
+
+    #[test]
+    fn test_non_numeric_mean_fails() {
+        let mut values = column(&["a", "", "b"]);
+        assert!(impute_values(
+            0,
+            &mut values,
+            &ImputeStrategy::Mean,
+            false,
+            &NumberFormat::default(),
+        )
+        .is_err());
+        assert_eq!(values[1], "", "Values should be untouched on failure");
+    }
+
+    #[test]
+    fn test_csv_impute_records_audit() {
+        let data = "id,score\n1,10\n2,\n3,20\n4,";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let report = csv.impute_column(1, &ImputeStrategy::Median).unwrap();
+        assert_eq!(report.fill_value.as_deref(), Some("15"));
+
+        let (_, values) = csv.get_column(1).unwrap();
+        assert_eq!(values, &["10", "15", "20", "15"]);
+
+        let step = &csv.cleaning_steps()[0];
+        assert_eq!(step.operation, "impute:median");
+        assert_eq!(step.changes.len(), 2);
+        assert_eq!(step.changes[0].row, 1);
+
+        assert!(csv.impute_column(9, &ImputeStrategy::Mode).is_err());
+    }
+
+    #[test]
+    fn test_impute_uses_column_number_format() {
+        let data = "price\n\"1,5\"\n\n\"2,5\"\n\"3,5\"";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let report = csv.impute_column(0, &ImputeStrategy::Mean).unwrap();
+        assert_eq!(report.fill_value.as_deref(), Some("2,5"));
+    }
+
+    #[test]
+    fn test_strategy_deserialization() {
+        let strategy: ImputeStrategy =
+            serde_json::from_str(r#"{"strategy":"constant","value":"N/A"}"#).unwrap();
+        assert_eq!(
+            strategy,
+            ImputeStrategy::Constant {
+                value: "N/A".to_string()
+            }
+        );
+
+        let strategy: ImputeStrategy =
+            serde_json::from_str(r#"{"strategy":"forward_fill"}"#).unwrap();
+        assert_eq!(strategy, ImputeStrategy::ForwardFill);
+    }
+}
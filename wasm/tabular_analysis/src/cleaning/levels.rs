@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::DataType;
+
+/// A distinct value of a categorical column and how many cells hold it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LevelCount {
+    pub level: String,
+    pub count: usize,
+}
+
+/// Result of merging, renaming or bucketing the levels of a categorical column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LevelReport {
+    pub column: usize,
+    /// Levels folded into another, in order of frequency
+    pub merged_levels: Vec<String>,
+    /// Cells rewritten to the new level
+    pub affected_rows: usize,
+    /// The column's levels afterwards
+    pub levels: Vec<LevelCount>,
+}
+
+/// Counts of each distinct non-empty value, trimmed, most frequent first and ties in
+/// alphabetical order
+pub fn count_levels(values: &[String]) -> Vec<LevelCount> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut levels: Vec<LevelCount> = counts
+        .into_iter()
+        .map(|(level, count)| LevelCount {
+            level: level.to_string(),
+            count,
+        })
+        .collect();
+    levels.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.level.cmp(&b.level)));
+    levels
+}
+
+impl CSV {
+    // Internal helper checking that a column is categorical, inferring it when it wasn't yet
+    fn categorical_column(&self, column: usize) -> Result<&[String], AnalysisError> {
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let data_type = match self.column_metadata(column) {
+            Some(metadata) => metadata.data_type,
+            None => self
+                .infer_column(column)
+                .map_or(DataType::Text, |m| m.data_type),
+        };
+        if data_type != DataType::Categorical {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Column {} is {}, not Categorical",
+                column, data_type
+            )));
+        }
+        Ok(values)
+    }
+
+    /// Levels of a categorical column with their counts, most frequent first
+    pub fn categorical_levels(&self, column: usize) -> Result<Vec<LevelCount>, AnalysisError> {
+        Ok(count_levels(self.categorical_column(column)?))
+    }
+
+    // Internal helper rewriting every cell holding one of `levels` to `into`, recording the
+    // changes and recomputing the column's statistics
+    fn rewrite_levels(
+        &mut self,
+        column: usize,
+        levels: &HashSet<String>,
+        into: &str,
+        operation: &str,
+    ) -> Result<LevelReport, AnalysisError> {
+        let into = into.trim();
+        if into.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "The new level must not be empty",
+            ));
+        }
+        let merged_levels: Vec<String> = count_levels(self.categorical_column(column)?)
+            .into_iter()
+            .map(|level| level.level)
+            .filter(|level| levels.contains(level) && level != into)
+            .collect();
+
+        let reason = format!("{}:{}", operation, into);
+        let values = self
+            .column_values_mut(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let changes: Vec<CellChange> = values
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, value)| merged_levels.iter().any(|level| level == value.trim()))
+            .map(|(row, value)| CellChange {
+                row,
+                column,
+                old_value: std::mem::replace(value, into.to_string()),
+                new_value: into.to_string(),
+                reason: reason.clone(),
+            })
+            .collect();
+        let affected_rows = changes.len();
+        self.record_cleaning_step(CleaningStep {
+            operation: operation.to_string(),
+            column: Some(column),
+            changes,
+        });
+        self.refresh_column_stats(column);
+
+        Ok(LevelReport {
+            column,
+            merged_levels,
+            affected_rows,
+            levels: self.categorical_levels(column)?,
+        })
+    }
+
+    /// Folds several levels of a categorical column into one, e.g. "NY" and "N.Y." into
+    /// "New York", which may be one of them or a new level
+    pub fn merge_levels(
+        &mut self,
+        column: usize,
+        levels: &[String],
+        into: &str,
+    ) -> Result<LevelReport, AnalysisError> {
+        if levels.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "At least one level to merge is required",
+            ));
+        }
+        let levels = levels
+            .iter()
+            .map(|level| level.trim().to_string())
+            .collect();
+        self.rewrite_levels(column, &levels, into, "levels:merge")
+    }
+
+    /// Renames a level of a categorical column
+    pub fn rename_level(
+        &mut self,
+        column: usize,
+        level: &str,
+        new_name: &str,
+    ) -> Result<LevelReport, AnalysisError> {
+        let level = level.trim();
+        if !self
+            .categorical_levels(column)?
+            .iter()
+            .any(|existing| existing.level == level)
+        {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Column {} has no level '{}'",
+                column, level
+            )));
+        }
+        let levels = HashSet::from([level.to_string()]);
+        self.rewrite_levels(column, &levels, new_name, "levels:rename")
+    }
+
+    /// Folds every level of a categorical column held by less than `min_share` (0 to 1) of
+    /// its non-empty cells into a single `other` level
+    pub fn bucket_rare_levels(
+        &mut self,
+        column: usize,
+        min_share: f64,
+        other: &str,
+    ) -> Result<LevelReport, AnalysisError> {
+        if !(min_share > 0.0 && min_share <= 1.0) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "The minimum share must be above 0 and at most 1, not {}",
+                min_share
+            )));
+        }
+        let levels = self.categorical_levels(column)?;
+        let total: usize = levels.iter().map(|level| level.count).sum();
+        let rare = levels
+            .into_iter()
+            .filter(|level| (level.count as f64) < min_share * total as f64)
+            .map(|level| level.level)
+            .collect();
+        self.rewrite_levels(column, &rare, other, "levels:bucket")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cities() -> CSV {
+        let mut data = "city\n".to_string();
+        for city in ["New York"; 6]
+            .iter()
+            .chain(&["NY"; 3])
+            .chain(&["Boston"; 8])
+            .chain(&["Salem", "Lowell", " NY "])
+        {
+            data.push_str(city);
+            data.push('\n');
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.override_type(0, DataType::Categorical).unwrap();
+        csv
+    }
+
+    fn level_names(levels: &[LevelCount]) -> Vec<(&str, usize)> {
+        levels.iter().map(|l| (l.level.as_str(), l.count)).collect()
+    }
+
+    #[test]
+    fn test_levels_with_counts() {
+        let csv = cities();
+        assert_eq!(
+            level_names(&csv.categorical_levels(0).unwrap()),
+            vec![
+                ("Boston", 8),
+                ("New York", 6),
+                ("NY", 4),
+                ("Lowell", 1),
+                ("Salem", 1)
+            ]
+        );
+        let text = CSV::from_string("note\nfirst long remark\nsecond one".to_string()).unwrap();
+        assert!(text.categorical_levels(0).is_err());
+    }
+
+    #[test]
+    fn test_merge_and_rename() {
+        let mut csv = cities();
+        let report = csv
+            .merge_levels(0, &["NY".to_string()], "New York")
+            .unwrap();
+        assert_eq!(report.merged_levels, vec!["NY"]);
+        assert_eq!(report.affected_rows, 4);
+        assert_eq!(report.levels[0].level, "New York");
+        assert_eq!(report.levels[0].count, 10);
+
+        // Statistics follow the new levels, and the type stays pinned
+        let metadata = csv.column_metadata(0).unwrap();
+        assert_eq!(metadata.text_stats.as_ref().unwrap().distinct_count, 4);
+        assert!(metadata.user_overridden);
+        assert_eq!(
+            csv.cleaning_steps().last().unwrap().operation,
+            "levels:merge"
+        );
+
+        let report = csv.rename_level(0, "Boston", "BOS").unwrap();
+        assert_eq!(report.affected_rows, 8);
+        assert!(csv.rename_level(0, "Boston", "BOS").is_err());
+        assert!(csv.rename_level(0, "BOS", " ").is_err());
+    }
+
+    #[test]
+    fn test_bucket_rare_levels() {
+        let mut csv = cities();
+        let report = csv.bucket_rare_levels(0, 0.1, "Other").unwrap();
+        assert_eq!(report.merged_levels, vec!["Lowell", "Salem"]);
+        assert_eq!(
+            level_names(&report.levels),
+            vec![("Boston", 8), ("New York", 6), ("NY", 4), ("Other", 2)]
+        );
+        assert!(csv.bucket_rare_levels(0, 0.0, "Other").is_err());
+    }
+}
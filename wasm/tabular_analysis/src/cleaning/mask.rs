@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::analysis::pii::{classify_value, is_name_header, PiiKind};
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::{credit_card::CreditCardType, TypeDetection};
+
+/// How flagged PII values are rewritten
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskStrategy {
+    /// Replace the value with a label such as `[EMAIL]`
+    #[default]
+    Redact,
+    /// Replace the value with a salted SHA-256 digest, so equal values still join
+    Hash,
+}
+
+/// Options for `mask_pii`; every field is optional from JS
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct MaskOptions {
+    pub strategy: MaskStrategy,
+    /// Columns to mask, all columns when omitted
+    pub columns: Option<Vec<usize>>,
+    /// PII kinds to mask, all kinds when omitted
+    pub kinds: Option<Vec<PiiKind>>,
+    /// Prepended to values before hashing so digests can't be reversed with lookup tables
+    pub salt: String,
+}
+
+/// Outcome of masking PII across the table
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MaskReport {
+    pub strategy: MaskStrategy,
+    pub masked_cells: usize,
+    pub masked_columns: Vec<usize>,
+}
+
+/// Computes the masked form of a value of the given kind
+pub fn mask_value(value: &str, kind: PiiKind, strategy: MaskStrategy, salt: &str) -> String {
+    match strategy {
+        // Card numbers keep their last four digits, which is enough to reconcile payments
+        MaskStrategy::Redact if kind == PiiKind::CreditCard => {
+            CreditCardType::normalize(value).unwrap_or_else(|| kind.redaction_label().to_string())
+        }
+        MaskStrategy::Redact => kind.redaction_label().to_string(),
+        MaskStrategy::Hash => {
+            let mut hasher = Sha256::new();
+            hasher.update(salt.as_bytes());
+            hasher.update(value.trim().as_bytes());
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect()
+        }
+    }
+}
+
+/// Masks every PII value in a column, returning the changed cells.
+/// Changes keep only the kind as their old value so the audit log doesn't retain the PII.
+pub fn mask_column(
+    column: usize,
+    header: &str,
+    values: &mut [String],
+    options: &MaskOptions,
+) -> Vec<CellChange> {
+    let name_column = is_name_header(header);
+    let mut changes = Vec::new();
+
+    for (row, value) in values.iter_mut().enumerate() {
+        let Some(kind) = classify_value(value, name_column) else {
+            continue;
+        };
+        if options
+            .kinds
+            .as_ref()
+            .is_some_and(|kinds| !kinds.contains(&kind))
+        {
+            continue;
+        }
+
+        let masked = mask_value(value, kind, options.strategy, &options.salt);
+        *value = masked.clone();
+        changes.push(CellChange {
+            row,
+            column,
+            old_value: kind.redaction_label().to_string(),
+            new_value: masked,
+            reason: format!("mask_pii:{:?}", kind).to_lowercase(),
+        });
+    }
+    changes
+}
+
+impl CSV {
+    /// Redacts or hashes detected PII in place, recording the changes
+    pub fn mask_pii_values(&mut self, options: &MaskOptions) -> Result<MaskReport, AnalysisError> {
+        let columns: Vec<usize> = match &options.columns {
+            Some(columns) => {
+                if let Some(index) = columns.iter().find(|&&i| i >= self.column_count()) {
+                    return Err(AnalysisError::ColumnOutOfBounds { column: *index });
+                }
+                columns.clone()
+            }
+            None => (0..self.column_count()).collect(),
+        };
+
+        let mut report = MaskReport {
+            strategy: options.strategy,
+            masked_cells: 0,
+            masked_columns: Vec::new(),
+        };
+        for column in columns {
+            let header = self
+                .get_column(column)
+                .map(|(header, _)| header.to_string())
+                .unwrap_or_default();
+            let values = self
+                .column_values_mut(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+
+            let changes = mask_column(column, &header, values, options);
+            if changes.is_empty() {
+                continue;
+            }
+            report.masked_cells += changes.len();
+            report.masked_columns.push(column);
+            self.record_cleaning_step(CleaningStep {
+                operation: "mask_pii".to_string(),
+                column: Some(column),
+                changes,
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_redact_column() {
+        let mut values = column(&[
+            "ada@example.com",
+            "hello",
+            "123-45-6789",
+            "4111 1111 1111 1111",
+        ]);
+        let changes = mask_column(0, "notes", &mut values, &MaskOptions::default());
+
+        assert_eq!(
+            values,
+            column(&["[EMAIL]", "hello", "[SSN]", "**** **** **** 1111"])
+        );
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[1].reason, "mask_pii:ssn");
+        assert!(
+            changes.iter().all(|c| !c.old_value.contains('@')),
+            "audit log must not keep the original PII"
+        );
+    }
+
+    #[test]
+    fn test_hash_is_stable_and_salted() {
+        let hashed = mask_value("ada@example.com", PiiKind::Email, MaskStrategy::Hash, "");
+        assert_eq!(hashed.len(), 64);
+        assert_eq!(
+            hashed,
+            mask_value(" ada@example.com ", PiiKind::Email, MaskStrategy::Hash, "")
+        );
+        assert_ne!(
+            hashed,
+            mask_value(
+                "ada@example.com",
+                PiiKind::Email,
+                MaskStrategy::Hash,
+                "pepper"
+            )
+        );
+    }
+
+    #[test]
+    fn test_mask_selected_kinds() {
+        let data = "full_name,contact\nAda Lovelace,ada@example.com\nAlan Turing,(555) 123-4567";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let options = MaskOptions {
+            kinds: Some(vec![PiiKind::Email, PiiKind::Name]),
+            ..MaskOptions::default()
+        };
+        let report = csv.mask_pii_values(&options).unwrap();
+
+        assert_eq!(report.masked_cells, 3);
+        assert_eq!(report.masked_columns, vec![0, 1]);
+        let (_, contacts) = csv.get_column(1).unwrap();
+        assert_eq!(contacts, &["[EMAIL]", "(555) 123-4567"]);
+        assert_eq!(csv.cleaning_steps().len(), 2);
+
+        let out_of_bounds = MaskOptions {
+            columns: Some(vec![5]),
+            ..MaskOptions::default()
+        };
+        assert!(csv.mask_pii_values(&out_of_bounds).is_err());
+    }
+}
@@ -0,0 +1,9 @@
+pub mod audit;
+pub mod corrections;
+pub mod dates;
+pub mod duplicates;
+pub mod impute;
+pub mod levels;
+pub mod mask;
+pub mod replace;
+pub mod split_merge;
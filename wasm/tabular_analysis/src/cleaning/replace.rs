@@ -0,0 +1,210 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::cleaning::audit::{CellChange, CleaningStep};
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+// Limits keep user-supplied patterns from exhausting memory in the browser
+const MAX_PATTERN_LENGTH: usize = 1024;
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+const MAX_REPLACE_SAMPLES: usize = 10;
+
+/// Before/after view of one cell affected by a replacement
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReplaceSample {
+    pub row: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// Result of a find-and-replace, either previewed or committed
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReplaceReport {
+    pub column: usize,
+    pub pattern: String,
+    pub replacement: String,
+    pub affected_rows: usize,
+    pub samples: Vec<ReplaceSample>,
+    /// False when the report is only a preview and no values were changed
+    pub committed: bool,
+}
+
+/// Compiles a user-supplied pattern with size limits, returning a readable error instead of panicking
+pub fn compile_pattern(pattern: &str) -> Result<Regex, AnalysisError> {
+    let invalid = |message: String| AnalysisError::InvalidPattern {
+        pattern: pattern.to_string(),
+        message,
+    };
+    if pattern.is_empty() {
+        return Err(invalid("Pattern must not be empty".to_string()));
+    }
+    if pattern.len() > MAX_PATTERN_LENGTH {
+        return Err(invalid(format!(
+            "Pattern is longer than {} characters",
+            MAX_PATTERN_LENGTH
+        )));
+    }
+
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| invalid(e.to_string()))
+}
+
+/// Computes the new value of every cell the pattern changes, as `(row, new_value)` pairs
+pub fn find_replacements(
+    values: &[String],
+    regex: &Regex,
+    replacement: &str,
+) -> Vec<(usize, String)> {
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(row, value)| {
+            let replaced = regex.replace_all(value, replacement);
+            (replaced != value.as_str()).then(|| (row, replaced.into_owned()))
+        })
+        .collect()
+}
+
+impl CSV {
+    /// Reports what a regex replacement would change without applying it
+    pub fn replace_report(
+        &self,
+        column: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<ReplaceReport, AnalysisError> {
+        let (report, _) = self.plan_replace(column, pattern, replacement)?;
+        Ok(report)
+    }
+
+    // Matches the pattern over the column once, returning the report along with every
+    // replacement so committing doesn't have to match again
+    fn plan_replace(
+        &self,
+        column: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<(ReplaceReport, Vec<(usize, String)>), AnalysisError> {
+        let regex = compile_pattern(pattern)?;
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+
+        let replacements = find_replacements(values, &regex, replacement);
+        let report = ReplaceReport {
+            column,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            affected_rows: replacements.len(),
+            samples: replacements
+                .iter()
+                .take(MAX_REPLACE_SAMPLES)
+                .map(|(row, after)| ReplaceSample {
+                    row: *row,
+                    before: values[*row].clone(),
+                    after: after.clone(),
+                })
+                .collect(),
+            committed: false,
+        };
+        Ok((report, replacements))
+    }
+
+    /// Replaces every regex match in a column, recording the changes
+    pub fn replace_in_column(
+        &mut self,
+        column: usize,
+        pattern: &str,
+        replacement: &str,
+    ) -> Result<ReplaceReport, AnalysisError> {
+        let (mut report, replacements) = self.plan_replace(column, pattern, replacement)?;
+        let reason = format!("replace:{}", pattern);
+
+        let values = self
+            .column_values_mut(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let changes: Vec<CellChange> = replacements
+            .into_iter()
+            .map(|(row, new_value)| CellChange {
+                row,
+                column,
+                old_value: std::mem::replace(&mut values[row], new_value.clone()),
+                new_value,
+                reason: reason.clone(),
+            })
+            .collect();
+
+        self.record_cleaning_step(CleaningStep {
+            operation: "replace".to_string(),
+            column: Some(column),
+            changes,
+        });
+
+        report.committed = true;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_compilation() {
+        assert!(compile_pattern(r"^\d+$").is_ok());
+        assert!(compile_pattern("").is_err());
+        assert!(compile_pattern("(unclosed").is_err());
+        assert!(compile_pattern(&"a".repeat(MAX_PATTERN_LENGTH + 1)).is_err());
+        // Patterns that compile to huge automata are rejected rather than allocated
+        assert!(compile_pattern(r"\w{1000}\w{1000}\w{1000}").is_err());
+    }
+
+    #[test]
+    fn test_find_replacements() {
+        let values: Vec<String> = vec!["N/A".into(), "12".into(), "n/a".into(), "".into()];
+        let regex = compile_pattern(r"(?i)^n/a$").unwrap();
+
+        let replacements = find_replacements(&values, &regex, "");
+        assert_eq!(replacements, vec![(0, String::new()), (2, String::new())]);
+    }
+
+    #[test]
+    fn test_preview_does_not_modify() {
+        let data = "name\n\"Smith, John\"\n\"Doe, Jane\"\nPrince";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let report = csv.replace_report(0, r"^(\w+), (\w+)$", "$2 $1").unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.affected_rows, 2);
+        assert_eq!(report.samples[0].before, "Smith, John");
+        assert_eq!(report.samples[0].after, "John Smith");
+
+        let (_, values) = csv.get_column(0).unwrap();
+        assert_eq!(values[0], "Smith, John");
+    }
+
+    #[test]
+    fn test_replace_commits_and_logs() {
+        let data = "phone\n555.123.4567\n555.987.6543\nunknown";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+
+        let report = csv.replace_in_column(0, r"\.", "-").unwrap();
+        assert!(report.committed);
+        assert_eq!(report.affected_rows, 2);
+
+        let (_, values) = csv.get_column(0).unwrap();
+        assert_eq!(values, &["555-123-4567", "555-987-6543", "unknown"]);
+
+        let step = csv.cleaning_steps().last().unwrap();
+        assert_eq!(step.operation, "replace");
+        assert_eq!(step.changes.len(), 2);
+        assert_eq!(step.changes[1].old_value, "555.987.6543");
+
+        assert!(csv.replace_in_column(3, "a", "b").is_err());
+        assert!(csv.replace_in_column(0, "[", "b").is_err());
+    }
+}
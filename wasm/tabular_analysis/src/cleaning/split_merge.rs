@@ -0,0 +1,198 @@
+use crate::cleaning::replace::compile_pattern;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+// Guards against a stray delimiter exploding one column into hundreds
+const MAX_SPLIT_PARTS: usize = 32;
+
+/// Splits every value on a literal delimiter or a regex, padding short rows with empty values
+pub fn split_values(
+    values: &[String],
+    delimiter: &str,
+    use_regex: bool,
+) -> Result<Vec<Vec<String>>, AnalysisError> {
+    if delimiter.is_empty() {
+        return Err(AnalysisError::invalid_argument(
+            "Delimiter must not be empty",
+        ));
+    }
+    let regex = if use_regex {
+        Some(compile_pattern(delimiter)?)
+    } else {
+        None
+    };
+
+    let rows: Vec<Vec<String>> = values
+        .iter()
+        .map(|value| {
+            let parts: Vec<&str> = match &regex {
+                Some(regex) => regex.splitn(value, MAX_SPLIT_PARTS).collect(),
+                None => value.splitn(MAX_SPLIT_PARTS, delimiter).collect(),
+            };
+            parts
+                .into_iter()
+                .map(|part| part.trim().to_string())
+                .collect()
+        })
+        .collect();
+
+    // Transpose the per-row parts into columns
+    let part_count = rows.iter().map(|parts| parts.len()).max().unwrap_or(0);
+    Ok((0..part_count)
+        .map(|part| {
+            rows.iter()
+                .map(|parts| parts.get(part).cloned().unwrap_or_default())
+                .collect()
+        })
+        .collect())
+}
+
+/// Joins values row by row, skipping empty values so they don't leave doubled separators
+pub fn merge_values(columns: &[&[String]], separator: &str) -> Vec<String> {
+    let row_count = columns.first().map_or(0, |values| values.len());
+    (0..row_count)
+        .map(|row| {
+            columns
+                .iter()
+                .filter_map(|values| values.get(row))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<&str>>()
+                .join(separator)
+        })
+        .collect()
+}
+
+impl CSV {
+    /// Splits a column on a delimiter into new columns, returning their indices
+    pub fn split_column_values(
+        &mut self,
+        index: usize,
+        delimiter: &str,
+        use_regex: bool,
+    ) -> Result<Vec<usize>, AnalysisError> {
+        let (header, values) = self
+            .get_column(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        let header = header.to_string();
+        let parts = split_values(values, delimiter, use_regex)?;
+
+        Ok(parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, values)| {
+                self.push_derived_column(
+                    format!("{}_{}", header, i + 1),
+                    values,
+                    &[index],
+                    "split",
+                    Some(delimiter.to_string()),
+                )
+            })
+            .collect())
+    }
+
+    /// Joins several columns into a new column, returning its index
+    pub fn merge_column_values(
+        &mut self,
+        indices: &[usize],
+        separator: &str,
+    ) -> Result<usize, AnalysisError> {
+        if indices.len() < 2 {
+            return Err(AnalysisError::invalid_argument(
+                "At least two columns are required to merge",
+            ));
+        }
+
+        let mut headers = Vec::with_capacity(indices.len());
+        let mut columns = Vec::with_capacity(indices.len());
+        for &index in indices {
+            let (header, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            headers.push(header);
+            columns.push(values);
+        }
+
+        let header = headers.join("_");
+        let merged = merge_values(&columns, separator);
+        Ok(self.push_derived_column(
+            header,
+            merged,
+            indices,
+            "merge",
+            Some(separator.to_string()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_values() {
+        let values = column(&["Smith, John", "Doe, Jane", "Prince"]);
+        let parts = split_values(&values, ",", false).unwrap();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], column(&["Smith", "Doe", "Prince"]));
+        assert_eq!(parts[1], column(&["John", "Jane", ""]));
+
+        // Regex mode handles mixed separators
+        let values = column(&["10-20", "30/40", "50 60"]);
+        let parts = split_values(&values, r"[-/ ]", true).unwrap();
+        assert_eq!(parts[1], column(&["20", "40", "60"]));
+
+        assert!(split_values(&values, "", false).is_err());
+        assert!(split_values(&values, "(", true).is_err());
+    }
+
+    #[test]
+    fn test_merge_values() {
+        let first = column(&["John", "Jane", ""]);
+        let last = column(&["Smith", "", "Prince"]);
+        assert_eq!(
+            merge_values(&[&first, &last], " "),
+            column(&["John Smith", "Jane", "Prince"])
+        );
+    }
+
+    #[test]
+    fn test_split_and_merge_columns() {
+        let data = "name,date\n\
+                    \"Smith, John\",2024-01-05\n\
+                    \"Doe, Jane\",2024-02-10";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+
+        let new_columns = csv.split_column_values(1, "-", false).unwrap();
+        assert_eq!(new_columns, vec![2, 3, 4]);
+        let (header, values) = csv.get_column(2).unwrap();
+        assert_eq!(header, "date_1");
+        assert_eq!(values, &["2024", "2024"]);
+
+        // Inference runs on the new columns; zero-padded months stay text
+        let metadata = csv.column_metadata(2).unwrap();
+        assert_eq!(metadata.data_type, DataType::Integer);
+        let metadata = csv.column_metadata(3).unwrap();
+        assert_eq!(metadata.data_type, DataType::Text);
+
+        let merged = csv.merge_column_values(&[2, 3, 4], "/").unwrap();
+        let (header, values) = csv.get_column(merged).unwrap();
+        assert_eq!(header, "date_1_date_2_date_3");
+        assert_eq!(values, &["2024/01/05", "2024/02/10"]);
+        assert_eq!(
+            csv.column_metadata(merged).unwrap().data_type,
+            DataType::Date
+        );
+
+        assert!(csv.merge_column_values(&[0], " ").is_err());
+        assert!(csv.merge_column_values(&[0, 99], " ").is_err());
+        assert!(csv.split_column_values(99, ",", false).is_err());
+    }
+}
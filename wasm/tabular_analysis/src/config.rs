@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::analysis::statistics::NumericOptions;
+use crate::analysis::tdigest::DEFAULT_COMPRESSION;
+use crate::error::AnalysisError;
+use crate::types::locale::SynonymDictionary;
+
+/// Version of the inference rules, recorded in every metadata output. Bump it whenever the
+/// same values and config would produce different metadata.
+pub const ANALYSIS_VERSION: u32 = 4;
+
+/// Options controlling how a CSV is analyzed, settable from JS as a plain object.
+/// Missing fields fall back to their defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Percentiles (0-100) reported in numeric statistics in addition to the quartiles
+    pub percentiles: Vec<f64>,
+    /// Numbers standing for missing data, like -999, left out of numeric statistics when
+    /// they lie beyond every other value of the column. None by default, since such codes
+    /// vary by source and `9999` is a real value in many columns.
+    pub sentinel_values: Vec<f64>,
+    /// t-digest compression for quantiles of columns too large to sort, and of streamed
+    /// ones: higher is more accurate and keeps more centroids, about twice this many
+    pub quantile_compression: f64,
+    pub detection: DetectionConfig,
+    pub memory: MemoryConfig,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            percentiles: vec![1.0, 5.0, 95.0, 99.0],
+            sentinel_values: Vec::new(),
+            quantile_compression: DEFAULT_COMPRESSION,
+            detection: DetectionConfig::default(),
+            memory: MemoryConfig::default(),
+        }
+    }
+}
+
+/// Options controlling column type detection
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DetectionConfig {
+    /// Share of non-empty values (0-1) that must match a type for the column to get it
+    pub type_threshold: f64,
+    /// Score (0-1) added to the types a column header suggests, 0 to ignore headers
+    pub header_hint_weight: f64,
+    /// Language whose boolean and category words are recognized alongside English
+    pub locale: String,
+    /// Extra dictionaries, replacing the built-in one for the same locale
+    pub dictionaries: Vec<SynonymDictionary>,
+}
+
+impl Default for DetectionConfig {
+    /// Tolerates a stray value or two in every twenty
+    fn default() -> Self {
+        Self {
+            type_threshold: 0.95,
+            header_hint_weight: 0.05,
+            locale: "en".to_string(),
+            dictionaries: Vec::new(),
+        }
+    }
+}
+
+impl DetectionConfig {
+    /// The dictionary for the selected locale, preferring a supplied one over the built-in
+    pub fn synonyms(&self) -> Option<SynonymDictionary> {
+        self.dictionaries
+            .iter()
+            .find(|dictionary| dictionary.locale.eq_ignore_ascii_case(&self.locale))
+            .cloned()
+            .or_else(|| SynonymDictionary::builtin(&self.locale))
+    }
+}
+
+/// What `analyze_with_memory_limit` does when the parsed table wouldn't fit the limit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpillStrategy {
+    /// Analyze a random sample of as many rows as fit
+    #[default]
+    Sample,
+    /// Analyze every row, reading one column at a time and dropping its values once its
+    /// statistics are computed
+    ColumnByColumn,
+}
+
+/// Ceiling on the memory analysis may use
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Estimated bytes the parsed values and their working copies may take, unlimited when
+    /// unset
+    pub limit_bytes: Option<usize>,
+    pub spill_strategy: SpillStrategy,
+    /// Seed of the rows kept by the sample strategy
+    pub sample_seed: u64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        MemoryConfig {
+            limit_bytes: None,
+            spill_strategy: SpillStrategy::default(),
+            sample_seed: 0x5eed,
+        }
+    }
+}
+
+fn config_error(message: String) -> AnalysisError {
+    AnalysisError::ConfigError { message }
+}
+
+// Range of t-digest compression, from a coarse sketch to near-exact quantiles
+const MIN_COMPRESSION: f64 = 10.0;
+const MAX_COMPRESSION: f64 = 10_000.0;
+
+impl AnalysisConfig {
+    /// Percentiles, sentinels and compression numeric statistics are computed with
+    pub fn numeric_options(&self) -> NumericOptions<'_> {
+        NumericOptions {
+            percentiles: &self.percentiles,
+            sentinels: &self.sentinel_values,
+            compression: self.quantile_compression,
+        }
+    }
+
+    /// Hex SHA-256 of the config's JSON form, identifying the options metadata was
+    /// computed with
+    pub fn hash(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        Sha256::digest(json.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Checks that every option is within its valid range
+    pub fn validate(&self) -> Result<(), AnalysisError> {
+        if let Some(p) = self
+            .percentiles
+            .iter()
+            .find(|p| !(0.0..=100.0).contains(*p))
+        {
+            return Err(config_error(format!(
+                "Percentile {} must be between 0 and 100",
+                p
+            )));
+        }
+        if let Some(value) = self.sentinel_values.iter().find(|v| !v.is_finite()) {
+            return Err(config_error(format!(
+                "Sentinel value {} must be a finite number",
+                value
+            )));
+        }
+        if !(MIN_COMPRESSION..=MAX_COMPRESSION).contains(&self.quantile_compression) {
+            return Err(config_error(format!(
+                "Quantile compression {} must be between {} and {}",
+                self.quantile_compression, MIN_COMPRESSION, MAX_COMPRESSION
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.detection.type_threshold) {
+            return Err(config_error(format!(
+                "Type threshold {} must be between 0 and 1",
+                self.detection.type_threshold
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.detection.header_hint_weight) {
+            return Err(config_error(format!(
+                "Header hint weight {} must be between 0 and 1",
+                self.detection.header_hint_weight
+            )));
+        }
+        if self.memory.limit_bytes == Some(0) {
+            return Err(config_error(
+                "Memory limit must be above 0 bytes".to_string(),
+            ));
+        }
+        if self.detection.synonyms().is_none() {
+            return Err(config_error(format!(
+                "No dictionary for locale '{}'",
+                self.detection.locale
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: AnalysisConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, AnalysisConfig::default());
+
+        let config: AnalysisConfig = serde_json::from_str(r#"{"percentiles":[10,90]}"#).unwrap();
+        assert_eq!(config.percentiles, vec![10.0, 90.0]);
+
+        let config: AnalysisConfig =
+            serde_json::from_str(r#"{"detection":{"type_threshold":0.8}}"#).unwrap();
+        assert_eq!(config.detection.type_threshold, 0.8);
+        assert_eq!(config.percentiles, AnalysisConfig::default().percentiles);
+    }
+
+    #[test]
+    fn test_config_hash() {
+        let config = AnalysisConfig::default();
+        assert_eq!(config.hash().len(), 64);
+        assert_eq!(config.hash(), AnalysisConfig::default().hash());
+
+        let other = AnalysisConfig {
+            percentiles: vec![50.0],
+            ..AnalysisConfig::default()
+        };
+        assert_ne!(config.hash(), other.hash());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AnalysisConfig::default().validate().is_ok());
+
+        let config = AnalysisConfig {
+            percentiles: vec![50.0, 101.0],
+            ..AnalysisConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AnalysisConfig {
+            detection: DetectionConfig {
+                type_threshold: 1.5,
+                ..DetectionConfig::default()
+            },
+            ..AnalysisConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let mut config = AnalysisConfig::default();
+        config.detection.header_hint_weight = -0.1;
+        assert!(config.validate().is_err());
+
+        let mut config = AnalysisConfig::default();
+        config.memory.limit_bytes = Some(0);
+        assert!(config.validate().is_err());
+
+        let mut config = AnalysisConfig::default();
+        config.sentinel_values.push(f64::NAN);
+        assert!(config.validate().is_err());
+
+        let config = AnalysisConfig {
+            quantile_compression: 1.0,
+            ..AnalysisConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let mut config = AnalysisConfig::default();
+        config.detection.locale = "eo".to_string();
+        assert!(config.validate().is_err());
+        config.detection.dictionaries.push(SynonymDictionary {
+            locale: "eo".to_string(),
+            true_values: vec!["jes".to_string()],
+            ..SynonymDictionary::default()
+        });
+        assert!(config.validate().is_ok());
+        assert!(config.detection.synonyms().unwrap().contains("Jes"));
+    }
+}
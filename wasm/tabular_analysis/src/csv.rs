@@ -1,191 +1,1599 @@
 // csv.rs
 
 // Import core functionality for CSV parsing and type detection
-use csv::Reader;
+use csv::Writer;
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen::{from_value, to_value};
+use std::cell::OnceCell;
 use std::io::Cursor;
-use wasm_bindgen::prelude::*;
+use std::rc::Rc;
 
 // Import our type detection system
-use crate::types::{type_scoring::TypeScores, DataType, TypeDetection};
+use crate::analysis::cache::{column_content_hash, ColumnCache};
+use crate::analysis::date_stats::{calculate_date_stats, DateStats};
+use crate::analysis::email_stats::{calculate_email_stats, EmailStats};
+use crate::analysis::memory::AnalysisMode;
+use crate::analysis::phone_stats::{calculate_phone_stats, PhoneStats};
+use crate::analysis::statistics::{
+    calculate_card_stats, calculate_numeric_stats, calculate_text_stats, CardStats, NumericOptions,
+    NumericStats, TextStats, EXACT_QUANTILE_LIMIT,
+};
+use crate::analysis::streaming::StreamingNumericStats;
+use crate::analysis::tokens::{calculate_token_stats, TokenStats, LONG_TEXT_LENGTH};
+use crate::cleaning::audit::CleaningStep;
+use crate::config::{AnalysisConfig, ANALYSIS_VERSION};
+use crate::error::AnalysisError;
+use crate::logging::targets;
+use crate::metrics::{now_ms, AnalysisMetrics, PhaseTimings};
+use crate::parallel::{detect_column_types, type_detection_executor, ExecutionStats};
+use crate::table::dialect::{flatten_headers, Dialect, ParseOptions};
+use crate::table::history::History;
+use crate::table::lineage::ColumnLineage;
+use crate::table::raw::RawTable;
+use crate::table::scan;
+use crate::types::{
+    big_integer::BigIntegerColumn,
+    identifier::ZeroPaddedCode,
+    mixed::MixedComposition,
+    number_format::NumberFormat,
+    ordinal::OrdinalScale,
+    quantity::QuantityColumn,
+    type_scoring::{TypeCandidate, TypeCounts, TypeScores},
+    DataType,
+};
+use crate::warning::{AnalysisWarning, WarningCode};
 
 // ColumnMetadata represents the analyzed properties of a CSV column
-#[wasm_bindgen(getter_with_clone)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ColumnMetadata {
     pub name: String,
     pub data_type: DataType,
     pub confidence: f64,
+    // Statistics matching the detected type
+    #[serde(default)]
+    pub numeric_stats: Option<NumericStats>,
+    #[serde(default)]
+    pub text_stats: Option<TextStats>,
+    #[serde(default)]
+    pub date_stats: Option<DateStats>,
+    #[serde(default)]
+    pub card_stats: Option<CardStats>,
+    #[serde(default)]
+    pub email_stats: Option<EmailStats>,
+    #[serde(default)]
+    pub phone_stats: Option<PhoneStats>,
+    // Suggested SQL column type, refined from the data type's default where the values need it
+    #[serde(default)]
+    pub sql_type: Option<String>,
+    // Separators and negative style used by numeric columns, applied when parsing their values
+    #[serde(default)]
+    pub number_format: Option<NumberFormat>,
+    // Every candidate type ranked by how many values match it, explaining the chosen type
+    #[serde(default)]
+    pub type_candidates: Vec<TypeCandidate>,
+    // Per-type breakdown with row indices for Mixed columns
+    #[serde(default)]
+    pub mixed_composition: Option<MixedComposition>,
+    // Units found in Quantity columns and the unit suggested for export
+    #[serde(default)]
+    pub quantity: Option<QuantityColumn>,
+    // Set when the type was pinned with `override_column_type`; inference then keeps it
+    #[serde(default)]
+    pub user_overridden: bool,
+    // Inference rules and options the metadata was computed with, for caching by consumers
+    #[serde(default)]
+    pub analysis_version: u32,
+    #[serde(default)]
+    pub config_hash: String,
+    // Whether the statistics cover every row or a sample taken to stay under the memory limit
+    #[serde(default)]
+    pub analysis_mode: AnalysisMode,
+    // Source columns and operations behind columns made or rewritten by cleaning, split,
+    // merge or computed expressions; None for columns as loaded
+    #[serde(default)]
+    pub lineage: Option<ColumnLineage>,
+    // Integer columns with values past ±2^53, kept as digit strings without float statistics
+    #[serde(default)]
+    pub big_integer: Option<BigIntegerColumn>,
+    // Categorical columns whose levels follow a known order, such as low/medium/high
+    #[serde(default)]
+    pub ordinal: Option<OrdinalScale>,
+    // Word-level statistics of text columns with long values, such as comments
+    #[serde(default)]
+    pub token_stats: Option<TokenStats>,
+}
+
+impl ColumnMetadata {
+    /// Width of fixed-width codes such as zero-padded ZIP codes, from a CHAR(n) SQL type
+    pub fn fixed_width(&self) -> Option<usize> {
+        self.sql_type
+            .as_deref()?
+            .strip_prefix("CHAR(")?
+            .strip_suffix(')')?
+            .parse()
+            .ok()
+    }
 }
 
 // CSV struct represents a parsed CSV file with type information
-#[wasm_bindgen]
 #[derive(Debug)]
 pub struct CSV {
     columns: Vec<Column>,
     row_count: usize,
+    cleaning_log: Vec<CleaningStep>,
+    config: AnalysisConfig,
+    // How the input was written, sniffed unless given
+    dialect: Dialect,
+    history: History,
+    // Problems repaired while reading the input, reported by `warnings`
+    parse_warnings: Vec<AnalysisWarning>,
+    // Time spent reading the input, zero for tables built another way
+    parse_ms: f64,
+    metrics: Option<AnalysisMetrics>,
+    // Inference split into slices by `infer_column_types_slice`, while it is unfinished
+    sliced_inference: Option<SlicedInference>,
+    // Metadata of columns analyzed before, reused while their values and the config match
+    column_cache: ColumnCache,
+}
+
+// Where a sliced inference run stopped and what it has spent so far
+#[derive(Debug, Clone, Copy)]
+struct SlicedInference {
+    next_column: usize,
+    phases: PhaseTimings,
+    elapsed_ms: f64,
+    cached_columns: usize,
+}
+
+/// How far a type inference split into slices has got
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct InferenceProgress {
+    pub columns_done: usize,
+    pub columns: usize,
+    pub done: bool,
+}
+
+/// How `append_rows` brought the column metadata up to date
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AppendReport {
+    pub rows_appended: usize,
+    /// Columns whose confidence and statistics were updated under their existing type
+    pub updated_columns: Vec<usize>,
+    /// Columns inferred again because the new rows could change their type
+    pub reinferred_columns: Vec<usize>,
+}
+
+// Statistics computed for a column under its type
+type ColumnStats = (
+    Option<NumericStats>,
+    Option<TextStats>,
+    Option<DateStats>,
+    Option<CardStats>,
+    Option<EmailStats>,
+    Option<PhoneStats>,
+);
+
+// Header, value count and inferred type name and confidence of a column
+pub type ColumnSummary = (String, usize, Option<(String, f64)>);
+
+// Header, values and metadata of a column, for building a CSV from scratch
+pub(crate) type ColumnParts = (String, Vec<String>, Option<ColumnMetadata>);
+
+// Header, shared values, metadata and lineage of a column, as kept in a saved session
+pub(crate) type SharedColumnParts = (
+    String,
+    Rc<Vec<String>>,
+    Option<ColumnMetadata>,
+    Option<ColumnLineage>,
+);
+
+// Columns of a CSV at one point in time, sharing their values with the table
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    columns: Vec<Column>,
+    row_count: usize,
+}
+
+impl Snapshot {
+    // Internal helpers taking a snapshot apart and putting it back together, keeping values
+    // shared between snapshots shared
+    pub(crate) fn parts(&self) -> (Vec<SharedColumnParts>, usize) {
+        let columns = self
+            .columns
+            .iter()
+            .map(|col| {
+                (
+                    col.header.clone(),
+                    Rc::clone(col.values()),
+                    col.metadata.clone(),
+                    col.lineage.clone(),
+                )
+            })
+            .collect();
+        (columns, self.row_count)
+    }
+
+    pub(crate) fn from_parts(columns: Vec<SharedColumnParts>, row_count: usize) -> Snapshot {
+        let columns = columns
+            .into_iter()
+            .map(|(header, values, metadata, lineage)| Column {
+                header,
+                values: OnceCell::from(values),
+                raw: None,
+                metadata,
+                appended: None,
+                lineage,
+            })
+            .collect();
+        Snapshot { columns, row_count }
+    }
 }
 
 // Column represents a single column of data in the CSV
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Column {
     header: String,
-    values: Vec<String>,
+    // Read from `raw` when first used, then shared with views of this CSV and copied only
+    // when written to while shared
+    values: OnceCell<Rc<Vec<String>>>,
+    // Parsed table and index the values are read from, until they have been
+    raw: Option<(Rc<RawTable>, usize)>,
     metadata: Option<ColumnMetadata>,
+    // Running totals kept by `append_rows`, cleared whenever the values are rewritten
+    appended: Option<AppendState>,
+    // Set once the column is made or rewritten by an operation
+    lineage: Option<ColumnLineage>,
+}
+
+impl Column {
+    fn new(header: String, values: Vec<String>, metadata: Option<ColumnMetadata>) -> Column {
+        Column {
+            header,
+            values: OnceCell::from(Rc::new(values)),
+            raw: None,
+            metadata,
+            appended: None,
+            lineage: None,
+        }
+    }
+
+    fn from_raw(header: String, raw: Rc<RawTable>, index: usize) -> Column {
+        Column {
+            header,
+            values: OnceCell::new(),
+            raw: Some((raw, index)),
+            metadata: None,
+            appended: None,
+            lineage: None,
+        }
+    }
+
+    fn values(&self) -> &Rc<Vec<String>> {
+        self.values.get_or_init(|| {
+            Rc::new(
+                self.raw
+                    .as_ref()
+                    .map_or_else(Vec::new, |(raw, index)| raw.column(*index)),
+            )
+        })
+    }
+
+    fn values_mut(&mut self) -> &mut Vec<String> {
+        self.values();
+        self.raw = None;
+        Rc::make_mut(self.values.get_mut().expect("values were read above"))
+    }
+
+    // One value, without reading the whole column if it hasn't been yet
+    fn value(&self, row: usize) -> Option<&str> {
+        match (self.values.get(), &self.raw) {
+            (Some(values), _) => values.get(row).map(String::as_str),
+            (None, Some((raw, index))) => raw.field(row, *index),
+            (None, None) => None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match (self.values.get(), &self.raw) {
+            (Some(values), _) => values.len(),
+            (None, Some((raw, _))) => raw.rows(),
+            (None, None) => 0,
+        }
+    }
+}
+
+// Type match counts of a column's values, so appended rows are scored without going over the
+// earlier ones again
+#[derive(Debug, Clone)]
+struct AppendState {
+    counts: TypeCounts,
+    // One-pass statistics of a numeric column too large for exact quantiles, with the format
+    // its values were parsed in
+    numeric: Option<(NumberFormat, StreamingNumericStats)>,
+}
+
+impl CSV {
+    fn from_columns(columns: Vec<Column>) -> CSV {
+        // Calculate row count from the first column (all columns should have same length)
+        let row_count = columns.first().map_or(0, Column::len);
+
+        CSV {
+            columns,
+            row_count,
+            cleaning_log: Vec::new(),
+            config: AnalysisConfig::default(),
+            dialect: Dialect::default(),
+            history: History::default(),
+            parse_warnings: Vec::new(),
+            parse_ms: 0.0,
+            metrics: None,
+            sliced_inference: None,
+            column_cache: ColumnCache::default(),
+        }
+    }
+}
+
+// Converts a reader error to a ParseError pointing at the data row, and the field when known.
+// The reader's first data record is row `first_row` of the table.
+pub(crate) fn parse_error(error: csv::Error, has_headers: bool, first_row: usize) -> AnalysisError {
+    // Record 0 is the header line when there is one
+    let row = error.position().map_or(first_row, |pos| {
+        first_row + pos.record().saturating_sub(has_headers as u64) as usize
+    });
+    let column = match error.kind() {
+        csv::ErrorKind::Utf8 { err, .. } => Some(err.field()),
+        _ => None,
+    };
+    AnalysisError::ParseError {
+        row,
+        column,
+        message: error.to_string(),
+    }
+}
+
+// Calls `visit` with the row and fields of each of the reader's records, returning the rows
+// that had fewer than `width` fields; their missing fields read as empty values. Rows are
+// numbered from `first_row`; a row with more fields than `width` fails.
+pub(crate) fn for_each_record<R: std::io::Read>(
+    reader: &mut csv::Reader<R>,
+    width: usize,
+    first_row: usize,
+    mut visit: impl FnMut(usize, &csv::StringRecord),
+) -> Result<Vec<usize>, AnalysisError> {
+    let has_headers = reader.has_headers();
+    let mut short_rows = Vec::new();
+    let mut record = csv::StringRecord::new();
+    let mut row = first_row;
+    while reader
+        .read_record(&mut record)
+        .map_err(|e| parse_error(e, has_headers, first_row))?
+    {
+        if is_short_row(record.len(), width, row)? {
+            short_rows.push(row);
+        }
+        visit(row, &record);
+        row += 1;
+    }
+    Ok(short_rows)
+}
+
+// Whether a row of `fields` fields needs padding to `width`; more fields than that fail
+pub(crate) fn is_short_row(fields: usize, width: usize, row: usize) -> Result<bool, AnalysisError> {
+    if fields > width {
+        return Err(AnalysisError::ParseError {
+            row,
+            column: Some(width),
+            message: format!("Row has {} fields but the header has {}", fields, width),
+        });
+    }
+    Ok(fields < width)
+}
+
+// Values of the reader's records, one Vec per column, and the rows that were padded
+fn read_columns<R: std::io::Read>(
+    reader: &mut csv::Reader<R>,
+    width: usize,
+    first_row: usize,
+) -> Result<(Vec<Vec<String>>, Vec<usize>), AnalysisError> {
+    let mut columns = vec![Vec::new(); width];
+    let short_rows = for_each_record(reader, width, first_row, |_, record| {
+        for (i, column) in columns.iter_mut().enumerate() {
+            column.push(record.get(i).unwrap_or_default().to_string());
+        }
+    })?;
+    Ok((columns, short_rows))
+}
+
+// Like `read_columns`, for records split by `Dialect::split_records`
+fn split_columns(
+    records: Vec<Vec<String>>,
+    width: usize,
+    first_row: usize,
+) -> Result<(Vec<Vec<String>>, Vec<usize>), AnalysisError> {
+    let mut columns = vec![Vec::new(); width];
+    let mut short_rows = Vec::new();
+    for (row, record) in (first_row..).zip(records) {
+        if is_short_row(record.len(), width, row)? {
+            short_rows.push(row);
+        }
+        let mut fields = record.into_iter();
+        for column in columns.iter_mut() {
+            column.push(fields.next().unwrap_or_default());
+        }
+    }
+    Ok((columns, short_rows))
+}
+
+// Names of the columns of a file without a header row
+pub(crate) fn generated_headers(width: usize) -> Vec<String> {
+    (1..=width).map(|i| format!("column_{}", i)).collect()
+}
+
+// Big integer range of an integer column, when it has values past ±2^53
+fn big_integer_column(
+    values: &[String],
+    data_type: DataType,
+    number_format: Option<NumberFormat>,
+) -> Option<BigIntegerColumn> {
+    match data_type {
+        DataType::Integer => BigIntegerColumn::detect(values, &number_format.unwrap_or_default()),
+        _ => None,
+    }
+}
+
+// Order of a categorical column's levels, when they follow a known scale
+fn ordinal_scale(values: &[String], data_type: DataType) -> Option<OrdinalScale> {
+    match data_type {
+        DataType::Categorical => OrdinalScale::detect(values),
+        _ => None,
+    }
+}
+
+// Token statistics of a text column whose values average at least LONG_TEXT_LENGTH
+fn token_stats(
+    values: &[String],
+    data_type: DataType,
+    text_stats: Option<&TextStats>,
+) -> Option<TokenStats> {
+    match data_type {
+        DataType::Text if text_stats?.avg_length >= LONG_TEXT_LENGTH => {
+            calculate_token_stats(values)
+        }
+        _ => None,
+    }
+}
+
+// DECIMAL(p,s) sized to a decimal column's values, and BIGINT or NUMERIC for big integers,
+// instead of the type's default
+fn refined_sql_type(
+    data_type: DataType,
+    numeric_stats: Option<&NumericStats>,
+    big_integer: Option<&BigIntegerColumn>,
+) -> Option<String> {
+    match data_type {
+        DataType::Integer => big_integer.map(BigIntegerColumn::sql_type),
+        DataType::Decimal => numeric_stats?.decimal_type(),
+        _ => None,
+    }
 }
 
 // Implement core CSV functionality
-#[wasm_bindgen]
 impl CSV {
-    // Constructor that creates a CSV from a string
-    #[wasm_bindgen(constructor)]
-    pub fn from_string(raw_data: String) -> Result<CSV, JsError> {
-        // Create a cursor for reading the string data
-        let cursor = Cursor::new(raw_data);
-        let mut reader = Reader::from_reader(cursor);
-
-        // Read headers from the CSV
-        let headers: Vec<String> = reader
-            .headers()
-            .map_err(|e| JsError::new(&format!("Failed to read headers: {}", e)))?
+    /// Parses CSV text in the dialect `Dialect::sniff` detects, so semicolon or tab separated
+    /// files and files without a header row read correctly. Rows with fewer fields than the
+    /// header are padded with empty values and reported by `warnings`; rows with more fail.
+    pub fn from_string(raw_data: String) -> Result<CSV, AnalysisError> {
+        CSV::from_string_with_options(raw_data, &ParseOptions::default())
+    }
+
+    /// Parses CSV text after dropping the banner, comment and footer lines `options` names,
+    /// in its dialect or the one detected from what is left. With several `header_rows`,
+    /// columns get the compound names `flatten_headers` makes of them.
+    pub fn from_string_with_options(
+        raw_data: String,
+        options: &ParseOptions,
+    ) -> Result<CSV, AnalysisError> {
+        options.validate()?;
+        let raw_data = options.prepare(&raw_data).unwrap_or(raw_data);
+        let mut dialect = match &options.dialect {
+            Some(dialect) => dialect.clone(),
+            None => Dialect::sniff(&raw_data),
+        };
+        if options.header_rows == 1 {
+            return CSV::from_string_with_dialect(raw_data, dialect);
+        }
+
+        // Stacked header rows often hold years over numbers, which reads like data
+        if options.dialect.is_none() {
+            dialect.has_headers = true;
+        }
+        if !dialect.has_headers {
+            return CSV::from_string_with_dialect(raw_data, dialect);
+        }
+        let (header_rows, body_start) = dialect.leading_records(&raw_data, options.header_rows);
+        let body = Dialect {
+            has_headers: false,
+            ..dialect.clone()
+        };
+        let mut csv = CSV::from_string_with_dialect(raw_data[body_start..].to_string(), body)?;
+        for (column, header) in csv.columns.iter_mut().zip(flatten_headers(&header_rows)) {
+            column.header = header;
+        }
+        csv.dialect = dialect;
+        Ok(csv)
+    }
+
+    /// Parses CSV text written in the given dialect, skipping detection
+    pub fn from_string_with_dialect(
+        raw_data: String,
+        dialect: Dialect,
+    ) -> Result<CSV, AnalysisError> {
+        dialect.validate()?;
+        let start = now_ms();
+        // With vector scanning, text without quotes is split on the delimiters directly; the
+        // reader handles the rest
+        let split = (scan::VECTORIZED && dialect.is_standard())
+            .then(|| RawTable::split_unquoted(&raw_data))
+            .flatten();
+        let (headers, raw, short_rows) = match split {
+            Some(split) => split,
+            None if dialect.needs_splitter() => {
+                let mut records = dialect.split_records(&raw_data)?;
+                let headers = match (dialect.has_headers, records.is_empty()) {
+                    (true, false) => records.remove(0),
+                    _ => generated_headers(records.first().map_or(0, Vec::len)),
+                };
+                let (raw, short_rows) = RawTable::from_records(records, headers.len())?;
+                (headers, raw, short_rows)
+            }
+            None => {
+                let cursor = Cursor::new(raw_data);
+                let mut reader = dialect.reader_builder().from_reader(cursor);
+                let first = reader.headers().map_err(|e| parse_error(e, true, 0))?;
+                let headers: Vec<String> = match dialect.has_headers {
+                    true => first.iter().map(|h| h.to_string()).collect(),
+                    false => generated_headers(first.len()),
+                };
+                let (raw, short_rows) = RawTable::read(&mut reader, headers.len())?;
+                (headers, raw, short_rows)
+            }
+        };
+
+        // Fields stay in one buffer until a column is used
+        let raw = Rc::new(raw);
+        let columns: Vec<Column> = headers
+            .into_iter()
+            .enumerate()
+            .map(|(index, header)| Column::from_raw(header, Rc::clone(&raw), index))
+            .collect();
+
+        let mut csv = CSV::from_columns(columns);
+        csv.dialect = dialect;
+        csv.parse_ms = now_ms() - start;
+        debug!(
+            target: targets::PARSE,
+            "Parsed {} rows and {} columns",
+            csv.row_count(),
+            csv.column_count()
+        );
+        if !short_rows.is_empty() {
+            let warning = AnalysisWarning::ragged_rows(short_rows);
+            warn!(target: targets::PARSE, "{}", warning.message);
+            csv.parse_warnings.push(warning);
+        }
+        Ok(csv)
+    }
+
+    /// Parses UTF-8 CSV bytes, such as a file read into a Node `Buffer`, skipping a leading
+    /// byte order mark
+    pub fn from_bytes(bytes: &[u8]) -> Result<CSV, AnalysisError> {
+        let bom = if bytes.starts_with(b"\xEF\xBB\xBF") {
+            3
+        } else {
+            0
+        };
+        let raw_data =
+            std::str::from_utf8(&bytes[bom..]).map_err(|e| AnalysisError::EncodingError {
+                offset: bom + e.valid_up_to(),
+            })?;
+        CSV::from_string(raw_data.to_string())
+    }
+
+    /// Number of data rows, not counting the header
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn headers(&self) -> Vec<&str> {
+        self.columns.iter().map(|col| col.header.as_str()).collect()
+    }
+
+    /// Header and values of a column
+    pub fn get_column(&self, index: usize) -> Option<(&str, &[String])> {
+        self.columns
+            .get(index)
+            .map(|col| (col.header.as_str(), col.values().as_slice()))
+    }
+
+    /// Header and values of every column
+    pub fn get_columns(&self) -> Vec<(&str, &[String])> {
+        self.columns
+            .iter()
+            .map(|col| (col.header.as_str(), col.values().as_slice()))
+            .collect()
+    }
+
+    /// Metadata of a column, if it has been inferred
+    pub fn column_metadata(&self, index: usize) -> Option<&ColumnMetadata> {
+        self.columns
+            .get(index)
+            .and_then(|col| col.metadata.as_ref())
+    }
+
+    /// Replaces a column's metadata without re-running inference
+    pub fn replace_column_metadata(
+        &mut self,
+        index: usize,
+        metadata: ColumnMetadata,
+    ) -> Result<(), AnalysisError> {
+        let column = self
+            .columns
+            .get_mut(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        column.metadata = Some(metadata);
+        Ok(())
+    }
+
+    /// Builds a new CSV from a subset of rows, keeping column metadata
+    pub fn select_rows(&self, rows: &[usize]) -> CSV {
+        let columns: Vec<Column> = self
+            .columns
             .iter()
-            .map(|h| h.to_string())
+            .map(|col| Column {
+                lineage: col.lineage.clone(),
+                ..Column::new(
+                    col.header.clone(),
+                    rows.iter()
+                        .filter_map(|&row| col.value(row).map(str::to_string))
+                        .collect(),
+                    col.metadata.clone(),
+                )
+            })
             .collect();
 
-        // Initialize columns with headers
-        let mut columns: Vec<Column> = headers
+        let mut csv = CSV::from_columns(columns);
+        csv.cleaning_log = self.cleaning_log.clone();
+        csv.config = self.config.clone();
+        csv.dialect = self.dialect.clone();
+        csv
+    }
+
+    // Internal helper sharing a column's values without copying them
+    pub(crate) fn shared_values(&self, index: usize) -> Option<Rc<Vec<String>>> {
+        self.columns.get(index).map(|col| Rc::clone(col.values()))
+    }
+
+    // Internal helper building a CSV from headers, values and metadata
+    pub(crate) fn from_parts(columns: Vec<ColumnParts>, config: AnalysisConfig) -> CSV {
+        let columns = columns
             .into_iter()
-            .map(|header| Column {
-                header,
-                values: Vec::new(),
-                metadata: None,
+            .map(|(header, values, metadata)| Column::new(header, values, metadata))
+            .collect();
+        let mut csv = CSV::from_columns(columns);
+        csv.config = config;
+        csv
+    }
+
+    // Internal helper rebuilding a CSV saved by `export_session`
+    pub(crate) fn from_session(
+        table: Snapshot,
+        config: AnalysisConfig,
+        cleaning_log: Vec<CleaningStep>,
+        history: History,
+        dialect: Dialect,
+        parse_warnings: Vec<AnalysisWarning>,
+        parse_ms: f64,
+    ) -> CSV {
+        let mut csv = CSV::from_columns(Vec::new());
+        csv.restore(table);
+        csv.config = config;
+        csv.dialect = dialect;
+        csv.cleaning_log = cleaning_log;
+        csv.history = history;
+        csv.parse_warnings = parse_warnings;
+        csv.parse_ms = parse_ms;
+        csv
+    }
+
+    // Internal helper exposing the time spent reading the input
+    pub(crate) fn parse_time_ms(&self) -> f64 {
+        self.parse_ms
+    }
+
+    /// Delimiter, quote, header row and decimal separator the input was read with
+    pub fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// Analysis options currently in effect
+    pub fn analysis_config(&self) -> &AnalysisConfig {
+        &self.config
+    }
+
+    // Internal helper to get mutable access to a column's values
+    pub(crate) fn column_values_mut(&mut self, index: usize) -> Option<&mut Vec<String>> {
+        self.columns.get_mut(index).map(|col| {
+            col.appended = None;
+            col.values_mut()
+        })
+    }
+
+    // Internal helper appending a new column and running type inference on it
+    pub(crate) fn push_column(&mut self, header: String, values: Vec<String>) -> usize {
+        let index = self.columns.len();
+        self.columns.push(Column::new(header, values, None));
+        if index == 0 {
+            self.row_count = self.columns[0].len();
+        }
+
+        self.columns[index].metadata = self.infer_column(index);
+        index
+    }
+
+    // Internal helper recording a cleaning operation in the audit log and in the lineage of
+    // the column it rewrote
+    pub(crate) fn record_cleaning_step(&mut self, step: CleaningStep) {
+        if let Some(index) = step.column {
+            self.extend_lineage(index, &step.operation);
+        }
+        self.cleaning_log.push(step);
+    }
+
+    // Internal helpers reading and storing a column's lineage, keeping its metadata in step
+    pub(crate) fn stored_lineage(&self, index: usize) -> Option<&ColumnLineage> {
+        self.columns.get(index)?.lineage.as_ref()
+    }
+
+    pub(crate) fn set_column_lineage(&mut self, index: usize, lineage: ColumnLineage) {
+        if let Some(column) = self.columns.get_mut(index) {
+            if let Some(metadata) = &mut column.metadata {
+                metadata.lineage = Some(lineage.clone());
+            }
+            column.lineage = Some(lineage);
+        }
+    }
+
+    // Internal helper renaming a column, keeping its metadata's name in step
+    pub(crate) fn set_header(&mut self, index: usize, header: String) {
+        if let Some(column) = self.columns.get_mut(index) {
+            if let Some(metadata) = &mut column.metadata {
+                metadata.name = header.clone();
+            }
+            column.header = header;
+        }
+    }
+
+    // Internal helper putting the columns at the current indices in `order` first to last;
+    // `order` must hold every index once
+    pub(crate) fn permute_columns(&mut self, order: &[usize]) {
+        let mut columns: Vec<Option<Column>> = std::mem::take(&mut self.columns)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.columns = order.iter().filter_map(|&i| columns[i].take()).collect();
+        // A sliced inference run counts columns by position
+        self.sliced_inference = None;
+    }
+
+    // Internal helper exposing the problems repaired while parsing
+    pub(crate) fn parse_warnings(&self) -> &[AnalysisWarning] {
+        &self.parse_warnings
+    }
+
+    // Internal helper recording a condition of how the input was read, reported by `warnings`
+    pub(crate) fn push_parse_warning(&mut self, warning: AnalysisWarning) {
+        self.parse_warnings.push(warning);
+    }
+
+    /// Every cleaning operation applied to this CSV, with the cells it changed
+    pub fn cleaning_steps(&self) -> &[CleaningStep] {
+        &self.cleaning_log
+    }
+
+    // Internal helper putting back audit log entries removed by `truncate_cleaning_log`; the
+    // restored columns already carry their lineage
+    pub(crate) fn extend_cleaning_log(&mut self, steps: Vec<CleaningStep>) {
+        self.cleaning_log.extend(steps);
+    }
+
+    // Internal helper removing the audit log entries after the first `len`, returning them
+    pub(crate) fn truncate_cleaning_log(&mut self, len: usize) -> Vec<CleaningStep> {
+        self.cleaning_log
+            .split_off(len.min(self.cleaning_log.len()))
+    }
+
+    // Internal helper capturing the columns for undo
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            columns: self.columns.clone(),
+            row_count: self.row_count,
+        }
+    }
+
+    // Internal helper putting back captured columns, returning the replaced ones
+    pub(crate) fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let current = self.snapshot();
+        self.columns = snapshot.columns;
+        self.row_count = snapshot.row_count;
+        current
+    }
+
+    // Internal helpers to access the undo/redo stacks
+    pub(crate) fn undo_history(&self) -> &History {
+        &self.history
+    }
+
+    pub(crate) fn undo_history_mut(&mut self) -> &mut History {
+        &mut self.history
+    }
+
+    /// Validates and stores analysis options; they take effect on the next type inference
+    pub fn apply_config(&mut self, config: AnalysisConfig) -> Result<(), AnalysisError> {
+        config.validate()?;
+        self.config = config;
+        // Counts depend on the synonyms and streamed statistics on the percentiles
+        for column in &mut self.columns {
+            column.appended = None;
+        }
+        Ok(())
+    }
+
+    /// Detects the type and statistics of every column, keeping types pinned by the user.
+    /// Candidate types of all columns are scored first, spread over threads on native builds.
+    /// Columns whose header, values and config match an earlier run are taken from the cache
+    /// instead. The time and memory it took are kept for `last_analysis_metrics`.
+    pub fn infer_column_types(&mut self) {
+        self.sliced_inference = None;
+        let start = now_ms();
+        let mut phases = PhaseTimings {
+            parse_ms: self.parse_ms,
+            ..PhaseTimings::default()
+        };
+
+        let config_hash = self.config.hash();
+        let contents: Vec<[u8; 32]> = (0..self.column_count())
+            .map(|i| self.column_content(i))
+            .collect();
+        let pending: Vec<usize> = (0..self.column_count())
+            .filter(|&i| {
+                !self.columns[i]
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|m| m.user_overridden)
+                    && !self.column_cache.contains(contents[i], &config_hash, None)
             })
             .collect();
+        phases.inference_ms += now_ms() - start;
+        let values: Vec<&[String]> = pending
+            .iter()
+            .map(|&i| self.columns[i].values().as_slice())
+            .collect();
+        let executor = type_detection_executor();
+        let (scores, execution) = detect_column_types(&executor, &values, &self.config.detection);
+        phases.inference_ms += execution.wall_ms;
+        debug!(
+            target: targets::WORKERS,
+            "Scored {} columns in {} chunks on {} workers ({:.0}% busy)",
+            pending.len(),
+            execution.chunks,
+            execution.workers,
+            execution.utilization() * 100.0
+        );
+        let mut scores = pending.into_iter().zip(scores).peekable();
 
-        // Read all records and populate column values
-        for result in reader.records() {
-            match result {
-                Ok(record) => {
-                    for (i, field) in record.iter().enumerate() {
-                        if i < columns.len() {
-                            columns[i].values.push(field.to_string());
-                        }
-                    }
-                }
-                Err(e) => return Err(JsError::new(&format!("Error reading row: {}", e))),
+        let mut cached_columns = 0;
+        for (i, content) in contents.into_iter().enumerate() {
+            let column_scores = scores.next_if(|(column, _)| *column == i).map(|(_, s)| s);
+            if self.infer_stored_column(i, column_scores, content, &config_hash, &mut phases) {
+                cached_columns += 1;
             }
         }
+        self.finish_inference(phases, now_ms() - start, &execution, cached_columns);
+    }
 
-        // Calculate row count from the first column (all columns should have same length)
-        let row_count = if columns.is_empty() {
-            0
+    /// Detects types like `infer_column_types`, one column at a time on the calling thread,
+    /// returning once `budget_ms` has passed after at least one column. Calling it again
+    /// carries on where it stopped, so a host without threads or workers can keep its event
+    /// loop responsive by yielding between calls until the progress is `done`.
+    /// `on_progress` is called after each column.
+    pub fn infer_column_types_slice(
+        &mut self,
+        budget_ms: f64,
+        mut on_progress: impl FnMut(&InferenceProgress),
+    ) -> InferenceProgress {
+        let start = now_ms();
+        let mut run = match self.sliced_inference.take() {
+            Some(run) if run.next_column < self.column_count() => run,
+            _ => SlicedInference {
+                next_column: 0,
+                phases: PhaseTimings {
+                    parse_ms: self.parse_ms,
+                    ..PhaseTimings::default()
+                },
+                elapsed_ms: 0.0,
+                cached_columns: 0,
+            },
+        };
+
+        let mut progress = InferenceProgress {
+            columns_done: run.next_column,
+            columns: self.column_count(),
+            done: false,
+        };
+        let config_hash = self.config.hash();
+        while run.next_column < self.column_count() {
+            let content = self.column_content(run.next_column);
+            if self.infer_stored_column(
+                run.next_column,
+                None,
+                content,
+                &config_hash,
+                &mut run.phases,
+            ) {
+                run.cached_columns += 1;
+            }
+            run.next_column += 1;
+            progress.columns_done = run.next_column;
+            progress.done = run.next_column == self.column_count();
+            on_progress(&progress);
+            if now_ms() - start >= budget_ms {
+                break;
+            }
+        }
+        run.elapsed_ms += now_ms() - start;
+
+        progress.done = run.next_column >= self.column_count();
+        if progress.done {
+            let execution = ExecutionStats {
+                workers: 1,
+                ..ExecutionStats::default()
+            };
+            self.finish_inference(run.phases, run.elapsed_ms, &execution, run.cached_columns);
         } else {
-            columns[0].values.len()
+            self.sliced_inference = Some(run);
+        }
+        progress
+    }
+
+    /// Drops an unfinished sliced inference, so the next slice starts over from the first
+    /// column. Columns already analyzed keep their metadata.
+    pub fn cancel_sliced_inference(&mut self) {
+        self.sliced_inference = None;
+    }
+
+    // Internal helper hashing a column's header and values for the column cache
+    fn column_content(&self, index: usize) -> [u8; 32] {
+        let column = &self.columns[index];
+        column_content_hash(&column.header, column.values())
+    }
+
+    // Internal helper inferring and storing one column's metadata, or recomputing it under
+    // the pinned type, adding the time of each phase to `phases`. Metadata cached for the
+    // same `content` and config is reused; returns whether it was.
+    fn infer_stored_column(
+        &mut self,
+        index: usize,
+        scores: Option<TypeScores>,
+        content: [u8; 32],
+        config_hash: &str,
+        phases: &mut PhaseTimings,
+    ) -> bool {
+        let pinned = self.columns[index]
+            .metadata
+            .as_ref()
+            .filter(|m| m.user_overridden)
+            .map(|m| m.data_type);
+        if let Some(cached) = self.column_cache.get(content, config_hash, pinned) {
+            let mut metadata = cached.clone();
+            metadata.lineage = self.stored_lineage(index).cloned();
+            debug!(
+                target: targets::INFERENCE,
+                "Column '{}' taken from the cache as {}",
+                metadata.name,
+                metadata.data_type
+            );
+            self.columns[index].metadata = Some(metadata);
+            return true;
+        }
+
+        let metadata = match pinned {
+            Some(data_type) => {
+                let column_start = now_ms();
+                let metadata = self.infer_column_as(index, data_type);
+                phases.stats_ms += now_ms() - column_start;
+                metadata
+            }
+            None => self.infer_column_timed(index, scores, phases),
         };
+        if let Some(metadata) = &metadata {
+            debug!(
+                target: targets::INFERENCE,
+                "Column '{}' inferred as {} ({:.2})",
+                metadata.name,
+                metadata.data_type,
+                metadata.confidence
+            );
+            self.column_cache
+                .insert(content, config_hash, pinned, metadata.clone());
+        }
+        self.columns[index].metadata = metadata;
+        false
+    }
 
-        Ok(CSV { columns, row_count })
+    // Internal helper recording the metrics of a finished inference run
+    fn finish_inference(
+        &mut self,
+        phases: PhaseTimings,
+        analysis_ms: f64,
+        execution: &ExecutionStats,
+        cached_columns: usize,
+    ) {
+        // Every column has been read, so the parsed buffer can be freed
+        for column in &mut self.columns {
+            if column.values.get().is_some() {
+                column.raw = None;
+            }
+        }
+        let metrics = AnalysisMetrics::new(phases, analysis_ms, execution, cached_columns, self);
+        info!(
+            target: targets::INFERENCE,
+            "Inferred types of {} columns over {} rows in {:.1} ms ({:.0} rows/s)",
+            self.column_count(),
+            self.row_count,
+            metrics.total_ms - metrics.phases.parse_ms,
+            metrics.rows_per_second
+        );
+        self.metrics = Some(metrics);
     }
 
-    // Get the number of rows in the CSV
-    #[wasm_bindgen]
-    pub fn row_count(&self) -> usize {
-        self.row_count
+    /// Time per phase, estimated peak memory and throughput of the last `infer_column_types`
+    pub fn last_analysis_metrics(&self) -> Option<&AnalysisMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Runs type detection for a single column without storing the result
+    pub fn infer_column(&self, index: usize) -> Option<ColumnMetadata> {
+        self.infer_column_timed(index, None, &mut PhaseTimings::default())
+    }
+
+    // Internal helper running type detection, adding the time of each phase to `phases`.
+    // Candidate types are scored here unless `scores` were computed ahead for the column.
+    fn infer_column_timed(
+        &self,
+        index: usize,
+        scores: Option<TypeScores>,
+        phases: &mut PhaseTimings,
+    ) -> Option<ColumnMetadata> {
+        let (header, values) = self.get_column(index)?;
+        let start = now_ms();
+
+        // First pass: use TypeScores to get initial type analysis
+        let detection = &self.config.detection;
+        let scores = scores
+            .unwrap_or_else(|| TypeScores::from_column(values, detection))
+            .with_header_hints(header, detection.header_hint_weight);
+        let (mut initial_type, mut confidence) =
+            scores.best_type(self.config.detection.type_threshold);
+
+        // Numbers written with other conventions, like "1.234,56" or "(1,234)", only match here
+        let number_format = NumberFormat::detect_preferring(values, self.dialect.decimal_separator);
+        if initial_type == DataType::Text {
+            if let Some(numeric_type) = number_format.and_then(|f| f.numeric_type(values)) {
+                initial_type = numeric_type;
+                confidence = 1.0;
+            }
+        }
+
+        // Measurements like "12 kg" or "100 km/h" are numbers once their unit is stripped
+        let quantity = if initial_type == DataType::Text {
+            QuantityColumn::detect(values, self.config.detection.type_threshold)
+        } else {
+            None
+        };
+        if let Some(quantity) = &quantity {
+            initial_type = DataType::Quantity;
+            confidence = quantity.coverage;
+        }
+
+        // Digit codes like "00423" look like integers but must keep their leading zeros
+        let zero_padded = if initial_type == DataType::Integer {
+            ZeroPaddedCode::detect(values)
+        } else {
+            None
+        };
+
+        // Second pass: enhance type detection with additional analysis
+        let mut final_type = if zero_padded.is_some() {
+            DataType::Text
+        } else if initial_type == DataType::Text {
+            self.analyze_potential_categorical_data(values)
+                .unwrap_or(DataType::Text)
+        } else {
+            initial_type
+        };
+
+        // Columns splitting between several types, like numbers mixed with dates, are
+        // reported with their composition instead of as plain text
+        let mixed_composition = if final_type == DataType::Text && zero_padded.is_none() {
+            MixedComposition::detect(values, scores.candidates(), &self.config.detection)
+        } else {
+            None
+        };
+        if let Some(composition) = &mixed_composition {
+            final_type = DataType::Mixed;
+            confidence = composition.components.iter().map(|c| c.ratio).sum();
+        }
+
+        let inferred = now_ms();
+        phases.inference_ms += inferred - start;
+
+        let sql_type = match zero_padded {
+            Some(code) => code.sql_type(),
+            None => final_type.default_sql_type().to_string(),
+        };
+        let sql_typed = now_ms();
+        phases.sql_ms += sql_typed - inferred;
+
+        let number_format = if let Some(quantity) = &quantity {
+            Some(quantity.number_format)
+        } else if final_type.is_numeric() {
+            Some(number_format.unwrap_or_default())
+        } else {
+            None
+        };
+
+        let big_integer = big_integer_column(values, final_type, number_format);
+        let (numeric_stats, text_stats, date_stats, card_stats, email_stats, phone_stats) = self
+            .type_stats(
+                values,
+                final_type,
+                number_format,
+                quantity.as_ref(),
+                big_integer.as_ref(),
+            );
+        let sql_type = refined_sql_type(final_type, numeric_stats.as_ref(), big_integer.as_ref())
+            .unwrap_or(sql_type);
+        let token_stats = token_stats(values, final_type, text_stats.as_ref());
+        phases.stats_ms += now_ms() - sql_typed;
+
+        Some(ColumnMetadata {
+            name: header.to_string(),
+            data_type: final_type,
+            confidence,
+            numeric_stats,
+            text_stats,
+            date_stats,
+            card_stats,
+            email_stats,
+            phone_stats,
+            sql_type: Some(sql_type),
+            number_format,
+            type_candidates: scores.candidates().to_vec(),
+            mixed_composition,
+            quantity,
+            user_overridden: false,
+            analysis_version: ANALYSIS_VERSION,
+            config_hash: self.config.hash(),
+            analysis_mode: AnalysisMode::Full,
+            lineage: self.stored_lineage(index).cloned(),
+            big_integer,
+            ordinal: ordinal_scale(values, final_type),
+            token_stats,
+        })
     }
 
-    // Get the number of columns in the CSV
-    #[wasm_bindgen]
-    pub fn column_count(&self) -> usize {
-        self.columns.len()
+    // Internal helper computing the statistics matching a column's type
+    fn type_stats(
+        &self,
+        values: &[String],
+        data_type: DataType,
+        number_format: Option<NumberFormat>,
+        quantity: Option<&QuantityColumn>,
+        big_integer: Option<&BigIntegerColumn>,
+    ) -> ColumnStats {
+        let (numeric_stats, text_stats, date_stats) = match data_type {
+            // Floats would round their values, so big integers only get their range
+            DataType::Integer if big_integer.is_some() => (None, None, None),
+            DataType::Integer | DataType::Decimal | DataType::Currency => (
+                calculate_numeric_stats(
+                    values,
+                    &number_format.unwrap_or_default(),
+                    &self.config.numeric_options(),
+                ),
+                None,
+                None,
+            ),
+            DataType::Text
+            | DataType::Email
+            | DataType::Phone
+            | DataType::Categorical
+            | DataType::Mixed => (None, calculate_text_stats(values), None),
+            // Stats are computed on the values converted to one unit, whose digits say nothing
+            // of the precision written
+            DataType::Quantity => (
+                quantity
+                    .and_then(|quantity| {
+                        calculate_numeric_stats(
+                            &quantity.normalized_values(values),
+                            &NumberFormat::default(),
+                            &NumericOptions {
+                                sentinels: &[],
+                                ..self.config.numeric_options()
+                            },
+                        )
+                    })
+                    .map(|stats| NumericStats {
+                        precision: 0,
+                        scale: 0,
+                        ..stats
+                    }),
+                None,
+                None,
+            ),
+            DataType::Date => (None, None, calculate_date_stats(values)),
+            // Text stats would list full card numbers among the most common values
+            DataType::CreditCard => (None, None, None),
+        };
+        let card_stats = if data_type == DataType::CreditCard {
+            calculate_card_stats(values)
+        } else {
+            None
+        };
+        let email_stats = if data_type == DataType::Email {
+            calculate_email_stats(values)
+        } else {
+            None
+        };
+        let phone_stats = if data_type == DataType::Phone {
+            calculate_phone_stats(values)
+        } else {
+            None
+        };
+        (
+            numeric_stats,
+            text_stats,
+            date_stats,
+            card_stats,
+            email_stats,
+            phone_stats,
+        )
     }
 
-    // Get the headers of the CSV
-    #[wasm_bindgen]
-    pub fn headers(&self) -> Result<JsValue, JsError> {
-        let headers = self
-            .columns
-            .iter()
-            .map(|col| col.header.clone())
-            .collect::<Vec<String>>();
+    /// Analyzes a column under a type chosen by the user instead of the inferred one;
+    /// values that don't fit the type are left out of its statistics
+    pub fn infer_column_as(&self, index: usize, data_type: DataType) -> Option<ColumnMetadata> {
+        let (header, values) = self.get_column(index)?;
+        let detection = &self.config.detection;
+        let scores = TypeScores::from_column(values, detection)
+            .with_header_hints(header, detection.header_hint_weight);
 
-        to_value(&headers).map_err(|e| JsError::new(&format!("Failed to serialize headers: {}", e)))
+        let quantity = if data_type == DataType::Quantity {
+            QuantityColumn::detect(values, 0.0)
+        } else {
+            None
+        };
+        // Without any value carrying a unit there is nothing to normalize, so it's text
+        let data_type = match (data_type, &quantity) {
+            (DataType::Quantity, None) => DataType::Text,
+            _ => data_type,
+        };
+        let number_format = match &quantity {
+            Some(quantity) => Some(quantity.number_format),
+            None if data_type.is_numeric() => Some(
+                NumberFormat::detect_preferring(values, self.dialect.decimal_separator)
+                    .unwrap_or_default(),
+            ),
+            None => None,
+        };
+        let mixed_composition = if data_type == DataType::Mixed {
+            MixedComposition::detect(values, scores.candidates(), detection)
+        } else {
+            None
+        };
+        let big_integer = big_integer_column(values, data_type, number_format);
+        let (numeric_stats, text_stats, date_stats, card_stats, email_stats, phone_stats) = self
+            .type_stats(
+                values,
+                data_type,
+                number_format,
+                quantity.as_ref(),
+                big_integer.as_ref(),
+            );
+        let sql_type = refined_sql_type(data_type, numeric_stats.as_ref(), big_integer.as_ref())
+            .unwrap_or_else(|| data_type.default_sql_type().to_string());
+        let token_stats = token_stats(values, data_type, text_stats.as_ref());
+
+        Some(ColumnMetadata {
+            name: header.to_string(),
+            data_type,
+            confidence: 1.0,
+            numeric_stats,
+            text_stats,
+            date_stats,
+            card_stats,
+            email_stats,
+            phone_stats,
+            sql_type: Some(sql_type),
+            number_format,
+            type_candidates: scores.candidates().to_vec(),
+            mixed_composition,
+            quantity,
+            user_overridden: true,
+            analysis_version: ANALYSIS_VERSION,
+            config_hash: self.config.hash(),
+            analysis_mode: AnalysisMode::Full,
+            lineage: self.stored_lineage(index).cloned(),
+            big_integer,
+            ordinal: ordinal_scale(values, data_type),
+            token_stats,
+        })
     }
 
-    // Internal helper to get a column's data
-    pub(crate) fn get_column(&self, index: usize) -> Option<(&str, &[String])> {
-        self.columns
-            .get(index)
-            .map(|col| (col.header.as_str(), col.values.as_slice()))
+    /// Pins a column to the given type, recomputing its statistics and SQL type under it.
+    /// Later calls to `infer_column_types` keep the chosen type. Fails for Quantity when no
+    /// value is a number with a unit.
+    pub fn override_type(
+        &mut self,
+        index: usize,
+        data_type: DataType,
+    ) -> Result<ColumnMetadata, AnalysisError> {
+        let metadata = self
+            .infer_column_as(index, data_type)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        if metadata.data_type != data_type {
+            return Err(AnalysisError::invalid_column(
+                index,
+                format!("No value can be read as {}", data_type),
+            ));
+        }
+        self.columns[index].metadata = Some(metadata.clone());
+        Ok(metadata)
     }
 
-    // Internal helper to get all columns
-    pub(crate) fn get_columns(&self) -> Vec<(&str, &[String])> {
-        self.columns
-            .iter()
-            .map(|col| (col.header.as_str(), col.values.as_slice()))
-            .collect()
+    // Internal helper recomputing a column's statistics under its current type once its
+    // values were rewritten, keeping its confidence and whether the type was pinned
+    pub(crate) fn refresh_column_stats(&mut self, index: usize) {
+        let Some(current) = self.column_metadata(index) else {
+            return;
+        };
+        let (confidence, user_overridden) = (current.confidence, current.user_overridden);
+        if let Some(metadata) = self.infer_column_as(index, current.data_type) {
+            self.columns[index].metadata = Some(ColumnMetadata {
+                confidence,
+                user_overridden,
+                ..metadata
+            });
+        }
     }
 
-    #[wasm_bindgen]
-    pub fn infer_column_types(&mut self) -> Result<(), JsError> {
-        for i in 0..self.column_count() {
-            if let Some((header, values)) = self.get_column(i) {
-                // First pass: use TypeScores to get initial type analysis
-                let scores = TypeScores::from_column(values);
-                let (initial_type, confidence) = scores.best_type();
-
-                // Second pass: enhance type detection with additional analysis
-                let final_type = if initial_type == DataType::Text {
-                    self.analyze_potential_categorical_data(values)
-                        .unwrap_or(DataType::Text)
-                } else {
-                    initial_type
-                };
+    /// Appends rows of headerless CSV text, such as the latest lines of a live source, and
+    /// brings the column metadata up to date without profiling the whole table again. Type
+    /// match counts are kept per column so only the new values are scored; a column is
+    /// inferred again only when the new rows could change its type, and otherwise keeps it
+    /// with updated confidence and statistics. Short rows are padded as in `from_string` and
+    /// a row with too many fields fails, leaving the table unchanged. Columns that were never
+    /// inferred are left for the next `infer_column_types`.
+    pub fn append_rows(&mut self, csv_chunk: &str) -> Result<AppendReport, AnalysisError> {
+        if self.columns.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "Rows can't be appended to a table without columns",
+            ));
+        }
+        let first_new = self.row_count;
+        let width = self.column_count();
+        let (chunk, short_rows) = match self.dialect.needs_splitter() {
+            true => split_columns(self.dialect.split_records(csv_chunk)?, width, first_new)?,
+            false => {
+                let mut reader = self
+                    .dialect
+                    .reader_builder()
+                    .has_headers(false)
+                    .from_reader(csv_chunk.as_bytes());
+                read_columns(&mut reader, width, first_new)?
+            }
+        };
+        let mut report = AppendReport {
+            rows_appended: chunk[0].len(),
+            ..AppendReport::default()
+        };
+        if report.rows_appended == 0 {
+            return Ok(report);
+        }
 
-                // Create and store the column metadata
-                let metadata = ColumnMetadata {
-                    name: header.to_string(),
-                    data_type: final_type,
-                    confidence,
-                };
+        if !short_rows.is_empty() {
+            let mut rows = match self
+                .parse_warnings
+                .iter()
+                .position(|w| w.code == WarningCode::RaggedRows)
+            {
+                Some(position) => self.parse_warnings.remove(position).rows,
+                None => Vec::new(),
+            };
+            rows.extend(short_rows);
+            let warning = AnalysisWarning::ragged_rows(rows);
+            warn!(target: targets::PARSE, "{}", warning.message);
+            self.parse_warnings.push(warning);
+        }
 
-                let js_metadata = to_value(&metadata)
-                    .map_err(|e| JsError::new(&format!("Failed to serialize metadata: {}", e)))?;
-                self.set_column_metadata(i, js_metadata)?;
+        // A sliced run would mix columns inferred with and without the new rows
+        self.sliced_inference = None;
+        let synonyms = self.config.detection.synonyms().unwrap_or_default();
+        let mut streams = Vec::with_capacity(chunk.len());
+        for (column, values) in self.columns.iter_mut().zip(chunk) {
+            let state = column.appended.take();
+            let mut stream = None;
+            if column.metadata.is_some() {
+                // Earlier values are counted once, on the first append after inference
+                let (counts, numeric) = match state {
+                    Some(state) => (state.counts, state.numeric),
+                    None => (TypeCounts::from_values(column.values(), &synonyms), None),
+                };
+                stream = numeric;
+                column.appended = Some(AppendState {
+                    counts: counts.merge(&TypeCounts::from_values(&values, &synonyms)),
+                    numeric: None,
+                });
             }
+            streams.push(stream);
+            column.values_mut().extend(values);
         }
-        Ok(())
+        self.row_count += report.rows_appended;
+
+        let mut phases = PhaseTimings::default();
+        let config_hash = self.config.hash();
+        for (index, stream) in streams.into_iter().enumerate() {
+            let overridden = match &self.columns[index].metadata {
+                Some(metadata) => metadata.user_overridden,
+                None => continue,
+            };
+            if overridden {
+                let content = self.column_content(index);
+                self.infer_stored_column(index, None, content, &config_hash, &mut phases);
+                report.updated_columns.push(index);
+            } else if let Some(metadata) = self.appended_metadata(index, first_new, stream) {
+                self.columns[index].metadata = Some(metadata);
+                report.updated_columns.push(index);
+            } else {
+                // The merged counts still spare scoring the column again
+                let scores = self.columns[index]
+                    .appended
+                    .as_ref()
+                    .map(|state| TypeScores::from_counts(&state.counts));
+                let content = self.column_content(index);
+                self.infer_stored_column(index, scores, content, &config_hash, &mut phases);
+                report.reinferred_columns.push(index);
+            }
+        }
+
+        info!(
+            target: targets::INFERENCE,
+            "Appended {} rows: {} columns updated, {} inferred again",
+            report.rows_appended,
+            report.updated_columns.len(),
+            report.reinferred_columns.len()
+        );
+        Ok(report)
     }
 
-    /// Sets metadata for a specific column
-    #[wasm_bindgen]
-    pub fn set_column_metadata(
+    // Internal helper updating a column's metadata for the values appended from row
+    // `first_new` on, or None when they could change its type. `stream` holds the column's
+    // streamed numeric statistics before the append, if it had any.
+    fn appended_metadata(
         &mut self,
         index: usize,
-        js_metadata: JsValue,
-    ) -> Result<(), JsError> {
-        let metadata: ColumnMetadata = from_value(js_metadata)
-            .map_err(|e| JsError::new(&format!("Failed to deserialize metadata: {}", e)))?;
+        first_new: usize,
+        stream: Option<(NumberFormat, StreamingNumericStats)>,
+    ) -> Option<ColumnMetadata> {
+        let column = &self.columns[index];
+        let metadata = column.metadata.as_ref()?;
+        let state = column.appended.as_ref()?;
+        // Other types come out of later passes over the whole column, like number formats,
+        // units, categories and mixtures, which a new value can tip either way
+        let scored_type = matches!(
+            metadata.data_type,
+            DataType::CreditCard
+                | DataType::Integer
+                | DataType::Currency
+                | DataType::Date
+                | DataType::Email
+                | DataType::Phone
+        );
+        if !scored_type || metadata.config_hash != self.config.hash() {
+            return None;
+        }
 
-        if let Some(column) = self.columns.get_mut(index) {
-            column.metadata = Some(metadata);
-            Ok(())
-        } else {
-            Err(JsError::new("Column index out of bounds"))
+        let detection = &self.config.detection;
+        let scores = TypeScores::from_counts(&state.counts)
+            .with_header_hints(&column.header, detection.header_hint_weight);
+        let (data_type, confidence) = scores.best_type(detection.type_threshold);
+        if data_type != metadata.data_type {
+            return None;
         }
-    }
 
-    /// Retrieves metadata for a specific column
-    #[wasm_bindgen]
-    pub fn get_column_metadata(&self, index: usize) -> Result<JsValue, JsError> {
-        let metadata = self
-            .columns
-            .get(index)
-            .and_then(|col| col.metadata.as_ref())
-            .ok_or_else(|| JsError::new("No metadata found for column"))?;
+        let values = column.values().as_slice();
+        let new_values = &values[first_new..];
+        // New digit codes with leading zeros may turn the whole column into codes, and values
+        // past ±2^53 into big integers
+        if data_type == DataType::Integer
+            && (ZeroPaddedCode::detect(new_values).is_some()
+                || metadata.big_integer.is_some()
+                || big_integer_column(new_values, data_type, metadata.number_format).is_some())
+        {
+            return None;
+        }
+        // The stored convention stays the best one while it parses every new value
+        let number_format = metadata.number_format;
+        if let Some(format) = number_format {
+            let keeps_format = new_values
+                .iter()
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .all(|v| {
+                    format.parse(v).is_some()
+                        && (format.parentheses_negative || !v.starts_with('('))
+                });
+            if !keeps_format {
+                return None;
+            }
+        }
+
+        // Large numeric columns are streamed anyway, so only the new values need pushing
+        let streamed = data_type.is_numeric() && values.len() > EXACT_QUANTILE_LIMIT;
+        let (mut numeric_stats, text_stats, date_stats, card_stats, email_stats, phone_stats) =
+            if streamed {
+                (None, None, None, None, None, None)
+            } else {
+                self.type_stats(values, data_type, number_format, None, None)
+            };
+        if streamed {
+            let format = number_format.unwrap_or_default();
+            let stream = match stream {
+                Some((streamed_format, mut stream)) if streamed_format == format => {
+                    stream.extend(new_values.iter().map(|v| v.as_str()));
+                    stream
+                }
+                _ => {
+                    let mut stream =
+                        StreamingNumericStats::with_percentiles(&self.config.percentiles)
+                            .with_compression(self.config.quantile_compression)
+                            .with_format(format)
+                            .with_sentinels(&self.config.sentinel_values);
+                    stream.extend(values.iter().map(|v| v.as_str()));
+                    stream
+                }
+            };
+            numeric_stats = stream.finish();
+            if let Some(state) = &mut self.columns[index].appended {
+                state.numeric = Some((format, stream));
+            }
+        }
 
-        to_value(&metadata)
-            .map_err(|e| JsError::new(&format!("Failed to serialize metadata: {}", e)))
+        let metadata = self.columns[index].metadata.as_ref()?;
+        Some(ColumnMetadata {
+            confidence,
+            numeric_stats,
+            text_stats,
+            date_stats,
+            card_stats,
+            email_stats,
+            phone_stats,
+            type_candidates: scores.candidates().to_vec(),
+            ..metadata.clone()
+        })
     }
 
     /// Advanced analysis for potential categorical data
@@ -228,37 +1636,58 @@ impl CSV {
         // 1. Low ratio of unique values (< 5%)
         // 2. Values aren't too long (< 50 chars on average)
         // 3. Most values appear multiple times
-        if unique_ratio < 0.05 && avg_length < 50.0 && frequency_ratio > 0.7 {
+        // 4. Values don't read as sentences, like canned replies repeated word for word
+        if unique_ratio < 0.05
+            && avg_length < 50.0
+            && frequency_ratio > 0.7
+            && !calculate_token_stats(values).is_some_and(|tokens| tokens.prose)
+        {
             Some(DataType::Categorical)
         } else {
             None
         }
     }
 
-    /// Retrieves a summary of the CSV structure and types
-    #[wasm_bindgen]
-    pub fn get_structure_summary(&self) -> Result<JsValue, JsError> {
-        let summary = self
-            .columns
+    /// Header, value count and, once inferred, type name and confidence of every column
+    pub fn structure_summary(&self) -> Vec<ColumnSummary> {
+        self.columns
             .iter()
             .map(|col| {
                 let metadata = col.metadata.as_ref().map(|m| (m.data_type, m.confidence));
                 (
                     col.header.clone(),
-                    col.values.len(),
+                    col.len(),
                     metadata.map(|(t, c)| (t.to_string(), c)),
                 )
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
+
+    /// Writes the headers and current values back out as CSV text
+    pub fn to_csv_string(&self) -> Result<String, AnalysisError> {
+        let write_error = |e: &dyn std::fmt::Display| AnalysisError::SerializationError {
+            message: format!("Failed to write CSV: {}", e),
+        };
+        let mut writer = Writer::from_writer(Vec::new());
+        writer
+            .write_record(self.headers())
+            .map_err(|e| write_error(&e))?;
+        for row in 0..self.row_count {
+            let record = self
+                .columns
+                .iter()
+                .map(|col| col.value(row).unwrap_or_default());
+            writer.write_record(record).map_err(|e| write_error(&e))?;
+        }
 
-        to_value(&summary).map_err(|e| JsError::new(&format!("Failed to serialize summary: {}", e)))
+        let bytes = writer.into_inner().map_err(|e| write_error(&e))?;
+        String::from_utf8(bytes).map_err(|e| write_error(&e))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wasm_bindgen_test::*;
 
     // Basic CSV functionality tests
     #[test]
@@ -279,228 +1708,584 @@ mod tests {
         assert_eq!(csv.row_count(), 3); // Empty line is still a row
     }
 
-    // Numeric type detection tests
-    #[wasm_bindgen_test]
-    fn test_numeric_detection() {
-        // Test integer detection
-        let data = "numbers\n123\n456\n789\n1,234\n-5,678";
+    #[test]
+    fn test_credit_card_columns_hide_numbers() {
+        let data = "card\n4111111111111111\n5500000000000004";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Integer);
-        assert!(metadata.confidence > 0.9);
+        let metadata = csv.column_metadata(0).unwrap();
+        assert_eq!(metadata.data_type, DataType::CreditCard);
+        assert!(metadata.text_stats.is_none());
+        assert_eq!(metadata.card_stats.as_ref().unwrap().brands.len(), 2);
+    }
 
-        // Test decimal detection
-        let data = "decimals\n123.45\n456.78\n789.01\n1,234.56\n-5,678.90";
+    #[test]
+    fn test_zero_padded_codes_stay_text() {
+        let data = "zip,count
+02134,1
+90210,20
+00501,3";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Decimal);
-        assert!(metadata.confidence > 0.9);
-    }
+        let zip = csv.column_metadata(0).unwrap();
+        assert_eq!(zip.data_type, DataType::Text);
+        assert_eq!(zip.sql_type.as_deref(), Some("CHAR(5)"));
+        assert!(zip.numeric_stats.is_none());
 
-    // Currency detection tests
-    #[wasm_bindgen_test]
-    fn test_currency_detection() {
-        let data = "amounts\n$1,234.56\n$2,345.67\n$3,456.78\nUSD 4,567.89\n$-1,234.56";
-        let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        let count = csv.column_metadata(1).unwrap();
+        assert_eq!(count.data_type, DataType::Integer);
+        assert_eq!(count.sql_type.as_deref(), Some("INT"));
+    }
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Currency);
-        assert!(metadata.confidence > 0.9);
+    #[test]
+    fn test_token_stats_and_repeated_sentences() {
+        let mut data = "status,reply\n".to_string();
+        for i in 0..60 {
+            let (status, reply) = match i % 2 {
+                0 => ("open", "Thank you for your order, it is on the way"),
+                _ => ("closed", "We are sorry that the item was out of stock"),
+            };
+            data.push_str(&format!("{},\"{}\"\n", status, reply));
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+
+        // Two canned replies repeat like levels but read as sentences
+        let reply = csv.column_metadata(1).unwrap();
+        assert_eq!(reply.data_type, DataType::Text);
+        let tokens = reply.token_stats.as_ref().unwrap();
+        assert!(tokens.prose);
+        assert_eq!(tokens.language.as_deref(), Some("en"));
+        let status = csv.column_metadata(0).unwrap();
+        assert_eq!(status.data_type, DataType::Categorical);
+        assert!(status.token_stats.is_none());
+    }
 
-        // Test with some missing currency symbols
-        let data = "amounts\n$1,234.56\n2,345.67\n$3,456.78\n4,567.89";
+    #[test]
+    fn test_big_integer_columns() {
+        let data = "id,tweet,n
+12345678901234567890,1790000000000000001,1
+98765432109876543210,1790000000000000002,2";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
-
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        // Should still detect as currency if pattern is consistent enough
-        assert_eq!(metadata.data_type, DataType::Currency);
+        csv.infer_column_types();
+
+        let id = csv.column_metadata(0).unwrap();
+        assert_eq!(id.data_type, DataType::Integer);
+        assert_eq!(id.sql_type.as_deref(), Some("NUMERIC(20,0)"));
+        assert!(id.numeric_stats.is_none());
+        assert_eq!(id.big_integer.as_ref().unwrap().max, "98765432109876543210");
+
+        let tweet = csv.column_metadata(1).unwrap();
+        assert_eq!(tweet.sql_type.as_deref(), Some("BIGINT"));
+        let page = csv.row_page(0, 1).unwrap();
+        assert_eq!(page.rows[0]["tweet"], "1790000000000000001");
+        assert_eq!(page.rows[0]["n"], 1.0);
+        assert!(csv.column_metadata(2).unwrap().big_integer.is_none());
     }
 
-    // Date format detection tests
-    #[wasm_bindgen_test]
-    fn test_date_detection() {
-        // Test ISO format dates
-        let data = "dates\n2024-01-01\n2024-02-15\n2024-03-30";
+    #[test]
+    fn test_mixed_type_columns() {
+        let data = "when,note
+2024-01-15,a
+3,b
+2024-02-01,c
+7,d";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
+
+        let when = csv.column_metadata(0).unwrap();
+        assert_eq!(when.data_type, DataType::Mixed);
+        assert_eq!(when.confidence, 1.0);
+        let composition = when.mixed_composition.as_ref().unwrap();
+        let rows: Vec<(DataType, Vec<usize>)> = composition
+            .components
+            .iter()
+            .map(|c| (c.data_type, c.rows.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (DataType::Integer, vec![1, 3]),
+                (DataType::Date, vec![0, 2])
+            ]
+        );
+        assert!(when.text_stats.is_some());
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Date);
-        assert!(metadata.confidence > 0.9);
+        let note = csv.column_metadata(1).unwrap();
+        assert_eq!(note.data_type, DataType::Text);
+        assert!(note.mixed_composition.is_none());
+    }
 
-        // Test mixed date formats
-        let data = "dates\n2024-01-01\n01/15/2024\n2024/01/30\n2024-02-01";
+    #[test]
+    fn test_quantity_columns() {
+        let data = "weight,size
+12 kg,3.5 MB
+500 g,1 GB
+2 kg,700 MB";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
-
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Date);
-        // Confidence might be lower with mixed formats but should still be reasonable
-        assert!(metadata.confidence > 0.7);
+        csv.infer_column_types();
+
+        let weight = csv.column_metadata(0).unwrap();
+        assert_eq!(weight.data_type, DataType::Quantity);
+        assert_eq!(weight.sql_type.as_deref(), Some("DECIMAL(18,6)"));
+        let quantity = weight.quantity.as_ref().unwrap();
+        assert_eq!(quantity.normalized_unit, "kg");
+        let stats = weight.numeric_stats.as_ref().unwrap();
+        assert_eq!((stats.min, stats.max), (0.5, 12.0));
+
+        let size = csv.column_metadata(1).unwrap();
+        assert_eq!(size.data_type, DataType::Quantity);
+        assert_eq!(size.quantity.as_ref().unwrap().normalized_unit, "MB");
+        assert_eq!(size.numeric_stats.as_ref().unwrap().max, 1000.0);
     }
 
-    // Email format detection tests
-    #[wasm_bindgen_test]
-    fn test_email_detection() {
-        let data =
-            "emails\nuser@example.com\nname.surname@domain.co.uk\ntest123@subdomain.site.com";
+    #[test]
+    fn test_quantity_override_without_matches() {
+        let data = "value\n1.5 kg\n2.5 kg\n\"1,5 MB\"\n\"2,5 MB\"\n\"3,5 m\"";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Email);
-        assert!(metadata.confidence > 0.9);
+        assert!(csv.override_type(0, DataType::Quantity).is_err());
+        let metadata = csv.infer_column_as(0, DataType::Quantity).unwrap();
+        assert_eq!(metadata.data_type, DataType::Text);
+        assert!(metadata.quantity.is_none());
+    }
 
-        // Test with some invalid emails mixed in
-        let data = "emails\nuser@example.com\ninvalid.email\ntest@domain.com";
-        let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+    #[test]
+    fn test_number_format_detection() {
+        let data = "amount;label
+\"1.234,50\";a
+\"(12,25)\";b
+\"7\";c"
+            .replace(';', ",");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        // Should fall back to Text if too many invalid emails
-        assert!(matches!(
-            metadata.data_type,
-            DataType::Email | DataType::Text
-        ));
+        let metadata = csv.column_metadata(0).unwrap();
+        assert_eq!(metadata.data_type, DataType::Decimal);
+        let format = metadata.number_format.unwrap();
+        assert_eq!(format.thousands_separator, Some('.'));
+        assert_eq!(format.decimal_separator, ',');
+        assert!(format.parentheses_negative);
+        assert_eq!(metadata.numeric_stats.as_ref().unwrap().min, -12.25);
+
+        assert!(csv.column_metadata(1).unwrap().number_format.is_none());
     }
 
-    // Phone number detection tests
-    #[wasm_bindgen_test]
-    fn test_phone_detection() {
-        let data = "phones\n(123) 456-7890\n123-456-7890\n1234567890\n+1-123-456-7890";
+    #[test]
+    fn test_negative_currency_amounts() {
+        let data = "balance\n$100.00\n\"($1,050.25)\"\n-$10.00";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Phone);
-        assert!(metadata.confidence > 0.8);
+        let metadata = csv.column_metadata(0).unwrap();
+        assert_eq!(metadata.data_type, DataType::Currency);
+        let stats = metadata.numeric_stats.as_ref().unwrap();
+        assert_eq!(stats.min, -1050.25);
+        assert_eq!(stats.negative_count, 2);
+    }
 
-        // Test international formats
-        let data = "phones\n+44 20 7123 4567\n+1 (123) 456-7890\n+61 2 8123 4567";
+    // Analysis configuration tests
+    #[test]
+    fn test_configured_percentiles() {
+        let data = "values\n1\n2\n3\n4\n5";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
-
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Phone);
+        let config = AnalysisConfig {
+            percentiles: vec![50.0, 75.0],
+            ..AnalysisConfig::default()
+        };
+        csv.apply_config(config).unwrap();
+        csv.infer_column_types();
+
+        let stats = csv
+            .column_metadata(0)
+            .unwrap()
+            .numeric_stats
+            .clone()
+            .unwrap();
+        let percentiles: Vec<(f64, f64)> =
+            stats.percentiles.iter().map(|p| (p.p, p.value)).collect();
+        assert_eq!(percentiles, vec![(50.0, 3.0), (75.0, 4.0)]);
+
+        let invalid = AnalysisConfig {
+            percentiles: vec![-1.0],
+            ..AnalysisConfig::default()
+        };
+        assert!(csv.apply_config(invalid).is_err());
     }
 
-    // Categorical data detection tests
-    #[wasm_bindgen_test]
-    fn test_categorical_detection() {
-        // Test obvious categorical data
-        let data = "status\nactive\npending\nactive\npending\nactive\ncompleted";
+    #[test]
+    fn test_column_type_override() {
+        let data = "code,amount\n101,5\n102,7\n103,n/a\n104,9";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(0).unwrap().data_type, DataType::Integer);
+        assert_eq!(csv.column_metadata(1).unwrap().data_type, DataType::Text);
+
+        let metadata = csv.override_type(0, DataType::Text).unwrap();
+        assert!(metadata.user_overridden);
+        assert_eq!(metadata.confidence, 1.0);
+        assert!(metadata.numeric_stats.is_none());
+        assert_eq!(metadata.text_stats.unwrap().max_length, 3);
+        assert_eq!(metadata.sql_type.as_deref(), Some("TEXT"));
+
+        // Values that don't parse are left out of the stats of the forced type
+        let metadata = csv.override_type(1, DataType::Integer).unwrap();
+        let stats = metadata.numeric_stats.unwrap();
+        assert_eq!((stats.min, stats.max), (5.0, 9.0));
+
+        // Re-running inference keeps the pinned types
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(0).unwrap().data_type, DataType::Text);
+        assert_eq!(csv.column_metadata(1).unwrap().data_type, DataType::Integer);
+        assert!(csv.column_metadata(1).unwrap().user_overridden);
+
+        assert!(csv.override_type(2, DataType::Text).is_err());
+    }
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Categorical);
+    #[test]
+    fn test_from_bytes() {
+        let csv = CSV::from_bytes(b"\xEF\xBB\xBFid,name\n1,Ada").unwrap();
+        assert_eq!(csv.headers(), vec!["id", "name"]);
+        assert_eq!(csv.row_count(), 1);
 
-        // Test with larger number of categories but still categorical
-        let mut data = String::from("priority\n");
-        for _ in 0..100 {
-            data.push_str("High\nMedium\nLow\nCritical\n");
-        }
+        assert_eq!(
+            CSV::from_bytes(b"id\n\xFF").unwrap_err(),
+            AnalysisError::EncodingError { offset: 3 }
+        );
+    }
 
-        let mut csv = CSV::from_string(data).unwrap();
-        csv.infer_column_types().unwrap();
+    #[test]
+    fn test_sniffed_dialect() {
+        let data = "id;price;name\n1;1,250;\"Ada; Lady\"\n2;3,75;Alan\n3;12,5;Grace";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        assert_eq!(csv.headers(), vec!["id", "price", "name"]);
+        assert_eq!(csv.get_column(2).unwrap().1[0], "Ada; Lady");
+        assert_eq!(csv.dialect().decimal_separator, ',');
+        csv.infer_column_types();
+        // "1,250" reads as a decimal like the rest of the column
+        let stats = csv
+            .column_metadata(1)
+            .unwrap()
+            .numeric_stats
+            .clone()
+            .unwrap();
+        assert_eq!(stats.max, 12.5);
+        assert_eq!(stats.min, 1.25);
+
+        csv.append_rows("4;2,5;Edsger\n").unwrap();
+        assert_eq!(csv.get_column(2).unwrap().1[3], "Edsger");
+
+        let dialect = Dialect {
+            delimiter: "|".to_string(),
+            has_headers: false,
+            ..Dialect::default()
+        };
+        let csv =
+            CSV::from_string_with_dialect("id|name\n1|Ada".to_string(), dialect.clone()).unwrap();
+        assert_eq!(csv.headers(), vec!["column_1", "column_2"]);
+        assert_eq!(csv.get_column(1).unwrap().1, ["name", "Ada"]);
+        assert_eq!(csv.dialect(), &dialect);
+    }
+
+    #[test]
+    fn test_skipped_lines() {
+        let data = "Quarterly export\n\n# generated by ledger\nid;amount\n1;12,5\n# 2;99\n3;7,25\nTotal;19,75\n";
+        let options = ParseOptions {
+            skip_rows: 2,
+            comment_prefix: Some("#".to_string()),
+            skip_footer_rows: 1,
+            ..ParseOptions::default()
+        };
+        let mut csv = CSV::from_string_with_options(data.to_string(), &options).unwrap();
+        assert_eq!(csv.headers(), vec!["id", "amount"]);
+        assert_eq!(csv.get_column(0).unwrap().1, ["1", "3"]);
+        assert_eq!(csv.dialect().delimiter, ";");
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(0).unwrap().data_type, DataType::Integer);
+    }
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Categorical);
+    #[test]
+    fn test_stacked_headers() {
+        let data =
+            ",2023,,2024\n,Q1,Q2,Q1\nRegion,Revenue,Revenue,Revenue\nNorth,10,12,14\nSouth,7,8,9\n";
+        let options = ParseOptions {
+            header_rows: 3,
+            ..ParseOptions::default()
+        };
+        let mut csv = CSV::from_string_with_options(data.to_string(), &options).unwrap();
+        assert_eq!(
+            csv.headers(),
+            vec![
+                "Region",
+                "2023_Q1_Revenue",
+                "2023_Q2_Revenue",
+                "2024_Q1_Revenue"
+            ]
+        );
+        assert_eq!(csv.row_count(), 2);
+        assert!(csv.dialect().has_headers);
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(3).unwrap().data_type, DataType::Integer);
     }
 
-    // Multiple column type detection tests
-    #[wasm_bindgen_test]
-    fn test_multiple_columns() {
-        let data = "id,name,email,status,amount\n\
-                   1,John Smith,john@test.com,active,$1,234.56\n\
-                   2,Jane Doe,jane@test.com,pending,$2,345.67\n\
-                   3,Bob Wilson,bob@test.com,completed,$3,456.78";
+    #[test]
+    fn test_multi_character_delimiters() {
+        let data = "id::name::joined\n1::\"Ada::L\"::2024-01-05\n2::Alan\n";
+        let dialect = Dialect {
+            delimiter: "::".to_string(),
+            ..Dialect::default()
+        };
+        let mut csv = CSV::from_string_with_dialect(data.to_string(), dialect).unwrap();
+        assert_eq!(csv.headers(), vec!["id", "name", "joined"]);
+        assert_eq!(csv.get_column(1).unwrap().1, ["Ada::L", "Alan"]);
+        assert_eq!(csv.warnings()[0].rows, vec![1]);
 
-        let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.append_rows("3::Grace::2024-02-01\n").unwrap();
+        assert_eq!(csv.get_column(2).unwrap().1[2], "2024-02-01");
+        assert!(matches!(
+            csv.append_rows("4::a::b::c"),
+            Err(AnalysisError::ParseError { row: 3, .. })
+        ));
+
+        let dialect = Dialect {
+            delimiter: r"\s*\|\s*".to_string(),
+            regex: true,
+            has_headers: false,
+            ..Dialect::default()
+        };
+        let csv = CSV::from_string_with_dialect("1 | Ada\n2|  Alan".to_string(), dialect).unwrap();
+        assert_eq!(csv.headers(), vec!["column_1", "column_2"]);
+        assert_eq!(csv.get_column(1).unwrap().1, ["Ada", "Alan"]);
+    }
 
-        // Check each column's type
-        let id_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(id_meta.data_type, DataType::Integer);
+    #[test]
+    fn test_parse_errors_locate_the_row() {
+        let error = CSV::from_string("a,b\n1,2\n3,4,5".to_string()).unwrap_err();
+        assert_eq!(error.code(), "parse_error");
+        assert!(
+            matches!(
+                error,
+                AnalysisError::ParseError {
+                    row: 1,
+                    column: Some(2),
+                    ..
+                }
+            ),
+            "got {:?}",
+            error
+        );
+    }
 
-        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
-        assert_eq!(name_meta.data_type, DataType::Text);
+    #[test]
+    fn test_csv_round_trip() {
+        let data = "name,note\nAda,\"Hello, world\"\nAlan,\"said \"\"hi\"\"\"\n";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+        let written = csv.to_csv_string().unwrap();
+        assert_eq!(written, data);
 
-        let email_meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
-        assert_eq!(email_meta.data_type, DataType::Email);
+        let reread = CSV::from_string(written).unwrap();
+        assert_eq!(
+            reread.get_column(1).unwrap().1,
+            csv.get_column(1).unwrap().1
+        );
+    }
 
-        let status_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
-        assert_eq!(status_meta.data_type, DataType::Categorical);
+    #[test]
+    fn test_sliced_inference_matches_full_inference() {
+        let data = "id,price,joined,email
+                    1,$1.50,2024-01-01,ada@example.com
+                    2,$2.25,2024-02-01,alan@example.com
+                    3,$9.99,2024-03-01,grace@example.com";
+        let mut full = CSV::from_string(data.to_string()).unwrap();
+        full.infer_column_types();
+        let mut sliced = CSV::from_string(data.to_string()).unwrap();
+
+        // A zero budget analyzes one column per slice
+        let mut reported = Vec::new();
+        let mut slices = 0;
+        loop {
+            let progress = sliced.infer_column_types_slice(0.0, |p| reported.push(p.columns_done));
+            slices += 1;
+            assert_eq!(progress.columns, 4);
+            if progress.done {
+                break;
+            }
+            assert!(sliced.last_analysis_metrics().is_none());
+        }
+        assert_eq!(slices, 4);
+        assert_eq!(reported, vec![1, 2, 3, 4]);
+        for i in 0..4 {
+            let (a, b) = (full.column_metadata(i), sliced.column_metadata(i));
+            assert_eq!(a.map(|m| m.data_type), b.map(|m| m.data_type));
+        }
+        assert_eq!(sliced.last_analysis_metrics().unwrap().workers, 1);
 
-        let amount_meta: ColumnMetadata = from_value(csv.get_column_metadata(4).unwrap()).unwrap();
-        assert_eq!(amount_meta.data_type, DataType::Currency);
+        // A generous budget finishes in one slice, starting over after a finished run
+        let progress = sliced.infer_column_types_slice(f64::INFINITY, |_| {});
+        assert_eq!((progress.columns_done, progress.done), (4, true));
     }
 
-    // Data quality and edge case tests
-    #[wasm_bindgen_test]
-    fn test_data_quality_handling() {
-        // Test handling of missing values
-        let data = "values\n123\n\n456\n\t\n789\n  \n";
+    #[test]
+    fn test_unchanged_columns_come_from_the_cache() {
+        let data = "id,price,note\n1,-999,a\n2,2.5,b\n3,4.0,c";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
-
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        let cached = |csv: &CSV| csv.last_analysis_metrics().unwrap().cached_columns;
+        csv.infer_column_types();
+        assert_eq!(cached(&csv), 0);
+        let price = |csv: &CSV| serde_json::to_value(csv.column_metadata(1)).unwrap();
+        let first = price(&csv);
+        assert_eq!(first["numeric_stats"]["min"], -999.0, "no sentinels by default");
+
+        csv.infer_column_types();
+        assert_eq!(cached(&csv), 3);
+        assert_eq!(price(&csv), first);
+
+        // Toggling a setting misses the cache, and toggling it back hits it again
+        let config = csv.analysis_config().clone();
+        let toggled = AnalysisConfig {
+            sentinel_values: vec![-999.0],
+            ..config.clone()
+        };
+        csv.apply_config(toggled).unwrap();
+        csv.infer_column_types();
+        assert_eq!(cached(&csv), 0);
         assert_eq!(
-            metadata.data_type,
-            DataType::Integer,
-            "Should handle empty/whitespace values"
+            csv.column_metadata(1)
+                .unwrap()
+                .numeric_stats
+                .as_ref()
+                .unwrap()
+                .min,
+            2.5
+        );
+        csv.apply_config(config).unwrap();
+        csv.infer_column_types();
+        assert_eq!(cached(&csv), 3);
+        assert_eq!(price(&csv), first);
+
+        // Only the changed column is analyzed again
+        csv.replace_in_column(2, "^a$", "z").unwrap();
+        csv.infer_column_types();
+        assert_eq!(cached(&csv), 2);
+        assert_eq!(
+            csv.column_metadata(2).unwrap().lineage,
+            csv.stored_lineage(2).cloned()
         );
-
-        // Test handling of quoted values
-        let data = "text,\"header,with,comma\"\n\
-                   value1,\"value,with,commas\"\n\
-                   value2,\"another,quoted,value\"";
-        let csv = CSV::from_string(data.to_string());
-        assert!(csv.is_ok(), "Should handle quoted values with commas");
     }
 
-    // Unicode and special character handling tests
-    #[wasm_bindgen_test]
-    fn test_special_characters() {
-        // Test Unicode in text fields
-        let data = "description\n🌟 Special offer!\n⭐ Featured item\n❤️ Popular choice";
+    #[test]
+    fn test_columns_are_read_when_used() {
+        let data = "id,name,notes\n1,Ada,\"said \"\"hi\"\"\"\n2,Alan,\n3,Grace,x";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        let read = |csv: &CSV| -> Vec<bool> {
+            csv.columns
+                .iter()
+                .map(|c| c.values.get().is_some())
+                .collect()
+        };
+        assert_eq!(csv.row_count(), 3);
+        assert_eq!(read(&csv), [false, false, false]);
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Text);
+        // Single values, row subsets and writing out don't read whole columns
+        assert_eq!(csv.to_csv_string().unwrap(), format!("{}\n", data));
+        assert_eq!(csv.select_rows(&[2]).get_column(1).unwrap().1, ["Grace"]);
+        assert_eq!(read(&csv), [false, false, false]);
 
-        // Test special characters in categorical data
-        let data = "status\n★ Gold\n★ Gold\n☆ Silver\n★ Gold\n☆ Silver";
-        let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        assert_eq!(csv.infer_column(2).unwrap().name, "notes");
+        assert_eq!(csv.get_column(2).unwrap().1, ["said \"hi\"", "", "x"]);
+        assert_eq!(read(&csv), [false, false, true]);
 
-        let metadata: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(metadata.data_type, DataType::Categorical);
+        csv.infer_column_types();
+        assert_eq!(read(&csv), [true, true, true]);
+        assert!(csv.columns.iter().all(|c| c.raw.is_none()));
     }
 
-    // Error handling tests
-    #[wasm_bindgen_test]
-    fn test_error_handling() {
-        // Test invalid column index
-        let data = "header\nvalue";
-        let csv = CSV::from_string(data.to_string()).unwrap();
-        assert!(csv.get_column_metadata(999).is_err());
+    fn metadata_json(csv: &CSV) -> Vec<serde_json::Value> {
+        (0..csv.column_count())
+            .map(|i| serde_json::to_value(csv.column_metadata(i)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_appended_rows_match_full_inference() {
+        let row = |i: usize| {
+            format!(
+                "{},${}.50,2024-01-{:02},user{}@example.com,note {}\n",
+                i,
+                i * 3,
+                i % 28 + 1,
+                i,
+                i
+            )
+        };
+        let header = "id,price,joined,email,note\n";
+        let first: String = (0..30).map(row).collect();
+        let second: String = (30..45).map(row).collect();
+
+        let mut csv = CSV::from_string(format!("{}{}", header, first)).unwrap();
+        csv.infer_column_types();
+        let report = csv.append_rows(&second).unwrap();
+        assert_eq!(report.rows_appended, 15);
+        assert_eq!(report.updated_columns, vec![0, 1, 2, 3]);
+        assert_eq!(report.reinferred_columns, vec![4]);
+        assert_eq!(csv.row_count(), 45);
+
+        let mut full = CSV::from_string(format!("{}{}{}", header, first, second)).unwrap();
+        full.infer_column_types();
+        assert_eq!(metadata_json(&csv), metadata_json(&full));
+
+        // Counts carry over between appends
+        csv.append_rows(&row(45)).unwrap();
+        full = CSV::from_string(format!("{}{}{}{}", header, first, second, row(45))).unwrap();
+        full.infer_column_types();
+        assert_eq!(metadata_json(&csv), metadata_json(&full));
+    }
 
-        // Test completely empty CSV
-        let data = "";
-        assert!(CSV::from_string(data.to_string()).is_err());
+    #[test]
+    fn test_appended_rows_reinfer_when_type_could_change() {
+        let first: String = (1..=25).map(|i| format!("{},{}\n", i, i * 10)).collect();
+        let mut csv = CSV::from_string(format!("qty,code\n{}", first)).unwrap();
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(1).unwrap().data_type, DataType::Integer);
+
+        // Words outvote the numbers in qty and a padded code turns code into text
+        let second = "n/a,0042\nunknown,7\nnone,8\n".repeat(10);
+        let report = csv.append_rows(&second).unwrap();
+        assert_eq!(report.reinferred_columns, vec![0, 1]);
+
+        let mut full = CSV::from_string(format!("qty,code\n{}{}", first, second)).unwrap();
+        full.infer_column_types();
+        assert_ne!(csv.column_metadata(0).unwrap().data_type, DataType::Integer);
+        assert_eq!(csv.column_metadata(1).unwrap().data_type, DataType::Text);
+        assert_eq!(metadata_json(&csv), metadata_json(&full));
+    }
 
-        // Test headers only
-        let data = "header1,header2";
-        let csv = CSV::from_string(data.to_string()).unwrap();
-        assert_eq!(csv.row_count(), 0);
+    #[test]
+    fn test_append_rows_checks_widths() {
+        let mut csv = CSV::from_string("a,b\n1,2\n3".to_string()).unwrap();
+        let report = csv.append_rows("4\n5,6").unwrap();
+        assert_eq!(report.rows_appended, 2);
+        assert_eq!(csv.get_column(1).unwrap().1, ["2", "", "", "6"]);
+        let ragged: Vec<_> = csv
+            .warnings()
+            .into_iter()
+            .filter(|w| w.code == WarningCode::RaggedRows)
+            .collect();
+        assert_eq!(ragged.len(), 1);
+        assert_eq!(ragged[0].rows, vec![1, 2]);
+
+        // A long row fails at its table row and appends nothing
+        match csv.append_rows("7,8\n9,10,11") {
+            Err(AnalysisError::ParseError { row, column, .. }) => {
+                assert_eq!((row, column), (5, Some(2)))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert_eq!(csv.row_count(), 4);
+        assert_eq!(csv.append_rows("").unwrap(), AppendReport::default());
     }
 }
 
@@ -520,27 +2305,26 @@ mod example_csv_file_tests {
 151,Mew,Psychic,100,true\
 ";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
         // Check # column (Integer)
-        let number_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        let number_meta = csv.column_metadata(0).unwrap();
         assert_eq!(number_meta.data_type, DataType::Integer);
 
         // Check Name column (Text)
-        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
+        let name_meta = csv.column_metadata(1).unwrap();
         assert_eq!(name_meta.data_type, DataType::Text);
 
         // Check Type 1 column (Categorical)
-        let type_meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
+        let type_meta = csv.column_metadata(2).unwrap();
         assert_eq!(type_meta.data_type, DataType::Categorical);
 
         // Check HP column (Integer)
-        let hp_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
+        let hp_meta = csv.column_metadata(3).unwrap();
         assert_eq!(hp_meta.data_type, DataType::Integer);
 
         // Check Legendary column (Categorical)
-        let legendary_meta: ColumnMetadata =
-            from_value(csv.get_column_metadata(4).unwrap()).unwrap();
+        let legendary_meta = csv.column_metadata(4).unwrap();
         assert_eq!(legendary_meta.data_type, DataType::Categorical);
     }
 
@@ -553,298 +2337,22 @@ Charmander,Fire,,39,52,0.6,8.5,1\n\
 Squirtle,Water,,44,48,0.5,9.0,1\
 ";
         let mut csv = CSV::from_string(data.to_string()).unwrap();
-        csv.infer_column_types().unwrap();
+        csv.infer_column_types();
 
         // Test name (Text)
-        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
+        let name_meta = csv.column_metadata(0).unwrap();
         assert_eq!(name_meta.data_type, DataType::Text);
 
         // Test type columns (Categorical)
-        let type1_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
+        let type1_meta = csv.column_metadata(1).unwrap();
         assert_eq!(type1_meta.data_type, DataType::Categorical);
 
         // Test numeric columns (Integer)
-        let hp_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
+        let hp_meta = csv.column_metadata(3).unwrap();
         assert_eq!(hp_meta.data_type, DataType::Integer);
 
         // Test decimal columns (Decimal)
-        let height_meta: ColumnMetadata = from_value(csv.get_column_metadata(5).unwrap()).unwrap();
+        let height_meta = csv.column_metadata(5).unwrap();
         assert_eq!(height_meta.data_type, DataType::Decimal);
     }
 }
-
-#[cfg(test)]
-mod example_csv_file_wasm_tests {
-    use super::*;
-    use js_sys::{Object, Reflect, Uint8Array};
-    use wasm_bindgen::prelude::*;
-    use wasm_bindgen_futures::JsFuture;
-    use wasm_bindgen_test::*;
-    use web_sys::Window;
-
-    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
-
-    async fn read_csv_file(filename: &str) -> Result<String, JsError> {
-        // Get the window object
-        let window = web_sys::window().ok_or_else(|| JsError::new("No window object found"))?;
-
-        // Access the fs object through the window's custom property
-        let fs = Reflect::get(&window, &JsValue::from_str("fs"))
-            .map_err(|_| JsError::new("No fs object found on window"))?;
-
-        // Call readFile on the fs object
-        let read_file = Reflect::get(&fs, &JsValue::from_str("readFile"))
-            .map_err(|_| JsError::new("No readFile method found on fs"))?;
-
-        // Create options object for UTF-8 encoding
-        let options = Object::new();
-        Reflect::set(
-            &options,
-            &JsValue::from_str("encoding"),
-            &JsValue::from_str("utf8"),
-        )
-        .map_err(|_| JsError::new("Failed to set encoding option"))?;
-
-        // Convert readFile to a JavaScript function and call it
-        let read_file_func = js_sys::Function::from(read_file);
-        let promise = read_file_func
-            .call2(&fs, &JsValue::from_str(filename), &options)
-            .map_err(|_| JsError::new("Failed to call readFile"))?;
-
-        // Convert to Promise, handling the conversion error explicitly
-        let promise = promise
-            .dyn_into::<js_sys::Promise>()
-            .map_err(|_| JsError::new("Failed to convert to Promise"))?;
-
-        // Wait for the promise to resolve
-        let content = JsFuture::from(promise)
-            .await
-            .map_err(|e| JsError::new(&format!("Failed to read file: {:?}", e)))?;
-
-        // Convert the content to a string
-        let content_str = content
-            .as_string()
-            .ok_or_else(|| JsError::new("Failed to convert file content to string"))?;
-
-        Ok(content_str)
-    }
-
-    #[wasm_bindgen_test]
-    async fn test_pokemon_csv_types() {
-        // Read the actual pokemon.csv file
-        let csv_content = read_csv_file("pokemon.csv").await.unwrap();
-        let mut csv = CSV::from_string(csv_content).unwrap();
-        csv.infer_column_types().unwrap();
-
-        // Test #/Number column (should be Integer)
-        let number_meta: ColumnMetadata = from_value(csv.get_column_metadata(0).unwrap()).unwrap();
-        assert_eq!(number_meta.data_type, DataType::Integer);
-        assert!(
-            number_meta.confidence > 0.9,
-            "Should have high confidence for Pokemon numbers"
-        );
-
-        // Test Name column (should be Text)
-        let name_meta: ColumnMetadata = from_value(csv.get_column_metadata(1).unwrap()).unwrap();
-        assert_eq!(name_meta.data_type, DataType::Text);
-
-        // Test Type 1 column (should be Categorical)
-        let type1_meta: ColumnMetadata = from_value(csv.get_column_metadata(2).unwrap()).unwrap();
-        assert_eq!(type1_meta.data_type, DataType::Categorical);
-        assert!(
-            type1_meta.confidence > 0.8,
-            "Should have high confidence for Pokemon types"
-        );
-
-        // Test Type 2 column (should be Categorical)
-        let type2_meta: ColumnMetadata = from_value(csv.get_column_metadata(3).unwrap()).unwrap();
-        assert_eq!(type2_meta.data_type, DataType::Categorical);
-
-        // Test Total column (should be Integer)
-        let total_meta: ColumnMetadata = from_value(csv.get_column_metadata(4).unwrap()).unwrap();
-        assert_eq!(total_meta.data_type, DataType::Integer);
-        assert!(
-            total_meta.confidence > 0.9,
-            "Should have high confidence for total stats"
-        );
-
-        // Test stat columns (should all be Integer)
-        let stat_columns = ["HP", "Attack", "Defense", "Sp. Atk", "Sp. Def", "Speed"];
-        for (i, &name) in stat_columns.iter().enumerate() {
-            let stat_meta: ColumnMetadata =
-                from_value(csv.get_column_metadata(i + 5).unwrap()).unwrap();
-            assert_eq!(
-                stat_meta.data_type,
-                DataType::Integer,
-                "Stat column {} ({}) should be Integer",
-                i + 5,
-                name
-            );
-            assert!(
-                stat_meta.confidence > 0.9,
-                "Should have high confidence for {} stats",
-                name
-            );
-        }
-
-        // Test Generation column (should be Integer)
-        let gen_meta: ColumnMetadata = from_value(csv.get_column_metadata(11).unwrap()).unwrap();
-        assert_eq!(gen_meta.data_type, DataType::Integer);
-        assert!(
-            gen_meta.confidence > 0.9,
-            "Should have high confidence for generation"
-        );
-
-        // Test Legendary column (should be Categorical)
-        let legendary_meta: ColumnMetadata =
-            from_value(csv.get_column_metadata(12).unwrap()).unwrap();
-        assert_eq!(legendary_meta.data_type, DataType::Categorical);
-        assert!(
-            legendary_meta.confidence > 0.9,
-            "Should have high confidence for legendary status"
-        );
-    }
-
-    #[wasm_bindgen_test]
-    async fn test_starters_csv_types() {
-        // Read the actual starters.csv file
-        let csv_content = read_csv_file("starters.csv").await.unwrap();
-        let mut csv = CSV::from_string(csv_content).unwrap();
-        csv.infer_column_types().unwrap();
-
-        // Define column groups with their expected types
-        let integer_columns = [
-            ("attack", 19),
-            ("base_egg_steps", 20),
-            ("base_happiness", 21),
-            ("base_total", 22),
-            ("capture_rate", 23),
-            ("defense", 25),
-            ("experience_growth", 26),
-            ("hp", 28),
-            ("pokedex_number", 32),
-            ("sp_attack", 33),
-            ("sp_defense", 34),
-            ("speed", 35),
-            ("generation", 39),
-            ("is_legendary", 40),
-        ];
-
-        let decimal_columns = [
-            ("against_bug", 1),
-            ("against_electric", 4),
-            ("against_fairy", 5),
-            ("against_fight", 6),
-            ("against_fire", 7),
-            ("against_flying", 8),
-            ("against_ghost", 9),
-            ("against_grass", 10),
-            ("against_ground", 11),
-            ("against_ice", 12),
-            ("against_normal", 13),
-            ("against_poison", 14),
-            ("against_psychic", 15),
-            ("against_rock", 16),
-            ("against_steel", 17),
-            ("against_water", 18),
-            ("height_m", 27),
-            ("percentage_male", 31),
-            ("weight_kg", 38),
-        ];
-
-        let categorical_columns = [
-            ("abilities", 0),
-            ("classfication", 24),
-            ("type1", 36),
-            ("type2", 37),
-        ];
-
-        let text_columns = [("japanese_name", 29), ("name", 30)];
-
-        // Test integer columns
-        for (name, idx) in integer_columns.iter() {
-            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
-            assert_eq!(
-                meta.data_type,
-                DataType::Integer,
-                "Column {} ({}) should be Integer",
-                idx,
-                name
-            );
-            assert!(
-                meta.confidence > 0.8,
-                "Should have high confidence for integer column {} ({})",
-                idx,
-                name
-            );
-        }
-
-        // Test decimal columns
-        for (name, idx) in decimal_columns.iter() {
-            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
-            assert_eq!(
-                meta.data_type,
-                DataType::Decimal,
-                "Column {} ({}) should be Decimal",
-                idx,
-                name
-            );
-            assert!(
-                meta.confidence > 0.8,
-                "Should have high confidence for decimal column {} ({})",
-                idx,
-                name
-            );
-        }
-
-        // Test categorical columns
-        for (name, idx) in categorical_columns.iter() {
-            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
-            assert_eq!(
-                meta.data_type,
-                DataType::Categorical,
-                "Column {} ({}) should be Categorical",
-                idx,
-                name
-            );
-            assert!(
-                meta.confidence > 0.7,
-                "Should have reasonable confidence for categorical column {} ({})",
-                idx,
-                name
-            );
-        }
-
-        // Test text columns
-        for (name, idx) in text_columns.iter() {
-            let meta: ColumnMetadata = from_value(csv.get_column_metadata(*idx).unwrap()).unwrap();
-            assert_eq!(
-                meta.data_type,
-                DataType::Text,
-                "Column {} ({}) should be Text",
-                idx,
-                name
-            );
-        }
-
-        // Additional validation for column count
-        assert_eq!(
-            csv.column_count(),
-            41,
-            "Should have 41 columns in starters.csv"
-        );
-    }
-
-    #[wasm_bindgen_test]
-    async fn test_file_not_found() {
-        let result = read_csv_file("nonexistent.csv").await;
-        assert!(result.is_err(), "Should error on nonexistent file");
-    }
-
-    #[wasm_bindgen_test]
-    async fn test_empty_csv() {
-        let empty_csv = "\n\n\n";
-        let result = CSV::from_string(empty_csv.to_string());
-        assert!(result.is_err(), "Should error on empty CSV");
-    }
-}
@@ -0,0 +1,230 @@
+use serde::Serialize;
+use std::fmt;
+
+use crate::parallel::ProcessingError;
+
+/// Errors returned by the analysis engine. Each kind serializes with a stable `code`
+/// (e.g. `"parse_error"`) next to its fields, so callers can branch on the kind and point
+/// at the offending row or column.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AnalysisError {
+    /// Malformed CSV; `row` counts data rows from 0, `column` is set when one field is at fault
+    ParseError {
+        row: usize,
+        column: Option<usize>,
+        message: String,
+    },
+    /// Input bytes that aren't valid UTF-8, starting at `offset`
+    EncodingError {
+        offset: usize,
+    },
+    /// Analysis options that are out of range or don't parse
+    ConfigError {
+        message: String,
+    },
+    ColumnOutOfBounds {
+        column: usize,
+    },
+    RowOutOfBounds {
+        row: usize,
+    },
+    /// A header named by a rule or another table that this table doesn't have
+    UnknownColumn {
+        header: String,
+    },
+    /// A column whose values can't be used for the operation, e.g. a regression over text
+    InvalidColumn {
+        column: usize,
+        message: String,
+    },
+    /// An argument outside what the operation accepts, e.g. `k = 0` for clustering
+    InvalidArgument {
+        message: String,
+    },
+    InvalidPattern {
+        pattern: String,
+        message: String,
+    },
+    InvalidRule {
+        message: String,
+    },
+    /// A computed column expression that doesn't parse, or fails on `row`
+    ExpressionError {
+        row: Option<usize>,
+        message: String,
+    },
+    /// Saved metadata that doesn't parse or comes from a newer version
+    InvalidMetadata {
+        message: String,
+    },
+    /// Two rows sharing the same key when comparing tables
+    DuplicateKey {
+        row: usize,
+        key: Vec<String>,
+    },
+    NothingToUndo,
+    NothingToRedo,
+    SerializationError {
+        message: String,
+    },
+    /// Staged column chunks couldn't be written to or read back from their spill store
+    SpillError {
+        message: String,
+    },
+    /// A remote file couldn't be downloaded; `status` is set when the server answered
+    FetchError {
+        status: Option<u16>,
+        message: String,
+    },
+    /// Work stopped at the caller's request before it finished
+    Cancelled,
+}
+
+impl AnalysisError {
+    /// Stable identifier of the error kind, the same as the serialized `code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            AnalysisError::ParseError { .. } => "parse_error",
+            AnalysisError::EncodingError { .. } => "encoding_error",
+            AnalysisError::ConfigError { .. } => "config_error",
+            AnalysisError::ColumnOutOfBounds { .. } => "column_out_of_bounds",
+            AnalysisError::RowOutOfBounds { .. } => "row_out_of_bounds",
+            AnalysisError::UnknownColumn { .. } => "unknown_column",
+            AnalysisError::InvalidColumn { .. } => "invalid_column",
+            AnalysisError::InvalidArgument { .. } => "invalid_argument",
+            AnalysisError::InvalidPattern { .. } => "invalid_pattern",
+            AnalysisError::InvalidRule { .. } => "invalid_rule",
+            AnalysisError::ExpressionError { .. } => "expression_error",
+            AnalysisError::InvalidMetadata { .. } => "invalid_metadata",
+            AnalysisError::DuplicateKey { .. } => "duplicate_key",
+            AnalysisError::NothingToUndo => "nothing_to_undo",
+            AnalysisError::NothingToRedo => "nothing_to_redo",
+            AnalysisError::SerializationError { .. } => "serialization_error",
+            AnalysisError::SpillError { .. } => "spill_error",
+            AnalysisError::FetchError { .. } => "fetch_error",
+            AnalysisError::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        AnalysisError::InvalidArgument {
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_column(column: usize, message: impl Into<String>) -> Self {
+        AnalysisError::InvalidColumn {
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::ParseError {
+                row,
+                column: Some(column),
+                message,
+            } => write!(
+                f,
+                "Error reading row {}, column {}: {}",
+                row, column, message
+            ),
+            AnalysisError::ParseError { row, message, .. } => {
+                write!(f, "Error reading row {}: {}", row, message)
+            }
+            AnalysisError::EncodingError { offset } => {
+                write!(f, "CSV data is not valid UTF-8 at byte {}", offset)
+            }
+            AnalysisError::ColumnOutOfBounds { column } => {
+                write!(f, "Column index {} out of bounds", column)
+            }
+            AnalysisError::RowOutOfBounds { row } => write!(f, "Row index {} out of bounds", row),
+            AnalysisError::UnknownColumn { header } => write!(f, "Unknown column '{}'", header),
+            AnalysisError::InvalidPattern { pattern, message } => {
+                write!(f, "Invalid pattern '{}': {}", pattern, message)
+            }
+            AnalysisError::ExpressionError {
+                row: Some(row),
+                message,
+            } => write!(f, "Row {}: {}", row, message),
+            AnalysisError::DuplicateKey { row, key } => {
+                write!(f, "Duplicate key {:?} in row {}", key, row)
+            }
+            AnalysisError::NothingToUndo => write!(f, "Nothing to undo"),
+            AnalysisError::NothingToRedo => write!(f, "Nothing to redo"),
+            AnalysisError::Cancelled => write!(f, "Cancelled"),
+            AnalysisError::ConfigError { message }
+            | AnalysisError::InvalidColumn { message, .. }
+            | AnalysisError::InvalidArgument { message }
+            | AnalysisError::InvalidRule { message }
+            | AnalysisError::ExpressionError { message, .. }
+            | AnalysisError::InvalidMetadata { message }
+            | AnalysisError::SerializationError { message }
+            | AnalysisError::SpillError { message }
+            | AnalysisError::FetchError { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+impl From<ProcessingError> for AnalysisError {
+    fn from(error: ProcessingError) -> Self {
+        match error {
+            ProcessingError::Cancelled => AnalysisError::Cancelled,
+            ProcessingError::ChunkFailed {
+                column, message, ..
+            } => AnalysisError::InvalidColumn { column, message },
+            ProcessingError::ProcessingFailed(message) => {
+                AnalysisError::InvalidArgument { message }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let errors = vec![
+            AnalysisError::ParseError {
+                row: 3,
+                column: Some(1),
+                message: "bad quote".to_string(),
+            },
+            AnalysisError::ColumnOutOfBounds { column: 7 },
+            AnalysisError::invalid_argument("k must be at least 1"),
+            AnalysisError::NothingToUndo,
+            AnalysisError::Cancelled,
+        ];
+        for error in errors {
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json["code"], error.code(), "code of {:?}", error);
+        }
+
+        let json = serde_json::to_value(AnalysisError::ParseError {
+            row: 3,
+            column: None,
+            message: "bad quote".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"code": "parse_error", "row": 3, "column": null, "message": "bad quote"})
+        );
+        assert_eq!(
+            AnalysisError::ColumnOutOfBounds { column: 7 }.to_string(),
+            "Column index 7 out of bounds"
+        );
+        assert_eq!(
+            AnalysisError::from(ProcessingError::Cancelled),
+            AnalysisError::Cancelled
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::export::loader::{single_date_format, DEFAULT_PATH, NULL_TOKENS};
+use crate::export::sql::{SqlDialect, SqlOptions};
+use crate::types::date::DateFormat;
+use crate::types::DataType;
+
+// A column as the load statements need it: its name in the table, quoted where needed, and
+// the one format its dates are written in when that isn't ISO 8601
+struct LoadColumn {
+    name: String,
+    date_format: Option<DateFormat>,
+}
+
+// SQL string literal
+fn sql_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+// String literal with backslash escapes, as MySQL reads them, or as DuckDB and SQL Server
+// read a tab delimiter
+fn escaped_string(text: &str, dialect: SqlDialect) -> String {
+    match dialect {
+        SqlDialect::MySql => format!(
+            "'{}'",
+            text.replace('\\', "\\\\")
+                .replace('\'', "\\'")
+                .replace('\t', "\\t")
+        ),
+        _ => sql_string(text).replace('\t', "\\t"),
+    }
+}
+
+// Order of day, month and year in a date format, as Postgres DateStyle and SQL Server
+// DATEFORMAT name it
+fn date_order(format: DateFormat) -> &'static str {
+    match format {
+        DateFormat::Iso8601 | DateFormat::JapaneseSlash => "YMD",
+        DateFormat::UsSlash | DateFormat::UsDash => "MDY",
+        DateFormat::EuropeanDash | DateFormat::EuropeanSlash => "DMY",
+    }
+}
+
+// The value shared by every item, if they agree
+fn shared<T: PartialEq + Copy>(mut items: impl Iterator<Item = T>) -> Option<T> {
+    let first = items.next()?;
+    items.all(|item| item == first).then_some(first)
+}
+
+impl CSV {
+    /// Script creating the table of `to_sql_schema` and loading the file into it with the
+    /// dialect's bulk loader: COPY FROM STDIN for Postgres, LOAD DATA for MySQL, the shell's
+    /// `.import` for SQLite, `read_csv_auto` for DuckDB and BULK INSERT for SQL Server. The
+    /// loader is given the file's delimiter, quote and header row, the missing-value tokens
+    /// found in the data and the format its dates are written in; suggested indexes are
+    /// created after loading. BigQuery loads files from Cloud Storage and gets no script.
+    pub fn to_sql_load_script(
+        &self,
+        table_name: &str,
+        options: &SqlOptions,
+    ) -> Result<String, AnalysisError> {
+        let dialect = options.dialect;
+        let csv_dialect = self.dialect();
+        if dialect == SqlDialect::BigQuery {
+            return Err(AnalysisError::invalid_argument(
+                "BigQuery loads files from Cloud Storage, with `bq load` or LOAD DATA",
+            ));
+        }
+        if csv_dialect.regex {
+            return Err(AnalysisError::invalid_argument(
+                "Files split by a regular expression can't be bulk-loaded as they are",
+            ));
+        }
+        let one_character = csv_dialect.delimiter.chars().count() == 1;
+        if !one_character && matches!(dialect, SqlDialect::Postgres | SqlDialect::Sqlite) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "{:?} loads files with a one-character delimiter, not '{}'",
+                dialect, csv_dialect.delimiter
+            )));
+        }
+        let schema = self.to_sql_schema(table_name, options)?;
+
+        // How often each missing-value token, or an empty cell, is written
+        let mut missing: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut columns = Vec::with_capacity(schema.columns.len());
+        for (index, column) in schema.columns.iter().enumerate() {
+            let (_, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            for value in values {
+                let value = value.trim();
+                if let Some(token) = NULL_TOKENS.iter().find(|&&t| t == value) {
+                    *missing.entry(token).or_default() += 1;
+                } else if value.is_empty() {
+                    *missing.entry("").or_default() += 1;
+                }
+            }
+            let data_type = match self.column_metadata(index) {
+                Some(metadata) => metadata.data_type,
+                None => self
+                    .infer_column(index)
+                    .map_or(DataType::Text, |m| m.data_type),
+            };
+            let date_format = match data_type {
+                DataType::Date => single_date_format(values),
+                _ => None,
+            };
+            columns.push(LoadColumn {
+                name: dialect.identifier(&column.name),
+                date_format: date_format.filter(|&format| format != DateFormat::Iso8601),
+            });
+        }
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        let date_formats = || columns.iter().filter_map(|c| c.date_format);
+        let delimiter = &csv_dialect.delimiter;
+        let quote = csv_dialect.quote.to_string();
+        let table = &schema.table_reference;
+
+        let mut script: Vec<String> = schema.drop_table.iter().cloned().collect();
+        script.push(schema.create_table.clone());
+        match dialect {
+            SqlDialect::Postgres => {
+                if let Some(order) = shared(date_formats().map(date_order)) {
+                    script.push(format!("SET datestyle = 'ISO, {}';", order));
+                }
+                // COPY takes a single null string; the most frequent one is used
+                let null = missing
+                    .iter()
+                    .max_by_key(|(_, &count)| count)
+                    .map(|(t, _)| *t);
+                let mut with = vec!["FORMAT csv".to_string()];
+                if csv_dialect.has_headers {
+                    with.push("HEADER true".to_string());
+                }
+                let delimiter = match delimiter.as_str() {
+                    "\t" => "E'\\t'".to_string(),
+                    _ => sql_string(delimiter),
+                };
+                with.push(format!("DELIMITER {}", delimiter));
+                with.push(format!("QUOTE {}", sql_string(&quote)));
+                if let Some(null) = null.filter(|null| !null.is_empty()) {
+                    with.push(format!("NULL {}", sql_string(null)));
+                }
+                let mut copy = format!(
+                    "-- Run with the file as input: psql -c \"COPY ...\" < {}\n\
+                     COPY {} ({}) FROM STDIN WITH ({});",
+                    DEFAULT_PATH,
+                    table,
+                    names.join(", "),
+                    with.join(", ")
+                );
+                let others: Vec<String> = missing
+                    .keys()
+                    .filter(|&&token| Some(token) != null && !token.is_empty())
+                    .map(|token| sql_string(token))
+                    .collect();
+                if !others.is_empty() {
+                    copy.push_str(&format!(
+                        "\n-- Also written for missing values, and rejected by typed columns: {}",
+                        others.join(", ")
+                    ));
+                }
+                script.push(copy);
+            }
+            SqlDialect::MySql => {
+                let variables: Vec<String> =
+                    (1..=columns.len()).map(|i| format!("@c{}", i)).collect();
+                let assignments: Vec<String> = columns
+                    .iter()
+                    .zip(&variables)
+                    .map(|(column, variable)| {
+                        let mut value = variable.clone();
+                        for token in missing.keys() {
+                            value =
+                                format!("NULLIF({}, {})", value, escaped_string(token, dialect));
+                        }
+                        if let Some(format) = column.date_format {
+                            value = format!(
+                                "STR_TO_DATE({}, {})",
+                                value,
+                                escaped_string(format.strftime(), dialect)
+                            );
+                        }
+                        format!("{} = {}", column.name, value)
+                    })
+                    .collect();
+                let mut load = format!(
+                    "LOAD DATA LOCAL INFILE {}\nINTO TABLE {}\nFIELDS TERMINATED BY {} \
+                     OPTIONALLY ENCLOSED BY {}\nLINES TERMINATED BY '\\n'\n",
+                    escaped_string(DEFAULT_PATH, dialect),
+                    table,
+                    escaped_string(delimiter, dialect),
+                    escaped_string(&quote, dialect)
+                );
+                if csv_dialect.has_headers {
+                    load.push_str("IGNORE 1 LINES\n");
+                }
+                load.push_str(&format!(
+                    "({})\nSET {};",
+                    variables.join(", "),
+                    assignments.join(",\n    ")
+                ));
+                script.push(load);
+            }
+            SqlDialect::Sqlite => {
+                let mut import = String::from(".mode csv\n");
+                if delimiter != "," {
+                    import.push_str(&format!(
+                        ".separator \"{}\"\n",
+                        delimiter.replace('\t', "\\t").replace('"', "\\\"")
+                    ));
+                }
+                let skip = if csv_dialect.has_headers {
+                    "--skip 1 "
+                } else {
+                    ""
+                };
+                import.push_str(&format!(".import {}{} {}", skip, DEFAULT_PATH, table));
+                script.push(import);
+                // The shell imports every value as text, missing ones included
+                if !missing.is_empty() {
+                    let tokens: Vec<String> = missing.keys().map(|t| sql_string(t)).collect();
+                    let updates: Vec<String> = names
+                        .iter()
+                        .map(|name| {
+                            format!(
+                                "UPDATE {} SET {} = NULL WHERE {} IN ({});",
+                                table,
+                                name,
+                                name,
+                                tokens.join(", ")
+                            )
+                        })
+                        .collect();
+                    script.push(updates.join("\n"));
+                }
+                let notes: Vec<String> = columns
+                    .iter()
+                    .filter_map(|column| {
+                        column.date_format.map(|format| {
+                            format!(
+                                "-- {} is written {}; SQLite keeps it as text",
+                                column.name,
+                                format.strftime()
+                            )
+                        })
+                    })
+                    .collect();
+                if !notes.is_empty() {
+                    script.push(notes.join("\n"));
+                }
+            }
+            SqlDialect::DuckDb => {
+                let mut arguments = vec![
+                    escaped_string(DEFAULT_PATH, dialect),
+                    format!("delim = {}", escaped_string(delimiter, dialect)),
+                    format!("quote = {}", sql_string(&quote)),
+                    format!("header = {}", csv_dialect.has_headers),
+                ];
+                if !missing.is_empty() {
+                    let tokens: Vec<String> = missing.keys().map(|t| sql_string(t)).collect();
+                    arguments.push(format!("nullstr = [{}]", tokens.join(", ")));
+                }
+                // One date format applies to the whole file
+                let date_format = shared(date_formats());
+                if let Some(format) = date_format {
+                    arguments.push(format!("dateformat = {}", sql_string(format.strftime())));
+                }
+                let mut insert = format!(
+                    "INSERT INTO {} SELECT * FROM read_csv_auto({});",
+                    table,
+                    arguments.join(", ")
+                );
+                if date_format.is_none() && date_formats().next().is_some() {
+                    insert.push_str(
+                        "\n-- Date columns are written in different formats; load them as \
+                         VARCHAR and convert each with strptime",
+                    );
+                }
+                script.push(insert);
+            }
+            SqlDialect::MsSql => {
+                if let Some(order) = shared(date_formats().map(date_order)) {
+                    script.push(format!("SET DATEFORMAT {};", order.to_ascii_lowercase()));
+                }
+                let mut with = vec!["FORMAT = 'CSV'".to_string()];
+                if csv_dialect.has_headers {
+                    with.push("FIRSTROW = 2".to_string());
+                }
+                with.push(format!(
+                    "FIELDTERMINATOR = {}",
+                    escaped_string(delimiter, dialect)
+                ));
+                with.push(format!("FIELDQUOTE = {}", sql_string(&quote)));
+                let mut insert = format!(
+                    "BULK INSERT {} FROM {} WITH ({});",
+                    table,
+                    sql_string(DEFAULT_PATH),
+                    with.join(", ")
+                );
+                let tokens: Vec<String> = missing
+                    .keys()
+                    .filter(|token| !token.is_empty())
+                    .map(|token| sql_string(token))
+                    .collect();
+                if !tokens.is_empty() {
+                    insert.push_str(&format!(
+                        "\n-- Also written for missing values, and rejected by typed columns: {}",
+                        tokens.join(", ")
+                    ));
+                }
+                script.push(insert);
+            }
+            SqlDialect::BigQuery => unreachable!("rejected above"),
+        }
+        script.extend(schema.indexes.iter().map(|index| index.statement.clone()));
+        Ok(script.join("\n\n") + "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::dialect::Dialect;
+
+    fn orders() -> CSV {
+        let data = "id;placed;note\n1;19/03/2024;NA\n2;20/03/2024;\n3;21/03/2024;fragile";
+        let dialect = Dialect {
+            delimiter: ";".to_string(),
+            ..Dialect::default()
+        };
+        let mut csv = CSV::from_string_with_dialect(data.to_string(), dialect).unwrap();
+        csv.infer_column_types();
+        csv.override_type(1, DataType::Date).unwrap();
+        csv
+    }
+
+    fn script(csv: &CSV, dialect: SqlDialect) -> String {
+        let options = SqlOptions {
+            dialect,
+            ..SqlOptions::default()
+        };
+        csv.to_sql_load_script("orders", &options).unwrap()
+    }
+
+    #[test]
+    fn test_load_scripts() {
+        let csv = orders();
+
+        let postgres = script(&csv, SqlDialect::Postgres);
+        assert!(postgres.starts_with("CREATE TABLE orders ("));
+        assert!(postgres.contains("SET datestyle = 'ISO, DMY';"));
+        assert!(postgres.contains(
+            "COPY orders (id, placed, note) FROM STDIN WITH \
+             (FORMAT csv, HEADER true, DELIMITER ';', QUOTE '\"', NULL 'NA');"
+        ));
+
+        let mysql = script(&csv, SqlDialect::MySql);
+        assert!(mysql.contains("FIELDS TERMINATED BY ';' OPTIONALLY ENCLOSED BY '\"'"));
+        assert!(mysql.contains("IGNORE 1 LINES\n(@c1, @c2, @c3)"));
+        assert!(mysql.contains("placed = STR_TO_DATE(NULLIF(NULLIF(@c2, ''), 'NA'), '%d/%m/%Y')"));
+
+        let sqlite = script(&csv, SqlDialect::Sqlite);
+        assert!(sqlite.contains(".mode csv\n.separator \";\"\n.import --skip 1 data.csv orders"));
+        assert!(sqlite.contains("UPDATE orders SET note = NULL WHERE note IN ('', 'NA');"));
+
+        let duckdb = script(&csv, SqlDialect::DuckDb);
+        assert!(duckdb.contains(
+            "INSERT INTO orders SELECT * FROM read_csv_auto('data.csv', delim = ';', \
+             quote = '\"', header = true, nullstr = ['', 'NA'], dateformat = '%d/%m/%Y');"
+        ));
+
+        let mssql = script(&csv, SqlDialect::MsSql);
+        assert!(mssql.contains("SET DATEFORMAT dmy;"));
+        assert!(mssql.contains("FIRSTROW = 2, FIELDTERMINATOR = ';'"));
+
+        let bigquery = SqlOptions {
+            dialect: SqlDialect::BigQuery,
+            ..SqlOptions::default()
+        };
+        assert!(csv.to_sql_load_script("orders", &bigquery).is_err());
+    }
+
+    #[test]
+    fn test_tab_delimiter_and_indexes() {
+        let dialect = Dialect {
+            delimiter: "\t".to_string(),
+            ..Dialect::default()
+        };
+        let csv = CSV::from_string_with_dialect("id\tname\n1\tAda\n2\tGrace".to_string(), dialect)
+            .unwrap();
+        let options = SqlOptions {
+            index_hints: true,
+            ..SqlOptions::default()
+        };
+        let postgres = csv.to_sql_load_script("people", &options).unwrap();
+        assert!(postgres.contains("DELIMITER E'\\t'"));
+        // Indexes are created once the rows are loaded
+        let copy = postgres.find("COPY").unwrap();
+        assert!(postgres.find("CREATE UNIQUE INDEX").unwrap() > copy);
+    }
+}
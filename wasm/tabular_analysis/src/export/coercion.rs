@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::statistics::{digit_counts, MAX_DECIMAL_PRECISION};
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::export::loader::NULL_TOKENS;
+use crate::export::sql::SqlDialect;
+use crate::types::date::Date;
+
+// Problem values listed per column; the rest are only counted
+const MAX_LISTED_ISSUES: usize = 100;
+
+/// Why a value wouldn't load as it is under its column's SQL type
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoercionKind {
+    /// Outside the integer type's range, or with more integer digits than the DECIMAL
+    /// allows; the load fails
+    Overflow,
+    /// More fraction digits than the DECIMAL's scale, silently rounded
+    Rounded,
+    /// Longer than the VARCHAR or CHAR; rejected, or cut off by MySQL outside strict mode
+    TooLong,
+    /// Not a number or date the column's type takes; the load fails
+    Invalid,
+}
+
+/// A value that wouldn't load as it is
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CoercionIssue {
+    pub row: usize,
+    pub value: String,
+    pub kind: CoercionKind,
+}
+
+/// Values of a column that wouldn't load as they are under its suggested SQL type
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColumnCoercions {
+    pub column: usize,
+    pub name: String,
+    pub sql_type: String,
+    /// Every value that wouldn't load as it is
+    pub issue_count: usize,
+    /// The first 100 of them
+    pub issues: Vec<CoercionIssue>,
+    /// A wider type taking every value that overflows, rounds or is too long; invalid values
+    /// need cleaning instead
+    pub widened_type: Option<String>,
+}
+
+/// Columns with values that would fail to load, or load changed, under the suggested types
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CoercionPreview {
+    pub dialect: SqlDialect,
+    pub columns: Vec<ColumnCoercions>,
+}
+
+// What a SQL type accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TypeLimits {
+    Integer { min: i128, max: i128 },
+    Decimal { precision: usize, scale: usize },
+    Float,
+    Text { length: usize },
+    Date,
+    // Types taking any value, like TEXT, or ones not checked
+    Any,
+}
+
+fn type_limits(sql_type: &str, dialect: SqlDialect) -> TypeLimits {
+    let sql_type = sql_type.trim().to_ascii_uppercase();
+    let (name, arguments) = match sql_type.split_once('(') {
+        Some((name, rest)) => (name.trim(), rest.trim_end_matches(')')),
+        None => (sql_type.as_str(), ""),
+    };
+    let arguments: Vec<usize> = arguments
+        .split(',')
+        .filter_map(|argument| argument.trim().parse().ok())
+        .collect();
+    let integer = |bits: u32| TypeLimits::Integer {
+        min: -(1i128 << (bits - 1)),
+        max: (1i128 << (bits - 1)) - 1,
+    };
+    match (name, arguments.as_slice()) {
+        ("SMALLINT", _) => integer(16),
+        // SQLite integers take 64 bits whatever they're declared as
+        ("INT" | "INTEGER", _) if dialect == SqlDialect::Sqlite => integer(64),
+        ("INT" | "INTEGER", _) => integer(32),
+        ("BIGINT" | "INT64", _) => integer(64),
+        ("DECIMAL" | "NUMERIC", [precision]) => TypeLimits::Decimal {
+            precision: *precision,
+            scale: 0,
+        },
+        ("DECIMAL" | "NUMERIC", [precision, scale, ..]) => TypeLimits::Decimal {
+            precision: *precision,
+            scale: *scale,
+        },
+        ("NUMERIC", []) if dialect == SqlDialect::BigQuery => TypeLimits::Decimal {
+            precision: 38,
+            scale: 9,
+        },
+        ("DECIMAL" | "NUMERIC" | "REAL" | "FLOAT" | "FLOAT64" | "DOUBLE", _) => TypeLimits::Float,
+        ("VARCHAR" | "CHAR" | "NVARCHAR" | "NCHAR", [length]) => {
+            TypeLimits::Text { length: *length }
+        }
+        ("DATE", _) => TypeLimits::Date,
+        _ => TypeLimits::Any,
+    }
+}
+
+// Widest values seen in a column, for the type that would take them all
+#[derive(Default)]
+struct Widest {
+    integer_digits: usize,
+    scale: usize,
+    length: usize,
+    fits_i64: bool,
+}
+
+// Checks one value against its type, noting how wide it is
+fn check_value(
+    value: &str,
+    metadata: &ColumnMetadata,
+    limits: TypeLimits,
+    widest: &mut Widest,
+) -> Option<CoercionKind> {
+    let number = || {
+        metadata
+            .number_format
+            .unwrap_or_default()
+            .plain_digits(value)
+    };
+    match limits {
+        TypeLimits::Integer { min, max } => {
+            let Some(plain) = number() else {
+                return Some(CoercionKind::Invalid);
+            };
+            let Ok(integer) = plain.parse::<i128>() else {
+                // Too long even for i128, or with a fraction
+                let (digits, scale) = digit_counts(&plain);
+                widest.integer_digits = widest.integer_digits.max(digits);
+                return Some(match scale {
+                    0 => CoercionKind::Overflow,
+                    _ => CoercionKind::Invalid,
+                });
+            };
+            widest.integer_digits = widest.integer_digits.max(digit_counts(&plain).0);
+            widest.fits_i64 &= i64::try_from(integer).is_ok();
+            (integer < min || integer > max).then_some(CoercionKind::Overflow)
+        }
+        TypeLimits::Decimal { precision, scale } => {
+            let Some(plain) = number() else {
+                return Some(CoercionKind::Invalid);
+            };
+            let (digits, fraction) = digit_counts(&plain);
+            widest.integer_digits = widest.integer_digits.max(digits);
+            widest.scale = widest.scale.max(fraction);
+            if digits > precision.saturating_sub(scale) {
+                Some(CoercionKind::Overflow)
+            } else {
+                (fraction > scale).then_some(CoercionKind::Rounded)
+            }
+        }
+        TypeLimits::Float => number().is_none().then_some(CoercionKind::Invalid),
+        TypeLimits::Text { length } => {
+            let chars = value.chars().count();
+            widest.length = widest.length.max(chars);
+            (chars > length).then_some(CoercionKind::TooLong)
+        }
+        TypeLimits::Date => Date::parse(value)
+            .is_none()
+            .then_some(CoercionKind::Invalid),
+        TypeLimits::Any => None,
+    }
+}
+
+// A type of the same kind wide enough for the widest values, when widening helps
+fn widened_type(
+    sql_type: &str,
+    limits: TypeLimits,
+    widest: &Widest,
+    dialect: SqlDialect,
+) -> Option<String> {
+    let decimal = |precision: usize, scale: usize| match dialect {
+        SqlDialect::BigQuery if precision - scale > 29 || scale > 9 => "BIGNUMERIC".to_string(),
+        SqlDialect::BigQuery => "NUMERIC".to_string(),
+        _ => format!("DECIMAL({},{})", precision, scale),
+    };
+    match limits {
+        TypeLimits::Integer { max, .. } if widest.fits_i64 && max < i64::MAX as i128 => {
+            Some("BIGINT".to_string())
+        }
+        TypeLimits::Integer { .. } => (widest.integer_digits <= MAX_DECIMAL_PRECISION)
+            .then(|| decimal(widest.integer_digits, 0)),
+        TypeLimits::Decimal { precision, scale } => {
+            let scale = scale.max(widest.scale);
+            let precision = precision.max(widest.integer_digits + scale);
+            (precision <= MAX_DECIMAL_PRECISION).then(|| decimal(precision, scale))
+        }
+        TypeLimits::Text { .. } => {
+            let name = sql_type.split('(').next().unwrap_or_default().trim();
+            let name = match name.to_ascii_uppercase().as_str() {
+                "NCHAR" | "NVARCHAR" => "NVARCHAR",
+                _ => "VARCHAR",
+            };
+            Some(format!("{}({})", name, widest.length))
+        }
+        _ => None,
+    }
+}
+
+impl CSV {
+    /// Values that would fail to load, or load changed, under the column types `to_sql_schema`
+    /// suggests for a dialect: integers out of range, numbers with more digits than their
+    /// DECIMAL, text longer than its VARCHAR and values that aren't numbers or dates where
+    /// those are expected. Numbers are read the way the column's number format reads them
+    /// and missing-value tokens are skipped, as the load scripts load them as null. Only
+    /// columns with such values are listed, each with a wider type where one would do.
+    pub fn preview_coercions(&self, dialect: SqlDialect) -> Result<CoercionPreview, AnalysisError> {
+        let mut columns = Vec::new();
+        for index in 0..self.column_count() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (name, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let sql_type = dialect.column_type(&metadata);
+            let limits = type_limits(&sql_type, dialect);
+            if limits == TypeLimits::Any {
+                continue;
+            }
+
+            let mut widest = Widest {
+                fits_i64: true,
+                ..Widest::default()
+            };
+            let mut issues = Vec::new();
+            let mut issue_count = 0;
+            let mut widening_helps = false;
+            for (row, value) in values.iter().enumerate() {
+                if value.trim().is_empty() || NULL_TOKENS.contains(&value.trim()) {
+                    continue;
+                }
+                let Some(kind) = check_value(value, &metadata, limits, &mut widest) else {
+                    continue;
+                };
+                widening_helps |= kind != CoercionKind::Invalid;
+                issue_count += 1;
+                if issues.len() < MAX_LISTED_ISSUES {
+                    issues.push(CoercionIssue {
+                        row,
+                        value: value.clone(),
+                        kind,
+                    });
+                }
+            }
+            if issue_count > 0 {
+                columns.push(ColumnCoercions {
+                    column: index,
+                    name: name.to_string(),
+                    widened_type: widening_helps
+                        .then(|| widened_type(&sql_type, limits, &widest, dialect))
+                        .flatten(),
+                    sql_type,
+                    issue_count,
+                    issues,
+                });
+            }
+        }
+        Ok(CoercionPreview { dialect, columns })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_type_limits() {
+        assert_eq!(
+            type_limits("DECIMAL(10,2)", SqlDialect::Postgres),
+            TypeLimits::Decimal {
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            type_limits("INTEGER", SqlDialect::Sqlite),
+            type_limits("BIGINT", SqlDialect::Postgres)
+        );
+        assert_eq!(
+            type_limits("VARCHAR(50)", SqlDialect::MySql),
+            TypeLimits::Text { length: 50 }
+        );
+        assert_eq!(type_limits("TEXT", SqlDialect::Postgres), TypeLimits::Any);
+    }
+
+    #[test]
+    fn test_preview_coercions() {
+        let data = "count,price,code,joined\n\
+                    12,10.50,AB,2024-01-05\n\
+                    3000000000,3.12345,ABCD,2024-02-30\n\
+                    7,NA,AB,2024-03-01";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.override_type(0, DataType::Integer).unwrap();
+        csv.override_type(1, DataType::Currency).unwrap();
+        csv.override_type(3, DataType::Date).unwrap();
+
+        let preview = csv.preview_coercions(SqlDialect::Postgres).unwrap();
+        let count = &preview.columns[0];
+        assert_eq!(count.sql_type, "INT");
+        assert_eq!(count.issues[0].row, 1);
+        assert_eq!(count.issues[0].kind, CoercionKind::Overflow);
+        assert_eq!(count.widened_type.as_deref(), Some("BIGINT"));
+
+        let price = &preview.columns[1];
+        assert_eq!(price.issue_count, 1);
+        assert_eq!(price.issues[0].kind, CoercionKind::Rounded);
+        assert_eq!(price.widened_type.as_deref(), Some("DECIMAL(19,5)"));
+
+        let joined = preview.columns.iter().find(|c| c.name == "joined").unwrap();
+        assert_eq!(joined.issues[0].value, "2024-02-30");
+        assert_eq!(joined.issues[0].kind, CoercionKind::Invalid);
+        assert_eq!(joined.widened_type, None);
+
+        // SQLite integers are 64-bit
+        let sqlite = csv.preview_coercions(SqlDialect::Sqlite).unwrap();
+        assert!(sqlite.columns.iter().all(|c| c.name != "count"));
+    }
+}
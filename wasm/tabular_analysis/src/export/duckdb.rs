@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+use crate::types::{date::DateType, DataType, TypeDetection};
+
+// Rows per INSERT statement, keeping each statement small enough to parse quickly
+const INSERT_BATCH_ROWS: usize = 1000;
+
+/// A column as created in DuckDB, with its values typed for `insertArrowTable`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DuckDbColumn {
+    pub name: String,
+    pub duckdb_type: String,
+    /// One value per row: numbers, ISO 8601 date strings, text, or null for empty cells
+    pub values: Vec<Value>,
+    /// Non-empty values that didn't parse as the column's type and are loaded as null
+    pub unparsed: usize,
+}
+
+/// SQL and data for loading a CSV into duckdb-wasm
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DuckDbStatements {
+    pub table_name: String,
+    pub create_table: String,
+    /// INSERT statements for every row, for connections without Arrow support
+    pub inserts: Vec<String>,
+    /// Columns in table order, ready for `tableFromArrays` and `insertArrowTable`
+    pub columns: Vec<DuckDbColumn>,
+}
+
+/// DuckDB type for a column; codes with leading zeros and other text stay VARCHAR
+pub fn duckdb_type(metadata: &ColumnMetadata) -> &'static str {
+    if metadata.fixed_width().is_some() {
+        return "VARCHAR";
+    }
+    match metadata.data_type {
+        DataType::Integer => match &metadata.big_integer {
+            Some(big) if !big.fits_i64 && big.max_digits <= 38 => "HUGEINT",
+            Some(big) if !big.fits_i64 => "VARCHAR",
+            _ => "BIGINT",
+        },
+        DataType::Decimal | DataType::Quantity => "DOUBLE",
+        DataType::Currency => "DECIMAL(19,4)",
+        DataType::Date => "DATE",
+        DataType::Email
+        | DataType::Phone
+        | DataType::CreditCard
+        | DataType::Categorical
+        | DataType::Text
+        | DataType::Mixed => "VARCHAR",
+    }
+}
+
+/// Quotes an identifier for DuckDB, doubling embedded quotes
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// Value loaded into a column of the given DuckDB type: text for VARCHAR columns, and for
+// typed ones the parsed value or null when it doesn't parse
+fn duckdb_value(value: &str, metadata: &ColumnMetadata, duckdb_type: &str) -> Value {
+    if value.trim().is_empty() {
+        return Value::Null;
+    }
+    match duckdb_type {
+        "VARCHAR" => Value::String(value.to_string()),
+        "DATE" => DateType::normalize(value).map_or(Value::Null, Value::String),
+        // Big integers are given as digit strings, which DuckDB casts without rounding
+        "BIGINT" | "HUGEINT" if metadata.big_integer.is_some() => {
+            match typed_value(value, Some(metadata)) {
+                digits @ Value::String(_) => digits,
+                _ => Value::Null,
+            }
+        }
+        // Whole numbers are written without a fraction so BIGINT columns take them as-is
+        "BIGINT" => match typed_value(value, Some(metadata)) {
+            Value::Number(number) => number
+                .as_f64()
+                .filter(|n| n.fract() == 0.0 && n.abs() < i64::MAX as f64)
+                .map_or(Value::Null, |n| Value::Number(Number::from(n as i64))),
+            _ => Value::Null,
+        },
+        _ => match typed_value(value, Some(metadata)) {
+            number @ Value::Number(_) => number,
+            _ => Value::Null,
+        },
+    }
+}
+
+// SQL literal for a loaded value
+fn sql_literal(value: &Value, duckdb_type: &str) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(text) if duckdb_type == "DATE" => format!("DATE '{}'", text),
+        Value::String(digits) if matches!(duckdb_type, "BIGINT" | "HUGEINT") => digits.clone(),
+        Value::String(text) => format!("'{}'", text.replace('\'', "''")),
+        other => other.to_string(),
+    }
+}
+
+impl CSV {
+    /// CREATE TABLE and INSERT statements for DuckDB with column types matched to the
+    /// detected ones, and the typed values of every column for duckdb-wasm's
+    /// `insertArrowTable`. Columns not inferred yet are inferred without storing the result.
+    pub fn to_duckdb_statements(
+        &self,
+        table_name: &str,
+    ) -> Result<DuckDbStatements, AnalysisError> {
+        if table_name.trim().is_empty() {
+            return Err(AnalysisError::invalid_argument("Table name can't be empty"));
+        }
+        let table = quote_identifier(table_name);
+
+        let mut columns = Vec::with_capacity(self.column_count());
+        for index in 0..self.column_count() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (header, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let duckdb_type = duckdb_type(&metadata);
+            let typed: Vec<Value> = values
+                .iter()
+                .map(|value| duckdb_value(value, &metadata, duckdb_type))
+                .collect();
+            let unparsed = values
+                .iter()
+                .zip(&typed)
+                .filter(|(value, typed)| !value.trim().is_empty() && typed.is_null())
+                .count();
+            columns.push(DuckDbColumn {
+                name: header.to_string(),
+                duckdb_type: duckdb_type.to_string(),
+                values: typed,
+                unparsed,
+            });
+        }
+
+        // Renamed columns note the header they had in the file
+        let definitions: Vec<String> = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let separator = if index + 1 < columns.len() { "," } else { "" };
+                let comment = self
+                    .stored_lineage(index)
+                    .and_then(|lineage| lineage.renamed_from())
+                    .map_or_else(String::new, |header| {
+                        format!(" -- {}", header.replace(['\r', '\n'], " "))
+                    });
+                format!(
+                    "    {} {}{}{}",
+                    quote_identifier(&column.name),
+                    column.duckdb_type,
+                    separator,
+                    comment
+                )
+            })
+            .collect();
+        let create_table = format!("CREATE TABLE {} (\n{}\n);", table, definitions.join("\n"));
+
+        let column_list: Vec<String> = columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect();
+        let inserts = (0..self.row_count())
+            .step_by(INSERT_BATCH_ROWS)
+            .map(|start| {
+                let end = (start + INSERT_BATCH_ROWS).min(self.row_count());
+                let rows: Vec<String> = (start..end)
+                    .map(|row| {
+                        let literals: Vec<String> = columns
+                            .iter()
+                            .map(|column| sql_literal(&column.values[row], &column.duckdb_type))
+                            .collect();
+                        format!("({})", literals.join(", "))
+                    })
+                    .collect();
+                format!(
+                    "INSERT INTO {} ({}) VALUES\n{};",
+                    table,
+                    column_list.join(", "),
+                    rows.join(",\n")
+                )
+            })
+            .collect();
+
+        Ok(DuckDbStatements {
+            table_name: table_name.to_string(),
+            create_table,
+            inserts,
+            columns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_statements_match_detected_types() {
+        let data = "id,zip,price,joined,name\n\
+                    1,02134,$10.50,2024-01-15,O'Brien\n\
+                    2,90210,$3.00,soon,\n\
+                    3,00501,$7.25,2024-03-09,Grace";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.override_type(3, DataType::Date).unwrap();
+
+        let statements = csv.to_duckdb_statements("my table").unwrap();
+        assert_eq!(
+            statements.create_table,
+            "CREATE TABLE \"my table\" (\n    \"id\" BIGINT,\n    \"zip\" VARCHAR,\n    \
+             \"price\" DECIMAL(19,4),\n    \"joined\" DATE,\n    \"name\" VARCHAR\n);"
+        );
+        assert_eq!(
+            statements.columns[0].values,
+            vec![json!(1), json!(2), json!(3)]
+        );
+        assert_eq!(statements.columns[1].values[0], json!("02134"));
+        assert_eq!(statements.columns[2].values[1], json!(3.0));
+        assert_eq!(statements.columns[3].values[1], Value::Null);
+        assert_eq!(statements.columns[3].unparsed, 1);
+        assert_eq!(statements.columns[4].values[1], Value::Null);
+        assert_eq!(statements.columns[4].unparsed, 0);
+
+        assert_eq!(statements.inserts.len(), 1);
+        assert!(statements.inserts[0].starts_with(
+            "INSERT INTO \"my table\" (\"id\", \"zip\", \"price\", \"joined\", \"name\") VALUES\n\
+             (1, '02134', 10.5, DATE '2024-01-15', 'O''Brien'),\n(2, '90210', 3.0, NULL, NULL)"
+        ));
+    }
+
+    #[test]
+    fn test_uninferred_columns_and_batches() {
+        let mut data = String::from("n\n");
+        for i in 0..INSERT_BATCH_ROWS + 1 {
+            data.push_str(&format!("{}\n", i));
+        }
+        let csv = CSV::from_string(data).unwrap();
+
+        let statements = csv.to_duckdb_statements("numbers").unwrap();
+        assert_eq!(statements.columns[0].duckdb_type, "BIGINT");
+        assert_eq!(statements.inserts.len(), 2);
+        assert!(csv.column_metadata(0).is_none());
+        assert!(csv.to_duckdb_statements(" ").is_err());
+    }
+}
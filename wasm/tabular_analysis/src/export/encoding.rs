@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+/// An ordinal column as integer codes for modeling: each cell's position in the column's
+/// levels, lowest first from 0
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OrdinalEncoding {
+    pub column: usize,
+    /// Name of the scale the levels follow, e.g. `size`
+    pub scale: String,
+    /// Level of each code, lowest first
+    pub levels: Vec<String>,
+    /// Code of every row; None for empty cells and values off the scale
+    pub codes: Vec<Option<usize>>,
+}
+
+impl CSV {
+    /// Codes of an ordinal categorical column in the order of its levels, e.g. 0, 1, 2 for
+    /// low, medium, high
+    pub fn ordinal_encoding(&self, column: usize) -> Result<OrdinalEncoding, AnalysisError> {
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let metadata = match self.column_metadata(column) {
+            Some(metadata) => metadata.clone(),
+            None => self
+                .infer_column(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })?,
+        };
+        let scale = metadata.ordinal.ok_or_else(|| {
+            AnalysisError::invalid_argument(format!(
+                "Column {} has no ordered levels to encode",
+                column
+            ))
+        })?;
+
+        Ok(OrdinalEncoding {
+            column,
+            codes: values.iter().map(|value| scale.rank(value)).collect(),
+            scale: scale.name,
+            levels: scale.levels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_ordinal_encoding() {
+        let data = "priority,team\nhigh,red\nLow,blue\n,red\nmedium,red\nhigh,blue";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.override_type(0, DataType::Categorical).unwrap();
+        csv.override_type(1, DataType::Categorical).unwrap();
+
+        let encoding = csv.ordinal_encoding(0).unwrap();
+        assert_eq!(encoding.scale, "level");
+        assert_eq!(encoding.levels, vec!["Low", "medium", "high"]);
+        assert_eq!(
+            encoding.codes,
+            vec![Some(2), Some(0), None, Some(1), Some(2)]
+        );
+        assert!(csv.ordinal_encoding(1).is_err());
+        assert!(csv.ordinal_encoding(2).is_err());
+    }
+}
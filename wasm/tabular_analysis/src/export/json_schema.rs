@@ -0,0 +1,184 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::types::DataType;
+
+pub const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// Categorical levels listed in an `enum`; columns with more are described as plain strings
+const MAX_ENUM_VALUES: usize = 100;
+
+// Text columns whose every value is an http(s) address get the `uri` format
+fn is_uri(value: &str) -> bool {
+    let value = value.trim();
+    let rest = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"));
+    rest.is_some_and(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+}
+
+/// JSON Schema of one column's values as `row_page` types them
+pub fn column_schema(metadata: &ColumnMetadata, values: &[String]) -> Value {
+    let mut schema = Map::new();
+    let non_empty = || values.iter().filter(|v| !v.trim().is_empty());
+
+    if let Some(width) = metadata.fixed_width() {
+        schema.insert("type".into(), json!("string"));
+        schema.insert("minLength".into(), json!(width));
+        schema.insert("maxLength".into(), json!(width));
+        schema.insert("pattern".into(), json!("^[0-9]+$"));
+        return Value::Object(schema);
+    }
+
+    match metadata.data_type {
+        DataType::Integer | DataType::Decimal | DataType::Currency | DataType::Quantity => {
+            let json_type = match metadata.data_type {
+                DataType::Integer => "integer",
+                _ => "number",
+            };
+            schema.insert("type".into(), json!(json_type));
+            if let Some(stats) = &metadata.numeric_stats {
+                schema.insert("minimum".into(), json!(stats.min));
+                schema.insert("maximum".into(), json!(stats.max));
+            }
+        }
+        DataType::Date => {
+            schema.insert("type".into(), json!("string"));
+            schema.insert("format".into(), json!("date"));
+        }
+        DataType::Email => {
+            schema.insert("type".into(), json!("string"));
+            schema.insert("format".into(), json!("email"));
+        }
+        DataType::Categorical => {
+            schema.insert("type".into(), json!("string"));
+            // Ordinal levels are listed lowest first, nominal ones alphabetically
+            let levels: Vec<&str> = match &metadata.ordinal {
+                Some(scale) => scale.levels.iter().map(String::as_str).collect(),
+                None => non_empty()
+                    .map(String::as_str)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+            };
+            if levels.len() <= MAX_ENUM_VALUES {
+                schema.insert("enum".into(), json!(levels));
+            }
+        }
+        DataType::Phone | DataType::CreditCard | DataType::Text | DataType::Mixed => {
+            schema.insert("type".into(), json!("string"));
+            let mut values = non_empty().peekable();
+            if metadata.data_type == DataType::Text
+                && values.peek().is_some()
+                && values.all(|v| is_uri(v))
+            {
+                schema.insert("format".into(), json!("uri"));
+            }
+            if let Some(stats) = &metadata.text_stats {
+                schema.insert("maxLength".into(), json!(stats.max_length));
+            }
+        }
+    }
+    Value::Object(schema)
+}
+
+impl CSV {
+    /// JSON Schema (draft 2020-12) of the rows as `row_page` returns them: each column with
+    /// its type, a format for dates, emails and URLs, the levels of categorical columns and
+    /// the range of numeric ones. Columns with empty cells also accept null and aren't
+    /// required. Columns not inferred yet are inferred without storing the result.
+    pub fn to_json_schema(&self) -> Result<Value, AnalysisError> {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for index in 0..self.column_count() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (header, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+
+            let mut schema = column_schema(&metadata, values);
+            if values.iter().any(|v| v.trim().is_empty()) {
+                if let Some(json_type) = schema.get_mut("type") {
+                    *json_type = json!([json_type.clone(), "null"]);
+                }
+                if let Some(levels) = schema.get_mut("enum").and_then(Value::as_array_mut) {
+                    levels.push(Value::Null);
+                }
+            } else {
+                required.push(header.to_string());
+            }
+            properties.insert(header.to_string(), schema);
+        }
+
+        Ok(json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_schemas() {
+        let mut data = String::from("id,zip,joined,email,site,status,note\n");
+        for i in 0..30 {
+            data.push_str(&format!(
+                "{},0{}134,2024-01-{:02},u{}@example.com,https://example.com/{},{},{}\n",
+                i + 1,
+                i % 9,
+                i + 1,
+                i,
+                i,
+                ["open", "closed"][i % 2],
+                if i == 3 { "" } else { "free text" }
+            ));
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+        csv.override_type(5, DataType::Categorical).unwrap();
+
+        let schema = csv.to_json_schema().unwrap();
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DRAFT);
+        let properties = &schema["properties"];
+        assert_eq!(
+            properties["id"],
+            json!({"type": "integer", "minimum": 1.0, "maximum": 30.0})
+        );
+        assert_eq!(properties["zip"]["maxLength"], 5);
+        assert_eq!(properties["joined"]["format"], "date");
+        assert_eq!(properties["email"]["format"], "email");
+        assert_eq!(properties["site"]["format"], "uri");
+        assert_eq!(properties["status"]["enum"], json!(["closed", "open"]));
+        assert_eq!(properties["note"]["type"], json!(["string", "null"]));
+        assert_eq!(
+            schema["required"],
+            json!(["id", "zip", "joined", "email", "site", "status"])
+        );
+    }
+
+    #[test]
+    fn test_nullable_enum() {
+        let data = "size,n\nS,1\nM,2\n,3\nS,4";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.override_type(0, DataType::Categorical).unwrap();
+
+        let schema = csv.to_json_schema().unwrap();
+        assert_eq!(
+            schema["properties"]["size"],
+            json!({"type": ["string", "null"], "enum": ["M", "S", null]})
+        );
+    }
+}
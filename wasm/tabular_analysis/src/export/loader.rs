@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::cleaning::dates::{parse_with_order, resolve_day_first};
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::types::{date::DateFormat, DataType};
+
+/// Values commonly written for a missing value, listed as NA values when a column holds them
+pub const NULL_TOKENS: [&str; 14] = [
+    "NA", "N/A", "n/a", "NULL", "null", "None", "none", "NaN", "nan", "#N/A", "-", "?", "--",
+    "missing",
+];
+
+// Path the snippets read from, set on their first line for the user to change
+pub(crate) const DEFAULT_PATH: &str = "data.csv";
+
+/// Library a loading snippet is written for
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoaderTarget {
+    /// Python, `pandas.read_csv`
+    Pandas,
+    /// R, `readr::read_csv`
+    Readr,
+}
+
+impl LoaderTarget {
+    pub fn parse(name: &str) -> Result<LoaderTarget, AnalysisError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "pandas" | "python" => Ok(LoaderTarget::Pandas),
+            "readr" | "r" => Ok(LoaderTarget::Readr),
+            _ => Err(AnalysisError::invalid_argument(format!(
+                "Unknown loader target '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+// What a snippet needs to know about a column
+struct LoadedColumn<'a> {
+    name: &'a str,
+    metadata: ColumnMetadata,
+    // The one format every date of a Date column is written in
+    date_format: Option<DateFormat>,
+}
+
+// Format shared by every parseable date of a column, None when they're written several ways
+pub(crate) fn single_date_format(values: &[String]) -> Option<DateFormat> {
+    let (day_first, _, _) = resolve_day_first(values);
+    let formats: BTreeSet<DateFormat> = values
+        .iter()
+        .filter_map(|value| parse_with_order(value, day_first))
+        .map(|date| date.format())
+        .collect();
+    match formats.len() {
+        1 => formats.into_iter().next(),
+        _ => None,
+    }
+}
+
+// Python string literal; JSON escaping is valid Python for these
+fn python_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+// R string literal
+fn r_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// R argument name, backquoted unless syntactic
+fn r_name(name: &str) -> String {
+    let syntactic = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
+    match syntactic {
+        true => name.to_string(),
+        false => format!("`{}`", name.replace('`', "\\`")),
+    }
+}
+
+// Whether a numeric column writes plain numbers, which the loaders parse without help
+fn plain_numbers(metadata: &ColumnMetadata) -> bool {
+    metadata.number_format.is_none_or(|format| {
+        format.thousands_separator.is_none()
+            && format.decimal_separator == '.'
+            && !format.parentheses_negative
+    })
+}
+
+// pandas dtype of a column, None for dates, which go through `parse_dates`
+fn pandas_dtype(column: &LoadedColumn) -> Option<&'static str> {
+    let metadata = &column.metadata;
+    if metadata.fixed_width().is_some() {
+        return Some("string");
+    }
+    match metadata.data_type {
+        DataType::Integer if plain_numbers(metadata) => Some("Int64"),
+        DataType::Decimal if plain_numbers(metadata) => Some("float64"),
+        DataType::Date => None,
+        DataType::Categorical => Some("category"),
+        _ => Some("string"),
+    }
+}
+
+fn pandas_code(columns: &[LoadedColumn], na_values: &[&str]) -> String {
+    let mut code = format!(
+        "import pandas as pd\n\npath = {}\ndf = pd.read_csv(\n    path,\n",
+        python_string(DEFAULT_PATH)
+    );
+
+    code.push_str("    dtype={\n");
+    for column in columns {
+        if let Some(dtype) = pandas_dtype(column) {
+            code.push_str(&format!(
+                "        {}: {},\n",
+                python_string(column.name),
+                python_string(dtype)
+            ));
+        }
+    }
+    code.push_str("    },\n");
+
+    let dates: Vec<&LoadedColumn> = columns
+        .iter()
+        .filter(|column| pandas_dtype(column).is_none())
+        .collect();
+    if !dates.is_empty() {
+        let names: Vec<String> = dates.iter().map(|c| python_string(c.name)).collect();
+        code.push_str(&format!("    parse_dates=[{}],\n", names.join(", ")));
+        let formats: Vec<String> = dates
+            .iter()
+            .filter_map(|c| {
+                c.date_format.map(|format| {
+                    format!(
+                        "{}: {}",
+                        python_string(c.name),
+                        python_string(format.strftime())
+                    )
+                })
+            })
+            .collect();
+        if !formats.is_empty() {
+            code.push_str(&format!("    date_format={{{}}},\n", formats.join(", ")));
+        }
+    }
+    if !na_values.is_empty() {
+        let tokens: Vec<String> = na_values.iter().map(|t| python_string(t)).collect();
+        code.push_str(&format!("    na_values=[{}],\n", tokens.join(", ")));
+    }
+    code.push_str(")\n");
+
+    // Numbers with currency symbols or separators are read as text, then converted once
+    // those are stripped. Quantities stay text, their units differing from row to row.
+    for column in columns {
+        let metadata = &column.metadata;
+        let converted = matches!(
+            metadata.data_type,
+            DataType::Integer | DataType::Decimal | DataType::Currency
+        ) && pandas_dtype(column) == Some("string");
+        if !converted {
+            continue;
+        }
+        let format = metadata.number_format.unwrap_or_default();
+        let name = python_string(column.name);
+        if format.parentheses_negative {
+            code.push_str(&format!(
+                "df[{}] = df[{}].str.replace(r\"^\\((.*)\\)$\", r\"-\\1\", regex=True)\n",
+                name, name
+            ));
+        }
+        let decimal = format.decimal_separator;
+        let mut conversion = format!(
+            "df[{}].str.replace(r\"[^0-9{}\\-]\", \"\", regex=True)",
+            name,
+            regex::escape(&decimal.to_string())
+        );
+        if decimal != '.' {
+            conversion.push_str(&format!(
+                ".str.replace({}, \".\")",
+                python_string(&decimal.to_string())
+            ));
+        }
+        code.push_str(&format!("df[{}] = pd.to_numeric({})\n", name, conversion));
+    }
+    code
+}
+
+// readr column specification of a column
+fn readr_spec(column: &LoadedColumn) -> String {
+    let metadata = &column.metadata;
+    if metadata.fixed_width().is_some() {
+        return "col_character()".to_string();
+    }
+    let fits_i32 = metadata
+        .numeric_stats
+        .as_ref()
+        .is_none_or(|s| s.min >= i32::MIN as f64 && s.max <= i32::MAX as f64);
+    match metadata.data_type {
+        // col_integer is 32-bit, so larger whole numbers are read as doubles
+        DataType::Integer if plain_numbers(metadata) && fits_i32 => "col_integer()".to_string(),
+        DataType::Integer | DataType::Decimal if plain_numbers(metadata) => {
+            "col_double()".to_string()
+        }
+        // col_number drops currency symbols and grouping marks around the number
+        DataType::Integer | DataType::Decimal | DataType::Currency => "col_number()".to_string(),
+        DataType::Date => match column.date_format {
+            Some(format) => format!("col_date(format = {})", r_string(format.strftime())),
+            None => "col_date()".to_string(),
+        },
+        DataType::Categorical => "col_factor()".to_string(),
+        // Quantities keep their units, which differ from row to row
+        DataType::Quantity
+        | DataType::Email
+        | DataType::Phone
+        | DataType::CreditCard
+        | DataType::Text
+        | DataType::Mixed => "col_character()".to_string(),
+    }
+}
+
+fn readr_code(columns: &[LoadedColumn], na_values: &[&str]) -> String {
+    let mut code = format!(
+        "library(readr)\n\npath <- {}\ndf <- read_csv(\n  path,\n  col_types = cols(\n",
+        r_string(DEFAULT_PATH)
+    );
+    for column in columns {
+        code.push_str(&format!(
+            "    {} = {},\n",
+            r_name(column.name),
+            readr_spec(column)
+        ));
+    }
+    code.push_str("    .default = col_character()\n  ),\n");
+
+    // Separators other than the defaults are set for the whole file
+    let decimal_comma = columns.iter().any(|column| {
+        column
+            .metadata
+            .number_format
+            .is_some_and(|format| format.decimal_separator == ',')
+    });
+    if decimal_comma {
+        code.push_str("  locale = locale(decimal_mark = \",\", grouping_mark = \".\"),\n");
+    }
+
+    let mut tokens = vec![r_string(""), r_string("NA")];
+    tokens.extend(
+        na_values
+            .iter()
+            .filter(|&&token| token != "NA")
+            .map(|token| r_string(token)),
+    );
+    code.push_str(&format!("  na = c({})\n)\n", tokens.join(", ")));
+    code
+}
+
+impl CSV {
+    /// Code loading the file with the detected column types in pandas or readr: a dtype or
+    /// column specification per column, dates parsed in the format they're written in, and
+    /// the missing-value tokens found in the data. Columns not inferred yet are inferred
+    /// without storing the result.
+    pub fn generate_loader_code(&self, target: LoaderTarget) -> Result<String, AnalysisError> {
+        let mut columns = Vec::with_capacity(self.column_count());
+        let mut found_tokens = BTreeSet::new();
+        for index in 0..self.column_count() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (name, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+
+            for value in values {
+                if let Some(position) = NULL_TOKENS.iter().position(|&t| t == value.trim()) {
+                    found_tokens.insert(position);
+                }
+            }
+            let date_format = match metadata.data_type {
+                DataType::Date => single_date_format(values),
+                _ => None,
+            };
+            columns.push(LoadedColumn {
+                name,
+                metadata,
+                date_format,
+            });
+        }
+
+        let na_values: Vec<&str> = found_tokens.into_iter().map(|i| NULL_TOKENS[i]).collect();
+        Ok(match target {
+            LoaderTarget::Pandas => pandas_code(&columns, &na_values),
+            LoaderTarget::Readr => readr_code(&columns, &na_values),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CSV {
+        let mut data = String::from("id,zip,joined,status,price,Sp. Atk\n");
+        for i in 0..30 {
+            data.push_str(&format!(
+                "{},0{}134,{:02}/01/2024,{},\"$1,{}00.50\",{}\n",
+                i,
+                i % 9,
+                i % 12 + 1,
+                ["open", "closed"][i % 2],
+                i % 10,
+                if i == 4 { "N/A" } else { "55" }
+            ));
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+        csv.override_type(3, DataType::Categorical).unwrap();
+        csv.override_type(5, DataType::Integer).unwrap();
+        csv
+    }
+
+    #[test]
+    fn test_pandas_snippet() {
+        let code = sample().generate_loader_code(LoaderTarget::Pandas).unwrap();
+        assert!(code.starts_with("import pandas as pd\n\npath = \"data.csv\"\n"));
+        assert!(code.contains("        \"id\": \"Int64\",\n"));
+        assert!(code.contains("        \"zip\": \"string\",\n"));
+        assert!(code.contains("        \"status\": \"category\",\n"));
+        assert!(code.contains("    parse_dates=[\"joined\"],\n"));
+        assert!(code.contains("    date_format={\"joined\": \"%m/%d/%Y\"},\n"));
+        assert!(code.contains("    na_values=[\"N/A\"],\n"));
+        assert!(code.contains(
+            "df[\"price\"] = pd.to_numeric(df[\"price\"].str.replace(r\"[^0-9\\.\\-]\", \"\", regex=True))\n"
+        ));
+    }
+
+    #[test]
+    fn test_readr_snippet() {
+        let code = sample().generate_loader_code(LoaderTarget::Readr).unwrap();
+        assert!(code.starts_with("library(readr)\n\npath <- \"data.csv\"\n"));
+        assert!(code.contains("    id = col_integer(),\n"));
+        assert!(code.contains("    zip = col_character(),\n"));
+        assert!(code.contains("    joined = col_date(format = \"%m/%d/%Y\"),\n"));
+        assert!(code.contains("    status = col_factor(),\n"));
+        assert!(code.contains("    price = col_number(),\n"));
+        assert!(code.contains("    `Sp. Atk` = col_integer(),\n"));
+        assert!(code.ends_with("  na = c(\"\", \"NA\", \"N/A\")\n)\n"));
+    }
+
+    #[test]
+    fn test_target_names() {
+        assert_eq!(LoaderTarget::parse("Python").unwrap(), LoaderTarget::Pandas);
+        assert_eq!(LoaderTarget::parse("r").unwrap(), LoaderTarget::Readr);
+        assert!(LoaderTarget::parse("julia").is_err());
+    }
+}
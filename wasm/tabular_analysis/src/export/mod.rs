@@ -0,0 +1,10 @@
+pub mod bulk_load;
+pub mod coercion;
+pub mod duckdb;
+pub mod encoding;
+pub mod json_schema;
+pub mod loader;
+#[cfg(feature = "polars")]
+pub mod polars;
+pub mod scaling;
+pub mod sql;
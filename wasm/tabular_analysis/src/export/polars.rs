@@ -0,0 +1,218 @@
+use chrono::NaiveDate;
+use polars::prelude::{
+    CategoricalOrdering, Column as PlColumn, DataFrame, DataType as ArrowType, NamedFrom,
+    PolarsError, Series,
+};
+
+use serde_json::Value;
+
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+use crate::types::{date::DateType, DataType, TypeDetection};
+
+/// Arrow dtype a column of the given type is converted to; codes with leading zeros and other
+/// text stay strings, as do integers beyond 64 bits
+pub fn arrow_dtype(metadata: &ColumnMetadata) -> ArrowType {
+    if metadata.fixed_width().is_some() {
+        return ArrowType::String;
+    }
+    match metadata.data_type {
+        DataType::Integer => match &metadata.big_integer {
+            Some(big) if !big.fits_i64 => ArrowType::String,
+            _ => ArrowType::Int64,
+        },
+        DataType::Decimal | DataType::Currency | DataType::Quantity => ArrowType::Float64,
+        DataType::Date => ArrowType::Date,
+        DataType::Categorical => ArrowType::Categorical(None, CategoricalOrdering::default()),
+        DataType::Email
+        | DataType::Phone
+        | DataType::CreditCard
+        | DataType::Text
+        | DataType::Mixed => ArrowType::String,
+    }
+}
+
+fn polars_error(error: PolarsError) -> AnalysisError {
+    AnalysisError::SerializationError {
+        message: format!("Polars conversion failed: {}", error),
+    }
+}
+
+// Parsed number of a cell, None when empty or not a number in the column's format
+fn number(value: &str, metadata: &ColumnMetadata) -> Option<f64> {
+    typed_value(value, Some(metadata)).as_f64()
+}
+
+// Days since 1970-01-01, Polars' representation of dates
+fn epoch_days(value: &str) -> Option<i32> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    let iso = DateType::normalize(value)?;
+    let date = NaiveDate::parse_from_str(&iso, "%Y-%m-%d").ok()?;
+    i32::try_from((date - epoch).num_days()).ok()
+}
+
+// Series of a column's values under the given dtype; cells that don't parse become null
+fn typed_series(
+    name: &str,
+    values: &[String],
+    metadata: &ColumnMetadata,
+    dtype: &ArrowType,
+) -> Result<Series, PolarsError> {
+    let name = name.into();
+    let series = match dtype {
+        // Big integers come as digit strings, parsed directly so no digit is rounded
+        ArrowType::Int64 => {
+            let numbers: Vec<Option<i64>> = values
+                .iter()
+                .map(|value| match typed_value(value, Some(metadata)) {
+                    Value::Number(number) => number.as_i64(),
+                    Value::String(digits) => digits.parse().ok(),
+                    _ => None,
+                })
+                .collect();
+            Series::new(name, numbers)
+        }
+        ArrowType::String if metadata.big_integer.is_some() => {
+            let digits: Vec<Option<String>> = values
+                .iter()
+                .map(|value| match typed_value(value, Some(metadata)) {
+                    Value::String(digits) => Some(digits),
+                    _ => None,
+                })
+                .collect();
+            Series::new(name, digits)
+        }
+        ArrowType::Float64 => {
+            let numbers: Vec<Option<f64>> =
+                values.iter().map(|value| number(value, metadata)).collect();
+            Series::new(name, numbers)
+        }
+        ArrowType::Date => {
+            let days: Vec<Option<i32>> = values.iter().map(|value| epoch_days(value)).collect();
+            Series::new(name, days).cast(&ArrowType::Date)?
+        }
+        _ => {
+            let text: Vec<Option<&str>> = values
+                .iter()
+                .map(|value| Some(value.as_str()).filter(|v| !v.trim().is_empty()))
+                .collect();
+            Series::new(name, text).cast(dtype)?
+        }
+    };
+    Ok(series)
+}
+
+impl CSV {
+    /// Converts the table to a Polars DataFrame with each column's dtype matched to its
+    /// detected type. Columns not inferred yet are inferred without storing the result.
+    pub fn to_polars(&self) -> Result<DataFrame, AnalysisError> {
+        let mut columns = Vec::with_capacity(self.column_count());
+        for index in 0..self.column_count() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (header, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let dtype = arrow_dtype(&metadata);
+            let series = typed_series(header, values, &metadata, &dtype).map_err(polars_error)?;
+            columns.push(PlColumn::from(series));
+        }
+        DataFrame::new(columns).map_err(polars_error)
+    }
+
+    /// Builds a CSV from a Polars DataFrame and infers its column types. Values are read as
+    /// their text form, dates as ISO 8601, and nulls become empty cells.
+    pub fn from_polars(frame: &DataFrame) -> Result<CSV, AnalysisError> {
+        let mut columns = Vec::with_capacity(frame.width());
+        for column in frame.get_columns() {
+            let text = column.cast(&ArrowType::String).map_err(polars_error)?;
+            let values = text
+                .str()
+                .map_err(polars_error)?
+                .into_iter()
+                .map(|value| value.unwrap_or_default().to_string())
+                .collect();
+            columns.push((column.name().to_string(), values, None));
+        }
+
+        let mut csv = CSV::from_parts(columns, Default::default());
+        csv.infer_column_types();
+        Ok(csv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dtypes_follow_detected_types() {
+        let mut data = String::from("id,zip,price,joined,status\n");
+        for i in 0..30 {
+            let status = ["open", "closed"][i % 2];
+            data.push_str(&format!(
+                "{},0{}134,$1{}.50,2024-01-{:02},{}\n",
+                i,
+                i % 9,
+                i,
+                i + 1,
+                status
+            ));
+        }
+        data.push_str("30,09134,,soon,open\n");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+        csv.override_type(4, DataType::Categorical).unwrap();
+
+        let frame = csv.to_polars().unwrap();
+        let dtypes: Vec<ArrowType> = frame.dtypes();
+        assert_eq!(dtypes[0], ArrowType::Int64);
+        assert_eq!(dtypes[1], ArrowType::String);
+        assert_eq!(dtypes[2], ArrowType::Float64);
+        assert_eq!(dtypes[3], ArrowType::Date);
+        assert!(matches!(dtypes[4], ArrowType::Categorical(..)));
+        assert_eq!(frame.height(), 31);
+        assert_eq!(frame.column("price").unwrap().null_count(), 1);
+        assert_eq!(frame.column("joined").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = "id,joined,name\n1,2024-01-15,Ada\n2,2024-02-01,\n3,2024-03-09,Grace";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let back = CSV::from_polars(&csv.to_polars().unwrap()).unwrap();
+        assert_eq!(back.headers(), vec!["id", "joined", "name"]);
+        assert_eq!(back.get_column(1).unwrap().1[2], "2024-03-09");
+        assert_eq!(back.get_column(2).unwrap().1[1], "");
+        assert_eq!(
+            back.column_metadata(0).unwrap().data_type,
+            DataType::Integer
+        );
+        assert_eq!(back.column_metadata(1).unwrap().data_type, DataType::Date);
+    }
+
+    #[test]
+    fn test_big_integers_keep_every_digit() {
+        let data = "id,ref\n9007199254740993,12345678901234567890\n-9223372036854775808,1\n";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let frame = csv.to_polars().unwrap();
+        let ids = frame.column("id").unwrap();
+        assert_eq!(ids.dtype(), &ArrowType::Int64);
+        let ids: Vec<Option<i64>> = ids.i64().unwrap().into_iter().collect();
+        assert_eq!(ids, vec![Some(9007199254740993), Some(i64::MIN)]);
+
+        let refs = frame.column("ref").unwrap();
+        assert_eq!(refs.dtype(), &ArrowType::String);
+        assert_eq!(refs.str().unwrap().get(0), Some("12345678901234567890"));
+        assert_eq!(refs.null_count(), 0);
+    }
+}
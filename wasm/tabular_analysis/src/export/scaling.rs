@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+
+/// How numeric columns are brought to a common scale for modeling
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleMethod {
+    /// Zero mean and unit standard deviation (z-scores)
+    Standardize,
+    /// From 0 at the minimum to 1 at the maximum
+    MinMax,
+    /// Zero median and unit interquartile range, so outliers weigh less
+    Robust,
+}
+
+impl ScaleMethod {
+    pub fn parse(name: &str) -> Result<ScaleMethod, AnalysisError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "standardize" | "standard" | "zscore" | "z-score" => Ok(ScaleMethod::Standardize),
+            "min_max" | "minmax" | "min-max" => Ok(ScaleMethod::MinMax),
+            "robust" => Ok(ScaleMethod::Robust),
+            _ => Err(AnalysisError::invalid_argument(format!(
+                "Unknown scaling method '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+/// A numeric column scaled as `(value - center) / scale`; `value * scale + center` gives the
+/// original value back
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScaledColumn {
+    pub column: usize,
+    pub name: String,
+    /// Mean, minimum or median, by method
+    pub center: f64,
+    /// Standard deviation, range or interquartile range, by method; 1 when the column has no
+    /// spread, so its values all scale to 0
+    pub scale: f64,
+    /// Scaled value of every row; None for empty cells, values that don't parse and
+    /// sentinels such as -999
+    pub values: Vec<Option<f64>>,
+}
+
+impl ScaledColumn {
+    /// The original value of a scaled one
+    pub fn inverse(&self, scaled: f64) -> f64 {
+        scaled * self.scale + self.center
+    }
+}
+
+/// Numeric columns scaled with the statistics already computed for them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScaledColumns {
+    pub method: ScaleMethod,
+    pub columns: Vec<ScaledColumn>,
+}
+
+impl ScaledColumns {
+    /// Row-major matrix of the scaled values, one row per CSV row and one entry per column
+    pub fn matrix(&self) -> Vec<Vec<Option<f64>>> {
+        let rows = self.columns.first().map_or(0, |c| c.values.len());
+        (0..rows)
+            .map(|row| self.columns.iter().map(|c| c.values[row]).collect())
+            .collect()
+    }
+}
+
+impl CSV {
+    /// Scales numeric columns by a method, using each column's analyzed statistics, and
+    /// returns the scaled values with the center and scale that undo it
+    pub fn scale(
+        &self,
+        columns: &[usize],
+        method: ScaleMethod,
+    ) -> Result<ScaledColumns, AnalysisError> {
+        if columns.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "At least one column to scale is required",
+            ));
+        }
+        let columns = columns
+            .iter()
+            .map(|&column| self.scale_column(column, method))
+            .collect::<Result<_, _>>()?;
+        Ok(ScaledColumns { method, columns })
+    }
+
+    fn scale_column(
+        &self,
+        column: usize,
+        method: ScaleMethod,
+    ) -> Result<ScaledColumn, AnalysisError> {
+        let (header, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let metadata = match self.column_metadata(column) {
+            Some(metadata) => metadata.clone(),
+            None => self
+                .infer_column(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })?,
+        };
+        let Some(stats) = metadata
+            .numeric_stats
+            .as_ref()
+            .filter(|_| metadata.data_type.is_numeric())
+        else {
+            return Err(AnalysisError::invalid_column(
+                column,
+                format!(
+                    "{} has no numeric statistics to scale by",
+                    metadata.data_type
+                ),
+            ));
+        };
+
+        let (center, spread) = match method {
+            ScaleMethod::Standardize => (stats.mean, stats.std_dev),
+            ScaleMethod::MinMax => (stats.min, stats.max - stats.min),
+            ScaleMethod::Robust => (stats.median, stats.quartiles[2] - stats.quartiles[0]),
+        };
+        let scale = match spread.is_finite() && spread > 0.0 {
+            true => spread,
+            false => 1.0,
+        };
+        let values = values
+            .iter()
+            .map(|value| {
+                let number = typed_value(value, Some(&metadata)).as_f64()?;
+                let sentinel = stats.sentinels.iter().any(|s| s.value == number);
+                (!sentinel).then(|| (number - center) / scale)
+            })
+            .collect();
+
+        Ok(ScaledColumn {
+            column,
+            name: header.to_string(),
+            center,
+            scale,
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaled(csv: &CSV, method: ScaleMethod) -> ScaledColumn {
+        csv.scale(&[1], method).unwrap().columns.remove(0)
+    }
+
+    #[test]
+    fn test_scale_methods() {
+        let data = "name,score\na,2\nb,4\nc,\nd,6\ne,8\nf,100";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let min_max = scaled(&csv, ScaleMethod::MinMax);
+        assert_eq!((min_max.center, min_max.scale), (2.0, 98.0));
+        assert_eq!(min_max.values[0], Some(0.0));
+        assert_eq!(min_max.values[2], None);
+        assert_eq!(min_max.values[5], Some(1.0));
+
+        let standard = scaled(&csv, ScaleMethod::Standardize);
+        let z: Vec<f64> = standard.values.iter().flatten().copied().collect();
+        let mean = z.iter().sum::<f64>() / z.len() as f64;
+        assert!(mean.abs() < 1e-9);
+        assert!((standard.inverse(z[1]) - 4.0).abs() < 1e-9);
+
+        // The outlier doesn't stretch the robust scale
+        let robust = scaled(&csv, ScaleMethod::Robust);
+        assert_eq!(robust.center, 6.0);
+        assert_eq!(robust.values[3], Some(0.0));
+        assert!(robust.scale < 10.0);
+
+        let scaled = csv.scale(&[1, 1], ScaleMethod::MinMax).unwrap();
+        assert_eq!(scaled.matrix()[0], vec![Some(0.0), Some(0.0)]);
+        assert!(csv.scale(&[0], ScaleMethod::MinMax).is_err());
+        assert!(csv.scale(&[], ScaleMethod::MinMax).is_err());
+        assert!(ScaleMethod::parse("z-score").is_ok());
+        assert!(ScaleMethod::parse("log").is_err());
+    }
+
+    #[test]
+    fn test_constant_column_scales_to_zero() {
+        let mut csv = CSV::from_string("id,n\n1,5\n2,5\n3,5".to_string()).unwrap();
+        csv.infer_column_types();
+        let column = scaled(&csv, ScaleMethod::Standardize);
+        assert_eq!(column.scale, 1.0);
+        assert_eq!(column.values, vec![Some(0.0); 3]);
+    }
+}
@@ -0,0 +1,1525 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::export::duckdb::duckdb_type;
+use crate::table::columns::{normalize_header, HeaderStyle};
+use crate::types::DataType;
+
+// Reserved in every supported dialect
+const CORE_RESERVED: &[&str] = &[
+    "ALL",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CAST",
+    "CHECK",
+    "COLUMN",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "FALSE",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IN",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LEFT",
+    "LIKE",
+    "NOT",
+    "NULL",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "PRIMARY",
+    "REFERENCES",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "TO",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WITH",
+];
+
+const POSTGRES_RESERVED: &[&str] = &[
+    "ANALYSE",
+    "ANALYZE",
+    "ARRAY",
+    "ASYMMETRIC",
+    "BINARY",
+    "BOTH",
+    "COLLATE",
+    "CONCURRENTLY",
+    "CURRENT_CATALOG",
+    "CURRENT_DATE",
+    "CURRENT_ROLE",
+    "CURRENT_SCHEMA",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "DEFERRABLE",
+    "DO",
+    "FETCH",
+    "FREEZE",
+    "GRANT",
+    "ILIKE",
+    "INITIALLY",
+    "ISNULL",
+    "LATERAL",
+    "LEADING",
+    "LIMIT",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "NATURAL",
+    "NOTNULL",
+    "OFFSET",
+    "ONLY",
+    "OVERLAPS",
+    "PLACING",
+    "RETURNING",
+    "SESSION_USER",
+    "SIMILAR",
+    "SOME",
+    "SYMMETRIC",
+    "TABLESAMPLE",
+    "TRAILING",
+    "USER",
+    "VARIADIC",
+    "VERBOSE",
+    "WINDOW",
+];
+
+const MYSQL_RESERVED: &[&str] = &[
+    "ACCESSIBLE",
+    "ADD",
+    "ALTER",
+    "BEFORE",
+    "BIGINT",
+    "BLOB",
+    "BOTH",
+    "CALL",
+    "CHANGE",
+    "CHAR",
+    "CHARACTER",
+    "CONDITION",
+    "CONTINUE",
+    "CONVERT",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "CURSOR",
+    "DATABASE",
+    "DATABASES",
+    "DEC",
+    "DECIMAL",
+    "DECLARE",
+    "DELAYED",
+    "DESCRIBE",
+    "DIV",
+    "DOUBLE",
+    "DUAL",
+    "EACH",
+    "ELSEIF",
+    "ENCLOSED",
+    "ESCAPED",
+    "EXIT",
+    "EXPLAIN",
+    "FETCH",
+    "FLOAT",
+    "FORCE",
+    "FULLTEXT",
+    "GRANT",
+    "GROUPS",
+    "IF",
+    "IGNORE",
+    "INDEX",
+    "INFILE",
+    "INT",
+    "INTEGER",
+    "INTERVAL",
+    "KEY",
+    "KEYS",
+    "KILL",
+    "LEADING",
+    "LEAVE",
+    "LIMIT",
+    "LINES",
+    "LOAD",
+    "LOCK",
+    "LONG",
+    "LOOP",
+    "MATCH",
+    "MOD",
+    "NATURAL",
+    "NUMERIC",
+    "OPTION",
+    "OUT",
+    "PARTITION",
+    "PRECISION",
+    "PROCEDURE",
+    "PURGE",
+    "RANGE",
+    "RANK",
+    "READ",
+    "REAL",
+    "RECURSIVE",
+    "REGEXP",
+    "RENAME",
+    "REPEAT",
+    "REPLACE",
+    "REQUIRE",
+    "RESTRICT",
+    "RETURN",
+    "REVOKE",
+    "RLIKE",
+    "ROW",
+    "ROWS",
+    "SCHEMA",
+    "SEPARATOR",
+    "SHOW",
+    "SMALLINT",
+    "SPATIAL",
+    "SQL",
+    "STARTING",
+    "TERMINATED",
+    "TINYINT",
+    "TRAILING",
+    "TRIGGER",
+    "UNDO",
+    "UNLOCK",
+    "UNSIGNED",
+    "USAGE",
+    "USE",
+    "VARCHAR",
+    "VARYING",
+    "WHILE",
+    "WINDOW",
+    "WRITE",
+    "XOR",
+    "ZEROFILL",
+];
+
+const SQLITE_RESERVED: &[&str] = &[
+    "ABORT",
+    "ACTION",
+    "ADD",
+    "AFTER",
+    "ALTER",
+    "ANALYZE",
+    "ATTACH",
+    "AUTOINCREMENT",
+    "BEFORE",
+    "BEGIN",
+    "CASCADE",
+    "COLLATE",
+    "COMMIT",
+    "CONFLICT",
+    "DATABASE",
+    "DEFERRABLE",
+    "DEFERRED",
+    "DETACH",
+    "EACH",
+    "ESCAPE",
+    "EXCLUSIVE",
+    "EXPLAIN",
+    "FAIL",
+    "GLOB",
+    "IF",
+    "IGNORE",
+    "IMMEDIATE",
+    "INDEX",
+    "INDEXED",
+    "INITIALLY",
+    "INSTEAD",
+    "ISNULL",
+    "KEY",
+    "LIMIT",
+    "MATCH",
+    "NATURAL",
+    "NO",
+    "NOTNULL",
+    "OF",
+    "OFFSET",
+    "PLAN",
+    "PRAGMA",
+    "QUERY",
+    "RAISE",
+    "RECURSIVE",
+    "REGEXP",
+    "REINDEX",
+    "RELEASE",
+    "RENAME",
+    "REPLACE",
+    "RESTRICT",
+    "ROLLBACK",
+    "ROW",
+    "SAVEPOINT",
+    "TEMP",
+    "TEMPORARY",
+    "TRANSACTION",
+    "TRIGGER",
+    "VACUUM",
+    "VIEW",
+    "VIRTUAL",
+    "WITHOUT",
+];
+
+const MSSQL_RESERVED: &[&str] = &[
+    "ADD",
+    "ALTER",
+    "AUTHORIZATION",
+    "BACKUP",
+    "BEGIN",
+    "BREAK",
+    "BROWSE",
+    "BULK",
+    "CASCADE",
+    "CHECKPOINT",
+    "CLOSE",
+    "CLUSTERED",
+    "COALESCE",
+    "COLLATE",
+    "COMMIT",
+    "COMPUTE",
+    "CONTAINS",
+    "CONTAINSTABLE",
+    "CONTINUE",
+    "CONVERT",
+    "CURRENT",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "CURSOR",
+    "DATABASE",
+    "DBCC",
+    "DEALLOCATE",
+    "DECLARE",
+    "DENY",
+    "DISK",
+    "DISTRIBUTED",
+    "DOUBLE",
+    "DUMP",
+    "ERRLVL",
+    "ESCAPE",
+    "EXEC",
+    "EXECUTE",
+    "EXIT",
+    "EXTERNAL",
+    "FETCH",
+    "FILE",
+    "FILLFACTOR",
+    "FREETEXT",
+    "FUNCTION",
+    "GOTO",
+    "GRANT",
+    "HOLDLOCK",
+    "IDENTITY",
+    "IDENTITYCOL",
+    "IDENTITY_INSERT",
+    "IF",
+    "INDEX",
+    "KEY",
+    "KILL",
+    "LINENO",
+    "LOAD",
+    "MERGE",
+    "NATIONAL",
+    "NOCHECK",
+    "NONCLUSTERED",
+    "NULLIF",
+    "OF",
+    "OFF",
+    "OFFSETS",
+    "OPEN",
+    "OPENDATASOURCE",
+    "OPENQUERY",
+    "OPENROWSET",
+    "OPENXML",
+    "OPTION",
+    "OVER",
+    "PERCENT",
+    "PIVOT",
+    "PLAN",
+    "PRECISION",
+    "PRINT",
+    "PROC",
+    "PROCEDURE",
+    "PUBLIC",
+    "RAISERROR",
+    "READ",
+    "READTEXT",
+    "RECONFIGURE",
+    "REPLICATION",
+    "RESTORE",
+    "RESTRICT",
+    "RETURN",
+    "REVERT",
+    "REVOKE",
+    "ROLLBACK",
+    "ROWCOUNT",
+    "ROWGUIDCOL",
+    "RULE",
+    "SAVE",
+    "SCHEMA",
+    "SESSION_USER",
+    "SETUSER",
+    "SHUTDOWN",
+    "SOME",
+    "STATISTICS",
+    "SYSTEM_USER",
+    "TABLESAMPLE",
+    "TEXTSIZE",
+    "TOP",
+    "TRAN",
+    "TRANSACTION",
+    "TRIGGER",
+    "TRUNCATE",
+    "TRY_CONVERT",
+    "TSEQUAL",
+    "UNPIVOT",
+    "UPDATETEXT",
+    "USE",
+    "USER",
+    "VARYING",
+    "VIEW",
+    "WAITFOR",
+    "WHILE",
+    "WITHIN",
+    "WRITETEXT",
+];
+
+const DUCKDB_RESERVED: &[&str] = &[
+    "ANALYSE",
+    "ANALYZE",
+    "ARRAY",
+    "ASYMMETRIC",
+    "BOTH",
+    "COLLATE",
+    "CURRENT_CATALOG",
+    "CURRENT_DATE",
+    "CURRENT_ROLE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "DEFERRABLE",
+    "DESCRIBE",
+    "DO",
+    "FETCH",
+    "GRANT",
+    "INITIALLY",
+    "LAMBDA",
+    "LATERAL",
+    "LEADING",
+    "LIMIT",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "OFFSET",
+    "ONLY",
+    "PIVOT",
+    "PIVOT_LONGER",
+    "PIVOT_WIDER",
+    "PLACING",
+    "QUALIFY",
+    "RETURNING",
+    "SHOW",
+    "SOME",
+    "SUMMARIZE",
+    "SYMMETRIC",
+    "TRAILING",
+    "UNPIVOT",
+    "VARIADIC",
+    "WINDOW",
+];
+
+const BIGQUERY_RESERVED: &[&str] = &[
+    "ARRAY",
+    "ASSERT_ROWS_MODIFIED",
+    "COLLATE",
+    "CONTAINS",
+    "CUBE",
+    "CURRENT",
+    "DEFINE",
+    "ENUM",
+    "ESCAPE",
+    "EXCLUDE",
+    "EXTRACT",
+    "FETCH",
+    "FOLLOWING",
+    "GROUPING",
+    "GROUPS",
+    "HASH",
+    "IF",
+    "IGNORE",
+    "INTERVAL",
+    "LATERAL",
+    "LIMIT",
+    "LOOKUP",
+    "MERGE",
+    "NATURAL",
+    "NEW",
+    "NO",
+    "NULLS",
+    "OF",
+    "OVER",
+    "PARTITION",
+    "PRECEDING",
+    "PROTO",
+    "QUALIFY",
+    "RANGE",
+    "RECURSIVE",
+    "RESPECT",
+    "ROLLUP",
+    "ROWS",
+    "SOME",
+    "STRUCT",
+    "TABLESAMPLE",
+    "TREAT",
+    "UNBOUNDED",
+    "WINDOW",
+    "WITHIN",
+];
+
+// Longest date span BigQuery partitions by day, staying under its partition limit; longer
+// spans are partitioned by month
+const MAX_DAILY_PARTITION_DAYS: i64 = 4000;
+// BigQuery clusters by at most four columns
+const MAX_CLUSTER_COLUMNS: usize = 4;
+// Columns with more distinct values than this, or than a tenth of their values, filter too
+// finely to cluster by
+const MAX_CLUSTER_CARDINALITY: usize = 1000;
+const CLUSTER_RATIO: usize = 10;
+// Composite indexes suggested at most, pairing the most selective filter columns with dates
+const MAX_COMPOSITE_INDEXES: usize = 3;
+// Categorical levels written into an ENUM or CHECK constraint; columns with more get none
+const MAX_CONSTRAINT_LEVELS: usize = 50;
+
+/// Database a schema is written for, deciding column types, reserved words and quoting
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    #[default]
+    Postgres,
+    MySql,
+    Sqlite,
+    /// SQL Server
+    MsSql,
+    DuckDb,
+    BigQuery,
+}
+
+impl SqlDialect {
+    pub fn parse(name: &str) -> Result<SqlDialect, AnalysisError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" => Ok(SqlDialect::Postgres),
+            "mysql" | "mariadb" => Ok(SqlDialect::MySql),
+            "sqlite" => Ok(SqlDialect::Sqlite),
+            "mssql" | "sqlserver" => Ok(SqlDialect::MsSql),
+            "duckdb" => Ok(SqlDialect::DuckDb),
+            "bigquery" => Ok(SqlDialect::BigQuery),
+            _ => Err(AnalysisError::invalid_argument(format!(
+                "Unknown SQL dialect '{}'",
+                name
+            ))),
+        }
+    }
+
+    fn reserved_words(self) -> &'static [&'static str] {
+        match self {
+            SqlDialect::Postgres => POSTGRES_RESERVED,
+            SqlDialect::MySql => MYSQL_RESERVED,
+            SqlDialect::Sqlite => SQLITE_RESERVED,
+            SqlDialect::MsSql => MSSQL_RESERVED,
+            SqlDialect::DuckDb => DUCKDB_RESERVED,
+            SqlDialect::BigQuery => BIGQUERY_RESERVED,
+        }
+    }
+
+    /// Whether a name is a keyword that can't be used as an unquoted identifier
+    pub fn is_reserved(self, name: &str) -> bool {
+        CORE_RESERVED
+            .iter()
+            .chain(self.reserved_words())
+            .any(|word| word.eq_ignore_ascii_case(name))
+    }
+
+    /// Quotes an identifier: double quotes for Postgres, SQLite and DuckDB, backquotes for
+    /// MySQL and BigQuery, brackets for SQL Server
+    pub fn quote(self, name: &str) -> String {
+        match self {
+            SqlDialect::Postgres | SqlDialect::Sqlite | SqlDialect::DuckDb => {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            }
+            SqlDialect::MySql => format!("`{}`", name.replace('`', "``")),
+            SqlDialect::BigQuery => format!("`{}`", name.replace('`', "\\`")),
+            SqlDialect::MsSql => format!("[{}]", name.replace(']', "]]")),
+        }
+    }
+
+    /// An identifier as written in statements: as is when it's a plain name that isn't
+    /// reserved, quoted otherwise. Postgres folds unquoted names to lowercase, so names with
+    /// capitals are quoted there too.
+    pub fn identifier(self, name: &str) -> String {
+        let plain = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !(self == SqlDialect::Postgres && name.chars().any(|c| c.is_ascii_uppercase()));
+        match plain && !self.is_reserved(name) {
+            true => name.to_string(),
+            false => self.quote(name),
+        }
+    }
+
+    // Longest table or schema name the dialect accepts, in bytes
+    fn max_name_length(self) -> Option<usize> {
+        match self {
+            SqlDialect::Postgres => Some(63),
+            SqlDialect::MySql => Some(64),
+            SqlDialect::MsSql => Some(128),
+            SqlDialect::BigQuery => Some(1024),
+            SqlDialect::Sqlite | SqlDialect::DuckDb => None,
+        }
+    }
+
+    /// Checks a table or schema name against the dialect's rules: not blank, without NUL
+    /// characters, within its length limit, and only letters, digits and underscores for
+    /// BigQuery, which can't quote others
+    pub fn validate_name(self, name: &str, kind: &str) -> Result<(), AnalysisError> {
+        let problem = if name.trim().is_empty() {
+            Some("can't be empty".to_string())
+        } else if name.contains('\0') {
+            Some("can't contain NUL characters".to_string())
+        } else if let Some(max) = self.max_name_length().filter(|&max| name.len() > max) {
+            Some(format!(
+                "is longer than the {} bytes {:?} allows",
+                max, self
+            ))
+        } else if self == SqlDialect::BigQuery
+            && !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            Some("can only hold letters, digits and underscores in BigQuery".to_string())
+        } else {
+            None
+        };
+        match problem {
+            Some(problem) => Err(AnalysisError::invalid_argument(format!(
+                "{} name '{}' {}",
+                kind, name, problem
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Column type for the detected type, refined by the suggested SQL type where the
+    /// dialect has it
+    pub fn column_type(self, metadata: &ColumnMetadata) -> String {
+        let sql_type = metadata
+            .sql_type
+            .clone()
+            .unwrap_or_else(|| metadata.data_type.default_sql_type().to_string());
+        let fixed_width = metadata.fixed_width().is_some();
+        // Digits of integer columns too large for 64 bits
+        let big_integer_digits = metadata
+            .big_integer
+            .as_ref()
+            .filter(|big| !big.fits_i64)
+            .map(|big| big.max_digits);
+        match self {
+            SqlDialect::Postgres | SqlDialect::MySql => sql_type,
+            SqlDialect::MsSql => match sql_type.as_str() {
+                "TEXT" => "NVARCHAR(MAX)".to_string(),
+                _ => sql_type,
+            },
+            SqlDialect::DuckDb => duckdb_type(metadata).to_string(),
+            SqlDialect::Sqlite => match metadata.data_type {
+                _ if fixed_width => "TEXT",
+                // Integers past 64 bits would be stored as rounded REALs
+                DataType::Integer if big_integer_digits.is_some() => "TEXT",
+                DataType::Integer => "INTEGER",
+                DataType::Decimal | DataType::Quantity => "REAL",
+                DataType::Currency => "NUMERIC",
+                _ => "TEXT",
+            }
+            .to_string(),
+            SqlDialect::BigQuery => match metadata.data_type {
+                _ if fixed_width => "STRING",
+                DataType::Integer => match big_integer_digits {
+                    Some(digits) if digits <= 29 => "NUMERIC",
+                    Some(digits) if digits <= 76 => "BIGNUMERIC",
+                    Some(_) => "STRING",
+                    None => "INT64",
+                },
+                DataType::Decimal | DataType::Quantity => "FLOAT64",
+                DataType::Currency => "NUMERIC",
+                DataType::Date => "DATE",
+                _ => "STRING",
+            }
+            .to_string(),
+        }
+    }
+}
+
+/// How `to_sql_schema` writes the table
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SqlOptions {
+    #[serde(default)]
+    pub dialect: SqlDialect,
+    /// Renames columns named like a reserved word, `order` to `order_`, instead of quoting
+    /// them
+    #[serde(default)]
+    pub rename_reserved: bool,
+    /// Schema, or BigQuery dataset, to create the table in
+    #[serde(default)]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+    /// Creates a temporary table, `#name` in SQL Server; can't be combined with a schema
+    #[serde(default)]
+    pub temporary: bool,
+    /// Adds a DROP TABLE IF EXISTS statement to run before creating the table
+    #[serde(default)]
+    pub drop_existing: bool,
+    /// Suggests partitioning and clustering from the column profiles: as PARTITION BY and
+    /// CLUSTER BY clauses for BigQuery and as comments for Postgres
+    #[serde(default)]
+    pub layout_hints: bool,
+    /// Suggests indexes from the column profiles, each with a comment saying why; BigQuery
+    /// tables have none
+    #[serde(default)]
+    pub index_hints: bool,
+    /// Restricts categorical columns to their levels: an ENUM type in MySQL and a CHECK
+    /// constraint elsewhere but BigQuery
+    #[serde(default)]
+    pub level_constraints: bool,
+}
+
+impl SqlOptions {
+    // Table as written in statements, schema-qualified and quoted where needed
+    fn table_reference(&self, table_name: &str) -> Result<String, AnalysisError> {
+        let dialect = self.dialect;
+        dialect.validate_name(table_name, "Table")?;
+        let table = match (dialect, self.temporary) {
+            (SqlDialect::MsSql, true) => dialect.quote(&format!("#{}", table_name)),
+            _ => dialect.identifier(table_name),
+        };
+        match &self.schema {
+            Some(_) if self.temporary => Err(AnalysisError::invalid_argument(
+                "Temporary tables can't be created in a schema",
+            )),
+            Some(schema) => {
+                dialect.validate_name(schema, "Schema")?;
+                Ok(format!("{}.{}", dialect.identifier(schema), table))
+            }
+            None => Ok(table),
+        }
+    }
+
+    // A suggested index with its CREATE INDEX statement, named after the table and columns
+    fn index_statement(
+        &self,
+        table_name: &str,
+        table: &str,
+        columns: Vec<String>,
+        unique: bool,
+        reason: String,
+    ) -> IndexSuggestion {
+        let dialect = self.dialect;
+        let name = normalize_header(
+            &format!("idx {} {}", table_name, columns.join(" ")),
+            0,
+            HeaderStyle::Snake,
+        );
+        let if_not_exists = match dialect {
+            SqlDialect::Postgres | SqlDialect::Sqlite | SqlDialect::DuckDb
+                if self.if_not_exists =>
+            {
+                "IF NOT EXISTS "
+            }
+            _ => "",
+        };
+        let keys: Vec<String> = columns.iter().map(|c| dialect.identifier(c)).collect();
+        let statement = format!(
+            "-- {}\nCREATE {}INDEX {}{} ON {} ({});",
+            reason,
+            if unique { "UNIQUE " } else { "" },
+            if_not_exists,
+            dialect.identifier(&name),
+            table,
+            keys.join(", ")
+        );
+        IndexSuggestion {
+            name,
+            columns,
+            unique,
+            reason,
+            statement,
+        }
+    }
+
+    // CREATE TABLE up to the table name, with the temporary and IF NOT EXISTS options
+    fn create_prefix(&self, table: &str) -> String {
+        let temporary = match (self.temporary, self.dialect) {
+            (false, _) | (true, SqlDialect::MsSql) => "",
+            (true, SqlDialect::Postgres | SqlDialect::MySql) => "TEMPORARY ",
+            (true, _) => "TEMP ",
+        };
+        match (self.if_not_exists, self.dialect) {
+            (false, _) => format!("CREATE {}TABLE {}", temporary, table),
+            // SQL Server has no IF NOT EXISTS; temporary tables are looked up in tempdb
+            (true, SqlDialect::MsSql) => {
+                let object = match self.temporary {
+                    true => format!("tempdb..{}", table),
+                    false => table.to_string(),
+                };
+                format!(
+                    "IF OBJECT_ID(N'{}', N'U') IS NULL\nCREATE TABLE {}",
+                    object.replace('\'', "''"),
+                    table
+                )
+            }
+            (true, _) => format!("CREATE {}TABLE IF NOT EXISTS {}", temporary, table),
+        }
+    }
+}
+
+/// A column of a generated table
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SqlColumn {
+    /// Name in the table, unquoted
+    pub name: String,
+    /// Header of the column in the CSV
+    pub header: String,
+    pub sql_type: String,
+    /// Whether the column has empty values, leaving out NOT NULL
+    pub nullable: bool,
+    /// Whether the header is a reserved word in the dialect
+    pub reserved: bool,
+    /// Values the column is restricted to by `level_constraints`
+    #[serde(default)]
+    pub levels: Vec<String>,
+}
+
+/// How often a partitioned table starts a new partition
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionGranularity {
+    Day,
+    Month,
+}
+
+/// Partitioning and clustering suggested for a table from its column profiles
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TableLayout {
+    /// Date column to partition by, the one with the fewest empty values
+    pub partition_column: Option<String>,
+    /// By day, or by month when the dates span too long for a partition per day
+    pub partition_granularity: Option<PartitionGranularity>,
+    /// Low-cardinality text columns to cluster by, fewest distinct values first
+    pub cluster_columns: Vec<String>,
+}
+
+impl TableLayout {
+    // BigQuery PARTITION BY and CLUSTER BY clauses following the column list
+    fn bigquery_clauses(&self, dialect: SqlDialect) -> String {
+        let mut clauses = String::new();
+        if let Some(column) = &self.partition_column {
+            let column = dialect.identifier(column);
+            match self.partition_granularity {
+                Some(PartitionGranularity::Month) => {
+                    clauses.push_str(&format!("\nPARTITION BY DATE_TRUNC({}, MONTH)", column))
+                }
+                _ => clauses.push_str(&format!("\nPARTITION BY {}", column)),
+            }
+        }
+        if !self.cluster_columns.is_empty() {
+            let columns: Vec<String> = self
+                .cluster_columns
+                .iter()
+                .map(|column| dialect.identifier(column))
+                .collect();
+            clauses.push_str(&format!("\nCLUSTER BY {}", columns.join(", ")));
+        }
+        clauses
+    }
+
+    // Comments after a Postgres CREATE TABLE, which would need its partitions created
+    // before rows could be loaded
+    fn postgres_comments(&self, table: &str) -> String {
+        let mut comments = String::new();
+        if let Some(column) = &self.partition_column {
+            let per = match self.partition_granularity {
+                Some(PartitionGranularity::Month) => "month",
+                _ => "day",
+            };
+            comments.push_str(&format!(
+                "\n-- Suggested partitioning: PARTITION BY RANGE ({}), a partition per {}",
+                SqlDialect::Postgres.identifier(column),
+                per
+            ));
+        }
+        if !self.cluster_columns.is_empty() {
+            let columns: Vec<String> = self
+                .cluster_columns
+                .iter()
+                .map(|column| SqlDialect::Postgres.identifier(column))
+                .collect();
+            comments.push_str(&format!(
+                "\n-- Suggested clustering: CLUSTER {} USING an index on ({})",
+                table,
+                columns.join(", ")
+            ));
+        }
+        comments
+    }
+}
+
+/// An index suggested from the column profiles
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IndexSuggestion {
+    pub name: String,
+    /// Indexed columns in key order, by their name in the table
+    pub columns: Vec<String>,
+    pub unique: bool,
+    /// Why the index is suggested, also written as a comment above the statement
+    pub reason: String,
+    pub statement: String,
+}
+
+/// CREATE TABLE statement for a dialect and the columns it defines
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SqlSchema {
+    pub dialect: SqlDialect,
+    pub table_name: String,
+    /// The table as written in the statements, with its schema and quoting
+    pub table_reference: String,
+    /// DROP TABLE IF EXISTS statement to run first, when asked for
+    #[serde(default)]
+    pub drop_table: Option<String>,
+    pub create_table: String,
+    pub columns: Vec<SqlColumn>,
+    /// Suggested partitioning and clustering, when asked for
+    #[serde(default)]
+    pub layout: Option<TableLayout>,
+    /// CREATE INDEX statements to run after creating the table, when asked for
+    #[serde(default)]
+    pub indexes: Vec<IndexSuggestion>,
+}
+
+impl CSV {
+    /// CREATE TABLE statement for the given dialect, with column types matched to the
+    /// detected ones and NOT NULL on columns without empty values. Names that are reserved
+    /// words or not plain identifiers are quoted the dialect's way, or reserved ones renamed
+    /// when the options ask for it. The table can be put in a schema, made temporary, created
+    /// only if missing or dropped first. Columns not inferred yet are inferred without
+    /// storing the result.
+    pub fn to_sql_schema(
+        &self,
+        table_name: &str,
+        options: &SqlOptions,
+    ) -> Result<SqlSchema, AnalysisError> {
+        let table = options.table_reference(table_name)?;
+        let dialect = options.dialect;
+        let headers = self.headers();
+        let mut taken: HashSet<String> = headers.iter().map(|h| h.to_ascii_lowercase()).collect();
+
+        let mut columns = Vec::with_capacity(self.column_count());
+        let mut profiles = Vec::with_capacity(self.column_count());
+        let mut definitions = Vec::with_capacity(self.column_count());
+        for (index, header) in headers.iter().enumerate() {
+            let metadata = match self.column_metadata(index) {
+                Some(metadata) => metadata.clone(),
+                None => self
+                    .infer_column(index)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?,
+            };
+            let (_, values) = self
+                .get_column(index)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+            let reserved = dialect.is_reserved(header);
+            let mut name = header.to_string();
+            if reserved && options.rename_reserved {
+                name.push('_');
+                while !taken.insert(name.to_ascii_lowercase()) {
+                    name.push('_');
+                }
+            }
+            let nullable = values.is_empty() || values.iter().any(|v| v.trim().is_empty());
+            let levels = match options.level_constraints {
+                true => constraint_levels(&metadata, values),
+                false => Vec::new(),
+            };
+            let literals: Vec<String> = levels.iter().map(|l| string_literal(l)).collect();
+            let sql_type = match dialect {
+                SqlDialect::MySql if !levels.is_empty() => {
+                    format!("ENUM({})", literals.join(", "))
+                }
+                _ => dialect.column_type(&metadata),
+            };
+            let check = match dialect {
+                SqlDialect::MySql | SqlDialect::BigQuery => String::new(),
+                _ if levels.is_empty() => String::new(),
+                _ => format!(
+                    " CHECK ({} IN ({}))",
+                    dialect.identifier(&name),
+                    literals.join(", ")
+                ),
+            };
+
+            // Renamed columns note the header they had in the file
+            let original = match name != *header {
+                true => Some(*header),
+                false => self
+                    .stored_lineage(index)
+                    .and_then(|lineage| lineage.renamed_from()),
+            };
+            definitions.push((
+                format!(
+                    "    {} {}{}{}",
+                    dialect.identifier(&name),
+                    sql_type,
+                    if nullable { "" } else { " NOT NULL" },
+                    check
+                ),
+                original.map(|header| header.replace(['\r', '\n'], " ")),
+            ));
+            columns.push(SqlColumn {
+                name,
+                header: header.to_string(),
+                sql_type,
+                nullable,
+                reserved,
+                levels,
+            });
+            profiles.push((metadata, values));
+        }
+
+        let last = definitions.len().saturating_sub(1);
+        let lines: Vec<String> = definitions
+            .into_iter()
+            .enumerate()
+            .map(|(index, (definition, comment))| {
+                let separator = if index < last { "," } else { "" };
+                match comment {
+                    Some(comment) => format!("{}{} -- {}", definition, separator, comment),
+                    None => format!("{}{}", definition, separator),
+                }
+            })
+            .collect();
+        let mut create_table = format!(
+            "{} (\n{}\n)",
+            options.create_prefix(&table),
+            lines.join("\n")
+        );
+        let layout = options
+            .layout_hints
+            .then(|| table_layout(&columns, &profiles));
+        match (&layout, dialect) {
+            (Some(layout), SqlDialect::BigQuery) => {
+                create_table.push_str(&layout.bigquery_clauses(dialect));
+                create_table.push(';');
+            }
+            (Some(layout), SqlDialect::Postgres) => {
+                create_table.push(';');
+                create_table.push_str(&layout.postgres_comments(&table));
+            }
+            _ => create_table.push(';'),
+        }
+
+        let indexes = match options.index_hints && dialect != SqlDialect::BigQuery {
+            true => suggest_indexes(&columns, &profiles)
+                .into_iter()
+                .map(|(indexed, unique, reason)| {
+                    options.index_statement(table_name, &table, indexed, unique, reason)
+                })
+                .collect(),
+            false => Vec::new(),
+        };
+
+        Ok(SqlSchema {
+            dialect,
+            table_name: table_name.to_string(),
+            drop_table: options
+                .drop_existing
+                .then(|| format!("DROP TABLE IF EXISTS {};", table)),
+            table_reference: table,
+            indexes,
+            create_table,
+            columns,
+            layout,
+        })
+    }
+}
+
+// SQL string literal
+fn string_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+// Levels of a categorical column in their ordinal order, or alphabetical for nominal ones,
+// when few enough to constrain it to
+fn constraint_levels(metadata: &ColumnMetadata, values: &[String]) -> Vec<String> {
+    if metadata.data_type != DataType::Categorical {
+        return Vec::new();
+    }
+    if let Some(scale) = &metadata.ordinal {
+        return match scale.levels.len() <= MAX_CONSTRAINT_LEVELS {
+            true => scale.levels.clone(),
+            false => Vec::new(),
+        };
+    }
+    let levels: BTreeSet<&str> = values
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect();
+    match levels.len() <= MAX_CONSTRAINT_LEVELS {
+        true => levels.into_iter().map(str::to_string).collect(),
+        false => Vec::new(),
+    }
+}
+
+// Distinct values of a text column with few enough of them, relative to its values, to be
+// filtered on by equality
+fn filter_cardinality(metadata: &ColumnMetadata, values: &[String]) -> Option<usize> {
+    if !matches!(metadata.data_type, DataType::Categorical | DataType::Text) {
+        return None;
+    }
+    let present: Vec<&str> = values
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect();
+    let distinct = present.iter().collect::<HashSet<_>>().len();
+    ((2..=MAX_CLUSTER_CARDINALITY).contains(&distinct) && distinct * CLUSTER_RATIO <= present.len())
+        .then_some(distinct)
+}
+
+// Indexes worth creating, as their columns, whether they're unique and why. Filter columns
+// are paired with date columns, the equality filter leading the range one; other columns
+// get an index of their own where they look like keys, filters or dates, unless a composite
+// index already leads with them.
+fn suggest_indexes(
+    columns: &[SqlColumn],
+    profiles: &[(ColumnMetadata, &[String])],
+) -> Vec<(Vec<String>, bool, String)> {
+    let mut filters: Vec<(&SqlColumn, usize)> = columns
+        .iter()
+        .zip(profiles)
+        .filter_map(|(column, (metadata, values))| {
+            filter_cardinality(metadata, values).map(|distinct| (column, distinct))
+        })
+        .collect();
+    // The more values a filter column has, the fewer rows each value selects
+    filters.sort_by_key(|(_, distinct)| std::cmp::Reverse(*distinct));
+    let dates: Vec<&SqlColumn> = columns
+        .iter()
+        .zip(profiles)
+        .filter(|(_, (metadata, _))| {
+            metadata.data_type == DataType::Date
+                && metadata
+                    .date_stats
+                    .as_ref()
+                    .is_some_and(|s| s.span_days > 0)
+        })
+        .map(|(column, _)| column)
+        .collect();
+
+    let mut indexes = Vec::new();
+    for (filter, distinct) in &filters {
+        for date in &dates {
+            indexes.push((
+                vec![filter.name.clone(), date.name.clone()],
+                false,
+                format!(
+                    "{} ({} values) filtered by value, then {} by range",
+                    filter.name, distinct, date.name
+                ),
+            ));
+        }
+    }
+    indexes.truncate(MAX_COMPOSITE_INDEXES);
+
+    for (column, (metadata, values)) in columns.iter().zip(profiles) {
+        let covered = indexes
+            .iter()
+            .any(|(indexed, _, _)| indexed.contains(&column.name));
+        if covered {
+            continue;
+        }
+        let present: Vec<&str> = values
+            .iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .collect();
+        let distinct = present.iter().collect::<HashSet<_>>().len();
+        let key_like = matches!(
+            metadata.data_type,
+            DataType::Integer | DataType::Email | DataType::Text
+        ) && present.len() >= 2
+            && present.len() == values.len()
+            && distinct == present.len();
+        if key_like {
+            indexes.push((
+                vec![column.name.clone()],
+                true,
+                format!("every value of {} is distinct, likely a key", column.name),
+            ));
+        } else if let Some(distinct) = filter_cardinality(metadata, values) {
+            indexes.push((
+                vec![column.name.clone()],
+                false,
+                format!("{} ({} values) filtered by value", column.name, distinct),
+            ));
+        } else if dates.iter().any(|date| date.name == column.name) {
+            indexes.push((
+                vec![column.name.clone()],
+                false,
+                format!("{} filtered by range", column.name),
+            ));
+        }
+    }
+    indexes
+}
+
+// Partition and cluster columns suggested from the profile of each column
+fn table_layout(columns: &[SqlColumn], profiles: &[(ColumnMetadata, &[String])]) -> TableLayout {
+    let empty_count = |values: &[String]| {
+        values
+            .iter()
+            .filter(|value| value.trim().is_empty())
+            .count()
+    };
+
+    let partition = columns
+        .iter()
+        .zip(profiles)
+        .filter_map(|(column, (metadata, values))| {
+            let span = metadata.date_stats.as_ref()?.span_days;
+            (metadata.data_type == DataType::Date && span > 0)
+                .then(|| (column, span, empty_count(values)))
+        })
+        .min_by_key(|(_, _, empty)| *empty);
+
+    let mut clusters: Vec<(&SqlColumn, usize)> = columns
+        .iter()
+        .zip(profiles)
+        .filter_map(|(column, (metadata, values))| {
+            filter_cardinality(metadata, values).map(|distinct| (column, distinct))
+        })
+        .collect();
+    clusters.sort_by_key(|(_, distinct)| *distinct);
+
+    TableLayout {
+        partition_column: partition.map(|(column, _, _)| column.name.clone()),
+        partition_granularity: partition.map(|(_, span, _)| match span {
+            span if span > MAX_DAILY_PARTITION_DAYS => PartitionGranularity::Month,
+            _ => PartitionGranularity::Day,
+        }),
+        cluster_columns: clusters
+            .into_iter()
+            .take(MAX_CLUSTER_COLUMNS)
+            .map(|(column, _)| column.name.clone())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_words_and_quoting() {
+        assert!(SqlDialect::Postgres.is_reserved("order"));
+        assert!(SqlDialect::MySql.is_reserved("Key"));
+        assert!(!SqlDialect::Postgres.is_reserved("key"));
+        assert!(SqlDialect::MsSql.is_reserved("percent"));
+
+        assert_eq!(SqlDialect::Postgres.identifier("order"), "\"order\"");
+        assert_eq!(SqlDialect::Postgres.identifier("Sp. Atk"), "\"Sp. Atk\"");
+        assert_eq!(SqlDialect::Postgres.identifier("Name"), "\"Name\"");
+        assert_eq!(SqlDialect::MySql.identifier("Name"), "Name");
+        assert_eq!(SqlDialect::MsSql.identifier("top"), "[top]");
+        assert_eq!(SqlDialect::MsSql.quote("a]b"), "[a]]b]");
+        assert_eq!(SqlDialect::MySql.quote("a`b"), "`a``b`");
+        assert_eq!(SqlDialect::Sqlite.identifier("total"), "total");
+        assert!(SqlDialect::parse("sqlserver").is_ok());
+        assert!(SqlDialect::parse("oracle").is_err());
+    }
+
+    #[test]
+    fn test_schema_per_dialect() {
+        let data = "id,order,Sp. Atk,joined\n1,3,50.5,2024-01-15\n2,,60.25,2024-02-01";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.override_type(2, DataType::Decimal).unwrap();
+
+        let postgres = csv.to_sql_schema("stats", &SqlOptions::default()).unwrap();
+        assert_eq!(
+            postgres.create_table,
+            "CREATE TABLE stats (\n    id INT NOT NULL,\n    \"order\" INT,\n    \
+             \"Sp. Atk\" DECIMAL(4,2) NOT NULL,\n    joined DATE NOT NULL\n);"
+        );
+        assert!(postgres.columns[1].reserved);
+        assert!(postgres.columns[1].nullable);
+
+        let options = SqlOptions {
+            dialect: SqlDialect::MsSql,
+            rename_reserved: true,
+            ..SqlOptions::default()
+        };
+        let mssql = csv.to_sql_schema("stats", &options).unwrap();
+        assert_eq!(mssql.columns[1].name, "order_");
+        assert!(mssql.create_table.contains("    order_ INT, -- order\n"));
+        assert!(mssql.create_table.contains("[Sp. Atk] DECIMAL(4,2)"));
+
+        let options = SqlOptions {
+            dialect: SqlDialect::BigQuery,
+            ..SqlOptions::default()
+        };
+        let bigquery = csv.to_sql_schema("stats", &options).unwrap();
+        let types: Vec<&str> = bigquery
+            .columns
+            .iter()
+            .map(|c| c.sql_type.as_str())
+            .collect();
+        assert_eq!(types, vec!["INT64", "INT64", "FLOAT64", "DATE"]);
+        assert!(bigquery.create_table.contains("`order` INT64"));
+    }
+
+    #[test]
+    fn test_table_options() {
+        let csv = CSV::from_string("id\n1".to_string()).unwrap();
+        let options = SqlOptions {
+            schema: Some("Staging".to_string()),
+            if_not_exists: true,
+            drop_existing: true,
+            ..SqlOptions::default()
+        };
+        let postgres = csv.to_sql_schema("uploads", &options).unwrap();
+        assert_eq!(postgres.table_reference, "\"Staging\".uploads");
+        assert_eq!(
+            postgres.drop_table.as_deref(),
+            Some("DROP TABLE IF EXISTS \"Staging\".uploads;")
+        );
+        assert!(postgres
+            .create_table
+            .starts_with("CREATE TABLE IF NOT EXISTS \"Staging\".uploads (\n"));
+
+        let options = SqlOptions {
+            dialect: SqlDialect::MsSql,
+            temporary: true,
+            if_not_exists: true,
+            ..SqlOptions::default()
+        };
+        let mssql = csv.to_sql_schema("uploads", &options).unwrap();
+        assert!(mssql.create_table.starts_with(
+            "IF OBJECT_ID(N'tempdb..[#uploads]', N'U') IS NULL\nCREATE TABLE [#uploads] (\n"
+        ));
+        let options = SqlOptions {
+            dialect: SqlDialect::Sqlite,
+            temporary: true,
+            ..SqlOptions::default()
+        };
+        let sqlite = csv.to_sql_schema("uploads", &options).unwrap();
+        assert!(sqlite.create_table.starts_with("CREATE TEMP TABLE uploads"));
+
+        let temporary_in_schema = SqlOptions {
+            temporary: true,
+            schema: Some("staging".to_string()),
+            ..SqlOptions::default()
+        };
+        assert!(csv.to_sql_schema("uploads", &temporary_in_schema).is_err());
+        assert!(csv
+            .to_sql_schema(&"t".repeat(64), &SqlOptions::default())
+            .is_err());
+        assert!(csv.to_sql_schema(" ", &SqlOptions::default()).is_err());
+        let bigquery = SqlOptions {
+            dialect: SqlDialect::BigQuery,
+            ..SqlOptions::default()
+        };
+        assert!(csv.to_sql_schema("my uploads", &bigquery).is_err());
+    }
+
+    #[test]
+    fn test_level_constraints() {
+        let mut csv = CSV::from_string("size\nS\nM\nL\nM\nO'Neil".to_string()).unwrap();
+        csv.override_type(0, DataType::Categorical).unwrap();
+        let schema = |csv: &CSV, dialect| {
+            let options = SqlOptions {
+                dialect,
+                level_constraints: true,
+                ..SqlOptions::default()
+            };
+            csv.to_sql_schema("sizes", &options).unwrap()
+        };
+
+        let postgres = schema(&csv, SqlDialect::Postgres);
+        assert!(postgres
+            .create_table
+            .contains("size VARCHAR(50) NOT NULL CHECK (size IN ('L', 'M', 'O''Neil', 'S'))"));
+        assert_eq!(postgres.columns[0].levels, vec!["L", "M", "O'Neil", "S"]);
+        let mysql = schema(&csv, SqlDialect::MySql);
+        assert_eq!(mysql.columns[0].sql_type, "ENUM('L', 'M', 'O''Neil', 'S')");
+        assert!(!mysql.create_table.contains("CHECK"));
+        assert!(!schema(&csv, SqlDialect::BigQuery)
+            .create_table
+            .contains("CHECK"));
+
+        // Merged levels leave the constraint, and the sizes left keep their order
+        csv.merge_levels(0, &["O'Neil".to_string()], "M").unwrap();
+        assert_eq!(
+            schema(&csv, SqlDialect::Sqlite).columns[0].levels,
+            vec!["S", "M", "L"]
+        );
+        assert_eq!(
+            schema(&csv, SqlDialect::MySql).columns[0].sql_type,
+            "ENUM('S', 'M', 'L')"
+        );
+        assert!(!csv
+            .to_sql_schema("sizes", &SqlOptions::default())
+            .unwrap()
+            .create_table
+            .contains("CHECK"));
+    }
+
+    #[test]
+    fn test_index_hints() {
+        let mut data = String::from("id,region,status,placed,note\n");
+        for i in 0..40 {
+            data.push_str(&format!(
+                "{},{},{},2024-01-{:02},{}\n",
+                i,
+                ["north", "south", "east"][i % 3],
+                ["open", "closed"][i % 2],
+                i % 28 + 1,
+                if i % 3 == 0 { "fragile" } else { "" }
+            ));
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+
+        let options = SqlOptions {
+            index_hints: true,
+            if_not_exists: true,
+            ..SqlOptions::default()
+        };
+        let schema = csv.to_sql_schema("orders", &options).unwrap();
+        let columns: Vec<Vec<&str>> = schema
+            .indexes
+            .iter()
+            .map(|index| index.columns.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                vec!["region", "placed"],
+                vec!["status", "placed"],
+                vec!["id"]
+            ]
+        );
+        assert_eq!(
+            schema.indexes[0].statement,
+            "-- region (3 values) filtered by value, then placed by range\n\
+             CREATE INDEX IF NOT EXISTS idx_orders_region_placed ON orders (region, placed);"
+        );
+        assert!(schema.indexes[2].unique);
+
+        let bigquery = SqlOptions {
+            dialect: SqlDialect::BigQuery,
+            index_hints: true,
+            ..SqlOptions::default()
+        };
+        assert!(csv
+            .to_sql_schema("orders", &bigquery)
+            .unwrap()
+            .indexes
+            .is_empty());
+    }
+
+    #[test]
+    fn test_layout_hints() {
+        let mut data = String::from("id,region,status,placed,shipped\n");
+        for i in 0..40 {
+            data.push_str(&format!(
+                "{},{},{},2010-01-{:02},{}\n",
+                i,
+                ["north", "south", "east"][i % 3],
+                ["open", "closed"][i % 2],
+                i % 28 + 1,
+                if i % 2 == 0 { "2024-03-01" } else { "" }
+            ));
+        }
+        data.push_str("40,north,open,2024-06-30,2024-07-01\n");
+        let mut csv = CSV::from_string(data).unwrap();
+        csv.infer_column_types();
+
+        let options = SqlOptions {
+            dialect: SqlDialect::BigQuery,
+            layout_hints: true,
+            ..SqlOptions::default()
+        };
+        let bigquery = csv.to_sql_schema("orders", &options).unwrap();
+        let layout = bigquery.layout.as_ref().unwrap();
+        assert_eq!(layout.partition_column.as_deref(), Some("placed"));
+        assert_eq!(
+            layout.partition_granularity,
+            Some(PartitionGranularity::Month)
+        );
+        assert_eq!(layout.cluster_columns, vec!["status", "region"]);
+        assert!(bigquery
+            .create_table
+            .ends_with("\n)\nPARTITION BY DATE_TRUNC(placed, MONTH)\nCLUSTER BY status, region;"));
+
+        let options = SqlOptions {
+            layout_hints: true,
+            ..SqlOptions::default()
+        };
+        let postgres = csv.to_sql_schema("orders", &options).unwrap();
+        assert!(postgres.create_table.contains(
+            ");\n-- Suggested partitioning: PARTITION BY RANGE (placed), a partition per month"
+        ));
+        assert!(postgres.create_table.ends_with(
+            "-- Suggested clustering: CLUSTER orders USING an index on (status, region)"
+        ));
+        assert!(csv
+            .to_sql_schema("orders", &SqlOptions::default())
+            .unwrap()
+            .layout
+            .is_none());
+    }
+}
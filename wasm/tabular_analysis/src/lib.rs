@@ -1,5 +1,25 @@
-use wasm_bindgen::prelude::*;
+//! Type inference, profiling and cleaning for tabular data. The analysis engine is plain
+//! Rust; the `wasm` feature (on by default) adds the wasm-bindgen layer used from JS.
 
-mod csv;
-//mod parallel;
-mod types;
+pub mod analysis;
+pub mod cleaning;
+pub mod config;
+pub mod csv;
+pub mod error;
+pub mod export;
+pub mod logging;
+pub mod metrics;
+pub mod parallel;
+pub mod random;
+pub mod table;
+pub mod types;
+pub mod warning;
+
+#[cfg(feature = "wasm")]
+pub mod bindings;
+
+pub use crate::config::AnalysisConfig;
+pub use crate::csv::{ColumnMetadata, CSV};
+pub use crate::error::AnalysisError;
+pub use crate::types::DataType;
+pub use crate::warning::AnalysisWarning;
@@ -0,0 +1,210 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::RwLock;
+
+use crate::error::AnalysisError;
+
+/// Targets the engine logs under, so hosts can turn each area up or down on its own
+pub mod targets {
+    /// Reading CSV text into columns
+    pub const PARSE: &str = "tabular_analysis::parse";
+    /// Column type detection and statistics
+    pub const INFERENCE: &str = "tabular_analysis::inference";
+    /// Operations that rewrite values, recorded in the cleaning log
+    pub const CLEANING: &str = "tabular_analysis::cleaning";
+    /// Work split across threads or web workers
+    pub const WORKERS: &str = "tabular_analysis::workers";
+}
+
+/// One log message as handed to a sink
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogRecord {
+    /// "error", "warn", "info", "debug" or "trace"
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// Which messages reach the sink: a default level plus overrides for targets starting with
+/// a given prefix, the longest matching prefix winning
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    level: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        LogFilter {
+            level: LevelFilter::Warn,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    /// Parses comma-separated directives such as `"info,tabular_analysis::inference=debug"`;
+    /// a bare level sets the default and `target=level` overrides it for that target
+    pub fn parse(spec: &str) -> Result<LogFilter, AnalysisError> {
+        let level = |name: &str| {
+            name.trim().parse::<LevelFilter>().map_err(|_| {
+                AnalysisError::invalid_argument(format!("Unknown log level '{}'", name.trim()))
+            })
+        };
+
+        let mut filter = LogFilter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, name)) => filter
+                    .targets
+                    .push((target.trim().to_string(), level(name)?)),
+                None => filter.level = level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Level in effect for a target
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// Most verbose level any target is let through at
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
+
+type Sink = Rc<dyn Fn(&LogRecord)>;
+
+static FILTER: RwLock<Option<LogFilter>> = RwLock::new(None);
+
+thread_local! {
+    // Sinks may be JS functions, which can only be called on the thread that set them
+    static SINK: RefCell<Option<Sink>> = const { RefCell::new(None) };
+}
+
+struct SinkLogger;
+
+static LOGGER: SinkLogger = SinkLogger;
+
+impl Log for SinkLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        FILTER
+            .read()
+            .ok()
+            .and_then(|filter| filter.as_ref().map(|f| f.level_for(metadata.target())))
+            .is_some_and(|level| metadata.level() <= level)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Cloned out so a sink that logs again doesn't find the cell borrowed
+        let Some(sink) = SINK.with(|sink| sink.borrow().clone()) else {
+            return;
+        };
+        sink(&LogRecord {
+            level: level_name(record.level()).to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
+
+/// Sends the engine's log messages passing `filter` to `sink` on the current thread, replacing
+/// any previous sink. Does nothing if the host already installed another `log` logger.
+pub fn set_log_sink(sink: impl Fn(&LogRecord) + 'static, filter: LogFilter) {
+    if log::set_logger(&LOGGER).is_err() && !log_sink_installed() {
+        return;
+    }
+    SINK.with(|cell| *cell.borrow_mut() = Some(Rc::new(sink)));
+    set_log_filter(filter);
+}
+
+/// Changes which messages reach the sink without replacing it
+pub fn set_log_filter(filter: LogFilter) {
+    log::set_max_level(filter.max_level());
+    if let Ok(mut current) = FILTER.write() {
+        *current = Some(filter);
+    }
+}
+
+/// Stops sending log messages to the current thread's sink
+pub fn clear_log_sink() {
+    SINK.with(|cell| *cell.borrow_mut() = None);
+}
+
+// Whether the installed `log` logger is this module's, as opposed to one set by the host
+fn log_sink_installed() -> bool {
+    std::ptr::eq(
+        log::logger() as *const dyn Log as *const (),
+        &LOGGER as *const SinkLogger as *const (),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter() {
+        let filter = LogFilter::parse("info, tabular_analysis::inference=debug").unwrap();
+        assert_eq!(filter.level_for(targets::PARSE), LevelFilter::Info);
+        assert_eq!(filter.level_for(targets::INFERENCE), LevelFilter::Debug);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+
+        let filter =
+            LogFilter::parse("tabular_analysis=off,tabular_analysis::parse=trace").unwrap();
+        assert_eq!(filter.level_for(targets::CLEANING), LevelFilter::Off);
+        assert_eq!(filter.level_for(targets::PARSE), LevelFilter::Trace);
+        assert_eq!(LogFilter::parse("").unwrap(), LogFilter::default());
+
+        assert!(LogFilter::parse("loud").is_err());
+        assert!(LogFilter::parse("tabular_analysis::parse=").is_err());
+    }
+
+    #[test]
+    fn test_log_sink_receives_records() {
+        let records = Rc::new(RefCell::new(Vec::new()));
+        let captured = Rc::clone(&records);
+        set_log_sink(
+            move |record| captured.borrow_mut().push(record.clone()),
+            LogFilter::parse("warn,tabular_analysis::parse=debug").unwrap(),
+        );
+
+        crate::csv::CSV::from_string("a,b\n1\n2,3".to_string()).unwrap();
+        log::debug!(target: targets::CLEANING, "filtered out");
+        clear_log_sink();
+        log::warn!(target: targets::PARSE, "sink cleared");
+
+        let records = records.borrow();
+        assert!(records.iter().all(|r| r.target == targets::PARSE));
+        assert!(records
+            .iter()
+            .any(|r| r.level == "warn" && r.message.contains("padded")));
+        assert!(records.iter().any(|r| r.level == "debug"));
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+use crate::csv::CSV;
+use crate::parallel::ExecutionStats;
+
+/// Wall time spent in each phase of an analysis, in milliseconds
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    /// Reading the CSV text into columns, recorded when the CSV was created from text
+    pub parse_ms: f64,
+    /// Scoring candidate types and picking each column's type
+    pub inference_ms: f64,
+    /// Numeric, text, date and card statistics under the chosen types
+    pub stats_ms: f64,
+    /// Choosing SQL column types
+    pub sql_ms: f64,
+}
+
+/// Cost of the most recent `infer_column_types` run, for tuning chunk sizes and thread counts
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AnalysisMetrics {
+    pub phases: PhaseTimings,
+    /// Wall time of the whole run, parsing included
+    pub total_ms: f64,
+    pub rows: usize,
+    pub columns: usize,
+    /// Rows analyzed per second of inference, statistics and SQL typing
+    pub rows_per_second: f64,
+    /// Estimated bytes held at the peak: the table's values plus working copies of the
+    /// largest column
+    pub peak_memory_bytes: usize,
+    /// Threads or workers the columns were spread over
+    pub workers: usize,
+    /// Share of the workers' combined time spent scoring candidate types, from 0 to 1
+    pub worker_utilization: f64,
+    /// Columns whose metadata was reused from an earlier run over the same values and config
+    #[serde(default)]
+    pub cached_columns: usize,
+}
+
+impl AnalysisMetrics {
+    pub(crate) fn new(
+        phases: PhaseTimings,
+        analysis_ms: f64,
+        execution: &ExecutionStats,
+        cached_columns: usize,
+        csv: &CSV,
+    ) -> Self {
+        let rows_per_second = if analysis_ms > 0.0 {
+            csv.row_count() as f64 / (analysis_ms / 1000.0)
+        } else {
+            0.0
+        };
+        AnalysisMetrics {
+            phases,
+            total_ms: phases.parse_ms + analysis_ms,
+            rows: csv.row_count(),
+            columns: csv.column_count(),
+            rows_per_second,
+            peak_memory_bytes: estimated_peak_memory(csv),
+            workers: execution.workers,
+            worker_utilization: execution.utilization(),
+            cached_columns,
+        }
+    }
+}
+
+fn estimated_peak_memory(csv: &CSV) -> usize {
+    let column_bytes: Vec<usize> = csv
+        .get_columns()
+        .iter()
+        .map(|(header, values)| {
+            header.len()
+                + values
+                    .iter()
+                    .map(|v| size_of::<String>() + v.capacity())
+                    .sum::<usize>()
+        })
+        .collect();
+    peak_memory(&column_bytes)
+}
+
+// Bytes of every column, plus two working copies of the largest one for the trimmed and
+// sorted values statistics build
+pub(crate) fn peak_memory(column_bytes: &[usize]) -> usize {
+    column_bytes.iter().sum::<usize>() + 2 * column_bytes.iter().max().copied().unwrap_or(0)
+}
+
+/// Milliseconds since an arbitrary start, from `performance.now()` since
+/// `std::time::Instant` panics in wasm
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::JsCast;
+
+    // Available in pages, workers and Node; Date.now() is only millisecond-precise
+    Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .filter(|performance| performance.is_object())
+        .and_then(|performance| {
+            let now = Reflect::get(&performance, &"now".into()).ok()?;
+            now.dyn_ref::<Function>()?
+                .call0(&performance)
+                .ok()?
+                .as_f64()
+        })
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Milliseconds since an arbitrary start, from a monotonic clock
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub fn now_ms() -> f64 {
+    use once_cell::sync::Lazy;
+    use std::time::Instant;
+
+    static START: Lazy<Instant> = Lazy::new(Instant::now);
+    START.elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analysis_metrics() {
+        let mut data = "id,name,joined\n".to_string();
+        for i in 0..200 {
+            data.push_str(&format!("{},user{},2024-01-{:02}\n", i, i, i % 28 + 1));
+        }
+        let mut csv = CSV::from_string(data).unwrap();
+        assert!(csv.last_analysis_metrics().is_none());
+
+        csv.infer_column_types();
+        let metrics = csv.last_analysis_metrics().unwrap();
+        assert_eq!((metrics.rows, metrics.columns), (200, 3));
+        assert!(metrics.phases.parse_ms >= 0.0);
+        assert!(metrics.phases.inference_ms > 0.0);
+        let phases = metrics.phases;
+        assert!(
+            metrics.total_ms + 1e-9
+                >= phases.parse_ms + phases.inference_ms + phases.stats_ms + phases.sql_ms
+        );
+        assert!(metrics.rows_per_second > 0.0);
+        assert!(metrics.peak_memory_bytes > 200 * 3 * size_of::<String>());
+        assert!(metrics.workers >= 1);
+        assert!((0.0..=1.0).contains(&metrics.worker_utilization));
+    }
+}
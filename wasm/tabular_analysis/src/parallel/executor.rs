@@ -1,85 +1,358 @@
-use crate::parallel::{calculate_chunk_size, MIN_CHUNK_SIZE};
+use crate::metrics::now_ms;
+use crate::parallel::pool::shared_pool;
+use crate::parallel::{
+    average_value_size, calculate_chunk_size, chunk_size_for_budget, ParallelResult, ValueSize,
+    MIN_CHUNK_SIZE,
+};
 use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-//TODO: add memory efficient batching ChunkResult<T>
-//TODO: use .try_fold to process in place without adding new vectors
-//TODO: share processors between threads let processor = Arc::new(processor);
-//TODO: cache line alignment optimization more precise cunk sizing
-//  let elements_per_cache_line = cache_line_size / elem_size
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ProcessingError {
+    /// A processor failure not yet tied to a chunk
     ProcessingFailed(String),
+    /// A processor failure on the chunk starting at `offset` in `column`
+    ChunkFailed {
+        column: usize,
+        offset: usize,
+        message: String,
+    },
+    /// The executor's cancel flag was set before every chunk was processed
+    Cancelled,
+}
+
+impl ProcessingError {
+    // Locates a processor's error at its chunk, keeping a location it already has
+    fn in_chunk(self, column: usize, offset: usize) -> Self {
+        match self {
+            ProcessingError::ProcessingFailed(message) => ProcessingError::ChunkFailed {
+                column,
+                offset,
+                message,
+            },
+            located => located,
+        }
+    }
+}
+
+impl fmt::Display for ProcessingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessingError::ProcessingFailed(message) => {
+                write!(f, "Parallel processing failed: {}", message)
+            }
+            ProcessingError::ChunkFailed {
+                column,
+                offset,
+                message,
+            } => write!(
+                f,
+                "Chunk at offset {} of column {} failed: {}",
+                offset, column, message
+            ),
+            ProcessingError::Cancelled => write!(f, "Parallel processing was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessingError {}
+
+/// Result of processing one chunk, with where the chunk came from and how long it took
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkResult<R> {
+    /// Index of the column in the slice handed to the executor
+    pub column: usize,
+    /// Position of the chunk's first element in its column
+    pub offset: usize,
+    pub value: R,
+    /// Milliseconds spent in the processor
+    pub busy_ms: f64,
+}
+
+/// How a run of `process_chunks` used the workers
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExecutionStats {
+    pub workers: usize,
+    pub chunks: usize,
+    /// Wall time of the whole run
+    pub wall_ms: f64,
+    /// Time spent in the processor, summed over all chunks
+    pub busy_ms: f64,
+}
+
+impl ExecutionStats {
+    /// Share of the workers' combined time spent processing chunks, from 0 to 1
+    pub fn utilization(&self) -> f64 {
+        if self.workers == 0 || self.wall_ms <= 0.0 {
+            return 1.0;
+        }
+        (self.busy_ms / (self.workers as f64 * self.wall_ms)).min(1.0)
+    }
 }
 
 /// parallel execution engine
 pub struct ParallelExecutor {
     chunk_size: usize,
+    // Bytes of values per task; when set, each column's chunk size follows its value sizes
+    memory_budget: Option<usize>,
+    parallel: bool,
+    // The module's shared pool; None runs parallel work on rayon's global pool
+    pool: Option<Arc<ThreadPool>>,
+    // Checked before every chunk of `process_column` and `process_columns`
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for ParallelExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ParallelExecutor {
+    /// Executor running on the pool shared across analyses, started on first use
     pub fn new() -> Self {
         Self {
             chunk_size: MIN_CHUNK_SIZE,
+            memory_budget: None,
+            parallel: true,
+            pool: shared_pool(),
+            cancel: None,
         }
     }
 
-    /// process single column of data in parallel
+    /// Executor sized for columns of up to `data_len` elements of `element_size` bytes
+    pub fn for_data(data_len: usize, element_size: usize) -> Self {
+        Self::new().with_chunk_size(calculate_chunk_size(data_len, element_size))
+    }
+
+    /// Executor running every chunk on the calling thread, for hosts where blocking on
+    /// worker threads isn't allowed, like the browser's main thread
+    pub fn sequential() -> Self {
+        Self {
+            chunk_size: MIN_CHUNK_SIZE,
+            memory_budget: None,
+            parallel: false,
+            pool: None,
+            cancel: None,
+        }
+    }
+
+    /// Parallel executor on native builds. Wasm builds get a sequential one, since blocking
+    /// the browser's main thread on workers isn't allowed.
+    pub fn for_host() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Self::sequential()
+        } else {
+            Self::new()
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self.memory_budget = None;
+        self
+    }
+
+    /// Stops `process_column` and `process_columns` before their next chunk once `flag` is
+    /// set, failing with `Cancelled`; chunks already running finish first
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Sizes each column's chunks to hold about `bytes` of values, measured from the
+    /// column's average value size, so wide text columns get fewer values per task
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes.max(1));
+        self
+    }
+
+    /// Values per chunk used for `data`
+    pub fn chunk_size<T: ValueSize>(&self, data: &[T]) -> usize {
+        match self.memory_budget {
+            Some(budget) => chunk_size_for_budget(data.len(), average_value_size(data), budget),
+            None => self.chunk_size,
+        }
+    }
+
+    /// Threads chunks are spread over
+    pub fn workers(&self) -> usize {
+        match (&self.pool, self.parallel) {
+            (Some(pool), _) => pool.current_num_threads(),
+            (None, true) => rayon::current_num_threads(),
+            (None, false) => 1,
+        }
+    }
+
+    // Runs `op` inside the executor's pool, so its parallel iterators use that pool's threads
+    fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Processes a single column in parallel chunks, folding each chunk's result into
+    /// `identity()` with `combiner`. The identity may seed any number of partial results, so
+    /// combining with it must leave a value unchanged. An empty column gives `identity()`;
+    /// a failing chunk stops the rest and is reported as `ChunkFailed` in column 0.
     /// F is the function that processes each chunk
-    /// C is the function that combines results
+    /// C is the function that combines results, in any grouping but keeping chunk order
     /// T will be some datatype that there is an array of being processed
-    /// R is the Rusult tipe if there is not some failure that throws ProcessingError
-    pub fn process_column<T, R, F, C>(
+    pub fn process_column<T, R, I, F, C>(
         &self,
         data: &[T],
+        identity: I,
         processor: F,
         combiner: C,
-    ) -> Result<R, ProcessingError>
+    ) -> ParallelResult<R>
     where
-        T: Send + Sync,
+        T: ValueSize + Send + Sync,
         R: Send,
-        F: Fn(&[T]) -> R + Send + Sync,
+        I: Fn() -> R + Send + Sync,
+        F: Fn(&[T]) -> ParallelResult<R> + Send + Sync,
         C: Fn(R, R) -> R + Send + Sync,
     {
-        // split the data into chunk
-        let chunks: Vec<&[T]> = data.chunks(self.chunk_size).collect();
-        // process chunks in parallel
-        let results: Vec<R> = chunks.par_iter().map(|chunk| processor(chunk)).collect();
-        // combine results
-        let final_result = results
-            .into_iter()
-            .reduce(|a, b| combiner(a, b))
-            .ok_or_else(|| ProcessingError::ProcessingFailed("No data processed".into()))?;
-
-        Ok(final_result)
-    }
-    /// type, result, function
-    pub fn process_columns<T, R, F, C>(
+        self.reduce_column(0, data, &identity, &processor, &combiner)
+    }
+
+    /// Processes every column like `process_column`, columns in parallel too. The first
+    /// failing chunk stops all columns and is reported with its column and offset.
+    pub fn process_columns<A, T, R, I, F, C>(
         &self,
-        columns: &[Vec<T>],
+        columns: &[A],
+        identity: I,
         processor: F,
         combiner: C,
-    ) -> Result<Vec<R>, ProcessingError>
+    ) -> ParallelResult<Vec<R>>
+    where
+        A: AsRef<[T]> + Sync,
+        T: ValueSize + Send + Sync,
+        R: Send,
+        I: Fn() -> R + Send + Sync,
+        F: Fn(&[T]) -> ParallelResult<R> + Send + Sync,
+        C: Fn(R, R) -> R + Send + Sync,
+    {
+        let process = |(column, data): (usize, &A)| {
+            self.reduce_column(column, data.as_ref(), &identity, &processor, &combiner)
+        };
+        if self.parallel {
+            self.install(|| columns.par_iter().enumerate().map(process).collect())
+        } else {
+            columns.iter().enumerate().map(process).collect()
+        }
+    }
+
+    // Folds the chunks of one column as they finish, without collecting chunk results first
+    fn reduce_column<T, R, I, F, C>(
+        &self,
+        column: usize,
+        data: &[T],
+        identity: &I,
+        processor: &F,
+        combiner: &C,
+    ) -> ParallelResult<R>
+    where
+        T: ValueSize + Send + Sync,
+        R: Send,
+        I: Fn() -> R + Send + Sync,
+        F: Fn(&[T]) -> ParallelResult<R> + Send + Sync,
+        C: Fn(R, R) -> R + Send + Sync,
+    {
+        let chunk_size = self.chunk_size(data);
+        let fold = |acc: R, (i, chunk): (usize, &[T])| {
+            if self
+                .cancel
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::Relaxed))
+            {
+                return Err(ProcessingError::Cancelled);
+            }
+            let value = processor(chunk).map_err(|e| e.in_chunk(column, i * chunk_size))?;
+            Ok(combiner(acc, value))
+        };
+        if self.parallel {
+            self.install(|| {
+                data.par_chunks(chunk_size)
+                    .enumerate()
+                    .try_fold(identity, fold)
+                    .try_reduce(identity, |a, b| Ok(combiner(a, b)))
+            })
+        } else {
+            data.chunks(chunk_size)
+                .enumerate()
+                .try_fold(identity(), fold)
+        }
+    }
+
+    /// Applies `f` to every item on the executor's workers, keeping the items' order
+    pub fn map<T, R, F>(&self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Send + Sync,
+    {
+        if self.parallel {
+            self.install(|| items.par_iter().map(&f).collect())
+        } else {
+            items.iter().map(f).collect()
+        }
+    }
+
+    /// Runs `processor` on every chunk of every column, spreading the chunks of all columns
+    /// over the workers together so a few long columns still keep every worker busy.
+    /// Results come back in column order, then chunk order, each with its location, so
+    /// processors that can fail return their `Result` as `R` and every chunk is run.
+    pub fn process_chunks<A, T, R, F>(
+        &self,
+        columns: &[A],
+        processor: F,
+    ) -> (Vec<ChunkResult<R>>, ExecutionStats)
     where
-        T: Send + Sync,
+        A: AsRef<[T]> + Sync,
+        T: ValueSize + Sync,
         R: Send,
-        F: Fn(&[T]) -> R + Send + Sync + Clone,
-        C: Fn(R, R) -> R + Send + Sync + Clone,
+        F: Fn(&[T]) -> R + Send + Sync,
     {
-        // Process each column in parallel
-        let results: Vec<R> = columns
-            .par_iter()
-            .map(|column| {
-                // Process all chunks and combine their results
-                let chunks: Vec<&[T]> = column.chunks(self.chunk_size).collect();
-                chunks
-                    .par_iter()
-                    .map(|chunk| processor(chunk))
-                    .reduce(|| processor(&[]), |a, b| combiner(a, b))
+        let start = now_ms();
+        let chunks: Vec<(usize, usize, &[T])> = columns
+            .iter()
+            .enumerate()
+            .flat_map(|(column, data)| {
+                let data = data.as_ref();
+                let chunk_size = self.chunk_size(data);
+                data.chunks(chunk_size)
+                    .enumerate()
+                    .map(move |(i, chunk)| (column, i * chunk_size, chunk))
             })
             .collect();
 
-        Ok(results)
+        let run = |&(column, offset, chunk): &(usize, usize, &[T])| {
+            let chunk_start = now_ms();
+            let value = processor(chunk);
+            ChunkResult {
+                column,
+                offset,
+                value,
+                busy_ms: now_ms() - chunk_start,
+            }
+        };
+        let results: Vec<ChunkResult<R>> = if self.parallel {
+            self.install(|| chunks.par_iter().map(run).collect())
+        } else {
+            chunks.iter().map(run).collect()
+        };
+
+        let stats = ExecutionStats {
+            workers: self.workers(),
+            chunks: results.len(),
+            wall_ms: now_ms() - start,
+            busy_ms: results.iter().map(|r| r.busy_ms).sum(),
+        };
+        (results, stats)
     }
 }
 
@@ -93,10 +366,12 @@ mod tests {
         let executor = ParallelExecutor::new();
 
         // Sum all numbers
-        let processor = |chunk: &[i32]| chunk.iter().sum::<i32>();
+        let processor = |chunk: &[i32]| Ok(chunk.iter().sum::<i32>());
         let combiner = |a, b| a + b;
 
-        let result = executor.process_column(&data, processor, combiner).unwrap();
+        let result = executor
+            .process_column(&data, || 0, processor, combiner)
+            .unwrap();
         let expected: i32 = data.iter().sum();
         assert_eq!(result, expected, "Failed to process all chunks in column");
     }
@@ -111,11 +386,11 @@ mod tests {
         let executor = ParallelExecutor::new();
 
         // Process columns to get their sums
-        let processor = |chunk: &[i32]| chunk.iter().sum::<i32>();
+        let processor = |chunk: &[i32]| Ok(chunk.iter().sum::<i32>());
         let combiner = |a, b| a + b;
 
         let results = executor
-            .process_columns(&columns, processor, combiner)
+            .process_columns(&columns, || 0, processor, combiner)
             .unwrap();
 
         // Verify results
@@ -143,11 +418,11 @@ mod tests {
         let executor = ParallelExecutor::new();
 
         // Count elements in each chunk
-        let processor = |chunk: &[i32]| chunk.len();
+        let processor = |chunk: &[i32]| Ok(chunk.len());
         let combiner = |a, b| a + b;
 
         let results = executor
-            .process_columns(&columns, processor, combiner)
+            .process_columns(&columns, || 0, processor, combiner)
             .unwrap();
 
         assert_eq!(results[0], 0, "Empty column should have 0 elements");
@@ -166,11 +441,11 @@ mod tests {
         let executor = ParallelExecutor::new();
 
         // Sum each column
-        let processor = |chunk: &[i32]| chunk.iter().sum::<i32>();
+        let processor = |chunk: &[i32]| Ok(chunk.iter().sum::<i32>());
         let combiner = |a, b| a + b;
 
         let results = executor
-            .process_columns(&columns, processor, combiner)
+            .process_columns(&columns, || 0, processor, combiner)
             .unwrap();
 
         assert_eq!(results[0], 1000, "First column sum");
@@ -188,10 +463,12 @@ mod tests {
         let data: Vec<i32> = (0..test_size as i32).collect();
 
         // Sum all numbers
-        let processor = |chunk: &[i32]| chunk.iter().sum::<i32>();
+        let processor = |chunk: &[i32]| Ok(chunk.iter().sum::<i32>());
         let combiner = |a, b| a + b;
 
-        let result = executor.process_column(&data, processor, combiner).unwrap();
+        let result = executor
+            .process_column(&data, || 0, processor, combiner)
+            .unwrap();
         let expected: i32 = data.iter().sum();
 
         assert_eq!(
@@ -199,4 +476,159 @@ mod tests {
             "Failed to process column with partial chunks correctly"
         );
     }
+
+    #[test]
+    fn test_failing_chunk_stops_processing() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let data: Vec<i32> = (0..10_000).collect();
+        let fail_on_negative = |processed: &AtomicUsize, chunk: &[i32]| {
+            processed.fetch_add(1, Ordering::Relaxed);
+            if chunk.iter().any(|&v| v < 0) {
+                Err(ProcessingError::ProcessingFailed("negative value".into()))
+            } else {
+                Ok(chunk.len())
+            }
+        };
+        let combiner = |a: usize, b: usize| a + b;
+
+        let mut failing = data.clone();
+        failing[250] = -1;
+        let processed = AtomicUsize::new(0);
+        let executor = ParallelExecutor::sequential().with_chunk_size(100);
+        let result = executor.process_column(
+            &failing,
+            || 0,
+            |c| fail_on_negative(&processed, c),
+            combiner,
+        );
+        assert_eq!(
+            result,
+            Err(ProcessingError::ChunkFailed {
+                column: 0,
+                offset: 200,
+                message: "negative value".into()
+            })
+        );
+        assert_eq!(processed.load(Ordering::Relaxed), 3);
+
+        let processed = AtomicUsize::new(0);
+        let executor = ParallelExecutor::new().with_chunk_size(100);
+        let columns = vec![data, failing];
+        let error = executor
+            .process_columns(
+                &columns,
+                || 0,
+                |c| fail_on_negative(&processed, c),
+                combiner,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Chunk at offset 200 of column 1 failed: negative value"
+        );
+        assert_eq!(
+            executor.process_columns(&columns[..1], || 0, |c| Ok(c.len()), combiner),
+            Ok(vec![10_000])
+        );
+        assert_eq!(
+            executor.process_column(&[] as &[i32], || 0, |c| Ok(c.len()), combiner),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_cancelled_processing() {
+        use std::sync::atomic::AtomicUsize;
+
+        let data: Vec<i32> = (0..1000).collect();
+        let flag = Arc::new(AtomicBool::new(false));
+        let processed = AtomicUsize::new(0);
+        let executor = ParallelExecutor::sequential()
+            .with_chunk_size(100)
+            .with_cancel_flag(flag.clone());
+        let result = executor.process_column(
+            &data,
+            || 0,
+            |chunk| {
+                // Cancelled while the third chunk runs
+                if processed.fetch_add(1, Ordering::Relaxed) == 2 {
+                    flag.store(true, Ordering::Relaxed);
+                }
+                Ok(chunk.len())
+            },
+            |a, b| a + b,
+        );
+        assert_eq!(result, Err(ProcessingError::Cancelled));
+        assert_eq!(processed.load(Ordering::Relaxed), 3);
+
+        let executor = ParallelExecutor::new().with_cancel_flag(flag);
+        let columns = vec![data.clone(), data];
+        assert_eq!(
+            executor.process_columns(&columns, || 0, |c| Ok(c.len()), |a, b| a + b),
+            Err(ProcessingError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_process_chunks() {
+        let columns: Vec<Vec<i32>> = vec![(0..2500).collect(), vec![], vec![7; 10]];
+        for executor in [ParallelExecutor::new(), ParallelExecutor::sequential()] {
+            let executor = executor.with_chunk_size(1000);
+            let (results, stats) = executor.process_chunks(&columns, |chunk: &[i32]| chunk.len());
+
+            let positions: Vec<(usize, usize, usize)> = results
+                .iter()
+                .map(|r| (r.column, r.offset, r.value))
+                .collect();
+            assert_eq!(
+                positions,
+                vec![(0, 0, 1000), (0, 1000, 1000), (0, 2000, 500), (2, 0, 10)]
+            );
+            assert_eq!(stats.chunks, 4);
+            assert!(stats.workers >= 1);
+            assert!((0.0..=1.0).contains(&stats.utilization()));
+        }
+        assert_eq!(ParallelExecutor::sequential().workers(), 1);
+        assert_eq!(
+            ParallelExecutor::new().with_chunk_size(0).chunk_size(&[1]),
+            1
+        );
+    }
+
+    #[test]
+    fn test_memory_budget_chunking() {
+        let narrow: Vec<String> = (0..20_000).map(|i| i.to_string()).collect();
+        let wide: Vec<String> = (0..20_000).map(|i| format!("{:x>500}", i)).collect();
+        let executor = ParallelExecutor::new().with_memory_budget(64 * 1024);
+
+        let (narrow_size, wide_size) = (executor.chunk_size(&narrow), executor.chunk_size(&wide));
+        assert!(
+            narrow_size > 4 * wide_size,
+            "{} vs {}",
+            narrow_size,
+            wide_size
+        );
+        assert!(wide_size * average_value_size(&wide) <= 64 * 1024);
+
+        let (results, _) =
+            executor.process_chunks(&[&narrow, &wide], |chunk: &[String]| chunk.len());
+        let total = |column| {
+            results
+                .iter()
+                .filter(|r| r.column == column)
+                .map(|r| r.value)
+                .sum::<usize>()
+        };
+        assert_eq!((total(0), total(1)), (20_000, 20_000));
+        let wide_offsets: Vec<usize> = results
+            .iter()
+            .filter(|r| r.column == 1)
+            .map(|r| r.offset)
+            .collect();
+        assert_eq!(
+            wide_offsets,
+            (0..20_000).step_by(wide_size).collect::<Vec<_>>()
+        );
+    }
 }
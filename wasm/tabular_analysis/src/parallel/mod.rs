@@ -1,29 +1,50 @@
 mod executor;
+mod packed;
+mod pool;
+mod scheduler;
 mod type_detection;
+mod value_size;
 mod web_executor;
 
 // Re-export the main components that other modules will use
-pub use executor::{ChunkResult, ParallelExecutor, ProcessingError};
-//pub use type_detection::{detect_column_types, TypeDetectionProcessor};
-//pub use web_executor::{WebExecutor, WorkerMessage, WorkerPool};
+pub use executor::{ChunkResult, ExecutionStats, ParallelExecutor, ProcessingError};
+pub use packed::{pack_column, unpack_column, PackedColumnResult};
+pub use pool::{configure_pool, shutdown};
+pub use scheduler::{ColumnFailure, ColumnQueue, QueueReport, RetryPolicy};
+pub(crate) use type_detection::type_detection_executor;
+pub use type_detection::{detect_column_types, TypeDetectionProcessor};
+pub use value_size::{average_value_size, ValueSize};
+#[cfg(all(feature = "threads", target_arch = "wasm32"))]
+pub use web_executor::init_thread_pool;
 
 // Constants shared across parallel processing
 pub const MIN_CHUNK_SIZE: usize = 1024; // Minimum chunk size aligned with common CPU cache sizes
 pub const MAX_CHUNKS_PER_THREAD: usize = 4; // Maximum chunks to avoid thread overhead
 pub const OPTIMAL_CHUNK_SIZE: usize = 4096; // Default optimal chunk size for most operations
+pub const CHUNK_MEMORY_BUDGET: usize = 256 * 1024; // Bytes of values per task, about an L2 cache
+pub const MIN_BUDGETED_CHUNK_SIZE: usize = 64; // Fewest values per task however large they are
 
-pub type ParallelResult<T> = Rusult<T, ProcessingError>;
+pub type ParallelResult<T> = Result<T, ProcessingError>;
 
 #[inline]
 pub(crate) fn calculate_chunk_size(data_len: usize, element_size: usize) -> usize {
-    const CAVHE_LINE_SIZE: usize = 64;
+    const CACHE_LINE_SIZE: usize = 64;
 
-    let elements_per_cache_line = CAVHE_LINE_SIZE / element_size;
+    let elements_per_cache_line = CACHE_LINE_SIZE / element_size.max(1);
     let optimal_elements = elements_per_cache_line * MAX_CHUNKS_PER_THREAD;
 
     optimal_elements.max(MIN_CHUNK_SIZE).min(data_len)
 }
 
+/// Values per chunk so a task holds about `budget` bytes of values averaging `value_size`
+#[inline]
+pub(crate) fn chunk_size_for_budget(data_len: usize, value_size: usize, budget: usize) -> usize {
+    (budget / value_size.max(1))
+        .max(MIN_BUDGETED_CHUNK_SIZE)
+        .min(data_len)
+        .max(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,5 +61,20 @@ mod tests {
 
         // Test with large element size
         assert!(calculate_chunk_size(10000, 128) >= MIN_CHUNK_SIZE);
+
+        // Zero-sized elements don't divide by zero
+        assert_eq!(calculate_chunk_size(10000, 0), MIN_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_chunk_size_for_budget() {
+        assert_eq!(chunk_size_for_budget(100_000, 32, 32 * 1000), 1000);
+        // Large values still get a few per task, and short columns a single chunk
+        assert_eq!(
+            chunk_size_for_budget(100_000, 1 << 20, CHUNK_MEMORY_BUDGET),
+            MIN_BUDGETED_CHUNK_SIZE
+        );
+        assert_eq!(chunk_size_for_budget(10, 32, CHUNK_MEMORY_BUDGET), 10);
+        assert_eq!(chunk_size_for_budget(0, 0, CHUNK_MEMORY_BUDGET), 1);
     }
 }
@@ -0,0 +1,174 @@
+use serde::Serialize;
+
+use crate::config::AnalysisConfig;
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+
+// Bytes of each little-endian count and offset
+const WORD: usize = 4;
+
+/// Packs column values into one buffer that can be handed to another thread or transferred to
+/// a web worker whole, instead of cloning each string: the value count, then count + 1 offsets
+/// into the text, all little-endian u32, then the UTF-8 text of every value back to back.
+pub fn pack_column(values: &[String]) -> Result<Vec<u8>, AnalysisError> {
+    let text_len: usize = values.iter().map(String::len).sum();
+    let word = |n: usize| {
+        u32::try_from(n)
+            .map(u32::to_le_bytes)
+            .map_err(|_| AnalysisError::invalid_argument("Column is too large to pack"))
+    };
+
+    let mut packed = Vec::with_capacity(WORD * (values.len() + 2) + text_len);
+    packed.extend(word(values.len())?);
+    let mut offset = 0;
+    packed.extend(word(offset)?);
+    for value in values {
+        offset += value.len();
+        packed.extend(word(offset)?);
+    }
+    for value in values {
+        packed.extend_from_slice(value.as_bytes());
+    }
+    Ok(packed)
+}
+
+/// Rebuilds the values of a column packed with `pack_column`
+pub fn unpack_column(packed: &[u8]) -> Result<Vec<String>, AnalysisError> {
+    let malformed = || AnalysisError::invalid_argument("Packed column is truncated or corrupt");
+    let word = |index: usize| -> Result<usize, AnalysisError> {
+        let bytes = packed
+            .get(index * WORD..(index + 1) * WORD)
+            .ok_or_else(malformed)?;
+        Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| malformed())?) as usize)
+    };
+
+    let count = word(0)?;
+    let text_start = count
+        .checked_add(2)
+        .and_then(|words| words.checked_mul(WORD))
+        .filter(|&start| start <= packed.len())
+        .ok_or_else(malformed)?;
+    let text = &packed[text_start..];
+
+    let mut values = Vec::with_capacity(count);
+    let mut start = word(1)?;
+    for i in 0..count {
+        let end = word(i + 2)?;
+        let bytes = text.get(start..end).ok_or_else(malformed)?;
+        let value = std::str::from_utf8(bytes).map_err(|e| AnalysisError::EncodingError {
+            offset: text_start + start + e.valid_up_to(),
+        })?;
+        values.push(value.to_string());
+        start = end;
+    }
+    Ok(values)
+}
+
+/// Answer of the generated web worker to one packed column: its metadata, or why it couldn't
+/// be analyzed, with the index it was posted under so replies can arrive in any order
+#[derive(Debug, Clone, Serialize)]
+pub struct PackedColumnResult {
+    pub column: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ColumnMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AnalysisError>,
+}
+
+impl PackedColumnResult {
+    /// Analyzes column `column` of a table, packed with `pack_column`
+    pub fn analyze(
+        column: usize,
+        header: String,
+        packed: &[u8],
+        config: AnalysisConfig,
+    ) -> PackedColumnResult {
+        let (metadata, error) = match CSV::analyze_packed_column(header, packed, config) {
+            Ok(metadata) => (Some(metadata), None),
+            Err(error) => (None, Some(error)),
+        };
+        PackedColumnResult {
+            column,
+            metadata,
+            error,
+        }
+    }
+}
+
+impl CSV {
+    /// Detects the type and statistics of a single column packed with `pack_column`, e.g.
+    /// inside a worker that was sent the column by the table's owner
+    pub fn analyze_packed_column(
+        header: String,
+        packed: &[u8],
+        config: AnalysisConfig,
+    ) -> Result<ColumnMetadata, AnalysisError> {
+        config.validate()?;
+        let values = unpack_column(packed)?;
+        CSV::from_parts(vec![(header, values, None)], config)
+            .infer_column(0)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: 0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_pack_round_trip() {
+        let values: Vec<String> = ["42", "", "naïve", "日本", "a,b\n\"c\""]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        let packed = pack_column(&values).unwrap();
+        assert_eq!(packed.len(), WORD * 7 + values.concat().len());
+        assert_eq!(unpack_column(&packed).unwrap(), values);
+        assert_eq!(
+            unpack_column(&pack_column(&[]).unwrap()).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_unpack_rejects_corrupt_buffers() {
+        let packed = pack_column(&["abc".to_string(), "de".to_string()]).unwrap();
+        assert!(matches!(
+            unpack_column(&packed[..packed.len() - 1]),
+            Err(AnalysisError::InvalidArgument { .. })
+        ));
+        assert!(unpack_column(&[]).is_err());
+        assert!(unpack_column(&u32::MAX.to_le_bytes()).is_err());
+
+        let mut invalid = packed.clone();
+        let last = invalid.len() - 1;
+        invalid[last] = 0xff;
+        assert_eq!(
+            unpack_column(&invalid),
+            Err(AnalysisError::EncodingError { offset: last })
+        );
+    }
+
+    #[test]
+    fn test_analyze_packed_column() {
+        let values: Vec<String> = (0..30).map(|i| (i * 3).to_string()).collect();
+        let packed = pack_column(&values).unwrap();
+        let metadata =
+            CSV::analyze_packed_column("score".to_string(), &packed, AnalysisConfig::default())
+                .unwrap();
+        assert_eq!(metadata.name, "score");
+        assert_eq!(metadata.data_type, DataType::Integer);
+
+        let result =
+            PackedColumnResult::analyze(4, "score".to_string(), &packed, AnalysisConfig::default());
+        assert_eq!(result.column, 4);
+        assert!(result.metadata.is_some() && result.error.is_none());
+        let result =
+            PackedColumnResult::analyze(5, "bad".to_string(), &[1], AnalysisConfig::default());
+        assert!(matches!(
+            result.error,
+            Some(AnalysisError::InvalidArgument { .. })
+        ));
+    }
+}
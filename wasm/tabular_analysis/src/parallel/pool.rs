@@ -0,0 +1,89 @@
+use log::{debug, warn};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, Mutex};
+
+use crate::logging::targets;
+
+struct PoolState {
+    // None for one thread per core
+    threads: Option<usize>,
+    pool: Option<Arc<ThreadPool>>,
+}
+
+static POOL: Mutex<PoolState> = Mutex::new(PoolState {
+    threads: None,
+    pool: None,
+});
+
+/// Sets how many threads analyses run on, 0 for one per core. The current pool is dropped once
+/// its running work finishes and the next analysis starts one of the new size.
+pub fn configure_pool(threads: usize) {
+    if let Ok(mut state) = POOL.lock() {
+        state.threads = (threads > 0).then_some(threads);
+        state.pool = None;
+    }
+}
+
+/// Stops the pool's threads once their running work finishes. The next analysis starts a new
+/// pool with the configured size.
+pub fn shutdown() {
+    if let Ok(mut state) = POOL.lock() {
+        state.pool = None;
+    }
+}
+
+// Pool shared by every analysis, started on first use so its threads are already warm for the
+// next file. Wasm builds use the global pool `initThreadPool` starts, if any, instead.
+pub(crate) fn shared_pool() -> Option<Arc<ThreadPool>> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let mut state = POOL.lock().ok()?;
+    if state.pool.is_none() {
+        let mut builder =
+            ThreadPoolBuilder::new().thread_name(|i| format!("tabular-analysis-{}", i));
+        if let Some(threads) = state.threads {
+            builder = builder.num_threads(threads);
+        }
+        match builder.build() {
+            Ok(pool) => {
+                debug!(
+                    target: targets::WORKERS,
+                    "Started a pool of {} threads",
+                    pool.current_num_threads()
+                );
+                state.pool = Some(Arc::new(pool));
+            }
+            Err(e) => {
+                warn!(
+                    target: targets::WORKERS,
+                    "Falling back to the global thread pool: {}", e
+                );
+                return None;
+            }
+        }
+    }
+    state.pool.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_is_reused_until_reconfigured() {
+        let first = shared_pool().unwrap();
+        assert!(Arc::ptr_eq(&first, &shared_pool().unwrap()));
+
+        configure_pool(2);
+        let resized = shared_pool().unwrap();
+        assert_eq!(resized.current_num_threads(), 2);
+        assert!(!Arc::ptr_eq(&first, &resized));
+
+        shutdown();
+        let restarted = shared_pool().unwrap();
+        assert_eq!(restarted.current_num_threads(), 2);
+        assert!(!Arc::ptr_eq(&resized, &restarted));
+        configure_pool(0);
+    }
+}
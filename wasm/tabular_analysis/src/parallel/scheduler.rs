@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::metrics::now_ms;
+use crate::parallel::average_value_size;
+
+/// How long a worker gets per column and how often a failing column is tried
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Milliseconds before a column still out is treated as failed, 0 for no limit
+    pub timeout_ms: f64,
+    /// Tries per column, the first one included, before it is given up on
+    pub max_attempts: usize,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            timeout_ms: 30_000.0,
+            max_attempts: 2,
+        }
+    }
+}
+
+/// A column given up on after its last attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFailure {
+    pub column: usize,
+    pub attempts: usize,
+    /// Why the last attempt failed
+    pub message: String,
+}
+
+/// Outcome of a queue: the columns analyzed and the ones that failed every attempt, so the
+/// columns that did finish can be used even when some didn't
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueReport {
+    pub completed: Vec<usize>,
+    pub failed: Vec<ColumnFailure>,
+}
+
+#[derive(Debug, Clone)]
+struct Task {
+    column: usize,
+    worker: usize,
+    started_ms: f64,
+}
+
+/// Hands columns to workers as they become idle, instead of dividing them up front. The most
+/// expensive columns go first so a wide one doesn't hold up the end of the run, and at most
+/// `max_in_flight` are out at once so only that many packed copies exist at a time. Columns
+/// that fail or time out are tried again, on another worker when one is free, up to the
+/// policy's attempts.
+#[derive(Debug, Clone)]
+pub struct ColumnQueue {
+    // Cheapest first, so the next column is popped off the end
+    pending: Vec<usize>,
+    in_flight: Vec<Task>,
+    max_in_flight: usize,
+    policy: RetryPolicy,
+    attempts: Vec<usize>,
+    // Worker whose attempt at the column last failed
+    failed_on: Vec<Option<usize>>,
+    report: QueueReport,
+}
+
+impl ColumnQueue {
+    /// Queues columns with the given estimated costs, e.g. their sizes in bytes
+    pub fn new(costs: &[usize], max_in_flight: usize, policy: RetryPolicy) -> Self {
+        let mut pending: Vec<usize> = (0..costs.len()).collect();
+        // Equally expensive columns go out in index order
+        pending.sort_by_key(|&column| (costs[column], Reverse(column)));
+        ColumnQueue {
+            pending,
+            in_flight: Vec::new(),
+            max_in_flight: max_in_flight.max(1),
+            policy,
+            attempts: vec![0; costs.len()],
+            failed_on: vec![None; costs.len()],
+            report: QueueReport::default(),
+        }
+    }
+
+    /// Queues every column of a table, costed by the bytes of its values
+    pub fn for_csv(csv: &CSV, max_in_flight: usize, policy: RetryPolicy) -> Self {
+        let costs: Vec<usize> = csv
+            .get_columns()
+            .iter()
+            .map(|(_, values)| average_value_size(values) * values.len())
+            .collect();
+        ColumnQueue::new(&costs, max_in_flight, policy)
+    }
+
+    /// Next column for `worker` to analyze, or None when all are handed out or
+    /// `max_in_flight` already are. A column that failed on `worker` is left for another
+    /// worker unless it is the only one left.
+    pub fn next_column(&mut self, worker: usize) -> Option<usize> {
+        if self.in_flight.len() >= self.max_in_flight {
+            return None;
+        }
+        let position = self
+            .pending
+            .iter()
+            .rposition(|&column| self.failed_on[column] != Some(worker))
+            .or_else(|| self.pending.len().checked_sub(1))?;
+        let column = self.pending.remove(position);
+        self.attempts[column] += 1;
+        self.in_flight.push(Task {
+            column,
+            worker,
+            started_ms: now_ms(),
+        });
+        Some(column)
+    }
+
+    /// Marks a handed-out column as analyzed, making room for the next one
+    pub fn complete(&mut self, column: usize) -> Result<(), AnalysisError> {
+        self.take_in_flight(column)?;
+        self.report.completed.push(column);
+        Ok(())
+    }
+
+    /// Records a failed attempt at a column, e.g. after its worker threw. The column goes
+    /// back to the front of the queue while it has attempts left.
+    pub fn fail(&mut self, column: usize, message: &str) -> Result<(), AnalysisError> {
+        let task = self.take_in_flight(column)?;
+        self.record_failure(task, message.to_string());
+        Ok(())
+    }
+
+    /// Fails every column out for longer than the policy's timeout, returning the workers
+    /// that held them so they can be restarted
+    pub fn expire_timed_out(&mut self) -> Vec<usize> {
+        self.expire_at(now_ms())
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Columns still waiting or being analyzed
+    pub fn remaining(&self) -> usize {
+        self.pending.len() + self.in_flight.len()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Columns analyzed and given up on so far
+    pub fn report(&self) -> &QueueReport {
+        &self.report
+    }
+
+    fn expire_at(&mut self, now: f64) -> Vec<usize> {
+        if self.policy.timeout_ms <= 0.0 {
+            return Vec::new();
+        }
+        let (expired, running): (Vec<Task>, Vec<Task>) = self
+            .in_flight
+            .drain(..)
+            .partition(|task| now - task.started_ms >= self.policy.timeout_ms);
+        self.in_flight = running;
+
+        let message = format!("Timed out after {} ms", self.policy.timeout_ms);
+        expired
+            .into_iter()
+            .map(|task| {
+                let worker = task.worker;
+                self.record_failure(task, message.clone());
+                worker
+            })
+            .collect()
+    }
+
+    fn record_failure(&mut self, task: Task, message: String) {
+        let column = task.column;
+        if self.attempts[column] < self.policy.max_attempts {
+            self.failed_on[column] = Some(task.worker);
+            self.pending.push(column);
+        } else {
+            self.report.failed.push(ColumnFailure {
+                column,
+                attempts: self.attempts[column],
+                message,
+            });
+        }
+    }
+
+    fn take_in_flight(&mut self, column: usize) -> Result<Task, AnalysisError> {
+        let position = self
+            .in_flight
+            .iter()
+            .position(|task| task.column == column)
+            .ok_or_else(|| {
+                AnalysisError::invalid_column(column, "Column was not handed out to a worker")
+            })?;
+        Ok(self.in_flight.swap_remove(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_hands_out_expensive_columns_first() {
+        let mut queue = ColumnQueue::new(&[10, 500, 10, 80], 2, RetryPolicy::default());
+        assert_eq!(queue.next_column(0), Some(1));
+        assert_eq!(queue.next_column(1), Some(3));
+        assert_eq!(queue.next_column(0), None, "two already in flight");
+
+        queue.complete(3).unwrap();
+        assert_eq!(queue.next_column(1), Some(0));
+        assert!(queue.complete(2).is_err(), "not handed out yet");
+
+        queue.complete(0).unwrap();
+        queue.complete(1).unwrap();
+        assert_eq!(queue.next_column(0), Some(2));
+        assert_eq!((queue.in_flight(), queue.remaining()), (1, 1));
+        queue.complete(2).unwrap();
+        assert!(queue.is_done());
+        assert_eq!(queue.next_column(0), None);
+        assert_eq!(queue.report().completed, vec![3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_failed_columns_retry_on_another_worker() {
+        let policy = RetryPolicy {
+            timeout_ms: 0.0,
+            max_attempts: 2,
+        };
+        let mut queue = ColumnQueue::new(&[100, 10], 2, policy);
+        assert_eq!(queue.next_column(0), Some(0));
+        queue.fail(0, "worker threw").unwrap();
+
+        // Worker 0 failed column 0, so it gets column 1 and column 0 waits for worker 1
+        assert_eq!(queue.next_column(0), Some(1));
+        assert_eq!(queue.next_column(1), Some(0));
+        queue.fail(0, "worker threw again").unwrap();
+        queue.complete(1).unwrap();
+
+        assert!(queue.is_done());
+        assert_eq!(
+            queue.report(),
+            &QueueReport {
+                completed: vec![1],
+                failed: vec![ColumnFailure {
+                    column: 0,
+                    attempts: 2,
+                    message: "worker threw again".to_string()
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_timed_out_columns_expire() {
+        let policy = RetryPolicy {
+            timeout_ms: 50.0,
+            max_attempts: 1,
+        };
+        let mut queue = ColumnQueue::new(&[10, 10], 2, policy);
+        queue.next_column(3);
+        queue.next_column(4);
+        let started = queue.in_flight[0].started_ms;
+        assert!(queue.expire_at(started + 10.0).is_empty());
+
+        queue.in_flight[1].started_ms = started + 40.0;
+        assert_eq!(queue.expire_at(started + 60.0), vec![3]);
+        assert_eq!(queue.in_flight(), 1);
+        assert_eq!(queue.report().failed[0].message, "Timed out after 50 ms");
+        assert!(
+            queue.complete(0).is_err(),
+            "expired columns are no longer out"
+        );
+    }
+
+    #[test]
+    fn test_queue_for_csv() {
+        let csv = CSV::from_string(format!("id,notes\n1,{}\n2,short", "x".repeat(400))).unwrap();
+        let mut queue = ColumnQueue::for_csv(&csv, 4, RetryPolicy::default());
+        assert_eq!(queue.next_column(0), Some(1));
+        assert_eq!(queue.next_column(0), Some(0));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::config::DetectionConfig;
+use crate::parallel::{ExecutionStats, ParallelExecutor, CHUNK_MEMORY_BUDGET};
+use crate::types::locale::SynonymDictionary;
+use crate::types::type_scoring::{TypeCounts, TypeScores};
+
+/// Counts the values of one chunk matching each candidate type. Counts of a column's chunks
+/// merge into the same scores `TypeScores::from_column` gives for the whole column.
+pub struct TypeDetectionProcessor {
+    synonyms: SynonymDictionary,
+}
+
+impl TypeDetectionProcessor {
+    pub fn new(config: &DetectionConfig) -> Self {
+        TypeDetectionProcessor {
+            synonyms: config.synonyms().unwrap_or_default(),
+        }
+    }
+
+    pub fn process(&self, chunk: &[String]) -> TypeCounts {
+        TypeCounts::from_values(chunk, &self.synonyms)
+    }
+}
+
+/// Scores the candidate types of every column, with the chunks of all columns spread over
+/// the executor's workers. Header hints are left for the caller to apply.
+pub fn detect_column_types(
+    executor: &ParallelExecutor,
+    columns: &[&[String]],
+    config: &DetectionConfig,
+) -> (Vec<TypeScores>, ExecutionStats) {
+    let processor = TypeDetectionProcessor::new(config);
+    let (results, stats) = executor.process_chunks(columns, |chunk| processor.process(chunk));
+
+    let mut counts = vec![TypeCounts::default(); columns.len()];
+    for result in &results {
+        let column = &mut counts[result.column];
+        *column = std::mem::take(column).merge(&result.value);
+    }
+    let scores = counts.iter().map(TypeScores::from_counts).collect();
+    (scores, stats)
+}
+
+/// Executor sizing each column's chunks to `CHUNK_MEMORY_BUDGET`
+pub(crate) fn type_detection_executor() -> ParallelExecutor {
+    ParallelExecutor::for_host().with_memory_budget(CHUNK_MEMORY_BUDGET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_scores_match_sequential() {
+        let ids: Vec<String> = (0..3000).map(|i| i.to_string()).collect();
+        let mut mixed: Vec<String> = (0..2500)
+            .map(|i| format!("2024-01-{:02}", i % 28 + 1))
+            .collect();
+        mixed.extend(["n/a".to_string(), String::new(), "a@b.com".to_string()]);
+        let empty = vec![String::new(); 5];
+        let config = DetectionConfig::default();
+
+        let columns: Vec<&[String]> = vec![&ids, &mixed, &empty];
+        let executor = ParallelExecutor::new().with_chunk_size(700);
+        let (scores, stats) = detect_column_types(&executor, &columns, &config);
+
+        assert_eq!(scores.len(), 3);
+        for (scores, values) in scores.iter().zip(&columns) {
+            assert_eq!(scores, &TypeScores::from_column(values, &config));
+        }
+        assert_eq!(stats.chunks, 5 + 4 + 1);
+    }
+}
@@ -0,0 +1,70 @@
+use std::mem::size_of;
+
+// Values measured when estimating a column's average size, spread evenly over the column
+const SIZE_SAMPLE: usize = 1000;
+
+/// Bytes a value takes up in memory, including what it owns on the heap
+pub trait ValueSize {
+    fn value_size(&self) -> usize;
+}
+
+impl ValueSize for String {
+    fn value_size(&self) -> usize {
+        size_of::<String>() + self.capacity()
+    }
+}
+
+impl ValueSize for &str {
+    fn value_size(&self) -> usize {
+        size_of::<&str>() + self.len()
+    }
+}
+
+macro_rules! fixed_value_size {
+    ($($t:ty),*) => {
+        $(impl ValueSize for $t {
+            fn value_size(&self) -> usize {
+                size_of::<$t>()
+            }
+        })*
+    };
+}
+
+fixed_value_size!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64);
+
+impl<A: ValueSize, B: ValueSize> ValueSize for (A, B) {
+    fn value_size(&self) -> usize {
+        self.0.value_size() + self.1.value_size()
+    }
+}
+
+/// Average size of a column's values, estimated from an evenly spread sample of them
+pub fn average_value_size<T: ValueSize>(values: &[T]) -> usize {
+    if values.is_empty() {
+        return 0;
+    }
+    let step = values.len().div_ceil(SIZE_SAMPLE);
+    let (count, total) = values
+        .iter()
+        .step_by(step)
+        .fold((0, 0), |(count, total), v| {
+            (count + 1, total + v.value_size())
+        });
+    total.div_ceil(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_value_size() {
+        let short: Vec<String> = (0..5000).map(|i| (i % 10).to_string()).collect();
+        let long: Vec<String> = (0..5000).map(|i| format!("{:0>200}", i)).collect();
+        assert!(average_value_size(&short) < size_of::<String>() + 32);
+        assert!(average_value_size(&long) >= size_of::<String>() + 200);
+
+        assert_eq!(average_value_size(&[1i32, 2, 3]), 4);
+        assert_eq!(average_value_size::<f64>(&[]), 0);
+    }
+}
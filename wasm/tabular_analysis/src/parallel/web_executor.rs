@@ -0,0 +1,5 @@
+// Browsers only share wasm memory between workers when the page is cross-origin isolated and
+// the module was built with atomics; the thread pool must then be started from JS with
+// `await initThreadPool(navigator.hardwareConcurrency)` before any parallel work runs.
+#[cfg(all(feature = "threads", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::lineage::LineageStep;
+
+// Longest identifier PostgreSQL keeps; MySQL and DuckDB allow at least as long
+const MAX_IDENTIFIER_LENGTH: usize = 63;
+
+/// Case and word separation of headers made by `normalize_headers`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderStyle {
+    /// `sp_atk`
+    #[default]
+    Snake,
+    /// `SP_ATK`
+    UpperSnake,
+    /// `spAtk`
+    Camel,
+}
+
+/// A header as it was and as `normalize_headers` made it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HeaderRename {
+    pub original: String,
+    pub normalized: String,
+}
+
+// ASCII letters for accented Latin ones, so `Zürich` keeps its letters as `zurich`
+fn fold_accent(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à'..='å' => "a",
+        'À'..='Å' => "A",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'ç' => "c",
+        'Ç' => "C",
+        'è'..='ë' => "e",
+        'È'..='Ë' => "E",
+        'ì'..='ï' => "i",
+        'Ì'..='Ï' => "I",
+        'ñ' => "n",
+        'Ñ' => "N",
+        'ò'..='ö' | 'ø' => "o",
+        'Ò'..='Ö' | 'Ø' => "O",
+        'ù'..='ü' => "u",
+        'Ù'..='Ü' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' => "Y",
+        'ß' => "ss",
+        _ => return None,
+    })
+}
+
+// Words of a header: runs of ASCII letters and digits, also split where camel case starts a
+// new word, as in `SpAtk` or `HTTPCode`
+fn header_words(header: &str) -> Vec<String> {
+    let folded: String = header
+        .chars()
+        .map(|c| fold_accent(c).map_or_else(|| c.to_string(), str::to_string))
+        .collect();
+    let chars: Vec<char> = folded.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+        let starts_word = c.is_ascii_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_ascii_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_ascii_uppercase() && next_is_lower)
+            });
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c.to_ascii_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// A header as an identifier usable unquoted in SQL: ASCII letters, digits and underscores,
+/// not starting with a digit and at most 63 characters, in the given style. Headers without
+/// any letter or digit become `column_<position>`, counting from 1.
+pub fn normalize_header(header: &str, position: usize, style: HeaderStyle) -> String {
+    let mut words = header_words(header);
+    if words.is_empty() {
+        words = vec!["column".to_string(), position.to_string()];
+    }
+    let mut identifier = match style {
+        HeaderStyle::Snake => words.join("_"),
+        HeaderStyle::UpperSnake => words.join("_").to_ascii_uppercase(),
+        HeaderStyle::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| match i {
+                0 => word.clone(),
+                _ => word[..1].to_ascii_uppercase() + &word[1..],
+            })
+            .collect(),
+    };
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier.truncate(MAX_IDENTIFIER_LENGTH);
+    identifier
+}
+
+impl CSV {
+    /// Renames columns by their current header, all at once so headers can be swapped,
+    /// keeping the column order. Metadata, SQL and other exports pick up the new names, and
+    /// each renamed column's lineage records its previous header. Fails without renaming
+    /// anything when a header is unknown, a new one is empty or two columns would share one.
+    pub fn rename_columns(&mut self, renames: &[(String, String)]) -> Result<(), AnalysisError> {
+        let headers = self.headers();
+        let renames = renames
+            .iter()
+            .map(|(from, to)| {
+                let index = headers
+                    .iter()
+                    .position(|header| header == from)
+                    .ok_or_else(|| AnalysisError::UnknownColumn {
+                        header: from.clone(),
+                    })?;
+                Ok((index, to.clone()))
+            })
+            .collect::<Result<Vec<_>, AnalysisError>>()?;
+        self.rename_at(renames)
+    }
+
+    /// Renames every header to a SQL-safe identifier in the given style, e.g. `Sp. Atk` to
+    /// `sp_atk`. Headers that normalize alike are told apart by `_2`, `_3`, ... in column
+    /// order. Each renamed column's lineage keeps its original header, which SQL exports
+    /// write as a comment; the returned list maps every original header to its new one.
+    pub fn normalize_headers(
+        &mut self,
+        style: HeaderStyle,
+    ) -> Result<Vec<HeaderRename>, AnalysisError> {
+        let headers: Vec<String> = self.headers().iter().map(|h| h.to_string()).collect();
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut renames = Vec::with_capacity(headers.len());
+        for (index, header) in headers.iter().enumerate() {
+            let base = normalize_header(header, index + 1, style);
+            let mut normalized = base.clone();
+            let mut count = 1;
+            // Identifiers are compared the way SQL compares unquoted names, ignoring case
+            while !taken.insert(normalized.to_ascii_lowercase()) {
+                count += 1;
+                let suffix = match style {
+                    HeaderStyle::Camel => count.to_string(),
+                    _ => format!("_{}", count),
+                };
+                let mut end = MAX_IDENTIFIER_LENGTH
+                    .saturating_sub(suffix.len())
+                    .min(base.len());
+                while !base.is_char_boundary(end) {
+                    end -= 1;
+                }
+                normalized = format!("{}{}", &base[..end], suffix);
+            }
+            renames.push(HeaderRename {
+                original: header.clone(),
+                normalized,
+            });
+        }
+        self.rename_at(
+            renames
+                .iter()
+                .enumerate()
+                .map(|(index, rename)| (index, rename.normalized.clone()))
+                .collect(),
+        )?;
+        Ok(renames)
+    }
+
+    // Renames the columns at the given indices, checking the new headers first
+    fn rename_at(&mut self, renames: Vec<(usize, String)>) -> Result<(), AnalysisError> {
+        let current: Vec<String> = self.headers().iter().map(|h| h.to_string()).collect();
+        let mut headers = current.clone();
+        for (index, to) in &renames {
+            if to.trim().is_empty() {
+                return Err(AnalysisError::invalid_column(*index, "New header is empty"));
+            }
+            headers[*index] = to.clone();
+        }
+        for (index, _) in &renames {
+            if headers.iter().filter(|h| **h == headers[*index]).count() > 1 {
+                return Err(AnalysisError::invalid_column(
+                    *index,
+                    format!("Another column is already named '{}'", headers[*index]),
+                ));
+            }
+        }
+
+        for (index, header) in renames {
+            if header == current[index] {
+                continue;
+            }
+            let mut lineage = self.column_lineage(index)?;
+            lineage.steps.push(LineageStep {
+                operation: "rename".to_string(),
+                inputs: vec![current[index].clone()],
+                detail: Some(header.clone()),
+            });
+            self.set_column_lineage(index, lineage);
+            self.set_header(index, header);
+        }
+        Ok(())
+    }
+
+    /// Moves the columns into the given order of their current indices, which must list
+    /// every column once; metadata and lineage move with them
+    pub fn reorder_columns(&mut self, order: &[usize]) -> Result<(), AnalysisError> {
+        if let Some(&column) = order.iter().find(|&&c| c >= self.column_count()) {
+            return Err(AnalysisError::ColumnOutOfBounds { column });
+        }
+        let distinct: HashSet<usize> = order.iter().copied().collect();
+        if order.len() != self.column_count() || distinct.len() != order.len() {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Order must list each of the {} columns once",
+                self.column_count()
+            )));
+        }
+        self.permute_columns(order);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renames(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rename_columns() {
+        let data = "Cust ID,Sp. Atk,when\n1,50,2024-01-05\n2,65,2024-02-11";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.rename_columns(&renames(&[
+            ("Cust ID", "customer_id"),
+            ("Sp. Atk", "when"),
+            ("when", "sp_atk"),
+        ]))
+        .unwrap();
+        assert_eq!(csv.headers(), vec!["customer_id", "when", "sp_atk"]);
+        assert_eq!(csv.column_metadata(0).unwrap().name, "customer_id");
+        let lineage = csv.column_lineage(0).unwrap();
+        assert_eq!(lineage.sources, vec!["Cust ID"]);
+        assert_eq!(lineage.steps[0].detail.as_deref(), Some("customer_id"));
+        let sql = csv.to_duckdb_statements("t").unwrap();
+        assert!(sql.create_table.contains("\"customer_id\""));
+
+        assert!(matches!(
+            csv.rename_columns(&renames(&[("missing", "x")])),
+            Err(AnalysisError::UnknownColumn { .. })
+        ));
+        assert!(csv.rename_columns(&renames(&[("when", "sp_atk")])).is_err());
+        assert!(csv.rename_columns(&renames(&[("when", " ")])).is_err());
+        assert_eq!(csv.headers(), vec!["customer_id", "when", "sp_atk"]);
+    }
+
+    #[test]
+    fn test_normalize_header() {
+        let snake = |header| normalize_header(header, 1, HeaderStyle::Snake);
+        assert_eq!(snake("Sp. Atk"), "sp_atk");
+        assert_eq!(snake("against_fight "), "against_fight");
+        assert_eq!(snake("HTTPStatusCode"), "http_status_code");
+        assert_eq!(snake("Zürich (km²)"), "zurich_km");
+        assert_eq!(snake("2023 Q1"), "_2023_q1");
+        assert_eq!(normalize_header("%", 4, HeaderStyle::Snake), "column_4");
+        assert_eq!(
+            normalize_header("Sp. Atk", 1, HeaderStyle::UpperSnake),
+            "SP_ATK"
+        );
+        assert_eq!(
+            normalize_header("sp_atk total", 1, HeaderStyle::Camel),
+            "spAtkTotal"
+        );
+        assert_eq!(snake(&"long ".repeat(20)).len(), MAX_IDENTIFIER_LENGTH);
+    }
+
+    #[test]
+    fn test_normalize_headers() {
+        let data = "Sp. Atk,sp_atk,SP ATK,Name,id\n50,60,70,Bulbasaur,1";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        let renames = csv.normalize_headers(HeaderStyle::Snake).unwrap();
+        assert_eq!(
+            csv.headers(),
+            vec!["sp_atk", "sp_atk_2", "sp_atk_3", "name", "id"]
+        );
+        assert_eq!(renames[0].original, "Sp. Atk");
+        assert_eq!(renames[2].normalized, "sp_atk_3");
+        assert_eq!(
+            csv.column_metadata(0)
+                .unwrap()
+                .lineage
+                .as_ref()
+                .unwrap()
+                .renamed_from(),
+            Some("Sp. Atk")
+        );
+        // Already normalized headers are left alone
+        assert!(csv.column_lineage(4).unwrap().steps.is_empty());
+
+        let sql = csv.to_duckdb_statements("stats").unwrap();
+        assert!(sql.create_table.contains("\"sp_atk\" BIGINT, -- Sp. Atk\n"));
+        assert!(sql.create_table.contains("\"name\" VARCHAR, -- Name\n"));
+        assert!(sql.create_table.contains("\"id\" BIGINT\n);"));
+    }
+
+    #[test]
+    fn test_reorder_columns() {
+        let mut csv = CSV::from_string("a,b,c\n1,x,2024-01-05".to_string()).unwrap();
+        csv.infer_column_types();
+        csv.with_history("reorder_columns", |csv| csv.reorder_columns(&[2, 0, 1]))
+            .unwrap();
+        assert_eq!(csv.headers(), vec!["c", "a", "b"]);
+        assert_eq!(csv.column_metadata(1).unwrap().name, "a");
+        assert_eq!(csv.get_column(2).unwrap().1, ["x"]);
+
+        assert!(csv.reorder_columns(&[0, 1]).is_err());
+        assert!(csv.reorder_columns(&[0, 0, 1]).is_err());
+        assert!(csv.reorder_columns(&[0, 1, 3]).is_err());
+        csv.undo_operation().unwrap();
+        assert_eq!(csv.headers(), vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,666 @@
+use chrono::NaiveDate;
+use serde_json::Value as JsonValue;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+use crate::types::big_integer::{compare_integer_with_float, compare_integers};
+use crate::types::DataType;
+
+/// A cell as seen by an expression, typed from its column's inferred type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Number(f64),
+    /// A whole number beyond ±2^53 as plain digits, which a float would round
+    BigInteger(String),
+    Text(String),
+    Date(NaiveDate),
+    Bool(bool),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Number(_) => "number",
+            Value::BigInteger(_) => "big integer",
+            Value::Text(_) => "text",
+            Value::Date(_) => "date",
+            Value::Bool(_) => "boolean",
+        }
+    }
+
+    // Conditions treat null as false
+    fn is_true(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            Value::Null => Ok(false),
+            other => Err(format!("Expected a condition, found {}", other.type_name())),
+        }
+    }
+
+    /// Renders the value as a cell; null becomes an empty cell and dates ISO 8601
+    pub fn to_cell(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Number(n) => n.to_string(),
+            Value::BigInteger(s) | Value::Text(s) => s.clone(),
+            Value::Date(d) => d.format("%Y-%m-%d").to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Concat,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Remainder => "%",
+            BinaryOp::Concat => "&",
+            BinaryOp::Less => "<",
+            BinaryOp::LessOrEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterOrEqual => ">=",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::And => "and",
+            BinaryOp::Or => "or",
+        }
+    }
+}
+
+/// Parsed expression; columns are resolved to indices when parsing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Column(usize),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Indices of the columns the expression reads, in the order they first appear
+    pub fn columns(&self) -> Vec<usize> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<usize>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Column(index) => {
+                if !columns.contains(index) {
+                    columns.push(*index);
+                }
+            }
+            Expr::Negate(inner) | Expr::Not(inner) => inner.collect_columns(columns),
+            Expr::Binary(_, left, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+            Expr::If(condition, then, otherwise) => {
+                condition.collect_columns(columns);
+                then.collect_columns(columns);
+                otherwise.collect_columns(columns);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Ident(String),
+    /// A `[bracketed]` column name, for headers with spaces or symbols
+    Column(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 14] = [
+    "<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "%", "&", "(", ")",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| format!("Invalid number '{}'", text))?;
+            tokens.push(Token::Number(number));
+        } else if c == '"' || c == '\'' || c == '[' {
+            let close = if c == '[' { ']' } else { c };
+            let start = i + 1;
+            let end = chars[start..]
+                .iter()
+                .position(|&d| d == close)
+                .map(|offset| start + offset)
+                .ok_or_else(|| format!("Missing closing {}", close))?;
+            let text: String = chars[start..end].iter().collect();
+            tokens.push(if c == '[' {
+                Token::Column(text)
+            } else {
+                Token::Text(text)
+            });
+            i = end + 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c == ',' {
+            tokens.push(Token::Symbol(","));
+            i += 1;
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(*s))
+                .ok_or_else(|| format!("Unexpected character '{}'", c))?;
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+// Recursive descent parser; precedence from lowest: or, and, comparisons,
+// + - &, * / %, unary - and not
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    headers: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}'", symbol))
+        }
+    }
+
+    fn column(&self, name: &str) -> Result<Expr, String> {
+        self.headers
+            .iter()
+            .position(|header| *header == name)
+            .map(Expr::Column)
+            .ok_or_else(|| format!("Unknown column '{}'", name))
+    }
+
+    fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat_keyword("or") {
+            left = Self::binary(BinaryOp::Or, left, self.and()?);
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.eat_keyword("and") {
+            left = Self::binary(BinaryOp::And, left, self.comparison()?);
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.additive()?;
+        let ops = [
+            ("<=", BinaryOp::LessOrEqual),
+            (">=", BinaryOp::GreaterOrEqual),
+            ("==", BinaryOp::Equal),
+            ("!=", BinaryOp::NotEqual),
+            ("<", BinaryOp::Less),
+            (">", BinaryOp::Greater),
+        ];
+        for (symbol, op) in ops {
+            if self.eat_symbol(symbol) {
+                return Ok(Self::binary(op, left, self.additive()?));
+            }
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinaryOp::Add
+            } else if self.eat_symbol("-") {
+                BinaryOp::Subtract
+            } else if self.eat_symbol("&") {
+                BinaryOp::Concat
+            } else {
+                return Ok(left);
+            };
+            left = Self::binary(op, left, self.multiplicative()?);
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinaryOp::Multiply
+            } else if self.eat_symbol("/") {
+                BinaryOp::Divide
+            } else if self.eat_symbol("%") {
+                BinaryOp::Remainder
+            } else {
+                return Ok(left);
+            };
+            left = Self::binary(op, left, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_symbol("-") {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else if self.eat_keyword("not") {
+            Ok(Expr::Not(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Value::Number(n))),
+            Some(Token::Text(s)) => Ok(Expr::Literal(Value::Text(s))),
+            Some(Token::Column(name)) => self.column(&name),
+            Some(Token::Symbol("(")) => {
+                let inner = self.or()?;
+                self.expect_symbol(")")?;
+                Ok(inner)
+            }
+            Some(Token::Ident(word)) if self.peek() == Some(&Token::Symbol("(")) => {
+                self.position += 1;
+                self.call(&word)
+            }
+            Some(Token::Ident(word)) => match word.to_ascii_lowercase().as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => self.column(&word),
+            },
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+
+    fn call(&mut self, function: &str) -> Result<Expr, String> {
+        let mut args = Vec::new();
+        if !self.eat_symbol(")") {
+            loop {
+                args.push(self.or()?);
+                if self.eat_symbol(")") {
+                    break;
+                }
+                self.expect_symbol(",")?;
+            }
+        }
+
+        match (function.to_ascii_lowercase().as_str(), args.len()) {
+            ("if", 3) => {
+                let mut args = args.into_iter().map(Box::new);
+                let (Some(c), Some(a), Some(b)) = (args.next(), args.next(), args.next()) else {
+                    unreachable!("three arguments were checked");
+                };
+                Ok(Expr::If(c, a, b))
+            }
+            ("if", n) => Err(format!("if expects 3 arguments, found {}", n)),
+            _ => Err(format!("Unknown function '{}'", function)),
+        }
+    }
+}
+
+/// Parses an expression over the given headers. Columns are referenced by bare name
+/// or in `[brackets]`; `+ - * / %` work on numbers, `&` concatenates text, subtracting
+/// two dates gives the days between them and `if(condition, then, else)` picks a value.
+pub fn parse_expression(expression: &str, headers: &[&str]) -> Result<Expr, AnalysisError> {
+    parse(expression, headers)
+        .map_err(|message| AnalysisError::ExpressionError { row: None, message })
+}
+
+fn parse(expression: &str, headers: &[&str]) -> Result<Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        position: 0,
+        headers,
+    };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("Unexpected {:?}", token)),
+    }
+}
+
+// Orders a big integer against another one or a number by value, None for other values
+fn big_integer_ordering(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (Value::BigInteger(a), Value::BigInteger(b)) => Some(compare_integers(a, b)),
+        (Value::BigInteger(a), Value::Number(b)) => compare_integer_with_float(a, *b),
+        (Value::Number(a), Value::BigInteger(b)) => {
+            compare_integer_with_float(b, *a).map(std::cmp::Ordering::reverse)
+        }
+        _ => None,
+    }
+}
+
+fn evaluate_binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, String> {
+    use Value::*;
+    let big_ordering = big_integer_ordering(&left, &right);
+    let result = match (op, left, right) {
+        (BinaryOp::Concat, a, b) => Text(a.to_cell() + &b.to_cell()),
+        (BinaryOp::Equal, a, b) => Bool(big_ordering.map_or(a == b, |o| o.is_eq())),
+        (BinaryOp::NotEqual, a, b) => Bool(big_ordering.map_or(a != b, |o| o.is_ne())),
+        (BinaryOp::And, a, b) => Bool(a.is_true()? && b.is_true()?),
+        (BinaryOp::Or, a, b) => Bool(a.is_true()? || b.is_true()?),
+        // Any other operation on a missing value has no result
+        (_, Null, _) | (_, _, Null) => Null,
+        (op, Number(a), Number(b)) => match op {
+            BinaryOp::Add => Number(a + b),
+            BinaryOp::Subtract => Number(a - b),
+            BinaryOp::Multiply => Number(a * b),
+            BinaryOp::Divide if b == 0.0 => Null,
+            BinaryOp::Divide => Number(a / b),
+            BinaryOp::Remainder if b == 0.0 => Null,
+            BinaryOp::Remainder => Number(a % b),
+            op => Bool(compare(op, a.partial_cmp(&b))),
+        },
+        (op, _, _) if is_comparison(op) && big_ordering.is_some() => {
+            Bool(compare(op, big_ordering))
+        }
+        // Floats would change their last digits, so they are only compared
+        (op, BigInteger(_), _) | (op, _, BigInteger(_)) if !is_comparison(op) => {
+            return Err(format!(
+                "Cannot apply '{}' to a big integer without losing digits",
+                op.symbol()
+            ))
+        }
+        (BinaryOp::Subtract, Date(a), Date(b)) => Number((a - b).num_days() as f64),
+        (op, Date(a), Date(b)) if is_comparison(op) => Bool(compare(op, Some(a.cmp(&b)))),
+        (op, Text(a), Text(b)) if is_comparison(op) => Bool(compare(op, Some(a.cmp(&b)))),
+        (op, a, b) => {
+            return Err(format!(
+                "Cannot apply '{}' to {} and {}",
+                op.symbol(),
+                a.type_name(),
+                b.type_name()
+            ))
+        }
+    };
+    Ok(result)
+}
+
+fn is_comparison(op: BinaryOp) -> bool {
+    matches!(
+        op,
+        BinaryOp::Less | BinaryOp::LessOrEqual | BinaryOp::Greater | BinaryOp::GreaterOrEqual
+    )
+}
+
+fn compare(op: BinaryOp, ordering: Option<std::cmp::Ordering>) -> bool {
+    ordering.is_some_and(|o| match op {
+        BinaryOp::Less => o.is_lt(),
+        BinaryOp::LessOrEqual => o.is_le(),
+        BinaryOp::Greater => o.is_gt(),
+        BinaryOp::GreaterOrEqual => o.is_ge(),
+        _ => false,
+    })
+}
+
+/// Evaluates an expression against one row, given every column's values typed
+pub fn evaluate(expr: &Expr, row: &[Value]) -> Result<Value, AnalysisError> {
+    eval(expr, row).map_err(|message| AnalysisError::ExpressionError { row: None, message })
+}
+
+fn eval(expr: &Expr, row: &[Value]) -> Result<Value, String> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Column(index) => Ok(row.get(*index).cloned().unwrap_or(Value::Null)),
+        Expr::Negate(inner) => match eval(inner, row)? {
+            Value::Number(n) => Ok(Value::Number(-n)),
+            Value::Null => Ok(Value::Null),
+            other => Err(format!("Cannot negate {}", other.type_name())),
+        },
+        Expr::Not(inner) => Ok(Value::Bool(!eval(inner, row)?.is_true()?)),
+        Expr::Binary(op, left, right) => evaluate_binary(*op, eval(left, row)?, eval(right, row)?),
+        Expr::If(condition, then, otherwise) => {
+            if eval(condition, row)?.is_true()? {
+                eval(then, row)
+            } else {
+                eval(otherwise, row)
+            }
+        }
+    }
+}
+
+impl CSV {
+    // Every column's value in one row, typed from the inferred metadata
+    fn typed_row(&self, row: usize) -> Vec<Value> {
+        self.get_columns()
+            .iter()
+            .enumerate()
+            .map(|(index, (_, values))| {
+                let value = values.get(row).map_or("", |v| v.as_str());
+                let metadata = self.column_metadata(index);
+                match typed_value(value, metadata) {
+                    JsonValue::Number(n) => n.as_f64().map_or(Value::Null, Value::Number),
+                    JsonValue::String(s) if metadata.is_some_and(|m| m.big_integer.is_some()) => {
+                        Value::BigInteger(s)
+                    }
+                    JsonValue::String(s)
+                        if metadata.is_some_and(|m| m.data_type == DataType::Date) =>
+                    {
+                        NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                            .map_or(Value::Text(s), Value::Date)
+                    }
+                    JsonValue::String(s) => Value::Text(s),
+                    _ => Value::Null,
+                }
+            })
+            .collect()
+    }
+
+    /// Appends a column computed from an expression over each row, returning its index
+    pub fn push_computed_column(
+        &mut self,
+        name: &str,
+        expression: &str,
+    ) -> Result<usize, AnalysisError> {
+        let headers: Vec<&str> = self.get_columns().iter().map(|(h, _)| *h).collect();
+        let expr = parse_expression(expression, &headers)?;
+
+        let values = (0..self.row_count())
+            .map(|row| {
+                eval(&expr, &self.typed_row(row))
+                    .map(|value| value.to_cell())
+                    .map_err(|message| AnalysisError::ExpressionError {
+                        row: Some(row),
+                        message,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.push_derived_column(
+            name.to_string(),
+            values,
+            &expr.columns(),
+            "computed",
+            Some(expression.to_string()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computed(csv: &mut CSV, expression: &str) -> Result<Vec<String>, AnalysisError> {
+        let index = csv.push_computed_column("result", expression)?;
+        Ok(csv.get_column(index).unwrap().1.to_vec())
+    }
+
+    #[test]
+    fn test_computed_columns() {
+        let data = "first,last,price,qty,start,end date\n\
+                    Ada,Lovelace,2.5,4,2024-01-01,2024-01-31\n\
+                    Alan,Turing,10,,2024-02-01,2024-01-01";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let test_cases = vec![
+            ("price * qty", vec!["10", ""]),
+            ("(price + 1) * -2", vec!["-7", "-22"]),
+            ("first & \" \" & last", vec!["Ada Lovelace", "Alan Turing"]),
+            ("[end date] - start", vec!["30", "-31"]),
+            (
+                "if(price > 5 and not (qty == null), \"big\", 'small')",
+                vec!["small", "small"],
+            ),
+            ("if(start < [end date], price, 0)", vec!["2.5", "0"]),
+            ("price / 0", vec!["", ""]),
+        ];
+        for (expression, expected) in test_cases {
+            assert_eq!(
+                computed(&mut csv, expression).unwrap(),
+                expected,
+                "Failed for input: {}",
+                expression
+            );
+        }
+
+        let index = csv.push_computed_column("share", "price / 4").unwrap();
+        assert!(
+            csv.column_metadata(index).unwrap().data_type.is_numeric(),
+            "computed columns are inferred like any other"
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let mut csv = CSV::from_string("a,b\n1,x".to_string()).unwrap();
+        csv.infer_column_types();
+
+        for expression in [
+            "a +",
+            "missing * 2",
+            "a * b",
+            "if(a, 1)",
+            "sqrt(a)",
+            "a )",
+            "\"open",
+        ] {
+            assert!(
+                computed(&mut csv, expression).is_err(),
+                "Failed for input: {}",
+                expression
+            );
+        }
+        assert_eq!(csv.column_count(), 2, "failed expressions add no column");
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let data = "id,parent\n\
+                    9007199254740993,9007199254740992\n\
+                    12345678901234567890,12345678901234567890";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let test_cases = vec![
+            ("id > parent", vec!["true", "false"]),
+            ("id == parent", vec!["false", "true"]),
+            ("id > 9007199254740992", vec!["true", "true"]),
+            (
+                "if(id != parent, id, \"same\")",
+                vec!["9007199254740993", "same"],
+            ),
+        ];
+        for (expression, expected) in test_cases {
+            assert_eq!(
+                computed(&mut csv, expression).unwrap(),
+                expected,
+                "Failed for input: {}",
+                expression
+            );
+        }
+
+        let error = computed(&mut csv, "id + 1").unwrap_err().to_string();
+        assert!(error.contains("without losing digits"), "{}", error);
+    }
+}
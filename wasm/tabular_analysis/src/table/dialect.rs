@@ -0,0 +1,624 @@
+use csv::{ReaderBuilder, StringRecord};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::spill::complete_rows_end;
+use crate::cleaning::replace::compile_pattern;
+use crate::csv::generated_headers;
+use crate::error::AnalysisError;
+
+// Text inspected by `Dialect::sniff`, and at most this many of its records
+const SNIFF_BYTES: usize = 64 * 1024;
+pub(crate) const SNIFF_RECORDS: usize = 200;
+// Delimiters tried when sniffing; on ties the earlier one wins, so `||` beats `|` when
+// both split every row alike
+const DELIMITERS: &[&str] = &[",", ";", "\t", "||", "|"];
+const QUOTES: &[char] = &['"', '\''];
+
+/// How a CSV file is written: its field delimiter and quote character, whether the first
+/// row holds headers, and the decimal separator its numbers use
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Dialect {
+    /// One character, a longer string like `||`, or with `regex` a pattern like `\s*;\s*`
+    pub delimiter: String,
+    /// Reads `delimiter` as a regular expression, matched outside quoted fields
+    pub regex: bool,
+    pub quote: char,
+    /// When false, columns are named `column_1`, `column_2`, ... and the first row is data
+    pub has_headers: bool,
+    /// Preferred when a numeric column's values read either way, like `1,250`
+    pub decimal_separator: char,
+}
+
+impl Default for Dialect {
+    /// Comma separated with double quotes and a header row, as in RFC 4180
+    fn default() -> Self {
+        Dialect {
+            delimiter: ",".to_string(),
+            regex: false,
+            quote: '"',
+            has_headers: true,
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl Dialect {
+    /// Detects the dialect from the first 64 KiB of `text`. The delimiter is the candidate
+    /// splitting the most records into the same number of fields, the quote the character
+    /// wrapping fields, and the first row is taken for data only when it holds numbers
+    /// where the rows below do and nothing about it looks like a header.
+    pub fn sniff(text: &str) -> Dialect {
+        let sample = sample(text);
+        let mut best: Option<(Dialect, f64)> = None;
+        for &delimiter in DELIMITERS {
+            let quote = detect_quote(sample, delimiter);
+            let dialect = Dialect {
+                delimiter: delimiter.to_string(),
+                quote,
+                ..Dialect::default()
+            };
+            let score = consistency(&dialect.records(sample));
+            if score > 0.0 && best.as_ref().is_none_or(|(_, best)| score > *best) {
+                best = Some((dialect, score));
+            }
+        }
+
+        let mut dialect = best.map_or_else(Dialect::default, |(dialect, _)| dialect);
+        let records = dialect.records(sample);
+        dialect.has_headers = has_headers(&records);
+        dialect.decimal_separator = decimal_separator(&records);
+        dialect
+    }
+
+    /// Checks that the quote is an ASCII character and the delimiter a valid pattern or a
+    /// non-empty string, neither containing a line break nor the quote
+    pub fn validate(&self) -> Result<(), AnalysisError> {
+        if !self.quote.is_ascii() || self.quote == '\n' || self.quote == '\r' {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Quote {:?} must be an ASCII character other than a line break",
+                self.quote
+            )));
+        }
+        if self.regex {
+            self.splitter()?;
+            return Ok(());
+        }
+        if self.delimiter.is_empty() || self.delimiter.contains(['\n', '\r', self.quote]) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Delimiter {:?} must be non-empty without line breaks or the quote",
+                self.delimiter
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the fast comma splitting path can read text in this dialect
+    pub(crate) fn is_standard(&self) -> bool {
+        self.delimiter == "," && !self.regex && self.quote == '"' && self.has_headers
+    }
+
+    // The delimiter when it is one byte the csv reader can split on
+    fn byte_delimiter(&self) -> Option<u8> {
+        match self.delimiter.as_bytes() {
+            &[byte] if !self.regex => Some(byte),
+            _ => None,
+        }
+    }
+
+    // Pattern matching the delimiter; one that matches empty text would split everywhere
+    fn splitter(&self) -> Result<Regex, AnalysisError> {
+        let pattern = match self.regex {
+            true => compile_pattern(&self.delimiter)?,
+            false => compile_pattern(&regex::escape(&self.delimiter))?,
+        };
+        if pattern.is_match("") {
+            return Err(AnalysisError::InvalidPattern {
+                pattern: self.delimiter.clone(),
+                message: "Delimiter pattern must not match empty text".to_string(),
+            });
+        }
+        Ok(pattern)
+    }
+
+    /// A flexible reader for this dialect, reading the first row as headers when it has
+    /// them; only for single-byte delimiters, see `split_records`
+    pub(crate) fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(self.byte_delimiter().unwrap_or(b','))
+            .quote(self.quote as u8)
+            .has_headers(self.has_headers)
+            .flexible(true);
+        builder
+    }
+
+    /// Column names for the first record a `reader_builder` reader returns: the record
+    /// itself when the dialect has headers, `column_1`, `column_2`, ... otherwise
+    pub(crate) fn header_names(&self, first: &StringRecord) -> Vec<String> {
+        match self.has_headers {
+            true => first.iter().map(str::to_string).collect(),
+            false => generated_headers(first.len()),
+        }
+    }
+
+    /// Whether the delimiter is longer than a byte or a pattern, which the csv reader can't
+    /// split on, so text is read with `split_records`
+    pub(crate) fn needs_splitter(&self) -> bool {
+        self.byte_delimiter().is_none()
+    }
+
+    /// Fields of every record in `text`. Line breaks and delimiters inside quotes belong to
+    /// the field, doubled quotes inside quotes read as one, and empty lines are skipped as
+    /// by the csv reader.
+    pub(crate) fn split_records(&self, text: &str) -> Result<Vec<Vec<String>>, AnalysisError> {
+        let splitter = self.splitter()?;
+        let mut records = Vec::new();
+        let mut in_quotes = false;
+        let mut start = 0;
+        for (i, c) in text.char_indices() {
+            if c == self.quote {
+                in_quotes = !in_quotes;
+            } else if c == '\n' && !in_quotes {
+                self.push_record(&mut records, &text[start..i], &splitter);
+                start = i + 1;
+            }
+        }
+        self.push_record(&mut records, &text[start..], &splitter);
+        Ok(records)
+    }
+
+    // Splits one record on the delimiter matches outside quotes, i.e. after an even number
+    // of quotes since the field started
+    fn push_record(&self, records: &mut Vec<Vec<String>>, line: &str, splitter: &Regex) {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            return;
+        }
+        let mut fields = Vec::new();
+        let mut start = 0;
+        for found in splitter.find_iter(line) {
+            let field = &line[start..found.start()];
+            if found.start() < start || field.matches(self.quote).count() % 2 == 1 {
+                continue;
+            }
+            fields.push(self.unquote(field));
+            start = found.end();
+        }
+        fields.push(self.unquote(&line[start..]));
+        records.push(fields);
+    }
+
+    fn unquote(&self, field: &str) -> String {
+        let quote = self.quote.to_string();
+        match field
+            .strip_prefix(self.quote)
+            .and_then(|f| f.strip_suffix(self.quote))
+        {
+            Some(inner) => inner.replace(&quote.repeat(2), &quote),
+            None => field.to_string(),
+        }
+    }
+
+    // Fields of the sample's records, ignoring records that fail to parse
+    fn records(&self, sample: &str) -> Vec<Vec<String>> {
+        if self.needs_splitter() {
+            let mut records = self.split_records(sample).unwrap_or_default();
+            records.truncate(SNIFF_RECORDS);
+            return records;
+        }
+        let mut builder = self.reader_builder();
+        builder
+            .has_headers(false)
+            .from_reader(sample.as_bytes())
+            .records()
+            .map_while(Result::ok)
+            .take(SNIFF_RECORDS)
+            .map(|record| record.iter().map(str::to_string).collect())
+            .collect()
+    }
+
+    /// Fields of the first `count` records and the byte offset where the records after
+    /// them start; records end at line breaks outside quotes and blank lines are skipped
+    pub(crate) fn leading_records(&self, text: &str, count: usize) -> (Vec<Vec<String>>, usize) {
+        let mut in_quotes = false;
+        let mut records = 0;
+        let mut line_start = 0;
+        let mut end = text.len();
+        for (i, c) in text.char_indices() {
+            if c == self.quote {
+                in_quotes = !in_quotes;
+            } else if c == '\n' && !in_quotes {
+                if !text[line_start..i].trim().is_empty() {
+                    records += 1;
+                }
+                line_start = i + 1;
+                if records == count {
+                    end = i + 1;
+                    break;
+                }
+            }
+        }
+        (self.records(&text[..end]), end)
+    }
+}
+
+/// Compound column names from stacked header rows, like `2023_Q1_Revenue` for a column
+/// under `2023`, `Q1` and `Revenue`. A blank in an upper row continues the name to its left,
+/// as a merged spreadsheet cell would, as long as the rows above it match; parts left
+/// blank are skipped and columns without any are named `column_<n>`.
+pub fn flatten_headers(rows: &[Vec<String>]) -> Vec<String> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut filled: Vec<Vec<String>> = Vec::with_capacity(rows.len());
+    for (level, row) in rows.iter().enumerate() {
+        let mut names: Vec<String> = Vec::with_capacity(width);
+        for column in 0..width {
+            let name = row.get(column).map_or("", |name| name.trim());
+            let spans = level + 1 < rows.len()
+                && column > 0
+                && filled
+                    .iter()
+                    .all(|above| above[column] == above[column - 1]);
+            names.push(match name.is_empty() && spans {
+                true => names[column - 1].clone(),
+                false => name.to_string(),
+            });
+        }
+        filled.push(names);
+    }
+    (0..width)
+        .map(|column| {
+            let parts: Vec<&str> = filled
+                .iter()
+                .map(|row| row[column].as_str())
+                .filter(|part| !part.is_empty())
+                .collect();
+            match parts.is_empty() {
+                true => format!("column_{}", column + 1),
+                false => parts.join("_"),
+            }
+        })
+        .collect()
+}
+
+/// How to read delimited text: its dialect, the lines around the table to leave out before
+/// headers are read and types inferred, and how many rows the header spans
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ParseOptions {
+    /// Detected with `Dialect::sniff` when unset
+    pub dialect: Option<Dialect>,
+    /// Lines dropped from the start, such as report banners above the header
+    pub skip_rows: usize,
+    /// Lines starting with this, like `#`, are dropped wherever they appear outside quotes
+    pub comment_prefix: Option<String>,
+    /// Non-blank lines dropped from the end, such as totals
+    pub skip_footer_rows: usize,
+    /// Header rows stacked above the data, merged by `flatten_headers` when more than one
+    pub header_rows: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            dialect: None,
+            skip_rows: 0,
+            comment_prefix: None,
+            skip_footer_rows: 0,
+            header_rows: 1,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn validate(&self) -> Result<(), AnalysisError> {
+        if self.header_rows == 0 {
+            return Err(AnalysisError::invalid_argument(
+                "header_rows must be at least 1; read files without headers with a dialect \
+                 whose has_headers is false",
+            ));
+        }
+        if self.comment_prefix.as_deref() == Some("") {
+            return Err(AnalysisError::invalid_argument(
+                "Comment prefix must not be empty",
+            ));
+        }
+        match &self.dialect {
+            Some(dialect) => dialect.validate(),
+            None => Ok(()),
+        }
+    }
+
+    /// The text without skipped, footer and comment lines, or None when nothing is left out
+    pub(crate) fn prepare(&self, text: &str) -> Option<String> {
+        if self.skip_rows == 0 && self.skip_footer_rows == 0 && self.comment_prefix.is_none() {
+            return None;
+        }
+        let mut lines: Vec<&str> = text.split_inclusive('\n').skip(self.skip_rows).collect();
+        let mut footer = self.skip_footer_rows;
+        while footer > 0 {
+            let Some(line) = lines.pop() else {
+                break;
+            };
+            if !line.trim().is_empty() {
+                footer -= 1;
+            }
+        }
+
+        let quote = self.dialect.as_ref().map_or('"', |dialect| dialect.quote);
+        let mut kept = String::with_capacity(text.len());
+        // Lines inside a quoted field are values, whatever they start with
+        let mut in_quotes = false;
+        for line in lines {
+            let comment = self
+                .comment_prefix
+                .as_deref()
+                .is_some_and(|prefix| line.trim_start().starts_with(prefix));
+            if comment && !in_quotes {
+                continue;
+            }
+            in_quotes ^= line.matches(quote).count() % 2 == 1;
+            kept.push_str(line);
+        }
+        Some(kept)
+    }
+}
+
+// Start of the text cut after its last complete row, unless it is short enough to take whole
+fn sample(text: &str) -> &str {
+    if text.len() <= SNIFF_BYTES {
+        return text;
+    }
+    let mut end = SNIFF_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let rows_end = complete_rows_end(&text[..end]);
+    &text[..if rows_end > 0 { rows_end } else { end }]
+}
+
+// The quote character wrapping the most fields when lines are split on `delimiter`
+fn detect_quote(sample: &str, delimiter: &str) -> char {
+    let wrapped = |quote: char| {
+        sample
+            .lines()
+            .flat_map(|line| line.split(delimiter))
+            .map(str::trim)
+            .filter(|field| field.len() >= 2 && field.starts_with(quote) && field.ends_with(quote))
+            .count()
+    };
+    QUOTES
+        .iter()
+        .copied()
+        .max_by_key(|&quote| (wrapped(quote), quote == '"'))
+        .unwrap_or('"')
+}
+
+// Share of records with the most common field count, zero when that count is one field or
+// the first record, the header row if any, has another
+fn consistency(records: &[Vec<String>]) -> f64 {
+    let Some(first) = records.first() else {
+        return 0.0;
+    };
+    let width = first.len();
+    if width <= 1 {
+        return 0.0;
+    }
+    let matching = records.iter().filter(|r| r.len() == width).count();
+    let most_common = records
+        .iter()
+        .map(|r| {
+            records
+                .iter()
+                .filter(|other| other.len() == r.len())
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+    match matching == most_common {
+        true => matching as f64 / records.len() as f64,
+        false => 0.0,
+    }
+}
+
+fn is_number(value: &str) -> bool {
+    let digits = value.trim().trim_start_matches(['-', '+']);
+    digits.bytes().any(|b| b.is_ascii_digit())
+        && digits
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'.' || b == b',')
+}
+
+// Each column votes: for headers when its first value is text above numbers or has another
+// length than values that all share one, against when it is a number above numbers
+pub(crate) fn has_headers(records: &[Vec<String>]) -> bool {
+    let Some((first, rows)) = records.split_first() else {
+        return true;
+    };
+    let (mut header_votes, mut data_votes) = (0, 0);
+    for (column, value) in first.iter().enumerate() {
+        let values: Vec<&str> = rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let Some(length) = values.first().map(|v| v.chars().count()) else {
+            continue;
+        };
+        if values.iter().all(|v| is_number(v)) {
+            match is_number(value) {
+                true => data_votes += 1,
+                false => header_votes += 1,
+            }
+        } else if values.iter().all(|v| v.chars().count() == length)
+            && value.trim().chars().count() != length
+        {
+            header_votes += 1;
+        }
+    }
+    header_votes > 0 || data_votes == 0
+}
+
+// The separator before the last digits of numbers, skipping those like `1,250` that read
+// either way; a comma only when it is more common than a point
+fn decimal_separator(records: &[Vec<String>]) -> char {
+    let (mut commas, mut points) = (0, 0);
+    for value in records.iter().flatten().map(|v| v.trim()) {
+        if !is_number(value) {
+            continue;
+        }
+        let Some(position) = value.rfind(['.', ',']) else {
+            continue;
+        };
+        let decimals = &value[position + 1..];
+        let grouped = decimals.len() == 3 && value[..position].trim_start_matches('-').len() <= 3;
+        match &value[position..position + 1] {
+            "," if !grouped && !decimals.is_empty() => commas += 1,
+            "." if !decimals.is_empty() => points += 1,
+            _ => {}
+        }
+    }
+    if commas > points {
+        ','
+    } else {
+        '.'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff() {
+        let dialect = Dialect::sniff("id,name,score\n1,Ada,3.5\n2,\"Turing, Alan\",4.25\n");
+        assert_eq!(dialect, Dialect::default());
+
+        let dialect = Dialect::sniff("id;name;price\n1;Ada;3,50\n2;Alan;1.234,75\n3;Grace;7\n");
+        assert_eq!(dialect.delimiter, ";");
+        assert_eq!(dialect.decimal_separator, ',');
+        assert!(dialect.has_headers);
+
+        let dialect = Dialect::sniff("1\t'Ada, Lady'\t36\n2\t'Alan'\t41\n3\t'Grace'\t85\n");
+        assert_eq!(dialect.delimiter, "\t");
+        assert_eq!(dialect.quote, '\'');
+        assert!(!dialect.has_headers);
+
+        // Text headers over text values of one length
+        assert!(Dialect::sniff("code|kind\nAB|x\nCD|y\n").has_headers);
+        // A lone column stays comma separated
+        assert_eq!(Dialect::sniff("name\nAda\nAlan").delimiter, ",");
+        assert_eq!(Dialect::sniff("id||name\n1||Ada\n2||Alan").delimiter, "||");
+        // "1,250" could be a thousand and a quarter
+        assert_eq!(Dialect::sniff("a;b\n1,250;2\n").decimal_separator, '.');
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Dialect::default().validate().is_ok());
+        let same = Dialect {
+            quote: ',',
+            ..Dialect::default()
+        };
+        assert!(same.validate().is_err());
+        let line_break = Dialect {
+            delimiter: "\n".to_string(),
+            ..Dialect::default()
+        };
+        assert!(line_break.validate().is_err());
+        let everywhere = Dialect {
+            delimiter: "\\s*".to_string(),
+            regex: true,
+            ..Dialect::default()
+        };
+        assert!(matches!(
+            everywhere.validate(),
+            Err(AnalysisError::InvalidPattern { .. })
+        ));
+        let wide = Dialect {
+            delimiter: "→".to_string(),
+            ..Dialect::default()
+        };
+        assert!(wide.validate().is_ok());
+    }
+
+    #[test]
+    fn test_prepare() {
+        let text = "Sales report\nExported 2024-05-01\nregion,note\n# draft\nnorth,\"a\n# not a comment\"\n  # indented\nsouth,b\nTotal,2\n\n";
+        let options = ParseOptions {
+            skip_rows: 2,
+            comment_prefix: Some("#".to_string()),
+            skip_footer_rows: 1,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            options.prepare(text).unwrap(),
+            "region,note\nnorth,\"a\n# not a comment\"\nsouth,b\n"
+        );
+        assert!(ParseOptions::default().prepare(text).is_none());
+        let empty = ParseOptions {
+            comment_prefix: Some(String::new()),
+            ..ParseOptions::default()
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_flatten_headers() {
+        let rows: Vec<Vec<String>> = [
+            vec!["", "2023", "", "2024", ""],
+            vec!["", "Q1", "Q2", "Q1", ""],
+            vec!["Region", "Revenue", "Revenue", "Revenue", ""],
+        ]
+        .iter()
+        .map(|row| row.iter().map(|s| s.to_string()).collect())
+        .collect();
+        assert_eq!(
+            flatten_headers(&rows),
+            vec![
+                "Region",
+                "2023_Q1_Revenue",
+                "2023_Q2_Revenue",
+                "2024_Q1_Revenue",
+                // Continues 2024 and Q1 to the left
+                "2024_Q1",
+            ]
+        );
+
+        let dialect = Dialect::default();
+        let text = "a,\"b\nc\"\n\nx,y\n1,2\n";
+        let (records, end) = dialect.leading_records(text, 2);
+        assert_eq!(records, vec![vec!["a", "b\nc"], vec!["x", "y"]]);
+        assert_eq!(&text[end..], "1,2\n");
+    }
+
+    #[test]
+    fn test_split_records() {
+        let dialect = Dialect {
+            delimiter: "||".to_string(),
+            ..Dialect::default()
+        };
+        let records = dialect
+            .split_records("id||note\r\n1||\"a||b\"\n\n2||\"say \"\"hi\"\"\nthere\"")
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["id", "note"],
+                vec!["1", "a||b"],
+                vec!["2", "say \"hi\"\nthere"],
+            ]
+        );
+
+        let pattern = Dialect {
+            delimiter: r"\s*;\s*".to_string(),
+            regex: true,
+            ..Dialect::default()
+        };
+        assert!(pattern.needs_splitter() && !Dialect::default().needs_splitter());
+        let records = pattern.split_records("a ;b;  c").unwrap();
+        assert_eq!(records, vec![vec!["a", "b", "c"]]);
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+/// A cell whose value differs between the two versions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CellDiff {
+    pub column: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// A row present in only one of the versions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RowRef {
+    pub key: Vec<String>,
+    pub row: usize,
+}
+
+/// A row present in both versions with at least one changed cell
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RowChange {
+    pub key: Vec<String>,
+    pub old_row: usize,
+    pub new_row: usize,
+    pub cells: Vec<CellDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct DiffSummary {
+    pub added_rows: usize,
+    pub removed_rows: usize,
+    pub changed_rows: usize,
+    pub unchanged_rows: usize,
+    pub changed_cells: usize,
+}
+
+/// Differences between an old and a new version of a table
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CsvDiff {
+    /// Headers identifying a row in both versions; empty when rows are matched by position
+    pub key_columns: Vec<String>,
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub summary: DiffSummary,
+    pub added: Vec<RowRef>,
+    pub removed: Vec<RowRef>,
+    pub changed: Vec<RowChange>,
+}
+
+// Key of every row, failing on repeated keys since rows couldn't be matched unambiguously
+fn row_keys(
+    columns: &[(&str, &[String])],
+    key_indices: &[usize],
+    row_count: usize,
+) -> Result<Vec<Vec<String>>, AnalysisError> {
+    let mut seen: HashMap<Vec<String>, usize> = HashMap::new();
+    (0..row_count)
+        .map(|row| {
+            let key: Vec<String> = if key_indices.is_empty() {
+                vec![row.to_string()]
+            } else {
+                key_indices
+                    .iter()
+                    .map(|&i| columns[i].1.get(row).cloned().unwrap_or_default())
+                    .collect()
+            };
+            if seen.insert(key.clone(), row).is_some() {
+                return Err(AnalysisError::DuplicateKey { row, key });
+            }
+            Ok(key)
+        })
+        .collect()
+}
+
+/// Compares two tables, matching rows on the key columns (by header) and cells on the
+/// columns both tables share
+pub fn diff_tables(
+    old: &[(&str, &[String])],
+    new: &[(&str, &[String])],
+    key_columns: &[usize],
+    old_row_count: usize,
+    new_row_count: usize,
+) -> Result<CsvDiff, AnalysisError> {
+    let new_index = |header: &str| new.iter().position(|(h, _)| *h == header);
+
+    let mut new_keys = Vec::new();
+    for &index in key_columns {
+        let (header, _) = old
+            .get(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        new_keys.push(
+            new_index(header).ok_or_else(|| AnalysisError::UnknownColumn {
+                header: header.to_string(),
+            })?,
+        );
+    }
+
+    // Columns present in both versions, as (header, old index, new index)
+    let shared: Vec<(&str, usize, usize)> = old
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (header, _))| new_index(header).map(|j| (*header, i, j)))
+        .collect();
+    let added_columns = new
+        .iter()
+        .filter(|(header, _)| !old.iter().any(|(h, _)| h == header))
+        .map(|(header, _)| header.to_string())
+        .collect();
+    let removed_columns = old
+        .iter()
+        .filter(|(header, _)| new_index(header).is_none())
+        .map(|(header, _)| header.to_string())
+        .collect();
+
+    let old_row_keys = row_keys(old, key_columns, old_row_count)?;
+    let new_row_keys = row_keys(new, &new_keys, new_row_count)?;
+    let new_rows: HashMap<&Vec<String>, usize> = new_row_keys
+        .iter()
+        .enumerate()
+        .map(|(row, key)| (key, row))
+        .collect();
+
+    let cell = |column: &[String], row: usize| column.get(row).cloned().unwrap_or_default();
+    let mut diff = CsvDiff {
+        key_columns: key_columns.iter().map(|&i| old[i].0.to_string()).collect(),
+        added_columns,
+        removed_columns,
+        summary: DiffSummary::default(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+
+    let mut matched = vec![false; new_row_count];
+    for (old_row, key) in old_row_keys.iter().enumerate() {
+        let Some(&new_row) = new_rows.get(key) else {
+            diff.removed.push(RowRef {
+                key: key.clone(),
+                row: old_row,
+            });
+            continue;
+        };
+        matched[new_row] = true;
+
+        let cells: Vec<CellDiff> = shared
+            .iter()
+            .filter_map(|&(header, i, j)| {
+                let old_value = cell(old[i].1, old_row);
+                let new_value = cell(new[j].1, new_row);
+                (old_value != new_value).then(|| CellDiff {
+                    column: header.to_string(),
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect();
+        if cells.is_empty() {
+            diff.summary.unchanged_rows += 1;
+        } else {
+            diff.summary.changed_cells += cells.len();
+            diff.changed.push(RowChange {
+                key: key.clone(),
+                old_row,
+                new_row,
+                cells,
+            });
+        }
+    }
+    diff.added = new_row_keys
+        .into_iter()
+        .enumerate()
+        .filter(|(row, _)| !matched[*row])
+        .map(|(row, key)| RowRef { key, row })
+        .collect();
+
+    diff.summary.added_rows = diff.added.len();
+    diff.summary.removed_rows = diff.removed.len();
+    diff.summary.changed_rows = diff.changed.len();
+    Ok(diff)
+}
+
+impl CSV {
+    /// Compares this CSV, as the old version, with `other`, matching rows on the key columns or by
+    /// position when none are given
+    pub fn diff_against(
+        &self,
+        other: &CSV,
+        key_columns: &[usize],
+    ) -> Result<CsvDiff, AnalysisError> {
+        diff_tables(
+            &self.get_columns(),
+            &other.get_columns(),
+            key_columns,
+            self.row_count(),
+            other.row_count(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyed_diff() {
+        let old = CSV::from_string(
+            "id,name,city\n1,Ada,London\n2,Alan,Wilmslow\n3,Grace,NYC".to_string(),
+        )
+        .unwrap();
+        let new = CSV::from_string(
+            "id,name,email\n2,Alan,alan@example.com\n1,Ada,\n4,Edsger,\n3,Grace Hopper,"
+                .to_string(),
+        )
+        .unwrap();
+        let diff = old.diff_against(&new, &[0]).unwrap();
+
+        assert_eq!(diff.key_columns, vec!["id"]);
+        assert_eq!(diff.added_columns, vec!["email"]);
+        assert_eq!(diff.removed_columns, vec!["city"]);
+        assert_eq!(
+            diff.summary,
+            DiffSummary {
+                added_rows: 1,
+                removed_rows: 0,
+                changed_rows: 1,
+                unchanged_rows: 2,
+                changed_cells: 1,
+            }
+        );
+        assert_eq!(
+            diff.added,
+            vec![RowRef {
+                key: vec!["4".to_string()],
+                row: 2
+            }]
+        );
+        assert_eq!(
+            diff.changed[0],
+            RowChange {
+                key: vec!["3".to_string()],
+                old_row: 2,
+                new_row: 3,
+                cells: vec![CellDiff {
+                    column: "name".to_string(),
+                    old_value: "Grace".to_string(),
+                    new_value: "Grace Hopper".to_string(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_positional_diff_and_errors() {
+        let old = CSV::from_string("a\n1\n2\n3".to_string()).unwrap();
+        let new = CSV::from_string("a\n1\n5".to_string()).unwrap();
+        let diff = old.diff_against(&new, &[]).unwrap();
+        assert_eq!(diff.summary.changed_rows, 1);
+        assert_eq!(diff.removed[0].row, 2);
+
+        assert!(old.diff_against(&new, &[3]).is_err());
+        let renamed = CSV::from_string("b\n1".to_string()).unwrap();
+        assert!(old.diff_against(&renamed, &[0]).is_err());
+        let repeated = CSV::from_string("a\n1\n1".to_string()).unwrap();
+        assert!(old.diff_against(&repeated, &[0]).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::AnalysisConfig;
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::dialect::{has_headers, SNIFF_RECORDS};
+
+/// Character positions of one column in a fixed-width file, from `start` up to `end`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FixedWidthColumn {
+    /// Taken from the header line when empty, or `column_<n>` without one
+    #[serde(default)]
+    pub name: String,
+    pub start: usize,
+    /// Up to the end of the line when unset
+    #[serde(default)]
+    pub end: Option<usize>,
+}
+
+/// Where the columns of a fixed-width file are and whether its first line names them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FixedWidthLayout {
+    pub columns: Vec<FixedWidthColumn>,
+    #[serde(default)]
+    pub has_headers: bool,
+}
+
+// Byte offset of every character of a line, and of its end
+fn char_offsets(line: &str) -> Vec<usize> {
+    line.char_indices()
+        .map(|(offset, _)| offset)
+        .chain([line.len()])
+        .collect()
+}
+
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().filter(|line| !line.trim().is_empty())
+}
+
+impl FixedWidthLayout {
+    /// Detects the columns from the first lines of `text`: each column starts where a run of
+    /// positions holding text in some line begins, after positions that are blank in every
+    /// line, and runs up to the next one. The first line names the columns when it looks
+    /// like a header, as for delimited files.
+    pub fn detect(text: &str) -> FixedWidthLayout {
+        let sample: Vec<&str> = lines(text).take(SNIFF_RECORDS).collect();
+        let mut filled: Vec<bool> = Vec::new();
+        for line in &sample {
+            for (position, c) in line.chars().enumerate() {
+                if position >= filled.len() {
+                    filled.resize(position + 1, false);
+                }
+                filled[position] |= !c.is_whitespace();
+            }
+        }
+        let starts: Vec<usize> = (0..filled.len())
+            .filter(|&p| filled[p] && (p == 0 || !filled[p - 1]))
+            .collect();
+        let columns = starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| FixedWidthColumn {
+                name: String::new(),
+                start,
+                end: starts.get(i + 1).copied(),
+            })
+            .collect();
+
+        let mut layout = FixedWidthLayout {
+            columns,
+            has_headers: false,
+        };
+        let records: Vec<Vec<String>> = sample.iter().map(|line| layout.split(line)).collect();
+        layout.has_headers = records.len() > 1 && has_headers(&records);
+        layout
+    }
+
+    /// Checks that there are columns and that each starts before it ends
+    pub fn validate(&self) -> Result<(), AnalysisError> {
+        if self.columns.is_empty() {
+            return Err(AnalysisError::invalid_argument(
+                "A fixed-width layout needs at least one column",
+            ));
+        }
+        for column in &self.columns {
+            if let Some(end) = column.end.filter(|&end| end <= column.start) {
+                return Err(AnalysisError::invalid_argument(format!(
+                    "Column '{}' starts at {} but ends at {}",
+                    column.name, column.start, end
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Trimmed text of every column in a line; columns past its end are empty
+    fn split(&self, line: &str) -> Vec<String> {
+        let offsets = char_offsets(line);
+        let at = |position: usize| offsets.get(position).copied().unwrap_or(line.len());
+        self.columns
+            .iter()
+            .map(|column| {
+                let end = column.end.map_or(line.len(), at);
+                line[at(column.start).min(end)..end].trim().to_string()
+            })
+            .collect()
+    }
+}
+
+impl CSV {
+    /// Reads a fixed-width file, such as a mainframe export, into columns laid out by
+    /// `layout`, with values trimmed of their padding. Blank lines are skipped and text past
+    /// the last column is ignored.
+    pub fn from_fixed_width(text: &str, layout: &FixedWidthLayout) -> Result<CSV, AnalysisError> {
+        layout.validate()?;
+        let mut lines = lines(text);
+        let header_line = match layout.has_headers {
+            true => lines.next().map(|line| layout.split(line)),
+            false => None,
+        };
+        let mut values = vec![Vec::new(); layout.columns.len()];
+        for line in lines {
+            for (column, value) in values.iter_mut().zip(layout.split(line)) {
+                column.push(value);
+            }
+        }
+
+        let columns = layout
+            .columns
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (column, values))| {
+                let header = match (&column.name, &header_line) {
+                    (name, _) if !name.is_empty() => name.clone(),
+                    (_, Some(header_line)) if !header_line[i].is_empty() => header_line[i].clone(),
+                    _ => format!("column_{}", i + 1),
+                };
+                (header, values, None)
+            })
+            .collect();
+        Ok(CSV::from_parts(columns, AnalysisConfig::default()))
+    }
+
+    /// Reads a fixed-width file with the layout `FixedWidthLayout::detect` finds
+    pub fn from_fixed_width_detected(text: &str) -> Result<CSV, AnalysisError> {
+        CSV::from_fixed_width(text, &FixedWidthLayout::detect(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    const EXPORT: &str = "\
+ID    NAME          JOINED        BALANCE
+ 1    Ada Byron     2024-01-05      12.50
+ 2    Alan          2024-02-11     100.00
+
+10    Grace Hopper  2024-03-30    1250.75
+";
+
+    #[test]
+    fn test_detected_layout() {
+        let layout = FixedWidthLayout::detect(EXPORT);
+        assert!(layout.has_headers);
+        let starts: Vec<usize> = layout.columns.iter().map(|c| c.start).collect();
+        assert_eq!(starts, vec![0, 6, 20, 34]);
+
+        let mut csv = CSV::from_fixed_width_detected(EXPORT).unwrap();
+        assert_eq!(csv.headers(), vec!["ID", "NAME", "JOINED", "BALANCE"]);
+        assert_eq!(csv.row_count(), 3);
+        assert_eq!(csv.get_column(1).unwrap().1[2], "Grace Hopper");
+        csv.infer_column_types();
+        assert_eq!(csv.column_metadata(0).unwrap().data_type, DataType::Integer);
+        assert_eq!(csv.column_metadata(2).unwrap().data_type, DataType::Date);
+        assert!(csv.column_metadata(3).unwrap().data_type.is_numeric());
+    }
+
+    #[test]
+    fn test_given_layout() {
+        let layout = FixedWidthLayout {
+            columns: vec![
+                FixedWidthColumn {
+                    name: "code".to_string(),
+                    start: 0,
+                    end: Some(3),
+                },
+                FixedWidthColumn {
+                    name: String::new(),
+                    start: 3,
+                    end: Some(8),
+                },
+            ],
+            has_headers: false,
+        };
+        let csv = CSV::from_fixed_width("A01Zürich\nB2 Bern\nC3", &layout).unwrap();
+        assert_eq!(csv.headers(), vec!["code", "column_2"]);
+        // Positions count characters, not bytes
+        assert_eq!(csv.get_column(1).unwrap().1, ["Züric", "Bern", ""]);
+
+        let empty = FixedWidthLayout {
+            columns: Vec::new(),
+            has_headers: false,
+        };
+        assert!(CSV::from_fixed_width("abc", &empty).is_err());
+    }
+}
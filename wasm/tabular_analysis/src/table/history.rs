@@ -0,0 +1,257 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::cleaning::audit::CleaningStep;
+use crate::csv::{Snapshot, CSV};
+use crate::error::AnalysisError;
+use crate::logging::targets;
+
+// Oldest operations are forgotten beyond this many undo steps
+const MAX_HISTORY: usize = 100;
+
+// Table state before an operation, plus where the cleaning log ended at that point
+#[derive(Debug, Clone)]
+struct UndoEntry {
+    operation: String,
+    before: Snapshot,
+    log_len: usize,
+}
+
+// Table state after an undone operation, plus the cleaning steps it had recorded
+#[derive(Debug, Clone)]
+struct RedoEntry {
+    operation: String,
+    after: Snapshot,
+    steps: Vec<CleaningStep>,
+}
+
+/// Undo and redo stacks of the operations that changed a CSV. Snapshots share column
+/// values with the table, so only the columns an operation rewrites are ever copied.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<UndoEntry>,
+    redo: Vec<RedoEntry>,
+}
+
+// Undo entries as operation, table before it and cleaning log length, and redo entries as
+// operation, table after it and the cleaning steps it recorded, as kept in a saved session
+pub(crate) type UndoParts = (String, Snapshot, usize);
+pub(crate) type RedoParts = (String, Snapshot, Vec<CleaningStep>);
+
+impl History {
+    // Internal helpers taking the stacks apart and putting them back together for sessions
+    pub(crate) fn parts(&self) -> (Vec<UndoParts>, Vec<RedoParts>) {
+        let undo = self
+            .undo
+            .iter()
+            .map(|e| (e.operation.clone(), e.before.clone(), e.log_len))
+            .collect();
+        let redo = self
+            .redo
+            .iter()
+            .map(|e| (e.operation.clone(), e.after.clone(), e.steps.clone()))
+            .collect();
+        (undo, redo)
+    }
+
+    pub(crate) fn from_parts(undo: Vec<UndoParts>, redo: Vec<RedoParts>) -> History {
+        History {
+            undo: undo
+                .into_iter()
+                .map(|(operation, before, log_len)| UndoEntry {
+                    operation,
+                    before,
+                    log_len,
+                })
+                .collect(),
+            redo: redo
+                .into_iter()
+                .map(|(operation, after, steps)| RedoEntry {
+                    operation,
+                    after,
+                    steps,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Operation names in the order they can be undone and redone
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistoryReport {
+    /// Applied operations, oldest first; the last one is undone next
+    pub undo: Vec<String>,
+    /// Undone operations, the first one is redone next
+    pub redo: Vec<String>,
+}
+
+impl CSV {
+    /// Runs a mutating operation, recording it for undo when it succeeds. A new
+    /// operation discards anything that was undone.
+    pub fn with_history<T>(
+        &mut self,
+        operation: &str,
+        apply: impl FnOnce(&mut CSV) -> Result<T, AnalysisError>,
+    ) -> Result<T, AnalysisError> {
+        let before = self.snapshot();
+        let log_len = self.cleaning_steps().len();
+        let result = apply(self)?;
+
+        let history = self.undo_history_mut();
+        history.undo.push(UndoEntry {
+            operation: operation.to_string(),
+            before,
+            log_len,
+        });
+        if history.undo.len() > MAX_HISTORY {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+        info!(target: targets::CLEANING, "Applied {}", operation);
+        Ok(result)
+    }
+
+    /// Reverts the most recent operation, returning its name
+    pub fn undo_operation(&mut self) -> Result<String, AnalysisError> {
+        let entry = self
+            .undo_history_mut()
+            .undo
+            .pop()
+            .ok_or(AnalysisError::NothingToUndo)?;
+
+        let after = self.restore(entry.before);
+        let steps = self.truncate_cleaning_log(entry.log_len);
+        self.undo_history_mut().redo.push(RedoEntry {
+            operation: entry.operation.clone(),
+            after,
+            steps,
+        });
+        info!(target: targets::CLEANING, "Undid {}", entry.operation);
+        Ok(entry.operation)
+    }
+
+    /// Re-applies the most recently undone operation, returning its name
+    pub fn redo_operation(&mut self) -> Result<String, AnalysisError> {
+        let entry = self
+            .undo_history_mut()
+            .redo
+            .pop()
+            .ok_or(AnalysisError::NothingToRedo)?;
+
+        let log_len = self.cleaning_steps().len();
+        let before = self.restore(entry.after);
+        self.extend_cleaning_log(entry.steps);
+        self.undo_history_mut().undo.push(UndoEntry {
+            operation: entry.operation.clone(),
+            before,
+            log_len,
+        });
+        info!(target: targets::CLEANING, "Redid {}", entry.operation);
+        Ok(entry.operation)
+    }
+
+    /// Names of the operations that can be undone and redone
+    pub fn history_report(&self) -> HistoryReport {
+        let history = self.undo_history();
+        HistoryReport {
+            undo: history.undo.iter().map(|e| e.operation.clone()).collect(),
+            redo: history
+                .redo
+                .iter()
+                .rev()
+                .map(|e| e.operation.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    fn values(csv: &CSV, column: usize) -> Vec<String> {
+        csv.get_column(column).unwrap().1.to_vec()
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut csv = CSV::from_string("a,b\n1,x\n,y".to_string()).unwrap();
+        csv.infer_column_types();
+
+        csv.with_history("replace", |csv| csv.replace_in_column(1, "x", "z"))
+            .unwrap();
+        csv.with_history("add_computed_column", |csv| {
+            csv.push_computed_column("c", "b & b")
+        })
+        .unwrap();
+        assert_eq!(csv.column_count(), 3);
+        assert_eq!(csv.cleaning_steps().len(), 1);
+
+        assert_eq!(csv.undo_operation().unwrap(), "add_computed_column");
+        assert_eq!(csv.column_count(), 2);
+        assert_eq!(csv.undo_operation().unwrap(), "replace");
+        assert_eq!(values(&csv, 1), vec!["x", "y"]);
+        assert!(csv.cleaning_steps().is_empty());
+        assert_eq!(csv.undo_operation(), Err(AnalysisError::NothingToUndo));
+        assert_eq!(
+            csv.history_report(),
+            HistoryReport {
+                undo: vec![],
+                redo: vec!["replace".to_string(), "add_computed_column".to_string()],
+            }
+        );
+
+        assert_eq!(csv.redo_operation().unwrap(), "replace");
+        assert_eq!(values(&csv, 1), vec!["z", "y"]);
+        assert_eq!(csv.cleaning_steps().len(), 1);
+        assert!(csv.column_metadata(0).is_some(), "metadata comes back too");
+
+        // A new operation drops what was left to redo
+        csv.with_history("replace", |csv| csv.replace_in_column(1, "y", "w"))
+            .unwrap();
+        assert_eq!(csv.redo_operation(), Err(AnalysisError::NothingToRedo));
+        assert_eq!(csv.history_report().undo, vec!["replace", "replace"]);
+    }
+
+    #[test]
+    fn test_undo_appended_rows() {
+        let mut csv = CSV::from_string("id,name\n1,a\n2,b".to_string()).unwrap();
+        csv.infer_column_types();
+        csv.with_history("replace", |csv| csv.replace_in_column(1, "a", "z"))
+            .unwrap();
+
+        csv.with_history("append_rows", |csv| csv.append_rows("3,c\n4,d"))
+            .unwrap();
+        assert_eq!(csv.row_count(), 4);
+        assert_eq!(csv.undo_operation().unwrap(), "append_rows");
+        assert_eq!(csv.row_count(), 2);
+        assert_eq!(values(&csv, 1), vec!["z", "b"]);
+        assert_eq!(csv.redo_operation().unwrap(), "append_rows");
+        assert_eq!(values(&csv, 0), vec!["1", "2", "3", "4"]);
+
+        // Type counts kept for appending come back with the rows they counted
+        csv.undo_operation().unwrap();
+        let report = csv.append_rows("x,e").unwrap();
+        assert_eq!(report.rows_appended, 1);
+        assert_eq!(csv.row_count(), 3);
+        assert_eq!(csv.column_metadata(0).unwrap().data_type, DataType::Text);
+    }
+
+    #[test]
+    fn test_failed_operations_are_not_recorded() {
+        let mut csv = CSV::from_string("a\n1".to_string()).unwrap();
+        assert!(csv
+            .with_history("replace", |csv| csv.replace_in_column(4, "1", "2"))
+            .is_err());
+        assert!(csv.history_report().undo.is_empty());
+
+        for i in 0..MAX_HISTORY + 5 {
+            csv.with_history("replace", |csv| {
+                csv.replace_in_column(0, ".+", &i.to_string())
+            })
+            .unwrap();
+        }
+        assert_eq!(csv.history_report().undo.len(), MAX_HISTORY);
+    }
+}
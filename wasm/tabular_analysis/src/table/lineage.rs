@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+
+/// One operation in the history of a column's values
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LineageStep {
+    /// `split`, `merge`, `computed`, `rename` or the cleaning operation, e.g. `impute:median`
+    pub operation: String,
+    /// Columns the operation read, by their header at the time
+    pub inputs: Vec<String>,
+    /// Expression, delimiter or separator the operation was given
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Where a column's values come from: the columns of the loaded file they derive from and
+/// the operations applied since, so exported reports can show how cleaned fields were made
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColumnLineage {
+    /// Columns of the loaded file, in the order first used
+    pub sources: Vec<String>,
+    /// Operations that produced the current values, oldest first
+    pub steps: Vec<LineageStep>,
+}
+
+impl ColumnLineage {
+    /// Lineage of a column as read from the file
+    pub fn original(header: &str) -> ColumnLineage {
+        ColumnLineage {
+            sources: vec![header.to_string()],
+            steps: Vec::new(),
+        }
+    }
+
+    /// Lineage of a column made by `step` from columns with the given lineages, keeping
+    /// their sources and the steps that made them
+    pub fn derived(inputs: &[ColumnLineage], step: LineageStep) -> ColumnLineage {
+        let mut lineage = ColumnLineage {
+            sources: Vec::new(),
+            steps: Vec::new(),
+        };
+        for input in inputs {
+            for source in &input.sources {
+                if !lineage.sources.contains(source) {
+                    lineage.sources.push(source.clone());
+                }
+            }
+            for input_step in &input.steps {
+                if !lineage.steps.contains(input_step) {
+                    lineage.steps.push(input_step.clone());
+                }
+            }
+        }
+        lineage.steps.push(step);
+        lineage
+    }
+
+    /// Header the column had before it was first renamed, if it has been
+    pub fn renamed_from(&self) -> Option<&str> {
+        self.steps
+            .iter()
+            .find(|step| step.operation == "rename")?
+            .inputs
+            .first()
+            .map(String::as_str)
+    }
+
+    /// One-line description, e.g. `first, last via merge, replace`
+    pub fn describe(&self) -> String {
+        let operations: Vec<&str> = self.steps.iter().map(|s| s.operation.as_str()).collect();
+        match operations.is_empty() {
+            true => self.sources.join(", "),
+            false => format!("{} via {}", self.sources.join(", "), operations.join(", ")),
+        }
+    }
+}
+
+impl CSV {
+    /// Source columns and operations behind a column's values; columns untouched since
+    /// loading have themselves as their only source and no steps
+    pub fn column_lineage(&self, index: usize) -> Result<ColumnLineage, AnalysisError> {
+        let (header, _) = self
+            .get_column(index)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column: index })?;
+        Ok(self
+            .stored_lineage(index)
+            .cloned()
+            .unwrap_or_else(|| ColumnLineage::original(header)))
+    }
+
+    // Internal helper appending a column made by `operation` from the `inputs` columns,
+    // recording its lineage, and running type inference on it
+    pub(crate) fn push_derived_column(
+        &mut self,
+        header: String,
+        values: Vec<String>,
+        inputs: &[usize],
+        operation: &str,
+        detail: Option<String>,
+    ) -> usize {
+        let lineages: Vec<ColumnLineage> = inputs
+            .iter()
+            .filter_map(|&input| self.column_lineage(input).ok())
+            .collect();
+        let step = LineageStep {
+            operation: operation.to_string(),
+            inputs: inputs
+                .iter()
+                .filter_map(|&input| self.get_column(input).map(|(h, _)| h.to_string()))
+                .collect(),
+            detail,
+        };
+        let index = self.push_column(header, values);
+        self.set_column_lineage(index, ColumnLineage::derived(&lineages, step));
+        index
+    }
+
+    // Internal helper adding an operation that rewrote a column in place to its lineage
+    pub(crate) fn extend_lineage(&mut self, index: usize, operation: &str) {
+        let Ok(mut lineage) = self.column_lineage(index) else {
+            return;
+        };
+        let header = self.get_column(index).map(|(h, _)| h.to_string());
+        lineage.steps.push(LineageStep {
+            operation: operation.to_string(),
+            inputs: header.into_iter().collect(),
+            detail: None,
+        });
+        self.set_column_lineage(index, lineage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaning::impute::ImputeStrategy;
+
+    #[test]
+    fn test_lineage_through_operations() {
+        let data = "first,last,score\nAda,Lovelace,1\nAlan,Turing,\nGrace,Hopper,3";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        assert_eq!(
+            csv.column_lineage(0).unwrap(),
+            ColumnLineage::original("first")
+        );
+        assert!(csv.column_metadata(0).unwrap().lineage.is_none());
+
+        let full = csv.merge_column_values(&[0, 1], " ").unwrap();
+        csv.impute_column(2, &ImputeStrategy::Median).unwrap();
+        let label = csv
+            .push_computed_column("label", "first_last & score")
+            .unwrap();
+
+        let lineage = csv.column_lineage(label).unwrap();
+        assert_eq!(lineage.sources, vec!["first", "last", "score"]);
+        let operations: Vec<&str> = lineage.steps.iter().map(|s| s.operation.as_str()).collect();
+        assert_eq!(operations, vec!["merge", "impute:median", "computed"]);
+        assert_eq!(lineage.steps[0].detail.as_deref(), Some(" "));
+        assert_eq!(lineage.steps[2].inputs, vec!["first_last", "score"]);
+        assert_eq!(
+            lineage.describe(),
+            "first, last, score via merge, impute:median, computed"
+        );
+
+        // Metadata carries the lineage, also after inference runs again
+        assert_eq!(
+            csv.column_metadata(full)
+                .unwrap()
+                .lineage
+                .as_ref()
+                .unwrap()
+                .steps
+                .len(),
+            1
+        );
+        csv.infer_column_types();
+        assert_eq!(
+            csv.column_metadata(2)
+                .unwrap()
+                .lineage
+                .as_ref()
+                .unwrap()
+                .steps
+                .len(),
+            1
+        );
+        assert!(csv.column_lineage(9).is_err());
+    }
+
+    #[test]
+    fn test_split_lineage_is_undone() {
+        let data = "name\n\"Smith, John\"\n\"Doe, Jane\"";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        let parts = csv
+            .with_history("split", |csv| csv.split_column_values(0, ",", false))
+            .unwrap();
+        let lineage = csv.column_lineage(parts[1]).unwrap();
+        assert_eq!(lineage.sources, vec!["name"]);
+        assert_eq!(lineage.steps[0].detail.as_deref(), Some(","));
+
+        csv.undo_operation().unwrap();
+        assert_eq!(csv.column_count(), 1);
+    }
+
+    #[test]
+    fn test_redo_keeps_lineage() {
+        let mut csv = CSV::from_string(
+            "status
+open
+gone"
+                .to_string(),
+        )
+        .unwrap();
+        csv.with_history("replace", |csv| csv.replace_in_column(0, "gone", "closed"))
+            .unwrap();
+        csv.undo_operation().unwrap();
+        assert!(csv.column_lineage(0).unwrap().steps.is_empty());
+
+        csv.redo_operation().unwrap();
+        assert_eq!(csv.column_lineage(0).unwrap().steps.len(), 1);
+        assert_eq!(csv.cleaning_steps().len(), 1);
+    }
+}
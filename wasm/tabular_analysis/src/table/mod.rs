@@ -0,0 +1,16 @@
+pub mod columns;
+pub mod computed;
+pub mod dialect;
+pub mod diff;
+pub mod fixed_width;
+pub mod history;
+pub mod lineage;
+pub mod pivot;
+pub mod raw;
+pub mod remote;
+pub mod reshape;
+pub mod rows;
+pub mod sample;
+pub mod scan;
+pub mod session;
+pub mod view;
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::table::rows::typed_value;
+
+// Guards against pivoting on an identifier-like column by mistake
+const MAX_PIVOT_COLUMNS: usize = 1000;
+
+/// How the values falling into one pivot cell are combined
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregate {
+    /// Non-empty values, numeric or not
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    pub fn parse(name: &str) -> Result<Aggregate, AnalysisError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "count" => Ok(Aggregate::Count),
+            "sum" => Ok(Aggregate::Sum),
+            "mean" | "avg" | "average" => Ok(Aggregate::Mean),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            _ => Err(AnalysisError::invalid_argument(format!(
+                "Unknown aggregate '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Accumulator {
+    values: usize,
+    numbers: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Accumulator {
+    fn add(&mut self, number: Option<f64>) {
+        self.values += 1;
+        let Some(number) = number else {
+            return;
+        };
+        if self.numbers == 0 {
+            self.min = number;
+            self.max = number;
+        }
+        self.numbers += 1;
+        self.sum += number;
+        self.min = self.min.min(number);
+        self.max = self.max.max(number);
+    }
+
+    fn result(&self, aggregate: Aggregate) -> Option<f64> {
+        match aggregate {
+            Aggregate::Count => Some(self.values as f64),
+            _ if self.numbers == 0 => None,
+            Aggregate::Sum => Some(self.sum),
+            Aggregate::Mean => Some(self.sum / self.numbers as f64),
+            Aggregate::Min => Some(self.min),
+            Aggregate::Max => Some(self.max),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PivotRow {
+    pub key: String,
+    /// One cell per pivot column; None when no numeric value fell into it
+    pub values: Vec<Option<f64>>,
+}
+
+/// A crosstab with one row per distinct value of the row column and one column per
+/// distinct value of the column column, both in order of first appearance
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PivotTable {
+    pub row_header: String,
+    pub column_header: String,
+    pub value_header: String,
+    pub aggregate: Aggregate,
+    pub columns: Vec<String>,
+    pub rows: Vec<PivotRow>,
+}
+
+// Position of a key, adding it in order of first appearance
+fn key_index(keys: &mut Vec<String>, index: &mut HashMap<String, usize>, key: &str) -> usize {
+    *index.entry(key.to_string()).or_insert_with(|| {
+        keys.push(key.to_string());
+        keys.len() - 1
+    })
+}
+
+impl CSV {
+    /// Crosstab of `value_col` aggregated by the values of `row_col` and `col_col`
+    pub fn pivot_table(
+        &self,
+        row_col: usize,
+        col_col: usize,
+        value_col: usize,
+        aggregate: Aggregate,
+    ) -> Result<PivotTable, AnalysisError> {
+        let column = |column: usize| {
+            self.get_column(column)
+                .ok_or(AnalysisError::ColumnOutOfBounds { column })
+        };
+        let (row_header, row_values) = column(row_col)?;
+        let (column_header, column_values) = column(col_col)?;
+        let (value_header, values) = column(value_col)?;
+        let metadata = self.column_metadata(value_col);
+
+        let (mut row_keys, mut row_index) = (Vec::new(), HashMap::new());
+        let (mut column_keys, mut column_index) = (Vec::new(), HashMap::new());
+        let mut cells: HashMap<(usize, usize), Accumulator> = HashMap::new();
+        for row in 0..self.row_count() {
+            let value = values[row].trim();
+            let r = key_index(&mut row_keys, &mut row_index, row_values[row].trim());
+            let c = key_index(
+                &mut column_keys,
+                &mut column_index,
+                column_values[row].trim(),
+            );
+            if column_keys.len() > MAX_PIVOT_COLUMNS {
+                return Err(AnalysisError::invalid_column(
+                    col_col,
+                    format!(
+                        "Column '{}' has more than {} distinct values",
+                        column_header, MAX_PIVOT_COLUMNS
+                    ),
+                ));
+            }
+            if value.is_empty() {
+                continue;
+            }
+
+            let number = match typed_value(value, metadata) {
+                Value::Number(n) => n.as_f64(),
+                _ => value.parse().ok(),
+            };
+            cells.entry((r, c)).or_default().add(number);
+        }
+
+        let rows = row_keys
+            .into_iter()
+            .enumerate()
+            .map(|(r, key)| PivotRow {
+                key,
+                values: (0..column_keys.len())
+                    .map(|c| cells.get(&(r, c)).and_then(|cell| cell.result(aggregate)))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(PivotTable {
+            row_header: row_header.to_string(),
+            column_header: column_header.to_string(),
+            value_header: value_header.to_string(),
+            aggregate,
+            columns: column_keys,
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pivot_table() {
+        let data = "region,quarter,sales\n\
+                    north,Q1,10\n\
+                    south,Q1,4\n\
+                    north,Q2,6\n\
+                    north,Q1,5\n\
+                    south,Q2,";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let table = csv.pivot_table(0, 1, 2, Aggregate::Sum).unwrap();
+        assert_eq!(table.columns, vec!["Q1", "Q2"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                PivotRow {
+                    key: "north".to_string(),
+                    values: vec![Some(15.0), Some(6.0)],
+                },
+                PivotRow {
+                    key: "south".to_string(),
+                    values: vec![Some(4.0), None],
+                },
+            ]
+        );
+
+        let test_cases = vec![
+            (Aggregate::Count, vec![Some(2.0), Some(1.0)]),
+            (Aggregate::Mean, vec![Some(7.5), Some(6.0)]),
+            (Aggregate::Min, vec![Some(5.0), Some(6.0)]),
+            (Aggregate::Max, vec![Some(10.0), Some(6.0)]),
+        ];
+        for (aggregate, expected) in test_cases {
+            let table = csv.pivot_table(0, 1, 2, aggregate).unwrap();
+            assert_eq!(
+                table.rows[0].values, expected,
+                "Failed for input: {:?}",
+                aggregate
+            );
+        }
+    }
+
+    #[test]
+    fn test_pivot_errors() {
+        let csv = CSV::from_string("a,b,c\n1,2,3".to_string()).unwrap();
+        assert!(csv.pivot_table(0, 1, 5, Aggregate::Sum).is_err());
+        assert_eq!(Aggregate::parse(" AVG ").unwrap(), Aggregate::Mean);
+        assert!(Aggregate::parse("median").is_err());
+    }
+}
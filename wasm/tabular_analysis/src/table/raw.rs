@@ -0,0 +1,226 @@
+use crate::csv::{for_each_record, is_short_row};
+use crate::error::AnalysisError;
+use crate::table::scan::structural_positions;
+
+/// Parsed fields of a table kept in one buffer, unescaped and back to back, with the offset
+/// where each field ends, so columns can be turned into owned strings only when they are
+/// used. A wide file where a few columns are analyzed never allocates the others' cells.
+#[derive(Debug, Default)]
+pub struct RawTable {
+    text: String,
+    // End of every field in `text`, row by row, each row padded to `width` fields
+    ends: Vec<usize>,
+    width: usize,
+}
+
+impl RawTable {
+    /// Reads every record of `reader` into a table of `width` columns, returning it with the
+    /// rows that had fewer fields and were padded with empty values. A row with more fields
+    /// than `width` fails.
+    pub fn read<R: std::io::Read>(
+        reader: &mut csv::Reader<R>,
+        width: usize,
+    ) -> Result<(RawTable, Vec<usize>), AnalysisError> {
+        let mut table = RawTable {
+            width,
+            ..RawTable::default()
+        };
+        let short_rows = for_each_record(reader, width, 0, |_, record| {
+            for i in 0..width {
+                table.text.push_str(record.get(i).unwrap_or_default());
+                table.ends.push(table.text.len());
+            }
+        })?;
+        Ok((table, short_rows))
+    }
+
+    /// Builds a table of `width` columns from records already split into fields, padding
+    /// and failing on rows as `read` does
+    pub fn from_records(
+        records: Vec<Vec<String>>,
+        width: usize,
+    ) -> Result<(RawTable, Vec<usize>), AnalysisError> {
+        let mut table = RawTable {
+            width,
+            ..RawTable::default()
+        };
+        let mut short_rows = Vec::new();
+        for (row, record) in records.into_iter().enumerate() {
+            if is_short_row(record.len(), width, row)? {
+                short_rows.push(row);
+            }
+            for i in 0..width {
+                table
+                    .text
+                    .push_str(record.get(i).map_or("", String::as_str));
+                table.ends.push(table.text.len());
+            }
+        }
+        Ok((table, short_rows))
+    }
+
+    /// Splits CSV text without quotes on commas and line breaks found by `structural_positions`,
+    /// reading the first row as the headers. Empty lines are skipped and short rows padded as
+    /// by `read`. Returns None when the text needs the full reader: it has quotes, a byte
+    /// order mark or no header, or a row is longer than the header.
+    pub fn split_unquoted(text: &str) -> Option<(Vec<String>, RawTable, Vec<usize>)> {
+        let bytes = text.as_bytes();
+        if bytes.starts_with(b"\xEF\xBB\xBF") {
+            return None;
+        }
+
+        let mut headers: Option<Vec<String>> = None;
+        // The fields never take more room than the text they were split from
+        let mut table = RawTable {
+            text: String::with_capacity(bytes.len()),
+            ..RawTable::default()
+        };
+        let mut short_rows = Vec::new();
+        // Fields read of the current row; the header row is read into the table like the
+        // others, then moved out of it
+        let mut fields = 0;
+        let mut positions = structural_positions(bytes);
+        let mut start = 0;
+        loop {
+            let end = positions.next().unwrap_or(bytes.len());
+            let delimiter = bytes.get(end).copied();
+            if delimiter == Some(b'"') {
+                return None;
+            }
+            let field = &text[start..end];
+            start = end + 1;
+            fields += 1;
+            if headers.is_some() && fields > table.width {
+                return None;
+            }
+            table.text.push_str(field);
+            table.ends.push(table.text.len());
+            if delimiter == Some(b',') {
+                continue;
+            }
+            if delimiter == Some(b'\r') && bytes.get(start) == Some(&b'\n') {
+                positions.next();
+                start += 1;
+            }
+
+            if fields == 1 && field.is_empty() {
+                // An empty line
+                table.ends.pop();
+            } else if headers.is_none() {
+                table.width = fields;
+                headers = Some(table.row_values(0));
+                table.text.clear();
+                table.ends.clear();
+            } else if fields < table.width {
+                short_rows.push(table.rows());
+                table
+                    .ends
+                    .resize(table.ends.len() + table.width - fields, table.text.len());
+            }
+            fields = 0;
+            if start >= bytes.len() {
+                break;
+            }
+        }
+        Some((headers?, table, short_rows))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.ends.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The field at `row` and `column`, or None outside the table
+    pub fn field(&self, row: usize, column: usize) -> Option<&str> {
+        if column >= self.width {
+            return None;
+        }
+        let index = row * self.width + column;
+        let end = *self.ends.get(index)?;
+        let start = index
+            .checked_sub(1)
+            .map_or(0, |previous| self.ends[previous]);
+        Some(&self.text[start..end])
+    }
+
+    // Owned copies of every value in a row
+    fn row_values(&self, row: usize) -> Vec<String> {
+        (0..self.width)
+            .filter_map(|column| self.field(row, column))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Owned copies of every value in a column
+    pub fn column(&self, column: usize) -> Vec<String> {
+        (0..self.rows())
+            .filter_map(|row| self.field(row, column))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csv::ReaderBuilder;
+
+    #[test]
+    fn test_raw_table_fields() {
+        let data = "a,b,c\n1,\"x, \"\"quoted\"\"\",3\n4\n\"\",5,6";
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(data.as_bytes());
+        reader.headers().unwrap();
+        let (table, short_rows) = RawTable::read(&mut reader, 3).unwrap();
+
+        assert_eq!((table.rows(), table.width()), (3, 3));
+        assert_eq!(short_rows, vec![1]);
+        assert_eq!(table.field(0, 1), Some("x, \"quoted\""));
+        assert_eq!(table.field(1, 2), Some(""));
+        assert_eq!(table.field(2, 0), Some(""));
+        assert_eq!(table.field(3, 0), None);
+        assert_eq!(table.field(0, 3), None);
+        assert_eq!(table.column(2), ["3", "", "6"]);
+    }
+
+    // Headers, fields and padded rows as the CSV reader gives them
+    fn read_with_reader(text: &str) -> (Vec<String>, Vec<Vec<String>>, Vec<usize>) {
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+        let (table, short_rows) = RawTable::read(&mut reader, headers.len()).unwrap();
+        let columns = (0..table.width()).map(|i| table.column(i)).collect();
+        (headers, columns, short_rows)
+    }
+
+    #[test]
+    fn test_split_unquoted_matches_reader() {
+        let texts = [
+            "a,b,c\n1,2,3\n4,5,6\n",
+            "a,b,c\r\n1,2,3\r\n4,5,6",
+            "a,b\n\n1,2\n\n\n3\n,\n",
+            "\na,b\r1,2\r3,4\n",
+            "a,b,c\n1,,\n2\n,,\nlonger values past sixteen bytes,x,y",
+            "only\n1\n2",
+        ];
+        for text in texts {
+            let (headers, table, short_rows) = RawTable::split_unquoted(text).unwrap();
+            let columns: Vec<Vec<String>> = (0..table.width()).map(|i| table.column(i)).collect();
+            assert_eq!(
+                (headers, columns, short_rows),
+                read_with_reader(text),
+                "{:?}",
+                text
+            );
+        }
+
+        for text in ["a,b\n\"1,2\",3", "a\n1,2", "\u{feff}a,b\n1,2", ""] {
+            assert!(RawTable::split_unquoted(text).is_none(), "{:?}", text);
+        }
+    }
+}
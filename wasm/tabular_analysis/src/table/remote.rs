@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::spill::complete_rows_end;
+use crate::csv::{parse_error, CSV};
+use crate::error::AnalysisError;
+use crate::random::{RandomSource, SplitMix64};
+use crate::table::dialect::Dialect;
+use crate::warning::{AnalysisWarning, Severity, WarningCode};
+
+/// How `CSV.from_url` downloads a remote file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct FetchOptions {
+    /// Download only the start of the file and `sample_ranges` random byte ranges of the rest,
+    /// for quick inference on giant files. Servers without range requests send the start only.
+    pub sample: bool,
+    /// Bytes read from the start of the file when sampling
+    pub head_bytes: u64,
+    pub sample_ranges: usize,
+    /// Bytes of each random range; rows cut off at either end are dropped
+    pub range_bytes: u64,
+    /// Same seed, same ranges
+    pub seed: u64,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            sample: false,
+            head_bytes: 256 * 1024,
+            sample_ranges: 16,
+            range_bytes: 64 * 1024,
+            seed: 0,
+        }
+    }
+}
+
+impl FetchOptions {
+    pub fn validate(&self) -> Result<(), AnalysisError> {
+        if self.sample && (self.head_bytes == 0 || self.range_bytes == 0) {
+            return Err(AnalysisError::invalid_argument(
+                "head_bytes and range_bytes must be positive to sample",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Bytes downloaded so far and, when the server sent a length, the bytes expected
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct FetchProgress {
+    pub loaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Total length of the file from a `Content-Range: bytes 0-99/1234` header, when given
+pub fn content_range_total(header: &str) -> Option<u64> {
+    header.trim().rsplit_once('/')?.1.parse().ok()
+}
+
+/// Inclusive byte ranges after the head to sample from a file of `total` bytes, sorted and
+/// not overlapping. Each range is drawn from its own equal stretch of the rest of the file, so
+/// the sample spans all of it.
+pub fn sample_ranges(total: u64, options: &FetchOptions) -> Vec<(u64, u64)> {
+    let rest = total.saturating_sub(options.head_bytes);
+    if rest == 0 || options.sample_ranges == 0 {
+        return Vec::new();
+    }
+    let count = (options.sample_ranges as u64).min(rest.div_ceil(options.range_bytes));
+    let stretch = rest / count;
+    let mut rng = SplitMix64::new(options.seed);
+    (0..count)
+        .map(|i| {
+            let start = options.head_bytes + i * stretch;
+            let slack = stretch.saturating_sub(options.range_bytes);
+            let start = start + rng.below_u64(slack + 1);
+            let end = (start + options.range_bytes).min(total) - 1;
+            (start, end)
+        })
+        .collect()
+}
+
+// Rows of a range taken from the middle of a file: whatever precedes the first line break
+// and follows the last one belongs to rows cut off by the range
+fn whole_rows(range: &[u8]) -> &[u8] {
+    let Some(first) = range.iter().position(|&b| b == b'\n') else {
+        return &[];
+    };
+    let last = range.iter().rposition(|&b| b == b'\n').unwrap_or(first);
+    &range[first + 1..last + 1]
+}
+
+impl CSV {
+    /// Builds a CSV from the start of a file and byte ranges sampled from the rest, with a
+    /// warning saying how much of the `total` bytes the rows came from and the `seed` the
+    /// ranges were picked with. Rows cut off at range
+    /// edges and rows of a different width, from ranges starting inside quoted line breaks,
+    /// are dropped.
+    pub fn from_sampled_ranges(
+        head: &[u8],
+        ranges: &[Vec<u8>],
+        total: Option<u64>,
+        seed: u64,
+    ) -> Result<CSV, AnalysisError> {
+        let head_text = String::from_utf8_lossy(head);
+        let head_text = match total.is_some_and(|total| (head.len() as u64) < total) {
+            true => &head_text[..complete_rows_end(&head_text)],
+            false => &head_text[..],
+        };
+        // Ranges are read in the dialect of the head, which has the header row
+        let dialect = Dialect::sniff(head_text);
+        let mut reader = dialect.reader_builder().from_reader(head_text.as_bytes());
+        let headers = dialect.header_names(reader.headers().map_err(|e| parse_error(e, true, 0))?);
+        let mut columns = vec![Vec::new(); headers.len()];
+        let mut push = |record: &csv::StringRecord| {
+            for (column, value) in columns.iter_mut().zip(record.iter()) {
+                column.push(value.to_string());
+            }
+        };
+        for record in reader.records() {
+            let record = record.map_err(|e| parse_error(e, true, 0))?;
+            if record.len() == headers.len() {
+                push(&record);
+            }
+        }
+
+        let mut sampled_bytes = head_text.len();
+        for range in ranges {
+            let rows = whole_rows(range);
+            sampled_bytes += rows.len();
+            let mut reader = dialect
+                .reader_builder()
+                .has_headers(false)
+                .from_reader(rows);
+            // A range starting inside a quoted field reads as garbage up to its end
+            for record in reader.records().map_while(Result::ok) {
+                if record.len() == headers.len() {
+                    push(&record);
+                }
+            }
+        }
+
+        let columns = headers
+            .into_iter()
+            .zip(columns)
+            .map(|(header, values)| (header, values, None))
+            .collect();
+        let mut csv = CSV::from_parts(columns, Default::default());
+        if let Some(total) = total.filter(|&total| sampled_bytes as u64 + 1 < total) {
+            csv.push_parse_warning(AnalysisWarning {
+                code: WarningCode::SampledInput,
+                severity: Severity::Info,
+                message: format!(
+                    "{} rows read from {} of the file's {} bytes, ranges picked with seed {}; \
+                     statistics describe the sample",
+                    csv.row_count(),
+                    sampled_bytes,
+                    total,
+                    seed
+                ),
+                column: None,
+                rows: Vec::new(),
+            });
+        }
+        Ok(csv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(rows: usize) -> String {
+        let mut data = String::from("id,name\n");
+        for i in 0..rows {
+            data.push_str(&format!("{},\"name {}\"\n", i, i));
+        }
+        data
+    }
+
+    #[test]
+    fn test_sample_ranges() {
+        assert_eq!(content_range_total("bytes 0-99/1234"), Some(1234));
+        assert_eq!(content_range_total("bytes 0-99/*"), None);
+
+        let options = FetchOptions {
+            head_bytes: 100,
+            sample_ranges: 4,
+            range_bytes: 50,
+            ..FetchOptions::default()
+        };
+        let ranges = sample_ranges(1100, &options);
+        assert_eq!(ranges.len(), 4);
+        let mut previous_end = 99;
+        for &(start, end) in &ranges {
+            assert!(start > previous_end && end - start == 49 && end < 1100);
+            previous_end = end;
+        }
+        assert_eq!(ranges, sample_ranges(1100, &options));
+        assert!(sample_ranges(100, &options).is_empty());
+        // Fewer ranges than asked for when they'd cover the rest anyway
+        assert_eq!(sample_ranges(160, &options).len(), 2);
+    }
+
+    #[test]
+    fn test_from_sampled_ranges() {
+        let data = file(2000);
+        let bytes = data.as_bytes();
+        let options = FetchOptions {
+            head_bytes: 300,
+            sample_ranges: 5,
+            range_bytes: 200,
+            ..FetchOptions::default()
+        };
+        let ranges: Vec<Vec<u8>> = sample_ranges(bytes.len() as u64, &options)
+            .into_iter()
+            .map(|(start, end)| bytes[start as usize..=end as usize].to_vec())
+            .collect();
+
+        let mut csv =
+            CSV::from_sampled_ranges(&bytes[..300], &ranges, Some(bytes.len() as u64), 0).unwrap();
+        assert_eq!(csv.headers(), vec!["id", "name"]);
+        assert!(csv.row_count() > 30 && csv.row_count() < 100);
+        let (_, names) = csv.get_column(1).unwrap();
+        assert!(names.iter().all(|name| name.starts_with("name ")));
+        assert!(csv
+            .warnings()
+            .iter()
+            .any(|w| w.code == WarningCode::SampledInput && w.message.contains("seed 0")));
+        csv.infer_column_types();
+        assert_eq!(
+            csv.column_metadata(0).unwrap().data_type,
+            crate::types::DataType::Integer
+        );
+
+        let data = data.replace(',', ";");
+        let bytes = data.as_bytes();
+        let ranges: Vec<Vec<u8>> = sample_ranges(bytes.len() as u64, &options)
+            .into_iter()
+            .map(|(start, end)| bytes[start as usize..=end as usize].to_vec())
+            .collect();
+        let csv =
+            CSV::from_sampled_ranges(&bytes[..300], &ranges, Some(bytes.len() as u64), 0).unwrap();
+        assert_eq!(csv.headers(), vec!["id", "name"]);
+        assert!(csv.row_count() > 30);
+
+        // The whole file fit in the head
+        let small = file(3);
+        let csv = CSV::from_sampled_ranges(small.as_bytes(), &[], Some(small.len() as u64), 0);
+        let csv = csv.unwrap();
+        assert_eq!(csv.row_count(), 3);
+        assert!(csv.parse_warnings().is_empty());
+    }
+}
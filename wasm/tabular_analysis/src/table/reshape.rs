@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::types::date::Date;
+
+// Guards against flipping a long table into one with a column per row by mistake
+const MAX_TRANSPOSED_COLUMNS: usize = 10_000;
+// Guards against spreading an identifier-like column by mistake
+const MAX_SPREAD_COLUMNS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Number,
+    Date,
+    Text,
+}
+
+fn value_kind(value: &str) -> Option<ValueKind> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else if value.parse::<f64>().is_ok() {
+        Some(ValueKind::Number)
+    } else if Date::parse(value).is_some() {
+        Some(ValueKind::Date)
+    } else {
+        Some(ValueKind::Text)
+    }
+}
+
+// Share of the groups whose non-empty values are all of one kind, leaving out empty groups
+fn consistency<'a>(groups: impl Iterator<Item = Vec<&'a str>>) -> f64 {
+    let (mut consistent, mut total) = (0, 0);
+    for group in groups {
+        let mut kinds = group.iter().filter_map(|value| value_kind(value));
+        let Some(first) = kinds.next() else {
+            continue;
+        };
+        total += 1;
+        if kinds.all(|kind| kind == first) {
+            consistent += 1;
+        }
+    }
+    match total {
+        0 => 0.0,
+        total => consistent as f64 / total as f64,
+    }
+}
+
+/// Whether a table seems stored one attribute per row, with entities as columns after a
+/// first column of attribute names, judged by whether values keep one kind along rows
+/// rather than down columns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TransposeCheck {
+    /// Share of rows whose values right of the first column are all numbers, dates or text
+    pub row_consistency: f64,
+    /// Share of columns right of the first whose values are all of one kind
+    pub column_consistency: f64,
+    /// Rows are consistent where columns aren't and the first column holds distinct names
+    pub likely_transposed: bool,
+}
+
+impl CSV {
+    /// Checks whether the table looks transposed, so `transpose` should be applied before
+    /// inference
+    pub fn check_transposed(&self) -> TransposeCheck {
+        let columns = self.get_columns();
+        let entities = columns.get(1..).unwrap_or_default();
+        let row_consistency = consistency(
+            (0..self.row_count())
+                .map(|row| entities.iter().map(|(_, v)| v[row].as_str()).collect()),
+        );
+        let column_consistency = consistency(
+            entities
+                .iter()
+                .map(|(_, values)| values.iter().map(String::as_str).collect()),
+        );
+        let labels_are_names = columns.first().is_some_and(|(_, labels)| {
+            let distinct: HashSet<&str> = labels.iter().map(|l| l.trim()).collect();
+            distinct.len() == labels.len()
+                && labels
+                    .iter()
+                    .all(|label| value_kind(label) == Some(ValueKind::Text))
+        });
+
+        TransposeCheck {
+            row_consistency,
+            column_consistency,
+            likely_transposed: self.row_count() >= 2
+                && entities.len() >= 2
+                && labels_are_names
+                && row_consistency >= 0.8
+                && column_consistency <= 0.5,
+        }
+    }
+
+    /// Flips the table so its rows become columns: the first column's values become the
+    /// headers, and each other column a row starting with its header, under the first
+    /// column's header. Types are inferred for the new columns.
+    pub fn transpose(&self) -> Result<CSV, AnalysisError> {
+        let columns = self.get_columns();
+        let Some(((label_header, labels), entities)) = columns.split_first() else {
+            return Err(AnalysisError::invalid_argument(
+                "A table without columns can't be transposed",
+            ));
+        };
+        if labels.len() >= MAX_TRANSPOSED_COLUMNS {
+            return Err(AnalysisError::invalid_argument(format!(
+                "Transposing {} rows would make more than {} columns",
+                labels.len(),
+                MAX_TRANSPOSED_COLUMNS
+            )));
+        }
+
+        let mut transposed = vec![(
+            label_header.to_string(),
+            entities
+                .iter()
+                .map(|(header, _)| header.to_string())
+                .collect(),
+            None,
+        )];
+        for (row, label) in labels.iter().enumerate() {
+            let header = match label.trim() {
+                "" => format!("column_{}", row + 2),
+                label => label.to_string(),
+            };
+            let values = entities
+                .iter()
+                .map(|(_, values)| values[row].clone())
+                .collect();
+            transposed.push((header, values, None));
+        }
+        let mut csv = CSV::from_parts(transposed, self.analysis_config().clone());
+        csv.infer_column_types();
+        Ok(csv)
+    }
+
+    /// Reshapes wide to long: each row becomes one row per value column, holding the id
+    /// columns, a `variable` column with the value column's header and a `value` column.
+    /// Every column but the ids is melted when `value_columns` is empty. Types are inferred
+    /// for the new table.
+    pub fn melt(
+        &self,
+        id_columns: &[usize],
+        value_columns: &[usize],
+    ) -> Result<CSV, AnalysisError> {
+        self.check_columns(id_columns.iter().chain(value_columns))?;
+        let value_columns: Vec<usize> = match value_columns.is_empty() {
+            true => (0..self.column_count())
+                .filter(|c| !id_columns.contains(c))
+                .collect(),
+            false => value_columns.to_vec(),
+        };
+        if let Some(column) = value_columns.iter().find(|c| id_columns.contains(c)) {
+            return Err(AnalysisError::invalid_column(
+                *column,
+                "Column can't be both an id and a value column",
+            ));
+        }
+
+        let columns = self.get_columns();
+        let rows = self.row_count() * value_columns.len();
+        let mut ids: Vec<Vec<String>> = vec![Vec::with_capacity(rows); id_columns.len()];
+        let mut variables = Vec::with_capacity(rows);
+        let mut values = Vec::with_capacity(rows);
+        for row in 0..self.row_count() {
+            for &value_column in &value_columns {
+                for (melted, &id_column) in ids.iter_mut().zip(id_columns) {
+                    melted.push(columns[id_column].1[row].clone());
+                }
+                let (header, column_values) = columns[value_column];
+                variables.push(header.to_string());
+                values.push(column_values[row].clone());
+            }
+        }
+
+        let mut melted: Vec<_> = id_columns
+            .iter()
+            .zip(ids)
+            .map(|(&column, values)| (columns[column].0.to_string(), values, None))
+            .collect();
+        melted.push(("variable".to_string(), variables, None));
+        melted.push(("value".to_string(), values, None));
+        let mut csv = CSV::from_parts(melted, self.analysis_config().clone());
+        csv.infer_column_types();
+        Ok(csv)
+    }
+
+    /// Reshapes long to wide, undoing `melt`: rows sharing the values of every other column
+    /// become one row, with a column per distinct key holding that key's value, in the order
+    /// keys first appear. Keys missing for a row leave an empty value; a key appearing
+    /// twice for the same row fails. Types are inferred for the new table.
+    pub fn spread(&self, key_column: usize, value_column: usize) -> Result<CSV, AnalysisError> {
+        self.check_columns([key_column, value_column].iter())?;
+        if key_column == value_column {
+            return Err(AnalysisError::invalid_column(
+                value_column,
+                "Key and value columns must differ",
+            ));
+        }
+        let columns = self.get_columns();
+        let id_columns: Vec<usize> = (0..self.column_count())
+            .filter(|&c| c != key_column && c != value_column)
+            .collect();
+
+        let mut keys: Vec<&str> = Vec::new();
+        let mut key_positions: HashMap<&str, usize> = HashMap::new();
+        let mut groups: Vec<usize> = Vec::new();
+        let mut group_positions: HashMap<Vec<&str>, usize> = HashMap::new();
+        let mut cells: HashMap<(usize, usize), &str> = HashMap::new();
+        for row in 0..self.row_count() {
+            let id: Vec<&str> = id_columns
+                .iter()
+                .map(|&c| columns[c].1[row].as_str())
+                .collect();
+            let group = *group_positions.entry(id).or_insert_with(|| {
+                groups.push(row);
+                groups.len() - 1
+            });
+            let key = columns[key_column].1[row].as_str();
+            let key_position = *key_positions.entry(key).or_insert_with(|| {
+                keys.push(key);
+                keys.len() - 1
+            });
+            if keys.len() > MAX_SPREAD_COLUMNS {
+                return Err(AnalysisError::invalid_column(
+                    key_column,
+                    format!("Column has more than {} distinct keys", MAX_SPREAD_COLUMNS),
+                ));
+            }
+            let value = columns[value_column].1[row].as_str();
+            if cells.insert((group, key_position), value).is_some() {
+                return Err(AnalysisError::invalid_argument(format!(
+                    "Row {} repeats the key '{}' for the same values of the other columns",
+                    row, key
+                )));
+            }
+        }
+
+        let mut spread: Vec<_> = id_columns
+            .iter()
+            .map(|&c| {
+                let values = groups
+                    .iter()
+                    .map(|&row| columns[c].1[row].clone())
+                    .collect();
+                (columns[c].0.to_string(), values, None)
+            })
+            .collect();
+        for (position, key) in keys.iter().enumerate() {
+            let values = (0..groups.len())
+                .map(|group| {
+                    cells
+                        .get(&(group, position))
+                        .map_or_else(String::new, |v| v.to_string())
+                })
+                .collect();
+            spread.push((key.to_string(), values, None));
+        }
+        let mut csv = CSV::from_parts(spread, self.analysis_config().clone());
+        csv.infer_column_types();
+        Ok(csv)
+    }
+
+    fn check_columns<'a>(
+        &self,
+        mut columns: impl Iterator<Item = &'a usize>,
+    ) -> Result<(), AnalysisError> {
+        match columns.find(|&&c| c >= self.column_count()) {
+            Some(&column) => Err(AnalysisError::ColumnOutOfBounds { column }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    const ATTRIBUTES: &str = "\
+field,alice,bob,carol
+age,36,41,29
+joined,2024-01-05,2023-02-11,2022-12-30
+city,Paris,Oslo,Lima";
+
+    #[test]
+    fn test_check_transposed() {
+        let csv = CSV::from_string(ATTRIBUTES.to_string()).unwrap();
+        let check = csv.check_transposed();
+        assert_eq!(check.row_consistency, 1.0);
+        assert_eq!(check.column_consistency, 0.0);
+        assert!(check.likely_transposed);
+
+        let normal = csv.transpose().unwrap();
+        assert!(!normal.check_transposed().likely_transposed);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let csv = CSV::from_string(ATTRIBUTES.to_string()).unwrap();
+        let flipped = csv.transpose().unwrap();
+        assert_eq!(flipped.headers(), vec!["field", "age", "joined", "city"]);
+        assert_eq!(flipped.get_column(0).unwrap().1, ["alice", "bob", "carol"]);
+        assert_eq!(flipped.get_column(2).unwrap().1[1], "2023-02-11");
+        assert_eq!(
+            flipped.column_metadata(1).unwrap().data_type,
+            DataType::Integer
+        );
+        assert_eq!(
+            flipped.column_metadata(2).unwrap().data_type,
+            DataType::Date
+        );
+
+        // Flipping twice restores the table
+        let restored = flipped.transpose().unwrap();
+        assert_eq!(restored.get_columns(), csv.get_columns());
+    }
+
+    #[test]
+    fn test_melt_and_spread() {
+        let data = "region,q1,q2\nnorth,10,12\nsouth,7,\n";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let long = csv.melt(&[0], &[]).unwrap();
+        assert_eq!(long.headers(), vec!["region", "variable", "value"]);
+        assert_eq!(long.row_count(), 4);
+        assert_eq!(
+            long.get_column(0).unwrap().1,
+            ["north", "north", "south", "south"]
+        );
+        assert_eq!(long.get_column(1).unwrap().1, ["q1", "q2", "q1", "q2"]);
+        assert_eq!(long.get_column(2).unwrap().1, ["10", "12", "7", ""]);
+        assert_eq!(
+            long.column_metadata(2).unwrap().data_type,
+            DataType::Integer
+        );
+
+        let wide = long.spread(1, 2).unwrap();
+        assert_eq!(wide.get_columns(), csv.get_columns());
+        assert_eq!(
+            wide.column_metadata(1).unwrap().data_type,
+            DataType::Integer
+        );
+
+        assert!(csv.melt(&[0], &[0]).is_err());
+        assert!(matches!(
+            csv.melt(&[5], &[]),
+            Err(AnalysisError::ColumnOutOfBounds { column: 5 })
+        ));
+        let repeated = CSV::from_string("id,key,value\n1,a,x\n1,a,y".to_string()).unwrap();
+        assert!(repeated.spread(1, 2).is_err());
+    }
+}
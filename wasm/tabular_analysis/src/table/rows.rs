@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::types::{big_integer::plain_integer, date::DateType, DataType, TypeDetection};
+
+// Magnitude from which an f64 no longer converts to an i64, 2^63
+const I64_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+/// One page of rows as objects keyed by header
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RowPage {
+    /// Index of the first row in the page
+    pub start: usize,
+    /// Total rows in the CSV, for sizing scrollbars and page counts
+    pub row_count: usize,
+    pub has_more: bool,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+/// Converts a cell to a JSON value matching its column's inferred type: numbers for
+/// numeric columns, digit strings for integers past ±2^53, ISO 8601 strings for dates,
+/// null for empty cells and the raw text otherwise or when the value doesn't parse
+pub fn typed_value(value: &str, metadata: Option<&ColumnMetadata>) -> Value {
+    if value.trim().is_empty() {
+        return Value::Null;
+    }
+    let Some(metadata) = metadata else {
+        return Value::String(value.to_string());
+    };
+
+    let typed = match metadata.data_type {
+        // JavaScript numbers would round them, so they stay exact as text
+        DataType::Integer if metadata.big_integer.is_some() => {
+            plain_integer(value, &metadata.number_format.unwrap_or_default()).map(Value::String)
+        }
+        // Whole numbers stay integers, so JSON output reads `1` rather than `1.0`
+        DataType::Integer => metadata
+            .number_format
+            .unwrap_or_default()
+            .parse(value)
+            .and_then(
+                |number| match number.fract() == 0.0 && number.abs() < I64_LIMIT {
+                    true => Some(Number::from(number as i64)),
+                    false => Number::from_f64(number),
+                },
+            )
+            .map(Value::Number),
+        DataType::Decimal | DataType::Currency => metadata
+            .number_format
+            .unwrap_or_default()
+            .parse(value)
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        // Quantities are given in the column's normalized unit
+        DataType::Quantity => metadata
+            .quantity
+            .as_ref()
+            .and_then(|quantity| quantity.normalized_value(value))
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        DataType::Date => DateType::normalize(value).map(Value::String),
+        _ => None,
+    };
+    typed.unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+impl CSV {
+    /// Up to `limit` rows starting at `start`, typed from the column metadata
+    pub fn row_page(&self, start: usize, limit: usize) -> Result<RowPage, AnalysisError> {
+        let row_count = self.row_count();
+        if start > row_count {
+            return Err(AnalysisError::RowOutOfBounds { row: start });
+        }
+        let end = start.saturating_add(limit).min(row_count);
+
+        let columns = self.get_columns();
+        let rows = (start..end)
+            .map(|row| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (header, values))| {
+                        let value = values.get(row).map_or("", |v| v.as_str());
+                        (
+                            header.to_string(),
+                            typed_value(value, self.column_metadata(index)),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(RowPage {
+            start,
+            row_count,
+            has_more: end < row_count,
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_rows() {
+        let data = "name,age,joined,weight
+Ada,36,2024-01-15,60 kg
+Alan,,2024-02-01,72 kg
+Grace,85,2024-03-09,500 g";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let page = csv.row_page(1, 10).unwrap();
+        assert_eq!(page.start, 1);
+        assert_eq!(page.row_count, 3);
+        assert!(!page.has_more);
+        assert_eq!(
+            Value::Object(page.rows[0].clone()),
+            json!({"name": "Alan", "age": null, "joined": "2024-02-01", "weight": 72.0})
+        );
+        assert_eq!(page.rows[1]["weight"], json!(0.5));
+        assert_eq!(page.rows[1]["age"], json!(85));
+    }
+
+    #[test]
+    fn test_paging() {
+        let data = "n\n1\n2\n3\n4\n5";
+        let csv = CSV::from_string(data.to_string()).unwrap();
+
+        let page = csv.row_page(0, 2).unwrap();
+        assert_eq!(page.rows.len(), 2);
+        assert!(page.has_more);
+        // Without inference every value stays text
+        assert_eq!(page.rows[1]["n"], json!("2"));
+
+        assert_eq!(csv.row_page(4, usize::MAX).unwrap().rows.len(), 1);
+        assert!(csv.row_page(5, 2).unwrap().rows.is_empty());
+        assert!(csv.row_page(6, 2).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use crate::csv::CSV;
+use crate::error::AnalysisError;
+use crate::random::{RandomSource, SplitMix64};
+use crate::warning::{AnalysisWarning, Severity, WarningCode};
+
+/// Picks `n` of the given rows uniformly without replacement, returned in their original
+/// order; all rows when there are fewer than `n`
+pub fn sample_indices(mut rows: Vec<usize>, n: usize, rng: &mut impl RandomSource) -> Vec<usize> {
+    let n = n.min(rows.len());
+    // Partial Fisher-Yates shuffle: only the first n positions are settled
+    for i in 0..n {
+        let j = i + rng.below(rows.len() - i);
+        rows.swap(i, j);
+    }
+    rows.truncate(n);
+    rows.sort_unstable();
+    rows
+}
+
+// Rows of a CSV picked at random, with a warning naming the seed that reproduces them
+fn sampled(csv: &CSV, rows: &[usize], seed: u32) -> CSV {
+    let mut sample = csv.select_rows(rows);
+    sample.push_parse_warning(AnalysisWarning {
+        code: WarningCode::SampledInput,
+        severity: Severity::Info,
+        message: format!(
+            "{} of {} rows sampled with seed {}; statistics describe the sample",
+            rows.len(),
+            csv.row_count(),
+            seed
+        ),
+        column: None,
+        rows: Vec::new(),
+    });
+    sample
+}
+
+// Rows of each distinct value of a column, in order of first appearance
+fn row_groups(values: &[String]) -> Vec<Vec<usize>> {
+    let mut group_index: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (row, value) in values.iter().enumerate() {
+        let index = *group_index.entry(value.trim()).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(row);
+    }
+    groups
+}
+
+impl CSV {
+    /// Returns a new CSV with `n` rows picked at random; the same seed gives the same rows.
+    /// Its `warnings` name the seed.
+    pub fn sample(&self, n: usize, seed: u32) -> CSV {
+        let mut rng = SplitMix64::new(seed as u64);
+        let rows = sample_indices((0..self.row_count()).collect(), n, &mut rng);
+        sampled(self, &rows, seed)
+    }
+
+    /// Returns a new CSV with up to `n_per_group` random rows for every distinct value
+    /// of a column, so rare groups stay represented
+    pub fn stratified_sample_rows(
+        &self,
+        column: usize,
+        n_per_group: usize,
+        seed: u32,
+    ) -> Result<CSV, AnalysisError> {
+        let (_, values) = self
+            .get_column(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+
+        // Groups are visited in order of first appearance to keep the sample deterministic
+        let mut rng = SplitMix64::new(seed as u64);
+        let mut rows: Vec<usize> = row_groups(values)
+            .into_iter()
+            .flat_map(|group| sample_indices(group, n_per_group, &mut rng))
+            .collect();
+        rows.sort_unstable();
+        Ok(sampled(self, &rows, seed))
+    }
+
+    /// Splits the rows at random into a training CSV with `ratio` (between 0 and 1) of them
+    /// and a test CSV with the rest, both in their original order. With `stratify_by`, every
+    /// value of that column is split by the ratio on its own, so rare classes show up in both.
+    pub fn train_test_split(
+        &self,
+        ratio: f64,
+        seed: u32,
+        stratify_by: Option<usize>,
+    ) -> Result<(CSV, CSV), AnalysisError> {
+        if !(ratio > 0.0 && ratio < 1.0) {
+            return Err(AnalysisError::invalid_argument(format!(
+                "The training ratio must be between 0 and 1, not {}",
+                ratio
+            )));
+        }
+        let groups = match stratify_by {
+            Some(column) => {
+                let (_, values) = self
+                    .get_column(column)
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+                row_groups(values)
+            }
+            None => vec![(0..self.row_count()).collect()],
+        };
+
+        let mut rng = SplitMix64::new(seed as u64);
+        let mut train = Vec::new();
+        for group in groups {
+            let n = (group.len() as f64 * ratio).round() as usize;
+            train.extend(sample_indices(group, n, &mut rng));
+        }
+        train.sort_unstable();
+        let mut in_train = vec![false; self.row_count()];
+        train.iter().for_each(|&row| in_train[row] = true);
+        let test: Vec<usize> = (0..self.row_count())
+            .filter(|&row| !in_train[row])
+            .collect();
+
+        Ok((sampled(self, &train, seed), sampled(self, &test, seed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_indices() {
+        let mut rng = SplitMix64::new(7);
+        let rows = sample_indices((0..100).collect(), 10, &mut rng);
+        assert_eq!(rows.len(), 10);
+        assert!(
+            rows.windows(2).all(|w| w[0] < w[1]),
+            "rows keep their order"
+        );
+
+        let mut rng = SplitMix64::new(7);
+        assert_eq!(rows, sample_indices((0..100).collect(), 10, &mut rng));
+
+        let mut rng = SplitMix64::new(8);
+        assert_ne!(rows, sample_indices((0..100).collect(), 10, &mut rng));
+
+        assert_eq!(sample_indices(vec![3, 5], 10, &mut rng), vec![3, 5]);
+    }
+
+    #[test]
+    fn test_csv_samples() {
+        let mut data = "id,group\n".to_string();
+        for i in 0..50 {
+            data.push_str(&format!(
+                "{},{}\n",
+                i,
+                if i < 45 { "common" } else { "rare" }
+            ));
+        }
+        let csv = CSV::from_string(data).unwrap();
+
+        let sample = csv.sample(5, 42);
+        assert_eq!(sample.row_count(), 5);
+        assert_eq!(sample.column_count(), 2);
+        assert_eq!(
+            sample.get_column(0).unwrap().1,
+            csv.sample(5, 42).get_column(0).unwrap().1
+        );
+        let warning = &sample.warnings()[0];
+        assert_eq!(warning.code, WarningCode::SampledInput);
+        assert!(warning.message.contains("seed 42"));
+
+        let stratified = csv.stratified_sample_rows(1, 3, 1).unwrap();
+        let (_, groups) = stratified.get_column(1).unwrap();
+        assert_eq!(groups.iter().filter(|g| *g == "common").count(), 3);
+        assert_eq!(groups.iter().filter(|g| *g == "rare").count(), 3);
+
+        assert!(csv.stratified_sample_rows(2, 3, 1).is_err());
+    }
+
+    #[test]
+    fn test_train_test_split() {
+        let mut data = "id,label\n".to_string();
+        for i in 0..100 {
+            data.push_str(&format!(
+                "{},{}\n",
+                i,
+                if i % 10 == 0 { "yes" } else { "no" }
+            ));
+        }
+        let csv = CSV::from_string(data).unwrap();
+        let ids = |csv: &CSV| csv.get_column(0).unwrap().1.to_vec();
+
+        let (train, test) = csv.train_test_split(0.8, 3, None).unwrap();
+        assert_eq!((train.row_count(), test.row_count()), (80, 20));
+        let mut all: Vec<usize> = ids(&train)
+            .iter()
+            .chain(&ids(&test))
+            .map(|id| id.parse().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..100).collect::<Vec<_>>(), "every row lands once");
+        assert_eq!(
+            ids(&train),
+            ids(&csv.train_test_split(0.8, 3, None).unwrap().0)
+        );
+
+        // Each label is split 80/20 on its own
+        let (train, test) = csv.train_test_split(0.8, 3, Some(1)).unwrap();
+        let yes = |csv: &CSV| {
+            csv.get_column(1)
+                .unwrap()
+                .1
+                .iter()
+                .filter(|v| *v == "yes")
+                .count()
+        };
+        assert_eq!((yes(&train), yes(&test)), (8, 2));
+
+        assert!(csv.train_test_split(1.0, 3, None).is_err());
+        assert!(csv.train_test_split(0.5, 3, Some(4)).is_err());
+    }
+}
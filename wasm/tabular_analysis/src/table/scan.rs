@@ -0,0 +1,148 @@
+/// Bytes that end a field or a row, or start a quoted field
+const STRUCTURAL: [u8; 4] = [b',', b'\n', b'\r', b'"'];
+
+// Bytes compared at once by the vector paths
+const LANES: usize = 16;
+
+/// Whether this build compares sixteen bytes at a time: the `simd` feature on x86_64 (SSE2),
+/// or on wasm compiled with `-C target-feature=+simd128`. Splitting text is only faster than
+/// the CSV reader when it does.
+pub const VECTORIZED: bool = cfg!(all(
+    feature = "simd",
+    any(
+        all(target_arch = "wasm32", target_feature = "simd128"),
+        target_arch = "x86_64"
+    )
+));
+
+/// Positions of every comma, line break and quote in `bytes`, in order. Each 16-byte chunk is
+/// compared once into a bit mask whose set bits are then handed out, so runs of plain text
+/// cost one comparison per chunk rather than one per byte.
+pub fn structural_positions(bytes: &[u8]) -> StructuralPositions<'_> {
+    StructuralPositions {
+        bytes,
+        chunk: 0,
+        next_chunk: 0,
+        mask: 0,
+    }
+}
+
+pub struct StructuralPositions<'a> {
+    bytes: &'a [u8],
+    // Start of the chunk `mask` was computed for, and of the one after it
+    chunk: usize,
+    next_chunk: usize,
+    // Structural bytes of the chunk not handed out yet
+    mask: u32,
+}
+
+impl Iterator for StructuralPositions<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.mask == 0 {
+            if self.next_chunk >= self.bytes.len() {
+                return None;
+            }
+            let end = (self.next_chunk + LANES).min(self.bytes.len());
+            self.mask = chunk_mask(&self.bytes[self.next_chunk..end]);
+            self.chunk = self.next_chunk;
+            self.next_chunk = end;
+        }
+        let bit = self.mask.trailing_zeros() as usize;
+        self.mask &= self.mask - 1;
+        Some(self.chunk + bit)
+    }
+}
+
+// Bit i set when byte i of a chunk of at most 16 bytes is structural
+fn chunk_mask(chunk: &[u8]) -> u32 {
+    #[cfg(all(
+        feature = "simd",
+        any(
+            all(target_arch = "wasm32", target_feature = "simd128"),
+            target_arch = "x86_64"
+        )
+    ))]
+    if chunk.len() == LANES {
+        // SAFETY: the cfg only compiles this where the mask's target feature is enabled for
+        // the whole build; SSE2 is part of every x86_64 CPU
+        return unsafe { structural_mask(chunk) };
+    }
+    chunk
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| STRUCTURAL.contains(b))
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+// Vector form of `chunk_mask` for a full 16-byte chunk
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+#[target_feature(enable = "simd128")]
+fn structural_mask(chunk: &[u8]) -> u32 {
+    use core::arch::wasm32::{u8x16_bitmask, u8x16_eq, u8x16_splat, v128, v128_load, v128_or};
+
+    // SAFETY: the chunk holds 16 bytes and v128_load doesn't need them aligned
+    let block = unsafe { v128_load(chunk.as_ptr() as *const v128) };
+    let [comma, newline, carriage_return, quote] = STRUCTURAL;
+    let hits = v128_or(
+        v128_or(
+            u8x16_eq(block, u8x16_splat(comma)),
+            u8x16_eq(block, u8x16_splat(newline)),
+        ),
+        v128_or(
+            u8x16_eq(block, u8x16_splat(carriage_return)),
+            u8x16_eq(block, u8x16_splat(quote)),
+        ),
+    );
+    u8x16_bitmask(hits) as u32
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+fn structural_mask(chunk: &[u8]) -> u32 {
+    use std::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128, _mm_set1_epi8,
+    };
+
+    // SAFETY: the chunk holds 16 bytes and _mm_loadu_si128 doesn't need them aligned
+    let block = unsafe { _mm_loadu_si128(chunk.as_ptr() as *const __m128i) };
+    let [comma, newline, carriage_return, quote] = STRUCTURAL.map(|b| b as i8);
+    let hits = _mm_or_si128(
+        _mm_or_si128(
+            _mm_cmpeq_epi8(block, _mm_set1_epi8(comma)),
+            _mm_cmpeq_epi8(block, _mm_set1_epi8(newline)),
+        ),
+        _mm_or_si128(
+            _mm_cmpeq_epi8(block, _mm_set1_epi8(carriage_return)),
+            _mm_cmpeq_epi8(block, _mm_set1_epi8(quote)),
+        ),
+    );
+    _mm_movemask_epi8(hits) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structural_positions() {
+        let text = "plain text without separators, then \"quoted\"\r\nand a long tail of \
+                    letters past the sixteen byte chunks\n";
+        let expected: Vec<usize> = text
+            .bytes()
+            .enumerate()
+            .filter(|(_, b)| STRUCTURAL.contains(b))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(
+            structural_positions(text.as_bytes()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            structural_positions(b"no separators here at all").next(),
+            None
+        );
+        assert_eq!(structural_positions(b"").next(), None);
+    }
+}
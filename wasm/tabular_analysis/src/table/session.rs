@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::cleaning::audit::CleaningStep;
+use crate::config::AnalysisConfig;
+use crate::csv::{ColumnMetadata, Snapshot, CSV};
+use crate::error::AnalysisError;
+use crate::table::dialect::Dialect;
+use crate::table::history::History;
+use crate::table::lineage::ColumnLineage;
+use crate::warning::AnalysisWarning;
+
+/// Version written into exported sessions; bump it when the layout changes incompatibly
+pub const SESSION_VERSION: u32 = 1;
+
+// Sessions start with these bytes, then one byte saying whether the rest is deflated JSON
+const SESSION_MAGIC: &[u8; 4] = b"TASN";
+const PLAIN: u8 = 0;
+const DEFLATED: u8 = 1;
+const COMPRESSION_LEVEL: u8 = 6;
+
+#[derive(Serialize, Deserialize)]
+struct SessionColumn {
+    header: String,
+    // Index into the session's value lists, shared by the snapshots holding the same values
+    values: usize,
+    metadata: Option<ColumnMetadata>,
+    lineage: Option<ColumnLineage>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionTable {
+    columns: Vec<SessionColumn>,
+    row_count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionUndo {
+    operation: String,
+    before: SessionTable,
+    log_len: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionRedo {
+    operation: String,
+    after: SessionTable,
+    steps: Vec<CleaningStep>,
+}
+
+// Everything needed to rebuild a CSV; `V` is borrowed when writing and owned when reading
+#[derive(Serialize, Deserialize)]
+struct Session<V> {
+    version: u32,
+    config: AnalysisConfig,
+    #[serde(default)]
+    dialect: Dialect,
+    table: SessionTable,
+    cleaning_log: Vec<CleaningStep>,
+    undo: Vec<SessionUndo>,
+    redo: Vec<SessionRedo>,
+    parse_warnings: Vec<AnalysisWarning>,
+    parse_ms: f64,
+    values: Vec<V>,
+}
+
+// Collects each distinct list of column values once, however many snapshots share it
+#[derive(Default)]
+struct ValuePool {
+    values: Vec<Rc<Vec<String>>>,
+    positions: HashMap<*const Vec<String>, usize>,
+}
+
+impl ValuePool {
+    fn table(&mut self, snapshot: &Snapshot) -> SessionTable {
+        let (columns, row_count) = snapshot.parts();
+        let columns = columns
+            .into_iter()
+            .map(|(header, values, metadata, lineage)| {
+                let values = *self
+                    .positions
+                    .entry(Rc::as_ptr(&values))
+                    .or_insert_with(|| {
+                        self.values.push(Rc::clone(&values));
+                        self.values.len() - 1
+                    });
+                SessionColumn {
+                    header,
+                    values,
+                    metadata,
+                    lineage,
+                }
+            })
+            .collect();
+        SessionTable { columns, row_count }
+    }
+}
+
+fn invalid_session(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidMetadata {
+        message: format!("Invalid session: {}", message.into()),
+    }
+}
+
+fn snapshot(table: SessionTable, values: &[Rc<Vec<String>>]) -> Result<Snapshot, AnalysisError> {
+    let columns = table
+        .columns
+        .into_iter()
+        .map(|column| {
+            let shared = values
+                .get(column.values)
+                .ok_or_else(|| invalid_session("column values missing"))?;
+            Ok((
+                column.header,
+                Rc::clone(shared),
+                column.metadata,
+                column.lineage,
+            ))
+        })
+        .collect::<Result<Vec<_>, AnalysisError>>()?;
+    Ok(Snapshot::from_parts(columns, table.row_count))
+}
+
+impl CSV {
+    /// Saves the table, column metadata, cleaning log, undo and redo history and analysis
+    /// options into one blob that `import_session` restores, e.g. to keep sessions in
+    /// IndexedDB. Values shared between the table and its undo snapshots are stored once;
+    /// `compress` deflates the blob.
+    pub fn export_session(&self, compress: bool) -> Result<Vec<u8>, AnalysisError> {
+        let mut pool = ValuePool::default();
+        let table = pool.table(&self.snapshot());
+        let (undo, redo) = self.undo_history().parts();
+        let undo = undo
+            .into_iter()
+            .map(|(operation, before, log_len)| SessionUndo {
+                operation,
+                before: pool.table(&before),
+                log_len,
+            })
+            .collect();
+        let redo = redo
+            .into_iter()
+            .map(|(operation, after, steps)| SessionRedo {
+                operation,
+                after: pool.table(&after),
+                steps,
+            })
+            .collect();
+
+        let session = Session {
+            version: SESSION_VERSION,
+            config: self.analysis_config().clone(),
+            dialect: self.dialect().clone(),
+            table,
+            cleaning_log: self.cleaning_steps().to_vec(),
+            undo,
+            redo,
+            parse_warnings: self.parse_warnings().to_vec(),
+            parse_ms: self.parse_time_ms(),
+            values: pool.values.iter().map(|v| v.as_slice()).collect(),
+        };
+        let json = serde_json::to_vec(&session).map_err(|e| AnalysisError::SerializationError {
+            message: format!("Failed to serialize session: {}", e),
+        })?;
+
+        let mut blob = SESSION_MAGIC.to_vec();
+        if compress {
+            blob.push(DEFLATED);
+            blob.extend(miniz_oxide::deflate::compress_to_vec(
+                &json,
+                COMPRESSION_LEVEL,
+            ));
+        } else {
+            blob.push(PLAIN);
+            blob.extend(json);
+        }
+        Ok(blob)
+    }
+
+    /// Restores a CSV saved by `export_session`, rejecting versions newer than this one
+    pub fn import_session(blob: &[u8]) -> Result<CSV, AnalysisError> {
+        let body = blob
+            .strip_prefix(SESSION_MAGIC.as_slice())
+            .ok_or_else(|| invalid_session("not written by export_session"))?;
+        let json = match body.split_first() {
+            Some((&PLAIN, json)) => json.to_vec(),
+            Some((&DEFLATED, deflated)) => miniz_oxide::inflate::decompress_to_vec(deflated)
+                .map_err(|e| invalid_session(format!("corrupt compressed data ({:?})", e)))?,
+            _ => return Err(invalid_session("unknown encoding")),
+        };
+        let session: Session<Vec<String>> =
+            serde_json::from_slice(&json).map_err(|e| invalid_session(e.to_string()))?;
+        if session.version > SESSION_VERSION {
+            return Err(invalid_session(format!(
+                "version {} is newer than the supported version {}",
+                session.version, SESSION_VERSION
+            )));
+        }
+        session.config.validate()?;
+
+        let values: Vec<Rc<Vec<String>>> = session.values.into_iter().map(Rc::new).collect();
+        let table = snapshot(session.table, &values)?;
+        let undo = session
+            .undo
+            .into_iter()
+            .map(|e| Ok((e.operation, snapshot(e.before, &values)?, e.log_len)))
+            .collect::<Result<Vec<_>, AnalysisError>>()?;
+        let redo = session
+            .redo
+            .into_iter()
+            .map(|e| Ok((e.operation, snapshot(e.after, &values)?, e.steps)))
+            .collect::<Result<Vec<_>, AnalysisError>>()?;
+
+        Ok(CSV::from_session(
+            table,
+            session.config,
+            session.cleaning_log,
+            History::from_parts(undo, redo),
+            session.dialect,
+            session.parse_warnings,
+            session.parse_ms,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    fn edited_csv() -> CSV {
+        let data = "id,status,name\n1,open,Ada\n2,gone,Alan\n3,open,Grace";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.with_history("replace", |csv| csv.replace_in_column(1, "gone", "closed"))
+            .unwrap();
+        csv.with_history("split", |csv| csv.split_column_values(2, "a", false))
+            .unwrap();
+        csv.undo_operation().unwrap();
+        csv
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let csv = edited_csv();
+        for compress in [false, true] {
+            let blob = csv.export_session(compress).unwrap();
+            let mut restored = CSV::import_session(&blob).unwrap();
+
+            assert_eq!(restored.headers(), csv.headers());
+            assert_eq!(restored.get_column(1).unwrap().1[1], "closed");
+            assert_eq!(
+                restored.column_metadata(0).unwrap().data_type,
+                DataType::Integer
+            );
+            assert_eq!(restored.cleaning_steps(), csv.cleaning_steps());
+            assert_eq!(restored.column_lineage(1).unwrap().steps.len(), 1);
+            assert_eq!(restored.history_report(), csv.history_report());
+
+            restored.redo_operation().unwrap();
+            assert_eq!(restored.column_count(), 5);
+            restored.undo_operation().unwrap();
+            restored.undo_operation().unwrap();
+            assert_eq!(restored.get_column(1).unwrap().1[1], "gone");
+        }
+    }
+
+    #[test]
+    fn test_shared_values_stored_once() {
+        let csv = edited_csv();
+        let blob = csv.export_session(false).unwrap();
+        let session: Session<Vec<String>> = serde_json::from_slice(&blob[5..]).unwrap();
+        // Three columns before the replace, the replaced column, and the two split columns
+        assert_eq!(session.values.len(), 6);
+
+        let compressed = csv.export_session(true).unwrap();
+        assert!(compressed.len() < blob.len());
+    }
+
+    #[test]
+    fn test_rejects_foreign_blobs() {
+        assert!(CSV::import_session(b"id,name\n1,Ada").is_err());
+
+        let mut blob = edited_csv().export_session(false).unwrap();
+        let json = String::from_utf8(blob.split_off(5)).unwrap();
+        blob.extend(
+            json.replacen("\"version\":1", "\"version\":99", 1)
+                .into_bytes(),
+        );
+        let error = CSV::import_session(&blob).unwrap_err();
+        assert!(error.to_string().contains("newer"));
+    }
+}
@@ -0,0 +1,258 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::config::AnalysisConfig;
+use crate::csv::{ColumnMetadata, CSV};
+use crate::error::AnalysisError;
+use crate::random::SplitMix64;
+use crate::table::rows::typed_value;
+use crate::table::sample::sample_indices;
+
+// What a cell sorts by: ordinal levels by rank, numbers by value, other text as written
+#[derive(Debug, PartialEq, PartialOrd)]
+enum SortKey {
+    Rank(usize),
+    Number(f64),
+    Text(String),
+}
+
+fn sort_key(value: &str, metadata: Option<&ColumnMetadata>) -> Option<SortKey> {
+    if let Some(rank) = metadata
+        .and_then(|m| m.ordinal.as_ref())
+        .and_then(|scale| scale.rank(value))
+    {
+        return Some(SortKey::Rank(rank));
+    }
+    match typed_value(value, metadata) {
+        Value::Null => None,
+        Value::Number(number) => number.as_f64().map(SortKey::Number),
+        _ => Some(SortKey::Text(value.trim().to_string())),
+    }
+}
+
+// A column of the parent CSV; the values are shared, not copied
+#[derive(Debug, Clone)]
+struct ViewColumn {
+    header: String,
+    values: Rc<Vec<String>>,
+    metadata: Option<ColumnMetadata>,
+}
+
+/// A selection of rows and columns of a CSV that shares the parent's values instead of
+/// copying them, so filters, column selections and samples can be chained cheaply.
+/// Later edits to the parent don't show up in the view.
+#[derive(Debug, Clone)]
+pub struct CsvView {
+    columns: Vec<ViewColumn>,
+    /// Parent row index of every row in the view
+    rows: Vec<usize>,
+    config: AnalysisConfig,
+}
+
+impl CSV {
+    /// Returns a view of every row and column of this CSV
+    pub fn view(&self) -> CsvView {
+        let columns = self
+            .get_columns()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (header, _))| {
+                Some(ViewColumn {
+                    header: header.to_string(),
+                    values: self.shared_values(index)?,
+                    metadata: self.column_metadata(index).cloned(),
+                })
+            })
+            .collect();
+
+        CsvView {
+            columns,
+            rows: (0..self.row_count()).collect(),
+            config: self.analysis_config().clone(),
+        }
+    }
+}
+
+impl CsvView {
+    /// Rows selected in the parent CSV
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn headers(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.header.as_str()).collect()
+    }
+
+    /// Keeps `n` random rows; the same seed gives the same rows
+    pub fn sample(&self, n: usize, seed: u32) -> CsvView {
+        let mut rng = SplitMix64::new(seed as u64);
+        let positions = sample_indices((0..self.rows.len()).collect(), n, &mut rng);
+        CsvView {
+            rows: positions.into_iter().map(|i| self.rows[i]).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Copies the view's rows and columns into a standalone CSV
+    pub fn materialize(&self) -> CSV {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| {
+                let values = self
+                    .rows
+                    .iter()
+                    .map(|&row| column.values[row].clone())
+                    .collect();
+                (column.header.clone(), values, column.metadata.clone())
+            })
+            .collect();
+        CSV::from_parts(columns, self.config.clone())
+    }
+
+    /// Keeps only the given columns, in the given order
+    pub fn select(&self, columns: &[usize]) -> Result<CsvView, AnalysisError> {
+        let columns = columns
+            .iter()
+            .map(|&index| {
+                self.columns
+                    .get(index)
+                    .cloned()
+                    .ok_or(AnalysisError::ColumnOutOfBounds { column: index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CsvView {
+            columns,
+            rows: self.rows.clone(),
+            config: self.config.clone(),
+        })
+    }
+
+    /// Keeps the rows whose value in a column equals `value`, ignoring surrounding whitespace
+    pub fn filter_rows(&self, column: usize, value: &str) -> Result<CsvView, AnalysisError> {
+        let values = &self
+            .columns
+            .get(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?
+            .values;
+        let value = value.trim();
+
+        Ok(CsvView {
+            rows: self
+                .rows
+                .iter()
+                .copied()
+                .filter(|&row| values[row].trim() == value)
+                .collect(),
+            ..self.clone()
+        })
+    }
+
+    /// Orders the rows by a column: ordinal columns by the order of their levels, numeric
+    /// columns by value and others alphabetically. Empty cells come last either way, and
+    /// rows with equal values keep their order.
+    pub fn sort_by(&self, column: usize, descending: bool) -> Result<CsvView, AnalysisError> {
+        let column = self
+            .columns
+            .get(column)
+            .ok_or(AnalysisError::ColumnOutOfBounds { column })?;
+        let mut keyed: Vec<(Option<SortKey>, usize)> = self
+            .rows
+            .iter()
+            .map(|&row| (sort_key(&column.values[row], column.metadata.as_ref()), row))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => {
+                let order = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+
+        Ok(CsvView {
+            rows: keyed.into_iter().map(|(_, row)| row).collect(),
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataType;
+
+    #[test]
+    fn test_chained_view() {
+        let data = "name,team,score\nAda,red,3\nAlan,blue,5\nGrace,red,8\nEdsger,red,1";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+
+        let view = csv
+            .view()
+            .filter_rows(1, " red ")
+            .unwrap()
+            .select(&[2, 0])
+            .unwrap();
+        assert_eq!(view.row_count(), 3);
+        assert_eq!(view.column_count(), 2);
+        assert!(
+            Rc::ptr_eq(&view.columns[1].values, &csv.shared_values(0).unwrap()),
+            "views share the parent's values"
+        );
+
+        let copy = view.materialize();
+        assert_eq!(copy.row_count(), 3);
+        assert_eq!(copy.get_column(0).unwrap().0, "score");
+        assert_eq!(copy.get_column(0).unwrap().1, &["3", "8", "1"]);
+        assert_eq!(copy.get_column(1).unwrap().1, &["Ada", "Grace", "Edsger"]);
+        assert!(
+            copy.column_metadata(0).is_some(),
+            "metadata is carried over"
+        );
+
+        assert_eq!(view.sample(2, 9).row_count(), 2);
+        assert!(view.select(&[5]).is_err());
+        assert!(view.filter_rows(7, "x").is_err());
+    }
+
+    #[test]
+    fn test_parent_edits_are_not_shared() {
+        let mut csv = CSV::from_string("a\n1\n2".to_string()).unwrap();
+        let view = csv.view();
+        csv.column_values_mut(0).unwrap()[0] = "9".to_string();
+
+        assert_eq!(view.materialize().get_column(0).unwrap().1, &["1", "2"]);
+        assert_eq!(csv.get_column(0).unwrap().1, &["9", "2"]);
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let data = "size,price\nL,10\nS,9.5\n,3\nXL,12\nM,\nS,100";
+        let mut csv = CSV::from_string(data.to_string()).unwrap();
+        csv.infer_column_types();
+        csv.override_type(0, DataType::Categorical).unwrap();
+        let column =
+            |view: &CsvView, index: usize| view.materialize().get_column(index).unwrap().1.to_vec();
+
+        let by_size = csv.view().sort_by(0, false).unwrap();
+        assert_eq!(column(&by_size, 0), vec!["S", "S", "M", "L", "XL", ""]);
+        let by_price = csv.view().sort_by(1, true).unwrap();
+        assert_eq!(
+            column(&by_price, 1),
+            vec!["100", "12", "10", "9.5", "3", ""]
+        );
+        assert!(csv.view().sort_by(2, false).is_err());
+    }
+}
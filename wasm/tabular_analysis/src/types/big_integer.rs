@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::number_format::NumberFormat;
+
+// Largest magnitude every whole number up to which an f64 holds exactly
+pub const MAX_SAFE_INTEGER: i128 = 1 << 53;
+// Most digits a NUMERIC(p,0) takes in every dialect
+const MAX_NUMERIC_DIGITS: usize = 38;
+
+/// Whole numbers too large for a float to hold exactly, beyond ±2^53, such as 20-digit IDs
+/// or snowflake keys. Their values are kept as digit strings, since parsing them as numbers
+/// would change their last digits.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BigIntegerColumn {
+    /// Smallest and largest values as plain digits, e.g. `-92233720368547758080`
+    pub min: String,
+    pub max: String,
+    /// Most digits of any value, without its sign
+    pub max_digits: usize,
+    /// Whether every value fits a 64-bit BIGINT
+    pub fits_i64: bool,
+}
+
+/// Orders whole numbers written as plain digits without parsing them: by sign, then by
+/// number of digits, then digit by digit
+pub fn compare_integers(a: &str, b: &str) -> Ordering {
+    let magnitude = |value: &str| {
+        let digits = value.trim_start_matches('-').trim_start_matches('0');
+        (digits.len(), digits.to_string())
+    };
+    match (a.starts_with('-'), b.starts_with('-')) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => magnitude(a).cmp(&magnitude(b)),
+        (true, true) => magnitude(b).cmp(&magnitude(a)),
+    }
+}
+
+/// Orders a whole number written as plain digits against a float, exactly when the float is
+/// a whole number and by the digits' nearest float otherwise
+pub fn compare_integer_with_float(digits: &str, number: f64) -> Option<Ordering> {
+    // i128 holds every whole float below 1e38 exactly
+    if number.fract() == 0.0 && number.abs() < 1e38 {
+        return Some(compare_integers(digits, &(number as i128).to_string()));
+    }
+    digits.parse::<f64>().ok()?.partial_cmp(&number)
+}
+
+/// A value of an integer column as plain digits, e.g. `-1234` for `(1,234)`, or None when
+/// it isn't a whole number in the format
+pub fn plain_integer(value: &str, format: &NumberFormat) -> Option<String> {
+    let plain = format.plain_digits(value)?;
+    (!plain.contains('.')).then_some(plain)
+}
+
+impl BigIntegerColumn {
+    /// Detects a column of whole numbers with at least one beyond ±2^53; any value that
+    /// isn't a whole number in the format rules the column out
+    pub fn detect(values: &[String], format: &NumberFormat) -> Option<BigIntegerColumn> {
+        let mut column: Option<BigIntegerColumn> = None;
+        let mut beyond_float = false;
+
+        for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            let plain = plain_integer(value, format)?;
+            let parsed = plain.parse::<i128>().ok();
+            beyond_float |= parsed.is_none_or(|n| n.abs() > MAX_SAFE_INTEGER);
+            let fits_i64 = parsed.is_some_and(|n| i64::try_from(n).is_ok());
+            let digits = plain.trim_start_matches('-').trim_start_matches('0').len();
+
+            match &mut column {
+                Some(column) => {
+                    if compare_integers(&plain, &column.min) == Ordering::Less {
+                        column.min = plain.clone();
+                    }
+                    if compare_integers(&plain, &column.max) == Ordering::Greater {
+                        column.max = plain;
+                    }
+                    column.max_digits = column.max_digits.max(digits);
+                    column.fits_i64 &= fits_i64;
+                }
+                None => {
+                    column = Some(BigIntegerColumn {
+                        min: plain.clone(),
+                        max: plain,
+                        max_digits: digits,
+                        fits_i64,
+                    })
+                }
+            }
+        }
+
+        column.filter(|_| beyond_float)
+    }
+
+    /// BIGINT while every value fits 64 bits, then NUMERIC(p,0) up to 38 digits and text
+    /// past that
+    pub fn sql_type(&self) -> String {
+        if self.fits_i64 {
+            "BIGINT".to_string()
+        } else if self.max_digits <= MAX_NUMERIC_DIGITS {
+            format!("NUMERIC({},0)", self.max_digits)
+        } else {
+            // One more character for the sign
+            format!("VARCHAR({})", self.max_digits + 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detection() {
+        let format = NumberFormat::default();
+        let ids = BigIntegerColumn::detect(
+            &column(&["12345678901234567890", "", "-98765432109876543210", "7"]),
+            &format,
+        )
+        .unwrap();
+        assert_eq!(ids.min, "-98765432109876543210");
+        assert_eq!(ids.max, "12345678901234567890");
+        assert_eq!(ids.max_digits, 20);
+        assert!(!ids.fits_i64);
+
+        // 2^53 + 1 is the first integer a float can't hold
+        let snowflake = BigIntegerColumn::detect(&column(&["9007199254740993"]), &format).unwrap();
+        assert!(snowflake.fits_i64);
+        assert!(BigIntegerColumn::detect(&column(&["9007199254740992", "12"]), &format).is_none());
+        assert!(
+            BigIntegerColumn::detect(&column(&["12345678901234567890", "1.5"]), &format).is_none()
+        );
+    }
+
+    #[test]
+    fn test_sql_type() {
+        let format = NumberFormat::default();
+        let detect = |values: &[&str]| BigIntegerColumn::detect(&column(values), &format).unwrap();
+        assert_eq!(detect(&["9007199254740993"]).sql_type(), "BIGINT");
+        assert_eq!(
+            detect(&["12345678901234567890"]).sql_type(),
+            "NUMERIC(20,0)"
+        );
+        assert_eq!(detect(&[&"9".repeat(40)]).sql_type(), "VARCHAR(41)");
+    }
+}
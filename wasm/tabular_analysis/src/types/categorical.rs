@@ -1,6 +1,6 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use std::collections::{HashMap, HashSet};
 
 // Constants for categorical detection
@@ -11,17 +11,18 @@ const MAX_CATEGORY_LENGTH: usize = 100; // Maximum reasonable length for a categ
 const MIN_NON_EMPTY_RATIO: f64 = 0.5; // At least 50% of values should be non-empty
 
 // Common categorical patterns
-static CATEGORICAL_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
+static CATEGORICAL_PATTERNS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
         // Common boolean patterns
-        Regex::new(r"^(?i)(true|false|yes|no|y|n|t|f)$").unwrap(),
+        r"^(?i)(true|false|yes|no|y|n|t|f)$",
         // Common rating patterns
-        Regex::new(r"^(?i)(high|medium|low|critical|major|minor)$").unwrap(),
+        r"^(?i)(high|medium|low|critical|major|minor)$",
         // Common status patterns
-        Regex::new(r"^(?i)(active|inactive|pending|completed|cancelled|failed|success)$").unwrap(),
+        r"^(?i)(active|inactive|pending|completed|cancelled|failed|success)$",
         // Common level patterns
-        Regex::new(r"^(?i)(beginner|intermediate|advanced|expert)$").unwrap(),
-    ]
+        r"^(?i)(beginner|intermediate|advanced|expert)$",
+    ])
+    .unwrap()
 });
 
 // Common categorical column name patterns
@@ -52,10 +53,7 @@ impl TypeDetection for CategoricalType {
         }
 
         // Check against categorical patterns
-        if CATEGORICAL_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(value))
-        {
+        if CATEGORICAL_PATTERNS.is_match(value) {
             return 0.9;
         }
 
@@ -71,9 +69,7 @@ impl TypeDetection for CategoricalType {
         }
 
         // Check against known categorical patterns
-        CATEGORICAL_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(clean_value))
+        CATEGORICAL_PATTERNS.is_match(clean_value)
     }
 
     fn normalize(value: &str) -> Option<String> {
@@ -193,11 +189,7 @@ impl CategoricalType {
     fn pattern_match_score(values: &[String]) -> f64 {
         let pattern_matches = values
             .iter()
-            .filter(|&value| {
-                CATEGORICAL_PATTERNS
-                    .iter()
-                    .any(|pattern| pattern.is_match(value))
-            })
+            .filter(|&value| CATEGORICAL_PATTERNS.is_match(value))
             .count();
 
         (pattern_matches as f64 / values.len() as f64).min(1.0)
@@ -0,0 +1,203 @@
+use super::TypeDetection;
+use serde::{Deserialize, Serialize};
+
+/// Card networks recognized from the issuer prefix of a card number
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    AmericanExpress,
+    Discover,
+    DinersClub,
+    Jcb,
+    UnionPay,
+}
+
+impl CardBrand {
+    /// Identifies the brand from the leading digits of a card number
+    pub fn from_digits(digits: &str) -> Option<CardBrand> {
+        let prefix =
+            |len: usize| -> u32 { digits.get(..len).and_then(|p| p.parse().ok()).unwrap_or(0) };
+
+        // More specific ranges are checked before the broad single-digit ones
+        match (prefix(1), prefix(2), prefix(3), prefix(4)) {
+            (_, 34 | 37, _, _) => Some(CardBrand::AmericanExpress),
+            (_, _, _, 3528..=3589) => Some(CardBrand::Jcb),
+            (_, 36 | 38, _, _) | (_, _, 300..=305, _) => Some(CardBrand::DinersClub),
+            (_, _, _, 6011) | (_, 65, _, _) | (_, _, 644..=649, _) => Some(CardBrand::Discover),
+            (_, 62, _, _) => Some(CardBrand::UnionPay),
+            (_, 51..=55, _, _) | (_, _, _, 2221..=2720) => Some(CardBrand::Mastercard),
+            (4, _, _, _) => Some(CardBrand::Visa),
+            _ => None,
+        }
+    }
+}
+
+/// Validates a digit string with the Luhn checksum used by payment card numbers
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        let Some(mut digit) = c.to_digit(10) else {
+            return false;
+        };
+        if i % 2 == 1 {
+            digit *= 2;
+            if digit > 9 {
+                digit -= 9;
+            }
+        }
+        sum += digit;
+    }
+    !digits.is_empty() && sum % 10 == 0
+}
+
+// Card digits with the common space and dash grouping removed, if nothing else is present
+fn card_digits(value: &str) -> Option<String> {
+    let value = value.trim();
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == ' ' || c == '-')
+    {
+        return None;
+    }
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    (13..=19).contains(&digits.len()).then_some(digits)
+}
+
+/// Masks all but the last four digits of anything shaped like a card number, whether or not
+/// it passes the checksum, so mistyped numbers shown in reports stay hidden too
+pub fn mask_card_number(value: &str) -> Option<String> {
+    let digits = card_digits(value)?;
+    Some(format!("**** **** **** {}", &digits[digits.len() - 4..]))
+}
+
+#[derive(Debug)]
+pub struct CreditCardType;
+
+impl CreditCardType {
+    /// Brand of a valid card number
+    pub fn brand(value: &str) -> Option<CardBrand> {
+        let digits = card_digits(value)?;
+        if !luhn_valid(&digits) {
+            return None;
+        }
+        CardBrand::from_digits(&digits)
+    }
+}
+
+impl TypeDetection for CreditCardType {
+    fn detect_confidence(value: &str) -> f64 {
+        let Some(digits) = card_digits(value) else {
+            return 0.0;
+        };
+
+        if luhn_valid(&digits) {
+            return 1.0;
+        }
+
+        // Right length but failing the checksum, e.g. a mistyped number or a long identifier
+        0.3
+    }
+
+    fn is_definite_match(value: &str) -> bool {
+        card_digits(value).is_some_and(|digits| luhn_valid(&digits))
+    }
+
+    /// Masks all but the last four digits, so card numbers never leave the browser in full
+    fn normalize(value: &str) -> Option<String> {
+        let digits = card_digits(value)?;
+        if !luhn_valid(&digits) {
+            return None;
+        }
+        mask_card_number(&digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn() {
+        let test_cases = vec![
+            ("4111111111111111", true),
+            ("4111111111111112", false),
+            ("79927398713", true),
+            ("0", true),
+            ("", false),
+            ("4111a11111111111", false),
+        ];
+        for (digits, expected) in test_cases {
+            assert_eq!(luhn_valid(digits), expected, "Luhn check of {}", digits);
+        }
+    }
+
+    #[test]
+    fn test_definite_matches() {
+        let test_cases = vec![
+            ("4111111111111111", true),
+            ("4111 1111 1111 1111", true),
+            ("5500-0000-0000-0004", true),
+            ("378282246310005", true),
+            ("4111111111111112", false), // Fails Luhn
+            ("411111111111", false),     // Too short
+            ("4111.1111.1111.1111", false),
+            ("", false),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(
+                CreditCardType::is_definite_match(input),
+                expected,
+                "Failed for input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_brands() {
+        let test_cases = vec![
+            ("4111111111111111", Some(CardBrand::Visa)),
+            ("5500000000000004", Some(CardBrand::Mastercard)),
+            ("2221000000000009", Some(CardBrand::Mastercard)),
+            ("378282246310005", Some(CardBrand::AmericanExpress)),
+            ("6011111111111117", Some(CardBrand::Discover)),
+            ("30569309025904", Some(CardBrand::DinersClub)),
+            ("3530111333300000", Some(CardBrand::Jcb)),
+            ("6200000000000005", Some(CardBrand::UnionPay)),
+            ("4111111111111112", None),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(
+                CreditCardType::brand(input),
+                expected,
+                "Failed for input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(
+            CreditCardType::normalize("4111-1111-1111-1234"),
+            None,
+            "invalid numbers are not normalized"
+        );
+        assert_eq!(
+            CreditCardType::normalize("4111 1111 1111 1111"),
+            Some("**** **** **** 1111".to_string())
+        );
+        assert_eq!(
+            CreditCardType::normalize("378282246310005"),
+            Some("**** **** **** 0005".to_string())
+        );
+        assert_eq!(
+            mask_card_number("4111-1111-1111-1234").as_deref(),
+            Some("**** **** **** 1234")
+        );
+        assert_eq!(mask_card_number("12345"), None);
+        assert_eq!(CreditCardType::detect_confidence("4111111111111112"), 0.3);
+        assert_eq!(CreditCardType::detect_confidence("12345"), 0.0);
+    }
+}
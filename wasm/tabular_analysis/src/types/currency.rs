@@ -1,6 +1,6 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::RegexSet;
 //TODO: Currently only dollars are supported, support for other currencies is needed
 #[derive(Debug, Clone, Copy)]
 pub enum CurrencySymbol {
@@ -30,26 +30,46 @@ impl CurrencySymbol {
 
     fn format_value(&self, amount: f64) -> String {
         match self {
+            // Negative amounts put the sign before the symbol: -$1234.56
+            CurrencySymbol::USD if amount < 0.0 => format!("-{}{:.2}", self.symbol(), -amount),
             CurrencySymbol::USD => format!("{}{:.2}", self.symbol(), amount),
         }
     }
 }
 
-static CURRENCY_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
+static CURRENCY_PATTERNS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
         // USD patterns only
-        Regex::new(r"^\$\d+(?:,\d{3})*(?:\.\d{2})?$").unwrap(),
-        Regex::new(r"^\d+(?:,\d{3})*(?:\.\d{2})?USD$").unwrap(),
-        Regex::new(r"^USD\d+(?:,\d{3})*(?:\.\d{2})?$").unwrap(),
-    ]
+        r"^\$\d+(?:,\d{3})*(?:\.\d{2})?$",
+        r"^\d+(?:,\d{3})*(?:\.\d{2})?USD$",
+        r"^USD\d+(?:,\d{3})*(?:\.\d{2})?$",
+    ])
+    .unwrap()
 });
 
+/// Splits a space-free value into its sign and unsigned amount, accepting a leading minus
+/// (`-$500`), a minus after the symbol (`$-500`) and accounting parentheses (`($1,234.56)`)
+fn split_sign(value: &str) -> (bool, String) {
+    if let Some(inner) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        return (true, inner.to_string());
+    }
+    if let Some(rest) = value.strip_prefix('-') {
+        return (true, rest.to_string());
+    }
+    for symbol in ["$", "USD"] {
+        if let Some(rest) = value.strip_prefix(symbol).and_then(|v| v.strip_prefix('-')) {
+            return (true, format!("{}{}", symbol, rest));
+        }
+    }
+    (false, value.to_string())
+}
+
 #[derive(Debug)]
 pub struct CurrencyType;
 
 impl TypeDetection for CurrencyType {
     fn detect_confidence(value: &str) -> f64 {
-        let clean_value = value.replace(' ', "");
+        let (_, clean_value) = split_sign(&value.replace(' ', ""));
         if clean_value.is_empty() {
             return 0.0;
         }
@@ -76,14 +96,12 @@ impl TypeDetection for CurrencyType {
     }
 
     fn is_definite_match(value: &str) -> bool {
-        let clean_value = value.replace(' ', "");
-        CURRENCY_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(&clean_value))
+        let (_, clean_value) = split_sign(&value.replace(' ', ""));
+        CURRENCY_PATTERNS.is_match(&clean_value)
     }
 
     fn normalize(value: &str) -> Option<String> {
-        let clean_value = value.replace(' ', "");
+        let (negative, clean_value) = split_sign(&value.replace(' ', ""));
         if clean_value.is_empty() {
             return None;
         }
@@ -95,6 +113,7 @@ impl TypeDetection for CurrencyType {
             .collect();
 
         let amount = numeric_part.replace(',', "").parse::<f64>().ok()?;
+        let amount = if negative { -amount } else { amount };
 
         // Only handle USD for now
         Some(CurrencySymbol::USD.format_value(amount))
@@ -114,6 +133,11 @@ mod tests {
             ("1234.56 USD", Some("$1234.56".into())),
             ("USD 1234.56", Some("$1234.56".into())),
             ("1234.567", Some("$1234.57".into())),
+            // Negative amounts in minus-sign and accounting conventions
+            ("-$500", Some("-$500.00".into())),
+            ("$-500", Some("-$500.00".into())),
+            ("($1,234.56)", Some("-$1234.56".into())),
+            ("(1,234.56 USD)", Some("-$1234.56".into())),
             ("ABC", None),
             ("", None),
         ];
@@ -134,6 +158,8 @@ mod tests {
             ("$1234.56", 1.0),
             ("$ 1234.56", 1.0),
             ("1234.56 USD", 1.0),
+            ("($1,234.56)", 1.0),
+            ("-$500", 1.0),
             ("1234.56", 0.5),
             ("ABC", 0.0),
             ("", 0.0),
@@ -155,6 +181,13 @@ mod tests {
             ("$ 1234.56", true),
             ("1234.56 USD", true),
             ("1234.567", false),
+            ("-$500", true),
+            ("$-500.25", true),
+            ("USD-12.00", true),
+            ("($1,234.56)", true),
+            ("(1,234.56 USD)", true),
+            ("(-$5)", false), // Both conventions at once
+            ("($1,234.56", false),
             ("$ABC", false),
             ("", false),
         ];
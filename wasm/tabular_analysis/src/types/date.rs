@@ -1,9 +1,23 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// Formats `Date::parse` tries, first match first, and their patterns as one set
+const PARSE_ORDER: [DateFormat; 6] = [
+    DateFormat::Iso8601,
+    DateFormat::JapaneseSlash,
+    DateFormat::UsSlash,
+    DateFormat::EuropeanDash,
+    DateFormat::EuropeanSlash,
+    DateFormat::UsDash,
+];
+
+static PARSE_PATTERNS: Lazy<RegexSet> =
+    Lazy::new(|| RegexSet::new(PARSE_ORDER.map(|format| format.pattern())).unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum DateFormat {
     /// YYYY-MM-DD (e.g., 2024-03-19)
     Iso8601,
@@ -41,22 +55,16 @@ impl Date {
         }
     }
 
-    pub fn from_str(value: &str) -> Option<Self> {
+    pub fn parse(value: &str) -> Option<Self> {
         let clean_value = value.trim();
         if clean_value.is_empty() {
             return None;
         }
 
-        // Try each format
-        for format in [
-            DateFormat::Iso8601,
-            DateFormat::JapaneseSlash,
-            DateFormat::UsSlash,
-            DateFormat::EuropeanDash,
-            DateFormat::EuropeanSlash,
-            DateFormat::UsDash,
-        ] {
-            if format.matches(clean_value) {
+        // Every format's pattern is tried in one pass, then the matches in order
+        let matched = PARSE_PATTERNS.matches(clean_value);
+        for (i, format) in PARSE_ORDER.into_iter().enumerate() {
+            if matched.matched(i) {
                 if let Some((mut year, month, day)) = format.extract_components(clean_value) {
                     // Handle two-digit years
                     if year < 100 {
@@ -70,6 +78,17 @@ impl Date {
         None
     }
 
+    /// Parses a value using only the given format, without trying the others
+    pub fn parse_as(value: &str, format: DateFormat) -> Option<Self> {
+        let clean_value = value.trim();
+        if !format.matches(clean_value) {
+            return None;
+        }
+
+        let (year, month, day) = format.extract_components(clean_value)?;
+        Date::new(year, month, day, format)
+    }
+
     pub fn to_format(&self, target_format: DateFormat) -> String {
         match target_format {
             DateFormat::Iso8601 => format!("{:04}-{:02}-{:02}", self.year, self.month, self.day),
@@ -111,6 +130,30 @@ impl fmt::Display for Date {
 }
 
 impl DateFormat {
+    /// Returns the day-first counterpart of a month-first format and vice versa,
+    /// for formats whose values can be read either way
+    pub fn swapped_day_month(&self) -> Option<DateFormat> {
+        match self {
+            DateFormat::UsSlash => Some(DateFormat::EuropeanSlash),
+            DateFormat::EuropeanSlash => Some(DateFormat::UsSlash),
+            DateFormat::UsDash => Some(DateFormat::EuropeanDash),
+            DateFormat::EuropeanDash => Some(DateFormat::UsDash),
+            DateFormat::Iso8601 | DateFormat::JapaneseSlash => None,
+        }
+    }
+
+    /// strftime pattern of the format, as read by pandas, readr and SQL loaders
+    pub fn strftime(&self) -> &'static str {
+        match self {
+            DateFormat::Iso8601 => "%Y-%m-%d",
+            DateFormat::UsSlash => "%m/%d/%Y",
+            DateFormat::EuropeanDash => "%d-%m-%Y",
+            DateFormat::EuropeanSlash => "%d/%m/%Y",
+            DateFormat::JapaneseSlash => "%Y/%m/%d",
+            DateFormat::UsDash => "%m-%d-%Y",
+        }
+    }
+
     fn pattern(&self) -> &'static str {
         match self {
             DateFormat::Iso8601 => r"^\d{4}-\d{2}-\d{2}$",
@@ -177,15 +220,15 @@ pub struct DateType;
 
 impl TypeDetection for DateType {
     fn detect_confidence(value: &str) -> f64 {
-        Date::from_str(value).map_or(0.0, |_| 1.0)
+        Date::parse(value).map_or(0.0, |_| 1.0)
     }
 
     fn is_definite_match(value: &str) -> bool {
-        Date::from_str(value).is_some()
+        Date::parse(value).is_some()
     }
 
     fn normalize(value: &str) -> Option<String> {
-        Date::from_str(value).map(|date| date.to_format(DateFormat::Iso8601))
+        Date::parse(value).map(|date| date.to_format(DateFormat::Iso8601))
     }
 }
 
@@ -211,3 +254,39 @@ impl DateType {
         day <= days_in_month
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_as() {
+        let test_cases = vec![
+            ("03/04/2024", DateFormat::UsSlash, Some((2024, 3, 4))),
+            ("03/04/2024", DateFormat::EuropeanSlash, Some((2024, 4, 3))),
+            ("19/03/2024", DateFormat::UsSlash, None),
+            ("19/03/2024", DateFormat::EuropeanSlash, Some((2024, 3, 19))),
+            ("2024-03-19", DateFormat::Iso8601, Some((2024, 3, 19))),
+            ("2024-03-19", DateFormat::UsDash, None),
+            ("02-29-2023", DateFormat::UsDash, None),
+        ];
+
+        for (input, format, expected) in test_cases {
+            let parsed = Date::parse_as(input, format).map(|d| (d.year(), d.month(), d.day()));
+            assert_eq!(
+                parsed, expected,
+                "Failed for input: {} as {:?}",
+                input, format
+            );
+        }
+    }
+
+    #[test]
+    fn test_swapped_day_month() {
+        assert_eq!(
+            DateFormat::UsSlash.swapped_day_month(),
+            Some(DateFormat::EuropeanSlash)
+        );
+        assert_eq!(DateFormat::Iso8601.swapped_day_month(), None);
+    }
+}
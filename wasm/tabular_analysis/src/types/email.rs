@@ -1,14 +1,15 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::RegexSet;
 
-static EMAIL_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
+static EMAIL_PATTERNS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
         // Updated pattern to prevent consecutive dots and require proper domain structure
-        Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9._%+-]*[a-zA-Z0-9]@([a-zA-Z0-9]([a-zA-Z0-9-]*[a-zA-Z0-9])?\.)+[a-zA-Z]{2,}$").unwrap(),
+        r"^[a-zA-Z0-9][a-zA-Z0-9._%+-]*[a-zA-Z0-9]@([a-zA-Z0-9]([a-zA-Z0-9-]*[a-zA-Z0-9])?\.)+[a-zA-Z]{2,}$",
         // Stricter pattern with additional checks
-        Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9._%+-]{0,63}@(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?\.){1,8}[a-zA-Z]{2,63}$").unwrap(),
-    ]
+        r"^[a-zA-Z0-9][a-zA-Z0-9._%+-]{0,63}@(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?\.){1,8}[a-zA-Z]{2,63}$",
+    ])
+    .unwrap()
 });
 
 #[derive(Debug)]
@@ -53,9 +54,7 @@ impl TypeDetection for EmailType {
             return false;
         }
 
-        EMAIL_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(&clean_value))
+        EMAIL_PATTERNS.is_match(&clean_value)
     }
 
     fn normalize(value: &str) -> Option<String> {
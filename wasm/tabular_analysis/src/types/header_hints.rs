@@ -0,0 +1,114 @@
+use super::DataType;
+
+// Header words suggesting each type. Short words must match a whole word of the header,
+// longer ones may appear inside a word, e.g. "email" in "useremail".
+const HEADER_HINTS: &[(DataType, &[&str])] = &[
+    (
+        DataType::Date,
+        &[
+            "date",
+            "day",
+            "dob",
+            "birthday",
+            "birthdate",
+            "created",
+            "updated",
+            "timestamp",
+        ],
+    ),
+    (DataType::Email, &["email", "mail"]),
+    (DataType::Phone, &["phone", "tel", "mobile", "cell", "fax"]),
+    (
+        DataType::Currency,
+        &[
+            "price", "cost", "amount", "salary", "revenue", "fee", "balance", "payment",
+        ],
+    ),
+    (
+        DataType::Integer,
+        &[
+            "id", "count", "qty", "age", "year", "zip", "postal", "postcode",
+        ],
+    ),
+    (DataType::CreditCard, &["card", "ccnumber", "pan"]),
+    (
+        DataType::Categorical,
+        &[
+            "type", "category", "status", "level", "grade", "rating", "priority",
+        ],
+    ),
+];
+
+// Words shorter than this only match whole header words
+const MIN_PARTIAL_MATCH_LENGTH: usize = 5;
+
+// Lowercase words of a header, split on punctuation, spaces and camelCase boundaries
+fn header_words(header: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+    for c in header.chars() {
+        let boundary = !c.is_alphanumeric() || (c.is_uppercase() && previous_lowercase);
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if c.is_alphanumeric() {
+            current.extend(c.to_lowercase());
+        }
+        previous_lowercase = c.is_lowercase();
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Types a column header suggests, in the order of the hint table
+pub fn hinted_types(header: &str) -> Vec<DataType> {
+    let words = header_words(header);
+    HEADER_HINTS
+        .iter()
+        .filter(|(_, hints)| {
+            hints.iter().any(|hint| {
+                words.iter().any(|word| {
+                    word == hint || (hint.len() >= MIN_PARTIAL_MATCH_LENGTH && word.contains(hint))
+                })
+            })
+        })
+        .map(|(data_type, _)| *data_type)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hinted_types() {
+        let test_cases = vec![
+            ("order_date", vec![DataType::Date]),
+            ("createdAt", vec![DataType::Date]),
+            ("UserEmail", vec![DataType::Email]),
+            ("Unit Price", vec![DataType::Currency]),
+            ("customer_id", vec![DataType::Integer]),
+            ("ZIP", vec![DataType::Integer]),
+            (
+                "payment_status",
+                vec![DataType::Currency, DataType::Categorical],
+            ),
+            // "id" inside a word is not a hint
+            ("valid", vec![]),
+            ("update", vec![]),
+            ("", vec![]),
+        ];
+
+        for (header, expected) in test_cases {
+            assert_eq!(
+                hinted_types(header),
+                expected,
+                "Failed for header: {}",
+                header
+            );
+        }
+    }
+}
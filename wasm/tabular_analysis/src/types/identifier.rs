@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/// Digit-only codes with significant leading zeros, such as ZIP codes or account numbers
+/// (`00423`), which would lose their zeros if loaded as integers
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroPaddedCode {
+    /// Shared length when every value has the same number of digits
+    pub fixed_width: Option<usize>,
+    pub max_length: usize,
+}
+
+impl ZeroPaddedCode {
+    /// Detects a zero-padded code column: every non-empty value is digits only and at least
+    /// one of them starts with a zero
+    pub fn detect(values: &[String]) -> Option<ZeroPaddedCode> {
+        let mut has_leading_zero = false;
+        let mut min_length = usize::MAX;
+        let mut max_length = 0;
+
+        for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            if !value.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            // A lone "0" is an ordinary number, not padding
+            has_leading_zero |= value.len() > 1 && value.starts_with('0');
+            min_length = min_length.min(value.len());
+            max_length = max_length.max(value.len());
+        }
+
+        has_leading_zero.then_some(ZeroPaddedCode {
+            fixed_width: (min_length == max_length).then_some(max_length),
+            max_length,
+        })
+    }
+
+    /// SQL column type that keeps the zeros, CHAR(n) for fixed-width codes
+    pub fn sql_type(&self) -> String {
+        match self.fixed_width {
+            Some(width) => format!("CHAR({})", width),
+            None => format!("VARCHAR({})", self.max_length),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detection() {
+        let test_cases = vec![
+            (vec!["00423", "01234", "98765", ""], Some((Some(5), 5))),
+            (vec!["007", "12345"], Some((None, 5))),
+            (vec!["0", "10", "200"], None),
+            (vec!["123", "456"], None),
+            (vec!["0012", "12a4"], None),
+            (vec!["-012", "0013"], None),
+            (vec!["", " "], None),
+        ];
+
+        for (values, expected) in test_cases {
+            let detected = ZeroPaddedCode::detect(&column(&values))
+                .map(|code| (code.fixed_width, code.max_length));
+            assert_eq!(detected, expected, "Failed for values: {:?}", values);
+        }
+    }
+
+    #[test]
+    fn test_sql_type() {
+        let fixed = ZeroPaddedCode::detect(&column(&["02134", "90210"])).unwrap();
+        assert_eq!(fixed.sql_type(), "CHAR(5)");
+
+        let variable = ZeroPaddedCode::detect(&column(&["0042", "123456"])).unwrap();
+        assert_eq!(variable.sql_type(), "VARCHAR(6)");
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+/// Words that mark boolean and common categorical values in one language, used on top of
+/// the English patterns built into categorical detection
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SynonymDictionary {
+    /// Language code the dictionary is selected by, e.g. `fr`
+    pub locale: String,
+    pub true_values: Vec<String>,
+    pub false_values: Vec<String>,
+    /// Ratings, statuses, levels and similar enum-like words
+    pub categories: Vec<String>,
+}
+
+// Built-in dictionaries as (locale, true values, false values, categories)
+type BuiltinDictionary = (
+    &'static str,
+    &'static [&'static str],
+    &'static [&'static str],
+    &'static [&'static str],
+);
+
+const BUILTIN_DICTIONARIES: &[BuiltinDictionary] = &[
+    // English is covered by the categorical patterns themselves
+    ("en", &[], &[], &[]),
+    (
+        "fr",
+        &["oui", "vrai", "o", "v"],
+        &["non", "faux", "n", "f"],
+        &[
+            "élevé",
+            "moyen",
+            "faible",
+            "actif",
+            "inactif",
+            "en attente",
+            "terminé",
+            "annulé",
+            "échoué",
+            "débutant",
+            "intermédiaire",
+            "avancé",
+            "expert",
+        ],
+    ),
+    (
+        "es",
+        &["sí", "si", "verdadero", "s", "v"],
+        &["no", "falso", "n", "f"],
+        &[
+            "alto",
+            "medio",
+            "bajo",
+            "activo",
+            "inactivo",
+            "pendiente",
+            "completado",
+            "cancelado",
+            "fallido",
+            "principiante",
+            "intermedio",
+            "avanzado",
+            "experto",
+        ],
+    ),
+    (
+        "de",
+        &["ja", "wahr", "j", "w"],
+        &["nein", "falsch", "n", "f"],
+        &[
+            "hoch",
+            "mittel",
+            "niedrig",
+            "aktiv",
+            "inaktiv",
+            "ausstehend",
+            "abgeschlossen",
+            "storniert",
+            "fehlgeschlagen",
+            "anfänger",
+            "fortgeschritten",
+            "experte",
+        ],
+    ),
+    (
+        "it",
+        &["sì", "si", "vero", "s", "v"],
+        &["no", "falso", "n", "f"],
+        &[
+            "alto",
+            "medio",
+            "basso",
+            "attivo",
+            "inattivo",
+            "in attesa",
+            "completato",
+            "annullato",
+            "fallito",
+            "principiante",
+            "intermedio",
+            "avanzato",
+            "esperto",
+        ],
+    ),
+    (
+        "pt",
+        &["sim", "verdadeiro", "s", "v"],
+        &["não", "nao", "falso", "n", "f"],
+        &[
+            "alto",
+            "médio",
+            "baixo",
+            "ativo",
+            "inativo",
+            "pendente",
+            "concluído",
+            "cancelado",
+            "falhou",
+            "iniciante",
+            "intermediário",
+            "avançado",
+            "especialista",
+        ],
+    ),
+    (
+        "nl",
+        &["ja", "waar", "j", "w"],
+        &["nee", "onwaar", "n", "o"],
+        &[
+            "hoog",
+            "gemiddeld",
+            "laag",
+            "actief",
+            "inactief",
+            "in behandeling",
+            "voltooid",
+            "geannuleerd",
+            "mislukt",
+            "beginner",
+            "gevorderd",
+            "expert",
+        ],
+    ),
+];
+
+impl SynonymDictionary {
+    /// The dictionary shipped for a language code, if there is one
+    pub fn builtin(locale: &str) -> Option<SynonymDictionary> {
+        let to_strings = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+        BUILTIN_DICTIONARIES
+            .iter()
+            .find(|(code, ..)| code.eq_ignore_ascii_case(locale))
+            .map(
+                |(code, true_values, false_values, categories)| SynonymDictionary {
+                    locale: code.to_string(),
+                    true_values: to_strings(true_values),
+                    false_values: to_strings(false_values),
+                    categories: to_strings(categories),
+                },
+            )
+    }
+
+    /// Whether a value is one of the dictionary's words, ignoring case
+    pub fn contains(&self, value: &str) -> bool {
+        let value = value.trim().to_lowercase();
+        !value.is_empty()
+            && self
+                .true_values
+                .iter()
+                .chain(&self.false_values)
+                .chain(&self.categories)
+                .any(|word| word.to_lowercase() == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_dictionaries() {
+        let test_cases = vec![
+            ("fr", "Oui", true),
+            ("fr", "NON", true),
+            ("es", "sí", true),
+            ("es", "Pendiente", true),
+            ("de", "nein", true),
+            ("de", "Fortgeschritten", true),
+            ("nl", "nee", true),
+            ("fr", "nein", false),
+            ("de", "", false),
+        ];
+        for (locale, value, expected) in test_cases {
+            let dictionary = SynonymDictionary::builtin(locale).unwrap();
+            assert_eq!(
+                dictionary.contains(value),
+                expected,
+                "Failed for input: {} in {}",
+                value,
+                locale
+            );
+        }
+
+        assert!(SynonymDictionary::builtin("EN").is_some());
+        assert!(SynonymDictionary::builtin("xx").is_none());
+    }
+}
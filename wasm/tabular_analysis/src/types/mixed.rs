@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+use super::type_scoring::{value_matches, TypeCandidate};
+use super::DataType;
+use crate::config::DetectionConfig;
+
+// Share of the non-empty values a type needs to count as part of a mixture
+const MIN_COMPONENT_RATIO: f64 = 0.1;
+
+/// One of the types found in a mixed column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MixedComponent {
+    pub data_type: DataType,
+    pub count: usize,
+    /// Share of the non-empty values, from 0 to 1
+    pub ratio: f64,
+    pub rows: Vec<usize>,
+}
+
+/// Breakdown of a column whose values split between several types
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MixedComposition {
+    /// Components ordered from the most to the least common type
+    pub components: Vec<MixedComponent>,
+    /// Non-empty rows matching none of the types
+    pub unmatched_rows: Vec<usize>,
+}
+
+impl MixedComposition {
+    /// Assigns every non-empty value to the best ranked candidate it matches and reports a
+    /// mixture when no single type reaches the threshold but two or more together do
+    pub fn detect(
+        values: &[String],
+        candidates: &[TypeCandidate],
+        config: &DetectionConfig,
+    ) -> Option<MixedComposition> {
+        let synonyms = config.synonyms().unwrap_or_default();
+        let mut rows_by_type: Vec<(DataType, Vec<usize>)> = candidates
+            .iter()
+            .map(|candidate| (candidate.data_type, Vec::new()))
+            .collect();
+        let mut unmatched_rows = Vec::new();
+        let mut non_empty = 0;
+
+        for (row, value) in values.iter().enumerate() {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            non_empty += 1;
+            match rows_by_type
+                .iter_mut()
+                .find(|(data_type, _)| value_matches(*data_type, value, &synonyms))
+            {
+                Some((_, rows)) => rows.push(row),
+                None => unmatched_rows.push(row),
+            }
+        }
+        if non_empty == 0 {
+            return None;
+        }
+
+        // Types with only a handful of values are counted as unmatched noise
+        let mut components = Vec::new();
+        for (data_type, rows) in rows_by_type {
+            let ratio = rows.len() as f64 / non_empty as f64;
+            if ratio >= MIN_COMPONENT_RATIO {
+                components.push(MixedComponent {
+                    data_type,
+                    count: rows.len(),
+                    ratio,
+                    rows,
+                });
+            } else {
+                unmatched_rows.extend(rows);
+            }
+        }
+        let matched = non_empty - unmatched_rows.len();
+        if components.len() < 2 || (matched as f64 / non_empty as f64) < config.type_threshold {
+            return None;
+        }
+
+        components.sort_by_key(|component| std::cmp::Reverse(component.count));
+        unmatched_rows.sort_unstable();
+        Some(MixedComposition {
+            components,
+            unmatched_rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::type_scoring::TypeScores;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn detect(values: &[String]) -> Option<MixedComposition> {
+        let config = DetectionConfig::default();
+        let scores = TypeScores::from_column(values, &config);
+        MixedComposition::detect(values, scores.candidates(), &config)
+    }
+
+    #[test]
+    fn test_numbers_and_dates() {
+        let values = column(&[
+            "12",
+            "2024-01-01",
+            "7",
+            "",
+            "31",
+            "2024-03-05",
+            "99",
+            "5",
+            "8",
+            "13",
+            "2024-06-30",
+        ]);
+        let composition = detect(&values).unwrap();
+
+        assert_eq!(composition.components.len(), 2);
+        let integers = &composition.components[0];
+        assert_eq!(integers.data_type, DataType::Integer);
+        assert_eq!(integers.count, 7);
+        assert_eq!(integers.ratio, 0.7);
+        assert_eq!(integers.rows, vec![0, 2, 4, 6, 7, 8, 9]);
+
+        let dates = &composition.components[1];
+        assert_eq!(dates.data_type, DataType::Date);
+        assert_eq!(dates.rows, vec![1, 5, 10]);
+        assert!(composition.unmatched_rows.is_empty());
+    }
+
+    #[test]
+    fn test_not_mixed() {
+        let test_cases = vec![
+            // A single type
+            vec!["1", "2", "3"],
+            // Mostly free text
+            vec!["12", "apple", "2024-01-01", "pear", "plum"],
+            // One dominant type with a sprinkle of another
+            vec![
+                "1",
+                "2",
+                "3",
+                "4",
+                "5",
+                "6",
+                "7",
+                "8",
+                "9",
+                "10",
+                "11",
+                "2024-01-01",
+            ],
+            vec!["", " "],
+        ];
+        for values in test_cases {
+            assert_eq!(
+                detect(&column(&values)),
+                None,
+                "Failed for values: {:?}",
+                values
+            );
+        }
+    }
+}
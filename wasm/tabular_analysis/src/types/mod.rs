@@ -1,20 +1,29 @@
-use wasm_bindgen::prelude::*;
-
+pub mod big_integer;
+pub mod credit_card;
 mod currency;
-mod date;
+pub mod date;
 //TODO: add back datetime when it becomes important
 //mod datetime;
 mod categorical;
-mod email;
-mod numeric;
-mod phone;
+pub mod email;
+pub mod header_hints;
+pub mod identifier;
+pub mod locale;
+pub mod mixed;
+pub mod number_format;
+pub mod numeric;
+pub mod ordinal;
+pub mod phone;
+pub mod quantity;
 pub mod type_scoring;
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
 
 /// Represents the detected data type of a column
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq, Clone, Copy)]
 pub enum DataType {
     Integer,
@@ -25,6 +34,11 @@ pub enum DataType {
     Phone,
     Categorical,
     Text,
+    CreditCard,
+    /// Several types in significant proportions, e.g. numbers mixed with dates
+    Mixed,
+    /// Numbers with a unit of measure, such as `12 kg`
+    Quantity,
 }
 
 impl DataType {
@@ -32,7 +46,7 @@ impl DataType {
     pub fn is_numeric(&self) -> bool {
         matches!(
             self,
-            DataType::Integer | DataType::Decimal | DataType::Currency
+            DataType::Integer | DataType::Decimal | DataType::Currency | DataType::Quantity
         )
     }
 
@@ -64,11 +78,15 @@ impl DataType {
             DataType::Integer => "INT",
             DataType::Decimal => "DECIMAL(10,2)",
             DataType::Currency => "DECIMAL(19,4)",
+            // Converted values need more fractional digits than typical decimals
+            DataType::Quantity => "DECIMAL(18,6)",
             DataType::Date => "DATE",
             DataType::Email => "VARCHAR(255)",
             DataType::Phone => "VARCHAR(20)",
+            // Card numbers are kept as text so leading digits and grouping survive
+            DataType::CreditCard => "VARCHAR(19)",
             DataType::Categorical => "VARCHAR(50)",
-            DataType::Text => "TEXT",
+            DataType::Text | DataType::Mixed => "TEXT",
         }
     }
 }
@@ -82,11 +100,14 @@ impl fmt::Display for DataType {
                 DataType::Integer => "Integer",
                 DataType::Decimal => "Decimal",
                 DataType::Currency => "Currency",
+                DataType::Quantity => "Quantity",
                 DataType::Date => "Date",
                 DataType::Email => "Email",
                 DataType::Phone => "Phone",
+                DataType::CreditCard => "CreditCard",
                 DataType::Categorical => "Categorical",
                 DataType::Text => "Text",
+                DataType::Mixed => "Mixed",
             }
         )
     }
@@ -114,6 +135,7 @@ mod tests {
         assert!(DataType::Integer.is_numeric());
         assert!(DataType::Decimal.is_numeric());
         assert!(DataType::Currency.is_numeric());
+        assert!(DataType::Quantity.is_numeric());
         assert!(!DataType::Text.is_numeric());
 
         // Test temporal types
@@ -135,11 +157,14 @@ mod tests {
         assert_eq!(DataType::Integer.default_sql_type(), "INT");
         assert_eq!(DataType::Decimal.default_sql_type(), "DECIMAL(10,2)");
         assert_eq!(DataType::Currency.default_sql_type(), "DECIMAL(19,4)");
+        assert_eq!(DataType::Quantity.default_sql_type(), "DECIMAL(18,6)");
         assert_eq!(DataType::Date.default_sql_type(), "DATE");
         assert_eq!(DataType::Email.default_sql_type(), "VARCHAR(255)");
         assert_eq!(DataType::Phone.default_sql_type(), "VARCHAR(20)");
+        assert_eq!(DataType::CreditCard.default_sql_type(), "VARCHAR(19)");
         assert_eq!(DataType::Categorical.default_sql_type(), "VARCHAR(50)");
         assert_eq!(DataType::Text.default_sql_type(), "TEXT");
+        assert_eq!(DataType::Mixed.default_sql_type(), "TEXT");
     }
 
     #[test]
@@ -147,10 +172,13 @@ mod tests {
         assert_eq!(format!("{}", DataType::Integer), "Integer");
         assert_eq!(format!("{}", DataType::Decimal), "Decimal");
         assert_eq!(format!("{}", DataType::Currency), "Currency");
+        assert_eq!(format!("{}", DataType::Quantity), "Quantity");
         assert_eq!(format!("{}", DataType::Date), "Date");
         assert_eq!(format!("{}", DataType::Email), "Email");
         assert_eq!(format!("{}", DataType::Phone), "Phone");
+        assert_eq!(format!("{}", DataType::CreditCard), "CreditCard");
         assert_eq!(format!("{}", DataType::Categorical), "Categorical");
         assert_eq!(format!("{}", DataType::Text), "Text");
+        assert_eq!(format!("{}", DataType::Mixed), "Mixed");
     }
 }
@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+
+use super::DataType;
+
+// Symbols and codes stripped from either end of a value before parsing
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥'];
+const CURRENCY_CODES: &[&str] = &["USD", "EUR", "GBP", "JPY"];
+// Space-like characters used as thousands separators, including no-break spaces
+const SPACE_SEPARATORS: &[char] = &[' ', '\u{a0}', '\u{202f}'];
+
+/// How a column writes its numbers: the thousands and decimal separators and
+/// whether negatives use accounting-style parentheses, e.g. `(1.234,56)`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// `None` when the column never groups digits
+    pub thousands_separator: Option<char>,
+    pub decimal_separator: char,
+    pub parentheses_negative: bool,
+}
+
+impl Default for NumberFormat {
+    /// `1,234.56` with a leading minus sign
+    fn default() -> Self {
+        NumberFormat {
+            thousands_separator: Some(','),
+            decimal_separator: '.',
+            parentheses_negative: false,
+        }
+    }
+}
+
+// Conventions tried during detection; on ties the earlier one wins
+const CANDIDATES: &[(Option<char>, char)] = &[
+    (Some(','), '.'),
+    (Some('.'), ','),
+    (Some(' '), '.'),
+    (Some(' '), ','),
+    (Some('\''), '.'),
+];
+
+impl NumberFormat {
+    /// Picks the convention that parses the most values in the column, or `None` when
+    /// no value parses under any of them
+    pub fn detect(values: &[String]) -> Option<NumberFormat> {
+        NumberFormat::detect_preferring(values, '.')
+    }
+
+    /// Like `detect`, but conventions with the given decimal separator win ties, so
+    /// `1,250` reads as a decimal in a file whose other numbers use decimal commas
+    pub fn detect_preferring(values: &[String], decimal_separator: char) -> Option<NumberFormat> {
+        let non_empty: Vec<&str> = values
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        let mut best: Option<(NumberFormat, usize)> = None;
+        let candidates = CANDIDATES
+            .iter()
+            .filter(|&&(_, decimal)| decimal == decimal_separator)
+            .chain(
+                CANDIDATES
+                    .iter()
+                    .filter(|&&(_, decimal)| decimal != decimal_separator),
+            );
+        for &(thousands_separator, decimal_separator) in candidates {
+            let format = NumberFormat {
+                thousands_separator,
+                decimal_separator,
+                parentheses_negative: true,
+            };
+            let parsed = non_empty
+                .iter()
+                .filter(|v| format.parse(v).is_some())
+                .count();
+            if parsed > 0 && best.is_none_or(|(_, count)| parsed > count) {
+                best = Some((format, parsed));
+            }
+        }
+
+        let (mut format, _) = best?;
+        format.parentheses_negative = non_empty
+            .iter()
+            .any(|v| v.starts_with('(') && format.parse(v).is_some());
+        // Report no grouping when no value actually uses the separator
+        if !non_empty.iter().any(|v| format.uses_thousands_separator(v)) {
+            format.thousands_separator = None;
+        }
+        Some(format)
+    }
+
+    fn is_thousands_separator(&self, c: char) -> bool {
+        match self.thousands_separator {
+            Some(' ') => SPACE_SEPARATORS.contains(&c),
+            Some(separator) => c == separator,
+            None => false,
+        }
+    }
+
+    fn uses_thousands_separator(&self, value: &str) -> bool {
+        self.parse(value).is_some() && value.chars().any(|c| self.is_thousands_separator(c))
+    }
+
+    /// Parses a value written in this format; currency symbols around the number are ignored
+    pub fn parse(&self, value: &str) -> Option<f64> {
+        self.plain_digits(value)?.parse().ok()
+    }
+
+    /// A value written in this format as its digits, exactly as written, with a leading minus
+    /// sign and `.` before any fraction, e.g. `-1250.50` for `(1,250.50)`
+    pub fn plain_digits(&self, value: &str) -> Option<String> {
+        let mut text = value.trim();
+        let mut negative = false;
+
+        if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            negative = true;
+            text = inner.trim();
+        }
+        text = strip_currency(text);
+        if let Some(rest) = text.strip_prefix('-') {
+            if negative {
+                return None;
+            }
+            negative = true;
+            text = strip_currency(rest);
+        }
+
+        let (integer_part, fraction) = match text.split_once(self.decimal_separator) {
+            Some((integer_part, fraction)) => (integer_part, Some(fraction)),
+            None => (text, None),
+        };
+        if let Some(fraction) = fraction {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+        }
+
+        let mut digits = String::with_capacity(text.len() + 2);
+        if negative {
+            digits.push('-');
+        }
+        let groups: Vec<&str> = integer_part
+            .split(|c| self.is_thousands_separator(c))
+            .collect();
+        if groups.len() > 1 {
+            // Grouped digits need a leading group of 1-3 digits followed by groups of exactly 3
+            let valid = (1..=3).contains(&groups[0].len())
+                && groups[1..].iter().all(|g| g.len() == 3)
+                && groups.iter().all(|g| g.bytes().all(|b| b.is_ascii_digit()));
+            if !valid {
+                return None;
+            }
+        } else if !integer_part.bytes().all(|b| b.is_ascii_digit())
+            || (integer_part.is_empty() && fraction.is_none())
+        {
+            return None;
+        }
+        groups.iter().for_each(|g| digits.push_str(g));
+        if let Some(fraction) = fraction {
+            digits.push('.');
+            digits.push_str(fraction);
+        }
+        Some(digits)
+    }
+
+    /// Integer or Decimal when every non-empty value parses in this format
+    pub fn numeric_type(&self, values: &[String]) -> Option<DataType> {
+        let mut integral = true;
+        let mut any = false;
+        for value in values.iter().filter(|v| !v.trim().is_empty()) {
+            let number = self.parse(value)?;
+            integral &= number.fract() == 0.0;
+            any = true;
+        }
+        any.then_some(if integral {
+            DataType::Integer
+        } else {
+            DataType::Decimal
+        })
+    }
+
+    /// Rewrites a value as a plain number with `.` decimals and a leading minus sign
+    pub fn normalize(&self, value: &str) -> Option<String> {
+        self.parse(value).map(plain_number)
+    }
+
+    /// Writes a number back in this format's convention, without digit grouping
+    pub fn format(&self, number: f64) -> String {
+        let plain = plain_number(number.abs());
+        let text = if self.decimal_separator == '.' {
+            plain
+        } else {
+            plain.replace('.', &self.decimal_separator.to_string())
+        };
+        match (number < 0.0, self.parentheses_negative) {
+            (false, _) => text,
+            (true, true) => format!("({})", text),
+            (true, false) => format!("-{}", text),
+        }
+    }
+}
+
+fn strip_currency(value: &str) -> &str {
+    let mut text = value.trim_matches(|c| CURRENCY_SYMBOLS.contains(&c)).trim();
+    for code in CURRENCY_CODES {
+        text = text.trim_start_matches(code).trim_end_matches(code).trim();
+    }
+    text
+}
+
+// Shortest decimal text for a number, without trailing zeros or float artifacts
+fn plain_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        return format!("{}", number as i64);
+    }
+    format!("{:.10}", number)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn format(thousands: Option<char>, decimal: char, parentheses: bool) -> NumberFormat {
+        NumberFormat {
+            thousands_separator: thousands,
+            decimal_separator: decimal,
+            parentheses_negative: parentheses,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let us = NumberFormat::default();
+        let eu = format(Some('.'), ',', true);
+        let test_cases = vec![
+            (us, "1,234.56", Some(1234.56)),
+            (us, "-1,234", Some(-1234.0)),
+            (us, "$1,000", Some(1000.0)),
+            (us, "(1,234.50)", Some(-1234.5)),
+            (us, "-$500", Some(-500.0)),
+            (us, "$-500", Some(-500.0)),
+            (us, "(1,234.56 USD)", Some(-1234.56)),
+            (us, "USD 12.50", Some(12.5)),
+            (us, "1,23", None),
+            (us, "1.234,56", None),
+            (eu, "1.234,56", Some(1234.56)),
+            (eu, "1,5", Some(1.5)),
+            (eu, "(1.234,56 €)", Some(-1234.56)),
+            (eu, "1.23", None),
+            (
+                format(Some(' '), ',', false),
+                "1 234 567,8",
+                Some(1234567.8),
+            ),
+            (format(Some(' '), ',', false), "1\u{a0}234", Some(1234.0)),
+            (us, "(-5)", None),
+            (us, "", None),
+            (us, ".", None),
+        ];
+
+        for (format, input, expected) in test_cases {
+            assert_eq!(
+                format.parse(input),
+                expected,
+                "Failed for input: {} with {:?}",
+                input,
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        let test_cases = vec![
+            (
+                vec!["1,234.56", "12.5", "7"],
+                Some(format(Some(','), '.', false)),
+            ),
+            (
+                vec!["1.234,56", "12,5", "7"],
+                Some(format(Some('.'), ',', false)),
+            ),
+            (
+                vec!["1 234,5", "(2 000,25)"],
+                Some(format(Some(' '), ',', true)),
+            ),
+            (vec!["12.5", "(3.25)"], Some(format(None, '.', true))),
+            // "1,234" reads either way, so the default convention wins
+            (vec!["1,234"], Some(format(Some(','), '.', false))),
+            (vec!["abc", ""], None),
+        ];
+
+        for (values, expected) in test_cases {
+            assert_eq!(
+                NumberFormat::detect(&column(&values)),
+                expected,
+                "Failed for values: {:?}",
+                values
+            );
+        }
+        assert_eq!(
+            NumberFormat::detect_preferring(&column(&["1,234"]), ','),
+            Some(format(None, ',', false))
+        );
+    }
+
+    #[test]
+    fn test_numeric_type() {
+        let eu = format(Some('.'), ',', false);
+        let test_cases = vec![
+            (vec!["1.234", "12", ""], Some(DataType::Integer)),
+            (vec!["1.234,5", "12"], Some(DataType::Decimal)),
+            (vec!["1.234,5", "n/a"], None),
+            (vec![""], None),
+        ];
+        for (values, expected) in test_cases {
+            assert_eq!(
+                eu.numeric_type(&column(&values)),
+                expected,
+                "Failed for values: {:?}",
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let eu = format(Some('.'), ',', true);
+        assert_eq!(eu.normalize("1.234,50"), Some("1234.5".to_string()));
+        assert_eq!(eu.normalize("(2.000)"), Some("-2000".to_string()));
+        assert_eq!(eu.normalize("abc"), None);
+
+        assert_eq!(eu.format(-1234.5), "(1234,5)");
+        assert_eq!(NumberFormat::default().format(-2.25), "-2.25");
+        assert_eq!(NumberFormat::default().format(3.0), "3");
+    }
+}
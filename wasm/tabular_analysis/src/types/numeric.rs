@@ -1,17 +1,18 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::RegexSet;
 
-static NUMERIC_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
+static NUMERIC_PATTERNS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
         // Integer patterns
-        Regex::new(r"^-?\d+$").unwrap(), // Basic integers
-        Regex::new(r"^-?\d{1,3}(,\d{3})*$").unwrap(), // Integers with commas
+        r"^-?\d+$",              // Basic integers
+        r"^-?\d{1,3}(,\d{3})*$", // Integers with commas
         // Decimal patterns
-        Regex::new(r"^-?\d*\.\d+$").unwrap(), // Decimals
-        Regex::new(r"^-?\d{1,3}(,\d{3})*\.\d+$").unwrap(), // Decimals with commas
-        Regex::new(r"^-?\d+\.\d*$").unwrap(), // Decimals with optional trailing zeros
-    ]
+        r"^-?\d*\.\d+$",              // Decimals
+        r"^-?\d{1,3}(,\d{3})*\.\d+$", // Decimals with commas
+        r"^-?\d+\.\d*$",              // Decimals with optional trailing zeros
+    ])
+    .unwrap()
 });
 
 #[derive(Debug)]
@@ -34,9 +35,7 @@ impl TypeDetection for NumericType {
             return false;
         }
 
-        NUMERIC_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(&clean_value))
+        NUMERIC_PATTERNS.is_match(&clean_value)
     }
 
     fn normalize(value: &str) -> Option<String> {
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// Fewest distinct levels for a column to count as ordered; two levels like yes/no rarely are
+const MIN_ORDINAL_LEVELS: usize = 3;
+
+// Known scales from lowest to highest. Each rank lists its spellings, lowercase with `_` and
+// `-` read as spaces.
+const SCALES: &[(&str, &[&[&str]])] = &[
+    (
+        "size",
+        &[
+            &["xxxs", "3xs"],
+            &["xxs", "2xs"],
+            &["xs", "extra small", "x small"],
+            &["s", "small", "sm"],
+            &["m", "medium", "med"],
+            &["l", "large", "lg"],
+            &["xl", "extra large", "x large"],
+            &["xxl", "2xl"],
+            &["xxxl", "3xl"],
+        ],
+    ),
+    (
+        "level",
+        &[
+            &["very low", "lowest"],
+            &["low"],
+            &["medium", "mid", "moderate", "med"],
+            &["high"],
+            &["very high", "highest"],
+        ],
+    ),
+    (
+        "frequency",
+        &[
+            &["never"],
+            &["rarely", "seldom"],
+            &["sometimes", "occasionally"],
+            &["often", "frequently"],
+            &["always"],
+        ],
+    ),
+    (
+        "agreement",
+        &[
+            &["strongly disagree"],
+            &["disagree"],
+            &["neutral", "neither agree nor disagree"],
+            &["agree"],
+            &["strongly agree"],
+        ],
+    ),
+    (
+        "quality",
+        &[
+            &["very poor", "terrible"],
+            &["poor", "bad"],
+            &["fair", "average"],
+            &["good"],
+            &["very good", "great"],
+            &["excellent"],
+        ],
+    ),
+    (
+        "satisfaction",
+        &[
+            &["very dissatisfied"],
+            &["dissatisfied"],
+            &["neutral"],
+            &["satisfied"],
+            &["very satisfied"],
+        ],
+    ),
+    (
+        "tier",
+        &[
+            &["bronze"],
+            &["silver"],
+            &["gold"],
+            &["platinum"],
+            &["diamond"],
+        ],
+    ),
+    (
+        "priority",
+        &[
+            &["trivial"],
+            &["minor", "low"],
+            &["normal", "medium"],
+            &["major", "high"],
+            &["critical", "urgent"],
+            &["blocker"],
+        ],
+    ),
+];
+
+/// Levels of a categorical column that follow a known order, such as low/medium/high,
+/// S/M/L/XL or star ratings. Sorting, ordinal encodings and SQL constraints use this order
+/// instead of the alphabetical one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OrdinalScale {
+    /// Name of the matched scale, e.g. `size` or `stars`
+    pub name: String,
+    /// The column's levels as written, lowest first
+    pub levels: Vec<String>,
+}
+
+// Lowercase with `_` and `-` as spaces and runs of spaces collapsed
+fn normalize(value: &str) -> String {
+    value
+        .to_lowercase()
+        .replace(['_', '-'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Rank of a star rating: a run of one star character, or "N star(s)"
+fn star_rank(value: &str) -> Option<usize> {
+    let mut chars = value.chars();
+    let first = chars.next()?;
+    if matches!(first, '★' | '⭐' | '*') && chars.all(|c| c == first) {
+        return Some(value.chars().count());
+    }
+    let (count, unit) = value.split_once(' ')?;
+    matches!(unit, "star" | "stars")
+        .then(|| count.parse().ok())
+        .flatten()
+}
+
+fn scale_rank(ranks: &[&[&str]], value: &str) -> Option<usize> {
+    ranks
+        .iter()
+        .position(|spellings| spellings.contains(&value))
+}
+
+impl OrdinalScale {
+    /// Detects a column whose distinct non-empty values all belong to one known scale, with
+    /// at least three of them
+    pub fn detect(values: &[String]) -> Option<OrdinalScale> {
+        let distinct: BTreeSet<&str> = values
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if distinct.len() < MIN_ORDINAL_LEVELS {
+            return None;
+        }
+        let normalized: Vec<(&str, String)> = distinct.iter().map(|&v| (v, normalize(v))).collect();
+
+        let ranked = |name: &str, rank: &dyn Fn(&str) -> Option<usize>| {
+            let mut levels = normalized
+                .iter()
+                .map(|(value, key)| Some((rank(key)?, *value)))
+                .collect::<Option<Vec<_>>>()?;
+            levels.sort();
+            Some(OrdinalScale {
+                name: name.to_string(),
+                levels: levels.into_iter().map(|(_, v)| v.to_string()).collect(),
+            })
+        };
+        ranked("stars", &star_rank).or_else(|| {
+            SCALES
+                .iter()
+                .find_map(|(name, ranks)| ranked(name, &|key| scale_rank(ranks, key)))
+        })
+    }
+
+    /// Position of a value among the levels, ignoring case and surrounding spaces
+    pub fn rank(&self, value: &str) -> Option<usize> {
+        let value = value.trim().to_lowercase();
+        self.levels
+            .iter()
+            .position(|level| level.to_lowercase() == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(values: &[&str]) -> Option<OrdinalScale> {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        OrdinalScale::detect(&values)
+    }
+
+    #[test]
+    fn test_known_scales() {
+        let sizes = detect(&["M", "XL", "S", "L", "M", "s"]).unwrap();
+        assert_eq!(sizes.name, "size");
+        assert_eq!(sizes.levels, vec!["S", "s", "M", "L", "XL"]);
+        assert_eq!(sizes.rank("xl"), Some(4));
+
+        let levels = detect(&["High", "low", "Medium", "very_high"]).unwrap();
+        assert_eq!(levels.name, "level");
+        assert_eq!(levels.levels, vec!["low", "Medium", "High", "very_high"]);
+
+        let stars = detect(&["★★★", "★", "★★★★★", "★★"]).unwrap();
+        assert_eq!(stars.name, "stars");
+        assert_eq!(stars.levels, vec!["★", "★★", "★★★", "★★★★★"]);
+        let stars = detect(&["1 star", "3 stars", "2 stars"]).unwrap();
+        assert_eq!(stars.levels, vec!["1 star", "2 stars", "3 stars"]);
+    }
+
+    #[test]
+    fn test_nominal_columns() {
+        assert_eq!(detect(&["red", "green", "blue"]), None);
+        // One value off the scale makes the column nominal
+        assert_eq!(detect(&["low", "medium", "high", "unknown"]), None);
+        // Too few levels to tell
+        assert_eq!(detect(&["low", "high", ""]), None);
+    }
+}
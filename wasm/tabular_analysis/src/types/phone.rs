@@ -1,16 +1,17 @@
 use super::TypeDetection;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::RegexSet;
 
-static PHONE_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
+static PHONE_PATTERNS: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
         // International format with optional country code
-        Regex::new(r"^\+?\d{1,3}[-. ]?\d{3}[-. ]?\d{3}[-. ]?\d{4}$").unwrap(),
+        r"^\+?\d{1,3}[-. ]?\d{3}[-. ]?\d{3}[-. ]?\d{4}$",
         // US/Canada format with parentheses
-        Regex::new(r"^\(\d{3}\)\s*\d{3}[-. ]?\d{4}$").unwrap(),
+        r"^\(\d{3}\)\s*\d{3}[-. ]?\d{4}$",
         // Basic format with separators
-        Regex::new(r"^\d{3}[-. ]?\d{3}[-. ]?\d{4}$").unwrap(),
-    ]
+        r"^\d{3}[-. ]?\d{3}[-. ]?\d{4}$",
+    ])
+    .unwrap()
 });
 
 #[derive(Debug)]
@@ -50,9 +51,7 @@ impl TypeDetection for PhoneType {
 
     fn is_definite_match(value: &str) -> bool {
         let clean_value = value.replace(' ', "");
-        PHONE_PATTERNS
-            .iter()
-            .any(|pattern| pattern.is_match(&clean_value))
+        PHONE_PATTERNS.is_match(&clean_value)
     }
 
     fn normalize(value: &str) -> Option<String> {
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::number_format::NumberFormat;
+
+/// What a unit measures; only units of the same dimension convert into each other
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Mass,
+    Length,
+    Volume,
+    DataSize,
+    Speed,
+    Duration,
+}
+
+struct Unit {
+    symbol: &'static str,
+    /// Other spellings, matched case-insensitively like the symbol
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    /// Size of the unit in the dimension's base unit (g, m, l, B, m/s, s)
+    factor: f64,
+}
+
+impl Unit {
+    const fn new(
+        symbol: &'static str,
+        aliases: &'static [&'static str],
+        dimension: Dimension,
+        factor: f64,
+    ) -> Self {
+        Unit {
+            symbol,
+            aliases,
+            dimension,
+            factor,
+        }
+    }
+}
+
+const UNITS: &[Unit] = &[
+    Unit::new("mg", &["milligrams"], Dimension::Mass, 0.001),
+    Unit::new("g", &["grams"], Dimension::Mass, 1.0),
+    Unit::new("kg", &["kgs", "kilograms"], Dimension::Mass, 1e3),
+    Unit::new("t", &["tonnes"], Dimension::Mass, 1e6),
+    Unit::new("lb", &["lbs", "pounds"], Dimension::Mass, 453.59237),
+    Unit::new("oz", &["ounces"], Dimension::Mass, 28.349523125),
+    Unit::new("mm", &[], Dimension::Length, 0.001),
+    Unit::new("cm", &[], Dimension::Length, 0.01),
+    Unit::new("m", &["meters", "metres"], Dimension::Length, 1.0),
+    Unit::new("km", &["kilometers", "kilometres"], Dimension::Length, 1e3),
+    Unit::new("in", &["inches"], Dimension::Length, 0.0254),
+    Unit::new("ft", &["feet"], Dimension::Length, 0.3048),
+    Unit::new("mi", &["miles"], Dimension::Length, 1609.344),
+    Unit::new("ml", &[], Dimension::Volume, 0.001),
+    Unit::new("l", &["liters", "litres"], Dimension::Volume, 1.0),
+    Unit::new("gal", &["gallons"], Dimension::Volume, 3.785411784),
+    Unit::new("B", &["bytes"], Dimension::DataSize, 1.0),
+    Unit::new("KB", &[], Dimension::DataSize, 1e3),
+    Unit::new("MB", &[], Dimension::DataSize, 1e6),
+    Unit::new("GB", &[], Dimension::DataSize, 1e9),
+    Unit::new("TB", &[], Dimension::DataSize, 1e12),
+    Unit::new("m/s", &[], Dimension::Speed, 1.0),
+    Unit::new("km/h", &["kph", "kmh"], Dimension::Speed, 1.0 / 3.6),
+    Unit::new("mph", &[], Dimension::Speed, 0.44704),
+    Unit::new("ms", &[], Dimension::Duration, 0.001),
+    Unit::new("s", &["sec", "secs", "seconds"], Dimension::Duration, 1.0),
+    Unit::new("min", &["mins", "minutes"], Dimension::Duration, 60.0),
+    Unit::new("h", &["hr", "hrs", "hours"], Dimension::Duration, 3600.0),
+];
+
+fn find_unit(text: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| {
+        unit.symbol.eq_ignore_ascii_case(text)
+            || unit
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(text))
+    })
+}
+
+// Splits "12.5 kg" into its number text and unit, before the unit is validated
+fn split_quantity(value: &str) -> Option<(&str, &'static Unit)> {
+    let value = value.trim();
+    let unit_start = value.find(|c: char| c.is_alphabetic())?;
+    let (number, unit) = value.split_at(unit_start);
+    let number = number.trim();
+    if number.is_empty() {
+        return None;
+    }
+    Some((number, find_unit(unit.trim())?))
+}
+
+/// How often a unit appears in a quantity column
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UnitCount {
+    pub unit: String,
+    pub count: usize,
+}
+
+/// A column of measurements such as `12 kg` or `100 km/h`, with the units it uses
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuantityColumn {
+    pub dimension: Dimension,
+    /// Units seen in the column, most common first
+    pub units: Vec<UnitCount>,
+    /// Unit every value is converted to for statistics and export, the most common one
+    pub normalized_unit: String,
+    /// Share of non-empty values that are quantities of the dimension, from 0 to 1
+    pub coverage: f64,
+    /// Separators used by the numeric part of the values
+    pub number_format: NumberFormat,
+}
+
+impl QuantityColumn {
+    /// Detects a column whose values are numbers followed by units of one dimension, when
+    /// at least `threshold` of the non-empty values are such quantities
+    pub fn detect(values: &[String], threshold: f64) -> Option<QuantityColumn> {
+        let non_empty: Vec<&str> = values
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let quantities: Vec<(&str, &Unit)> =
+            non_empty.iter().filter_map(|v| split_quantity(v)).collect();
+        // The dimension most values measure; other dimensions count against coverage
+        let mut dimensions: HashMap<Dimension, usize> = HashMap::new();
+        for (_, unit) in &quantities {
+            *dimensions.entry(unit.dimension).or_insert(0) += 1;
+        }
+        let dimension = UNITS
+            .iter()
+            .rev()
+            .map(|unit| unit.dimension)
+            .max_by_key(|d| dimensions.get(d).copied().unwrap_or(0))?;
+
+        let numbers: Vec<String> = quantities.iter().map(|(n, _)| n.to_string()).collect();
+        let number_format = NumberFormat::detect(&numbers)?;
+
+        // Count each unit in table order so ties resolve the same way on every run
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let mut matched = 0;
+        for (number, unit) in &quantities {
+            if unit.dimension == dimension && number_format.parse(number).is_some() {
+                *counts.entry(unit.symbol).or_insert(0) += 1;
+                matched += 1;
+            }
+        }
+        let coverage = matched as f64 / non_empty.len() as f64;
+        if matched == 0 || coverage < threshold {
+            return None;
+        }
+
+        let mut units: Vec<UnitCount> = UNITS
+            .iter()
+            .filter_map(|unit| {
+                counts.get(unit.symbol).map(|&count| UnitCount {
+                    unit: unit.symbol.to_string(),
+                    count,
+                })
+            })
+            .collect();
+        units.sort_by_key(|unit| std::cmp::Reverse(unit.count));
+
+        Some(QuantityColumn {
+            dimension,
+            normalized_unit: units[0].unit.clone(),
+            units,
+            coverage,
+            number_format,
+        })
+    }
+
+    /// Parses a value and converts it to the normalized unit
+    pub fn normalized_value(&self, value: &str) -> Option<f64> {
+        let (number, unit) = split_quantity(value)?;
+        let target = find_unit(&self.normalized_unit)?;
+        if unit.dimension != self.dimension {
+            return None;
+        }
+        let number = self.number_format.parse(number)?;
+        Some(number * unit.factor / target.factor)
+    }
+
+    /// Every value converted to the normalized unit as a plain number, empty when it
+    /// isn't a quantity of the column's dimension
+    pub fn normalized_values(&self, values: &[String]) -> Vec<String> {
+        values
+            .iter()
+            .map(|v| {
+                self.normalized_value(v)
+                    .map(|number| number.to_string())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detection() {
+        let test_cases = vec![
+            (
+                vec!["12 kg", "3.5kg", "500 g", ""],
+                Some((Dimension::Mass, "kg")),
+            ),
+            (
+                vec!["3.5 MB", "1 GB", "700 MB"],
+                Some((Dimension::DataSize, "MB")),
+            ),
+            (
+                vec!["100 km/h", "60 mph", "80 kph"],
+                Some((Dimension::Speed, "km/h")),
+            ),
+            (
+                vec!["5 Minutes", "2 hrs", "10 min"],
+                Some((Dimension::Duration, "min")),
+            ),
+            (vec!["12 kg", "3 km"], None),
+            (vec!["3 apples", "4 pears"], None),
+            (vec!["12", "15"], None),
+            (vec!["kg", "g"], None),
+        ];
+
+        for (values, expected) in test_cases {
+            let detected = QuantityColumn::detect(&column(&values), 0.95)
+                .map(|q| (q.dimension, q.normalized_unit.clone()));
+            assert_eq!(
+                detected,
+                expected.map(|(d, u)| (d, u.to_string())),
+                "Failed for values: {:?}",
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalized_values() {
+        let values = column(&["1,5 kg", "500 g", "2 lb", "n/a"]);
+        let quantity = QuantityColumn::detect(&values, 0.7).unwrap();
+        assert_eq!(quantity.coverage, 0.75);
+        assert_eq!(quantity.number_format.decimal_separator, ',');
+        assert_eq!(
+            quantity.units,
+            vec![
+                UnitCount {
+                    unit: "g".to_string(),
+                    count: 1
+                },
+                UnitCount {
+                    unit: "kg".to_string(),
+                    count: 1
+                },
+                UnitCount {
+                    unit: "lb".to_string(),
+                    count: 1
+                },
+            ]
+        );
+
+        let normalized = quantity.normalized_values(&values);
+        assert_eq!(normalized[0], "1500");
+        assert_eq!(normalized[1], "500");
+        assert!((quantity.normalized_value("2 lb").unwrap() - 907.18474).abs() < 1e-9);
+        assert_eq!(normalized[3], "");
+    }
+
+    #[test]
+    fn test_no_matching_values_at_zero_threshold() {
+        // No value of the most common dimension parses in the number format the column uses
+        let values = column(&["1.5 kg", "2.5 kg", "1,5 MB", "2,5 MB", "3,5 m"]);
+        assert!(QuantityColumn::detect(&values, 0.0).is_none());
+    }
+}
@@ -1,134 +1,208 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::DetectionConfig;
 use crate::types::{
-    categorical::CategoricalType, currency::CurrencyType, date::DateType, email::EmailType,
+    categorical::CategoricalType, credit_card::CreditCardType, currency::CurrencyType,
+    date::DateType, email::EmailType, header_hints::hinted_types, locale::SynonymDictionary,
     numeric::NumericType, phone::PhoneType, DataType, TypeDetection,
 };
 
-/// Holds confidence scores for how well data matches each possible type
-#[derive(Debug, Default)]
+/// How well a column matches one candidate type
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TypeCandidate {
+    pub data_type: DataType,
+    /// Share of non-empty values that definitely match the type plus the header prior,
+    /// from 0 to 1
+    pub score: f64,
+    pub matches: usize,
+    pub mismatches: usize,
+    /// Added to the score when the column header suggests this type
+    pub prior: f64,
+}
+
+/// Whether a value definitely matches a candidate type; the locale's synonyms count as
+/// categorical matches
+pub fn value_matches(data_type: DataType, value: &str, synonyms: &SynonymDictionary) -> bool {
+    if let Some(&first) = value.trim_start().as_bytes().first() {
+        if !may_start(data_type, first) {
+            return false;
+        }
+    }
+    detector_matches(data_type, value, synonyms)
+}
+
+fn detector_matches(data_type: DataType, value: &str, synonyms: &SynonymDictionary) -> bool {
+    let confidence = match data_type {
+        DataType::CreditCard => CreditCardType::detect_confidence(value),
+        DataType::Integer | DataType::Decimal => NumericType::detect_confidence(value),
+        DataType::Currency => CurrencyType::detect_confidence(value),
+        DataType::Date => DateType::detect_confidence(value),
+        DataType::Email => EmailType::detect_confidence(value),
+        DataType::Phone => PhoneType::detect_confidence(value),
+        DataType::Categorical if synonyms.contains(value) => return true,
+        DataType::Categorical => CategoricalType::detect_confidence(value),
+        DataType::Quantity | DataType::Text | DataType::Mixed => return false,
+    };
+    confidence == 1.0
+}
+
+// Whether a definite match of the type can start with this byte, checked before running any
+// of its patterns. Non-ASCII bytes always pass: `\d` and case-insensitive letters in the
+// patterns match some non-ASCII characters.
+fn may_start(data_type: DataType, first: u8) -> bool {
+    if !first.is_ascii() {
+        return true;
+    }
+    match data_type {
+        DataType::CreditCard => first.is_ascii_digit() || first == b'-',
+        DataType::Integer | DataType::Decimal => first.is_ascii_digit() || b"-.".contains(&first),
+        // Symbols, signs and accounting parentheses, or the "USD" code
+        DataType::Currency => first.is_ascii_digit() || b"$-(U".contains(&first),
+        DataType::Date => first.is_ascii_digit(),
+        // Patterns run on the lowercased value
+        DataType::Email => first.is_ascii_alphanumeric(),
+        DataType::Phone => first.is_ascii_digit() || b"+(".contains(&first),
+        // Synonyms can be any text
+        DataType::Categorical | DataType::Quantity | DataType::Text | DataType::Mixed => true,
+    }
+}
+
+/// Types every column is scored against, in priority order, which breaks ties between equal
+/// scores. Card numbers are all digits too, so they must win over Integer.
+pub const CANDIDATE_TYPES: [DataType; 7] = [
+    DataType::CreditCard,
+    DataType::Integer,
+    DataType::Currency,
+    DataType::Date,
+    DataType::Email,
+    DataType::Phone,
+    DataType::Categorical,
+];
+
+// Distinct values whose matches `TypeCounts::from_values` remembers. A column that reaches
+// it with fewer repeats than distinct values is taken as mostly unique and stops using the
+// memo, whose lookups would only slow it down.
+const MEMO_CAPACITY: usize = 1024;
+
+/// Non-empty values in part of a column and how many of them match each of
+/// `CANDIDATE_TYPES`. Counts of separate chunks add up to those of the whole column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeCounts {
+    pub non_empty: usize,
+    pub matches: [usize; CANDIDATE_TYPES.len()],
+}
+
+impl TypeCounts {
+    /// Matches every non-empty value against each candidate type. Each distinct value is
+    /// matched once, so status and enum columns repeating a few values cost little more
+    /// than their distinct values.
+    pub fn from_values(values: &[String], synonyms: &SynonymDictionary) -> Self {
+        let mut counts = TypeCounts::default();
+        let mut memo: Option<HashMap<&str, [bool; CANDIDATE_TYPES.len()]>> = Some(HashMap::new());
+        let mut repeats = 0;
+        for value in values.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            counts.non_empty += 1;
+            let remembered = memo.as_ref().and_then(|memo| memo.get(value).copied());
+            let matched = match remembered {
+                Some(matched) => {
+                    repeats += 1;
+                    matched
+                }
+                None => {
+                    let matched =
+                        CANDIDATE_TYPES.map(|data_type| value_matches(data_type, value, synonyms));
+                    if let Some(map) = &mut memo {
+                        if map.len() < MEMO_CAPACITY {
+                            map.insert(value, matched);
+                        } else if repeats < map.len() {
+                            memo = None;
+                        }
+                    }
+                    matched
+                }
+            };
+            for (matches, matched) in counts.matches.iter_mut().zip(matched) {
+                *matches += usize::from(matched);
+            }
+        }
+        counts
+    }
+
+    /// Adds the counts of another chunk of the same column
+    pub fn merge(mut self, other: &TypeCounts) -> Self {
+        self.non_empty += other.non_empty;
+        for (matches, other) in self.matches.iter_mut().zip(other.matches) {
+            *matches += other;
+        }
+        self
+    }
+}
+
+/// Candidate types for a column, ranked from best to worst match
+#[derive(Debug, Default, PartialEq)]
 pub struct TypeScores {
-    pub numeric: f64,
-    pub currency: f64,
-    pub date: f64,
-    pub email: f64,
-    pub phone: f64,
-    pub categorical: f64,
+    candidates: Vec<TypeCandidate>,
 }
 
 impl TypeScores {
-    /// Creates TypeScores from analyzing a column of values
-    pub fn from_column(values: &[String]) -> Self {
-        // Get non-empty values
-        let non_empty_values: Vec<&str> = values
-            .iter()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
+    /// Scores every candidate type against the non-empty values of a column
+    pub fn from_column(values: &[String], config: &DetectionConfig) -> Self {
+        let synonyms = config.synonyms().unwrap_or_default();
+        TypeScores::from_counts(&TypeCounts::from_values(values, &synonyms))
+    }
 
-        // If all values are empty, return default scores (will resolve to Text type)
-        if non_empty_values.is_empty() {
+    /// Scores every candidate type from a column's match counts
+    pub fn from_counts(counts: &TypeCounts) -> Self {
+        // All-empty columns have no evidence for any type and resolve to Text
+        if counts.non_empty == 0 {
             return TypeScores::default();
         }
 
-        // For each type, check if ALL values match that type
-        let scores = TypeScores {
-            numeric: if non_empty_values
-                .iter()
-                .all(|&v| NumericType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| NumericType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-            currency: if non_empty_values
-                .iter()
-                .all(|&v| CurrencyType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| CurrencyType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-            date: if non_empty_values
-                .iter()
-                .all(|&v| DateType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| DateType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-            email: if non_empty_values
-                .iter()
-                .all(|&v| EmailType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| EmailType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-            phone: if non_empty_values
-                .iter()
-                .all(|&v| PhoneType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| PhoneType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-            categorical: if non_empty_values
-                .iter()
-                .all(|&v| CategoricalType::detect_confidence(v) == 1.0)
-            {
-                1.0
-            } else {
-                non_empty_values
-                    .iter()
-                    .map(|&v| CategoricalType::detect_confidence(v))
-                    .sum::<f64>()
-                    / non_empty_values.len() as f64
-            },
-        };
+        let mut candidates: Vec<TypeCandidate> = CANDIDATE_TYPES
+            .into_iter()
+            .zip(counts.matches)
+            .map(|(data_type, matches)| TypeCandidate {
+                data_type,
+                score: matches as f64 / counts.non_empty as f64,
+                matches,
+                mismatches: counts.non_empty - matches,
+                prior: 0.0,
+            })
+            .collect();
+        // A stable sort keeps the priority order among equal scores
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
 
-        scores
+        TypeScores { candidates }
     }
 
-    /// Returns the appropriate data type and its confidence score
-    pub fn best_type(&self) -> (DataType, f64) {
-        // First create the array and store it in a named variable
-        let type_scores = [
-            (DataType::Integer, self.numeric),
-            (DataType::Currency, self.currency),
-            (DataType::Date, self.date),
-            (DataType::Email, self.email),
-            (DataType::Phone, self.phone),
-            (DataType::Categorical, self.categorical),
-        ];
+    /// Nudges the types a column header suggests, e.g. Date for "order_date", by `weight`.
+    /// Only types some values already match are nudged, so a header alone never decides.
+    pub fn with_header_hints(mut self, header: &str, weight: f64) -> Self {
+        let hinted = hinted_types(header);
+        for candidate in &mut self.candidates {
+            if candidate.matches > 0 && hinted.contains(&candidate.data_type) {
+                candidate.prior = weight;
+                candidate.score = (candidate.score + weight).min(1.0);
+            }
+        }
+        self.candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        self
+    }
 
-        // Now use into_iter() instead of iter() to take ownership of the values
-        let perfect_match = type_scores
-            .into_iter()
-            .find(|(_, confidence)| (confidence - 1.0).abs() < f64::EPSILON);
+    /// Every candidate type with its evidence, best match first
+    pub fn candidates(&self) -> &[TypeCandidate] {
+        &self.candidates
+    }
 
-        if let Some((dtype, confidence)) = perfect_match {
-            (dtype, confidence) // No need for clone() or deref since we own the values
-        } else {
-            (DataType::Text, 0.0)
+    /// Returns the best type whose score reaches the threshold and that score,
+    /// or Text with no confidence when no candidate does
+    pub fn best_type(&self, threshold: f64) -> (DataType, f64) {
+        match self.candidates.first() {
+            Some(best) if best.matches > 0 && best.score >= threshold => {
+                (best.data_type, best.score)
+            }
+            _ => (DataType::Text, 0.0),
         }
     }
 }
@@ -137,11 +211,91 @@ impl TypeScores {
 mod tests {
     use super::*;
 
+    fn default_threshold() -> f64 {
+        DetectionConfig::default().type_threshold
+    }
+
+    #[test]
+    fn test_prescreen_only_skips_mismatches() {
+        let values = [
+            "42",
+            "-3.5",
+            ".5",
+            "1,234",
+            "$12.50",
+            "-$5",
+            "($1,234.56)",
+            "USD100",
+            "100USD",
+            "2024-03-19",
+            "19/03/2024",
+            "ann@example.com",
+            "Ann@Example.com",
+            "+1 555 123 4567",
+            "(555) 123-4567",
+            "4111 1111 1111 1111",
+            "-4111111111111111",
+            "yes",
+            "High",
+            "n/a",
+            "#7",
+            "~",
+            "@x",
+            "ＡＢ",
+            "١٢٣",
+            "ſ",
+            "  42",
+            "\t$5",
+        ];
+        let synonyms = SynonymDictionary::default();
+        for value in values {
+            let first = value.trim_start().as_bytes()[0];
+            for data_type in CANDIDATE_TYPES {
+                if detector_matches(data_type, value, &synonyms) {
+                    assert!(
+                        may_start(data_type, first),
+                        "{:?} skipped {:?}",
+                        data_type,
+                        value
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_repeated_values_count_every_time() {
+        let mut repeated: Vec<String> = (0..5000)
+            .map(|i| ["yes", "no", " yes ", "a@b.com", "12"][i % 5].to_string())
+            .collect();
+        // Unique values past the memo's capacity, which are matched without being remembered
+        repeated.extend((0..MEMO_CAPACITY + 100).map(|i| i.to_string()));
+        // A column that starts out unique stops using the memo for the repeats after
+        let mut unique: Vec<String> = (0..MEMO_CAPACITY + 100).map(|i| i.to_string()).collect();
+        unique.extend(repeated[..5000].iter().cloned());
+
+        for values in [repeated, unique] {
+            let counts = TypeCounts::from_values(&values, &SynonymDictionary::default());
+            let expected = TypeCounts {
+                non_empty: values.len(),
+                matches: CANDIDATE_TYPES.map(|data_type| {
+                    values
+                        .iter()
+                        .filter(|v| {
+                            value_matches(data_type, v.trim(), &SynonymDictionary::default())
+                        })
+                        .count()
+                }),
+            };
+            assert_eq!(counts, expected);
+        }
+    }
+
     #[test]
     fn test_numeric_detection() {
         let values = vec!["123".to_string(), "456".to_string(), "789".to_string()];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Integer);
         assert!(confidence > 0.9);
     }
@@ -153,8 +307,8 @@ mod tests {
             "$250.50".to_string(),
             "$1,234.56".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Currency);
         assert!(confidence > 0.9);
     }
@@ -166,8 +320,8 @@ mod tests {
             "2024-02-15".to_string(),
             "2024-03-30".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Date);
         assert!(confidence > 0.9);
     }
@@ -179,8 +333,8 @@ mod tests {
             "another@test.com".to_string(),
             "email@domain.org".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Email);
         assert!(confidence > 0.9);
     }
@@ -192,12 +346,34 @@ mod tests {
             "234-567-8901".to_string(),
             "345.678.9012".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Phone);
         assert!(confidence > 0.9);
     }
 
+    #[test]
+    fn test_credit_card_detection() {
+        let values = vec![
+            "4111111111111111".to_string(),
+            "5500000000000004".to_string(),
+            "378282246310005".to_string(),
+        ];
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
+        assert_eq!(data_type, DataType::CreditCard);
+        assert!(confidence > 0.9);
+
+        // Long identifiers that fail the checksum stay numeric
+        let values = vec![
+            "4111111111111111".to_string(),
+            "4111111111111112".to_string(),
+        ];
+        let (data_type, _) = TypeScores::from_column(&values, &DetectionConfig::default())
+            .best_type(default_threshold());
+        assert_eq!(data_type, DataType::Integer);
+    }
+
     #[test]
     fn test_categorical_detection() {
         let values = vec![
@@ -207,8 +383,8 @@ mod tests {
             "High".to_string(),
             "Medium".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Categorical);
         assert!(confidence > 0.7);
     }
@@ -221,8 +397,8 @@ mod tests {
             "def".to_string(),
             "456".to_string(),
         ];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Text);
         assert!(confidence < 0.5);
     }
@@ -230,9 +406,89 @@ mod tests {
     #[test]
     fn test_empty_values() {
         let values = vec!["".to_string(), "  ".to_string(), "\n".to_string()];
-        let scores = TypeScores::from_column(&values);
-        let (data_type, confidence) = scores.best_type();
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let (data_type, confidence) = scores.best_type(default_threshold());
         assert_eq!(data_type, DataType::Text);
         assert_eq!(confidence, 0.0);
     }
+
+    #[test]
+    fn test_candidates_are_ranked_with_evidence() {
+        let values = vec![
+            "2024-01-01".to_string(),
+            "2024-02-15".to_string(),
+            "42".to_string(),
+            "".to_string(),
+        ];
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+        let candidates = scores.candidates();
+        assert_eq!(candidates.len(), 7);
+        assert_eq!(candidates[0].data_type, DataType::Date);
+        assert_eq!((candidates[0].matches, candidates[0].mismatches), (2, 1));
+        assert_eq!(candidates[1].data_type, DataType::Integer);
+        assert_eq!((candidates[1].matches, candidates[1].mismatches), (1, 2));
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_threshold_tolerates_stray_values() {
+        let mut values: Vec<String> = (1..=19).map(|i| i.to_string()).collect();
+        values.push("n/a".to_string());
+        let scores = TypeScores::from_column(&values, &DetectionConfig::default());
+
+        let (data_type, confidence) = scores.best_type(default_threshold());
+        assert_eq!(data_type, DataType::Integer);
+        assert_eq!(confidence, 0.95);
+
+        assert_eq!(scores.best_type(1.0), (DataType::Text, 0.0));
+        assert_eq!(
+            TypeScores::from_column(&["abc".to_string()], &DetectionConfig::default())
+                .best_type(0.0),
+            (DataType::Text, 0.0),
+            "a type with no matching values is never picked"
+        );
+    }
+
+    #[test]
+    fn test_locale_synonyms() {
+        let values = vec![
+            "oui".to_string(),
+            "Non".to_string(),
+            "oui".to_string(),
+            "yes".to_string(),
+        ];
+        let english = DetectionConfig::default();
+        let (data_type, _) = TypeScores::from_column(&values, &english).best_type(0.95);
+        assert_eq!(data_type, DataType::Text);
+
+        let french = DetectionConfig {
+            locale: "fr".to_string(),
+            ..DetectionConfig::default()
+        };
+        let (data_type, confidence) = TypeScores::from_column(&values, &french).best_type(0.95);
+        assert_eq!(data_type, DataType::Categorical);
+        assert_eq!(confidence, 1.0);
+    }
+
+    #[test]
+    fn test_header_hints() {
+        let mut values: Vec<String> = (1..=18).map(|d| format!("2024-01-{:02}", d)).collect();
+        values.push("soon".to_string());
+        values.push("n/a".to_string());
+        let config = DetectionConfig::default();
+
+        let (data_type, _) = TypeScores::from_column(&values, &config).best_type(0.95);
+        assert_eq!(data_type, DataType::Text);
+
+        let scores = TypeScores::from_column(&values, &config).with_header_hints("due_date", 0.05);
+        let (data_type, confidence) = scores.best_type(0.95);
+        assert_eq!(data_type, DataType::Date);
+        assert!((confidence - 0.95).abs() < 1e-9);
+        assert_eq!(scores.candidates()[0].prior, 0.05);
+
+        // A hint can't promote a type no value matches
+        let words = vec!["apple".to_string(), "pear".to_string()];
+        let scores = TypeScores::from_column(&words, &config).with_header_hints("order_date", 1.0);
+        assert_eq!(scores.best_type(0.95), (DataType::Text, 0.0));
+    }
 }
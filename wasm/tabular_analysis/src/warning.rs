@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cleaning::dates::{ambiguous_date_rows, resolve_day_first};
+use crate::csv::CSV;
+use crate::types::DataType;
+
+/// Rows below which statistics and categorical detection aren't reliable
+pub const MIN_RELIABLE_ROWS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Worth knowing, e.g. a column with no values
+    Info,
+    /// Results may be misleading, e.g. too few rows or dates read in a guessed order
+    Warning,
+}
+
+/// Kind of condition a warning reports, serialized as a stable snake_case code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCode {
+    SmallSample,
+    RaggedRows,
+    AmbiguousDates,
+    EmptyColumn,
+    SampledInput,
+}
+
+/// A condition that doesn't stop the analysis but deserves the user's attention
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisWarning {
+    pub code: WarningCode,
+    pub severity: Severity,
+    pub message: String,
+    /// Column the warning is about, if it concerns a single one
+    pub column: Option<usize>,
+    /// Data rows involved, counting from 0 after the header
+    pub rows: Vec<usize>,
+}
+
+impl AnalysisWarning {
+    // Rows that had fewer fields than the header and were padded with empty values
+    pub(crate) fn ragged_rows(rows: Vec<usize>) -> Self {
+        AnalysisWarning {
+            code: WarningCode::RaggedRows,
+            severity: Severity::Warning,
+            message: format!(
+                "{} rows had fewer fields than the header and were padded with empty values",
+                rows.len()
+            ),
+            column: None,
+            rows,
+        }
+    }
+}
+
+impl CSV {
+    /// Conditions worth surfacing alongside the results: rows repaired while parsing, too few
+    /// rows for reliable statistics, and per-column issues found by type inference
+    pub fn warnings(&self) -> Vec<AnalysisWarning> {
+        let mut warnings = self.parse_warnings().to_vec();
+
+        if self.row_count() < MIN_RELIABLE_ROWS {
+            warnings.push(AnalysisWarning {
+                code: WarningCode::SmallSample,
+                severity: Severity::Warning,
+                message: format!(
+                    "Only {} rows; statistics and type detection need at least {} to be reliable",
+                    self.row_count(),
+                    MIN_RELIABLE_ROWS
+                ),
+                column: None,
+                rows: Vec::new(),
+            });
+        }
+
+        for (column, (header, values)) in self.get_columns().into_iter().enumerate() {
+            if !values.is_empty() && values.iter().all(|v| v.trim().is_empty()) {
+                warnings.push(AnalysisWarning {
+                    code: WarningCode::EmptyColumn,
+                    severity: Severity::Info,
+                    message: format!("Column '{}' has no values", header),
+                    column: Some(column),
+                    rows: Vec::new(),
+                });
+            }
+
+            let is_date = self
+                .column_metadata(column)
+                .is_some_and(|m| m.data_type == DataType::Date);
+            if !is_date {
+                continue;
+            }
+            let rows = ambiguous_date_rows(values);
+            // The order is settled when the unambiguous values all point one way
+            let (day_first, day_first_evidence, month_first_evidence) = resolve_day_first(values);
+            if !rows.is_empty() && (day_first_evidence == 0) == (month_first_evidence == 0) {
+                warnings.push(AnalysisWarning {
+                    code: WarningCode::AmbiguousDates,
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Column '{}' has {} dates that read differently day-first and \
+                         month-first; they are read {}",
+                        header,
+                        rows.len(),
+                        if day_first {
+                            "day-first"
+                        } else {
+                            "month-first"
+                        }
+                    ),
+                    column: Some(column),
+                    rows,
+                });
+            }
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(warnings: &[AnalysisWarning]) -> Vec<WarningCode> {
+        warnings.iter().map(|w| w.code).collect()
+    }
+
+    #[test]
+    fn test_ragged_rows_are_padded() {
+        let csv = CSV::from_string("a,b,c\n1,2,3\n4\n5,6,7\n8,9".to_string()).unwrap();
+        assert_eq!(csv.row_count(), 4);
+        assert_eq!(csv.get_column(2).unwrap().1, ["3", "", "7", ""]);
+
+        let warnings = csv.warnings();
+        let ragged = warnings
+            .iter()
+            .find(|w| w.code == WarningCode::RaggedRows)
+            .unwrap();
+        assert_eq!(ragged.rows, vec![1, 3]);
+        assert_eq!(ragged.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_small_sample_and_empty_columns() {
+        let csv = CSV::from_string("id,note\n1,\n2,".to_string()).unwrap();
+        let warnings = csv.warnings();
+        assert_eq!(
+            codes(&warnings),
+            vec![WarningCode::SmallSample, WarningCode::EmptyColumn]
+        );
+        assert_eq!(warnings[1].column, Some(1));
+        assert_eq!(warnings[1].severity, Severity::Info);
+
+        let mut data = "id\n".to_string();
+        for i in 0..MIN_RELIABLE_ROWS {
+            data.push_str(&format!("{}\n", i));
+        }
+        assert!(CSV::from_string(data).unwrap().warnings().is_empty());
+    }
+
+    #[test]
+    fn test_ambiguous_dates() {
+        let mut csv =
+            CSV::from_string("day\n03/04/2024\n05/06/2024\n07/07/2024".to_string()).unwrap();
+        csv.infer_column_types();
+        let warnings = csv.warnings();
+        let ambiguous = warnings
+            .iter()
+            .find(|w| w.code == WarningCode::AmbiguousDates)
+            .unwrap();
+        assert_eq!(ambiguous.column, Some(0));
+        assert_eq!(ambiguous.rows, vec![0, 1]);
+
+        // 25/12 settles the column as day-first
+        let mut csv =
+            CSV::from_string("day\n03/04/2024\n25/12/2024\n07/07/2024".to_string()).unwrap();
+        csv.infer_column_types();
+        assert!(!codes(&csv.warnings()).contains(&WarningCode::AmbiguousDates));
+    }
+}
@@ -8,7 +8,7 @@ echo "Running Rust tests..."
 cargo test
 
 echo "Running WASM tests..."
-wasm-pack test --node
+wasm-pack test --node --no-default-features --features wasm
 
 # The script will exit with non-zero status if any command fails
 # due to the set -e flag